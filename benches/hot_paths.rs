@@ -0,0 +1,110 @@
+//! Benchmarks for request hot paths
+//!
+//! Covers payload validation, pagination token encoding and response
+//! serialization in every supported format. Run with `make bench`; criterion
+//! keeps the previous run under `target/criterion` and reports regressions
+//! against it.
+
+// `criterion_group!` generates an undocumented entry point
+#![allow(missing_docs)]
+
+use std::hint::black_box;
+
+use chrono::{TimeZone, Utc};
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use rust_kickstart::{
+    CreateUser, LinkBuilder, UpdateUser, User,
+    pagination::PaginationToken,
+    user::{
+        domain::{Metadata, PaginatedUsersResponse, PaginationParams, UserStatus},
+        validation::{validate_create_user, validate_update_user},
+    },
+};
+
+/// A page of `count` users with links, as returned by `GET /users`
+fn users_page(count: i32) -> PaginatedUsersResponse {
+    let created_at = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).single().expect("Valid timestamp");
+    let users: Vec<User> = (1..=count)
+        .map(|id| User {
+            id,
+            name: format!("User Number {}", "x".repeat(usize::try_from(id % 20).unwrap_or_default())),
+            age: 20 + id % 50,
+            created_at,
+            updated_at: created_at,
+            status: UserStatus::Active,
+            metadata: None,
+            warnings: Vec::new(),
+            links: None,
+        })
+        .collect();
+    let next_token = PaginationToken::encode(count, created_at).ok();
+    let params = PaginationParams { next_token: None, limit: Some(count), tag: None, prev_token: None, order: None, include_estimated_total: None, metadata: Metadata::new() };
+
+    PaginatedUsersResponse {
+        count: users.len(),
+        users,
+        next_token,
+        prev_token: None,
+        has_more: true,
+        estimated_total: None,
+        links: None,
+    }
+    .with_links(&LinkBuilder::new(Some("https://api.example.com")), &params)
+}
+
+/// Create and update payload validation, valid and invalid
+fn bench_validation(c: &mut Criterion) {
+    let mut group = c.benchmark_group("validation");
+
+    let valid = CreateUser { name: "Ann Lee".to_owned(), age: 34, metadata: None };
+    let invalid = CreateUser { name: format!("{}1", "a".repeat(120)), age: -3, metadata: None };
+    let update = UpdateUser { name: Some("Ann Lee".to_owned()), age: Some(35), metadata: None };
+
+    group.bench_function("create_valid", |b| b.iter(|| validate_create_user(black_box(&valid))));
+    group.bench_function("create_invalid", |b| b.iter(|| validate_create_user(black_box(&invalid))));
+    group.bench_function("update_valid", |b| b.iter(|| validate_update_user(black_box(&update))));
+    group.finish();
+}
+
+/// Cursor token encoding and decoding
+fn bench_pagination_token(c: &mut Criterion) {
+    let mut group = c.benchmark_group("pagination_token");
+
+    let timestamp = Utc.with_ymd_and_hms(2025, 6, 1, 12, 30, 0).single().expect("Valid timestamp");
+    let token = PaginationToken::encode(4242, timestamp).expect("Failed to encode token");
+
+    group.bench_function("encode", |b| b.iter(|| PaginationToken::encode(black_box(4242), black_box(timestamp))));
+    group.bench_function("decode", |b| b.iter(|| PaginationToken::decode(black_box(&token))));
+    group.bench_function("decode_invalid", |b| b.iter(|| PaginationToken::decode(black_box("not-a-token"))));
+    group.finish();
+}
+
+/// Users page serialization in each response format, by page size
+fn bench_serialization(c: &mut Criterion) {
+    let mut group = c.benchmark_group("serialize_users_page");
+
+    for count in [1, 50, 200] {
+        let page = users_page(count);
+
+        group.bench_with_input(BenchmarkId::new("json", count), &page, |b, page| {
+            b.iter(|| serde_json::to_vec(black_box(page)).expect("Failed to serialize"));
+        });
+        group.bench_with_input(BenchmarkId::new("xml", count), &page, |b, page| {
+            b.iter(|| quick_xml::se::to_string(black_box(page)).expect("Failed to serialize"));
+        });
+        group.bench_with_input(BenchmarkId::new("msgpack", count), &page, |b, page| {
+            b.iter(|| rmp_serde::to_vec_named(black_box(page)).expect("Failed to serialize"));
+        });
+        group.bench_with_input(BenchmarkId::new("cbor", count), &page, |b, page| {
+            b.iter(|| {
+                let mut buffer = Vec::new();
+                ciborium::into_writer(black_box(page), &mut buffer).expect("Failed to serialize");
+                buffer
+            });
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_validation, bench_pagination_token, bench_serialization);
+criterion_main!(benches);
@@ -0,0 +1,56 @@
+//! Embeds build information for `GET /version`
+//!
+//! Sets `GIT_SHA`, `BUILD_EPOCH`, `BUILD_FEATURES` and `RUSTC_VERSION` for
+//! the crate. `GIT_SHA` and `SOURCE_DATE_EPOCH` can be given in the
+//! environment, e.g. in container builds without the `.git` directory or for
+//! reproducible builds. It also has the crate rebuilt when a migration
+//! changes, since `sqlx::migrate!` embeds them.
+
+use std::{
+    env,
+    process::Command,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// Trimmed standard output of a command, if it succeeded
+fn output(program: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(program).args(args).output().ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_owned())
+        .filter(|value| !value.is_empty())
+}
+
+fn main() {
+    println!("cargo:rerun-if-changed=.git/HEAD");
+    println!("cargo:rerun-if-changed=.git/refs");
+    println!("cargo:rerun-if-env-changed=GIT_SHA");
+    println!("cargo:rerun-if-env-changed=SOURCE_DATE_EPOCH");
+    // Re-embed migrations (`sqlx::migrate!`) when one is added or changed
+    println!("cargo:rerun-if-changed=migrations");
+
+    let git_sha = env::var("GIT_SHA")
+        .ok()
+        .filter(|sha| !sha.is_empty())
+        .or_else(|| output("git", &["rev-parse", "HEAD"]))
+        .unwrap_or_else(|| "unknown".to_owned());
+
+    let build_epoch = env::var("SOURCE_DATE_EPOCH")
+        .ok()
+        .and_then(|epoch| epoch.parse::<u64>().ok())
+        .unwrap_or_else(|| SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |elapsed| elapsed.as_secs()));
+
+    let mut features: Vec<String> = env::vars()
+        .filter_map(|(key, _)| key.strip_prefix("CARGO_FEATURE_").map(|feature| feature.to_lowercase().replace('_', "-")))
+        .collect();
+    features.sort();
+
+    let rustc = env::var("RUSTC").unwrap_or_else(|_| "rustc".to_owned());
+    let rustc_version = output(&rustc, &["--version"]).unwrap_or_else(|| "unknown".to_owned());
+
+    println!("cargo:rustc-env=GIT_SHA={git_sha}");
+    println!("cargo:rustc-env=BUILD_EPOCH={build_epoch}");
+    println!("cargo:rustc-env=BUILD_FEATURES={}", features.join(","));
+    println!("cargo:rustc-env=RUSTC_VERSION={rustc_version}");
+}
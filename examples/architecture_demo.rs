@@ -1,9 +1,11 @@
 //! Architecture demonstration
 //! 
-//! Run with: cargo run --example architecture_demo
+//! Run with: cargo run --example `architecture_demo`
 //! 
 //! This example shows how the modular architecture works in practice.
 
+#![allow(clippy::print_stdout)]
+
 // use rust_kickstart::{UserService, BankService, CreateUser};
 
 #[tokio::main]
@@ -17,14 +19,15 @@ async fn main() {
     println!("📦 Module Structure:");
     println!("├── user/");
     println!("│   ├── domain.rs      (models, validation)");
+    println!("│   ├── ports.rs       (UserReadPort / UserWritePort - PUBLIC)");
     println!("│   ├── repository.rs  (database - PRIVATE)");
-    println!("│   ├── service.rs     (business logic - PUBLIC)");
+    println!("│   ├── service.rs     (UserService implements the ports)");
     println!("│   └── controller.rs  (HTTP handlers)");
     println!("└── bank/");
-    println!("    └── service.rs     (uses UserService)");
+    println!("    └── service.rs     (depends on the user ports)");
     
     println!("\n🔒 Encapsulation Rules:");
-    println!("✅ Bank can use UserService");
+    println!("✅ Bank depends on UserReadPort / UserWritePort");
     println!("❌ Bank CANNOT use UserRepository (compile error!)");
     println!("✅ UserService coordinates domain + repository");
     println!("✅ Controllers handle HTTP concerns only");
@@ -41,10 +44,10 @@ async fn main() {
     println!("// ✅ ALLOWED: Create services");
     println!("let user_service = UserService::new(pool);");
     println!("let bank_service = BankService::new(user_service);");
-    println!("");
+    println!();
     println!("// ✅ ALLOWED: Bank uses UserService");
     println!("bank_service.create_account(user_id, 1000.0).await?;");
-    println!("");
+    println!();
     println!("// ❌ NOT ALLOWED: Direct repository access");
     println!("// use crate::user::repository::UserRepository; // Won't compile!");
     println!("```");
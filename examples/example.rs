@@ -1,9 +1,11 @@
 //! Example demonstrating the modular architecture
 //! 
-//! This example shows how the bank module can use UserService
-//! but cannot access UserRepository directly.
+//! This example shows how the bank module can use `UserService`
+//! but cannot access `UserRepository` directly.
 
-use rust_kickstart::user::UserService;
+#![allow(clippy::print_stdout)]
+
+use rust_kickstart::user::{UserReadPort, UserService};
 use rust_kickstart::bank::BankService;
 
 /// Example function demonstrating proper module usage
@@ -15,20 +17,20 @@ pub async fn demonstrate_architecture(user_service: UserService) {
     
     // ✅ ALLOWED: Bank can create accounts through UserService
     match bank_service.create_account(1, 1000.0).await {
-        Ok(message) => println!("✅ Bank: {}", message),
-        Err(e) => println!("❌ Bank: {}", e),
+        Ok(message) => println!("✅ Bank: {message}"),
+        Err(e) => println!("❌ Bank: {e}"),
     }
     
     // ✅ ALLOWED: Bank can get user information through UserService
     match bank_service.get_account_holder_name(1).await {
-        Ok(name) => println!("✅ Bank: Account holder name: {}", name),
-        Err(e) => println!("❌ Bank: {}", e),
+        Ok(name) => println!("✅ Bank: Account holder name: {name}"),
+        Err(e) => println!("❌ Bank: {e}"),
     }
     
     // ✅ ALLOWED: Direct use of UserService
     match user_service.get_all_users().await {
         Ok(users) => println!("✅ Direct UserService: Found {} users", users.len()),
-        Err(e) => println!("❌ Direct UserService: {}", e),
+        Err(e) => println!("❌ Direct UserService: {e}"),
     }
     
     println!("\n=== What's NOT allowed (would cause compile errors) ===");
@@ -0,0 +1,11 @@
+-- Scratch rows of the synthetic self-test (POST /admin/selftest)
+--
+-- Each run inserts, reads, updates and deletes one row, so the table is
+-- normally empty. It references nothing, so the self-test never touches
+-- business data.
+CREATE TABLE selftest_scratch (
+    id BIGSERIAL PRIMARY KEY,
+    payload TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ
+);
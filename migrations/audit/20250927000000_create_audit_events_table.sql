@@ -0,0 +1,12 @@
+-- Persisted audit trail. Rows keep their user and account IDs after those
+-- are deleted, so there are no foreign keys.
+CREATE TABLE audit_events (
+    id BIGSERIAL PRIMARY KEY,
+    user_id INT,
+    account_id INT,
+    action VARCHAR(50) NOT NULL,
+    details JSONB NOT NULL DEFAULT '{}',
+    occurred_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_audit_events_user_id ON audit_events (user_id, occurred_at DESC, id);
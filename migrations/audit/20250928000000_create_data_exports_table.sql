@@ -0,0 +1,16 @@
+-- GDPR data exports. A background job fills in the bundle; downloads use a
+-- signed link that stops working at expires_at.
+CREATE TYPE export_status AS ENUM ('pending', 'ready', 'failed');
+
+CREATE TABLE data_exports (
+    id SERIAL PRIMARY KEY,
+    user_id INT NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    status export_status NOT NULL DEFAULT 'pending',
+    bundle JSONB,
+    requested_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    completed_at TIMESTAMPTZ,
+    expires_at TIMESTAMPTZ
+);
+
+CREATE INDEX idx_data_exports_user_id ON data_exports (user_id, id);
+CREATE INDEX idx_data_exports_pending ON data_exports (id) WHERE status = 'pending';
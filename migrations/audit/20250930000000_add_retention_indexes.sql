@@ -0,0 +1,3 @@
+-- Retention purges scan by age
+CREATE INDEX idx_audit_events_occurred_at ON audit_events (occurred_at);
+CREATE INDEX idx_data_exports_expires_at ON data_exports (expires_at) WHERE status <> 'pending';
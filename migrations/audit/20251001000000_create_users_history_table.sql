@@ -0,0 +1,44 @@
+-- Previous versions of users, written by triggers on every update and
+-- delete. Version n of a user is the row as it was before its n-th change;
+-- rows outlive the user so deletions stay visible.
+CREATE TABLE users_history (
+    id BIGSERIAL PRIMARY KEY,
+    user_id INT NOT NULL,
+    version INT NOT NULL,
+    operation VARCHAR(10) NOT NULL,
+    name VARCHAR(255) NOT NULL,
+    age INT NOT NULL,
+    status user_status NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL,
+    changed_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (user_id, version)
+);
+
+-- Updates and deletes lock the user row, so concurrent changes of one user
+-- cannot compute the same version
+CREATE FUNCTION record_user_history() RETURNS trigger AS $$
+BEGIN
+    INSERT INTO users_history (user_id, version, operation, name, age, status, created_at)
+    VALUES (
+        OLD.id,
+        COALESCE((SELECT MAX(version) FROM users_history WHERE user_id = OLD.id), 0) + 1,
+        lower(TG_OP),
+        OLD.name,
+        OLD.age,
+        OLD.status,
+        OLD.created_at
+    );
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER users_history_on_update
+AFTER UPDATE ON users
+FOR EACH ROW
+WHEN (OLD.name IS DISTINCT FROM NEW.name OR OLD.age IS DISTINCT FROM NEW.age OR OLD.status IS DISTINCT FROM NEW.status)
+EXECUTE FUNCTION record_user_history();
+
+CREATE TRIGGER users_history_on_delete
+AFTER DELETE ON users
+FOR EACH ROW
+EXECUTE FUNCTION record_user_history();
@@ -0,0 +1,10 @@
+-- Domain events, numbered in the order they were committed. Writers lock the
+-- table until they commit, so an entry never becomes visible after one with
+-- a higher seq: consumers resume after the last seq they saw without missing
+-- any. Rolled back writes leave gaps in the numbering.
+CREATE TABLE change_log (
+    seq BIGSERIAL PRIMARY KEY,
+    event_type VARCHAR(100) NOT NULL,
+    payload JSONB NOT NULL,
+    recorded_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
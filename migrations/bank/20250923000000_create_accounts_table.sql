@@ -0,0 +1,13 @@
+-- Bank accounts held by users; balances are stored in cents
+CREATE TABLE accounts (
+    id SERIAL PRIMARY KEY,
+    user_id INT NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    balance_cents BIGINT NOT NULL DEFAULT 0 CHECK (balance_cents >= 0),
+    frozen BOOLEAN NOT NULL DEFAULT FALSE,
+    frozen_reason TEXT,
+    frozen_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Lookup of accounts by holder
+CREATE INDEX idx_accounts_user_id ON accounts (user_id);
@@ -0,0 +1,45 @@
+-- Checking or savings; only savings accounts accrue interest
+CREATE TYPE account_kind AS ENUM ('checking', 'savings');
+
+ALTER TABLE accounts
+ADD COLUMN kind account_kind NOT NULL DEFAULT 'checking';
+
+-- One money movement. The optional idempotency key turns replays (such as a
+-- re-run interest accrual) into no-ops.
+CREATE TABLE ledger_transactions (
+    id BIGSERIAL PRIMARY KEY,
+    kind VARCHAR(30) NOT NULL,
+    idempotency_key TEXT UNIQUE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Double-entry postings; the amounts of a transaction sum to zero. Customer
+-- postings reference an account, the bank's own books (cash, interest
+-- expense) do not.
+CREATE TABLE ledger_entries (
+    id BIGSERIAL PRIMARY KEY,
+    transaction_id BIGINT NOT NULL REFERENCES ledger_transactions (id) ON DELETE CASCADE,
+    account_id INT REFERENCES accounts (id) ON DELETE CASCADE,
+    book VARCHAR(30) NOT NULL,
+    amount_cents BIGINT NOT NULL,
+    CHECK ((book = 'customer') = (account_id IS NOT NULL))
+);
+
+CREATE INDEX idx_ledger_entries_transaction_id ON ledger_entries (transaction_id);
+CREATE INDEX idx_ledger_entries_account_id ON ledger_entries (account_id);
+
+-- Backfill opening entries so existing balances are covered by the ledger
+WITH opened AS (
+    INSERT INTO ledger_transactions (kind, idempotency_key, created_at)
+    SELECT 'opening', 'opening:' || id, created_at FROM accounts WHERE balance_cents > 0
+    RETURNING id, idempotency_key
+)
+INSERT INTO ledger_entries (transaction_id, account_id, book, amount_cents)
+SELECT
+    opened.id,
+    CASE WHEN postings.book = 'customer' THEN accounts.id END,
+    postings.book,
+    postings.sign * accounts.balance_cents
+FROM opened
+JOIN accounts ON opened.idempotency_key = 'opening:' || accounts.id
+CROSS JOIN (VALUES ('customer', 1), ('cash', -1)) AS postings (book, sign);
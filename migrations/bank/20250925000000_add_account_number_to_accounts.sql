@@ -0,0 +1,4 @@
+-- Account numbers (IBAN or Luhn-checked digits), generated when an account
+-- is opened. Accounts opened before this migration have none.
+ALTER TABLE accounts
+ADD COLUMN account_number VARCHAR(34) UNIQUE;
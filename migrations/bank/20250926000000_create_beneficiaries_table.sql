@@ -0,0 +1,10 @@
+-- External transfer targets saved by users; removed together with their user.
+-- Payments to accounts at other banks must go to a saved beneficiary.
+CREATE TABLE beneficiaries (
+    id SERIAL PRIMARY KEY,
+    user_id INT NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    name VARCHAR(100) NOT NULL,
+    account_number VARCHAR(34) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (user_id, account_number)
+);
@@ -0,0 +1,21 @@
+-- Durable job queue. Workers claim queued jobs with SKIP LOCKED and hold
+-- them until locked_until; a job whose worker dies is claimed again once that
+-- passes. Jobs failing max_attempts times are dead-lettered ('dead') until an
+-- operator retries them.
+CREATE TYPE job_status AS ENUM ('queued', 'running', 'succeeded', 'dead');
+
+CREATE TABLE jobs (
+    id BIGSERIAL PRIMARY KEY,
+    kind TEXT NOT NULL,
+    payload JSONB NOT NULL DEFAULT '{}',
+    status job_status NOT NULL DEFAULT 'queued',
+    attempts INT NOT NULL DEFAULT 0,
+    max_attempts INT NOT NULL CHECK (max_attempts > 0),
+    last_error TEXT,
+    locked_until TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_jobs_claimable ON jobs (id) WHERE status IN ('queued', 'running');
+CREATE INDEX idx_jobs_status ON jobs (status, id);
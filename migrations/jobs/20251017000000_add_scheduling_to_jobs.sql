@@ -0,0 +1,10 @@
+-- Named queues, priorities and delayed jobs. Workers of a queue claim its
+-- due jobs (run_at in the past) highest priority first, then oldest first.
+ALTER TABLE jobs
+    ADD COLUMN queue TEXT NOT NULL DEFAULT 'default',
+    ADD COLUMN priority INT NOT NULL DEFAULT 0,
+    ADD COLUMN run_at TIMESTAMPTZ NOT NULL DEFAULT NOW();
+
+DROP INDEX idx_jobs_claimable;
+CREATE INDEX idx_jobs_due ON jobs (queue, priority DESC, run_at, id) WHERE status = 'queued';
+CREATE INDEX idx_jobs_running ON jobs (queue, locked_until) WHERE status = 'running';
@@ -0,0 +1,7 @@
+-- When each singleton job last started on any replica, so that a job
+-- scheduled by every replica still runs once per interval across the
+-- deployment
+CREATE TABLE job_runs (
+    job TEXT PRIMARY KEY,
+    last_run_at TIMESTAMPTZ NOT NULL
+);
@@ -0,0 +1,15 @@
+-- Request signing keys of partner integrations
+--
+-- `key_digest` is the SHA-256 digest of each issued secret. Partners sign with
+-- that digest as the HMAC key, so it is secret-equivalent: anyone who can read
+-- this table can sign requests as the partner. Rotating a key gives the
+-- previous ones an expiry so partners can switch over.
+CREATE TABLE partner_keys (
+    id SERIAL PRIMARY KEY,
+    partner VARCHAR(50) NOT NULL,
+    key_digest BYTEA NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    expires_at TIMESTAMPTZ
+);
+
+CREATE INDEX idx_partner_keys_partner ON partner_keys (partner);
@@ -0,0 +1,13 @@
+-- Accounts each partner integration may debit
+--
+-- A valid signature only proves which partner sent a request; the partner
+-- can pay out of the accounts granted to it here and no others.
+CREATE TABLE partner_accounts (
+    partner VARCHAR(50) NOT NULL,
+    account_id INT NOT NULL REFERENCES accounts (id) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (partner, account_id)
+);
+
+-- Lookup of the partners allowed to debit an account
+CREATE INDEX idx_partner_accounts_account_id ON partner_accounts (account_id);
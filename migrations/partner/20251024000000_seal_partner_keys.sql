@@ -0,0 +1,7 @@
+-- Signing keys are stored in `sealed_key`, encrypted with the server's
+-- key-encryption key (`PARTNER_KEY_ENCRYPTION_KEY`), so reading this table no
+-- longer allows signing requests. `key_digest` only holds keys stored in the
+-- clear before; the `partner-keys` startup hook seals them and clears it.
+ALTER TABLE partner_keys ADD COLUMN sealed_key BYTEA;
+ALTER TABLE partner_keys ALTER COLUMN key_digest DROP NOT NULL;
+ALTER TABLE partner_keys ADD CONSTRAINT partner_keys_one_key CHECK ((key_digest IS NULL) <> (sealed_key IS NULL));
@@ -0,0 +1,5 @@
+-- Add lifecycle status to users
+CREATE TYPE user_status AS ENUM ('active', 'suspended', 'archived');
+
+ALTER TABLE users
+ADD COLUMN status user_status NOT NULL DEFAULT 'active';
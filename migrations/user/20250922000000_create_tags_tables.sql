@@ -0,0 +1,20 @@
+-- Tags (labels) that can be attached to many users
+CREATE TABLE tags (
+    id SERIAL PRIMARY KEY,
+    name VARCHAR(50) NOT NULL UNIQUE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Many-to-many relation between users and tags
+CREATE TABLE user_tags (
+    user_id INT NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    tag_id INT NOT NULL REFERENCES tags (id) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (user_id, tag_id)
+);
+
+-- Lookup of users by tag (the primary key covers lookup of tags by user)
+CREATE INDEX idx_user_tags_tag_id ON user_tags (tag_id, user_id);
+
+-- Prefix search for tag autocompletion
+CREATE INDEX idx_tags_name_prefix ON tags (name text_pattern_ops);
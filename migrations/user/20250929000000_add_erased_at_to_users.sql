@@ -0,0 +1,3 @@
+-- Set when a user's personal data is erased (GDPR right to erasure). The row
+-- stays so accounts and the ledger keep their references.
+ALTER TABLE users ADD COLUMN erased_at TIMESTAMPTZ;
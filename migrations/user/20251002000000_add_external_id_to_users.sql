@@ -0,0 +1,5 @@
+-- Identifier of a user in the system that syncs it (e.g. an email address),
+-- the conflict target of `PUT /users` upserts
+ALTER TABLE users ADD COLUMN external_id VARCHAR(255);
+
+CREATE UNIQUE INDEX users_external_id_key ON users (external_id);
@@ -0,0 +1,11 @@
+-- IDs of users in third-party systems (CRM, identity provider subject, ...);
+-- a user has at most one ID per system and an ID belongs to one user
+CREATE TABLE external_identities (
+    id SERIAL PRIMARY KEY,
+    user_id INT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    system VARCHAR(50) NOT NULL,
+    external_id VARCHAR(255) NOT NULL,
+    linked_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (system, external_id),
+    UNIQUE (user_id, system)
+);
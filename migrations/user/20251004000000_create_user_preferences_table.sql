@@ -0,0 +1,7 @@
+-- Per-user preferences as a JSONB object of known keys; keys absent from the
+-- object fall back to the configured defaults
+CREATE TABLE user_preferences (
+    user_id INT PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    preferences JSONB NOT NULL DEFAULT '{}' CHECK (jsonb_typeof(preferences) = 'object'),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,5 @@
+-- Free-form string labels attached to a user; filtered with JSONB containment,
+-- which the jsonb_path_ops GIN index serves
+ALTER TABLE users ADD COLUMN metadata JSONB CHECK (metadata IS NULL OR jsonb_typeof(metadata) = 'object');
+
+CREATE INDEX idx_users_metadata ON users USING GIN (metadata jsonb_path_ops);
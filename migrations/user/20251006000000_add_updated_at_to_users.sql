@@ -0,0 +1,21 @@
+-- Last modification time of each user, served as Last-Modified. A trigger
+-- bumps it on every update that changes the row, so bulk updates, status
+-- transitions, reverts and erasure are covered without each query setting it.
+ALTER TABLE users ADD COLUMN updated_at TIMESTAMPTZ;
+UPDATE users SET updated_at = created_at;
+ALTER TABLE users
+    ALTER COLUMN updated_at SET NOT NULL,
+    ALTER COLUMN updated_at SET DEFAULT NOW();
+
+CREATE FUNCTION touch_user_updated_at() RETURNS trigger AS $$
+BEGIN
+    NEW.updated_at := NOW();
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER users_touch_updated_at
+BEFORE UPDATE ON users
+FOR EACH ROW
+WHEN (OLD.* IS DISTINCT FROM NEW.*)
+EXECUTE FUNCTION touch_user_updated_at();
@@ -0,0 +1,30 @@
+-- Change feed of users, written by a trigger on every insert, update and
+-- delete. Readers only see rows of transactions older than every running
+-- one (txid below the snapshot's xmin) and follow them in (txid, seq) order,
+-- so a change committed late never lands behind a cursor already handed out.
+CREATE TABLE user_changes (
+    seq BIGSERIAL PRIMARY KEY,
+    user_id INT NOT NULL,
+    operation VARCHAR(10) NOT NULL,
+    txid xid8 NOT NULL DEFAULT pg_current_xact_id(),
+    changed_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_user_changes_txid_seq ON user_changes (txid, seq);
+CREATE INDEX idx_user_changes_changed_at ON user_changes (changed_at);
+
+CREATE FUNCTION record_user_change() RETURNS trigger AS $$
+BEGIN
+    INSERT INTO user_changes (user_id, operation)
+    VALUES (
+        COALESCE(NEW.id, OLD.id),
+        CASE TG_OP WHEN 'INSERT' THEN 'create' ELSE lower(TG_OP) END
+    );
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER user_changes_on_write
+AFTER INSERT OR UPDATE OR DELETE ON users
+FOR EACH ROW
+EXECUTE FUNCTION record_user_change();
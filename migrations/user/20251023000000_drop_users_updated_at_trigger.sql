@@ -0,0 +1,4 @@
+-- users.updated_at is now set by the application from its injected clock, so
+-- the trigger stamping it with the database's NOW() is dropped.
+DROP TRIGGER users_touch_updated_at ON users;
+DROP FUNCTION touch_user_updated_at();
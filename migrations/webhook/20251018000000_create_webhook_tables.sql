@@ -0,0 +1,38 @@
+-- Outgoing webhooks. A subscription receives the events whose types it lists
+-- (every event when the list is empty). Deliveries are signed with each live
+-- secret's SHA-256 digest; rotating a secret gives the previous ones an
+-- expiry, so receivers can switch over while both signatures are sent.
+CREATE TABLE webhooks (
+    id SERIAL PRIMARY KEY,
+    url TEXT NOT NULL,
+    event_types TEXT[] NOT NULL DEFAULT '{}',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE webhook_secrets (
+    id SERIAL PRIMARY KEY,
+    webhook_id INT NOT NULL REFERENCES webhooks (id) ON DELETE CASCADE,
+    key_digest BYTEA NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    expires_at TIMESTAMPTZ
+);
+
+CREATE INDEX idx_webhook_secrets_webhook ON webhook_secrets (webhook_id);
+
+-- One row per delivery attempt, kept for debugging receivers. Retries of an
+-- event share its delivery_id, which receivers see as X-Webhook-Id.
+CREATE TABLE webhook_deliveries (
+    id BIGSERIAL PRIMARY KEY,
+    webhook_id INT NOT NULL REFERENCES webhooks (id) ON DELETE CASCADE,
+    delivery_id TEXT NOT NULL,
+    event_type TEXT NOT NULL,
+    attempt INT NOT NULL,
+    response_status INT,
+    error TEXT,
+    duration_ms INT NOT NULL,
+    attempted_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_webhook_deliveries_webhook ON webhook_deliveries (webhook_id, id DESC);
+CREATE INDEX idx_webhook_deliveries_delivery ON webhook_deliveries (delivery_id);
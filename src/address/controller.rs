@@ -0,0 +1,204 @@
+//! Address controller - HTTP handlers for the `/users/{id}/addresses` sub-resource
+
+use axum::{
+    Json,
+    extract::Path,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use tracing::{error, warn};
+
+use crate::address::AddressService;
+use crate::error_codes::{ErrorCode, ErrorResponse, internal_error};
+use crate::registry::Inject;
+use crate::user::UserId;
+use crate::user::domain::{ApiResponse, ValidationErrorResponse, UserError};
+
+use super::domain::{Address, AddressError, CreateAddress, UpdateAddress};
+
+/// Maps address errors to HTTP responses
+fn error_response(error: AddressError, user_id: i32) -> Response {
+    match error {
+        AddressError::ValidationError(errors) => {
+            warn!(?errors, user_id, "Controller: Address validation failed");
+            (
+                StatusCode::BAD_REQUEST,
+                Json(ValidationErrorResponse { errors }),
+            ).into_response()
+        }
+        AddressError::UserNotFound => {
+            warn!(user_id, "Controller: User not found for address operation");
+            (
+                StatusCode::NOT_FOUND,
+                Json(ErrorResponse::new(ErrorCode::UserNotFound, error.to_string())),
+            ).into_response()
+        }
+        AddressError::NotFound => {
+            warn!(user_id, "Controller: Address not found");
+            (
+                StatusCode::NOT_FOUND,
+                Json(ErrorResponse::new(ErrorCode::AddressNotFound, error.to_string())),
+            ).into_response()
+        }
+        AddressError::DatabaseError(msg) => {
+            error!(error = %msg, user_id, "Controller: Database error in address operation");
+            internal_error()
+        }
+        AddressError::UserServiceError(UserError::DatabaseError(e)) => {
+            error!(error = %e, user_id, "Controller: Database error in user lookup for address operation");
+            e.into_response()
+        }
+        AddressError::UserServiceError(e) => {
+            error!(error = %e, user_id, "Controller: User service error in address operation");
+            internal_error()
+        }
+    }
+}
+
+/// HTTP handler for listing the addresses of a user
+#[utoipa::path(
+    get,
+    path = "/users/{id}/addresses",
+    tag = "addresses",
+    params(
+        ("id" = i32, Path, description = "User ID")
+    ),
+    responses(
+        (status = 200, description = "Addresses of the user", body = Vec<Address>),
+        (status = 400, description = "Invalid user ID", body = ValidationErrorResponse),
+        (status = 404, description = "User not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    )
+)]
+#[tracing::instrument(skip(address_service), fields(user_id = user_id))]
+pub async fn list_addresses_handler(
+    Inject(address_service): Inject<AddressService>,
+    UserId(user_id): UserId,
+) -> impl IntoResponse {
+    match address_service.list_addresses(user_id).await {
+        Ok(addresses) => (StatusCode::OK, Json(addresses)).into_response(),
+        Err(e) => error_response(e, user_id),
+    }
+}
+
+/// HTTP handler for adding an address to a user
+#[utoipa::path(
+    post,
+    path = "/users/{id}/addresses",
+    tag = "addresses",
+    params(
+        ("id" = i32, Path, description = "User ID")
+    ),
+    request_body = CreateAddress,
+    responses(
+        (status = 201, description = "Address created", body = Address),
+        (status = 400, description = "Validation errors", body = ValidationErrorResponse),
+        (status = 404, description = "User not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    )
+)]
+#[tracing::instrument(skip(address_service, payload), fields(user_id = user_id))]
+pub async fn create_address_handler(
+    Inject(address_service): Inject<AddressService>,
+    UserId(user_id): UserId,
+    Json(payload): Json<CreateAddress>,
+) -> impl IntoResponse {
+    match address_service.create_address(user_id, payload).await {
+        Ok(address) => (StatusCode::CREATED, Json(address)).into_response(),
+        Err(e) => error_response(e, user_id),
+    }
+}
+
+/// HTTP handler for retrieving a single address of a user
+#[utoipa::path(
+    get,
+    path = "/users/{id}/addresses/{address_id}",
+    tag = "addresses",
+    params(
+        ("id" = i32, Path, description = "User ID"),
+        ("address_id" = i32, Path, description = "Address ID")
+    ),
+    responses(
+        (status = 200, description = "Address found", body = Address),
+        (status = 400, description = "Invalid user ID", body = ValidationErrorResponse),
+        (status = 404, description = "User or address not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    )
+)]
+#[tracing::instrument(skip(address_service, path), fields(user_id = user_id, address_id = path.1))]
+pub async fn get_address_handler(
+    Inject(address_service): Inject<AddressService>,
+    UserId(user_id): UserId,
+    Path(path): Path<(i32, i32)>,
+) -> impl IntoResponse {
+    let address_id = path.1;
+    match address_service.get_address(user_id, address_id).await {
+        Ok(address) => (StatusCode::OK, Json(address)).into_response(),
+        Err(e) => error_response(e, user_id),
+    }
+}
+
+/// HTTP handler for updating an address of a user
+#[utoipa::path(
+    put,
+    path = "/users/{id}/addresses/{address_id}",
+    tag = "addresses",
+    params(
+        ("id" = i32, Path, description = "User ID"),
+        ("address_id" = i32, Path, description = "Address ID")
+    ),
+    request_body = UpdateAddress,
+    responses(
+        (status = 200, description = "Address updated", body = Address),
+        (status = 400, description = "Validation errors", body = ValidationErrorResponse),
+        (status = 404, description = "User or address not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    )
+)]
+#[tracing::instrument(skip(address_service, path, payload), fields(user_id = user_id, address_id = path.1))]
+pub async fn update_address_handler(
+    Inject(address_service): Inject<AddressService>,
+    UserId(user_id): UserId,
+    Path(path): Path<(i32, i32)>,
+    Json(payload): Json<UpdateAddress>,
+) -> impl IntoResponse {
+    let address_id = path.1;
+    match address_service.update_address(user_id, address_id, payload).await {
+        Ok(address) => (StatusCode::OK, Json(address)).into_response(),
+        Err(e) => error_response(e, user_id),
+    }
+}
+
+/// HTTP handler for deleting an address of a user
+#[utoipa::path(
+    delete,
+    path = "/users/{id}/addresses/{address_id}",
+    tag = "addresses",
+    params(
+        ("id" = i32, Path, description = "User ID"),
+        ("address_id" = i32, Path, description = "Address ID")
+    ),
+    responses(
+        (status = 200, description = "Address deleted", body = ApiResponse),
+        (status = 400, description = "Invalid user ID", body = ValidationErrorResponse),
+        (status = 404, description = "User or address not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    )
+)]
+#[tracing::instrument(skip(address_service, path), fields(user_id = user_id, address_id = path.1))]
+pub async fn delete_address_handler(
+    Inject(address_service): Inject<AddressService>,
+    UserId(user_id): UserId,
+    Path(path): Path<(i32, i32)>,
+) -> impl IntoResponse {
+    let address_id = path.1;
+    match address_service.delete_address(user_id, address_id).await {
+        Ok(()) => (
+            StatusCode::OK,
+            Json(ApiResponse {
+                message: format!("Address with id {address_id} deleted successfully"),
+            }),
+        ).into_response(),
+        Err(e) => error_response(e, user_id),
+    }
+}
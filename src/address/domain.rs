@@ -0,0 +1,93 @@
+//! Address domain models
+
+use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+use utoipa::ToSchema;
+use chrono::{DateTime, Utc};
+
+use crate::user::domain::{UserError, ValidationError};
+
+/// Request payload for creating a new address
+#[derive(Deserialize, ToSchema, JsonSchema, Debug, Clone)]
+pub struct CreateAddress {
+    /// First address line (street and number)
+    pub line1: String,
+    /// Second address line (apartment, suite, etc.)
+    pub line2: Option<String>,
+    /// City or locality
+    pub city: String,
+    /// Postal code, validated against the country's format
+    pub postal_code: String,
+    /// ISO 3166-1 alpha-2 country code (e.g. `US`, `DE`)
+    pub country_code: String,
+}
+
+/// Request payload for updating an existing address
+#[derive(Deserialize, ToSchema, JsonSchema, Debug, Clone)]
+pub struct UpdateAddress {
+    /// Updated first address line (optional)
+    pub line1: Option<String>,
+    /// Updated second address line (optional)
+    pub line2: Option<String>,
+    /// Updated city (optional)
+    pub city: Option<String>,
+    /// Updated postal code (optional)
+    pub postal_code: Option<String>,
+    /// Updated country code (optional)
+    pub country_code: Option<String>,
+}
+
+/// Address entity returned by the API
+#[derive(Serialize, ToSchema, JsonSchema, Debug, Clone, sqlx::FromRow)]
+pub struct Address {
+    /// Unique address identifier
+    pub id: i32,
+    /// Owning user
+    pub user_id: i32,
+    /// First address line
+    pub line1: String,
+    /// Second address line
+    pub line2: Option<String>,
+    /// City or locality
+    pub city: String,
+    /// Postal code
+    pub postal_code: String,
+    /// ISO 3166-1 alpha-2 country code
+    pub country_code: String,
+    /// When the address was created
+    pub created_at: DateTime<Utc>,
+}
+
+impl Address {
+    /// Applies a partial update on top of this address, producing the full new state
+    #[must_use]
+    pub fn merged_with(&self, update: UpdateAddress) -> CreateAddress {
+        CreateAddress {
+            line1: update.line1.unwrap_or_else(|| self.line1.clone()),
+            line2: update.line2.or_else(|| self.line2.clone()),
+            city: update.city.unwrap_or_else(|| self.city.clone()),
+            postal_code: update.postal_code.unwrap_or_else(|| self.postal_code.clone()),
+            country_code: update.country_code.unwrap_or_else(|| self.country_code.clone()),
+        }
+    }
+}
+
+/// Domain errors for address operations
+#[derive(Debug, thiserror::Error)]
+pub enum AddressError {
+    /// Validation errors occurred during address data processing
+    #[error("Validation failed")]
+    ValidationError(Vec<ValidationError>),
+    /// The owning user does not exist
+    #[error("User not found")]
+    UserNotFound,
+    /// Address was not found for this user
+    #[error("Address not found")]
+    NotFound,
+    /// Database operation failed
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+    /// Error from the underlying user service
+    #[error("User service error: {0}")]
+    UserServiceError(UserError),
+}
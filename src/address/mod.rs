@@ -0,0 +1,18 @@
+//! Address module
+//!
+//! Demonstrates a nested resource (`/users/{id}/addresses`) built with the same
+//! layering as the user module. Addresses are stored in their own table and are
+//! removed automatically when their user is deleted.
+
+pub mod domain;
+pub mod repository;
+pub mod service;
+pub mod controller;
+pub mod validation;
+
+// Public exports
+pub use service::AddressService;
+pub use domain::{Address, AddressError, CreateAddress, UpdateAddress};
+
+// Export controller for OpenAPI documentation
+pub use controller::*;
@@ -0,0 +1,158 @@
+//! Address repository - handles database operations
+//!
+//! This module is private to the address module. All database access must go
+//! through `AddressService`.
+
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use tracing::{error, info, warn};
+
+use super::domain::{Address, AddressError, CreateAddress};
+
+/// Address repository for database operations
+#[derive(Clone)]
+pub(super) struct AddressRepository {
+    pool: PgPool,
+}
+
+impl AddressRepository {
+    /// Creates a new `AddressRepository` instance
+    pub(super) fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Creates a new address for a user
+    ///
+    /// Without an explicit `id` the addresses sequence assigns one.
+    pub(super) async fn create(
+        &self,
+        user_id: i32,
+        data: &CreateAddress,
+        id: Option<i32>,
+        created_at: DateTime<Utc>,
+    ) -> Result<Address, AddressError> {
+        info!(user_id, "Creating new address in database");
+
+        let address = sqlx::query_as!(
+            Address,
+            "INSERT INTO addresses (id, user_id, line1, line2, city, postal_code, country_code, created_at)
+             VALUES (COALESCE($8::INT4, nextval(pg_get_serial_sequence('addresses', 'id'))::INT4), $1, $2, $3, $4, $5, $6, $7)
+             RETURNING id, user_id, line1, line2, city, postal_code, country_code, created_at",
+            user_id,
+            data.line1.trim(),
+            data.line2.as_deref().map(str::trim),
+            data.city.trim(),
+            data.postal_code.trim(),
+            data.country_code.to_ascii_uppercase(),
+            created_at,
+            id
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| {
+            error!(error = %e, user_id, "Failed to create address in database");
+            AddressError::DatabaseError(e.to_string())
+        })?;
+
+        info!(user_id, address_id = address.id, "Address created successfully in database");
+        Ok(address)
+    }
+
+    /// Retrieves all addresses of a user
+    pub(super) async fn find_by_user(&self, user_id: i32) -> Result<Vec<Address>, AddressError> {
+        info!(user_id, "Fetching addresses for user from database");
+
+        let addresses = sqlx::query_as!(
+            Address,
+            "SELECT id, user_id, line1, line2, city, postal_code, country_code, created_at
+             FROM addresses WHERE user_id = $1 ORDER BY id",
+            user_id
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| {
+            error!(error = %e, user_id, "Failed to fetch addresses from database");
+            AddressError::DatabaseError(e.to_string())
+        })?;
+
+        info!(user_id, count = addresses.len(), "Addresses fetched successfully from database");
+        Ok(addresses)
+    }
+
+    /// Retrieves a specific address of a user
+    pub(super) async fn find_by_id(&self, user_id: i32, id: i32) -> Result<Option<Address>, AddressError> {
+        info!(user_id, address_id = id, "Fetching address by ID from database");
+
+        let address = sqlx::query_as!(
+            Address,
+            "SELECT id, user_id, line1, line2, city, postal_code, country_code, created_at
+             FROM addresses WHERE user_id = $1 AND id = $2",
+            user_id,
+            id
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| {
+            error!(error = %e, user_id, address_id = id, "Failed to fetch address from database");
+            AddressError::DatabaseError(e.to_string())
+        })?;
+
+        if address.is_none() {
+            warn!(user_id, address_id = id, "Address not found in database");
+        }
+
+        Ok(address)
+    }
+
+    /// Replaces the stored state of an address
+    pub(super) async fn update(&self, user_id: i32, id: i32, data: &CreateAddress) -> Result<Option<Address>, AddressError> {
+        info!(user_id, address_id = id, "Updating address in database");
+
+        let address = sqlx::query_as!(
+            Address,
+            "UPDATE addresses
+             SET line1 = $3, line2 = $4, city = $5, postal_code = $6, country_code = $7
+             WHERE user_id = $1 AND id = $2
+             RETURNING id, user_id, line1, line2, city, postal_code, country_code, created_at",
+            user_id,
+            id,
+            data.line1.trim(),
+            data.line2.as_deref().map(str::trim),
+            data.city.trim(),
+            data.postal_code.trim(),
+            data.country_code.to_ascii_uppercase()
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| {
+            error!(error = %e, user_id, address_id = id, "Failed to update address in database");
+            AddressError::DatabaseError(e.to_string())
+        })?;
+
+        if address.is_some() {
+            info!(user_id, address_id = id, "Address updated successfully in database");
+        }
+
+        Ok(address)
+    }
+
+    /// Deletes an address of a user
+    pub(super) async fn delete(&self, user_id: i32, id: i32) -> Result<bool, AddressError> {
+        info!(user_id, address_id = id, "Deleting address from database");
+
+        let result = sqlx::query!("DELETE FROM addresses WHERE user_id = $1 AND id = $2", user_id, id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| {
+                error!(error = %e, user_id, address_id = id, "Failed to delete address from database");
+                AddressError::DatabaseError(e.to_string())
+            })?;
+
+        let deleted = result.rows_affected() > 0;
+        if !deleted {
+            warn!(user_id, address_id = id, "Address not found for deletion in database");
+        }
+
+        Ok(deleted)
+    }
+}
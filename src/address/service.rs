@@ -0,0 +1,124 @@
+//! Address service - business logic layer
+//!
+//! Addresses are a nested resource of users. The service talks to the user
+//! module only through `UserReadPort`, following the same boundaries as the bank module.
+
+use std::sync::Arc;
+
+use sqlx::PgPool;
+use tracing::{info, warn};
+
+use crate::clock::{SharedClock, SystemClock};
+use crate::ids::{DefaultIdGenerator, SharedIdGenerator};
+use crate::user::{SharedUserReadPort, UserReadPort};
+
+use super::domain::{Address, AddressError, CreateAddress, UpdateAddress};
+use super::repository::AddressRepository;
+use super::validation::validate_address;
+
+/// Address service that handles business logic for user addresses
+#[derive(Clone)]
+pub struct AddressService {
+    repository: AddressRepository,
+    users: SharedUserReadPort,
+    clock: SharedClock,
+    ids: SharedIdGenerator,
+}
+
+impl AddressService {
+    /// Creates a new `AddressService` instance
+    #[must_use] pub fn new(pool: PgPool, users: impl UserReadPort + 'static) -> Self {
+        Self {
+            repository: AddressRepository::new(pool),
+            users: Arc::new(users),
+            clock: SystemClock::shared(),
+            ids: DefaultIdGenerator::shared(),
+        }
+    }
+
+    /// Uses `clock` instead of the system clock for timestamps
+    #[must_use] pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Uses `ids` instead of the database sequence for new address IDs
+    #[must_use] pub fn with_id_generator(mut self, ids: SharedIdGenerator) -> Self {
+        self.ids = ids;
+        self
+    }
+
+    /// Ensures the owning user exists before touching its addresses
+    async fn ensure_user_exists(&self, user_id: i32) -> Result<(), AddressError> {
+        match self.users.user_exists(user_id).await {
+            Ok(true) => Ok(()),
+            Ok(false) => {
+                warn!(user_id, "AddressService: User not found");
+                Err(AddressError::UserNotFound)
+            }
+            Err(e) => {
+                warn!(user_id, error = ?e, "AddressService: Error checking user existence");
+                Err(AddressError::UserServiceError(e))
+            }
+        }
+    }
+
+    /// Creates a new address for a user with validation
+    pub async fn create_address(&self, user_id: i32, data: CreateAddress) -> Result<Address, AddressError> {
+        info!(user_id, "AddressService: Creating address");
+
+        if let Err(validation_errors) = validate_address(&data) {
+            warn!(?validation_errors, "AddressService: Validation failed for create address");
+            return Err(AddressError::ValidationError(validation_errors));
+        }
+
+        self.ensure_user_exists(user_id).await?;
+        self.repository.create(user_id, &data, self.ids.next_id(), self.clock.now()).await
+    }
+
+    /// Lists all addresses of a user
+    pub async fn list_addresses(&self, user_id: i32) -> Result<Vec<Address>, AddressError> {
+        info!(user_id, "AddressService: Listing addresses");
+
+        self.ensure_user_exists(user_id).await?;
+        self.repository.find_by_user(user_id).await
+    }
+
+    /// Retrieves a specific address of a user
+    pub async fn get_address(&self, user_id: i32, id: i32) -> Result<Address, AddressError> {
+        info!(user_id, address_id = id, "AddressService: Fetching address");
+
+        self.ensure_user_exists(user_id).await?;
+        self.repository.find_by_id(user_id, id).await?.ok_or(AddressError::NotFound)
+    }
+
+    /// Updates an address, validating the merged result
+    ///
+    /// Validation runs on the combined state so that, for example, changing only the
+    /// country still checks the stored postal code against the new country's format.
+    pub async fn update_address(&self, user_id: i32, id: i32, data: UpdateAddress) -> Result<Address, AddressError> {
+        info!(user_id, address_id = id, "AddressService: Updating address");
+
+        let existing = self.get_address(user_id, id).await?;
+        let merged = existing.merged_with(data);
+
+        if let Err(validation_errors) = validate_address(&merged) {
+            warn!(?validation_errors, "AddressService: Validation failed for update address");
+            return Err(AddressError::ValidationError(validation_errors));
+        }
+
+        self.repository.update(user_id, id, &merged).await?.ok_or(AddressError::NotFound)
+    }
+
+    /// Deletes an address of a user
+    pub async fn delete_address(&self, user_id: i32, id: i32) -> Result<(), AddressError> {
+        info!(user_id, address_id = id, "AddressService: Deleting address");
+
+        self.ensure_user_exists(user_id).await?;
+        if self.repository.delete(user_id, id).await? {
+            Ok(())
+        } else {
+            Err(AddressError::NotFound)
+        }
+    }
+}
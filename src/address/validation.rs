@@ -0,0 +1,168 @@
+//! Address validation logic
+//!
+//! Validates country codes and country-specific postal code formats.
+
+use crate::error_codes::ErrorCode;
+use crate::user::validation::common::{field_error, ValidationResult};
+use crate::user::validation::rules::validate_max_length;
+
+use super::domain::CreateAddress;
+
+/// Postal code masks per country: `N` matches a digit, `A` a letter, anything else itself
+const POSTAL_CODE_FORMATS: &[(&str, &[&str])] = &[
+    ("US", &["NNNNN", "NNNNN-NNNN"]),
+    ("CA", &["ANA NAN", "ANANAN"]),
+    ("GB", &["AN NAA", "ANN NAA", "AAN NAA", "AANN NAA", "ANA NAA", "AANA NAA"]),
+    ("DE", &["NNNNN"]),
+    ("FR", &["NNNNN"]),
+    ("ES", &["NNNNN"]),
+    ("IT", &["NNNNN"]),
+    ("NL", &["NNNN AA", "NNNNAA"]),
+    ("BR", &["NNNNN-NNN", "NNNNNNNN"]),
+    ("JP", &["NNN-NNNN"]),
+];
+
+/// Checks a value against a postal code mask
+fn matches_mask(value: &str, mask: &str) -> bool {
+    value.chars().count() == mask.chars().count()
+        && value.chars().zip(mask.chars()).all(|(c, m)| match m {
+            'N' => c.is_ascii_digit(),
+            'A' => c.is_ascii_alphabetic(),
+            other => c == other,
+        })
+}
+
+/// Validates an ISO 3166-1 alpha-2 country code
+pub fn validate_country_code(country_code: &str, field_name: &str) -> ValidationResult {
+    if country_code.len() == 2 && country_code.chars().all(|c| c.is_ascii_alphabetic()) {
+        Ok(())
+    } else {
+        Err(vec![field_error(field_name, ErrorCode::InvalidCountryCode, "Country code must be a two-letter ISO 3166-1 code")])
+    }
+}
+
+/// Validates a postal code against the format of the given country
+///
+/// Countries without a known format accept 2-10 letters, digits, spaces or dashes.
+pub fn validate_postal_code(postal_code: &str, country_code: &str, field_name: &str) -> ValidationResult {
+    let postal_code = postal_code.trim();
+    let country_code = country_code.to_ascii_uppercase();
+
+    let valid = match POSTAL_CODE_FORMATS.iter().find(|(country, _)| *country == country_code) {
+        Some((_, masks)) => masks.iter().any(|mask| matches_mask(postal_code, mask)),
+        None => {
+            (2..=10).contains(&postal_code.len())
+                && postal_code
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == ' ' || c == '-')
+        },
+    };
+
+    if valid {
+        Ok(())
+    } else {
+        Err(vec![field_error(
+            field_name,
+            ErrorCode::InvalidPostalCode,
+            format!("Postal code is not valid for country {country_code}"),
+        )])
+    }
+}
+
+/// Validates a required free-text address field
+fn validate_required_text(value: &str, field_name: &str, max_length: usize) -> ValidationResult {
+    if value.trim().is_empty() {
+        return Err(vec![field_error(field_name, ErrorCode::FieldEmpty, "Field cannot be empty")]);
+    }
+    validate_max_length(value, field_name, max_length)
+}
+
+/// Validates the complete state of an address
+pub fn validate_address(address: &CreateAddress) -> ValidationResult {
+    let mut all_errors = Vec::new();
+
+    if let Err(mut errors) = validate_required_text(&address.line1, "line1", 255) {
+        all_errors.append(&mut errors);
+    }
+
+    if let Some(line2) = &address.line2
+        && let Err(mut errors) = validate_max_length(line2, "line2", 255)
+    {
+        all_errors.append(&mut errors);
+    }
+
+    if let Err(mut errors) = validate_required_text(&address.city, "city", 100) {
+        all_errors.append(&mut errors);
+    }
+
+    // Postal code formats depend on the country, so only check them for a valid country
+    match validate_country_code(&address.country_code, "country_code") {
+        Ok(()) => {
+            if let Err(mut errors) =
+                validate_postal_code(&address.postal_code, &address.country_code, "postal_code")
+            {
+                all_errors.append(&mut errors);
+            }
+        },
+        Err(mut errors) => all_errors.append(&mut errors),
+    }
+
+    if all_errors.is_empty() {
+        Ok(())
+    } else {
+        Err(all_errors)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn address(postal_code: &str, country_code: &str) -> CreateAddress {
+        CreateAddress {
+            line1: "1 Main Street".to_owned(),
+            line2: None,
+            city: "Springfield".to_owned(),
+            postal_code: postal_code.to_owned(),
+            country_code: country_code.to_owned(),
+        }
+    }
+
+    #[test]
+    fn test_validate_country_code() {
+        assert!(validate_country_code("US", "country_code").is_ok());
+        assert!(validate_country_code("de", "country_code").is_ok());
+        assert!(validate_country_code("USA", "country_code").is_err());
+        assert!(validate_country_code("1A", "country_code").is_err());
+    }
+
+    #[test]
+    fn test_validate_postal_code_known_formats() {
+        assert!(validate_postal_code("12345", "US", "postal_code").is_ok());
+        assert!(validate_postal_code("12345-6789", "US", "postal_code").is_ok());
+        assert!(validate_postal_code("K1A 0B1", "CA", "postal_code").is_ok());
+        assert!(validate_postal_code("SW1A 1AA", "GB", "postal_code").is_ok());
+        assert!(validate_postal_code("1234", "US", "postal_code").is_err());
+        assert!(validate_postal_code("ABCDE", "DE", "postal_code").is_err());
+    }
+
+    #[test]
+    fn test_validate_postal_code_unknown_country_fallback() {
+        assert!(validate_postal_code("AB-123", "ZZ", "postal_code").is_ok());
+        assert!(validate_postal_code("X", "ZZ", "postal_code").is_err());
+    }
+
+    #[test]
+    fn test_validate_address_valid() {
+        assert!(validate_address(&address("10115", "DE")).is_ok());
+    }
+
+    #[test]
+    fn test_validate_address_collects_errors() {
+        let mut invalid = address("??", "XXX");
+        invalid.line1 = "  ".to_owned();
+        let errors = validate_address(&invalid).unwrap_err();
+        assert!(errors.iter().any(|e| e.field.as_deref() == Some("line1")));
+        assert!(errors.iter().any(|e| e.field.as_deref() == Some("country_code")));
+    }
+}
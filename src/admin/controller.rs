@@ -0,0 +1,154 @@
+//! Admin controller - HTTP handlers for internal dashboards
+
+use std::sync::Arc;
+
+use axum::{
+    Json,
+    extract::Query,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use tracing::{error, warn};
+
+use crate::admin::{AdminService, QueryService, SelfTestService};
+use crate::config::ConfigDump;
+use crate::module::ModuleCatalog;
+use crate::registry::Inject;
+use crate::error_codes::{ErrorCode, ErrorResponse};
+
+use super::domain::{AdminOverview, QueryError, QueryRequest, QueryResult, RuntimeDiagnostics, RuntimeParams, SelfTestReport};
+
+/// HTTP handler returning the dashboard overview
+#[utoipa::path(
+    get,
+    path = "/admin/overview",
+    tag = "admin",
+    responses(
+        (status = 200, description = "Counts of users, accounts and recent signups, plus request and job statistics", body = AdminOverview),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+        (status = 403, description = "Caller lacks the admin role", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    )
+)]
+#[tracing::instrument(skip(admin_service))]
+pub async fn admin_overview_handler(Inject(admin_service): Inject<AdminService>) -> impl IntoResponse {
+    match admin_service.overview().await {
+        Ok(overview) => (StatusCode::OK, Json(overview)).into_response(),
+        Err(e) => {
+            error!(error = %e, "Controller: Failed to gather admin overview");
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
+
+/// HTTP handler returning runtime and process diagnostics
+#[utoipa::path(
+    get,
+    path = "/admin/runtime",
+    tag = "admin",
+    params(
+        ("heap" = Option<bool>, Query, description = "Also report heap figures (resident, proportional, anonymous and swapped memory); slower in large processes")
+    ),
+    responses(
+        (status = 200, description = "Tokio runtime metrics (alive tasks, queue depth, per-worker busy time), process stats (RSS, open FDs, threads) and allocator figures", body = RuntimeDiagnostics),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+        (status = 403, description = "Caller lacks the admin role", body = ErrorResponse)
+    )
+)]
+#[tracing::instrument(skip(admin_service))]
+pub async fn admin_runtime_handler(
+    Inject(admin_service): Inject<AdminService>,
+    Query(params): Query<RuntimeParams>,
+) -> Json<RuntimeDiagnostics> {
+    Json(admin_service.runtime(params.heap.unwrap_or(false)))
+}
+
+/// HTTP handler returning the sanitized configuration dump
+#[utoipa::path(
+    get,
+    path = "/admin/config",
+    tag = "admin",
+    responses(
+        (status = 200, description = "Resolved configuration with secrets masked, enabled features and the route table with access policies", body = ConfigDump),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+        (status = 403, description = "Caller lacks the admin role", body = ErrorResponse)
+    )
+)]
+#[tracing::instrument(skip(config_dump))]
+pub async fn admin_config_handler(Inject(config_dump): Inject<Arc<ConfigDump>>) -> Json<ConfigDump> {
+    Json(ConfigDump::clone(&config_dump))
+}
+
+/// HTTP handler listing the registered modules and what each contributes
+#[utoipa::path(
+    get,
+    path = "/admin/modules",
+    tag = "admin",
+    responses(
+        (status = 200, description = "Registered modules in mounting order, with their documented routes, health checks, feature flags and dependencies", body = ModuleCatalog),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+        (status = 403, description = "Caller lacks the admin role", body = ErrorResponse)
+    )
+)]
+#[tracing::instrument(skip(catalog))]
+pub async fn admin_modules_handler(Inject(catalog): Inject<Arc<ModuleCatalog>>) -> Json<ModuleCatalog> {
+    Json(ModuleCatalog::clone(&catalog))
+}
+
+/// HTTP handler running a named, read-only admin query
+///
+/// Queries are defined in code (`admin::query`); only their parameters come
+/// from the request. Rows are capped and the query is cancelled after the
+/// configured timeout.
+#[utoipa::path(
+    post,
+    path = "/admin/query",
+    tag = "admin",
+    request_body = QueryRequest,
+    responses(
+        (status = 200, description = "Rows of the query as JSON objects; `truncated` tells whether more rows matched", body = QueryResult),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+        (status = 403, description = "Caller lacks the admin role", body = ErrorResponse),
+        (status = 404, description = "No query has this name", body = ErrorResponse),
+        (status = 422, description = "A parameter is missing or has the wrong type", body = ErrorResponse),
+        (status = 503, description = "The query exceeded its timeout or the database is unavailable", body = ErrorResponse)
+    )
+)]
+#[tracing::instrument(skip(query_service))]
+pub async fn admin_query_handler(
+    Inject(query_service): Inject<QueryService>,
+    Json(request): Json<QueryRequest>,
+) -> Response {
+    match query_service.run(request).await {
+        Ok(result) => (StatusCode::OK, Json(result)).into_response(),
+        Err(e) => {
+            let (status, code) = match &e {
+                QueryError::UnknownQuery(_) => (StatusCode::NOT_FOUND, ErrorCode::QueryNotFound),
+                QueryError::InvalidParameter { .. } => (StatusCode::UNPROCESSABLE_ENTITY, ErrorCode::InvalidQueryParameter),
+                QueryError::Timeout(_) => (StatusCode::SERVICE_UNAVAILABLE, ErrorCode::QueryTimeout),
+                QueryError::DatabaseError(db) => return db.clone().into_response(),
+            };
+            warn!(error = %e, "Controller: Admin query rejected");
+            (status, Json(ErrorResponse::new(code, e.to_string()))).into_response()
+        }
+    }
+}
+
+/// HTTP handler running the synthetic create/read/update/delete self-test
+#[utoipa::path(
+    post,
+    path = "/admin/selftest",
+    tag = "admin",
+    responses(
+        (status = 200, description = "Every step passed; timing of each step of the cycle against the scratch table", body = SelfTestReport),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+        (status = 403, description = "Caller lacks the admin role", body = ErrorResponse),
+        (status = 503, description = "A step failed; the report says which and why", body = SelfTestReport)
+    )
+)]
+#[tracing::instrument(skip(selftest_service))]
+pub async fn admin_selftest_handler(Inject(selftest_service): Inject<SelfTestService>) -> Response {
+    let report = selftest_service.run().await;
+    let status = if report.passed { StatusCode::OK } else { StatusCode::SERVICE_UNAVAILABLE };
+    (status, Json(report)).into_response()
+}
@@ -0,0 +1,206 @@
+//! Admin domain models
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use utoipa::ToSchema;
+
+use crate::bank::BankError;
+use crate::db::{DbError, RetryCounts};
+use crate::stats::RequestCounts;
+use crate::user::domain::UserError;
+
+/// Aggregated figures for internal dashboards
+#[derive(Serialize, ToSchema, Debug, Clone, PartialEq)]
+pub struct AdminOverview {
+    /// All users
+    pub users: i64,
+    /// All bank accounts
+    pub accounts: i64,
+    /// Users created within the last 24 hours
+    pub recent_signups: i64,
+    /// Responses served by this instance since it started, by status class
+    pub requests: RequestCounts,
+    /// Balance changes retried after serialization failures or deadlocks on this instance
+    pub transaction_retries: RetryCounts,
+    /// Background job runs in progress on this instance; jobs run in-process
+    /// at fixed intervals, so there is no queue beyond these
+    pub job_queue_depth: u64,
+    /// When the figures were gathered; responses may be cached for a few seconds
+    pub generated_at: DateTime<Utc>,
+}
+
+/// Runtime and process figures for latency debugging
+#[derive(Serialize, ToSchema, Debug, Clone, PartialEq)]
+pub struct RuntimeDiagnostics {
+    /// Tokio runtime metrics
+    pub runtime: RuntimeStats,
+    /// Process resource usage
+    pub process: ProcessStats,
+    /// Memory allocator in use and, when requested, its heap figures
+    pub allocator: AllocatorStats,
+    /// When the figures were gathered
+    pub generated_at: DateTime<Utc>,
+}
+
+/// Metrics of the Tokio runtime serving requests
+#[derive(Serialize, ToSchema, Debug, Clone, PartialEq, Eq)]
+pub struct RuntimeStats {
+    /// Tasks spawned and not yet completed; steady growth points to a task leak
+    pub alive_tasks: usize,
+    /// Tasks waiting in the shared injection queue for a free worker
+    pub global_queue_depth: usize,
+    /// Per-worker figures, one entry per worker thread
+    pub workers: Vec<WorkerStats>,
+}
+
+/// Metrics of a single runtime worker thread
+#[derive(Serialize, ToSchema, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WorkerStats {
+    /// Time spent running tasks since the runtime started, in milliseconds
+    pub busy_ms: u64,
+    /// Times the worker went idle since the runtime started
+    pub park_count: u64,
+}
+
+/// Resource usage of the process (absent where the platform does not report it)
+#[derive(Serialize, ToSchema, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProcessStats {
+    /// Resident set size in bytes
+    pub resident_memory_bytes: Option<u64>,
+    /// Open file descriptors, sockets included
+    pub open_fds: Option<u64>,
+    /// Operating system threads, blocking pool included
+    pub threads: Option<u64>,
+}
+
+/// Memory allocator figures
+#[derive(Serialize, ToSchema, Debug, Clone, PartialEq, Eq)]
+pub struct AllocatorStats {
+    /// Global allocator the binary was built with
+    pub allocator: String,
+    /// Heap figures, present when requested with `heap=true` (Linux only)
+    pub heap: Option<HeapStats>,
+}
+
+/// Memory mapped by the process, split by kind (from `/proc/self/smaps_rollup`)
+#[derive(Serialize, ToSchema, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HeapStats {
+    /// Resident memory in bytes, shared pages included
+    pub resident_bytes: u64,
+    /// Resident memory in bytes with shared pages divided among their users
+    pub proportional_bytes: u64,
+    /// Anonymous memory in bytes: the allocator's heap, thread stacks and the like
+    pub anonymous_bytes: u64,
+    /// Memory swapped out, in bytes
+    pub swap_bytes: u64,
+}
+
+/// Query parameters of the runtime diagnostics
+#[derive(Deserialize, ToSchema, Debug, Clone, Default)]
+pub struct RuntimeParams {
+    /// Include heap figures, which read every memory mapping of the process
+    pub heap: Option<bool>,
+}
+
+/// Request running one of the named admin queries
+#[derive(Deserialize, ToSchema, Debug, Clone)]
+pub struct QueryRequest {
+    /// Name of the query
+    pub query: String,
+    /// Parameter values by name
+    #[serde(default)]
+    #[schema(value_type = Object)]
+    pub params: Map<String, Value>,
+    /// Rows to return at most; capped by the configured row limit
+    pub limit: Option<u32>,
+}
+
+/// Rows returned by a named admin query
+#[derive(Serialize, ToSchema, Debug, Clone, PartialEq)]
+pub struct QueryResult {
+    /// Name of the query
+    pub query: String,
+    /// Matching rows as objects keyed by column name
+    #[schema(value_type = Vec<Object>)]
+    pub rows: Vec<Value>,
+    /// Whether more rows matched than were returned
+    pub truncated: bool,
+}
+
+/// Errors of named admin queries
+#[derive(Debug, thiserror::Error)]
+pub enum QueryError {
+    /// No query has this name
+    #[error("Unknown query `{0}`")]
+    UnknownQuery(String),
+    /// A parameter is missing or has the wrong type
+    #[error("Parameter `{name}` must be {expected}")]
+    InvalidParameter {
+        /// Name of the parameter
+        name: &'static str,
+        /// Expected type of the value
+        expected: &'static str,
+    },
+    /// The query ran longer than the configured timeout
+    #[error("Query exceeded its {0} ms timeout")]
+    Timeout(u64),
+    /// Database operation failed
+    #[error("Database error: {0}")]
+    DatabaseError(DbError),
+}
+
+/// Step of the self-test cycle
+#[derive(Serialize, ToSchema, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SelfTestStepName {
+    /// Inserts a scratch row
+    Create,
+    /// Reads it back and compares the payload
+    Read,
+    /// Changes its payload
+    Update,
+    /// Deletes it
+    Delete,
+}
+
+/// Outcome and timing of one self-test step
+#[derive(Serialize, ToSchema, Debug, Clone, PartialEq, Eq)]
+pub struct SelfTestStep {
+    /// The step
+    pub step: SelfTestStepName,
+    /// Whether it succeeded
+    pub ok: bool,
+    /// Time it took, in microseconds
+    pub duration_us: u64,
+    /// Why it failed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Result of a self-test run
+///
+/// Steps after a failed one are not run, except `delete`, which always runs
+/// once the scratch row exists so that no row is left behind.
+#[derive(Serialize, ToSchema, Debug, Clone, PartialEq, Eq)]
+pub struct SelfTestReport {
+    /// Whether every step succeeded
+    pub passed: bool,
+    /// Time the whole cycle took, in microseconds
+    pub total_us: u64,
+    /// Steps in the order they ran
+    pub steps: Vec<SelfTestStep>,
+    /// When the run started
+    pub ran_at: DateTime<Utc>,
+}
+
+/// Domain errors for admin operations
+#[derive(Debug, thiserror::Error)]
+pub enum AdminError {
+    /// Error from the underlying user service
+    #[error("User service error: {0}")]
+    UserServiceError(UserError),
+    /// Error from the underlying account service
+    #[error("Account service error: {0}")]
+    AccountServiceError(BankError),
+}
@@ -0,0 +1,28 @@
+//! Admin module
+//!
+//! Aggregated figures for internal dashboards (`GET /admin/overview`). Reads
+//! users through `UserReadPort` and accounts through `AccountService`, and
+//! adds the request and job statistics the application keeps in memory.
+//! `GET /admin/runtime` reports Tokio runtime, process and allocator figures,
+//! and `GET /admin/modules` the registered [`crate::Module`]s.
+//! `POST /admin/query` runs the named, read-only queries of [`query`], and
+//! `POST /admin/selftest` the database canary of [`selftest`].
+
+pub mod controller;
+pub mod domain;
+pub mod query;
+mod runtime;
+pub mod selftest;
+pub mod service;
+
+// Public exports
+pub use domain::{
+    AdminError, AdminOverview, AllocatorStats, HeapStats, ProcessStats, QueryError, QueryRequest, QueryResult,
+    RuntimeDiagnostics, RuntimeParams, RuntimeStats, SelfTestReport, SelfTestStep, SelfTestStepName, WorkerStats,
+};
+pub use query::{NamedQuery, QueryService};
+pub use selftest::SelfTestService;
+pub use service::AdminService;
+
+// Export controller for OpenAPI documentation
+pub use controller::*;
@@ -0,0 +1,252 @@
+//! Named admin queries
+//!
+//! Support staff run the read-only queries defined here through
+//! `POST /admin/query` instead of getting raw SQL access. Each query is a
+//! fixed `SELECT` with typed, positional parameters. It runs in a read-only
+//! transaction under a statement timeout, and its rows are capped. Forks add
+//! their own queries with [`QueryService::with_query`].
+
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde_json::{Map, Value};
+use sqlx::PgPool;
+use tracing::{error, info, warn};
+
+use crate::db::DbError;
+
+use super::domain::{QueryError, QueryRequest, QueryResult};
+
+/// Rows a query returns when the request gives no limit and none is configured
+pub const DEFAULT_MAX_ROWS: u32 = 100;
+
+/// Time a query may run when none is configured
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Type of a query parameter
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParamKind {
+    /// A JSON integer, bound as `INT8`
+    Integer,
+    /// A JSON string, bound as `TEXT`
+    Text,
+    /// An RFC 3339 JSON string, bound as `TIMESTAMPTZ`
+    Timestamp,
+}
+
+/// A named parameter of a query
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QueryParam {
+    /// Name of the parameter in requests
+    pub name: &'static str,
+    /// Type of its value
+    pub kind: ParamKind,
+}
+
+/// A query support staff may run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NamedQuery {
+    /// Name of the query in requests
+    pub name: &'static str,
+    /// `SELECT` statement; parameters are `$1`, `$2`... in the order of `params`
+    pub sql: &'static str,
+    /// Parameters of the statement
+    pub params: &'static [QueryParam],
+}
+
+/// Queries available out of the box
+pub const BUILTIN_QUERIES: &[NamedQuery] = &[
+    NamedQuery {
+        name: "user_by_external_id",
+        sql: "SELECT id, name, age, status, created_at, erased_at, external_id FROM users WHERE external_id = $1",
+        params: &[QueryParam { name: "external_id", kind: ParamKind::Text }],
+    },
+    NamedQuery {
+        name: "users_by_status",
+        sql: "SELECT id, name, age, status, created_at FROM users WHERE status::TEXT = $1 ORDER BY id",
+        params: &[QueryParam { name: "status", kind: ParamKind::Text }],
+    },
+    NamedQuery {
+        name: "user_accounts",
+        sql: "SELECT id, account_number, kind, balance_cents, frozen, frozen_reason, created_at
+              FROM accounts WHERE user_id = $1 ORDER BY id",
+        params: &[QueryParam { name: "user_id", kind: ParamKind::Integer }],
+    },
+    NamedQuery {
+        name: "user_audit_trail",
+        sql: "SELECT id, account_id, action, details, occurred_at FROM audit_events
+              WHERE user_id = $1 AND occurred_at >= $2 ORDER BY occurred_at DESC, id DESC",
+        params: &[
+            QueryParam { name: "user_id", kind: ParamKind::Integer },
+            QueryParam { name: "since", kind: ParamKind::Timestamp },
+        ],
+    },
+    NamedQuery {
+        name: "pending_data_exports",
+        sql: "SELECT id, user_id, requested_at FROM data_exports WHERE status = 'pending' ORDER BY requested_at, id",
+        params: &[],
+    },
+];
+
+/// A parameter value bound to a query
+enum Bound {
+    /// An integer
+    Integer(i64),
+    /// A string
+    Text(String),
+    /// A point in time
+    Timestamp(DateTime<Utc>),
+}
+
+impl QueryParam {
+    /// Reads this parameter from the request's parameters
+    fn bind(&self, params: &Map<String, Value>) -> Result<Bound, QueryError> {
+        let value = params.get(self.name);
+        let bound = match self.kind {
+            ParamKind::Integer => value.and_then(Value::as_i64).map(Bound::Integer),
+            ParamKind::Text => value.and_then(Value::as_str).map(|text| Bound::Text(text.to_owned())),
+            ParamKind::Timestamp => value
+                .and_then(Value::as_str)
+                .and_then(|text| DateTime::parse_from_rfc3339(text).ok())
+                .map(|at| Bound::Timestamp(at.with_timezone(&Utc))),
+        };
+        bound.ok_or(QueryError::InvalidParameter {
+            name: self.name,
+            expected: match self.kind {
+                ParamKind::Integer => "an integer",
+                ParamKind::Text => "a string",
+                ParamKind::Timestamp => "an RFC 3339 timestamp",
+            },
+        })
+    }
+}
+
+/// Service running named admin queries
+#[derive(Clone)]
+pub struct QueryService {
+    pool: PgPool,
+    queries: Vec<NamedQuery>,
+    max_rows: u32,
+    timeout: Duration,
+}
+
+impl QueryService {
+    /// Creates a `QueryService` offering the [`BUILTIN_QUERIES`]
+    #[must_use] pub fn new(pool: PgPool) -> Self {
+        Self {
+            pool,
+            queries: BUILTIN_QUERIES.to_vec(),
+            max_rows: DEFAULT_MAX_ROWS,
+            timeout: DEFAULT_TIMEOUT,
+        }
+    }
+
+    /// Offers `query` too, replacing a query of the same name
+    #[must_use] pub fn with_query(mut self, query: NamedQuery) -> Self {
+        self.queries.retain(|existing| existing.name != query.name);
+        self.queries.push(query);
+        self
+    }
+
+    /// Returns at most `max_rows` rows per query, whatever the request asks for
+    #[must_use] pub fn with_max_rows(mut self, max_rows: u32) -> Self {
+        self.max_rows = max_rows.max(1);
+        self
+    }
+
+    /// Cancels queries running longer than `timeout`
+    #[must_use] pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Runs the requested query and returns its rows as JSON objects
+    pub async fn run(&self, request: QueryRequest) -> Result<QueryResult, QueryError> {
+        let query = self
+            .queries
+            .iter()
+            .find(|query| query.name == request.query)
+            .ok_or_else(|| QueryError::UnknownQuery(request.query.clone()))?;
+        let bound = query
+            .params
+            .iter()
+            .map(|param| param.bind(&request.params))
+            .collect::<Result<Vec<_>, _>>()?;
+        let limit = request.limit.unwrap_or(self.max_rows).clamp(1, self.max_rows);
+        let timeout_ms = u64::try_from(self.timeout.as_millis()).unwrap_or(u64::MAX).max(1);
+        info!(query = query.name, limit, timeout_ms, "Running admin query");
+
+        // One extra row tells whether the result was truncated
+        let sql = format!("SELECT to_jsonb(q) FROM ({}) q LIMIT {}", query.sql, u64::from(limit) + 1);
+        let mut statement = sqlx::query_scalar::<_, Value>(&sql);
+        for value in bound {
+            statement = match value {
+                Bound::Integer(value) => statement.bind(value),
+                Bound::Text(value) => statement.bind(value),
+                Bound::Timestamp(value) => statement.bind(value),
+            };
+        }
+
+        let database_error = |e: sqlx::Error| {
+            if let sqlx::Error::Database(db) = &e
+                && db.code().as_deref() == Some("57014")
+            {
+                warn!(query = query.name, timeout_ms, "Admin query timed out");
+                return QueryError::Timeout(timeout_ms);
+            }
+            error!(error = %e, query = query.name, "Admin query failed");
+            QueryError::DatabaseError(DbError::from(e))
+        };
+        let mut tx = self.pool.begin().await.map_err(database_error)?;
+        sqlx::query("SET TRANSACTION READ ONLY").execute(&mut *tx).await.map_err(database_error)?;
+        sqlx::query(&format!("SET LOCAL statement_timeout = {timeout_ms}"))
+            .execute(&mut *tx)
+            .await
+            .map_err(database_error)?;
+        let mut rows = statement.fetch_all(&mut *tx).await.map_err(database_error)?;
+        tx.rollback().await.map_err(database_error)?;
+
+        let truncated = rows.len() > limit as usize;
+        rows.truncate(limit as usize);
+        info!(query = query.name, rows = rows.len(), truncated, "Admin query completed");
+        Ok(QueryResult {
+            query: query.name.to_owned(),
+            rows,
+            truncated,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parameters_are_type_checked() {
+        let params: Map<String, Value> =
+            serde_json::from_str(r#"{"user_id": 7, "name": "x", "since": "2026-01-01T00:00:00Z"}"#).unwrap();
+        let param = |name, kind| QueryParam { name, kind };
+
+        assert!(matches!(param("user_id", ParamKind::Integer).bind(&params), Ok(Bound::Integer(7))));
+        assert!(matches!(param("since", ParamKind::Timestamp).bind(&params), Ok(Bound::Timestamp(_))));
+        assert!(matches!(
+            param("name", ParamKind::Integer).bind(&params),
+            Err(QueryError::InvalidParameter { name: "name", expected: "an integer" })
+        ));
+        assert!(matches!(
+            param("missing", ParamKind::Text).bind(&params),
+            Err(QueryError::InvalidParameter { name: "missing", .. })
+        ));
+        assert!(param("name", ParamKind::Timestamp).bind(&params).is_err());
+    }
+
+    #[test]
+    fn test_builtin_queries_number_their_parameters() {
+        for query in BUILTIN_QUERIES {
+            for position in 1..=query.params.len() {
+                assert!(query.sql.contains(&format!("${position}")), "{} lacks ${position}", query.name);
+            }
+            assert!(!query.sql.contains(&format!("${}", query.params.len() + 1)), "{} has extra parameters", query.name);
+        }
+    }
+}
@@ -0,0 +1,111 @@
+//! Runtime and process diagnostics
+//!
+//! Reads the Tokio runtime's own metrics and the process entries of
+//! `/proc/self`, so latency issues can be narrowed down (saturated workers,
+//! a growing injection queue, a task leak, memory growth) without attaching
+//! a profiler. Process figures are only available on Linux. Blocking pool
+//! figures need a build with `--cfg tokio_unstable` and are left out.
+//!
+//! The binary uses the system allocator: the crate forbids `unsafe`, which a
+//! counting or alternative `#[global_allocator]` needs. Heap figures are
+//! therefore taken from the kernel's view of the process, on demand, since
+//! summing every mapping takes a moment in a large process.
+
+use std::fs;
+
+use tokio::runtime::Handle;
+
+use super::domain::{AllocatorStats, HeapStats, ProcessStats, RuntimeStats, WorkerStats};
+
+/// Global allocator the binary is built with
+const ALLOCATOR: &str = "system";
+
+/// Gathers the metrics of the runtime driving the caller
+#[must_use]
+pub fn runtime_stats() -> RuntimeStats {
+    let metrics = Handle::current().metrics();
+    let workers = (0..metrics.num_workers())
+        .map(|worker| WorkerStats {
+            busy_ms: u64::try_from(metrics.worker_total_busy_duration(worker).as_millis()).unwrap_or(u64::MAX),
+            park_count: metrics.worker_park_count(worker),
+        })
+        .collect();
+    RuntimeStats {
+        alive_tasks: metrics.num_alive_tasks(),
+        global_queue_depth: metrics.global_queue_depth(),
+        workers,
+    }
+}
+
+/// Gathers memory, file descriptor and thread figures of this process
+#[must_use]
+pub fn process_stats() -> ProcessStats {
+    let status = fs::read_to_string("/proc/self/status").unwrap_or_default();
+    ProcessStats {
+        resident_memory_bytes: field(&status, "VmRSS:").map(|kib| kib * 1024),
+        open_fds: fs::read_dir("/proc/self/fd").ok().map(|entries| entries.count() as u64),
+        threads: field(&status, "Threads:"),
+    }
+}
+
+/// Names the allocator and, if `include_heap` is set, gathers its heap figures
+#[must_use]
+pub fn allocator_stats(include_heap: bool) -> AllocatorStats {
+    AllocatorStats {
+        allocator: ALLOCATOR.to_owned(),
+        heap: include_heap.then(heap_stats).flatten(),
+    }
+}
+
+/// Reads the memory totals of every mapping of this process
+fn heap_stats() -> Option<HeapStats> {
+    let rollup = fs::read_to_string("/proc/self/smaps_rollup").ok()?;
+    let bytes = |name: &str| field(&rollup, name).map(|kib| kib * 1024);
+    Some(HeapStats {
+        resident_bytes: bytes("Rss:")?,
+        proportional_bytes: bytes("Pss:")?,
+        anonymous_bytes: bytes("Anonymous:")?,
+        swap_bytes: bytes("Swap:")?,
+    })
+}
+
+/// Value of the `name` line of a `/proc` key-value file, without its unit
+fn field(text: &str, name: &str) -> Option<u64> {
+    text.lines()
+        .find_map(|line| line.strip_prefix(name))
+        .and_then(|value| value.split_whitespace().next())
+        .and_then(|value| value.parse().ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_runtime_stats_cover_every_worker() {
+        let stats = runtime_stats();
+
+        assert_eq!(stats.workers.len(), 2);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_process_stats_on_linux() {
+        let stats = process_stats();
+
+        assert!(stats.resident_memory_bytes.is_some_and(|bytes| bytes > 0));
+        assert!(stats.open_fds.is_some_and(|fds| fds >= 3));
+        assert!(stats.threads.is_some_and(|threads| threads >= 1));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_heap_stats_only_on_demand() {
+        let summary = allocator_stats(false);
+        let detailed = allocator_stats(true);
+
+        assert_eq!(summary.allocator, "system");
+        assert!(summary.heap.is_none());
+        assert!(detailed.heap.is_some_and(|heap| heap.resident_bytes >= heap.anonymous_bytes));
+    }
+}
@@ -0,0 +1,154 @@
+//! Synthetic self-test
+//!
+//! `POST /admin/selftest` runs one create → read → update → delete cycle
+//! against the `selftest_scratch` table, each step its own committed
+//! statement on the pool, and reports how long each took. It exercises
+//! writes, reads and the pool end to end, which the `SELECT 1` of the health
+//! check does not, without touching business data.
+
+use std::{
+    future::Future,
+    time::{Duration, Instant},
+};
+
+use sqlx::PgPool;
+use tracing::{info, warn};
+
+use crate::clock::{SharedClock, SystemClock};
+use crate::db::DbError;
+
+use super::domain::{SelfTestReport, SelfTestStep, SelfTestStepName};
+
+/// Runs the self-test cycle
+#[derive(Clone)]
+pub struct SelfTestService {
+    pool: PgPool,
+    clock: SharedClock,
+}
+
+impl SelfTestService {
+    /// Creates a service running against `pool`
+    #[must_use]
+    pub fn new(pool: PgPool) -> Self {
+        Self {
+            pool,
+            clock: SystemClock::shared(),
+        }
+    }
+
+    /// Uses `clock` for the run time and scratch payloads
+    #[must_use]
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Runs the cycle, stopping at the first failed step but always deleting the scratch row
+    pub async fn run(&self) -> SelfTestReport {
+        let ran_at = self.clock.now();
+        let started = Instant::now();
+        let payload = format!("selftest {}", ran_at.to_rfc3339());
+        let updated = format!("{payload} (updated)");
+        let mut steps = Vec::new();
+
+        let (step, id) = timed(SelfTestStepName::Create, self.create(&payload)).await;
+        steps.push(step);
+        if let Some(id) = id {
+            let (step, read) = timed(SelfTestStepName::Read, self.read(id, &payload)).await;
+            steps.push(step);
+            if read.is_some() {
+                let (step, _) = timed(SelfTestStepName::Update, self.update(id, &updated)).await;
+                steps.push(step);
+            }
+            let (step, _) = timed(SelfTestStepName::Delete, self.delete(id)).await;
+            steps.push(step);
+        }
+
+        let report = SelfTestReport {
+            passed: steps.iter().all(|step| step.ok),
+            total_us: micros(started.elapsed()),
+            steps,
+            ran_at,
+        };
+        if report.passed {
+            info!(total_us = report.total_us, "SelfTestService: Self-test passed");
+        } else {
+            warn!(?report, "SelfTestService: Self-test failed");
+        }
+        report
+    }
+
+    /// Inserts a scratch row holding `payload`, returning its ID
+    async fn create(&self, payload: &str) -> Result<i64, String> {
+        sqlx::query_scalar!("INSERT INTO selftest_scratch (payload) VALUES ($1) RETURNING id", payload)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(database_error)
+    }
+
+    /// Reads the scratch row back, checking that it holds `payload`
+    async fn read(&self, id: i64, payload: &str) -> Result<(), String> {
+        let stored = sqlx::query_scalar!("SELECT payload FROM selftest_scratch WHERE id = $1", id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(database_error)?;
+        match stored {
+            Some(stored) if stored == payload => Ok(()),
+            Some(_) => Err("The scratch row was read back with a different payload".to_owned()),
+            None => Err("The scratch row was not found after it was created".to_owned()),
+        }
+    }
+
+    /// Replaces the payload of the scratch row with `payload`
+    async fn update(&self, id: i64, payload: &str) -> Result<(), String> {
+        let stored = sqlx::query_scalar!(
+            "UPDATE selftest_scratch SET payload = $2, updated_at = $3 WHERE id = $1 RETURNING payload",
+            id,
+            payload,
+            self.clock.now()
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(database_error)?;
+        match stored {
+            Some(stored) if stored == payload => Ok(()),
+            Some(_) => Err("The update did not store the new payload".to_owned()),
+            None => Err("The scratch row disappeared before it was updated".to_owned()),
+        }
+    }
+
+    /// Deletes the scratch row
+    async fn delete(&self, id: i64) -> Result<(), String> {
+        let deleted = sqlx::query!("DELETE FROM selftest_scratch WHERE id = $1", id)
+            .execute(&self.pool)
+            .await
+            .map_err(database_error)?
+            .rows_affected();
+        if deleted == 1 {
+            Ok(())
+        } else {
+            Err("The scratch row was not found when deleting it".to_owned())
+        }
+    }
+}
+
+/// Runs `step`, timing it
+async fn timed<T>(name: SelfTestStepName, step: impl Future<Output = Result<T, String>>) -> (SelfTestStep, Option<T>) {
+    let started = Instant::now();
+    let result = step.await;
+    let duration_us = micros(started.elapsed());
+    match result {
+        Ok(value) => (SelfTestStep { step: name, ok: true, duration_us, error: None }, Some(value)),
+        Err(error) => (SelfTestStep { step: name, ok: false, duration_us, error: Some(error) }, None),
+    }
+}
+
+/// Describes a failed statement, counting it as a database failure
+fn database_error(error: sqlx::Error) -> String {
+    DbError::from(error).to_string()
+}
+
+/// `duration` in whole microseconds
+fn micros(duration: Duration) -> u64 {
+    u64::try_from(duration.as_micros()).unwrap_or(u64::MAX)
+}
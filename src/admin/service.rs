@@ -0,0 +1,113 @@
+//! Admin service - business logic layer
+//!
+//! Gathers the dashboard figures concurrently and caches them briefly, so a
+//! dashboard polling every second costs the database one round of counts
+//! per cache period.
+
+use std::sync::{Arc, Mutex, PoisonError};
+
+use chrono::Duration;
+use tracing::{info, warn};
+
+use crate::bank::AccountService;
+use crate::clock::{SharedClock, SystemClock};
+use crate::jobs::JobTracker;
+use crate::stats::RequestStats;
+use crate::user::{SharedUserReadPort, UserReadPort};
+
+use super::domain::{AdminError, AdminOverview, RuntimeDiagnostics};
+use super::runtime::{allocator_stats, process_stats, runtime_stats};
+
+/// Default time an overview is served from the cache
+const DEFAULT_CACHE_TTL_SECS: i64 = 5;
+
+/// Admin service that aggregates figures from the other modules
+#[derive(Clone)]
+pub struct AdminService {
+    users: SharedUserReadPort,
+    accounts: AccountService,
+    requests: RequestStats,
+    jobs: JobTracker,
+    clock: SharedClock,
+    cache_ttl: Duration,
+    cache: Arc<Mutex<Option<AdminOverview>>>,
+}
+
+impl AdminService {
+    /// Creates a new `AdminService` instance
+    #[must_use] pub fn new(
+        users: impl UserReadPort + 'static,
+        accounts: AccountService,
+        requests: RequestStats,
+        jobs: JobTracker,
+    ) -> Self {
+        Self {
+            users: Arc::new(users),
+            accounts,
+            requests,
+            jobs,
+            clock: SystemClock::shared(),
+            cache_ttl: Duration::seconds(DEFAULT_CACHE_TTL_SECS),
+            cache: Arc::default(),
+        }
+    }
+
+    /// Uses `clock` instead of the system clock for timestamps and cache expiry
+    #[must_use] pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Serves an overview from the cache for `ttl` after gathering it (zero disables caching)
+    #[must_use] pub fn with_cache_ttl(mut self, ttl: Duration) -> Self {
+        self.cache_ttl = ttl;
+        self
+    }
+
+    /// Returns the dashboard figures, from the cache while it is fresh
+    pub async fn overview(&self) -> Result<AdminOverview, AdminError> {
+        let now = self.clock.now();
+        let cached = self.cache.lock().unwrap_or_else(PoisonError::into_inner).clone();
+        if let Some(overview) = cached.filter(|overview| now - overview.generated_at < self.cache_ttl) {
+            return Ok(overview);
+        }
+
+        info!("AdminService: Gathering overview");
+        let (users, accounts) = tokio::join!(
+            self.users.count_users(now - Duration::days(1)),
+            self.accounts.count_accounts()
+        );
+        let users = users.map_err(|e| {
+            warn!(error = %e, "AdminService: Failed to count users");
+            AdminError::UserServiceError(e)
+        })?;
+        let accounts = accounts.map_err(|e| {
+            warn!(error = %e, "AdminService: Failed to count accounts");
+            AdminError::AccountServiceError(e)
+        })?;
+
+        let overview = AdminOverview {
+            users: users.total,
+            accounts,
+            recent_signups: users.created_since,
+            requests: self.requests.snapshot(),
+            transaction_retries: self.accounts.retry_counts(),
+            job_queue_depth: self.jobs.running(),
+            generated_at: now,
+        };
+        *self.cache.lock().unwrap_or_else(PoisonError::into_inner) = Some(overview.clone());
+        Ok(overview)
+    }
+
+    /// Returns the current runtime, process and allocator figures; never cached
+    ///
+    /// Heap figures are only gathered when `include_heap` is set.
+    #[must_use] pub fn runtime(&self, include_heap: bool) -> RuntimeDiagnostics {
+        RuntimeDiagnostics {
+            runtime: runtime_stats(),
+            process: process_stats(),
+            allocator: allocator_stats(include_heap),
+            generated_at: self.clock.now(),
+        }
+    }
+}
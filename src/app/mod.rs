@@ -0,0 +1,673 @@
+//! Application lifecycle
+//!
+//! [`AppBuilder`] assembles the application from its configuration and runs
+//! named hooks around it: startup hooks (run migrations, warm caches, start
+//! workers) run in registration order before the router is built, and shutdown
+//! hooks (flush telemetry, stop workers) run in reverse order once the server
+//! has drained. Forks extend startup by registering hooks instead of editing
+//! `main.rs`. Built-in background jobs (interest accrual, ledger verification, data exports, retention purges, column backfills) start once the
+//! startup hooks have run and stop with the server, letting runs in progress finish for up to
+//! `JOB_SHUTDOWN_GRACE_SECS`; each runs once per interval across the replicas. They all write, so none start
+//! with `READ_ONLY` set.
+
+use std::{future::Future, net::SocketAddr, sync::Arc, time::Duration};
+
+use axum::Router;
+use futures_util::future::BoxFuture;
+use sqlx::PgPool;
+use tokio::net::TcpListener;
+use tracing::{error, info, warn};
+
+use crate::bank::{AccountService, InterestAccrualJob, LedgerVerificationJob};
+use crate::config::ConfigError;
+use crate::db::{BackfillJob, Migrations};
+use crate::jobs::{JobHandle, JobHandler, QueueWorker, SharedJobHandler, singleton, spawn_periodic};
+use crate::notify::NotifyListener;
+use crate::privacy::DataExportJob;
+use crate::retention::{RetentionJob, RetentionPolicy, RetentionService};
+use crate::{
+    AppConfig, AppProviders, DatabaseCircuit, Module, ModuleRegistry, ServiceRegistry, UserService, create_router, job_queue,
+    privacy_service, user_rename, webhook_service,
+};
+use crate::partner::{KeyEncryptionKey, PartnerService};
+use crate::webhook::{WebhookDeliveryHandler, forward_events};
+
+mod state;
+
+pub use state::AppStateBuilder;
+
+/// Error returned by a lifecycle hook
+pub type HookError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Outcome of a startup hook
+pub type HookResult = Result<(), HookError>;
+
+type StartupHook = Box<dyn FnOnce(StartupContext) -> BoxFuture<'static, HookResult> + Send>;
+type ShutdownHook = Box<dyn FnOnce() -> BoxFuture<'static, ()> + Send>;
+
+/// Resources available to startup hooks
+#[derive(Debug, Clone)]
+pub struct StartupContext {
+    /// Database pool the application will use
+    pub pool: PgPool,
+    /// Loaded configuration
+    pub config: AppConfig,
+}
+
+/// Errors that can occur while starting or running the application
+#[derive(Debug, thiserror::Error)]
+pub enum AppError {
+    /// Required configuration is missing
+    #[error("Invalid configuration: {0}")]
+    Config(#[from] ConfigError),
+    /// The database could not be reached
+    #[error("Failed to connect to the database: {0}")]
+    Database(#[from] sqlx::Error),
+    /// A startup hook failed; the application does not start
+    #[error("Startup hook `{name}` failed: {source}")]
+    StartupHook {
+        /// Name the hook was registered under
+        name: &'static str,
+        /// Error returned by the hook
+        source: HookError,
+    },
+    /// The server stopped with an I/O error
+    #[error("Server error: {0}")]
+    Serve(#[from] std::io::Error),
+}
+
+/// Builds the application and registers its lifecycle hooks
+pub struct AppBuilder {
+    config: AppConfig,
+    pool: Option<PgPool>,
+    providers: AppProviders,
+    modules: ModuleRegistry,
+    services: ServiceRegistry,
+    job_handlers: Vec<SharedJobHandler>,
+    startup: Vec<(&'static str, StartupHook)>,
+    shutdown: Vec<(&'static str, ShutdownHook)>,
+}
+
+impl AppBuilder {
+    /// Starts building an application from `config`
+    #[must_use]
+    pub fn new(config: AppConfig) -> Self {
+        Self {
+            config,
+            pool: None,
+            providers: AppProviders::default(),
+            modules: ModuleRegistry::new(),
+            services: ServiceRegistry::new(),
+            job_handlers: Vec::new(),
+            startup: Vec::new(),
+            shutdown: Vec::new(),
+        }
+    }
+
+    /// Uses an existing pool instead of connecting to `DATABASE_URL`
+    #[must_use]
+    pub fn pool(mut self, pool: PgPool) -> Self {
+        self.pool = Some(pool);
+        self
+    }
+
+    /// Uses the given clock and ID generator
+    #[must_use]
+    pub fn providers(mut self, providers: AppProviders) -> Self {
+        self.providers = providers;
+        self
+    }
+
+    /// Mounts `module` alongside the built-in routes
+    #[must_use]
+    pub fn module(mut self, module: impl Module + 'static) -> Self {
+        self.modules.register(module);
+        self
+    }
+
+    /// Registers `service` in place of the built-in service of the same type
+    #[must_use]
+    pub fn service<T: Send + Sync + 'static>(mut self, service: T) -> Self {
+        self.services.insert(service);
+        self
+    }
+
+    /// Runs queued jobs of `handler.kind()` with `handler`
+    ///
+    /// A worker runs each queue of `JOB_QUEUES`, polling every
+    /// `JOB_QUEUE_INTERVAL_SECS`, with these handlers and the built-in
+    /// webhook delivery handler.
+    #[must_use]
+    pub fn job_handler(mut self, handler: impl JobHandler + 'static) -> Self {
+        self.job_handlers.push(Arc::new(handler));
+        self
+    }
+
+    /// Registers a hook to run before the router is built
+    ///
+    /// Hooks run in registration order; the first failure aborts startup.
+    #[must_use]
+    pub fn on_startup<F, Fut>(mut self, name: &'static str, hook: F) -> Self
+    where
+        F: FnOnce(StartupContext) -> Fut + Send + 'static,
+        Fut: Future<Output = HookResult> + Send + 'static,
+    {
+        self.startup.push((name, Box::new(move |ctx| Box::pin(hook(ctx)))));
+        self
+    }
+
+    /// Registers a hook to run after the server has shut down
+    ///
+    /// Hooks run in reverse registration order, so resources are released in
+    /// the opposite order they were set up.
+    #[must_use]
+    pub fn on_shutdown<F, Fut>(mut self, name: &'static str, hook: F) -> Self
+    where
+        F: FnOnce() -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        self.shutdown.push((name, Box::new(move || Box::pin(hook()))));
+        self
+    }
+
+    /// Connects to the database, runs the startup hooks and builds the router
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database is unreachable or a startup hook fails.
+    pub async fn build(self) -> Result<App, AppError> {
+        let pool = if let Some(pool) = self.pool {
+            pool
+        } else {
+            info!("Connecting to database...");
+            let pool = crate::db::connect(&self.config.database).await?;
+            info!(
+                "Database connection established with {} max connections",
+                self.config.database.max_connections
+            );
+            pool
+        };
+
+        let mut providers = self.providers;
+        let mut shutdown = self.shutdown;
+
+        let context = StartupContext {
+            pool: pool.clone(),
+            config: self.config.clone(),
+        };
+        for (name, hook) in self.startup {
+            info!(hook = name, "Running startup hook");
+            hook(context.clone()).await.map_err(|source| {
+                error!(hook = name, error = %source, "Startup hook failed");
+                AppError::StartupHook { name, source }
+            })?;
+        }
+
+        if self.config.database.monitor_interval_secs > 0 {
+            let interval = Duration::from_secs(self.config.database.monitor_interval_secs);
+            providers.circuit = DatabaseCircuit::new(interval);
+            let slow_acquire = (self.config.database.slow_acquire_ms > 0).then_some(Duration::from_millis(self.config.database.slow_acquire_ms));
+            let monitor = providers.circuit.monitor(pool.clone(), interval, slow_acquire);
+            shutdown.push(("database-monitor", Box::new(move || Box::pin(async move { monitor.abort() }))));
+        }
+        let channels = self.config.notify.channel_list();
+        if !channels.is_empty() {
+            let listener = NotifyListener::new(pool.clone(), channels, providers.notifications.clone())
+                .with_max_backoff(Duration::from_secs(self.config.notify.reconnect_max_secs))
+                .spawn();
+            shutdown.push(("notify-listener", Box::new(move || Box::pin(async move { listener.abort() }))));
+        }
+        // Every background job writes, so a read-only replica runs none of them
+        let jobs = if self.config.server.read_only {
+            info!("Read-only mode: background jobs are not started");
+            Vec::new()
+        } else {
+            spawn_jobs(&pool, &providers, &self.config, self.job_handlers, &mut shutdown)
+        };
+        if !jobs.is_empty() {
+            let grace = Duration::from_secs(self.config.jobs.shutdown_grace_secs);
+            shutdown.push(("background-jobs", Box::new(move || Box::pin(stop_jobs(jobs, grace)))));
+        }
+
+        Ok(App {
+            router: create_router(pool, providers, &self.modules, &self.services, Some(&self.config)),
+            shutdown,
+        })
+    }
+}
+
+/// A built application, ready to serve
+pub struct App {
+    router: Router,
+    shutdown: Vec<(&'static str, ShutdownHook)>,
+}
+
+impl App {
+    /// The application router
+    pub fn router(&self) -> Router {
+        self.router.clone()
+    }
+
+    /// Returns the router without running the shutdown hooks
+    pub fn into_router(self) -> Router {
+        self.router
+    }
+
+    /// Serves requests on `listener` until `signal` resolves, then runs the shutdown hooks
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the server fails; shutdown hooks run either way.
+    pub async fn serve(
+        self,
+        listener: TcpListener,
+        signal: impl Future<Output = ()> + Send + 'static,
+    ) -> Result<(), AppError> {
+        let result = axum::serve(listener, self.router.into_make_service_with_connect_info::<SocketAddr>())
+            .with_graceful_shutdown(signal)
+            .await;
+
+        run_shutdown_hooks(self.shutdown).await;
+        Ok(result?)
+    }
+
+    /// Serves requests over mutual TLS on `listener` until `signal` resolves, then runs the shutdown hooks
+    ///
+    /// Handlers see the client certificate subject through the
+    /// [`ClientIdentity`](crate::mtls::ClientIdentity) extractor.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the server fails; shutdown hooks run either way.
+    #[cfg(feature = "mtls")]
+    pub async fn serve_mtls(
+        self,
+        listener: crate::mtls::MtlsListener,
+        signal: impl Future<Output = ()> + Send + 'static,
+    ) -> Result<(), AppError> {
+        let result = axum::serve(
+            listener,
+            self.router
+                .layer(axum::middleware::from_fn(crate::mtls::expose_peer))
+                .into_make_service_with_connect_info::<crate::mtls::MtlsPeer>(),
+        )
+        .with_graceful_shutdown(signal)
+        .await;
+
+        run_shutdown_hooks(self.shutdown).await;
+        Ok(result?)
+    }
+}
+
+/// Runs shutdown hooks in reverse registration order
+async fn run_shutdown_hooks(hooks: Vec<(&'static str, ShutdownHook)>) {
+    for (name, hook) in hooks.into_iter().rev() {
+        info!(hook = name, "Running shutdown hook");
+        hook().await;
+    }
+}
+
+/// Starts the background jobs enabled in `config`, registering shutdown hooks
+/// for the tasks that feed them
+fn spawn_jobs(
+    pool: &PgPool,
+    providers: &AppProviders,
+    config: &AppConfig,
+    job_handlers: Vec<SharedJobHandler>,
+    shutdown: &mut Vec<(&'static str, ShutdownHook)>,
+) -> Vec<JobHandle> {
+    let (bank, privacy, retention, migration) = (&config.bank, &config.privacy, &config.retention, &config.migration);
+    let mut jobs = Vec::new();
+    let accounts = AccountService::new(pool.clone(), UserService::new(pool.clone()))
+        .with_clock(Arc::clone(&providers.clock));
+    if bank.savings_rate_bps > 0 && bank.interest_interval_secs > 0 {
+        let job = InterestAccrualJob::new(accounts.clone(), bank.savings_rate_bps, bank.interest_batch_size);
+        let every = Duration::from_secs(bank.interest_interval_secs);
+        jobs.push(spawn_periodic(singleton(pool.clone(), providers.jobs.track(Arc::new(job)), every), every));
+    }
+    if bank.ledger_verify_interval_secs > 0 {
+        let job = LedgerVerificationJob::new(accounts);
+        let every = Duration::from_secs(bank.ledger_verify_interval_secs);
+        jobs.push(spawn_periodic(singleton(pool.clone(), providers.jobs.track(Arc::new(job)), every), every));
+    }
+    if privacy.export_interval_secs > 0 {
+        let exports = privacy_service(pool, UserService::new(pool.clone()), &providers.clock, privacy);
+        let job = DataExportJob::new(exports, privacy.export_batch_size);
+        let every = Duration::from_secs(privacy.export_interval_secs);
+        jobs.push(spawn_periodic(singleton(pool.clone(), providers.jobs.track(Arc::new(job)), every), every));
+    }
+    let policies = RetentionPolicy::from_config(retention);
+    if retention.interval_secs > 0 && !policies.is_empty() {
+        let purger = RetentionService::new(pool.clone(), policies)
+            .with_clock(Arc::clone(&providers.clock))
+            .with_batch_size(retention.batch_size);
+        let every = Duration::from_secs(retention.interval_secs);
+        jobs.push(spawn_periodic(singleton(pool.clone(), providers.jobs.track(Arc::new(RetentionJob::new(purger))), every), every));
+    }
+    if migration.backfill_interval_secs > 0
+        && let Some(rename) = user_rename(migration)
+    {
+        let job = BackfillJob::new(pool.clone(), rename, migration.backfill_batch_size);
+        let every = Duration::from_secs(migration.backfill_interval_secs);
+        jobs.push(spawn_periodic(singleton(pool.clone(), providers.jobs.track(Arc::new(job)), every), every));
+    }
+    let queue = &config.jobs;
+    if queue.queue_interval_secs > 0 {
+        let webhooks = webhook_service(pool, &providers.clock, &providers.ids, queue, &config.webhook);
+        let forwarder = forward_events(webhooks.clone(), &providers.events);
+        shutdown.push(("webhook-forwarding", Box::new(move || Box::pin(async move { forwarder.abort() }))));
+        let worker = job_handlers
+            .into_iter()
+            .fold(
+                QueueWorker::new(job_queue(pool, queue))
+                    .with_batch_size(queue.queue_batch_size)
+                    .with_handler(Arc::new(WebhookDeliveryHandler::new(webhooks))),
+                QueueWorker::with_handler,
+            );
+        for (name, concurrency) in queue.queue_limits() {
+            let worker = worker.clone().on_queue(name).with_concurrency(concurrency);
+            jobs.push(spawn_periodic(providers.jobs.track(Arc::new(worker)), Duration::from_secs(queue.queue_interval_secs)));
+        }
+    }
+    jobs
+}
+
+/// Stops background jobs together, letting each run in progress finish within `grace`
+async fn stop_jobs(jobs: Vec<JobHandle>, grace: Duration) {
+    let stopped = futures_util::future::join_all(jobs.into_iter().map(|job| job.stop(grace))).await;
+    let aborted = stopped.iter().filter(|finished| !**finished).count();
+    if aborted > 0 {
+        warn!(aborted, "Background jobs aborted at shutdown; their work is retried by the next run");
+    }
+}
+
+/// Resolves when the process receives Ctrl+C
+///
+/// # Panics
+///
+/// Panics if the signal handler cannot be installed.
+pub async fn ctrl_c() {
+    tokio::signal::ctrl_c()
+        .await
+        .expect("Failed to install CTRL+C signal handler");
+    info!("Shutdown signal received, starting graceful shutdown...");
+}
+
+/// Resolves when the process receives Ctrl+C or, on Unix, SIGTERM
+///
+/// Orchestrators (Kubernetes, Fly.io, ECS) send SIGTERM before stopping a
+/// replica during a deploy.
+///
+/// # Panics
+///
+/// Panics if a signal handler cannot be installed.
+pub async fn shutdown_signal() {
+    #[cfg(unix)]
+    let terminate = async {
+        tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+            .expect("Failed to install SIGTERM signal handler")
+            .recv()
+            .await;
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        () = ctrl_c() => {},
+        () = terminate => info!("SIGTERM received, starting graceful shutdown..."),
+    }
+}
+
+/// Startup hook applying the pending migrations of every built-in slice
+///
+/// Forks including only some slices register their own hook running
+/// `Migrations::builtin().without(..)` instead.
+///
+/// # Errors
+///
+/// Returns an error if a migration fails.
+pub async fn run_migrations(context: StartupContext) -> HookResult {
+    let migrations = Migrations::builtin();
+    migrations.run(&context.pool).await?;
+    info!(domains = ?migrations.domains().collect::<Vec<_>>(), "Database migrations applied");
+    Ok(())
+}
+
+/// Startup hook sealing partner signing keys stored in the clear before keys
+/// were sealed with the configured `PARTNER_KEY_ENCRYPTION_KEY`
+///
+/// Register it after the migrations, and only with a configured key: keys
+/// sealed with the random per-process key would not open after a restart.
+///
+/// # Errors
+///
+/// Returns an error if the keys cannot be read or updated.
+pub async fn seal_partner_keys(context: StartupContext) -> HookResult {
+    let kek = KeyEncryptionKey::from_config(&context.config.partner);
+    PartnerService::new(context.pool).with_key_encryption_key(kek).seal_stored_keys().await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::postgres::PgPoolOptions;
+    use std::sync::{Arc, Mutex};
+
+    fn lazy_builder() -> AppBuilder {
+        let pool = PgPoolOptions::new()
+            .connect_lazy("postgres://hooks@127.0.0.1:1/hooks")
+            .expect("Failed to build lazy pool");
+        let config = AppConfig {
+            database: crate::config::DatabaseConfig {
+                url: "postgres://hooks@127.0.0.1:1/hooks".to_owned(),
+                max_connections: 1,
+                min_connections: 0,
+                acquire_timeout_secs: 30,
+                idle_timeout_secs: 600,
+                max_lifetime_secs: 1800,
+                slow_acquire_ms: 1000,
+                run_migrations: false,
+                monitor_interval_secs: 0,
+            },
+            server: crate::ServerConfig {
+                host: "127.0.0.1".to_owned(),
+                port: 0,
+                response_envelope: false,
+                public_base_url: None,
+                read_only: false,
+                request_timeout_secs: 0,
+                long_request_timeout_secs: 0,
+                cache_max_age_secs: 0,
+                trusted_proxies: None,
+                docs_access: None,
+            },
+            auth: crate::AuthConfig { api_tokens: None },
+            bank: crate::config::BankConfig {
+                savings_rate_bps: 0,
+                interest_interval_secs: 0,
+                interest_batch_size: 1,
+                ledger_verify_interval_secs: 0,
+                daily_transfer_limit_cents: 0,
+                monthly_transfer_limit_cents: 0,
+                account_number_scheme: "luhn".to_owned(),
+                account_number_prefix: "40".to_owned(),
+                iban_country_code: "DE".to_owned(),
+                bank_code: "37040044".to_owned(),
+                tx_max_attempts: 1,
+            },
+            privacy: crate::config::PrivacyConfig {
+                export_signing_key: None,
+                export_link_ttl_secs: 60,
+                export_interval_secs: 0,
+                export_batch_size: 1,
+            },
+            retention: crate::config::RetentionConfig {
+                audit_events_days: 0,
+                data_exports_days: 0,
+                webhook_deliveries_days: 0,
+                user_changes_days: 0,
+                interval_secs: 0,
+                batch_size: 1,
+            },
+            pagination: crate::config::PaginationConfig {
+                default_limit: 200,
+                max_limit: 200,
+            },
+            preferences: crate::config::PreferenceConfig::default(),
+            screening: crate::config::ScreeningConfig::default(),
+            validation: crate::config::ValidationConfig {
+                name_max_length: 100,
+                age_min: 1,
+                age_max: 150,
+                unusual_age_above: 100,
+                required_metadata_keys: Vec::new(),
+                strict: false,
+            },
+            health: crate::config::HealthConfig::default(),
+            admin: crate::config::AdminConfig {
+                query_max_rows: 1,
+                query_timeout_ms: 1,
+                ip_allowlist: None,
+                ip_denylist: None,
+            },
+            partner: crate::config::PartnerConfig {
+                signature_window_secs: 0,
+                key_rotation_grace_secs: 0,
+                key_encryption_key: None,
+            },
+            tls: crate::config::TlsConfig::default(),
+            telemetry: crate::config::TelemetryConfig::default(),
+            migration: crate::config::MigrationConfig {
+                user_dual_write: None,
+                backfill_interval_secs: 0,
+                backfill_batch_size: 1,
+            },
+            jobs: crate::config::JobsConfig::default(),
+            webhook: crate::config::WebhookConfig {
+                timeout_ms: 1,
+                secret_rotation_grace_secs: 0,
+            },
+            notify: crate::config::NotifyConfig::default(),
+            environment: crate::config::Environment::Test,
+        };
+        AppBuilder::new(config).pool(pool)
+    }
+
+    #[tokio::test]
+    async fn test_hooks_run_in_order() {
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let record = |name: &'static str| {
+            let calls = Arc::clone(&calls);
+            move || {
+                calls.lock().unwrap().push(name);
+                std::future::ready(())
+            }
+        };
+        let (first, second, flush, stop) = (record("first"), record("second"), record("flush"), record("stop"));
+
+        let app = lazy_builder()
+            .on_startup("first", move |_ctx| async move {
+                first().await;
+                Ok(())
+            })
+            .on_startup("second", move |_ctx| async move {
+                second().await;
+                Ok(())
+            })
+            .on_shutdown("flush", flush)
+            .on_shutdown("stop", stop)
+            .build()
+            .await
+            .expect("Startup should succeed");
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        app.serve(listener, std::future::ready(())).await.unwrap();
+
+        assert_eq!(*calls.lock().unwrap(), ["first", "second", "stop", "flush"]);
+    }
+
+    #[tokio::test]
+    async fn test_failing_startup_hook_aborts() {
+        let mut builder = lazy_builder();
+        builder.config.database.monitor_interval_secs = 1;
+        builder.config.notify.channels = "cache_invalidation".to_owned();
+        let tasks = tokio::runtime::Handle::current().metrics();
+        let alive = tasks.num_alive_tasks();
+
+        let result = builder
+            .on_startup("warm-cache", |_ctx| async { Err("cache unavailable".into()) })
+            .build()
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(AppError::StartupHook { name: "warm-cache", ref source }) if source.to_string() == "cache unavailable"
+        ));
+        assert_eq!(tasks.num_alive_tasks(), alive, "No background task should outlive a failed startup");
+    }
+
+    #[tokio::test]
+    async fn test_read_only_starts_no_background_jobs() {
+        let mut builder = lazy_builder();
+        builder.config.server.read_only = true;
+        builder.config.bank.savings_rate_bps = 100;
+        builder.config.bank.interest_interval_secs = 60;
+        builder.config.bank.ledger_verify_interval_secs = 60;
+        builder.config.privacy.export_interval_secs = 60;
+        builder.config.retention.audit_events_days = 30;
+        builder.config.retention.interval_secs = 60;
+        builder.config.jobs.queue_interval_secs = 60;
+        builder.config.jobs.queues = "default:1".to_owned();
+        let tasks = tokio::runtime::Handle::current().metrics();
+        let alive = tasks.num_alive_tasks();
+
+        let app = builder.build().await.expect("Startup should succeed");
+
+        assert_eq!(tasks.num_alive_tasks(), alive, "A read-only replica should not run jobs that write");
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        app.serve(listener, std::future::ready(())).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_unreachable_database_is_an_error() {
+        let result = AppBuilder::new(lazy_builder().config).build().await;
+
+        assert!(matches!(result, Err(AppError::Database(_))));
+    }
+
+    #[tokio::test]
+    async fn test_docs_are_not_served_in_production() {
+        use axum::{body::Body, http::Request};
+        use tower::ServiceExt;
+
+        let status = |environment| async move {
+            let mut builder = lazy_builder();
+            builder.config.environment = environment;
+            let router = builder.build().await.expect("Startup should succeed").into_router();
+            let request = Request::get("/swagger-ui").body(Body::empty()).unwrap();
+            router.oneshot(request).await.unwrap().status()
+        };
+
+        assert_eq!(status(crate::config::Environment::Staging).await, 200);
+        assert_eq!(status(crate::config::Environment::Production).await, 404);
+    }
+
+    #[tokio::test]
+    async fn test_router_runs_with_the_given_config() {
+        use axum::{body::Body, http::Request};
+        use tower::ServiceExt;
+
+        let mut builder = lazy_builder();
+        builder.config.auth.api_tokens = Some("ops-token=ops:admin".to_owned());
+        builder.config.pagination.max_limit = 42;
+        let router = builder.build().await.expect("Startup should succeed").into_router();
+        let request = Request::get("/admin/config").header("authorization", "Bearer ops-token").body(Body::empty()).unwrap();
+        let response = router.oneshot(request).await.unwrap();
+
+        assert_eq!(response.status(), 200, "Tokens of the given config should be accepted");
+        let body = http_body_util::BodyExt::collect(response.into_body()).await.unwrap().to_bytes();
+        let dump: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(dump["config"]["pagination"]["max_limit"], 42);
+    }
+}
@@ -0,0 +1,108 @@
+//! Application state construction with service overrides
+
+use axum::Router;
+use sqlx::PgPool;
+
+use crate::{AppProviders, AppState, Module, ModuleRegistry, ServiceRegistry, assemble, create_router};
+
+/// Builds an [`AppState`] or router, replacing individual built-in services
+///
+/// Services are keyed by type, so an override replaces the built-in service
+/// handlers extract with the same type: a mock `SharedUserReadPort` and
+/// `SharedUserWritePort` stand in for the user service, a `HealthService`
+/// with other checks for the built-in one. Built-in services are still
+/// constructed with the originals; only what handlers see changes.
+pub struct AppStateBuilder {
+    pool: PgPool,
+    providers: AppProviders,
+    modules: ModuleRegistry,
+    overrides: ServiceRegistry,
+}
+
+impl AppState {
+    /// Starts building the state of an application using `pool`
+    #[must_use]
+    pub fn builder(pool: PgPool) -> AppStateBuilder {
+        AppStateBuilder {
+            pool,
+            providers: AppProviders::default(),
+            modules: ModuleRegistry::new(),
+            overrides: ServiceRegistry::new(),
+        }
+    }
+}
+
+impl AppStateBuilder {
+    /// Uses the given clock, ID generator, circuit and job tracker
+    #[must_use]
+    pub fn providers(mut self, providers: AppProviders) -> Self {
+        self.providers = providers;
+        self
+    }
+
+    /// Mounts `module` alongside the built-in routes
+    #[must_use]
+    pub fn module(mut self, module: impl Module + 'static) -> Self {
+        self.modules.register(module);
+        self
+    }
+
+    /// Registers `service` in place of the service of the same type, built-in or from a module
+    #[must_use]
+    pub fn service<T: Send + Sync + 'static>(mut self, service: T) -> Self {
+        self.overrides.insert(service);
+        self
+    }
+
+    /// State for [`crate::api_router`]
+    #[must_use]
+    pub fn build(self) -> AppState {
+        assemble(self.pool, self.providers, &self.modules, &self.overrides, None).state
+    }
+
+    /// Router with the built-in routes and middleware, like [`crate::create_app_with_modules`]
+    pub fn into_router(self) -> Router {
+        create_router(self.pool, self.providers, &self.modules, &self.overrides, None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::HealthService;
+    use crate::health::HealthCheck;
+    use axum::{body::Body, http::Request};
+    use futures_util::future::BoxFuture;
+    use http_body_util::BodyExt;
+    use sqlx::postgres::PgPoolOptions;
+    use std::sync::Arc;
+    use tower::ServiceExt;
+
+    struct FailingCache;
+
+    impl HealthCheck for FailingCache {
+        fn name(&self) -> &'static str {
+            "cache"
+        }
+
+        fn check(&self) -> BoxFuture<'_, Result<(), String>> {
+            Box::pin(async { Err("cache unreachable".to_owned()) })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_service_overrides_replace_built_in_services() {
+        let pool = PgPoolOptions::new()
+            .acquire_timeout(std::time::Duration::from_millis(100))
+            .connect_lazy("postgres://state@127.0.0.1:1/state")
+            .unwrap();
+        let health = HealthService::new(pool.clone()).with_checks([Arc::new(FailingCache) as _]);
+        let router = AppState::builder(pool).service(health).into_router();
+
+        let response = router.oneshot(Request::get("/health").body(Body::empty()).unwrap()).await.unwrap();
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let body = String::from_utf8(body.to_vec()).unwrap();
+
+        assert!(body.contains("cache unreachable"), "Overridden health service should run its checks: {body}");
+    }
+}
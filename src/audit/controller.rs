@@ -0,0 +1,81 @@
+//! Activity controller - HTTP handlers for user activity timelines
+
+use axum::{
+    Json,
+    extract::Query,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use tracing::{error, warn};
+
+use crate::audit::ActivityService;
+use crate::error_codes::{ErrorCode, ErrorResponse, internal_error};
+use crate::registry::Inject;
+use crate::user::UserId;
+use crate::user::domain::{UserError, ValidationErrorResponse};
+
+use super::domain::{ActivityError, ActivityPage, ActivityParams};
+
+/// Maps activity errors to HTTP responses
+fn error_response(error: ActivityError, user_id: i32) -> Response {
+    match error {
+        ActivityError::InvalidToken => {
+            warn!(user_id, "Controller: Invalid activity pagination token provided");
+            (
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse::new(ErrorCode::InvalidPaginationToken, error.to_string())),
+            ).into_response()
+        }
+        ActivityError::UserNotFound => {
+            warn!(user_id, "Controller: User not found for activity");
+            (
+                StatusCode::NOT_FOUND,
+                Json(ErrorResponse::new(ErrorCode::UserNotFound, error.to_string())),
+            ).into_response()
+        }
+        ActivityError::DatabaseError(msg) => {
+            error!(error = %msg, user_id, "Controller: Database error in activity operation");
+            internal_error()
+        }
+        ActivityError::UserServiceError(UserError::DatabaseError(e)) => {
+            error!(error = %e, user_id, "Controller: Database error in user lookup for activity operation");
+            e.into_response()
+        }
+        ActivityError::UserServiceError(e) => {
+            error!(error = %e, user_id, "Controller: User service error in activity operation");
+            internal_error()
+        }
+    }
+}
+
+/// HTTP handler for a user's activity timeline
+///
+/// Merges the user's audit events with the ledger entries of their accounts,
+/// newest first.
+#[utoipa::path(
+    get,
+    path = "/users/{id}/activity",
+    tag = "activity",
+    params(
+        ("id" = i32, Path, description = "User ID"),
+        ("next_token" = Option<String>, Query, description = "Pagination token from the previous page"),
+        ("limit" = Option<i32>, Query, description = "Number of entries to return (default: 50, max: 200)")
+    ),
+    responses(
+        (status = 200, description = "One page of the user's activity timeline", body = ActivityPage),
+        (status = 400, description = "Invalid user ID; an invalid pagination token answers `INVALID_PAGINATION_TOKEN` in an `ErrorResponse`", body = ValidationErrorResponse),
+        (status = 404, description = "User not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    )
+)]
+#[tracing::instrument(skip(activity_service, params), fields(user_id = user_id, limit = params.limit))]
+pub async fn user_activity_handler(
+    Inject(activity_service): Inject<ActivityService>,
+    UserId(user_id): UserId,
+    Query(params): Query<ActivityParams>,
+) -> impl IntoResponse {
+    match activity_service.user_activity(user_id, params).await {
+        Ok(page) => (StatusCode::OK, Json(page)).into_response(),
+        Err(e) => error_response(e, user_id),
+    }
+}
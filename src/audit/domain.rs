@@ -0,0 +1,84 @@
+//! Audit domain models
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use utoipa::ToSchema;
+
+use crate::user::domain::UserError;
+
+/// Source of an activity timeline entry
+#[derive(Serialize, Deserialize, ToSchema, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ActivityKind {
+    /// An audit event about the user or one of their accounts
+    Audit,
+    /// A ledger entry on one of the user's accounts
+    Transaction,
+}
+
+/// One entry of a user's activity timeline
+#[derive(Serialize, ToSchema, Debug, Clone)]
+pub struct ActivityEntry {
+    /// Entry identifier, unique across kinds (`audit:<id>` or `ledger:<id>`)
+    pub id: String,
+    /// Source of the entry
+    pub kind: ActivityKind,
+    /// Audit action (e.g. `account.frozen`) or ledger transaction kind (e.g. `transfer`)
+    pub action: String,
+    /// Account involved, if any
+    pub account_id: Option<i32>,
+    /// Signed amount posted to the account, for transactions
+    pub amount_cents: Option<i64>,
+    /// Audit event details, or the ledger transaction ID for transactions
+    #[schema(value_type = Object)]
+    pub details: Value,
+    /// When the event happened
+    pub occurred_at: DateTime<Utc>,
+}
+
+/// Query parameters for the activity timeline
+#[derive(Deserialize, ToSchema, Debug, Clone)]
+pub struct ActivityParams {
+    /// Pagination token from previous page (opaque cursor)
+    pub next_token: Option<String>,
+    /// Number of entries to return (default: 50, max: 200)
+    pub limit: Option<i32>,
+}
+
+/// One page of a user's activity timeline, newest first
+#[derive(Serialize, ToSchema, Debug, Clone)]
+pub struct ActivityPage {
+    /// Entries of this page
+    pub entries: Vec<ActivityEntry>,
+    /// Token for the next page (opaque cursor)
+    pub next_token: Option<String>,
+    /// Whether there are older entries
+    pub has_more: bool,
+}
+
+/// Position in the timeline after which the next page starts
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub(super) struct ActivityCursor {
+    /// Timestamp of the last entry returned
+    pub occurred_at: DateTime<Utc>,
+    /// ID of the last entry returned
+    pub id: String,
+}
+
+/// Domain errors for activity operations
+#[derive(Debug, thiserror::Error)]
+pub enum ActivityError {
+    /// The user does not exist
+    #[error("User not found")]
+    UserNotFound,
+    /// Invalid pagination token
+    #[error("Invalid pagination token")]
+    InvalidToken,
+    /// Database operation failed
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+    /// Error from the underlying user service
+    #[error("User service error: {0}")]
+    UserServiceError(UserError),
+}
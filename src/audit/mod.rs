@@ -0,0 +1,20 @@
+//! Audit module
+//!
+//! Persisted audit trail (`audit_events`) and the per-user activity timeline
+//! (`GET /users/{id}/activity`), which merges a user's audit events with the
+//! ledger entries of their accounts. Other modules write audit events with
+//! [`record`] inside their own database transactions.
+
+pub mod controller;
+pub mod domain;
+pub mod recorder;
+pub mod repository;
+pub mod service;
+
+// Public exports
+pub use domain::{ActivityEntry, ActivityError, ActivityKind, ActivityPage, ActivityParams};
+pub use recorder::{AuditRecord, record};
+pub use service::ActivityService;
+
+// Export controller for OpenAPI documentation
+pub use controller::*;
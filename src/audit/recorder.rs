@@ -0,0 +1,53 @@
+//! Audit event recorder
+//!
+//! Callers pass their open transaction, so an audit event is stored exactly
+//! when the change it describes is committed.
+
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use sqlx::PgExecutor;
+
+/// A user changed lifecycle status
+pub const USER_STATUS_CHANGED: &str = "user.status_changed";
+/// A user was restored to a previous version
+pub const USER_REVERTED: &str = "user.reverted";
+/// A compliance hold was placed on an account
+pub const ACCOUNT_FROZEN: &str = "account.frozen";
+/// A compliance hold was lifted from an account
+pub const ACCOUNT_UNFROZEN: &str = "account.unfrozen";
+/// A user requested an export of their data
+pub const DATA_EXPORT_REQUESTED: &str = "privacy.export_requested";
+/// A user's personal data was erased
+pub const USER_ERASED: &str = "privacy.user_erased";
+
+/// An audit event to be stored
+#[derive(Debug, Clone)]
+pub struct AuditRecord<'a> {
+    /// User the event concerns
+    pub user_id: Option<i32>,
+    /// Account the event concerns
+    pub account_id: Option<i32>,
+    /// Dotted action name, such as [`ACCOUNT_FROZEN`]
+    pub action: &'a str,
+    /// Action-specific details
+    pub details: Value,
+}
+
+/// Stores an audit event that happened at `at`
+///
+/// # Errors
+///
+/// Returns the database error if the insert fails.
+pub async fn record(executor: impl PgExecutor<'_>, event: AuditRecord<'_>, at: DateTime<Utc>) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        "INSERT INTO audit_events (user_id, account_id, action, details, occurred_at) VALUES ($1, $2, $3, $4, $5)",
+        event.user_id,
+        event.account_id,
+        event.action,
+        event.details,
+        at
+    )
+    .execute(executor)
+    .await?;
+    Ok(())
+}
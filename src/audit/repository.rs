@@ -0,0 +1,97 @@
+//! Activity repository - handles database operations
+//!
+//! This module is private to the audit module. All database access must go
+//! through `ActivityService`, except for writes through [`super::record`].
+
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use sqlx::PgPool;
+use tracing::{error, info};
+
+use super::domain::{ActivityCursor, ActivityEntry, ActivityError, ActivityKind};
+
+/// A row of the timeline query; converted into `ActivityEntry` before leaving the repository
+struct ActivityRow {
+    id: String,
+    kind: String,
+    action: String,
+    account_id: Option<i32>,
+    amount_cents: Option<i64>,
+    details: Value,
+    occurred_at: DateTime<Utc>,
+}
+
+impl From<ActivityRow> for ActivityEntry {
+    fn from(row: ActivityRow) -> Self {
+        Self {
+            id: row.id,
+            kind: if row.kind == "audit" { ActivityKind::Audit } else { ActivityKind::Transaction },
+            action: row.action,
+            account_id: row.account_id,
+            amount_cents: row.amount_cents,
+            details: row.details,
+            occurred_at: row.occurred_at,
+        }
+    }
+}
+
+/// Activity repository for database operations
+#[derive(Clone)]
+pub(super) struct ActivityRepository {
+    pool: PgPool,
+}
+
+impl ActivityRepository {
+    /// Creates a new `ActivityRepository` instance
+    pub(super) fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Fetches up to `limit` timeline entries of a user older than `cursor`, newest first
+    ///
+    /// Audit events and the ledger entries of the user's accounts are merged
+    /// with `UNION ALL` and ordered by `(occurred_at, id)`, which is also the
+    /// keyset the cursor points into.
+    pub(super) async fn find_activity(
+        &self,
+        user_id: i32,
+        cursor: Option<&ActivityCursor>,
+        limit: i32,
+    ) -> Result<Vec<ActivityEntry>, ActivityError> {
+        info!(user_id, limit, "Fetching user activity from database");
+
+        let rows = sqlx::query_as!(
+            ActivityRow,
+            r#"SELECT id AS "id!", kind AS "kind!", action AS "action!", account_id, amount_cents,
+                      details AS "details!", occurred_at AS "occurred_at!"
+             FROM (
+                 SELECT 'audit:' || a.id AS id, 'audit' AS kind, a.action, a.account_id,
+                        NULL::BIGINT AS amount_cents, a.details, a.occurred_at
+                 FROM audit_events a
+                 WHERE a.user_id = $1
+                 UNION ALL
+                 SELECT 'ledger:' || e.id, 'transaction', t.kind, e.account_id,
+                        e.amount_cents, jsonb_build_object('transaction_id', t.id), t.created_at
+                 FROM ledger_entries e
+                 JOIN ledger_transactions t ON t.id = e.transaction_id
+                 JOIN accounts acc ON acc.id = e.account_id
+                 WHERE acc.user_id = $1
+             ) activity
+             WHERE $2::timestamptz IS NULL OR (occurred_at, id) < ($2, $3)
+             ORDER BY occurred_at DESC, id DESC
+             LIMIT $4"#,
+            user_id,
+            cursor.map(|c| c.occurred_at),
+            cursor.map(|c| c.id.as_str()),
+            i64::from(limit)
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| {
+            error!(error = %e, user_id, "Failed to fetch user activity from database");
+            ActivityError::DatabaseError(e.to_string())
+        })?;
+
+        Ok(rows.into_iter().map(ActivityEntry::from).collect())
+    }
+}
@@ -0,0 +1,90 @@
+//! Activity service - business logic layer
+//!
+//! Pages through a user's activity timeline for support tooling. Talks to the
+//! user module only through `UserReadPort`.
+
+use std::sync::Arc;
+
+use sqlx::PgPool;
+use tracing::{info, warn};
+
+use crate::pagination::PaginationToken;
+use crate::user::{SharedUserReadPort, UserReadPort};
+
+use super::domain::{ActivityCursor, ActivityError, ActivityPage, ActivityParams};
+use super::repository::ActivityRepository;
+
+/// Default number of timeline entries per page
+const DEFAULT_PAGE_LIMIT: i32 = 50;
+/// Maximum number of timeline entries per page
+const MAX_PAGE_LIMIT: i32 = 200;
+
+/// Activity service that handles business logic for user timelines
+#[derive(Clone)]
+pub struct ActivityService {
+    repository: ActivityRepository,
+    users: SharedUserReadPort,
+}
+
+impl ActivityService {
+    /// Creates a new `ActivityService` instance
+    #[must_use] pub fn new(pool: PgPool, users: impl UserReadPort + 'static) -> Self {
+        Self {
+            repository: ActivityRepository::new(pool),
+            users: Arc::new(users),
+        }
+    }
+
+    /// Ensures the user exists before reading its timeline
+    async fn ensure_user_exists(&self, user_id: i32) -> Result<(), ActivityError> {
+        match self.users.user_exists(user_id).await {
+            Ok(true) => Ok(()),
+            Ok(false) => {
+                warn!(user_id, "ActivityService: User not found");
+                Err(ActivityError::UserNotFound)
+            }
+            Err(e) => {
+                warn!(user_id, error = ?e, "ActivityService: Error checking user existence");
+                Err(ActivityError::UserServiceError(e))
+            }
+        }
+    }
+
+    /// Returns one page of a user's activity timeline, newest first
+    pub async fn user_activity(&self, user_id: i32, params: ActivityParams) -> Result<ActivityPage, ActivityError> {
+        let limit = params.limit.unwrap_or(DEFAULT_PAGE_LIMIT).clamp(1, MAX_PAGE_LIMIT);
+        info!(user_id, limit, "ActivityService: Fetching user activity");
+
+        let cursor = params
+            .next_token
+            .as_deref()
+            .map(PaginationToken::decode_cursor::<ActivityCursor>)
+            .transpose()
+            .map_err(|_e| ActivityError::InvalidToken)?;
+        self.ensure_user_exists(user_id).await?;
+
+        // Fetch one extra entry to check if there are more pages
+        let mut entries = self.repository.find_activity(user_id, cursor.as_ref(), limit + 1).await?;
+        let has_more = entries.len() > usize::try_from(limit).unwrap_or_default();
+        if has_more {
+            entries.pop();
+        }
+
+        let next_token = if has_more {
+            entries
+                .last()
+                .map(|entry| {
+                    PaginationToken::encode_cursor(&ActivityCursor {
+                        occurred_at: entry.occurred_at,
+                        id: entry.id.clone(),
+                    })
+                })
+                .transpose()
+                .map_err(|e| ActivityError::DatabaseError(e.to_string()))?
+        } else {
+            None
+        };
+
+        Ok(ActivityPage { entries, next_token, has_more })
+    }
+}
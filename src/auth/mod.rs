@@ -0,0 +1,406 @@
+//! Route access control
+//!
+//! Every route's access requirement is declared once in a [`RoutePolicies`]
+//! table (routes not listed are public; methods not listed on a listed route
+//! are denied, and HEAD follows GET). The router enforces the table with
+//! the [`enforce_access`] route layer, and the same table adds the matching
+//! security requirements to the `OpenAPI` document, so the two cannot drift.
+//!
+//! Callers are identified by an [`Authenticator`]; the default one accepts
+//! static bearer tokens configured through `API_TOKENS`. The caller is
+//! recorded on the request span (see [`spans`]), so every span and log line
+//! below it can be queried by principal.
+
+pub mod spans;
+
+use std::{collections::HashMap, sync::Arc};
+
+use axum::{
+    Json,
+    extract::{MatchedPath, Request, State},
+    http::{HeaderMap, Method, StatusCode, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use sha2::{Digest, Sha256};
+use tracing::{Span, warn};
+use utoipa::openapi::{
+    OpenApi,
+    security::{Http, HttpAuthScheme, SecurityRequirement, SecurityScheme},
+};
+
+use crate::error_codes::{ErrorCode, ErrorResponse, error_response};
+
+/// Name of the bearer security scheme in the `OpenAPI` document
+pub const BEARER_SCHEME: &str = "bearer_token";
+
+/// Access requirement of a route
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum AccessPolicy {
+    /// Anyone may call the route
+    #[default]
+    Public,
+    /// Any authenticated caller may call the route
+    Authenticated,
+    /// Only authenticated callers holding this role may call the route
+    Role(String),
+    /// Nobody may call the route
+    Denied,
+}
+
+/// Authenticated caller, available to handlers as a request extension
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Principal {
+    /// Caller identifier
+    pub subject: String,
+    /// Roles granted to the caller
+    pub roles: Vec<String>,
+    /// Tenant the caller acts for, if any
+    pub tenant: Option<String>,
+    /// Non-secret identifier of the credential used, if any
+    pub key_id: Option<String>,
+}
+
+impl Principal {
+    /// Whether the caller holds `role`
+    #[must_use]
+    pub fn has_role(&self, role: &str) -> bool {
+        self.roles.iter().any(|r| r == role)
+    }
+}
+
+/// Identifies the caller of a request
+pub trait Authenticator: Send + Sync {
+    /// Returns the caller, or `None` when the request carries no valid credentials
+    fn authenticate(&self, headers: &HeaderMap) -> Option<Principal>;
+}
+
+/// Authenticator accepting a fixed set of bearer tokens
+#[derive(Debug, Clone, Default)]
+pub struct StaticTokenAuthenticator {
+    tokens: HashMap<String, Principal>,
+}
+
+impl StaticTokenAuthenticator {
+    /// Parses tokens from `token=subject:role|role:tenant;token=subject`
+    ///
+    /// Roles and tenant are optional. Each principal's key id is a fingerprint
+    /// of its token (see [`key_id`]). Malformed entries are skipped with a warning.
+    #[must_use]
+    pub fn from_spec(spec: &str) -> Self {
+        let mut tokens = HashMap::new();
+        for entry in spec.split(';').map(str::trim).filter(|entry| !entry.is_empty()) {
+            let Some((token, identity)) = entry.split_once('=') else {
+                warn!("Ignoring API token entry without `=`");
+                continue;
+            };
+            let mut fields = identity.splitn(3, ':');
+            let subject = fields.next().unwrap_or_default();
+            let roles = fields.next().unwrap_or_default();
+            let tenant = fields.next().filter(|tenant| !tenant.is_empty());
+            if token.is_empty() || subject.is_empty() {
+                warn!("Ignoring API token entry with an empty token or subject");
+                continue;
+            }
+            tokens.insert(
+                token.to_owned(),
+                Principal {
+                    subject: subject.to_owned(),
+                    roles: roles
+                        .split('|')
+                        .filter(|role| !role.is_empty())
+                        .map(ToOwned::to_owned)
+                        .collect(),
+                    tenant: tenant.map(ToOwned::to_owned),
+                    key_id: Some(key_id(token)),
+                },
+            );
+        }
+        Self { tokens }
+    }
+}
+
+/// Fingerprint identifying a bearer token in traces without revealing it
+///
+/// The first 12 hex digits of the token's SHA-256 digest.
+#[must_use]
+pub fn key_id(token: &str) -> String {
+    Sha256::digest(token.as_bytes())
+        .iter()
+        .take(6)
+        .map(|byte| format!("{byte:02x}"))
+        .collect::<Vec<_>>()
+        .concat()
+}
+
+impl Authenticator for StaticTokenAuthenticator {
+    fn authenticate(&self, headers: &HeaderMap) -> Option<Principal> {
+        let token = headers
+            .get(header::AUTHORIZATION)?
+            .to_str()
+            .ok()?
+            .strip_prefix("Bearer ")?;
+        self.tokens.get(token.trim()).cloned()
+    }
+}
+
+/// Table of route access policies, keyed by method and route pattern (e.g. `/users/{id}`)
+#[derive(Debug, Clone, Default)]
+pub struct RoutePolicies {
+    policies: HashMap<(Method, String), AccessPolicy>,
+}
+
+impl RoutePolicies {
+    /// Creates an empty table (every route public)
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Declares the access policy of a route
+    #[must_use]
+    pub fn route(mut self, method: Method, path: &str, policy: AccessPolicy) -> Self {
+        self.policies.insert((method, path.to_owned()), policy);
+        self
+    }
+
+    /// Access policy of a route; routes not declared are public
+    ///
+    /// HEAD requests, which Axum answers with the GET handler, get the GET
+    /// policy. A method not declared on a path that declares others is
+    /// [`AccessPolicy::Denied`], so an undeclared handler is never public by accident.
+    #[must_use]
+    pub fn policy_for(&self, method: &Method, path: &str) -> AccessPolicy {
+        let method = if method == Method::HEAD { Method::GET } else { method.clone() };
+        if let Some(policy) = self.policies.get(&(method, path.to_owned())) {
+            return policy.clone();
+        }
+        if self.policies.keys().any(|(_, declared)| declared == path) {
+            AccessPolicy::Denied
+        } else {
+            AccessPolicy::Public
+        }
+    }
+
+    /// Whether the policy of a route was declared, public ones included
+    #[must_use]
+    pub fn is_declared(&self, method: &Method, path: &str) -> bool {
+        self.policies.contains_key(&(method.clone(), path.to_owned()))
+    }
+
+    /// Adds the bearer scheme and per-operation security requirements to `openapi`
+    pub fn apply_to_openapi(&self, openapi: &mut OpenApi) {
+        if self.policies.values().all(|policy| *policy == AccessPolicy::Public) {
+            return;
+        }
+
+        openapi.components.get_or_insert_with(Default::default).add_security_scheme(
+            BEARER_SCHEME,
+            SecurityScheme::Http(Http::new(HttpAuthScheme::Bearer)),
+        );
+
+        for ((method, path), policy) in &self.policies {
+            let roles: Vec<String> = match policy {
+                AccessPolicy::Public | AccessPolicy::Denied => continue,
+                AccessPolicy::Authenticated => Vec::new(),
+                AccessPolicy::Role(role) => vec![role.clone()],
+            };
+            let Some(item) = openapi.paths.paths.get_mut(path) else {
+                continue;
+            };
+            let operation = match *method {
+                Method::GET => &mut item.get,
+                Method::PUT => &mut item.put,
+                Method::POST => &mut item.post,
+                Method::DELETE => &mut item.delete,
+                Method::PATCH => &mut item.patch,
+                _ => continue,
+            };
+            if let Some(operation) = operation {
+                operation.security = Some(vec![SecurityRequirement::new(BEARER_SCHEME, roles)]);
+            }
+        }
+    }
+}
+
+/// State of the [`enforce_access`] middleware
+#[derive(Clone)]
+pub struct AccessControl {
+    policies: Arc<RoutePolicies>,
+    authenticator: Arc<dyn Authenticator>,
+}
+
+impl AccessControl {
+    /// Enforces `policies`, identifying callers with `authenticator`
+    pub fn new(policies: RoutePolicies, authenticator: impl Authenticator + 'static) -> Self {
+        Self {
+            policies: Arc::new(policies),
+            authenticator: Arc::new(authenticator),
+        }
+    }
+}
+
+/// Rejects a request that lacks valid credentials
+fn unauthorized() -> Response {
+    (
+        StatusCode::UNAUTHORIZED,
+        [(header::WWW_AUTHENTICATE, "Bearer")],
+        Json(ErrorResponse::new(ErrorCode::Unauthenticated, "Missing or invalid credentials")),
+    )
+        .into_response()
+}
+
+/// Route-layer middleware enforcing the declared access policy of the matched route
+///
+/// The authenticated [`Principal`] (if any) is added to the request extensions
+/// and recorded on the request span, also on public routes.
+pub async fn enforce_access(
+    State(access): State<AccessControl>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let policy = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|path| access.policies.policy_for(request.method(), path.as_str()))
+        .unwrap_or_default();
+    let principal = access.authenticator.authenticate(request.headers());
+
+    match (&policy, &principal) {
+        (AccessPolicy::Denied, _) => {
+            warn!(uri = %request.uri(), method = %request.method(), "Rejected request for an undeclared method");
+            return error_response(
+                StatusCode::METHOD_NOT_ALLOWED,
+                ErrorCode::MethodNotAllowed,
+                format!("{} is not allowed on this route", request.method()),
+            );
+        }
+        (AccessPolicy::Authenticated | AccessPolicy::Role(_), None) => {
+            warn!(uri = %request.uri(), "Rejected unauthenticated request");
+            return unauthorized();
+        }
+        (AccessPolicy::Role(role), Some(principal)) if !principal.has_role(role) => {
+            warn!(uri = %request.uri(), subject = %principal.subject, role, "Rejected request lacking role");
+            return error_response(StatusCode::FORBIDDEN, ErrorCode::Forbidden, format!("This route requires the {role} role"));
+        }
+        _ => {}
+    }
+
+    if let Some(principal) = principal {
+        spans::record_principal(&Span::current(), &principal);
+        request.extensions_mut().insert(principal);
+    }
+    next.run(request).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{Router, body::Body, middleware, routing::get};
+    use tower::ServiceExt;
+
+    fn app() -> Router {
+        let policies = RoutePolicies::new()
+            .route(Method::GET, "/me", AccessPolicy::Authenticated)
+            .route(Method::GET, "/admin", AccessPolicy::Role("admin".to_owned()));
+        let access = AccessControl::new(
+            policies,
+            StaticTokenAuthenticator::from_spec("alice-token=alice:admin|ops; bob-token=bob"),
+        );
+
+        Router::new()
+            .route("/public", get(|| async { "public" }))
+            .route("/me", get(|| async { "me" }))
+            .route("/admin", get(|| async { "admin" }))
+            .route_layer(middleware::from_fn_with_state(access, enforce_access))
+    }
+
+    async fn status(uri: &str, token: Option<&str>) -> StatusCode {
+        let mut request = Request::get(uri);
+        if let Some(token) = token {
+            request = request.header(header::AUTHORIZATION, format!("Bearer {token}"));
+        }
+        app()
+            .oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap()
+            .status()
+    }
+
+    #[test]
+    fn test_static_tokens_from_spec() {
+        let auth = StaticTokenAuthenticator::from_spec("alice-token=alice:admin|ops; bob-token=bob; broken");
+        let mut headers = HeaderMap::new();
+        headers.insert(header::AUTHORIZATION, "Bearer alice-token".parse().unwrap());
+
+        let alice = auth.authenticate(&headers).expect("Token should be accepted");
+        assert_eq!(alice.subject, "alice");
+        assert!(alice.has_role("admin") && alice.has_role("ops"));
+        assert_eq!(alice.tenant, None);
+        assert_eq!(alice.key_id, Some(key_id("alice-token")));
+        assert_eq!(auth.tokens.len(), 2, "Malformed entries should be skipped");
+    }
+
+    #[test]
+    fn test_tenant_and_key_id() {
+        let auth = StaticTokenAuthenticator::from_spec("svc-token=billing::acme");
+        let mut headers = HeaderMap::new();
+        headers.insert(header::AUTHORIZATION, "Bearer svc-token".parse().unwrap());
+
+        let billing = auth.authenticate(&headers).expect("Token should be accepted");
+        assert!(billing.roles.is_empty());
+        assert_eq!(billing.tenant.as_deref(), Some("acme"));
+        assert_eq!(key_id("svc-token").len(), 12);
+        assert_ne!(key_id("svc-token"), key_id("other-token"));
+    }
+
+    #[tokio::test]
+    async fn test_enforce_access() {
+        assert_eq!(status("/public", None).await, StatusCode::OK);
+        assert_eq!(status("/me", None).await, StatusCode::UNAUTHORIZED);
+        assert_eq!(status("/me", Some("wrong")).await, StatusCode::UNAUTHORIZED);
+        assert_eq!(status("/me", Some("bob-token")).await, StatusCode::OK);
+        assert_eq!(status("/admin", Some("bob-token")).await, StatusCode::FORBIDDEN);
+        assert_eq!(status("/admin", Some("alice-token")).await, StatusCode::OK);
+    }
+
+    #[test]
+    fn test_apply_to_openapi() {
+        let mut openapi: OpenApi = serde_json::from_value(serde_json::json!({
+            "openapi": "3.1.0",
+            "info": { "title": "test", "version": "1" },
+            "paths": {
+                "/admin": { "get": { "responses": {} } },
+                "/public": { "get": { "responses": {} } }
+            }
+        }))
+        .unwrap();
+
+        RoutePolicies::new()
+            .route(Method::GET, "/admin", AccessPolicy::Role("admin".to_owned()))
+            .apply_to_openapi(&mut openapi);
+
+        let doc = serde_json::to_value(&openapi).unwrap();
+        assert_eq!(doc["paths"]["/admin"]["get"]["security"], serde_json::json!([{ BEARER_SCHEME: ["admin"] }]));
+        assert!(doc["paths"]["/public"]["get"]["security"].is_null());
+        assert_eq!(doc["components"]["securitySchemes"][BEARER_SCHEME]["scheme"], "bearer");
+    }
+
+    #[test]
+    fn test_declared_public_routes() {
+        let policies = RoutePolicies::new().route(Method::POST, "/users", AccessPolicy::Public);
+
+        assert!(policies.is_declared(&Method::POST, "/users"));
+        assert!(!policies.is_declared(&Method::DELETE, "/users"));
+        assert_eq!(policies.policy_for(&Method::POST, "/users"), AccessPolicy::Public);
+        assert_eq!(policies.policy_for(&Method::DELETE, "/users"), AccessPolicy::Denied);
+        assert_eq!(policies.policy_for(&Method::GET, "/tags"), AccessPolicy::Public, "Undeclared paths stay public");
+    }
+
+    #[test]
+    fn test_head_follows_get() {
+        let policies = RoutePolicies::new().route(Method::GET, "/admin", AccessPolicy::Role("admin".to_owned()));
+
+        assert_eq!(policies.policy_for(&Method::HEAD, "/admin"), AccessPolicy::Role("admin".to_owned()));
+    }
+}
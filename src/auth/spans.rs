@@ -0,0 +1,177 @@
+//! Principal attributes on tracing spans
+//!
+//! [`enforce_access`](super::enforce_access) records the caller on the
+//! request span as `user_id`, `tenant_id` and `api_key_id` (fields declared
+//! empty by [`RequestSpan`](crate::config::tracing::RequestSpan)). Log lines
+//! carry them through the span list. With the `otel` feature,
+//! [`PrincipalAttributes`] also copies them onto every span opened below the
+//! request span (handlers, repositories, outbound calls), so the tracing
+//! backend can filter any span by principal.
+
+use tracing::Span;
+
+use super::Principal;
+
+/// Span field holding the caller's subject
+pub const USER_ID: &str = "user_id";
+/// Span field holding the caller's tenant
+pub const TENANT_ID: &str = "tenant_id";
+/// Span field holding the fingerprint of the caller's credential
+pub const API_KEY_ID: &str = "api_key_id";
+
+/// Records `principal` on `span`, which must declare the principal fields
+pub fn record_principal(span: &Span, principal: &Principal) {
+    span.record(USER_ID, principal.subject.as_str());
+    if let Some(tenant) = &principal.tenant {
+        span.record(TENANT_ID, tenant.as_str());
+    }
+    if let Some(key_id) = &principal.key_id {
+        span.record(API_KEY_ID, key_id.as_str());
+    }
+}
+
+#[cfg(feature = "otel")]
+pub use otel::PrincipalAttributes;
+
+#[cfg(feature = "otel")]
+mod otel {
+    use opentelemetry::KeyValue;
+    use tracing::{
+        Subscriber,
+        field::{Field, Visit},
+        span::{Attributes, Id, Record},
+    };
+    use tracing_opentelemetry::OtelData;
+    use tracing_subscriber::{Layer, layer::Context, registry::LookupSpan};
+
+    use super::{API_KEY_ID, TENANT_ID, USER_ID};
+
+    /// Principal attributes recorded on a span, inherited by the spans below it
+    #[derive(Debug, Clone, Default)]
+    struct Principal(Vec<KeyValue>);
+
+    impl Visit for Principal {
+        fn record_str(&mut self, field: &Field, value: &str) {
+            if [USER_ID, TENANT_ID, API_KEY_ID].contains(&field.name()) {
+                self.0.push(KeyValue::new(field.name(), value.to_owned()));
+            }
+        }
+
+        fn record_debug(&mut self, _field: &Field, _value: &dyn std::fmt::Debug) {}
+    }
+
+    /// Layer copying principal attributes from the request span onto its descendants
+    ///
+    /// Must be added after the `OpenTelemetry` layer, which creates the span
+    /// data it extends.
+    #[derive(Debug, Clone, Copy, Default)]
+    pub struct PrincipalAttributes;
+
+    impl<S> Layer<S> for PrincipalAttributes
+    where
+        S: Subscriber + for<'span> LookupSpan<'span>,
+    {
+        fn on_new_span(&self, _attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+            let Some(span) = ctx.span(id) else {
+                return;
+            };
+            let Some(inherited) = span
+                .parent()
+                .and_then(|parent| parent.extensions().get::<Principal>().cloned())
+            else {
+                return;
+            };
+            let mut extensions = span.extensions_mut();
+            if let Some(data) = extensions.get_mut::<OtelData>() {
+                data.builder
+                    .attributes
+                    .get_or_insert_with(Vec::new)
+                    .extend(inherited.0.iter().cloned());
+            }
+            extensions.insert(inherited);
+        }
+
+        fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+            let Some(span) = ctx.span(id) else {
+                return;
+            };
+            let mut recorded = Principal::default();
+            values.record(&mut recorded);
+            if recorded.0.is_empty() {
+                return;
+            }
+            let mut extensions = span.extensions_mut();
+            match extensions.get_mut::<Principal>() {
+                Some(principal) => principal.0.extend(recorded.0),
+                None => extensions.insert(recorded),
+            }
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use std::sync::{Arc, Mutex};
+        use futures_util::future::BoxFuture;
+        use opentelemetry::trace::TracerProvider as _;
+        use opentelemetry_sdk::export::trace::{ExportResult, SpanData, SpanExporter};
+        use opentelemetry_sdk::trace::TracerProvider;
+        use tracing_subscriber::layer::SubscriberExt;
+
+        use crate::auth::{Principal as Caller, spans::record_principal};
+
+        /// Exporter keeping finished spans in memory
+        #[derive(Debug, Clone, Default)]
+        struct Collect(Arc<Mutex<Vec<SpanData>>>);
+
+        impl SpanExporter for Collect {
+            fn export(&mut self, batch: Vec<SpanData>) -> BoxFuture<'static, ExportResult> {
+                self.0.lock().unwrap().extend(batch);
+                Box::pin(async { Ok(()) })
+            }
+        }
+
+        #[test]
+        fn test_descendant_spans_inherit_the_principal() {
+            let exporter = Collect::default();
+            let provider = TracerProvider::builder().with_simple_exporter(exporter.clone()).build();
+            let subscriber = tracing_subscriber::registry()
+                .with(tracing_opentelemetry::layer().with_tracer(provider.tracer("test")))
+                .with(PrincipalAttributes);
+
+            tracing::subscriber::with_default(subscriber, || {
+                let request = tracing::info_span!(
+                    "request",
+                    user_id = tracing::field::Empty,
+                    tenant_id = tracing::field::Empty,
+                    api_key_id = tracing::field::Empty,
+                );
+                let _request = request.enter();
+                record_principal(
+                    &request,
+                    &Caller {
+                        subject: "alice".to_owned(),
+                        roles: Vec::new(),
+                        tenant: Some("acme".to_owned()),
+                        key_id: Some("0123456789ab".to_owned()),
+                    },
+                );
+                tracing::info_span!("db_query").in_scope(|| tracing::info_span!("row_decode").in_scope(|| {}));
+            });
+
+            let spans = exporter.0.lock().unwrap().clone();
+            let attribute = |name: &str, key: &str| {
+                spans
+                    .iter()
+                    .find(|span| span.name == name)
+                    .and_then(|span| span.attributes.iter().find(|kv| kv.key.as_str() == key))
+                    .map(|kv| kv.value.to_string())
+            };
+            for name in ["request", "db_query", "row_decode"] {
+                assert_eq!(attribute(name, USER_ID).as_deref(), Some("alice"), "{name} should carry the user");
+                assert_eq!(attribute(name, TENANT_ID).as_deref(), Some("acme"), "{name} should carry the tenant");
+                assert_eq!(attribute(name, API_KEY_ID).as_deref(), Some("0123456789ab"), "{name} should carry the key id");
+            }
+        }
+    }
+}
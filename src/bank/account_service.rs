@@ -0,0 +1,465 @@
+//! Account service - business logic for persisted bank accounts
+//!
+//! Enforces the bank's policies on every balance change: a compliance hold
+//! (`frozen`) blocks withdrawals and transfers in either direction, and
+//! balances cannot go negative. Placing and lifting holds, and every attempt
+//! blocked by one, is written to the `audit` log target. Savings accounts
+//! accrue daily interest (see `InterestAccrualJob`). Money only leaves for
+//! another bank towards a beneficiary the account holder saved beforehand,
+//! and transfers of either kind count against the account's transfer limits.
+//! Balance changes that lose a serialization race or deadlock are retried
+//! (see [`AccountService::with_retry_policy`]).
+//! Talks to the user module only through [`UserLookup`].
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use sqlx::PgPool;
+use tracing::{error, info, warn};
+
+use crate::clock::{SharedClock, SystemClock};
+use crate::db::{RetryCounts, RetryPolicy, RetryStats, retry};
+use crate::error_codes::ErrorCode;
+use crate::metrics::{TRANSFER_VOLUME, TRANSFERS_COMPLETED};
+use crate::user::domain::PaginationParams;
+use crate::user::validation::common::{count_failures, field_error};
+
+use super::domain::{
+    Account, AccountNumberCheck, BankError, Beneficiary, CreateBeneficiary, ExternalTransfer, ExternalTransferRequest,
+    FreezeAccount, InterestAccrual, LedgerVerification, OpenAccount, Transfer, TransferLimitUsage, TransferRequest,
+    UserWithAccounts, UsersWithAccountsPage,
+};
+use super::limits::TransferLimits;
+use super::lookup::{SharedUserLookup, UserLookup};
+use super::repository::AccountRepository;
+use super::validation::{
+    AccountNumberScheme, normalize_account_number, validate_account_number, validate_amount, validate_balance,
+    validate_beneficiary_name, validate_reason, validate_transfer_accounts,
+};
+
+/// Rejects debiting `amount_cents` from `account` when a hold or the balance forbids it
+fn check_debit(account: &Account, amount_cents: i64, operation: &'static str) -> Result<(), BankError> {
+    check_not_frozen(account, operation)?;
+    if account.balance_cents < amount_cents {
+        warn!(account_id = account.id, amount_cents, operation, "AccountService: Insufficient funds");
+        return Err(BankError::InsufficientFunds);
+    }
+    Ok(())
+}
+
+/// Rejects `operation` on a frozen account, recording the attempt in the audit log
+fn check_not_frozen(account: &Account, operation: &'static str) -> Result<(), BankError> {
+    if account.frozen {
+        warn!(
+            target: "audit",
+            account_id = account.id,
+            operation,
+            reason = account.frozen_reason.as_deref(),
+            "Blocked operation on frozen account"
+        );
+        return Err(BankError::AccountFrozen { account_id: account.id });
+    }
+    Ok(())
+}
+
+/// Counts a completed transfer of `kind` and its amount in the domain metrics
+fn record_transfer(kind: &'static str, amount_cents: i64) {
+    TRANSFERS_COMPLETED.increment(&[("kind", kind)]);
+    TRANSFER_VOLUME.add(&[("kind", kind)], amount_cents.unsigned_abs());
+}
+
+/// Interest earned in one day on `balance_cents` at `annual_rate_bps`, rounded down to the cent
+#[must_use]
+pub fn daily_interest(balance_cents: i64, annual_rate_bps: u32) -> i64 {
+    let interest = i128::from(balance_cents) * i128::from(annual_rate_bps) / (10_000 * 365);
+    i64::try_from(interest).unwrap_or(i64::MAX).max(0)
+}
+
+/// Account service that handles business logic for bank accounts
+#[derive(Clone)]
+pub struct AccountService {
+    repository: AccountRepository,
+    users: SharedUserLookup,
+    clock: SharedClock,
+    account_numbers: AccountNumberScheme,
+    limits: TransferLimits,
+    retry_policy: RetryPolicy,
+    retries: RetryStats,
+}
+
+impl AccountService {
+    /// Creates a new `AccountService` instance
+    #[must_use] pub fn new(pool: PgPool, users: impl UserLookup + 'static) -> Self {
+        Self {
+            repository: AccountRepository::new(pool),
+            users: Arc::new(users),
+            clock: SystemClock::shared(),
+            account_numbers: AccountNumberScheme::default(),
+            limits: TransferLimits::default(),
+            retry_policy: RetryPolicy::default(),
+            retries: RetryStats::default(),
+        }
+    }
+
+    /// Uses `clock` instead of the system clock for timestamps
+    #[must_use] pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Generates account numbers for new accounts with `scheme`
+    #[must_use] pub fn with_account_numbers(mut self, scheme: AccountNumberScheme) -> Self {
+        self.account_numbers = scheme;
+        self
+    }
+
+    /// Caps what each account may send by transfer; unlimited by default
+    #[must_use] pub fn with_transfer_limits(mut self, limits: TransferLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Retries balance changes that lose a serialization race or deadlock according to `policy`
+    #[must_use] pub fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = policy;
+        self
+    }
+
+    /// How often balance changes were retried since the process started
+    #[must_use] pub fn retry_counts(&self) -> RetryCounts {
+        self.retries.snapshot()
+    }
+
+    /// Checks an account number, returning it normalized along with its format
+    pub fn check_account_number(account_number: &str) -> Result<AccountNumberCheck, BankError> {
+        let account_number = normalize_account_number(account_number);
+        let format = validate_account_number(&account_number, "account_number").map_err(BankError::ValidationError)?;
+        Ok(AccountNumberCheck { account_number, format })
+    }
+
+    /// Ensures the user exists before opening accounts or saving beneficiaries for it
+    async fn ensure_user_exists(&self, user_id: i32) -> Result<(), BankError> {
+        match self.users.user_exists(user_id).await {
+            Ok(true) => Ok(()),
+            Ok(false) => {
+                warn!(user_id, "AccountService: User not found");
+                Err(BankError::UserNotFound)
+            }
+            Err(e) => {
+                warn!(user_id, error = ?e, "AccountService: Error checking user existence");
+                Err(BankError::UserServiceError(e))
+            }
+        }
+    }
+
+    /// Opens an account for an existing user
+    pub async fn open_account(&self, request: OpenAccount) -> Result<Account, BankError> {
+        let balance_cents = request.initial_balance_cents.unwrap_or_default();
+        info!(user_id = request.user_id, balance_cents, "AccountService: Opening account");
+
+        validate_balance(balance_cents, "initial_balance_cents").map_err(BankError::ValidationError)?;
+        self.ensure_user_exists(request.user_id).await?;
+        self.repository
+            .insert(request.user_id, request.kind.unwrap_or_default(), balance_cents, self.clock.now(), |id| {
+                // Serial IDs start at 1, so they are never negative
+                self.account_numbers.generate(id.unsigned_abs())
+            })
+            .await
+    }
+
+    /// Counts all accounts
+    pub async fn count_accounts(&self) -> Result<i64, BankError> {
+        self.repository.count().await
+    }
+
+    /// Retrieves an account by ID
+    pub async fn get_account(&self, id: i32) -> Result<Account, BankError> {
+        info!(account_id = id, "AccountService: Getting account");
+
+        self.repository.find_by_id(id).await?.ok_or(BankError::AccountNotFound)
+    }
+
+    /// Lists a page of users with the accounts each one holds
+    ///
+    /// The accounts of the whole page are fetched in one query and grouped by
+    /// holder here, so the page costs the same number of queries whatever
+    /// its size.
+    pub async fn list_users_with_accounts(&self, params: PaginationParams) -> Result<UsersWithAccountsPage, BankError> {
+        info!(limit = params.limit, "AccountService: Listing users with accounts");
+
+        let page = self.users.get_users_paginated(params).await.map_err(|e| {
+            warn!(error = ?e, "AccountService: Error listing users");
+            BankError::UserServiceError(e)
+        })?;
+        let user_ids: Vec<i32> = page.users.iter().map(|user| user.id).collect();
+        let mut accounts_by_user: HashMap<i32, Vec<Account>> = HashMap::with_capacity(user_ids.len());
+        for account in self.repository.find_by_user_ids(&user_ids).await? {
+            accounts_by_user.entry(account.user_id).or_default().push(account);
+        }
+
+        let users = page
+            .users
+            .into_iter()
+            .map(|user| UserWithAccounts {
+                accounts: accounts_by_user.remove(&user.id).unwrap_or_default(),
+                user,
+            })
+            .collect();
+        Ok(UsersWithAccountsPage {
+            users,
+            next_token: page.next_token,
+            prev_token: page.prev_token,
+            has_more: page.has_more,
+            count: page.count,
+        })
+    }
+
+    /// Withdraws money from an account
+    pub async fn withdraw(&self, id: i32, amount_cents: i64) -> Result<Account, BankError> {
+        info!(account_id = id, amount_cents, "AccountService: Withdrawing");
+
+        validate_amount(amount_cents, "amount_cents").map_err(BankError::ValidationError)?;
+        let at = self.clock.now();
+        retry(self.retry_policy, &self.retries, "withdrawal", || {
+            self.repository.withdraw(id, amount_cents, at, |account| check_debit(account, amount_cents, "withdrawal"))
+        })
+        .await
+    }
+
+    /// Moves money between two accounts; neither may be frozen
+    ///
+    /// Returns the transfer with the debited account's limit usage once it is made.
+    pub async fn transfer(&self, request: TransferRequest) -> Result<(Transfer, TransferLimitUsage), BankError> {
+        let TransferRequest { from_account_id, to_account_id, amount_cents } = request;
+        info!(from_account_id, to_account_id, amount_cents, "AccountService: Transferring");
+
+        validate_amount(amount_cents, "amount_cents")
+            .and(validate_transfer_accounts(from_account_id, to_account_id, "to_account_id"))
+            .inspect_err(|errors| count_failures("transfer", errors))
+            .map_err(BankError::ValidationError)?;
+
+        let at = self.clock.now();
+        let (from, to, usage) = retry(self.retry_policy, &self.retries, "transfer", || {
+            self.repository.transfer(from_account_id, to_account_id, amount_cents, at, |from, to, totals| {
+                check_debit(from, amount_cents, "transfer")?;
+                check_not_frozen(to, "transfer")?;
+                self.limits.check(from.id, totals, amount_cents, at)
+            })
+        })
+        .await?;
+        record_transfer("internal", amount_cents);
+        Ok((Transfer { from, to, amount_cents }, usage))
+    }
+
+    /// Pays an account at another bank; the account number must be a saved beneficiary
+    ///
+    /// Account numbers of this bank's own accounts are rejected: those are
+    /// paid with [`AccountService::transfer`], which needs no beneficiary.
+    /// Returns the transfer with the account's limit usage once it is made.
+    pub async fn external_transfer(&self, request: ExternalTransferRequest) -> Result<(ExternalTransfer, TransferLimitUsage), BankError> {
+        let ExternalTransferRequest { from_account_id, to_account_number, amount_cents } = request;
+        let account_number = normalize_account_number(&to_account_number);
+        info!(from_account_id, to_account_number = %account_number, amount_cents, "AccountService: Transferring to another bank");
+
+        let number_check = validate_account_number(&account_number, "to_account_number").map(|_| ());
+        validate_amount(amount_cents, "amount_cents")
+            .and(number_check)
+            .inspect_err(|errors| count_failures("external_transfer", errors))
+            .map_err(BankError::ValidationError)?;
+        if self.repository.find_by_account_number(&account_number).await?.is_some() {
+            return Err(BankError::ValidationError(vec![field_error(
+                "to_account_number",
+                ErrorCode::InternalAccountNumber,
+                "Account belongs to this bank; use POST /transfers",
+            )]));
+        }
+
+        let at = self.clock.now();
+        let (from, beneficiary, usage) = retry(self.retry_policy, &self.retries, "external_transfer", || {
+            self.repository.external_transfer(from_account_id, &account_number, amount_cents, at, |from, beneficiary, totals| {
+                check_debit(from, amount_cents, "external_transfer")?;
+                let usage = self.limits.check(from.id, totals, amount_cents, at)?;
+                let beneficiary = beneficiary.ok_or_else(|| {
+                    warn!(from_account_id, to_account_number = %account_number, "AccountService: Not a saved beneficiary");
+                    BankError::BeneficiaryRequired { account_number: account_number.clone() }
+                })?;
+                Ok((beneficiary, usage))
+            })
+        })
+        .await?;
+        record_transfer("external", amount_cents);
+        Ok((ExternalTransfer { from, beneficiary, amount_cents }, usage))
+    }
+
+    /// Reports how much of each transfer limit an account has used
+    pub async fn transfer_limit_usage(&self, id: i32) -> Result<TransferLimitUsage, BankError> {
+        info!(account_id = id, "AccountService: Getting transfer limit usage");
+
+        self.get_account(id).await?;
+        let at = self.clock.now();
+        let totals = self.repository.find_transfer_totals(id, at).await?;
+        Ok(self.limits.usage(id, totals, at))
+    }
+
+    /// Lists the beneficiaries saved by a user
+    pub async fn list_beneficiaries(&self, user_id: i32) -> Result<Vec<Beneficiary>, BankError> {
+        info!(user_id, "AccountService: Listing beneficiaries");
+
+        self.ensure_user_exists(user_id).await?;
+        self.repository.find_beneficiaries(user_id).await
+    }
+
+    /// Saves a beneficiary for a user, enabling transfers to its account number
+    pub async fn add_beneficiary(&self, user_id: i32, request: CreateBeneficiary) -> Result<Beneficiary, BankError> {
+        let account_number = normalize_account_number(&request.account_number);
+        info!(user_id, account_number = %account_number, "AccountService: Adding beneficiary");
+
+        let number_check = validate_account_number(&account_number, "account_number").map(|_| ());
+        validate_beneficiary_name(&request.name, "name")
+            .and(number_check)
+            .map_err(BankError::ValidationError)?;
+        self.ensure_user_exists(user_id).await?;
+        self.repository
+            .insert_beneficiary(user_id, request.name.trim(), &account_number, self.clock.now())
+            .await?
+            .ok_or(BankError::BeneficiaryExists { account_number })
+    }
+
+    /// Removes a beneficiary of a user
+    pub async fn remove_beneficiary(&self, user_id: i32, beneficiary_id: i32) -> Result<(), BankError> {
+        info!(user_id, beneficiary_id, "AccountService: Removing beneficiary");
+
+        self.ensure_user_exists(user_id).await?;
+        if self.repository.delete_beneficiary(user_id, beneficiary_id).await? {
+            Ok(())
+        } else {
+            warn!(user_id, beneficiary_id, "AccountService: Beneficiary not found");
+            Err(BankError::BeneficiaryNotFound)
+        }
+    }
+
+    /// Credits one day of interest to every savings account, for today's date
+    ///
+    /// Accounts are processed in batches of `batch_size`, each in its own
+    /// transaction. Every credit is keyed by the date, so re-running on the
+    /// same day (after a crash, or on another instance) credits nothing
+    /// twice. Frozen accounts still accrue: holds block money leaving an
+    /// account, not interest owed to it.
+    pub async fn accrue_interest(&self, annual_rate_bps: u32, batch_size: u32) -> Result<InterestAccrual, BankError> {
+        let period = self.clock.now().date_naive();
+        info!(%period, annual_rate_bps, batch_size, "AccountService: Accruing interest");
+
+        let period_key = period.to_string();
+        let mut accrual = InterestAccrual { period, accounts_credited: 0, total_cents: 0 };
+        let mut after_id = 0;
+        while let Some(batch) = self
+            .repository
+            .accrue_interest_batch(after_id, batch_size, &period_key, self.clock.now(), |account| {
+                daily_interest(account.balance_cents, annual_rate_bps)
+            })
+            .await?
+        {
+            after_id = batch.last_id;
+            accrual.accounts_credited += batch.credited;
+            accrual.total_cents += batch.total_cents;
+        }
+
+        info!(
+            target: "audit",
+            %period,
+            accounts_credited = accrual.accounts_credited,
+            total_cents = accrual.total_cents,
+            "Interest accrued"
+        );
+        Ok(accrual)
+    }
+
+    /// Checks the ledger invariants: every ledger transaction sums to zero and
+    /// every stored balance equals the sum of the account's ledger entries
+    pub async fn verify_ledger(&self) -> Result<LedgerVerification, BankError> {
+        let verification = self.repository.verify_ledger().await?;
+        if verification.consistent {
+            info!(accounts_checked = verification.accounts_checked, "AccountService: Ledger is consistent");
+        } else {
+            error!(
+                accounts_checked = verification.accounts_checked,
+                discrepancies = verification.discrepancies.len(),
+                unbalanced_transactions = verification.unbalanced_transactions.len(),
+                "AccountService: Ledger is inconsistent"
+            );
+        }
+        Ok(verification)
+    }
+
+    /// Places a compliance hold on an account, blocking withdrawals and transfers
+    pub async fn freeze_account(&self, id: i32, request: FreezeAccount) -> Result<Account, BankError> {
+        validate_reason(&request.reason, "reason").map_err(BankError::ValidationError)?;
+
+        let reason = request.reason.trim();
+        let account = self
+            .repository
+            .freeze(id, reason, self.clock.now())
+            .await?
+            .ok_or(BankError::AccountNotFound)?;
+        info!(target: "audit", account_id = id, reason, "Account frozen");
+        Ok(account)
+    }
+
+    /// Lifts the compliance hold on an account
+    pub async fn unfreeze_account(&self, id: i32) -> Result<Account, BankError> {
+        let account = self.repository.unfreeze(id, self.clock.now()).await?.ok_or(BankError::AccountNotFound)?;
+        info!(target: "audit", account_id = id, "Account unfrozen");
+        Ok(account)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::*;
+    use crate::bank::domain::AccountKind;
+
+    fn account(balance_cents: i64, frozen: bool) -> Account {
+        Account {
+            id: 1,
+            user_id: 1,
+            account_number: None,
+            kind: AccountKind::Checking,
+            balance_cents,
+            frozen,
+            frozen_reason: frozen.then(|| "Sanctions screening".to_owned()),
+            frozen_at: frozen.then(Utc::now),
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_check_debit_enforces_holds_and_balance() {
+        assert!(check_debit(&account(500, false), 500, "withdrawal").is_ok());
+        assert!(matches!(
+            check_debit(&account(500, false), 501, "withdrawal"),
+            Err(BankError::InsufficientFunds)
+        ));
+        assert!(matches!(
+            check_debit(&account(500, true), 100, "withdrawal"),
+            Err(BankError::AccountFrozen { account_id: 1 })
+        ));
+    }
+
+    #[test]
+    fn test_daily_interest_rounds_down() {
+        // 3.65% a year is 0.01% a day
+        assert_eq!(daily_interest(1_000_000, 365), 100);
+        assert_eq!(daily_interest(999_999, 365), 99);
+        assert_eq!(daily_interest(5_000, 100), 0);
+        assert_eq!(daily_interest(1_000_000, 0), 0);
+        assert_eq!(daily_interest(i64::MAX, u32::MAX), i64::MAX);
+    }
+
+    #[test]
+    fn test_check_not_frozen() {
+        assert!(check_not_frozen(&account(0, false), "transfer").is_ok());
+        assert!(matches!(check_not_frozen(&account(0, true), "transfer"), Err(BankError::AccountFrozen { .. })));
+    }
+}
@@ -0,0 +1,503 @@
+//! Account controller - HTTP handlers for bank accounts, transfers, compliance holds,
+//! ledger verification, users with their accounts expanded and the
+//! `/users/{id}/beneficiaries` sub-resource
+
+use axum::{
+    Json,
+    extract::{Extension, Path, Query},
+    http::StatusCode,
+    response::{AppendHeaders, IntoResponse, Response},
+};
+use tracing::{error, warn};
+
+use crate::bank::AccountService;
+use crate::error_codes::{ErrorCode, ErrorResponse, internal_error};
+use crate::partner::{PartnerError, PartnerId, PartnerService};
+use crate::registry::Inject;
+use crate::user::UserId;
+use crate::pagination::SortOrder;
+use crate::user::domain::{ApiResponse, PaginationParams, ValidationErrorResponse, UserError};
+
+use super::domain::{
+    Account, AccountNumberCheck, BankError, Beneficiary, CreateBeneficiary, ExternalTransfer, ExternalTransferRequest,
+    FreezeAccount, LedgerVerification, LimitExceededResponse, OpenAccount, Transfer, TransferLimitUsage, TransferRequest,
+    UsersWithAccountsPage, ValidateAccountNumber, Withdrawal,
+};
+
+/// Maps bank errors to HTTP responses
+fn error_response(error: BankError, account_id: Option<i32>) -> Response {
+    match error {
+        BankError::ValidationError(errors) => {
+            warn!(?errors, account_id, "Controller: Account validation failed");
+            (
+                StatusCode::BAD_REQUEST,
+                Json(ValidationErrorResponse { errors }),
+            ).into_response()
+        }
+        BankError::UserNotFound | BankError::AccountNotFound | BankError::BeneficiaryNotFound => {
+            warn!(account_id, error = %error, "Controller: Account resource not found");
+            let code = if matches!(error, BankError::UserNotFound) {
+                ErrorCode::UserNotFound
+            } else if matches!(error, BankError::BeneficiaryNotFound) {
+                ErrorCode::BeneficiaryNotFound
+            } else {
+                ErrorCode::AccountNotFound
+            };
+            (
+                StatusCode::NOT_FOUND,
+                Json(ErrorResponse::new(code, error.to_string())),
+            ).into_response()
+        }
+        BankError::AccountFrozen { .. } | BankError::BeneficiaryExists { .. } => {
+            warn!(account_id, error = %error, "Controller: Conflicting account operation");
+            let code = if matches!(error, BankError::AccountFrozen { .. }) { ErrorCode::AccountFrozen } else { ErrorCode::BeneficiaryExists };
+            (
+                StatusCode::CONFLICT,
+                Json(ErrorResponse::new(code, error.to_string())),
+            ).into_response()
+        }
+        BankError::InsufficientFunds | BankError::BeneficiaryRequired { .. } => {
+            warn!(account_id, error = %error, "Controller: Transfer rejected");
+            let code = if matches!(error, BankError::InsufficientFunds) { ErrorCode::InsufficientFunds } else { ErrorCode::BeneficiaryRequired };
+            (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                Json(ErrorResponse::new(code, error.to_string())),
+            ).into_response()
+        }
+        BankError::LimitExceeded(limit) => {
+            warn!(account_id, window = ?limit.window, remaining_cents = limit.remaining_cents, "Controller: Transfer limit exceeded");
+            let message = BankError::LimitExceeded(limit.clone()).to_string();
+            (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                AppendHeaders(limit.headers()),
+                Json(LimitExceededResponse {
+                    code: ErrorCode::TransferLimitExceeded,
+                    error: "limit_exceeded".to_owned(),
+                    message,
+                    limit,
+                }),
+            ).into_response()
+        }
+        BankError::DatabaseError(e) => {
+            error!(error = %e, account_id, "Controller: Database error in account operation");
+            e.into_response()
+        }
+        BankError::UserServiceError(UserError::DatabaseError(e)) => {
+            error!(error = %e, account_id, "Controller: Database error in user lookup for account operation");
+            e.into_response()
+        }
+        BankError::UserServiceError(e @ UserError::InvalidToken) => {
+            warn!(error = %e, "Controller: Invalid pagination token provided");
+            (
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse::new(ErrorCode::InvalidPaginationToken, e.to_string())),
+            ).into_response()
+        }
+        BankError::UserServiceError(UserError::ValidationError(errors)) => {
+            warn!(?errors, "Controller: Invalid pagination parameters");
+            (
+                StatusCode::BAD_REQUEST,
+                Json(ValidationErrorResponse { errors }),
+            ).into_response()
+        }
+        BankError::UserServiceError(e) => {
+            error!(error = %e, account_id, "Controller: User service error in account operation");
+            internal_error()
+        }
+    }
+}
+
+/// HTTP handler for opening an account
+#[utoipa::path(
+    post,
+    path = "/accounts",
+    tag = "accounts",
+    request_body = OpenAccount,
+    responses(
+        (status = 201, description = "Account opened", body = Account),
+        (status = 400, description = "Invalid opening balance", body = ValidationErrorResponse),
+        (status = 404, description = "User not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    )
+)]
+#[tracing::instrument(skip(account_service, payload), fields(user_id = payload.user_id))]
+pub async fn open_account_handler(
+    Inject(account_service): Inject<AccountService>,
+    Json(payload): Json<OpenAccount>,
+) -> impl IntoResponse {
+    match account_service.open_account(payload).await {
+        Ok(account) => (StatusCode::CREATED, Json(account)).into_response(),
+        Err(e) => error_response(e, None),
+    }
+}
+
+/// HTTP handler for getting an account by ID
+#[utoipa::path(
+    get,
+    path = "/accounts/{id}",
+    tag = "accounts",
+    params(
+        ("id" = i32, Path, description = "Account ID")
+    ),
+    responses(
+        (status = 200, description = "Account found", body = Account),
+        (status = 404, description = "Account not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    )
+)]
+#[tracing::instrument(skip(account_service), fields(account_id = id))]
+pub async fn get_account_handler(
+    Inject(account_service): Inject<AccountService>,
+    Path(id): Path<i32>,
+) -> impl IntoResponse {
+    match account_service.get_account(id).await {
+        Ok(account) => (StatusCode::OK, Json(account)).into_response(),
+        Err(e) => error_response(e, Some(id)),
+    }
+}
+
+/// HTTP handler for withdrawing from an account
+#[utoipa::path(
+    post,
+    path = "/accounts/{id}/withdraw",
+    tag = "accounts",
+    params(
+        ("id" = i32, Path, description = "Account ID")
+    ),
+    request_body = Withdrawal,
+    responses(
+        (status = 200, description = "Withdrawal made", body = Account),
+        (status = 400, description = "Invalid amount", body = ValidationErrorResponse),
+        (status = 404, description = "Account not found", body = ErrorResponse),
+        (status = 409, description = "Account is frozen", body = ErrorResponse),
+        (status = 422, description = "Insufficient funds", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    )
+)]
+#[tracing::instrument(skip(account_service, payload), fields(account_id = id, amount_cents = payload.amount_cents))]
+pub async fn withdraw_handler(
+    Inject(account_service): Inject<AccountService>,
+    Path(id): Path<i32>,
+    Json(payload): Json<Withdrawal>,
+) -> impl IntoResponse {
+    match account_service.withdraw(id, payload.amount_cents).await {
+        Ok(account) => (StatusCode::OK, Json(account)).into_response(),
+        Err(e) => error_response(e, Some(id)),
+    }
+}
+
+/// HTTP handler for moving money between accounts
+#[utoipa::path(
+    post,
+    path = "/transfers",
+    tag = "accounts",
+    request_body = TransferRequest,
+    responses(
+        (status = 200, description = "Transfer made; `RateLimit-*` headers report the transfer limit with the least quota left", body = Transfer),
+        (status = 400, description = "Invalid amount or accounts", body = ValidationErrorResponse),
+        (status = 404, description = "Account not found", body = ErrorResponse),
+        (status = 409, description = "An account is frozen", body = ErrorResponse),
+        (status = 422, description = "Insufficient funds, or `limit_exceeded` (body: `LimitExceededResponse`, `RateLimit-*` headers report the exceeded limit)", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    )
+)]
+#[tracing::instrument(
+    skip(account_service, payload),
+    fields(from_account_id = payload.from_account_id, to_account_id = payload.to_account_id)
+)]
+pub async fn transfer_handler(
+    Inject(account_service): Inject<AccountService>,
+    Json(payload): Json<TransferRequest>,
+) -> impl IntoResponse {
+    let from_account_id = payload.from_account_id;
+    match account_service.transfer(payload).await {
+        Ok((transfer, usage)) => (StatusCode::OK, AppendHeaders(usage.headers()), Json(transfer)).into_response(),
+        Err(e) => error_response(e, Some(from_account_id)),
+    }
+}
+
+/// HTTP handler for viewing how much of its transfer limits an account has used
+#[utoipa::path(
+    get,
+    path = "/accounts/{id}/transfer-limits",
+    tag = "accounts",
+    params(
+        ("id" = i32, Path, description = "Account ID")
+    ),
+    responses(
+        (status = 200, description = "Usage of each configured transfer limit; `RateLimit-*` headers report the one with the least quota left", body = TransferLimitUsage),
+        (status = 404, description = "Account not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    )
+)]
+#[tracing::instrument(skip(account_service), fields(account_id = id))]
+pub async fn transfer_limits_handler(
+    Inject(account_service): Inject<AccountService>,
+    Path(id): Path<i32>,
+) -> impl IntoResponse {
+    match account_service.transfer_limit_usage(id).await {
+        Ok(usage) => (StatusCode::OK, AppendHeaders(usage.headers()), Json(usage)).into_response(),
+        Err(e) => error_response(e, Some(id)),
+    }
+}
+
+/// HTTP handler for paying an account at another bank
+///
+/// Only partners call this route, with signed requests (see [`crate::partner::signing`]),
+/// and only out of the accounts an admin granted them.
+#[utoipa::path(
+    post,
+    path = "/transfers/external",
+    tag = "accounts",
+    request_body = ExternalTransferRequest,
+    responses(
+        (status = 200, description = "Transfer made; `RateLimit-*` headers report the transfer limit with the least quota left", body = ExternalTransfer),
+        (status = 400, description = "Invalid amount or account number", body = ValidationErrorResponse),
+        (status = 401, description = "Missing, stale, replayed or invalid partner signature", body = ErrorResponse),
+        (status = 403, description = "The account is not granted to the signing partner", body = ErrorResponse),
+        (status = 404, description = "Account not found", body = ErrorResponse),
+        (status = 409, description = "The account is frozen", body = ErrorResponse),
+        (status = 422, description = "Insufficient funds, the account number is not a saved beneficiary, or `limit_exceeded` (body: `LimitExceededResponse`, `RateLimit-*` headers report the exceeded limit)", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    )
+)]
+#[tracing::instrument(skip(account_service, partner_service, payload), fields(partner = %partner.0, from_account_id = payload.from_account_id))]
+pub async fn external_transfer_handler(
+    Inject(account_service): Inject<AccountService>,
+    Inject(partner_service): Inject<PartnerService>,
+    Extension(partner): Extension<PartnerId>,
+    Json(payload): Json<ExternalTransferRequest>,
+) -> impl IntoResponse {
+    let from_account_id = payload.from_account_id;
+    match partner_service.authorize_debit(&partner.0, from_account_id).await {
+        Ok(()) => {}
+        Err(e @ PartnerError::AccountNotGranted { .. }) => {
+            warn!(partner = %partner.0, from_account_id, "Controller: Partner may not debit the account");
+            return (
+                StatusCode::FORBIDDEN,
+                Json(ErrorResponse::new(ErrorCode::AccountNotGranted, e.to_string())),
+            ).into_response();
+        }
+        Err(e) => {
+            error!(error = %e, partner = %partner.0, "Controller: Failed to check the partner's account grant");
+            return internal_error();
+        }
+    }
+    match account_service.external_transfer(payload).await {
+        Ok((transfer, usage)) => (StatusCode::OK, AppendHeaders(usage.headers()), Json(transfer)).into_response(),
+        Err(e) => error_response(e, Some(from_account_id)),
+    }
+}
+
+/// HTTP handler for listing users with their accounts expanded
+///
+/// Pages like `GET /users` and embeds each user's accounts; the accounts of
+/// the whole page are loaded in a single query.
+#[utoipa::path(
+    get,
+    path = "/users/with-accounts",
+    tag = "accounts",
+    params(
+        ("next_token" = Option<String>, Query, description = "Pagination token from previous page (opaque cursor)"),
+        ("prev_token" = Option<String>, Query, description = "Pagination token for the page before the current one; cannot be combined with `next_token`"),
+        ("order" = Option<SortOrder>, Query, description = "Sort order by creation time (default `asc`)"),
+        ("limit" = Option<i32>, Query, description = "Number of users to return (default `PAGINATION_DEFAULT_LIMIT`, capped at `PAGINATION_MAX_LIMIT`)"),
+        ("tag" = Option<String>, Query, description = "Only return users carrying this tag")
+    ),
+    responses(
+        (status = 200, description = "Paginated list of users with their accounts", body = UsersWithAccountsPage),
+        (status = 400, description = "Both tokens provided; an invalid pagination token answers `INVALID_PAGINATION_TOKEN` in an `ErrorResponse`", body = ValidationErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    )
+)]
+#[tracing::instrument(skip(account_service, params), fields(limit = params.limit, tag = params.tag.as_deref()))]
+pub async fn list_users_with_accounts_handler(
+    Inject(account_service): Inject<AccountService>,
+    Query(params): Query<PaginationParams>,
+) -> impl IntoResponse {
+    match account_service.list_users_with_accounts(params).await {
+        Ok(page) => (StatusCode::OK, Json(page)).into_response(),
+        Err(e) => error_response(e, None),
+    }
+}
+
+/// HTTP handler for listing the beneficiaries of a user
+#[utoipa::path(
+    get,
+    path = "/users/{id}/beneficiaries",
+    tag = "beneficiaries",
+    params(
+        ("id" = i32, Path, description = "User ID")
+    ),
+    responses(
+        (status = 200, description = "Beneficiaries of the user", body = Vec<Beneficiary>),
+        (status = 400, description = "Invalid user ID", body = ValidationErrorResponse),
+        (status = 404, description = "User not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    )
+)]
+#[tracing::instrument(skip(account_service), fields(user_id = user_id))]
+pub async fn list_beneficiaries_handler(
+    Inject(account_service): Inject<AccountService>,
+    UserId(user_id): UserId,
+) -> impl IntoResponse {
+    match account_service.list_beneficiaries(user_id).await {
+        Ok(beneficiaries) => (StatusCode::OK, Json(beneficiaries)).into_response(),
+        Err(e) => error_response(e, None),
+    }
+}
+
+/// HTTP handler for saving a beneficiary for a user
+#[utoipa::path(
+    post,
+    path = "/users/{id}/beneficiaries",
+    tag = "beneficiaries",
+    params(
+        ("id" = i32, Path, description = "User ID")
+    ),
+    request_body = CreateBeneficiary,
+    responses(
+        (status = 201, description = "Beneficiary saved", body = Beneficiary),
+        (status = 400, description = "Validation errors", body = ValidationErrorResponse),
+        (status = 404, description = "User not found", body = ErrorResponse),
+        (status = 409, description = "Account number already saved", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    )
+)]
+#[tracing::instrument(skip(account_service, payload), fields(user_id = user_id))]
+pub async fn create_beneficiary_handler(
+    Inject(account_service): Inject<AccountService>,
+    UserId(user_id): UserId,
+    Json(payload): Json<CreateBeneficiary>,
+) -> impl IntoResponse {
+    match account_service.add_beneficiary(user_id, payload).await {
+        Ok(beneficiary) => (StatusCode::CREATED, Json(beneficiary)).into_response(),
+        Err(e) => error_response(e, None),
+    }
+}
+
+/// HTTP handler for removing a beneficiary of a user
+#[utoipa::path(
+    delete,
+    path = "/users/{id}/beneficiaries/{beneficiary_id}",
+    tag = "beneficiaries",
+    params(
+        ("id" = i32, Path, description = "User ID"),
+        ("beneficiary_id" = i32, Path, description = "Beneficiary ID")
+    ),
+    responses(
+        (status = 200, description = "Beneficiary removed", body = ApiResponse),
+        (status = 400, description = "Invalid user ID", body = ValidationErrorResponse),
+        (status = 404, description = "User or beneficiary not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    )
+)]
+#[tracing::instrument(skip(account_service, path), fields(user_id = user_id, beneficiary_id = path.1))]
+pub async fn delete_beneficiary_handler(
+    Inject(account_service): Inject<AccountService>,
+    UserId(user_id): UserId,
+    Path(path): Path<(i32, i32)>,
+) -> impl IntoResponse {
+    let beneficiary_id = path.1;
+    match account_service.remove_beneficiary(user_id, beneficiary_id).await {
+        Ok(()) => (
+            StatusCode::OK,
+            Json(ApiResponse {
+                message: format!("Beneficiary with id {beneficiary_id} deleted successfully"),
+            }),
+        ).into_response(),
+        Err(e) => error_response(e, None),
+    }
+}
+
+/// HTTP handler for checking an account number (IBAN or Luhn)
+#[utoipa::path(
+    post,
+    path = "/accounts/validate-number",
+    tag = "accounts",
+    request_body = ValidateAccountNumber,
+    responses(
+        (status = 200, description = "Valid account number", body = AccountNumberCheck),
+        (status = 400, description = "Invalid account number", body = ValidationErrorResponse)
+    )
+)]
+#[tracing::instrument(skip(payload))]
+pub async fn validate_account_number_handler(Json(payload): Json<ValidateAccountNumber>) -> impl IntoResponse {
+    match AccountService::check_account_number(&payload.account_number) {
+        Ok(check) => (StatusCode::OK, Json(check)).into_response(),
+        Err(e) => error_response(e, None),
+    }
+}
+
+/// HTTP handler for placing a compliance hold on an account
+#[utoipa::path(
+    post,
+    path = "/accounts/{id}/freeze",
+    tag = "accounts",
+    params(
+        ("id" = i32, Path, description = "Account ID")
+    ),
+    request_body = FreezeAccount,
+    responses(
+        (status = 200, description = "Account frozen", body = Account),
+        (status = 400, description = "Invalid reason", body = ValidationErrorResponse),
+        (status = 404, description = "Account not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    )
+)]
+#[tracing::instrument(skip(account_service, payload), fields(account_id = id))]
+pub async fn freeze_account_handler(
+    Inject(account_service): Inject<AccountService>,
+    Path(id): Path<i32>,
+    Json(payload): Json<FreezeAccount>,
+) -> impl IntoResponse {
+    match account_service.freeze_account(id, payload).await {
+        Ok(account) => (StatusCode::OK, Json(account)).into_response(),
+        Err(e) => error_response(e, Some(id)),
+    }
+}
+
+/// HTTP handler for lifting the compliance hold on an account
+#[utoipa::path(
+    post,
+    path = "/accounts/{id}/unfreeze",
+    tag = "accounts",
+    params(
+        ("id" = i32, Path, description = "Account ID")
+    ),
+    responses(
+        (status = 200, description = "Account unfrozen", body = Account),
+        (status = 404, description = "Account not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    )
+)]
+#[tracing::instrument(skip(account_service), fields(account_id = id))]
+pub async fn unfreeze_account_handler(
+    Inject(account_service): Inject<AccountService>,
+    Path(id): Path<i32>,
+) -> impl IntoResponse {
+    match account_service.unfreeze_account(id).await {
+        Ok(account) => (StatusCode::OK, Json(account)).into_response(),
+        Err(e) => error_response(e, Some(id)),
+    }
+}
+
+/// HTTP handler verifying the ledger against stored balances
+#[utoipa::path(
+    get,
+    path = "/admin/ledger/verify",
+    tag = "admin",
+    responses(
+        (status = 200, description = "Verification report; `consistent` is false when discrepancies were found", body = LedgerVerification),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+        (status = 403, description = "Caller lacks the admin role", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    )
+)]
+#[tracing::instrument(skip(account_service))]
+pub async fn verify_ledger_handler(Inject(account_service): Inject<AccountService>) -> impl IntoResponse {
+    match account_service.verify_ledger().await {
+        Ok(verification) => (StatusCode::OK, Json(verification)).into_response(),
+        Err(e) => error_response(e, None),
+    }
+}
@@ -0,0 +1,326 @@
+//! Bank domain models
+
+use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+use utoipa::ToSchema;
+use chrono::{DateTime, NaiveDate, Utc};
+
+use crate::db::{DbError, Retryable};
+use crate::error_codes::ErrorCode;
+use crate::user::domain::{User, UserError, ValidationError};
+
+/// Kind of bank account
+#[derive(Serialize, Deserialize, ToSchema, JsonSchema, Debug, Clone, Copy, PartialEq, Eq, Default, sqlx::Type)]
+#[sqlx(type_name = "account_kind", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum AccountKind {
+    /// Everyday account; does not accrue interest
+    #[default]
+    Checking,
+    /// Savings account; accrues daily interest
+    Savings,
+}
+
+/// Bank account entity returned by the API
+#[derive(Serialize, ToSchema, JsonSchema, Debug, Clone, sqlx::FromRow)]
+pub struct Account {
+    /// Unique account identifier
+    pub id: i32,
+    /// Account holder
+    pub user_id: i32,
+    /// Account number (IBAN or Luhn-checked digits); absent on accounts opened before numbers were introduced
+    pub account_number: Option<String>,
+    /// Checking or savings
+    pub kind: AccountKind,
+    /// Current balance in cents
+    pub balance_cents: i64,
+    /// Whether a compliance hold blocks withdrawals and transfers
+    pub frozen: bool,
+    /// Why the account was frozen (while frozen)
+    pub frozen_reason: Option<String>,
+    /// When the account was frozen (while frozen)
+    pub frozen_at: Option<DateTime<Utc>>,
+    /// When the account was opened
+    pub created_at: DateTime<Utc>,
+}
+
+/// A user with the accounts they hold
+#[derive(Serialize, ToSchema, JsonSchema, Debug, Clone)]
+pub struct UserWithAccounts {
+    /// The user
+    #[serde(flatten)]
+    pub user: User,
+    /// Accounts held by the user, oldest first (empty when none)
+    pub accounts: Vec<Account>,
+}
+
+/// A page of users with their accounts expanded
+#[derive(Serialize, ToSchema, JsonSchema, Debug, Clone)]
+pub struct UsersWithAccountsPage {
+    /// Users of this page
+    pub users: Vec<UserWithAccounts>,
+    /// Token for the next page (opaque cursor)
+    pub next_token: Option<String>,
+    /// Token for the previous page (absent on the first page)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prev_token: Option<String>,
+    /// Whether there are more users available after this page
+    pub has_more: bool,
+    /// Number of users returned in this page
+    pub count: usize,
+}
+
+/// Request payload for opening an account
+#[derive(Deserialize, ToSchema, JsonSchema, Debug, Clone)]
+pub struct OpenAccount {
+    /// Account holder
+    pub user_id: i32,
+    /// Checking or savings (default: checking)
+    pub kind: Option<AccountKind>,
+    /// Opening balance in cents (default: 0)
+    pub initial_balance_cents: Option<i64>,
+}
+
+/// Format of an account number
+#[derive(Serialize, ToSchema, JsonSchema, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum AccountNumberFormat {
+    /// International Bank Account Number with mod-97 check digits
+    Iban,
+    /// Digits ending in a Luhn check digit
+    Luhn,
+}
+
+/// Request payload for validating an account number
+#[derive(Deserialize, ToSchema, JsonSchema, Debug, Clone)]
+pub struct ValidateAccountNumber {
+    /// Account number to check; spaces and dashes are ignored
+    pub account_number: String,
+}
+
+/// A valid account number
+#[derive(Serialize, ToSchema, JsonSchema, Debug, Clone, PartialEq, Eq)]
+pub struct AccountNumberCheck {
+    /// The account number, normalized (no spaces or dashes, uppercase)
+    pub account_number: String,
+    /// Detected format
+    pub format: AccountNumberFormat,
+}
+
+/// Request payload for withdrawing from an account
+#[derive(Deserialize, ToSchema, JsonSchema, Debug, Clone)]
+pub struct Withdrawal {
+    /// Amount to withdraw in cents
+    pub amount_cents: i64,
+}
+
+/// Request payload for moving money between accounts
+#[derive(Deserialize, ToSchema, JsonSchema, Debug, Clone)]
+pub struct TransferRequest {
+    /// Account debited
+    pub from_account_id: i32,
+    /// Account credited
+    pub to_account_id: i32,
+    /// Amount to transfer in cents
+    pub amount_cents: i64,
+}
+
+/// Outcome of a transfer
+#[derive(Serialize, ToSchema, JsonSchema, Debug, Clone)]
+pub struct Transfer {
+    /// Debited account after the transfer
+    pub from: Account,
+    /// Credited account after the transfer
+    pub to: Account,
+    /// Amount transferred in cents
+    pub amount_cents: i64,
+}
+
+/// External transfer target saved by a user
+#[derive(Serialize, ToSchema, JsonSchema, Debug, Clone, PartialEq, Eq, sqlx::FromRow)]
+pub struct Beneficiary {
+    /// Unique beneficiary identifier
+    pub id: i32,
+    /// User who saved the beneficiary
+    pub user_id: i32,
+    /// Name of the account holder at the other bank
+    pub name: String,
+    /// Account number at the other bank, normalized
+    pub account_number: String,
+    /// When the beneficiary was saved
+    pub created_at: DateTime<Utc>,
+}
+
+/// Request payload for saving a beneficiary
+#[derive(Deserialize, ToSchema, JsonSchema, Debug, Clone)]
+pub struct CreateBeneficiary {
+    /// Name of the account holder at the other bank
+    pub name: String,
+    /// IBAN or Luhn account number; spaces and dashes are ignored
+    pub account_number: String,
+}
+
+/// Request payload for paying an account at another bank
+#[derive(Deserialize, ToSchema, JsonSchema, Debug, Clone)]
+pub struct ExternalTransferRequest {
+    /// Account debited
+    pub from_account_id: i32,
+    /// Account number credited; must be a saved beneficiary of the debited account's holder
+    pub to_account_number: String,
+    /// Amount to transfer in cents
+    pub amount_cents: i64,
+}
+
+/// Outcome of a transfer to another bank
+#[derive(Serialize, ToSchema, JsonSchema, Debug, Clone)]
+pub struct ExternalTransfer {
+    /// Debited account after the transfer
+    pub from: Account,
+    /// Beneficiary paid
+    pub beneficiary: Beneficiary,
+    /// Amount transferred in cents
+    pub amount_cents: i64,
+}
+
+/// Rolling window a transfer limit applies to
+#[derive(Serialize, ToSchema, JsonSchema, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum LimitWindow {
+    /// The last 24 hours
+    Daily,
+    /// The last 30 days
+    Monthly,
+}
+
+/// Usage of one transfer limit
+#[derive(Serialize, ToSchema, JsonSchema, Debug, Clone, PartialEq, Eq)]
+pub struct LimitUsage {
+    /// Window the limit applies to
+    pub window: LimitWindow,
+    /// Most the account may send within the window, in cents
+    pub limit_cents: i64,
+    /// Sent by transfer within the window, in cents
+    pub used_cents: i64,
+    /// Still available within the window, in cents
+    pub remaining_cents: i64,
+    /// Seconds until the oldest transfer counted leaves the window and frees
+    /// part of the limit; 0 when no transfer counts
+    pub resets_in_secs: i64,
+}
+
+/// Current transfer limit usage of an account
+#[derive(Serialize, ToSchema, JsonSchema, Debug, Clone, PartialEq, Eq)]
+pub struct TransferLimitUsage {
+    /// The account
+    pub account_id: i32,
+    /// Usage of each configured limit; windows without a limit are omitted
+    pub limits: Vec<LimitUsage>,
+}
+
+/// Error body returned when a transfer would exceed a limit
+#[derive(Serialize, ToSchema, JsonSchema, Debug, Clone, PartialEq, Eq)]
+pub struct LimitExceededResponse {
+    /// Always `TRANSFER_LIMIT_EXCEEDED`
+    pub code: ErrorCode,
+    /// Always `limit_exceeded`; kept for existing clients, prefer `code`
+    pub error: String,
+    /// Human-readable description
+    pub message: String,
+    /// The limit exceeded, with the quota remaining in its window
+    pub limit: LimitUsage,
+}
+
+/// Request payload for placing a compliance hold on an account
+#[derive(Deserialize, ToSchema, JsonSchema, Debug, Clone)]
+pub struct FreezeAccount {
+    /// Why the account is frozen, recorded on the account and in the audit log
+    pub reason: String,
+}
+
+/// Outcome of an interest accrual run
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InterestAccrual {
+    /// Day the interest was accrued for
+    pub period: NaiveDate,
+    /// Savings accounts credited in this run (accounts already credited for the period are skipped)
+    pub accounts_credited: u64,
+    /// Total interest credited in cents
+    pub total_cents: i64,
+}
+
+/// Account whose stored balance differs from the sum of its ledger entries
+#[derive(Serialize, ToSchema, JsonSchema, Debug, Clone, PartialEq, Eq)]
+pub struct BalanceDiscrepancy {
+    /// Affected account
+    pub account_id: i32,
+    /// Balance stored on the account in cents
+    pub stored_balance_cents: i64,
+    /// Balance recomputed from the ledger in cents
+    pub ledger_balance_cents: i64,
+}
+
+/// Result of checking the ledger invariants against stored balances
+#[derive(Serialize, ToSchema, JsonSchema, Debug, Clone, PartialEq, Eq)]
+pub struct LedgerVerification {
+    /// Whether every invariant holds
+    pub consistent: bool,
+    /// Number of accounts checked
+    pub accounts_checked: i64,
+    /// Accounts whose stored balance differs from their ledger balance
+    pub discrepancies: Vec<BalanceDiscrepancy>,
+    /// Ledger transactions whose postings do not sum to zero
+    pub unbalanced_transactions: Vec<i64>,
+}
+
+/// Bank-specific errors
+#[derive(Debug, thiserror::Error)]
+pub enum BankError {
+    /// User was not found in the system
+    #[error("User not found")]
+    UserNotFound,
+    /// Error from the underlying user service
+    #[error("User service error: {0}")]
+    UserServiceError(UserError),
+    /// Account has insufficient funds for the operation
+    #[error("Insufficient funds")]
+    InsufficientFunds,
+    /// Bank account was not found
+    #[error("Account not found")]
+    AccountNotFound,
+    /// A compliance hold blocks withdrawals and transfers on the account
+    #[error("Account {account_id} is frozen")]
+    AccountFrozen {
+        /// The frozen account
+        account_id: i32,
+    },
+    /// Beneficiary was not found for the user
+    #[error("Beneficiary not found")]
+    BeneficiaryNotFound,
+    /// The user already saved a beneficiary with this account number
+    #[error("Beneficiary with account number {account_number} already exists")]
+    BeneficiaryExists {
+        /// The duplicate account number
+        account_number: String,
+    },
+    /// Transfers to accounts at other banks must go to a saved beneficiary
+    #[error("Account number {account_number} is not a saved beneficiary")]
+    BeneficiaryRequired {
+        /// The unknown account number
+        account_number: String,
+    },
+    /// The transfer would exceed a transfer limit of the debited account
+    #[error("{:?} transfer limit exceeded; {} cents remaining", .0.window, .0.remaining_cents)]
+    LimitExceeded(LimitUsage),
+    /// Request payload is invalid
+    #[error("Validation failed")]
+    ValidationError(Vec<ValidationError>),
+    /// Database operation failed
+    #[error("Database error: {0}")]
+    DatabaseError(DbError),
+}
+
+impl Retryable for BankError {
+    fn is_retryable(&self) -> bool {
+        matches!(self, Self::DatabaseError(e) if e.is_retryable())
+    }
+}
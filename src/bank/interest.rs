@@ -0,0 +1,45 @@
+//! Interest accrual job
+//!
+//! Credits daily interest to savings accounts through
+//! [`AccountService::accrue_interest`]. Each day is credited once, so the job
+//! can run as often as convenient: a run on a day already accrued is a no-op,
+//! and a run interrupted mid-way is completed by the next one.
+
+use futures_util::future::BoxFuture;
+
+use crate::jobs::{Job, JobError};
+
+use super::AccountService;
+
+/// Periodic job accruing interest on savings accounts
+#[derive(Clone)]
+pub struct InterestAccrualJob {
+    accounts: AccountService,
+    annual_rate_bps: u32,
+    batch_size: u32,
+}
+
+impl InterestAccrualJob {
+    /// Accrues `annual_rate_bps` a year, crediting `batch_size` accounts per transaction
+    #[must_use]
+    pub fn new(accounts: AccountService, annual_rate_bps: u32, batch_size: u32) -> Self {
+        Self {
+            accounts,
+            annual_rate_bps,
+            batch_size: batch_size.max(1),
+        }
+    }
+}
+
+impl Job for InterestAccrualJob {
+    fn name(&self) -> &'static str {
+        "interest-accrual"
+    }
+
+    fn run(&self) -> BoxFuture<'_, Result<(), JobError>> {
+        Box::pin(async move {
+            self.accounts.accrue_interest(self.annual_rate_bps, self.batch_size).await?;
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,209 @@
+//! Transfer limits
+//!
+//! Caps the money an account can send by transfer over rolling windows: the
+//! last 24 hours (`daily`) and the last 30 days (`monthly`). Usage is summed
+//! from the ledger inside the transfer's transaction, after the debited
+//! account is locked, so concurrent transfers cannot overshoot a limit.
+//!
+//! Usage is reported to clients in the `RateLimit-Limit`, `RateLimit-Remaining`
+//! and `RateLimit-Reset` headers of the IETF `RateLimit` header fields draft,
+//! in cents and seconds, for the window with the least quota remaining.
+
+use axum::http::{HeaderName, HeaderValue};
+use chrono::{DateTime, Duration, Utc};
+
+use crate::config::BankConfig;
+
+use super::domain::{BankError, LimitUsage, LimitWindow, TransferLimitUsage};
+
+/// `RateLimit-Limit` response header: the limit of the reported window, in cents
+const RATELIMIT_LIMIT: HeaderName = HeaderName::from_static("ratelimit-limit");
+/// `RateLimit-Remaining` response header: what is left of it, in cents
+const RATELIMIT_REMAINING: HeaderName = HeaderName::from_static("ratelimit-remaining");
+/// `RateLimit-Reset` response header: seconds until part of it frees up
+const RATELIMIT_RESET: HeaderName = HeaderName::from_static("ratelimit-reset");
+
+/// Money an account sent by transfer within each rolling window, in cents
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TransferTotals {
+    /// Sent within the last 24 hours
+    pub daily_cents: i64,
+    /// Sent within the last 30 days
+    pub monthly_cents: i64,
+    /// When the oldest transfer of the last 24 hours was made
+    pub daily_oldest_at: Option<DateTime<Utc>>,
+    /// When the oldest transfer of the last 30 days was made
+    pub monthly_oldest_at: Option<DateTime<Utc>>,
+}
+
+impl TransferTotals {
+    fn used(self, window: LimitWindow) -> (i64, Option<DateTime<Utc>>) {
+        match window {
+            LimitWindow::Daily => (self.daily_cents, self.daily_oldest_at),
+            LimitWindow::Monthly => (self.monthly_cents, self.monthly_oldest_at),
+        }
+    }
+
+    /// Totals once a transfer of `amount_cents` made at `at` is counted
+    #[must_use]
+    pub fn with_transfer(self, amount_cents: i64, at: DateTime<Utc>) -> Self {
+        Self {
+            daily_cents: self.daily_cents.saturating_add(amount_cents),
+            monthly_cents: self.monthly_cents.saturating_add(amount_cents),
+            daily_oldest_at: self.daily_oldest_at.or(Some(at)),
+            monthly_oldest_at: self.monthly_oldest_at.or(Some(at)),
+        }
+    }
+}
+
+impl LimitWindow {
+    /// Length of the rolling window
+    #[must_use]
+    pub fn duration(self) -> Duration {
+        match self {
+            Self::Daily => Duration::days(1),
+            Self::Monthly => Duration::days(30),
+        }
+    }
+}
+
+/// Per-account transfer limits; `None` means unlimited
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TransferLimits {
+    /// Most an account may send within 24 hours, in cents
+    pub daily_cents: Option<i64>,
+    /// Most an account may send within 30 days, in cents
+    pub monthly_cents: Option<i64>,
+}
+
+impl TransferLimits {
+    /// Limits configured by `config`; non-positive limits disable a window
+    #[must_use]
+    pub fn from_config(config: &BankConfig) -> Self {
+        Self {
+            daily_cents: Some(config.daily_transfer_limit_cents).filter(|limit| *limit > 0),
+            monthly_cents: Some(config.monthly_transfer_limit_cents).filter(|limit| *limit > 0),
+        }
+    }
+
+    /// Usage of each configured limit at `at` given what the account already sent
+    #[must_use]
+    pub fn usage(&self, account_id: i32, totals: TransferTotals, at: DateTime<Utc>) -> TransferLimitUsage {
+        let limits = [(LimitWindow::Daily, self.daily_cents), (LimitWindow::Monthly, self.monthly_cents)]
+            .into_iter()
+            .filter_map(|(window, limit)| {
+                let limit_cents = limit?;
+                let (used_cents, oldest_at) = totals.used(window);
+                let resets_in = oldest_at.map_or_else(Duration::zero, |oldest_at| oldest_at + window.duration() - at);
+                Some(LimitUsage {
+                    window,
+                    limit_cents,
+                    used_cents,
+                    remaining_cents: limit_cents.saturating_sub(used_cents).max(0),
+                    resets_in_secs: (resets_in.num_milliseconds().max(0) + 999) / 1000,
+                })
+            })
+            .collect();
+        TransferLimitUsage { account_id, limits }
+    }
+
+    /// Rejects sending `amount_cents` at `at` when it would exceed a limit,
+    /// reporting the first one exceeded
+    ///
+    /// Returns the usage once the amount is sent.
+    pub fn check(&self, account_id: i32, totals: TransferTotals, amount_cents: i64, at: DateTime<Utc>) -> Result<TransferLimitUsage, BankError> {
+        match self.usage(account_id, totals, at).limits.into_iter().find(|usage| amount_cents > usage.remaining_cents) {
+            Some(usage) => Err(BankError::LimitExceeded(usage)),
+            None => Ok(self.usage(account_id, totals.with_transfer(amount_cents, at), at)),
+        }
+    }
+}
+
+impl LimitUsage {
+    /// `RateLimit-*` response headers reporting this limit
+    #[must_use]
+    pub fn headers(&self) -> Vec<(HeaderName, HeaderValue)> {
+        vec![
+            (RATELIMIT_LIMIT, HeaderValue::from(self.limit_cents)),
+            (RATELIMIT_REMAINING, HeaderValue::from(self.remaining_cents)),
+            (RATELIMIT_RESET, HeaderValue::from(self.resets_in_secs)),
+        ]
+    }
+}
+
+impl TransferLimitUsage {
+    /// The limit with the least quota remaining, which a transfer hits first
+    #[must_use]
+    pub fn tightest(&self) -> Option<&LimitUsage> {
+        self.limits.iter().min_by_key(|usage| usage.remaining_cents)
+    }
+
+    /// `RateLimit-*` response headers reporting the [`tightest`](Self::tightest)
+    /// limit; none when no limit is configured
+    #[must_use]
+    pub fn headers(&self) -> Vec<(HeaderName, HeaderValue)> {
+        self.tightest().map(LimitUsage::headers).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use chrono::TimeZone;
+
+    const LIMITS: TransferLimits = TransferLimits { daily_cents: Some(1_000), monthly_cents: Some(5_000) };
+
+    fn at() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 3, 1, 12, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn test_check_reports_first_exceeded_window() {
+        let totals = TransferTotals { daily_cents: 600, monthly_cents: 4_800, ..TransferTotals::default() };
+
+        assert!(LIMITS.check(1, totals, 200, at()).is_ok());
+        assert!(matches!(
+            LIMITS.check(1, totals, 300, at()),
+            Err(BankError::LimitExceeded(LimitUsage { window: LimitWindow::Monthly, remaining_cents: 200, .. }))
+        ));
+        assert!(matches!(
+            LIMITS.check(1, totals, 500, at()),
+            Err(BankError::LimitExceeded(LimitUsage { window: LimitWindow::Daily, remaining_cents: 400, .. }))
+        ));
+    }
+
+    #[test]
+    fn test_unlimited_windows_are_omitted() {
+        let limits = TransferLimits { daily_cents: None, monthly_cents: Some(5_000) };
+        let usage = limits.usage(7, TransferTotals { daily_cents: 100, monthly_cents: 6_000, ..TransferTotals::default() }, at());
+
+        assert_eq!(usage.limits.len(), 1);
+        assert_eq!(usage.limits[0].remaining_cents, 0);
+        assert!(TransferLimits::default().check(7, TransferTotals::default(), i64::MAX, at()).is_ok_and(|usage| usage.headers().is_empty()));
+    }
+
+    #[test]
+    fn test_headers_report_the_tightest_window_after_the_transfer() {
+        let totals = TransferTotals {
+            daily_cents: 300,
+            monthly_cents: 4_500,
+            daily_oldest_at: None,
+            monthly_oldest_at: Some(at() - Duration::days(29) - Duration::hours(12) - Duration::milliseconds(500)),
+        };
+
+        let usage = LIMITS.check(1, totals, 200, at()).expect("The transfer should fit the limits");
+        let headers = usage.headers();
+
+        assert_eq!(usage.limits[0].resets_in_secs, 86_400, "The transfer itself is the oldest of the day");
+        assert_eq!(
+            headers,
+            vec![
+                (RATELIMIT_LIMIT, HeaderValue::from(5_000)),
+                (RATELIMIT_REMAINING, HeaderValue::from(300)),
+                (RATELIMIT_RESET, HeaderValue::from(43_200)),
+            ],
+            "The monthly window has less left; its oldest transfer leaves it in 12 hours less half a second, rounded up"
+        );
+    }
+}
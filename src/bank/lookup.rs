@@ -0,0 +1,56 @@
+//! User lookup - the slice of the user module the bank depends on
+//!
+//! `BankService` only needs a handful of user operations. Depending on this
+//! narrow trait instead of the full user ports keeps the bank's business
+//! rules testable against an in-memory fake. Every implementation of both
+//! user ports (such as `UserService`) is a `UserLookup`.
+
+use std::sync::Arc;
+
+use futures_util::future::BoxFuture;
+
+use crate::user::domain::{PaginatedUsersResponse, PaginationParams, UserError};
+use crate::user::{UpdateUser, User, UserReadPort, UserWritePort};
+
+/// User operations required by the bank module
+pub trait UserLookup: Send + Sync {
+    /// Checks if a user exists
+    fn user_exists(&self, id: i32) -> BoxFuture<'_, Result<bool, UserError>>;
+
+    /// Retrieves a specific user by ID
+    fn get_user_by_id(&self, id: i32) -> BoxFuture<'_, Result<User, UserError>>;
+
+    /// Gets a user's name by ID
+    fn get_user_name(&self, id: i32) -> BoxFuture<'_, Result<String, UserError>>;
+
+    /// Updates an existing user with validation
+    fn update_user(&self, id: i32, user_data: UpdateUser) -> BoxFuture<'_, Result<User, UserError>>;
+
+    /// Retrieves users with pagination
+    fn get_users_paginated(&self, params: PaginationParams) -> BoxFuture<'_, Result<PaginatedUsersResponse, UserError>>;
+}
+
+impl<T: UserReadPort + UserWritePort> UserLookup for T {
+    fn user_exists(&self, id: i32) -> BoxFuture<'_, Result<bool, UserError>> {
+        UserReadPort::user_exists(self, id)
+    }
+
+    fn get_user_by_id(&self, id: i32) -> BoxFuture<'_, Result<User, UserError>> {
+        UserReadPort::get_user_by_id(self, id)
+    }
+
+    fn get_user_name(&self, id: i32) -> BoxFuture<'_, Result<String, UserError>> {
+        UserReadPort::get_user_name(self, id)
+    }
+
+    fn update_user(&self, id: i32, user_data: UpdateUser) -> BoxFuture<'_, Result<User, UserError>> {
+        UserWritePort::update_user(self, id, user_data)
+    }
+
+    fn get_users_paginated(&self, params: PaginationParams) -> BoxFuture<'_, Result<PaginatedUsersResponse, UserError>> {
+        UserReadPort::get_users_paginated(self, params)
+    }
+}
+
+/// User lookup shared between bank services
+pub type SharedUserLookup = Arc<dyn UserLookup>;
@@ -1,9 +1,37 @@
 //! Bank module
 //! 
-//! This module demonstrates how other modules can use `UserService`
-//! but cannot access `UserRepository` directly.
+//! Persisted accounts with withdrawals, transfers, compliance holds and
+//! interest accrual and payments to saved beneficiaries at other banks
+//! (`AccountService`, `InterestAccrualJob`), recorded in a
+//! double-entry ledger that `LedgerVerificationJob` checks, plus `BankService`, which demonstrates how other modules
+//! can use the user module through a narrow trait (`UserLookup`) but cannot
+//! access `UserRepository` directly.
 
+pub mod account_service;
+pub mod controller;
+pub mod domain;
+pub mod interest;
+pub mod limits;
+pub mod lookup;
+pub mod repository;
 pub mod service;
+pub mod validation;
+pub mod verification;
 
 // Public exports
-pub use service::{BankService, BankError};
\ No newline at end of file
+pub use account_service::AccountService;
+pub use domain::{
+    Account, AccountKind, AccountNumberCheck, AccountNumberFormat, BalanceDiscrepancy, BankError, Beneficiary,
+    CreateBeneficiary, ExternalTransfer, ExternalTransferRequest, FreezeAccount, InterestAccrual, LedgerVerification,
+    LimitExceededResponse, LimitUsage, LimitWindow, OpenAccount, Transfer, TransferLimitUsage, TransferRequest,
+    UserWithAccounts, UsersWithAccountsPage, ValidateAccountNumber, Withdrawal,
+};
+pub use interest::InterestAccrualJob;
+pub use limits::TransferLimits;
+pub use lookup::{SharedUserLookup, UserLookup};
+pub use service::BankService;
+pub use validation::AccountNumberScheme;
+pub use verification::LedgerVerificationJob;
+
+// Export controller for OpenAPI documentation
+pub use controller::*;
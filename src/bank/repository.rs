@@ -0,0 +1,567 @@
+//! Account repository - handles database operations
+//!
+//! This module is private to the bank module. All database access must go
+//! through `AccountService`. Balance changes lock the affected rows and run
+//! the service's policy check inside the same transaction, so a hold placed
+//! concurrently cannot be bypassed. Every balance change is recorded in the
+//! double-entry ledger in that same transaction, where transfers also sum
+//! the debited account's recent transfers for the limit check.
+
+use chrono::{DateTime, Utc};
+use serde_json::{Value, json};
+use sqlx::{PgExecutor, PgPool, Postgres, Transaction};
+use tracing::{error, info};
+
+use crate::audit::{self, AuditRecord};
+
+use super::domain::{Account, AccountKind, BalanceDiscrepancy, BankError, Beneficiary, LedgerVerification, LimitWindow, TransferLimitUsage};
+use super::limits::TransferTotals;
+
+/// Ledger book of customer accounts
+const CUSTOMER_BOOK: &str = "customer";
+/// Ledger book of the bank's cash, the counterpart of deposits and withdrawals
+const CASH_BOOK: &str = "cash";
+/// Ledger book of the bank's interest expense, the counterpart of interest credits
+const INTEREST_EXPENSE_BOOK: &str = "interest_expense";
+/// Ledger book of payments to other banks, the counterpart of external transfers
+const EXTERNAL_PAYMENTS_BOOK: &str = "external_payments";
+
+/// A ledger posting: customer account (if any), book and signed amount in cents
+type Posting = (Option<i32>, &'static str, i64);
+
+/// Outcome of crediting one batch of savings accounts with interest
+#[derive(Debug, Clone, Copy)]
+pub(super) struct InterestBatch {
+    /// Highest account ID in the batch; the next batch starts after it
+    pub(super) last_id: i32,
+    /// Accounts credited (accounts already credited for the period are skipped)
+    pub(super) credited: u64,
+    /// Total interest credited in cents
+    pub(super) total_cents: i64,
+}
+
+/// Logs a database error and converts it into a `BankError`
+fn database_error(context: &'static str) -> impl FnOnce(sqlx::Error) -> BankError {
+    move |e| {
+        error!(error = %e, "{context}");
+        BankError::DatabaseError(e.into())
+    }
+}
+
+/// Account repository for database operations
+#[derive(Clone)]
+pub(super) struct AccountRepository {
+    pool: PgPool,
+}
+
+impl AccountRepository {
+    /// Creates a new `AccountRepository` instance
+    pub(super) fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Opens an account for a user, recording the opening balance in the ledger
+    ///
+    /// `account_number` derives the account number from the new account's ID.
+    pub(super) async fn insert(
+        &self,
+        user_id: i32,
+        kind: AccountKind,
+        balance_cents: i64,
+        created_at: DateTime<Utc>,
+        account_number: impl FnOnce(i32) -> String + Send,
+    ) -> Result<Account, BankError> {
+        info!(user_id, ?kind, balance_cents, "Inserting account into database");
+
+        let mut tx = self.pool.begin().await.map_err(database_error("Failed to begin transaction for account opening"))?;
+        let id = sqlx::query_scalar!(
+            "INSERT INTO accounts (user_id, kind, balance_cents, created_at) VALUES ($1, $2, $3, $4) RETURNING id",
+            user_id,
+            kind as AccountKind,
+            balance_cents,
+            created_at
+        )
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(database_error("Failed to insert account into database"))?;
+        let account = sqlx::query_as!(
+            Account,
+            r#"UPDATE accounts SET account_number = $2 WHERE id = $1
+             RETURNING id, user_id, account_number, kind AS "kind: AccountKind", balance_cents, frozen, frozen_reason, frozen_at, created_at"#,
+            id,
+            account_number(id)
+        )
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(database_error("Failed to assign account number"))?;
+
+        if balance_cents > 0 {
+            let postings = [(Some(account.id), CUSTOMER_BOOK, balance_cents), (None, CASH_BOOK, -balance_cents)];
+            Self::record(&mut tx, "opening", None, created_at, &postings).await?;
+        }
+        tx.commit().await.map_err(database_error("Failed to commit account opening"))?;
+        Ok(account)
+    }
+
+    /// Finds an account by ID
+    pub(super) async fn find_by_id(&self, id: i32) -> Result<Option<Account>, BankError> {
+        info!(account_id = id, "Fetching account from database");
+
+        sqlx::query_as!(
+            Account,
+            r#"SELECT id, user_id, account_number, kind AS "kind: AccountKind", balance_cents, frozen, frozen_reason, frozen_at, created_at
+             FROM accounts WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(database_error("Failed to fetch account from database"))
+    }
+
+    /// Finds an account by its account number
+    pub(super) async fn find_by_account_number(&self, account_number: &str) -> Result<Option<Account>, BankError> {
+        info!(account_number, "Fetching account by number from database");
+
+        sqlx::query_as!(
+            Account,
+            r#"SELECT id, user_id, account_number, kind AS "kind: AccountKind", balance_cents, frozen, frozen_reason, frozen_at, created_at
+             FROM accounts WHERE account_number = $1"#,
+            account_number
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(database_error("Failed to fetch account by number from database"))
+    }
+
+    /// Finds the accounts of several users in one query, ordered by user and then by ID
+    ///
+    /// Expanding a page of users costs this single round trip however many
+    /// users the page holds; never call [`Self::find_by_id`] per user instead.
+    pub(super) async fn find_by_user_ids(&self, user_ids: &[i32]) -> Result<Vec<Account>, BankError> {
+        info!(users = user_ids.len(), "Fetching accounts of users from database");
+
+        sqlx::query_as!(
+            Account,
+            r#"SELECT id, user_id, account_number, kind AS "kind: AccountKind", balance_cents, frozen, frozen_reason, frozen_at, created_at
+             FROM accounts WHERE user_id = ANY($1) ORDER BY user_id, id"#,
+            user_ids
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(database_error("Failed to fetch accounts of users from database"))
+    }
+
+    /// Lists the beneficiaries of a user
+    pub(super) async fn find_beneficiaries(&self, user_id: i32) -> Result<Vec<Beneficiary>, BankError> {
+        info!(user_id, "Fetching beneficiaries from database");
+
+        sqlx::query_as!(
+            Beneficiary,
+            "SELECT id, user_id, name, account_number, created_at FROM beneficiaries WHERE user_id = $1 ORDER BY id",
+            user_id
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(database_error("Failed to fetch beneficiaries from database"))
+    }
+
+    /// Saves a beneficiary, returning `None` when the user already saved its account number
+    pub(super) async fn insert_beneficiary(
+        &self,
+        user_id: i32,
+        name: &str,
+        account_number: &str,
+        created_at: DateTime<Utc>,
+    ) -> Result<Option<Beneficiary>, BankError> {
+        info!(user_id, account_number, "Inserting beneficiary into database");
+
+        sqlx::query_as!(
+            Beneficiary,
+            "INSERT INTO beneficiaries (user_id, name, account_number, created_at) VALUES ($1, $2, $3, $4)
+             ON CONFLICT (user_id, account_number) DO NOTHING
+             RETURNING id, user_id, name, account_number, created_at",
+            user_id,
+            name,
+            account_number,
+            created_at
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(database_error("Failed to insert beneficiary into database"))
+    }
+
+    /// Deletes a beneficiary of a user, returning whether it existed
+    pub(super) async fn delete_beneficiary(&self, user_id: i32, id: i32) -> Result<bool, BankError> {
+        info!(user_id, beneficiary_id = id, "Deleting beneficiary from database");
+
+        let result = sqlx::query!("DELETE FROM beneficiaries WHERE user_id = $1 AND id = $2", user_id, id)
+            .execute(&self.pool)
+            .await
+            .map_err(database_error("Failed to delete beneficiary from database"))?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Counts all accounts
+    pub(super) async fn count(&self) -> Result<i64, BankError> {
+        info!("Counting accounts in database");
+
+        sqlx::query_scalar!(r#"SELECT COUNT(*) AS "count!" FROM accounts"#)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(database_error("Failed to count accounts in database"))
+    }
+
+    /// Places a hold on an account, returning `None` when it does not exist
+    pub(super) async fn freeze(&self, id: i32, reason: &str, frozen_at: DateTime<Utc>) -> Result<Option<Account>, BankError> {
+        info!(account_id = id, "Freezing account in database");
+
+        let mut tx = self.pool.begin().await.map_err(database_error("Failed to begin transaction for freeze"))?;
+        let account = sqlx::query_as!(
+            Account,
+            r#"UPDATE accounts SET frozen = TRUE, frozen_reason = $2, frozen_at = $3 WHERE id = $1
+             RETURNING id, user_id, account_number, kind AS "kind: AccountKind", balance_cents, frozen, frozen_reason, frozen_at, created_at"#,
+            id,
+            reason,
+            frozen_at
+        )
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(database_error("Failed to freeze account in database"))?;
+
+        if let Some(account) = &account {
+            Self::audit(&mut tx, account, audit::recorder::ACCOUNT_FROZEN, json!({ "reason": reason }), frozen_at).await?;
+        }
+        tx.commit().await.map_err(database_error("Failed to commit freeze"))?;
+        Ok(account)
+    }
+
+    /// Lifts the hold on an account, returning `None` when it does not exist
+    pub(super) async fn unfreeze(&self, id: i32, unfrozen_at: DateTime<Utc>) -> Result<Option<Account>, BankError> {
+        info!(account_id = id, "Unfreezing account in database");
+
+        let mut tx = self.pool.begin().await.map_err(database_error("Failed to begin transaction for unfreeze"))?;
+        let account = sqlx::query_as!(
+            Account,
+            r#"UPDATE accounts SET frozen = FALSE, frozen_reason = NULL, frozen_at = NULL WHERE id = $1
+             RETURNING id, user_id, account_number, kind AS "kind: AccountKind", balance_cents, frozen, frozen_reason, frozen_at, created_at"#,
+            id
+        )
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(database_error("Failed to unfreeze account in database"))?;
+
+        if let Some(account) = &account {
+            Self::audit(&mut tx, account, audit::recorder::ACCOUNT_UNFROZEN, json!({}), unfrozen_at).await?;
+        }
+        tx.commit().await.map_err(database_error("Failed to commit unfreeze"))?;
+        Ok(account)
+    }
+
+    /// Debits an account once `check` accepts its locked state
+    pub(super) async fn withdraw(
+        &self,
+        id: i32,
+        amount_cents: i64,
+        at: DateTime<Utc>,
+        check: impl FnOnce(&Account) -> Result<(), BankError> + Send,
+    ) -> Result<Account, BankError> {
+        info!(account_id = id, amount_cents, "Withdrawing from account in database");
+
+        let mut tx = self.pool.begin().await.map_err(database_error("Failed to begin transaction for withdrawal"))?;
+        let account = Self::lock(&mut tx, &[id]).await?.pop().ok_or(BankError::AccountNotFound)?;
+        check(&account)?;
+
+        let account = Self::add_to_balance(&mut tx, id, -amount_cents).await?;
+        let postings = [(Some(id), CUSTOMER_BOOK, -amount_cents), (None, CASH_BOOK, amount_cents)];
+        Self::record(&mut tx, "withdrawal", None, at, &postings).await?;
+        tx.commit().await.map_err(database_error("Failed to commit withdrawal"))?;
+        Ok(account)
+    }
+
+    /// Moves money between two accounts once `check` accepts their locked states
+    ///
+    /// `check` receives the debited account first, then the money it
+    /// already sent by transfer within the limit windows, and returns the
+    /// debited account's limit usage once the transfer is made.
+    pub(super) async fn transfer(
+        &self,
+        from_id: i32,
+        to_id: i32,
+        amount_cents: i64,
+        at: DateTime<Utc>,
+        check: impl FnOnce(&Account, &Account, TransferTotals) -> Result<TransferLimitUsage, BankError> + Send,
+    ) -> Result<(Account, Account, TransferLimitUsage), BankError> {
+        info!(from_account_id = from_id, to_account_id = to_id, amount_cents, "Transferring between accounts in database");
+
+        let mut tx = self.pool.begin().await.map_err(database_error("Failed to begin transaction for transfer"))?;
+        let accounts = Self::lock(&mut tx, &[from_id, to_id]).await?;
+        let find = |id: i32| accounts.iter().find(|account| account.id == id).ok_or(BankError::AccountNotFound);
+        let totals = Self::transfer_totals(&mut *tx, from_id, at).await?;
+        let usage = check(find(from_id)?, find(to_id)?, totals)?;
+
+        let from = Self::add_to_balance(&mut tx, from_id, -amount_cents).await?;
+        let to = Self::add_to_balance(&mut tx, to_id, amount_cents).await?;
+        let postings = [(Some(from_id), CUSTOMER_BOOK, -amount_cents), (Some(to_id), CUSTOMER_BOOK, amount_cents)];
+        Self::record(&mut tx, "transfer", None, at, &postings).await?;
+        tx.commit().await.map_err(database_error("Failed to commit transfer"))?;
+        Ok((from, to, usage))
+    }
+
+    /// Pays an account at another bank once `check` accepts the locked debited account
+    ///
+    /// `check` also receives the account holder's beneficiary with
+    /// `account_number`, if any, and the money the account already sent by
+    /// transfer within the limit windows; it returns the beneficiary to pay
+    /// and the account's limit usage once the transfer is made. The
+    /// beneficiary row stays locked until commit, so it cannot be deleted
+    /// mid-transfer.
+    pub(super) async fn external_transfer(
+        &self,
+        from_id: i32,
+        account_number: &str,
+        amount_cents: i64,
+        at: DateTime<Utc>,
+        check: impl FnOnce(&Account, Option<Beneficiary>, TransferTotals) -> Result<(Beneficiary, TransferLimitUsage), BankError> + Send,
+    ) -> Result<(Account, Beneficiary, TransferLimitUsage), BankError> {
+        info!(from_account_id = from_id, account_number, amount_cents, "Transferring to another bank in database");
+
+        let mut tx = self.pool.begin().await.map_err(database_error("Failed to begin transaction for external transfer"))?;
+        let account = Self::lock(&mut tx, &[from_id]).await?.pop().ok_or(BankError::AccountNotFound)?;
+        let beneficiary = sqlx::query_as!(
+            Beneficiary,
+            "SELECT id, user_id, name, account_number, created_at FROM beneficiaries
+             WHERE user_id = $1 AND account_number = $2 FOR SHARE",
+            account.user_id,
+            account_number
+        )
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(database_error("Failed to fetch beneficiary"))?;
+        let totals = Self::transfer_totals(&mut *tx, from_id, at).await?;
+        let (beneficiary, usage) = check(&account, beneficiary, totals)?;
+
+        let account = Self::add_to_balance(&mut tx, from_id, -amount_cents).await?;
+        let postings = [(Some(from_id), CUSTOMER_BOOK, -amount_cents), (None, EXTERNAL_PAYMENTS_BOOK, amount_cents)];
+        Self::record(&mut tx, "external_transfer", None, at, &postings).await?;
+        tx.commit().await.map_err(database_error("Failed to commit external transfer"))?;
+        Ok((account, beneficiary, usage))
+    }
+
+    /// Sums what an account sent by transfer (internal or external) within each limit window ending at `at`,
+    /// with the time of the oldest transfer counted in each
+    async fn transfer_totals(
+        executor: impl PgExecutor<'_>,
+        account_id: i32,
+        at: DateTime<Utc>,
+    ) -> Result<TransferTotals, BankError> {
+        let totals = sqlx::query!(
+            r#"SELECT
+                 COALESCE(SUM(-e.amount_cents) FILTER (WHERE t.created_at > $2), 0)::BIGINT AS "daily_cents!",
+                 COALESCE(SUM(-e.amount_cents), 0)::BIGINT AS "monthly_cents!",
+                 MIN(t.created_at) FILTER (WHERE t.created_at > $2) AS daily_oldest_at,
+                 MIN(t.created_at) AS monthly_oldest_at
+             FROM ledger_entries e
+             JOIN ledger_transactions t ON t.id = e.transaction_id
+             WHERE e.account_id = $1 AND e.amount_cents < 0
+               AND t.kind IN ('transfer', 'external_transfer') AND t.created_at > $3"#,
+            account_id,
+            at - LimitWindow::Daily.duration(),
+            at - LimitWindow::Monthly.duration()
+        )
+        .fetch_one(executor)
+        .await
+        .map_err(database_error("Failed to sum recent transfers"))?;
+        Ok(TransferTotals {
+            daily_cents: totals.daily_cents,
+            monthly_cents: totals.monthly_cents,
+            daily_oldest_at: totals.daily_oldest_at,
+            monthly_oldest_at: totals.monthly_oldest_at,
+        })
+    }
+
+    /// Sums what an account sent by transfer within each limit window ending at `at`
+    pub(super) async fn find_transfer_totals(&self, account_id: i32, at: DateTime<Utc>) -> Result<TransferTotals, BankError> {
+        Self::transfer_totals(&self.pool, account_id, at).await
+    }
+
+    /// Credits interest to the next `limit` savings accounts after `after_id`, in one transaction
+    ///
+    /// `interest` computes each account's credit from its locked state. Each
+    /// credit is keyed by `period` and the account, so an account is credited
+    /// at most once per period however often this runs. Returns `None` once
+    /// no savings accounts are left.
+    pub(super) async fn accrue_interest_batch(
+        &self,
+        after_id: i32,
+        limit: u32,
+        period: &str,
+        at: DateTime<Utc>,
+        interest: impl Fn(&Account) -> i64 + Send,
+    ) -> Result<Option<InterestBatch>, BankError> {
+        info!(after_id, limit, period, "Accruing interest for a batch of savings accounts in database");
+
+        let mut tx = self.pool.begin().await.map_err(database_error("Failed to begin transaction for interest accrual"))?;
+        let accounts = sqlx::query_as!(
+            Account,
+            r#"SELECT id, user_id, account_number, kind AS "kind: AccountKind", balance_cents, frozen, frozen_reason, frozen_at, created_at
+             FROM accounts WHERE kind = 'savings' AND id > $1 ORDER BY id LIMIT $2 FOR UPDATE"#,
+            after_id,
+            i64::from(limit)
+        )
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(database_error("Failed to lock savings accounts"))?;
+
+        let Some(last_id) = accounts.last().map(|account| account.id) else {
+            return Ok(None);
+        };
+        let mut batch = InterestBatch { last_id, credited: 0, total_cents: 0 };
+        for account in &accounts {
+            let amount_cents = interest(account);
+            if amount_cents <= 0 {
+                continue;
+            }
+            let key = format!("interest:{period}:{}", account.id);
+            let postings = [(Some(account.id), CUSTOMER_BOOK, amount_cents), (None, INTEREST_EXPENSE_BOOK, -amount_cents)];
+            if Self::record(&mut tx, "interest", Some(&key), at, &postings).await? {
+                Self::add_to_balance(&mut tx, account.id, amount_cents).await?;
+                batch.credited += 1;
+                batch.total_cents += amount_cents;
+            }
+        }
+        tx.commit().await.map_err(database_error("Failed to commit interest accrual"))?;
+        Ok(Some(batch))
+    }
+
+    /// Recomputes balances from the ledger and compares them with the stored ones
+    ///
+    /// All checks read one consistent snapshot, so money moving during the
+    /// check cannot show up as a discrepancy.
+    pub(super) async fn verify_ledger(&self) -> Result<LedgerVerification, BankError> {
+        info!("Verifying ledger against stored balances in database");
+
+        let mut tx = self.pool.begin().await.map_err(database_error("Failed to begin ledger verification"))?;
+        sqlx::query!("SET TRANSACTION ISOLATION LEVEL REPEATABLE READ, READ ONLY")
+            .execute(&mut *tx)
+            .await
+            .map_err(database_error("Failed to set ledger verification isolation"))?;
+
+        let accounts_checked = sqlx::query_scalar!(r#"SELECT COUNT(*) AS "count!" FROM accounts"#)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(database_error("Failed to count accounts"))?;
+        let discrepancies = sqlx::query_as!(
+            BalanceDiscrepancy,
+            r#"SELECT a.id AS account_id, a.balance_cents AS stored_balance_cents,
+                    COALESCE(SUM(e.amount_cents), 0)::BIGINT AS "ledger_balance_cents!"
+             FROM accounts a
+             LEFT JOIN ledger_entries e ON e.account_id = a.id
+             GROUP BY a.id
+             HAVING a.balance_cents <> COALESCE(SUM(e.amount_cents), 0)
+             ORDER BY a.id"#
+        )
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(database_error("Failed to compare ledger balances"))?;
+        let unbalanced_transactions = sqlx::query_scalar!(
+            "SELECT transaction_id FROM ledger_entries
+             GROUP BY transaction_id
+             HAVING SUM(amount_cents) <> 0
+             ORDER BY transaction_id"
+        )
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(database_error("Failed to find unbalanced ledger transactions"))?;
+        tx.commit().await.map_err(database_error("Failed to finish ledger verification"))?;
+
+        Ok(LedgerVerification {
+            consistent: discrepancies.is_empty() && unbalanced_transactions.is_empty(),
+            accounts_checked,
+            discrepancies,
+            unbalanced_transactions,
+        })
+    }
+
+    /// Records an audit event about `account` in its owner's audit log
+    async fn audit(
+        tx: &mut Transaction<'static, Postgres>,
+        account: &Account,
+        action: &str,
+        details: Value,
+        at: DateTime<Utc>,
+    ) -> Result<(), BankError> {
+        let event = AuditRecord { user_id: Some(account.user_id), account_id: Some(account.id), action, details };
+        audit::record(&mut **tx, event, at).await.map_err(database_error("Failed to record audit event"))
+    }
+
+    /// Records a ledger transaction and its postings, which must sum to zero
+    ///
+    /// Returns `false` without recording anything when `idempotency_key` was already used.
+    async fn record(
+        tx: &mut Transaction<'static, Postgres>,
+        kind: &str,
+        idempotency_key: Option<&str>,
+        at: DateTime<Utc>,
+        postings: &[Posting],
+    ) -> Result<bool, BankError> {
+        debug_assert_eq!(postings.iter().map(|(_, _, amount)| amount).sum::<i64>(), 0, "Unbalanced ledger transaction");
+
+        let transaction_id = sqlx::query_scalar!(
+            "INSERT INTO ledger_transactions (kind, idempotency_key, created_at) VALUES ($1, $2, $3)
+             ON CONFLICT (idempotency_key) DO NOTHING
+             RETURNING id",
+            kind,
+            idempotency_key,
+            at
+        )
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(database_error("Failed to insert ledger transaction"))?;
+        let Some(transaction_id) = transaction_id else {
+            return Ok(false);
+        };
+
+        let account_ids: Vec<Option<i32>> = postings.iter().map(|(account_id, _, _)| *account_id).collect();
+        let books: Vec<String> = postings.iter().map(|(_, book, _)| (*book).to_owned()).collect();
+        let amounts: Vec<i64> = postings.iter().map(|(_, _, amount)| *amount).collect();
+        sqlx::query!(
+            "INSERT INTO ledger_entries (transaction_id, account_id, book, amount_cents)
+             SELECT $1, * FROM UNNEST($2::int[], $3::text[], $4::bigint[])",
+            transaction_id,
+            &account_ids as &[Option<i32>],
+            &books,
+            &amounts
+        )
+        .execute(&mut **tx)
+        .await
+        .map_err(database_error("Failed to insert ledger entries"))?;
+        Ok(true)
+    }
+
+    /// Locks the given accounts in ID order, so concurrent transfers cannot deadlock
+    async fn lock(tx: &mut Transaction<'static, Postgres>, ids: &[i32]) -> Result<Vec<Account>, BankError> {
+        sqlx::query_as!(
+            Account,
+            r#"SELECT id, user_id, account_number, kind AS "kind: AccountKind", balance_cents, frozen, frozen_reason, frozen_at, created_at
+             FROM accounts WHERE id = ANY($1) ORDER BY id FOR UPDATE"#,
+            ids
+        )
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(database_error("Failed to lock accounts"))
+    }
+
+    /// Adds `delta_cents` (possibly negative) to an account's balance
+    async fn add_to_balance(tx: &mut Transaction<'static, Postgres>, id: i32, delta_cents: i64) -> Result<Account, BankError> {
+        sqlx::query_as!(
+            Account,
+            r#"UPDATE accounts SET balance_cents = balance_cents + $2 WHERE id = $1
+             RETURNING id, user_id, account_number, kind AS "kind: AccountKind", balance_cents, frozen, frozen_reason, frozen_at, created_at"#,
+            id,
+            delta_cents
+        )
+        .fetch_one(&mut **tx)
+        .await
+        .map_err(database_error("Failed to update account balance"))
+    }
+}
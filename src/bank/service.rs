@@ -1,31 +1,37 @@
 //! Bank service - demonstrates inter-module communication
 //! 
-//! This service shows how the bank module can use `UserService`
-//! but cannot directly access `UserRepository`.
+//! This service shows how the bank module can use the user module through
+//! the narrow [`UserLookup`] trait but cannot directly access `UserRepository`.
+
+use std::sync::Arc;
 
 use tracing::{info, warn};
 
-use crate::user::{UserService, User};
+use super::domain::BankError;
+use super::lookup::{SharedUserLookup, UserLookup};
+use crate::user::User;
 use crate::user::domain::UserError;
 
 /// Bank service that needs to interact with users
 #[derive(Clone)]
 pub struct BankService {
-    user_service: UserService,
+    users: SharedUserLookup,
 }
 
 impl BankService {
-    /// Creates a new `BankService` instance
-    #[must_use] pub fn new(user_service: UserService) -> Self {
-        Self { user_service }
+    /// Creates a new `BankService` instance on top of any user lookup (e.g. `UserService`)
+    #[must_use] pub fn new(users: impl UserLookup + 'static) -> Self {
+        Self {
+            users: Arc::new(users),
+        }
     }
 
     /// Creates a bank account for a user (requires user to exist)
     pub async fn create_account(&self, user_id: i32, initial_balance: f64) -> Result<String, BankError> {
         info!(user_id, initial_balance, "BankService: Creating account for user");
 
-        // We can use UserService to check if user exists
-        match self.user_service.user_exists(user_id).await {
+        // We can use the user lookup to check if user exists
+        match self.users.user_exists(user_id).await {
             Ok(true) => {
                 // User exists, create account
                 info!(user_id, "BankService: User exists, creating account");
@@ -46,8 +52,8 @@ impl BankService {
     pub async fn get_account_info(&self, user_id: i32) -> Result<AccountInfo, BankError> {
         info!(user_id, "BankService: Getting account info for user");
 
-        // We can get user details through UserService
-        match self.user_service.get_user_by_id(user_id).await {
+        // We can get user details through the user lookup
+        match self.users.get_user_by_id(user_id).await {
             Ok(user) => {
                 info!(user_id, user_name = %user.name, "BankService: Found user for account info");
                 Ok(AccountInfo {
@@ -69,17 +75,18 @@ impl BankService {
         }
     }
 
-    /// Updates account holder information (delegates to `UserService`)
+    /// Updates account holder information (delegates to the user lookup)
     pub async fn update_account_holder(&self, user_id: i32, new_name: Option<String>) -> Result<User, BankError> {
         info!(user_id, ?new_name, "BankService: Updating account holder information");
 
-        // We can use UserService to update user information
+        // We can use the user lookup to update user information
         let update_data = crate::user::UpdateUser {
             name: new_name,
             age: None,
+            metadata: None,
         };
 
-        match self.user_service.update_user(user_id, update_data).await {
+        match self.users.update_user(user_id, update_data).await {
             Ok(user) => {
                 info!(user_id, "BankService: Account holder information updated");
                 Ok(user)
@@ -99,7 +106,7 @@ impl BankService {
     pub async fn get_account_holder_name(&self, user_id: i32) -> Result<String, BankError> {
         info!(user_id, "BankService: Getting account holder name");
 
-        match self.user_service.get_user_name(user_id).await {
+        match self.users.get_user_name(user_id).await {
             Ok(name) => {
                 info!(user_id, user_name = %name, "BankService: Got account holder name");
                 Ok(name)
@@ -131,19 +138,141 @@ pub struct AccountInfo {
     pub account_status: String,
 }
 
-/// Bank-specific errors
-#[derive(Debug, thiserror::Error)]
-pub enum BankError {
-    /// User was not found in the system
-    #[error("User not found")]
-    UserNotFound,
-    /// Error from the underlying user service
-    #[error("User service error: {0}")]
-    UserServiceError(UserError),
-    /// Account has insufficient funds for the operation
-    #[error("Insufficient funds")]
-    InsufficientFunds,
-    /// Bank account was not found
-    #[error("Account not found")]
-    AccountNotFound,
-}
\ No newline at end of file
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    use chrono::Utc;
+    use futures_util::future::BoxFuture;
+
+    use super::*;
+    use crate::user::UpdateUser;
+    use crate::user::domain::{PaginatedUsersResponse, PaginationParams, UserStatus};
+    use crate::db::{DbError, DbErrorKind};
+
+    /// In-memory user lookup, optionally failing every call
+    #[derive(Default)]
+    struct FakeUsers {
+        users: Mutex<HashMap<i32, User>>,
+        broken: bool,
+    }
+
+    impl FakeUsers {
+        fn with_user(id: i32, name: &str) -> Self {
+            let user = User {
+                id,
+                name: name.to_owned(),
+                age: 30,
+                created_at: Utc::now(),
+                updated_at: Utc::now(),
+                status: UserStatus::Active,
+                metadata: None,
+                warnings: Vec::new(),
+                links: None,
+            };
+            Self {
+                users: Mutex::new(HashMap::from([(id, user)])),
+                broken: false,
+            }
+        }
+
+        fn broken() -> Self {
+            Self {
+                broken: true,
+                ..Self::default()
+            }
+        }
+
+        fn find(&self, id: i32) -> Result<User, UserError> {
+            if self.broken {
+                return Err(UserError::DatabaseError(DbError::new(DbErrorKind::ServiceUnavailable, "connection refused")));
+            }
+            let users = self.users.lock().map_err(|e| UserError::DatabaseError(DbError::other(e.to_string())))?;
+            users.get(&id).cloned().ok_or(UserError::NotFound)
+        }
+    }
+
+    impl UserLookup for FakeUsers {
+        fn user_exists(&self, id: i32) -> BoxFuture<'_, Result<bool, UserError>> {
+            let exists = match self.find(id) {
+                Ok(_) => Ok(true),
+                Err(UserError::NotFound) => Ok(false),
+                Err(e) => Err(e),
+            };
+            Box::pin(async move { exists })
+        }
+
+        fn get_user_by_id(&self, id: i32) -> BoxFuture<'_, Result<User, UserError>> {
+            let user = self.find(id);
+            Box::pin(async move { user })
+        }
+
+        fn get_user_name(&self, id: i32) -> BoxFuture<'_, Result<String, UserError>> {
+            let name = self.find(id).map(|user| user.name);
+            Box::pin(async move { name })
+        }
+
+        fn update_user(&self, id: i32, user_data: UpdateUser) -> BoxFuture<'_, Result<User, UserError>> {
+            let updated = self.find(id).map(|mut user| {
+                user.name = user_data.name.unwrap_or(user.name);
+                user.age = user_data.age.unwrap_or(user.age);
+                self.users.lock().expect("Fake users lock poisoned").insert(id, user.clone());
+                user
+            });
+            Box::pin(async move { updated })
+        }
+
+        fn get_users_paginated(&self, _params: PaginationParams) -> BoxFuture<'_, Result<PaginatedUsersResponse, UserError>> {
+            let mut users: Vec<User> = self.users.lock().expect("Fake users lock poisoned").values().cloned().collect();
+            users.sort_by_key(|user| user.id);
+            let page = PaginatedUsersResponse {
+                count: users.len(),
+                users,
+                next_token: None,
+                prev_token: None,
+                has_more: false,
+                estimated_total: None,
+                links: None,
+            };
+            Box::pin(async move { Ok(page) })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_create_account_requires_existing_user() {
+        let bank = BankService::new(FakeUsers::with_user(1, "Ann Lee"));
+
+        let account = bank.create_account(1, 250.0).await.expect("Account should be created");
+        assert_eq!(account, "Account created for user 1 with balance $250.00");
+        assert!(matches!(bank.create_account(2, 250.0).await, Err(BankError::UserNotFound)));
+    }
+
+    #[tokio::test]
+    async fn test_get_account_info_combines_user_and_account() {
+        let bank = BankService::new(FakeUsers::with_user(1, "Ann Lee"));
+
+        let info = bank.get_account_info(1).await.expect("Account info should be found");
+        assert_eq!((info.user_id, info.user_name.as_str(), info.user_age), (1, "Ann Lee", 30));
+        assert_eq!(info.account_status, "Active");
+        assert!(matches!(bank.get_account_info(2).await, Err(BankError::UserNotFound)));
+    }
+
+    #[tokio::test]
+    async fn test_update_account_holder_renames_user() {
+        let bank = BankService::new(FakeUsers::with_user(1, "Ann Lee"));
+
+        let user = bank.update_account_holder(1, Some("Ann Ray".to_owned())).await.expect("Holder should be updated");
+        assert_eq!(user.name, "Ann Ray");
+        assert_eq!(bank.get_account_holder_name(1).await.expect("Holder should exist"), "Ann Ray");
+        assert!(matches!(bank.update_account_holder(2, None).await, Err(BankError::UserNotFound)));
+    }
+
+    #[tokio::test]
+    async fn test_user_lookup_failures_surface_as_user_service_errors() {
+        let bank = BankService::new(FakeUsers::broken());
+
+        assert!(matches!(bank.create_account(1, 10.0).await, Err(BankError::UserServiceError(_))));
+        assert!(matches!(bank.get_account_holder_name(1).await, Err(BankError::UserServiceError(_))));
+    }
+}
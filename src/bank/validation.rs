@@ -0,0 +1,333 @@
+//! Bank validation logic
+//!
+//! Besides amount and hold rules, this module generates and validates account
+//! numbers. Two formats are supported: IBANs (ISO 13616, mod-97 check digits)
+//! and plain digit strings ending in a Luhn check digit.
+
+use crate::error_codes::ErrorCode;
+use crate::config::BankConfig;
+use crate::user::domain::ValidationError;
+use crate::user::validation::common::{field_error, ValidationResult};
+
+use super::domain::AccountNumberFormat;
+
+/// Maximum length of a hold reason
+pub const MAX_REASON_LENGTH: usize = 500;
+/// Maximum length of a beneficiary name
+pub const MAX_BENEFICIARY_NAME_LENGTH: usize = 100;
+
+/// Digits of the account sequence number embedded in generated account numbers
+const SEQUENCE_DIGITS: usize = 10;
+
+/// IBAN lengths of common countries; other countries are checked against the generic 15-34 range
+const IBAN_LENGTHS: &[(&str, usize)] = &[
+    ("AT", 20), ("BE", 16), ("CH", 21), ("DE", 22), ("DK", 18), ("ES", 24), ("FI", 18), ("FR", 27),
+    ("GB", 22), ("IE", 22), ("IT", 27), ("LU", 20), ("NL", 18), ("NO", 15), ("PL", 28), ("PT", 25), ("SE", 24),
+];
+
+/// How account numbers are generated for new accounts
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AccountNumberScheme {
+    /// `prefix`, the zero-padded account sequence number and a Luhn check digit
+    Luhn {
+        /// Leading digits identifying the bank
+        prefix: String,
+    },
+    /// IBAN of `country_code` whose BBAN is `bank_code` followed by the zero-padded sequence number
+    Iban {
+        /// ISO 3166-1 alpha-2 country code
+        country_code: String,
+        /// National bank code
+        bank_code: String,
+    },
+}
+
+impl Default for AccountNumberScheme {
+    fn default() -> Self {
+        Self::Luhn { prefix: "40".to_owned() }
+    }
+}
+
+impl AccountNumberScheme {
+    /// Scheme configured by `config`; unknown scheme names fall back to Luhn
+    #[must_use]
+    pub fn from_config(config: &BankConfig) -> Self {
+        if config.account_number_scheme.eq_ignore_ascii_case("iban") {
+            Self::Iban {
+                country_code: config.iban_country_code.to_ascii_uppercase(),
+                bank_code: config.bank_code.clone(),
+            }
+        } else {
+            Self::Luhn { prefix: config.account_number_prefix.clone() }
+        }
+    }
+
+    /// Account number for the account with sequence number `sequence`
+    ///
+    /// Numbers are unique as long as sequence numbers are.
+    #[must_use]
+    pub fn generate(&self, sequence: u32) -> String {
+        match self {
+            Self::Luhn { prefix } => {
+                let payload = format!("{prefix}{sequence:0SEQUENCE_DIGITS$}");
+                let check_digit = luhn_check_digit(&payload);
+                format!("{payload}{check_digit}")
+            }
+            Self::Iban { country_code, bank_code } => {
+                let country_code = country_code.to_ascii_uppercase();
+                let bban = format!("{}{sequence:0width$}", bank_code.to_ascii_uppercase(), width = SEQUENCE_DIGITS);
+                let check_digits = 98 - iban_remainder(&format!("{bban}{country_code}00"));
+                format!("{country_code}{check_digits:02}{bban}")
+            }
+        }
+    }
+}
+
+/// Luhn check digit completing `digits`; non-digit characters are ignored
+#[must_use]
+pub fn luhn_check_digit(digits: &str) -> u32 {
+    // Doubling starts at the rightmost payload digit, which sits left of the check digit
+    let sum: u32 = digits
+        .chars()
+        .rev()
+        .filter_map(|c| c.to_digit(10))
+        .enumerate()
+        .map(|(position, digit)| if position % 2 == 0 { luhn_double(digit) } else { digit })
+        .sum();
+    (10 - sum % 10) % 10
+}
+
+/// Doubles a digit as the Luhn algorithm does, folding two-digit results
+const fn luhn_double(digit: u32) -> u32 {
+    let doubled = digit * 2;
+    if doubled > 9 { doubled - 9 } else { doubled }
+}
+
+/// Whether `number` (digits only) ends in a correct Luhn check digit
+#[must_use]
+pub fn is_valid_luhn(number: &str) -> bool {
+    match number.len().checked_sub(1).map(|last| number.split_at(last)) {
+        Some((payload, check)) if !payload.is_empty() && number.chars().all(|c| c.is_ascii_digit()) => {
+            check.parse::<u32>().is_ok_and(|check| check == luhn_check_digit(payload))
+        }
+        _ => false,
+    }
+}
+
+/// Remainder modulo 97 of an alphanumeric string read as a number, letters counting as 10-35
+fn iban_remainder(value: &str) -> u32 {
+    value.chars().filter_map(|c| c.to_digit(36)).fold(0, |remainder, digit| {
+        if digit < 10 {
+            (remainder * 10 + digit) % 97
+        } else {
+            (remainder * 100 + digit) % 97
+        }
+    })
+}
+
+/// Whether `iban` (uppercase, without spaces) has a valid structure, length and check digits
+#[must_use]
+pub fn is_valid_iban(iban: &str) -> bool {
+    let bytes = iban.as_bytes();
+    let structure_ok = bytes.len() >= 4
+        && bytes[..2].iter().all(u8::is_ascii_uppercase)
+        && bytes[2..4].iter().all(u8::is_ascii_digit)
+        && bytes[4..].iter().all(|b| b.is_ascii_uppercase() || b.is_ascii_digit());
+    if !structure_ok {
+        return false;
+    }
+
+    let (country_code, rest) = iban.split_at(2);
+    let length_ok = IBAN_LENGTHS
+        .iter()
+        .find(|(country, _)| *country == country_code)
+        .map_or((15..=34).contains(&iban.len()), |(_, length)| iban.len() == *length);
+    length_ok && iban_remainder(&format!("{}{country_code}{}", &rest[2..], &rest[..2])) == 1
+}
+
+/// Normalizes an account number for validation and lookup (no spaces or dashes, uppercase)
+#[must_use]
+pub fn normalize_account_number(number: &str) -> String {
+    number
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != '-')
+        .map(|c| c.to_ascii_uppercase())
+        .collect()
+}
+
+/// Validates a normalized account number, returning its format
+///
+/// Numbers starting with a letter are checked as IBANs, all-digit numbers
+/// with the Luhn algorithm.
+pub fn validate_account_number(number: &str, field_name: &str) -> Result<AccountNumberFormat, Vec<ValidationError>> {
+    if number.is_empty() {
+        return Err(vec![field_error(field_name, ErrorCode::FieldEmpty, "Account number cannot be empty")]);
+    }
+
+    if number.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        if is_valid_iban(number) {
+            Ok(AccountNumberFormat::Iban)
+        } else {
+            Err(vec![field_error(field_name, ErrorCode::InvalidAccountNumber, "Invalid IBAN")])
+        }
+    } else if number.chars().all(|c| c.is_ascii_digit()) {
+        if number.len() < 2 {
+            Err(vec![field_error(field_name, ErrorCode::InvalidAccountNumber, "Account number is too short")])
+        } else if is_valid_luhn(number) {
+            Ok(AccountNumberFormat::Luhn)
+        } else {
+            Err(vec![field_error(field_name, ErrorCode::InvalidAccountNumber, "Invalid check digit")])
+        }
+    } else {
+        Err(vec![field_error(
+            field_name,
+            ErrorCode::InvalidAccountNumber,
+            "Account number must be an IBAN or consist of digits only",
+        )])
+    }
+}
+
+/// Validates a money amount in cents, which must be positive
+pub fn validate_amount(amount_cents: i64, field_name: &str) -> ValidationResult {
+    if amount_cents > 0 {
+        Ok(())
+    } else {
+        Err(vec![field_error(field_name, ErrorCode::InvalidAmount, "Amount must be greater than zero")])
+    }
+}
+
+/// Validates an opening balance in cents, which cannot be negative
+pub fn validate_balance(balance_cents: i64, field_name: &str) -> ValidationResult {
+    if balance_cents >= 0 {
+        Ok(())
+    } else {
+        Err(vec![field_error(field_name, ErrorCode::InvalidAmount, "Balance cannot be negative")])
+    }
+}
+
+/// Validates that a transfer moves money between two different accounts
+pub fn validate_transfer_accounts(from_account_id: i32, to_account_id: i32, field_name: &str) -> ValidationResult {
+    if from_account_id == to_account_id {
+        Err(vec![field_error(field_name, ErrorCode::SameAccountTransfer, "Cannot transfer to the same account")])
+    } else {
+        Ok(())
+    }
+}
+
+/// Validates the reason given for a compliance hold
+pub fn validate_reason(reason: &str, field_name: &str) -> ValidationResult {
+    let mut errors = Vec::new();
+
+    if reason.trim().is_empty() {
+        errors.push(field_error(field_name, ErrorCode::FieldEmpty, "Reason cannot be empty"));
+    }
+
+    if reason.len() > MAX_REASON_LENGTH {
+        errors.push(field_error(field_name, ErrorCode::TooLong, format!("Reason cannot exceed {MAX_REASON_LENGTH} characters")));
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+/// Validates the account holder name of a beneficiary
+pub fn validate_beneficiary_name(name: &str, field_name: &str) -> ValidationResult {
+    let mut errors = Vec::new();
+
+    if name.trim().is_empty() {
+        errors.push(field_error(field_name, ErrorCode::FieldEmpty, "Name cannot be empty"));
+    }
+
+    if name.chars().count() > MAX_BENEFICIARY_NAME_LENGTH {
+        errors.push(field_error(
+            field_name,
+            ErrorCode::TooLong,
+            format!("Name cannot exceed {MAX_BENEFICIARY_NAME_LENGTH} characters"),
+        ));
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_amount() {
+        assert!(validate_amount(1, "amount_cents").is_ok());
+        assert!(validate_amount(0, "amount_cents").is_err());
+        assert!(validate_amount(-5, "amount_cents").is_err());
+    }
+
+    #[test]
+    fn test_validate_balance() {
+        assert!(validate_balance(0, "initial_balance_cents").is_ok());
+        assert!(validate_balance(-1, "initial_balance_cents").is_err());
+    }
+
+    #[test]
+    fn test_validate_transfer_accounts() {
+        assert!(validate_transfer_accounts(1, 2, "to_account_id").is_ok());
+        assert!(validate_transfer_accounts(3, 3, "to_account_id").is_err());
+    }
+
+    #[test]
+    fn test_luhn() {
+        assert_eq!(luhn_check_digit("7992739871"), 3);
+        assert!(is_valid_luhn("79927398713"));
+        assert!(!is_valid_luhn("79927398710"));
+        assert!(!is_valid_luhn("7"));
+    }
+
+    #[test]
+    fn test_iban() {
+        assert!(is_valid_iban("DE89370400440532013000"));
+        assert!(is_valid_iban("GB29NWBK60161331926819"));
+        assert!(!is_valid_iban("DE88370400440532013000"), "Wrong check digits");
+        assert!(!is_valid_iban("DE8937040044053201300"), "Wrong length for Germany");
+        assert!(!is_valid_iban("de89370400440532013000"), "Not normalized");
+    }
+
+    #[test]
+    fn test_generated_account_numbers_validate() {
+        let luhn = AccountNumberScheme::default().generate(42);
+        assert_eq!(luhn, "4000000000428");
+        assert!(matches!(validate_account_number(&luhn, "account_number"), Ok(AccountNumberFormat::Luhn)));
+
+        let iban = AccountNumberScheme::Iban { country_code: "de".to_owned(), bank_code: "37040044".to_owned() }
+            .generate(532_013_000);
+        assert_eq!(iban, "DE89370400440532013000");
+        assert!(matches!(validate_account_number(&iban, "account_number"), Ok(AccountNumberFormat::Iban)));
+    }
+
+    #[test]
+    fn test_validate_account_number() {
+        assert_eq!(normalize_account_number("de89 3704-0044 0532 0130 00"), "DE89370400440532013000");
+        assert!(validate_account_number("", "account_number").is_err());
+        assert!(validate_account_number("79927398710", "account_number").is_err());
+        assert!(validate_account_number("12AB", "account_number").is_err());
+    }
+
+    #[test]
+    fn test_validate_reason() {
+        assert!(validate_reason("Sanctions screening match", "reason").is_ok());
+        assert!(validate_reason("  ", "reason").is_err());
+        assert!(validate_reason(&"a".repeat(501), "reason").is_err());
+    }
+
+    #[test]
+    fn test_validate_beneficiary_name() {
+        assert!(validate_beneficiary_name("Erika Mustermann", "name").is_ok());
+        assert!(validate_beneficiary_name("", "name").is_err());
+        assert!(validate_beneficiary_name(&"é".repeat(100), "name").is_ok());
+        assert!(validate_beneficiary_name(&"a".repeat(101), "name").is_err());
+    }
+}
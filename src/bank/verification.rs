@@ -0,0 +1,48 @@
+//! Ledger verification job
+//!
+//! Periodically runs [`AccountService::verify_ledger`] and reports the
+//! outcome as a structured event on the `metrics` log target
+//! (`ledger_accounts_checked`, `ledger_discrepancies`,
+//! `ledger_unbalanced_transactions`), so log-based alerting can fire on any
+//! non-zero discrepancy count.
+
+use futures_util::future::BoxFuture;
+use tracing::info;
+
+use crate::jobs::{Job, JobError};
+
+use super::AccountService;
+
+/// Periodic job checking the double-entry ledger invariants
+#[derive(Clone)]
+pub struct LedgerVerificationJob {
+    accounts: AccountService,
+}
+
+impl LedgerVerificationJob {
+    /// Verifies the ledger kept by `accounts`
+    #[must_use]
+    pub fn new(accounts: AccountService) -> Self {
+        Self { accounts }
+    }
+}
+
+impl Job for LedgerVerificationJob {
+    fn name(&self) -> &'static str {
+        "ledger-verification"
+    }
+
+    fn run(&self) -> BoxFuture<'_, Result<(), JobError>> {
+        Box::pin(async move {
+            let verification = self.accounts.verify_ledger().await?;
+            info!(
+                target: "metrics",
+                ledger_accounts_checked = verification.accounts_checked,
+                ledger_discrepancies = verification.discrepancies.len(),
+                ledger_unbalanced_transactions = verification.unbalanced_transactions.len(),
+                "Ledger verification"
+            );
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,45 @@
+//! # TypeScript bindings generator
+//!
+//! Exports TypeScript definitions for the public API types into `bindings/`.
+//!
+//! Run with: `cargo run --bin gen-types --features typescript [output-dir]`
+
+use std::path::PathBuf;
+
+use rust_kickstart::error_codes::ErrorResponse;
+use rust_kickstart::user::domain::{
+    ApiResponse, CreateUser, PaginatedUsersResponse, UpdateUser, UpsertUser, User, ValidationErrorResponse,
+};
+use ts_rs::{Config, TS};
+
+/// Default directory the bindings are written to
+const DEFAULT_OUTPUT_DIR: &str = "bindings";
+
+#[allow(clippy::print_stdout, clippy::print_stderr)]
+fn main() {
+    let out_dir = std::env::args()
+        .nth(1)
+        .map_or_else(|| PathBuf::from(DEFAULT_OUTPUT_DIR), PathBuf::from);
+
+    // i64 values travel as plain JSON numbers, so don't emit `bigint`
+    let cfg = Config::from_env().with_out_dir(&out_dir).with_large_int("number");
+
+    // `export_all` also writes every type these depend on (UserStatus, ValidationError, ...)
+    let results = [
+        CreateUser::export_all(&cfg),
+        UpdateUser::export_all(&cfg),
+        UpsertUser::export_all(&cfg),
+        User::export_all(&cfg),
+        PaginatedUsersResponse::export_all(&cfg),
+        ValidationErrorResponse::export_all(&cfg),
+        ApiResponse::export_all(&cfg),
+        ErrorResponse::export_all(&cfg),
+    ];
+
+    if let Some(Err(e)) = results.into_iter().find(Result::is_err) {
+        eprintln!("❌ Failed to export TypeScript bindings: {e}");
+        std::process::exit(1);
+    }
+
+    println!("✅ TypeScript bindings written to {}", out_dir.display());
+}
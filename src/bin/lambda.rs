@@ -0,0 +1,14 @@
+//! AWS Lambda entry point
+//!
+//! Build with `cargo lambda build --release --features lambda --bin lambda`
+//! and deploy the resulting `bootstrap`; configuration comes from the
+//! function's environment variables, as for the server.
+
+#[tokio::main]
+#[allow(clippy::print_stderr)]
+async fn main() {
+    if let Err(e) = rust_kickstart::lambda::run().await {
+        eprintln!("Lambda function stopped: {e}");
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,76 @@
+//! Build information
+//!
+//! The build script embeds the git commit, build time, enabled Cargo features
+//! and compiler version, so `GET /version` tells operators exactly which
+//! build an environment runs.
+
+use axum::Json;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use utoipa::ToSchema;
+
+/// Identity of the running build
+#[derive(Serialize, ToSchema, Debug, Clone, PartialEq, Eq)]
+pub struct BuildInfo {
+    /// Crate version
+    pub version: String,
+    /// Commit the build was made from (`unknown` when built outside git without `GIT_SHA`)
+    pub git_sha: String,
+    /// When the build was made (`SOURCE_DATE_EPOCH` when set)
+    pub build_timestamp: DateTime<Utc>,
+    /// Enabled Cargo features, sorted
+    pub features: Vec<String>,
+    /// Compiler that made the build (`rustc --version`)
+    pub rustc_version: String,
+}
+
+impl BuildInfo {
+    /// Information about this build
+    #[must_use]
+    pub fn current() -> Self {
+        Self {
+            version: env!("CARGO_PKG_VERSION").to_owned(),
+            git_sha: env!("GIT_SHA").to_owned(),
+            build_timestamp: env!("BUILD_EPOCH")
+                .parse()
+                .ok()
+                .and_then(|epoch| DateTime::from_timestamp(epoch, 0))
+                .unwrap_or_default(),
+            features: env!("BUILD_FEATURES")
+                .split(',')
+                .filter(|feature| !feature.is_empty())
+                .map(ToOwned::to_owned)
+                .collect(),
+            rustc_version: env!("RUSTC_VERSION").to_owned(),
+        }
+    }
+}
+
+/// HTTP handler reporting the running build
+#[utoipa::path(
+    get,
+    path = "/version",
+    responses(
+        (status = 200, description = "Version, commit, build time, features and compiler of the running build", body = BuildInfo)
+    ),
+    tag = "health"
+)]
+pub async fn version_handler() -> Json<BuildInfo> {
+    Json(BuildInfo::current())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_current_build_is_described() {
+        let info = BuildInfo::current();
+
+        assert_eq!(info.version, env!("CARGO_PKG_VERSION"));
+        assert!(!info.git_sha.is_empty());
+        assert!(info.build_timestamp > DateTime::UNIX_EPOCH);
+        assert!(info.rustc_version.starts_with("rustc "));
+        assert!(info.features.windows(2).all(|pair| pair[0] <= pair[1]));
+    }
+}
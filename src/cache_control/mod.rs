@@ -0,0 +1,164 @@
+//! HTTP caching policies
+//!
+//! Whether a response may be cached is declared per group of routes in a
+//! [`CachePolicies`] registry rather than by each handler, so a CDN can sit in
+//! front of the service without caching anything it should not. The router
+//! applies [`cache_headers`] as a route layer: successful GET/HEAD responses
+//! of a cacheable route get `Cache-Control: public, max-age=N` and the
+//! registry's `Vary` headers, and every other response gets `no-store`.
+//! A `Cache-Control` set by the handler itself is left untouched.
+
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+use axum::{
+    extract::{MatchedPath, Request, State},
+    http::{HeaderName, HeaderValue, Method, StatusCode, header},
+    middleware::Next,
+    response::Response,
+};
+
+/// How responses of a route may be cached
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CachePolicy {
+    /// Never stored by any cache
+    #[default]
+    NoStore,
+    /// Stored by shared caches such as CDNs for `max_age`
+    Public {
+        /// How long a cached response stays fresh
+        max_age: Duration,
+    },
+}
+
+impl CachePolicy {
+    /// Public caching for `max_age`; a zero duration disables caching
+    #[must_use]
+    pub const fn public(max_age: Duration) -> Self {
+        if max_age.is_zero() { Self::NoStore } else { Self::Public { max_age } }
+    }
+
+    /// `Cache-Control` value of this policy
+    #[must_use]
+    pub fn header_value(&self) -> HeaderValue {
+        match self {
+            Self::NoStore => HeaderValue::from_static("no-store"),
+            Self::Public { max_age } => HeaderValue::from_str(&format!("public, max-age={}", max_age.as_secs()))
+                .unwrap_or_else(|_| HeaderValue::from_static("no-store")),
+        }
+    }
+}
+
+/// Registry of cache policies, keyed by route pattern (e.g. `/users/{id}`)
+///
+/// Routes not listed, and every method but GET and HEAD, are `no-store`.
+#[derive(Debug, Clone, Default)]
+pub struct CachePolicies {
+    routes: Arc<HashMap<String, CachePolicy>>,
+    vary: Vec<HeaderName>,
+}
+
+impl CachePolicies {
+    /// Creates a registry where nothing is cacheable
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Applies `policy` to the GET and HEAD responses of every route in `paths`
+    #[must_use]
+    pub fn group<I, S>(mut self, paths: I, policy: CachePolicy) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let routes = Arc::make_mut(&mut self.routes);
+        for path in paths {
+            routes.insert(path.into(), policy);
+        }
+        self
+    }
+
+    /// Adds a request header that cacheable responses vary on
+    #[must_use]
+    pub fn vary(mut self, header: HeaderName) -> Self {
+        self.vary.push(header);
+        self
+    }
+
+    /// Policy of a request to the route pattern `path`
+    #[must_use]
+    pub fn policy_for(&self, method: &Method, path: &str) -> CachePolicy {
+        if method == Method::GET || method == Method::HEAD {
+            self.routes.get(path).copied().unwrap_or_default()
+        } else {
+            CachePolicy::NoStore
+        }
+    }
+}
+
+/// Route-layer middleware adding `Cache-Control` and `Vary` headers to responses
+pub async fn cache_headers(State(policies): State<CachePolicies>, request: Request, next: Next) -> Response {
+    let policy = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|path| policies.policy_for(request.method(), path.as_str()))
+        .unwrap_or_default();
+
+    let mut response = next.run(request).await;
+    if response.headers().contains_key(header::CACHE_CONTROL) {
+        return response;
+    }
+
+    let status = response.status();
+    let policy = if status.is_success() || status == StatusCode::NOT_MODIFIED { policy } else { CachePolicy::NoStore };
+    let headers = response.headers_mut();
+    headers.insert(header::CACHE_CONTROL, policy.header_value());
+    if policy != CachePolicy::NoStore {
+        for name in &policies.vary {
+            headers.append(header::VARY, HeaderValue::from_name(name.clone()));
+        }
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{Router, body::Body, middleware, routing::get};
+    use tower::ServiceExt;
+
+    async fn headers(method: Method, uri: &str) -> (Option<HeaderValue>, Option<HeaderValue>) {
+        let policies = CachePolicies::new()
+            .group(["/docs", "/missing"], CachePolicy::public(Duration::from_secs(90)))
+            .group(["/off"], CachePolicy::public(Duration::ZERO))
+            .vary(header::ACCEPT);
+        let app = Router::new()
+            .route("/docs", get(|| async { "docs" }).post(|| async { "posted" }))
+            .route("/missing", get(|| async { StatusCode::NOT_FOUND }))
+            .route("/off", get(|| async { "off" }))
+            .route("/private", get(|| async { "private" }))
+            .route("/custom", get(|| async { ([(header::CACHE_CONTROL, "max-age=5")], "custom") }))
+            .route_layer(middleware::from_fn_with_state(policies, cache_headers));
+        let response = app
+            .oneshot(Request::builder().method(method).uri(uri).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        (
+            response.headers().get(header::CACHE_CONTROL).cloned(),
+            response.headers().get(header::VARY).cloned(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_only_successful_reads_of_cacheable_routes_are_public() {
+        assert_eq!(
+            headers(Method::GET, "/docs").await,
+            (Some(HeaderValue::from_static("public, max-age=90")), Some(HeaderValue::from_static("accept")))
+        );
+        assert_eq!(headers(Method::POST, "/docs").await, (Some(HeaderValue::from_static("no-store")), None));
+        assert_eq!(headers(Method::GET, "/missing").await, (Some(HeaderValue::from_static("no-store")), None));
+        assert_eq!(headers(Method::GET, "/off").await, (Some(HeaderValue::from_static("no-store")), None));
+        assert_eq!(headers(Method::GET, "/private").await, (Some(HeaderValue::from_static("no-store")), None));
+        assert_eq!(headers(Method::GET, "/custom").await, (Some(HeaderValue::from_static("max-age=5")), None));
+    }
+}
@@ -0,0 +1,47 @@
+//! Change log controller - HTTP handlers for the persisted domain events
+
+use axum::{
+    Json,
+    extract::Query,
+    http::StatusCode,
+    response::IntoResponse,
+};
+use tracing::error;
+
+use crate::error_codes::ErrorResponse;
+use crate::registry::Inject;
+
+use super::domain::{ChangeLogError, ChangeLogPage, ChangeLogParams};
+use super::service::ChangeLogService;
+
+/// HTTP handler for reading the change log
+///
+/// Entries are numbered in commit order and kept forever, so a consumer can
+/// rebuild its state from `after_seq=0` and then sync incrementally by
+/// passing the `last_seq` of each page, across restarts of either side.
+#[utoipa::path(
+    get,
+    path = "/changes",
+    tag = "changes",
+    params(
+        ("after_seq" = Option<i64>, Query, description = "Only return entries with a greater sequence number (default: 0, from the start)"),
+        ("limit" = Option<i32>, Query, description = "Number of entries to return (default `PAGINATION_DEFAULT_LIMIT`, capped at `PAGINATION_MAX_LIMIT`)")
+    ),
+    responses(
+        (status = 200, description = "Entries after the sequence number, oldest first", body = ChangeLogPage),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    )
+)]
+#[tracing::instrument(skip(change_log, params), fields(after_seq = params.after_seq, limit = params.limit))]
+pub async fn list_changes_handler(
+    Inject(change_log): Inject<ChangeLogService>,
+    Query(params): Query<ChangeLogParams>,
+) -> impl IntoResponse {
+    match change_log.entries_after(params).await {
+        Ok(page) => (StatusCode::OK, Json(page)).into_response(),
+        Err(ChangeLogError::DatabaseError(e)) => {
+            error!(error = %e, "Controller: Database error in change log read");
+            e.into_response()
+        }
+    }
+}
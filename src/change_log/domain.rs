@@ -0,0 +1,50 @@
+//! Change log domain models
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use utoipa::ToSchema;
+
+use crate::db::DbError;
+
+/// A domain event as stored in the change log
+#[derive(Serialize, ToSchema, Debug, Clone, PartialEq)]
+pub struct ChangeLogEntry {
+    /// Sequence number; entries are committed in increasing order
+    pub seq: i64,
+    /// Type of the event, such as `user_status_changed`
+    pub event_type: String,
+    /// The event, as delivered to webhooks
+    #[schema(value_type = Object)]
+    pub event: Value,
+    /// When the event was stored
+    pub recorded_at: DateTime<Utc>,
+}
+
+/// Query parameters for reading the change log
+#[derive(Deserialize, ToSchema, Debug, Clone, Default)]
+pub struct ChangeLogParams {
+    /// Only return entries with a greater sequence number (default: 0, from the start)
+    pub after_seq: Option<i64>,
+    /// Number of entries to return (default `PAGINATION_DEFAULT_LIMIT`, capped at `PAGINATION_MAX_LIMIT`)
+    pub limit: Option<i32>,
+}
+
+/// Entries of the change log after a sequence number, oldest first
+#[derive(Serialize, ToSchema, Debug, Clone)]
+pub struct ChangeLogPage {
+    /// Entries of this page
+    pub entries: Vec<ChangeLogEntry>,
+    /// Sequence number to pass as `after_seq` to continue
+    pub last_seq: i64,
+    /// Whether more entries are available right away
+    pub has_more: bool,
+}
+
+/// Domain errors for change log operations
+#[derive(Debug, thiserror::Error)]
+pub enum ChangeLogError {
+    /// Database operation failed
+    #[error("Database error: {0}")]
+    DatabaseError(DbError),
+}
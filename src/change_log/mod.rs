@@ -0,0 +1,21 @@
+//! Change log module
+//!
+//! Persisted domain events (`change_log`), numbered in commit order, and the
+//! endpoint consumers read them from (`GET /changes?after_seq=`). Unlike the
+//! in-process [`EventBus`](crate::events::EventBus), the log survives
+//! restarts and is never purged. Modules append events with [`record`]
+//! inside the transaction of the change, then publish them on the bus.
+
+pub mod controller;
+pub mod domain;
+pub mod recorder;
+pub mod repository;
+pub mod service;
+
+// Public exports
+pub use domain::{ChangeLogEntry, ChangeLogError, ChangeLogPage, ChangeLogParams};
+pub use recorder::record;
+pub use service::ChangeLogService;
+
+// Export controller for OpenAPI documentation
+pub use controller::*;
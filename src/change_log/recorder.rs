@@ -0,0 +1,32 @@
+//! Change log recorder
+//!
+//! Callers pass their open transaction, so a domain event is stored exactly
+//! when the change it describes is committed.
+
+use sqlx::PgConnection;
+use sqlx::types::Json;
+
+use crate::events::DomainEvent;
+
+/// Appends `event` to the change log, returning its sequence number
+///
+/// Takes a lock on `change_log` that is held until the transaction ends, so
+/// concurrent writers commit their entries in sequence order. Call it last
+/// in the transaction to keep the lock short.
+///
+/// # Errors
+///
+/// Returns the database error if the lock or the insert fails.
+pub async fn record(conn: &mut PgConnection, event: &DomainEvent) -> Result<i64, sqlx::Error> {
+    // SHARE ROW EXCLUSIVE conflicts with itself but not with readers
+    sqlx::query!("LOCK TABLE change_log IN SHARE ROW EXCLUSIVE MODE")
+        .execute(&mut *conn)
+        .await?;
+    sqlx::query_scalar!(
+        "INSERT INTO change_log (event_type, payload) VALUES ($1, $2) RETURNING seq",
+        event.event_type(),
+        Json(event) as Json<&DomainEvent>
+    )
+    .fetch_one(conn)
+    .await
+}
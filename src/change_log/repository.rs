@@ -0,0 +1,44 @@
+//! Change log repository - handles database operations
+//!
+//! This module is private to the change log module. All reads must go
+//! through `ChangeLogService`, writes through [`super::record`].
+
+use sqlx::PgPool;
+use tracing::{error, info};
+
+use super::domain::{ChangeLogEntry, ChangeLogError};
+
+/// Change log repository for database operations
+#[derive(Clone)]
+pub(super) struct ChangeLogRepository {
+    pool: PgPool,
+}
+
+impl ChangeLogRepository {
+    /// Creates a new `ChangeLogRepository` instance
+    pub(super) fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Retrieves up to `limit` entries after `after_seq`, oldest first
+    pub(super) async fn find_after(&self, after_seq: i64, limit: i64) -> Result<Vec<ChangeLogEntry>, ChangeLogError> {
+        info!(after_seq, limit, "Fetching change log entries from database");
+
+        sqlx::query_as!(
+            ChangeLogEntry,
+            r#"SELECT seq, event_type, payload AS event, recorded_at
+             FROM change_log
+             WHERE seq > $1
+             ORDER BY seq
+             LIMIT $2"#,
+            after_seq,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| {
+            error!(error = %e, after_seq, "Failed to fetch change log entries from database");
+            ChangeLogError::DatabaseError(e.into())
+        })
+    }
+}
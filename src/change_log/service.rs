@@ -0,0 +1,52 @@
+//! Change log service - business logic layer
+//!
+//! Pages through the persisted domain events for consumers that rebuild
+//! state from them or sync incrementally.
+
+use sqlx::PgPool;
+use tracing::info;
+
+use crate::pagination::PageLimits;
+
+use super::domain::{ChangeLogError, ChangeLogPage, ChangeLogParams};
+use super::repository::ChangeLogRepository;
+
+/// Change log service that reads persisted domain events
+#[derive(Clone)]
+pub struct ChangeLogService {
+    repository: ChangeLogRepository,
+    page_limits: PageLimits,
+}
+
+impl ChangeLogService {
+    /// Creates a new `ChangeLogService` instance
+    #[must_use] pub fn new(pool: PgPool) -> Self {
+        Self {
+            repository: ChangeLogRepository::new(pool),
+            page_limits: PageLimits::default(),
+        }
+    }
+
+    /// Uses `limits` for the page size instead of 200/200
+    #[must_use] pub fn with_page_limits(mut self, limits: PageLimits) -> Self {
+        self.page_limits = limits;
+        self
+    }
+
+    /// Returns the entries after `params.after_seq`, oldest first
+    pub async fn entries_after(&self, params: ChangeLogParams) -> Result<ChangeLogPage, ChangeLogError> {
+        let after_seq = params.after_seq.unwrap_or_default().max(0);
+        let limit = self.page_limits.resolve(params.limit);
+        info!(after_seq, limit, "ChangeLogService: Fetching change log entries");
+
+        // Fetch one extra entry to check if there are more
+        let mut entries = self.repository.find_after(after_seq, i64::from(limit) + 1).await?;
+        let has_more = entries.len() > usize::try_from(limit).unwrap_or_default();
+        if has_more {
+            entries.pop();
+        }
+
+        let last_seq = entries.last().map_or(after_seq, |entry| entry.seq);
+        Ok(ChangeLogPage { entries, last_seq, has_more })
+    }
+}
@@ -0,0 +1,185 @@
+//! Database circuit breaker
+//!
+//! A background monitor pings the database at a fixed interval. When a ping
+//! fails the [`DatabaseCircuit`] opens: API requests fail fast with 503
+//! instead of waiting on the pool and surfacing raw 500s, and `/health` and
+//! `/ready` report the database unhealthy so load balancers stop routing here.
+//! The pool re-establishes connections on its own; the first successful ping
+//! closes the circuit again.
+
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::Duration,
+};
+
+use axum::{
+    Json,
+    extract::{Request, State},
+    http::{HeaderValue, StatusCode, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use sqlx::PgPool;
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+use crate::db;
+use crate::error_codes::{ErrorCode, ErrorResponse};
+
+/// Message returned while the circuit is open
+pub const CIRCUIT_OPEN_MESSAGE: &str = "The database is unavailable; failing fast until it recovers";
+
+/// Paths served while the circuit is open: health probes, build info and documentation
+const ALWAYS_SERVED: [&str; 6] = ["/health", "/ready", "/live", "/version", "/api-docs", "/swagger-ui"];
+
+/// Shared open/closed state of the database connection
+///
+/// Clones share the same state. A new circuit is closed and stays closed
+/// unless a monitor is running.
+#[derive(Debug, Clone, Default)]
+pub struct DatabaseCircuit {
+    open: Arc<AtomicBool>,
+    retry_after: Duration,
+}
+
+impl DatabaseCircuit {
+    /// Creates a closed circuit advising clients to retry after `retry_after`
+    #[must_use]
+    pub fn new(retry_after: Duration) -> Self {
+        Self {
+            open: Arc::default(),
+            retry_after,
+        }
+    }
+
+    /// Whether requests are currently failing fast
+    #[must_use]
+    pub fn is_open(&self) -> bool {
+        self.open.load(Ordering::Relaxed)
+    }
+
+    /// Records the outcome of a connectivity probe, logging state changes
+    pub fn record(&self, reachable: bool) {
+        let was_open = self.open.swap(!reachable, Ordering::Relaxed);
+        match (was_open, reachable) {
+            (false, false) => warn!("Database unreachable; opening circuit"),
+            (true, true) => info!("Database reachable again; closing circuit"),
+            _ => {}
+        }
+    }
+
+    /// Pings the database every `interval` and updates the circuit
+    ///
+    /// Each ping is bounded by `interval`, so a hanging connection counts as
+    /// a failure. A ping waiting longer than `slow_acquire` for a connection
+    /// logs the pool's statistics. Abort the returned handle to stop
+    /// monitoring.
+    #[must_use]
+    pub fn monitor(&self, pool: PgPool, interval: Duration, slow_acquire: Option<Duration>) -> JoinHandle<()> {
+        let circuit = self.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                let ping = tokio::time::timeout(interval, async {
+                    let mut connection = db::acquire(&pool, slow_acquire).await?;
+                    sqlx::query("SELECT 1").execute(&mut *connection).await
+                })
+                .await;
+                circuit.record(matches!(ping, Ok(Ok(_))));
+            }
+        })
+    }
+}
+
+/// Whether `path` is the root or one of [`ALWAYS_SERVED`] or below it
+///
+/// Matches whole segments, so `/versions` or `/ready-for-review` still fail fast.
+fn always_served(path: &str) -> bool {
+    path == "/"
+        || ALWAYS_SERVED
+            .iter()
+            .filter_map(|served| path.strip_prefix(served))
+            .any(|rest| rest.is_empty() || rest.starts_with('/'))
+}
+
+/// Middleware answering 503 while the circuit is open, except for probes and docs
+pub async fn fail_fast(State(circuit): State<DatabaseCircuit>, request: Request, next: Next) -> Response {
+    if !circuit.is_open() || always_served(request.uri().path()) {
+        return next.run(request).await;
+    }
+
+    warn!(uri = %request.uri(), "Failing fast while the database circuit is open");
+    let mut response = (
+        StatusCode::SERVICE_UNAVAILABLE,
+        Json(ErrorResponse::new(ErrorCode::CircuitOpen, CIRCUIT_OPEN_MESSAGE.to_owned())),
+    )
+        .into_response();
+    response.headers_mut().insert(
+        header::RETRY_AFTER,
+        HeaderValue::from(circuit.retry_after.as_secs().max(1)),
+    );
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{Router, body::Body, middleware, routing::get};
+    use sqlx::postgres::PgPoolOptions;
+    use tower::ServiceExt;
+
+    async fn status(circuit: &DatabaseCircuit, uri: &str) -> StatusCode {
+        let app = Router::new()
+            .route("/users", get(|| async { "users" }))
+            .route("/ready", get(|| async { "ready" }))
+            .layer(middleware::from_fn_with_state(circuit.clone(), fail_fast));
+        app.oneshot(Request::get(uri).body(Body::empty()).unwrap())
+            .await
+            .unwrap()
+            .status()
+    }
+
+    #[tokio::test]
+    async fn test_open_circuit_fails_fast_except_for_probes() {
+        let circuit = DatabaseCircuit::new(Duration::from_secs(5));
+        assert_eq!(status(&circuit, "/users").await, StatusCode::OK);
+
+        circuit.record(false);
+        assert_eq!(status(&circuit, "/users").await, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(status(&circuit, "/ready").await, StatusCode::OK);
+
+        circuit.record(true);
+        assert_eq!(status(&circuit, "/users").await, StatusCode::OK);
+    }
+
+    #[test]
+    fn test_always_served_paths_match_whole_segments() {
+        assert!(always_served("/"));
+        assert!(always_served("/ready"));
+        assert!(always_served("/api-docs/openapi.json"));
+        assert!(always_served("/swagger-ui/index.html"));
+        assert!(!always_served("/readyz"));
+        assert!(!always_served("/versions"));
+        assert!(!always_served("/api-docs-internal/users"));
+        assert!(!always_served("/users/health"));
+    }
+
+    #[tokio::test]
+    async fn test_monitor_opens_circuit_when_database_is_unreachable() {
+        let pool = PgPoolOptions::new()
+            .acquire_timeout(Duration::from_millis(50))
+            .connect_lazy("postgres://circuit@127.0.0.1:1/circuit")
+            .unwrap();
+        let circuit = DatabaseCircuit::new(Duration::from_millis(100));
+
+        let monitor = circuit.monitor(pool, Duration::from_millis(100), None);
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        monitor.abort();
+
+        assert!(circuit.is_open());
+    }
+}
@@ -0,0 +1,298 @@
+//! Client address resolution
+//!
+//! [`resolve_client_ip`] works out once per request which address the
+//! request came from and stores it as a [`ClientIp`] extension, which
+//! handlers extract and middleware, audit logs and the access log span read,
+//! instead of each parsing headers on its own.
+//!
+//! The client is the connection's peer, unless the peer is a trusted proxy:
+//! then the forwarding chain, from `Forwarded` (RFC 7239 `for=`) or else
+//! `X-Forwarded-For`, is read from the right, skipping trusted proxies, and
+//! the first other hop is the client. Entries added by untrusted hops are
+//! never believed; an entry that is not an address (`unknown`, obfuscated
+//! identifiers, garbage) ends the walk at the proxy that added it.
+
+use std::{
+    convert::Infallible,
+    net::{IpAddr, SocketAddr},
+    str::FromStr,
+    sync::Arc,
+};
+
+use axum::{
+    RequestExt,
+    extract::{ConnectInfo, FromRequestParts, OptionalFromRequestParts, Request, State},
+    http::{HeaderMap, HeaderName, header::FORWARDED, request::Parts},
+    middleware::Next,
+    response::Response,
+};
+use tracing::{error, warn};
+
+use crate::error_codes::internal_error;
+
+/// Header listing the addresses a request was forwarded for, client first
+const X_FORWARDED_FOR: HeaderName = HeaderName::from_static("x-forwarded-for");
+
+/// A CIDR block, or a single address
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IpNet {
+    network: IpAddr,
+    prefix: u8,
+}
+
+/// A rule that is neither an address nor a CIDR block
+#[derive(Debug, thiserror::Error)]
+#[error("Invalid CIDR block `{0}`")]
+pub struct InvalidIpNet(String);
+
+impl FromStr for IpNet {
+    type Err = InvalidIpNet;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let invalid = || InvalidIpNet(value.to_owned());
+        let (address, prefix) = value.split_once('/').map_or((value, None), |(address, prefix)| (address, Some(prefix)));
+        let network = address.parse::<IpAddr>().map_err(|_e| invalid())?.to_canonical();
+        let max = if network.is_ipv4() { 32 } else { 128 };
+        let prefix = match prefix {
+            Some(prefix) => prefix.parse::<u8>().ok().filter(|prefix| *prefix <= max).ok_or_else(invalid)?,
+            None => max,
+        };
+        Ok(Self { network, prefix })
+    }
+}
+
+impl IpNet {
+    /// Whether `ip` lies in the block (IPv4-mapped IPv6 addresses count as IPv4)
+    #[must_use]
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        match (self.network, ip.to_canonical()) {
+            (IpAddr::V4(network), IpAddr::V4(ip)) => {
+                let mask = u32::MAX.checked_shl(32 - u32::from(self.prefix)).unwrap_or(0);
+                (u32::from(network) ^ u32::from(ip)) & mask == 0
+            }
+            (IpAddr::V6(network), IpAddr::V6(ip)) => {
+                let mask = u128::MAX.checked_shl(128 - u32::from(self.prefix)).unwrap_or(0);
+                (u128::from(network) ^ u128::from(ip)) & mask == 0
+            }
+            (IpAddr::V4(_), IpAddr::V6(_)) | (IpAddr::V6(_), IpAddr::V4(_)) => false,
+        }
+    }
+
+    /// Parses comma-separated blocks
+    ///
+    /// Malformed entries are skipped with a warning.
+    #[must_use]
+    pub fn parse_list(spec: &str) -> Vec<Self> {
+        spec.split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .filter_map(|entry| {
+                entry
+                    .parse()
+                    .inspect_err(|e| warn!(error = %e, "Ignoring CIDR block entry"))
+                    .ok()
+            })
+            .collect()
+    }
+}
+
+/// Reverse proxies whose forwarding headers are believed
+#[derive(Debug, Clone, Default)]
+pub struct TrustedProxies {
+    blocks: Vec<IpNet>,
+}
+
+impl TrustedProxies {
+    /// Trusts peers in `blocks`
+    #[must_use]
+    pub const fn new(blocks: Vec<IpNet>) -> Self {
+        Self { blocks }
+    }
+
+    /// Parses comma-separated blocks, trusting nobody when unset
+    #[must_use]
+    pub fn from_spec(spec: Option<&str>) -> Self {
+        Self::new(spec.map(IpNet::parse_list).unwrap_or_default())
+    }
+
+    /// Whether `ip` is a trusted proxy
+    fn trusts(&self, ip: IpAddr) -> bool {
+        self.blocks.iter().any(|block| block.contains(ip))
+    }
+
+    /// Client of a request received from `peer`
+    #[must_use]
+    pub fn client_ip(&self, peer: IpAddr, headers: &HeaderMap) -> IpAddr {
+        let mut client = peer;
+        for hop in forwarding_chain(headers).iter().rev() {
+            if !self.trusts(client) {
+                break;
+            }
+            let Some(ip) = hop else {
+                break;
+            };
+            client = *ip;
+        }
+        client
+    }
+}
+
+/// Addresses of the forwarding chain, client first; `None` for hops that are not addresses
+///
+/// `Forwarded` wins over `X-Forwarded-For` when a request carries both.
+fn forwarding_chain(headers: &HeaderMap) -> Vec<Option<IpAddr>> {
+    let values = |name| {
+        headers
+            .get_all(name)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .map(str::trim)
+            .filter(|hop| !hop.is_empty())
+    };
+    if headers.contains_key(FORWARDED) {
+        values(FORWARDED)
+            .map(|element| {
+                element
+                    .split(';')
+                    .filter_map(|pair| pair.split_once('='))
+                    .find(|(name, _)| name.trim().eq_ignore_ascii_case("for"))
+                    .and_then(|(_, node)| parse_node(node))
+            })
+            .collect()
+    } else {
+        values(X_FORWARDED_FOR).map(parse_node).collect()
+    }
+}
+
+/// Address of a forwarding hop such as `192.0.2.1`, `"[2001:db8::1]:4711"` or `192.0.2.1:80`
+fn parse_node(node: &str) -> Option<IpAddr> {
+    let node = node.trim().trim_matches('"');
+    node.parse::<IpAddr>()
+        .ok()
+        .or_else(|| node.parse::<SocketAddr>().ok().map(|address| address.ip()))
+        .or_else(|| node.strip_prefix('[')?.strip_suffix(']')?.parse().ok())
+}
+
+/// Address the request came from, as resolved by [`resolve_client_ip`]
+///
+/// Extracting it when the address is unknown (the server does not record
+/// peer addresses) fails with 500; extract `Option<ClientIp>` to handle that.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClientIp(pub IpAddr);
+
+impl<S: Send + Sync> FromRequestParts<S> for ClientIp {
+    type Rejection = Response;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        parts.extensions.get::<Self>().copied().ok_or_else(|| {
+            error!("Client address unavailable; serve the router with ConnectInfo<SocketAddr>");
+            internal_error()
+        })
+    }
+}
+
+impl<S: Send + Sync> OptionalFromRequestParts<S> for ClientIp {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Option<Self>, Self::Rejection> {
+        Ok(parts.extensions.get::<Self>().copied())
+    }
+}
+
+/// Middleware storing the request's [`ClientIp`], when the connection's peer is known
+///
+/// Runs outside the access log layer, so request spans carry the address.
+pub async fn resolve_client_ip(State(proxies): State<Arc<TrustedProxies>>, mut request: Request, next: Next) -> Response {
+    if let Ok(ConnectInfo(peer)) = request.extract_parts::<ConnectInfo<SocketAddr>>().await {
+        let client = proxies.client_ip(peer.ip(), request.headers());
+        request.extensions_mut().insert(ClientIp(client));
+    }
+    next.run(request).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{Router, body::Body, extract::connect_info::MockConnectInfo, middleware, routing::get};
+    use tower::ServiceExt;
+
+    fn ip(value: &str) -> IpAddr {
+        value.parse().unwrap()
+    }
+
+    fn headers(pairs: &[(HeaderName, &str)]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for (name, value) in pairs {
+            headers.append(name.clone(), value.parse().unwrap());
+        }
+        headers
+    }
+
+    #[test]
+    fn test_blocks_match_their_addresses() {
+        let private = "10.0.0.0/8".parse::<IpNet>().unwrap();
+        let host = "192.168.1.7".parse::<IpNet>().unwrap();
+        let documentation = "2001:db8::/32".parse::<IpNet>().unwrap();
+        let everything = "0.0.0.0/0".parse::<IpNet>().unwrap();
+
+        assert!(private.contains(ip("10.200.3.4")));
+        assert!(private.contains(ip("::ffff:10.0.0.1")));
+        assert!(!private.contains(ip("11.0.0.1")));
+        assert!(host.contains(ip("192.168.1.7")));
+        assert!(!host.contains(ip("192.168.1.8")));
+        assert!(documentation.contains(ip("2001:db8:1::5")));
+        assert!(!documentation.contains(ip("2001:db9::5")));
+        assert!(!documentation.contains(ip("10.0.0.1")));
+        assert!(everything.contains(ip("203.0.113.9")));
+        assert!("10.0.0.0/33".parse::<IpNet>().is_err());
+        assert!("example.com".parse::<IpNet>().is_err());
+        assert_eq!(IpNet::parse_list("10.0.0.0/8, nonsense,,::1"), vec![private, "::1".parse().unwrap()]);
+    }
+
+    #[test]
+    fn test_forwarding_headers_are_only_believed_from_trusted_proxies() {
+        let proxies = TrustedProxies::from_spec(Some("10.0.0.0/8"));
+        let forwarded_for = headers(&[(X_FORWARDED_FOR, "198.51.100.1, 203.0.113.5"), (X_FORWARDED_FOR, "10.1.1.1")]);
+
+        // The trusted proxies at 10.x forwarded for 203.0.113.5; its own entry is not believed
+        assert_eq!(proxies.client_ip(ip("10.0.0.2"), &forwarded_for), ip("203.0.113.5"));
+        assert_eq!(proxies.client_ip(ip("203.0.113.9"), &forwarded_for), ip("203.0.113.9"));
+        assert_eq!(proxies.client_ip(ip("10.0.0.2"), &HeaderMap::new()), ip("10.0.0.2"));
+        assert_eq!(TrustedProxies::default().client_ip(ip("10.0.0.2"), &forwarded_for), ip("10.0.0.2"));
+    }
+
+    #[test]
+    fn test_forwarded_header_takes_precedence() {
+        let proxies = TrustedProxies::from_spec(Some("10.0.0.0/8"));
+        let forwarded = |value| headers(&[(FORWARDED, value), (X_FORWARDED_FOR, "198.51.100.1")]);
+
+        assert_eq!(
+            proxies.client_ip(ip("10.0.0.2"), &forwarded(r#"for=192.0.2.60;proto=https, For="[2001:db8:cafe::17]:4711""#)),
+            ip("2001:db8:cafe::17")
+        );
+        assert_eq!(proxies.client_ip(ip("10.0.0.2"), &forwarded("for=192.0.2.60:8080;by=10.0.0.2")), ip("192.0.2.60"));
+        assert_eq!(proxies.client_ip(ip("10.0.0.2"), &forwarded("for=192.0.2.60, for=unknown")), ip("10.0.0.2"));
+        assert_eq!(proxies.client_ip(ip("10.0.0.2"), &forwarded("proto=https")), ip("10.0.0.2"));
+    }
+
+    async fn resolved(peer: Option<&str>, forwarded_for: &str) -> String {
+        let proxies = Arc::new(TrustedProxies::from_spec(Some("10.0.0.0/8")));
+        let mut app = Router::new()
+            .route("/", get(|client: Option<ClientIp>| async move { client.map(|ClientIp(ip)| ip.to_string()).unwrap_or_default() }))
+            .layer(middleware::from_fn_with_state(proxies, resolve_client_ip));
+        if let Some(peer) = peer {
+            app = app.layer(MockConnectInfo(SocketAddr::new(ip(peer), 4000)));
+        }
+        let request = Request::get("/").header(X_FORWARDED_FOR, forwarded_for).body(Body::empty()).unwrap();
+        let body = app.oneshot(request).await.unwrap().into_body();
+        String::from_utf8(axum::body::to_bytes(body, 64).await.unwrap().to_vec()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_extractor_sees_the_resolved_client() {
+        assert_eq!(resolved(Some("10.0.0.2"), "203.0.113.5").await, "203.0.113.5");
+        assert_eq!(resolved(Some("198.51.100.7"), "203.0.113.5").await, "198.51.100.7");
+        assert_eq!(resolved(None, "203.0.113.5").await, "");
+    }
+}
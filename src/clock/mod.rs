@@ -0,0 +1,108 @@
+//! Wall-clock abstraction
+//!
+//! Services read the current time through a [`Clock`] instead of calling
+//! `Utc::now()` directly, so tests can pin timestamps (`created_at`, event
+//! times, health check timestamps) with a `MockClock`. Elapsed-time
+//! measurements keep using `Instant`, which is unaffected by the clock.
+
+use std::{fmt::Debug, sync::Arc};
+
+use chrono::{DateTime, Utc};
+
+/// Source of the current time
+pub trait Clock: Debug + Send + Sync {
+    /// Current time
+    fn now(&self) -> DateTime<Utc>;
+}
+
+/// Clock shared between services and the application state
+pub type SharedClock = Arc<dyn Clock>;
+
+/// The system clock
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl SystemClock {
+    /// The system clock as a [`SharedClock`]
+    #[must_use]
+    pub fn shared() -> SharedClock {
+        Arc::new(Self)
+    }
+}
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// Clock that only moves when told to
+///
+/// Clones share the same time, so a test can keep a handle and advance the
+/// clock the services were built with.
+#[cfg(any(test, feature = "test-util"))]
+#[derive(Debug, Clone)]
+pub struct MockClock {
+    now: Arc<std::sync::Mutex<DateTime<Utc>>>,
+}
+
+#[cfg(any(test, feature = "test-util"))]
+impl MockClock {
+    /// Creates a clock stopped at `now`
+    #[must_use]
+    pub fn new(now: DateTime<Utc>) -> Self {
+        Self {
+            now: Arc::new(std::sync::Mutex::new(now)),
+        }
+    }
+
+    /// Moves the clock to `now`
+    ///
+    /// # Panics
+    ///
+    /// Panics if the clock's lock is poisoned.
+    pub fn set(&self, now: DateTime<Utc>) {
+        *self.now.lock().expect("Mock clock lock poisoned") = now;
+    }
+
+    /// Moves the clock forward by `duration`
+    ///
+    /// # Panics
+    ///
+    /// Panics if the clock's lock is poisoned.
+    pub fn advance(&self, duration: chrono::Duration) {
+        *self.now.lock().expect("Mock clock lock poisoned") += duration;
+    }
+
+    /// This clock as a [`SharedClock`] sharing the same time
+    #[must_use]
+    pub fn shared(&self) -> SharedClock {
+        Arc::new(self.clone())
+    }
+}
+
+#[cfg(any(test, feature = "test-util"))]
+impl Clock for MockClock {
+    fn now(&self) -> DateTime<Utc> {
+        *self.now.lock().expect("Mock clock lock poisoned")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+
+    #[test]
+    fn test_mock_clock_is_shared_between_clones() {
+        let start = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+        let clock = MockClock::new(start);
+        let shared = clock.shared();
+
+        assert_eq!(shared.now(), start);
+        clock.advance(Duration::minutes(5));
+        assert_eq!(shared.now(), start + Duration::minutes(5));
+        clock.set(start);
+        assert_eq!(shared.now(), start);
+    }
+}
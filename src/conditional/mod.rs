@@ -0,0 +1,73 @@
+//! Conditional requests
+//!
+//! Time-based validators (RFC 9110): responses carry a `Last-Modified` date,
+//! and a GET whose `If-Modified-Since` is not older than that date gets a
+//! bodyless 304 so caches can keep serving their copy. HTTP dates have
+//! one-second precision, so modification times are compared in whole seconds.
+
+use axum::{
+    http::{HeaderMap, HeaderValue, header},
+    response::Response,
+};
+use chrono::{DateTime, Utc};
+
+/// Formats `at` as an HTTP date, e.g. `Sun, 06 Nov 1994 08:49:37 GMT`
+#[must_use]
+pub fn http_date(at: DateTime<Utc>) -> String {
+    at.format("%a, %d %b %Y %H:%M:%S GMT").to_string()
+}
+
+/// Whether the `If-Modified-Since` header in `headers` shows that the
+/// client's copy of a resource last modified at `modified_at` is current
+///
+/// Missing or unparsable dates never match, so the full response is sent.
+#[must_use]
+pub fn not_modified_since(headers: &HeaderMap, modified_at: DateTime<Utc>) -> bool {
+    headers
+        .get(header::IF_MODIFIED_SINCE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| DateTime::parse_from_rfc2822(value).ok())
+        .is_some_and(|since| modified_at.timestamp() <= since.timestamp())
+}
+
+/// Adds a `Last-Modified` header for `modified_at` to `response`
+#[must_use]
+pub fn with_last_modified(mut response: Response, modified_at: DateTime<Utc>) -> Response {
+    if let Ok(value) = HeaderValue::from_str(&http_date(modified_at)) {
+        response.headers_mut().insert(header::LAST_MODIFIED, value);
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(seconds: u32, millis: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(1994, 11, 6, 8, 49, seconds).single().expect("Valid timestamp")
+            + chrono::Duration::milliseconds(i64::from(millis))
+    }
+
+    fn if_modified_since(value: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::IF_MODIFIED_SINCE, HeaderValue::from_str(value).expect("Valid header value"));
+        headers
+    }
+
+    #[test]
+    fn test_http_date() {
+        assert_eq!(http_date(at(37, 0)), "Sun, 06 Nov 1994 08:49:37 GMT");
+    }
+
+    #[test]
+    fn test_not_modified_since_compares_whole_seconds() {
+        let headers = if_modified_since("Sun, 06 Nov 1994 08:49:37 GMT");
+
+        assert!(not_modified_since(&headers, at(36, 0)));
+        assert!(not_modified_since(&headers, at(37, 900)));
+        assert!(!not_modified_since(&headers, at(38, 0)));
+        assert!(!not_modified_since(&HeaderMap::new(), at(36, 0)));
+        assert!(!not_modified_since(&if_modified_since("yesterday"), at(36, 0)));
+    }
+}
@@ -0,0 +1,38 @@
+//! Admin configuration module
+
+use std::env;
+
+use serde::Serialize;
+
+/// Admin tooling configuration
+#[derive(Debug, Clone, Serialize)]
+pub struct AdminConfig {
+    /// Rows a named admin query returns at most
+    pub query_max_rows: u32,
+    /// Milliseconds a named admin query may run before it is cancelled
+    pub query_timeout_ms: u64,
+    /// Comma-separated CIDR blocks allowed to call admin routes (any address when unset)
+    pub ip_allowlist: Option<String>,
+    /// Comma-separated CIDR blocks never allowed to call admin routes
+    pub ip_denylist: Option<String>,
+}
+
+impl AdminConfig {
+    /// Load admin configuration from environment variables
+    #[must_use] pub fn load() -> Self {
+        Self {
+            query_max_rows: env::var("ADMIN_QUERY_MAX_ROWS")
+                .unwrap_or_else(|_| "100".to_owned())
+                .parse()
+                .unwrap_or(100)
+                .max(1),
+            query_timeout_ms: env::var("ADMIN_QUERY_TIMEOUT_MS")
+                .unwrap_or_else(|_| "2000".to_owned())
+                .parse()
+                .unwrap_or(2000)
+                .max(1),
+            ip_allowlist: env::var("ADMIN_IP_ALLOWLIST").ok(),
+            ip_denylist: env::var("ADMIN_IP_DENYLIST").ok(),
+        }
+    }
+}
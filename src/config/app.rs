@@ -1,40 +1,105 @@
 //! Application configuration module
 
-use std::env;
-use super::{DatabaseConfig, ServerConfig};
+use serde::Serialize;
+
+use super::{AdminConfig, AuthConfig, BankConfig, ConfigError, DatabaseConfig, Environment, HealthConfig, JobsConfig, MigrationConfig, NotifyConfig, PaginationConfig, PartnerConfig, PreferenceConfig, PrivacyConfig, RetentionConfig, ScreeningConfig, ServerConfig, TelemetryConfig, TlsConfig, ValidationConfig, WebhookConfig};
 
 /// Main application configuration
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct AppConfig {
     /// Database configuration
     pub database: DatabaseConfig,
     /// Server configuration
     pub server: ServerConfig,
-    /// Environment (development, production, etc.)
-    pub environment: String,
+    /// Authentication configuration
+    pub auth: AuthConfig,
+    /// Bank configuration
+    pub bank: BankConfig,
+    /// Privacy (GDPR) configuration
+    pub privacy: PrivacyConfig,
+    /// Data retention configuration
+    pub retention: RetentionConfig,
+    /// Page size configuration
+    pub pagination: PaginationConfig,
+    /// User preference configuration
+    pub preferences: PreferenceConfig,
+    /// Name screening configuration
+    pub screening: ScreeningConfig,
+    /// User validation limits
+    pub validation: ValidationConfig,
+    /// Health check configuration
+    pub health: HealthConfig,
+    /// Admin tooling configuration
+    pub admin: AdminConfig,
+    /// Partner request signing configuration
+    pub partner: PartnerConfig,
+    /// Mutual TLS configuration
+    pub tls: TlsConfig,
+    /// OpenTelemetry exporter configuration
+    pub telemetry: TelemetryConfig,
+    /// Expand/contract schema change configuration
+    pub migration: MigrationConfig,
+    /// Background job configuration
+    pub jobs: JobsConfig,
+    /// Outgoing webhook configuration
+    pub webhook: WebhookConfig,
+    /// Postgres LISTEN/NOTIFY configuration
+    pub notify: NotifyConfig,
+    /// Environment (development, staging, production or test); see [`Environment::profile`]
+    pub environment: Environment,
 }
 
 impl AppConfig {
     /// Load configuration from environment variables
+    ///
+    /// # Panics
+    ///
+    /// Panics when required settings are missing; see [`Self::try_load`].
     #[must_use] pub fn load() -> Self {
+        Self::try_load().expect("Failed to load configuration")
+    }
+
+    /// Load configuration from environment variables, failing when required settings are missing
+    pub fn try_load() -> Result<Self, ConfigError> {
         // Load .env file if it exists (for development)
         dotenvy::dotenv().ok();
 
+        Ok(Self::load_with_database(DatabaseConfig::try_load()?))
+    }
+
+    /// Load configuration from environment variables, with `database` in place of the `DATABASE_*` settings
+    #[must_use] pub fn load_with_database(database: DatabaseConfig) -> Self {
         Self {
-            database: DatabaseConfig::load(),
+            database,
             server: ServerConfig::load(),
-            environment: env::var("ENVIRONMENT")
-                .unwrap_or_else(|_| "development".to_owned()),
+            auth: AuthConfig::load(),
+            bank: BankConfig::load(),
+            privacy: PrivacyConfig::load(),
+            retention: RetentionConfig::load(),
+            pagination: PaginationConfig::load(),
+            preferences: PreferenceConfig::load(),
+            screening: ScreeningConfig::load(),
+            validation: ValidationConfig::load(),
+            health: HealthConfig::load(),
+            admin: AdminConfig::load(),
+            partner: PartnerConfig::load(),
+            tls: TlsConfig::load(),
+            telemetry: TelemetryConfig::load(),
+            migration: MigrationConfig::load(),
+            jobs: JobsConfig::load(),
+            webhook: WebhookConfig::load(),
+            notify: NotifyConfig::load(),
+            environment: Environment::load(),
         }
     }
 
     /// Check if running in development mode
     #[must_use] pub fn is_development(&self) -> bool {
-        self.environment == "development"
+        self.environment == Environment::Development
     }
 
     /// Check if running in production mode
     #[must_use] pub fn is_production(&self) -> bool {
-        self.environment == "production"
+        self.environment == Environment::Production
     }
 }
\ No newline at end of file
@@ -0,0 +1,22 @@
+//! Authentication configuration module
+
+use std::env;
+
+use serde::Serialize;
+
+/// Authentication configuration
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct AuthConfig {
+    /// Static bearer tokens as `token=subject:role|role;token=subject`
+    #[serde(serialize_with = "super::redact::secret")]
+    pub api_tokens: Option<String>,
+}
+
+impl AuthConfig {
+    /// Load authentication configuration from environment variables
+    #[must_use] pub fn load() -> Self {
+        Self {
+            api_tokens: env::var("API_TOKENS").ok().filter(|tokens| !tokens.is_empty()),
+        }
+    }
+}
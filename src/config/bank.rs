@@ -0,0 +1,74 @@
+//! Bank configuration module
+
+use std::env;
+
+use serde::Serialize;
+
+/// Bank configuration
+#[derive(Debug, Clone, Serialize)]
+pub struct BankConfig {
+    /// Annual interest rate of savings accounts in basis points (0 disables interest accrual)
+    pub savings_rate_bps: u32,
+    /// Seconds between interest accrual runs (0 disables the job); each day is accrued once
+    pub interest_interval_secs: u64,
+    /// Accounts credited per database transaction during interest accrual
+    pub interest_batch_size: u32,
+    /// Seconds between ledger verification runs (0 disables the job)
+    pub ledger_verify_interval_secs: u64,
+    /// Most an account may send by transfer within 24 hours, in cents (0 for no limit)
+    pub daily_transfer_limit_cents: i64,
+    /// Most an account may send by transfer within 30 days, in cents (0 for no limit)
+    pub monthly_transfer_limit_cents: i64,
+    /// Account number scheme for new accounts: `luhn` or `iban`
+    pub account_number_scheme: String,
+    /// Leading digits of Luhn account numbers
+    pub account_number_prefix: String,
+    /// Country code of IBAN account numbers
+    pub iban_country_code: String,
+    /// National bank code inside IBAN account numbers
+    pub bank_code: String,
+    /// Attempts of a balance change that keeps losing serialization races or deadlocks (1 disables retries)
+    pub tx_max_attempts: u32,
+}
+
+impl BankConfig {
+    /// Load bank configuration from environment variables
+    #[must_use] pub fn load() -> Self {
+        Self {
+            savings_rate_bps: env::var("BANK_SAVINGS_RATE_BPS")
+                .unwrap_or_else(|_| "0".to_owned())
+                .parse()
+                .unwrap_or(0),
+            interest_interval_secs: env::var("BANK_INTEREST_INTERVAL_SECS")
+                .unwrap_or_else(|_| "3600".to_owned())
+                .parse()
+                .unwrap_or(3600),
+            interest_batch_size: env::var("BANK_INTEREST_BATCH_SIZE")
+                .unwrap_or_else(|_| "500".to_owned())
+                .parse()
+                .unwrap_or(500)
+                .max(1),
+            ledger_verify_interval_secs: env::var("BANK_LEDGER_VERIFY_INTERVAL_SECS")
+                .unwrap_or_else(|_| "3600".to_owned())
+                .parse()
+                .unwrap_or(3600),
+            daily_transfer_limit_cents: env::var("BANK_DAILY_TRANSFER_LIMIT_CENTS")
+                .unwrap_or_else(|_| "0".to_owned())
+                .parse()
+                .unwrap_or(0),
+            monthly_transfer_limit_cents: env::var("BANK_MONTHLY_TRANSFER_LIMIT_CENTS")
+                .unwrap_or_else(|_| "0".to_owned())
+                .parse()
+                .unwrap_or(0),
+            account_number_scheme: env::var("BANK_ACCOUNT_NUMBER_SCHEME").unwrap_or_else(|_| "luhn".to_owned()),
+            account_number_prefix: env::var("BANK_ACCOUNT_NUMBER_PREFIX").unwrap_or_else(|_| "40".to_owned()),
+            iban_country_code: env::var("BANK_IBAN_COUNTRY").unwrap_or_else(|_| "DE".to_owned()),
+            bank_code: env::var("BANK_CODE").unwrap_or_else(|_| "37040044".to_owned()),
+            tx_max_attempts: env::var("BANK_TX_MAX_ATTEMPTS")
+                .unwrap_or_else(|_| "3".to_owned())
+                .parse()
+                .unwrap_or(3)
+                .max(1),
+        }
+    }
+}
@@ -2,25 +2,78 @@
 
 use std::env;
 
+use serde::Serialize;
+
+use super::ConfigError;
+
 /// Database configuration
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct DatabaseConfig {
     /// Database connection URL
+    #[serde(serialize_with = "super::redact::url")]
     pub url: String,
     /// Maximum number of database connections
     pub max_connections: u32,
+    /// Connections the pool keeps open even when idle
+    pub min_connections: u32,
+    /// Seconds a caller waits for a connection before the acquisition fails
+    pub acquire_timeout_secs: u64,
+    /// Seconds an idle connection above `min_connections` is kept open (0 keeps it forever)
+    pub idle_timeout_secs: u64,
+    /// Seconds after which a connection is closed and replaced (0 keeps it forever)
+    pub max_lifetime_secs: u64,
+    /// Milliseconds after which waiting for a connection is logged as a warning (0 disables the warning)
+    pub slow_acquire_ms: u64,
+    /// Whether to apply pending migrations on startup
+    pub run_migrations: bool,
+    /// Seconds between connectivity pings of the database circuit breaker (0 disables it)
+    pub monitor_interval_secs: u64,
 }
 
 impl DatabaseConfig {
     /// Load database configuration from environment variables
+    ///
+    /// # Panics
+    ///
+    /// Panics when `DATABASE_URL` is not set; see [`Self::try_load`].
     #[must_use] pub fn load() -> Self {
-        Self {
-            url: env::var("DATABASE_URL")
-                .expect("DATABASE_URL must be set"),
+        Self::try_load().expect("Failed to load database configuration")
+    }
+
+    /// Load database configuration from environment variables, failing when `DATABASE_URL` is not set
+    pub fn try_load() -> Result<Self, ConfigError> {
+        Ok(Self {
+            url: env::var("DATABASE_URL").ok().ok_or(ConfigError::Missing("DATABASE_URL"))?,
             max_connections: env::var("DB_MAX_CONNECTIONS")
                 .unwrap_or_else(|_| "5".to_owned())
                 .parse()
                 .unwrap_or(5),
-        }
+            min_connections: env::var("DB_MIN_CONNECTIONS")
+                .unwrap_or_else(|_| "0".to_owned())
+                .parse()
+                .unwrap_or(0),
+            acquire_timeout_secs: env::var("DB_ACQUIRE_TIMEOUT_SECS")
+                .unwrap_or_else(|_| "30".to_owned())
+                .parse()
+                .unwrap_or(30),
+            idle_timeout_secs: env::var("DB_IDLE_TIMEOUT_SECS")
+                .unwrap_or_else(|_| "600".to_owned())
+                .parse()
+                .unwrap_or(600),
+            max_lifetime_secs: env::var("DB_MAX_LIFETIME_SECS")
+                .unwrap_or_else(|_| "1800".to_owned())
+                .parse()
+                .unwrap_or(1800),
+            slow_acquire_ms: env::var("DB_SLOW_ACQUIRE_MS")
+                .unwrap_or_else(|_| "1000".to_owned())
+                .parse()
+                .unwrap_or(1000),
+            run_migrations: env::var("DB_RUN_MIGRATIONS")
+                .is_ok_and(|value| matches!(value.as_str(), "true" | "1")),
+            monitor_interval_secs: env::var("DB_MONITOR_INTERVAL_SECS")
+                .unwrap_or_else(|_| "5".to_owned())
+                .parse()
+                .unwrap_or(5),
+        })
     }
 }
\ No newline at end of file
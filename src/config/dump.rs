@@ -0,0 +1,110 @@
+//! Sanitized configuration dump
+//!
+//! Everything needed to tell how an instance was configured: the resolved
+//! configuration with secrets masked (see [`super::redact`]), the Cargo
+//! features it was built with and its routes with their access policy. The
+//! dump is logged once at startup on the `config` target and served by
+//! `GET /admin/config`.
+
+use serde::Serialize;
+use tracing::info;
+use utoipa::ToSchema;
+
+use super::AppConfig;
+use crate::build_info::BuildInfo;
+
+/// Sanitized configuration of the running instance
+#[derive(Serialize, ToSchema, Debug, Clone, PartialEq)]
+pub struct ConfigDump {
+    /// Resolved configuration values, secrets masked as `***` (`null` when the
+    /// router was built without [`crate::AppBuilder`])
+    #[schema(value_type = Option<Object>)]
+    pub config: serde_json::Value,
+    /// Enabled Cargo features, sorted
+    pub features: Vec<String>,
+    /// Served routes, sorted by path then method
+    pub routes: Vec<RouteEntry>,
+}
+
+/// A served route and who may call it
+#[derive(Serialize, ToSchema, Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct RouteEntry {
+    /// Route pattern (e.g. `/users/{id}`)
+    pub path: String,
+    /// HTTP method
+    pub method: String,
+    /// `public`, `authenticated` or `role:<name>`
+    pub access: String,
+}
+
+impl ConfigDump {
+    /// Dumps `config` along with the build's features and the given routes
+    #[must_use]
+    pub fn new(config: Option<&AppConfig>, mut routes: Vec<RouteEntry>) -> Self {
+        routes.sort();
+        Self {
+            config: config.and_then(|config| serde_json::to_value(config).ok()).unwrap_or_default(),
+            features: BuildInfo::current().features,
+            routes,
+        }
+    }
+
+    /// Emits the dump as a single structured event on the `config` target
+    pub fn log(&self) {
+        info!(
+            target: "config",
+            config = %self.config,
+            features = ?self.features,
+            routes = %serde_json::to_value(&self.routes).unwrap_or_default(),
+            "Resolved configuration"
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{DatabaseConfig, PrivacyConfig};
+
+    #[test]
+    fn test_secrets_are_masked() {
+        let database = DatabaseConfig {
+            url: "postgres://app:s3cr3t@db/app".to_owned(),
+            max_connections: 5,
+            min_connections: 0,
+            acquire_timeout_secs: 30,
+            idle_timeout_secs: 600,
+            max_lifetime_secs: 1800,
+            slow_acquire_ms: 1000,
+            run_migrations: false,
+            monitor_interval_secs: 5,
+        };
+        let privacy = PrivacyConfig {
+            export_signing_key: Some("signing-key".to_owned()),
+            export_link_ttl_secs: 900,
+            export_interval_secs: 30,
+            export_batch_size: 10,
+        };
+
+        let database = serde_json::to_value(database).unwrap();
+        let privacy = serde_json::to_value(privacy).unwrap();
+
+        assert_eq!(database["url"], "postgres://app:***@db/app");
+        assert_eq!(database["max_connections"], 5);
+        assert_eq!(privacy["export_signing_key"], "***");
+    }
+
+    #[test]
+    fn test_routes_are_sorted() {
+        let route = |path: &str, method: &str| RouteEntry {
+            path: path.to_owned(),
+            method: method.to_owned(),
+            access: "public".to_owned(),
+        };
+
+        let dump = ConfigDump::new(None, vec![route("/users", "POST"), route("/admin/config", "GET"), route("/users", "GET")]);
+
+        assert!(dump.config.is_null());
+        assert_eq!(dump.routes, [route("/admin/config", "GET"), route("/users", "GET"), route("/users", "POST")]);
+    }
+}
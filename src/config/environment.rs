@@ -0,0 +1,135 @@
+//! Deployment environment and the behavior it implies
+//!
+//! `ENVIRONMENT` selects an [`Environment`], and [`Environment::profile`]
+//! derives the defaults that differ between environments in one place,
+//! instead of build-type checks and string comparisons spread through the
+//! code.
+
+use std::{env, fmt};
+
+use serde::Serialize;
+
+/// Environment the instance runs in
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Environment {
+    /// A developer's machine (`development`, the default)
+    #[default]
+    Development,
+    /// Pre-production deployment (`staging`)
+    Staging,
+    /// Production deployment (`production`)
+    Production,
+    /// Automated test runs (`test`)
+    Test,
+}
+
+/// Format of log lines
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    /// Human-readable, colored lines
+    Pretty,
+    /// One JSON object per line, for log ingestion
+    Json,
+}
+
+/// Defaults derived from the [`Environment`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Profile {
+    /// Format of log lines
+    pub log_format: LogFormat,
+    /// Log filter used when `RUST_LOG` is unset
+    pub log_filter: &'static str,
+    /// Serve Swagger UI and the `OpenAPI` document
+    pub swagger_ui: bool,
+    /// Enable routes that seed demo or fixture data
+    pub seed_routes: bool,
+    /// Allow cross-origin requests from any origin
+    pub permissive_cors: bool,
+}
+
+impl Environment {
+    /// Parses an `ENVIRONMENT` value (`dev` and `prod` are accepted too); `None` when unknown
+    #[must_use]
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "development" | "dev" => Some(Self::Development),
+            "staging" => Some(Self::Staging),
+            "production" | "prod" => Some(Self::Production),
+            "test" => Some(Self::Test),
+            _ => None,
+        }
+    }
+
+    /// Load the environment from `ENVIRONMENT`, `development` when unset or unknown
+    #[must_use] pub fn load() -> Self {
+        env::var("ENVIRONMENT").ok().and_then(|value| Self::parse(&value)).unwrap_or_default()
+    }
+
+    /// Name as written in `ENVIRONMENT`
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Development => "development",
+            Self::Staging => "staging",
+            Self::Production => "production",
+            Self::Test => "test",
+        }
+    }
+
+    /// Defaults of this environment
+    #[must_use]
+    pub const fn profile(self) -> Profile {
+        let local = matches!(self, Self::Development | Self::Test);
+        Profile {
+            log_format: if local { LogFormat::Pretty } else { LogFormat::Json },
+            log_filter: if local {
+                "rust_kickstart=debug,tower_http=info,tower_http::trace::on_request=info,tower_http::trace::on_response=info,axum::rejection=trace,sqlx=info"
+            } else {
+                "rust_kickstart=info,tower_http=info,sqlx=warn"
+            },
+            swagger_ui: !matches!(self, Self::Production),
+            seed_routes: local,
+            permissive_cors: local,
+        }
+    }
+}
+
+impl fmt::Display for Environment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_accepts_names_and_short_forms() {
+        assert_eq!(Environment::parse("production"), Some(Environment::Production));
+        assert_eq!(Environment::parse(" Prod "), Some(Environment::Production));
+        assert_eq!(Environment::parse("dev"), Some(Environment::Development));
+        assert_eq!(Environment::parse("staging"), Some(Environment::Staging));
+        assert_eq!(Environment::parse("test"), Some(Environment::Test));
+        assert_eq!(Environment::parse("qa"), None);
+    }
+
+    #[test]
+    fn test_profiles() {
+        let development = Environment::Development.profile();
+        assert_eq!(development.log_format, LogFormat::Pretty);
+        assert!(development.log_filter.contains("rust_kickstart=debug"));
+        assert!(development.swagger_ui && development.seed_routes && development.permissive_cors);
+        assert_eq!(Environment::Test.profile(), development);
+
+        let staging = Environment::Staging.profile();
+        assert_eq!(staging.log_format, LogFormat::Json);
+        assert!(staging.log_filter.contains("rust_kickstart=info"));
+        assert!(staging.swagger_ui && !staging.seed_routes && !staging.permissive_cors);
+
+        let production = Environment::Production.profile();
+        assert_eq!(production, Profile { swagger_ui: false, ..staging });
+    }
+}
@@ -0,0 +1,9 @@
+//! Configuration errors
+
+/// Configuration that cannot be loaded
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ConfigError {
+    /// A required environment variable is not set
+    #[error("{0} must be set")]
+    Missing(&'static str),
+}
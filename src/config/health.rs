@@ -0,0 +1,28 @@
+//! Health check configuration module
+
+use std::env;
+
+use serde::Serialize;
+
+/// Health check configuration
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct HealthConfig {
+    /// Downstream HTTP dependencies pinged by `/health` as `name=url;name=url`
+    #[serde(serialize_with = "super::redact::urls")]
+    pub dependencies: Option<String>,
+    /// Milliseconds a dependency ping may take before the dependency is reported degraded
+    pub dependency_timeout_ms: u64,
+}
+
+impl HealthConfig {
+    /// Load health check configuration from environment variables
+    #[must_use] pub fn load() -> Self {
+        Self {
+            dependencies: env::var("HEALTH_DEPENDENCIES").ok().filter(|dependencies| !dependencies.is_empty()),
+            dependency_timeout_ms: env::var("HEALTH_DEPENDENCY_TIMEOUT_MS")
+                .unwrap_or_else(|_| "2000".to_owned())
+                .parse()
+                .unwrap_or(2000),
+        }
+    }
+}
@@ -0,0 +1,93 @@
+//! Background job configuration module
+
+use std::env;
+
+use serde::Serialize;
+
+/// Background job configuration
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct JobsConfig {
+    /// Seconds job runs in progress at shutdown may take to finish before they are aborted
+    pub shutdown_grace_secs: u64,
+    /// Seconds between polls of the job queue (0 disables the worker)
+    pub queue_interval_secs: u64,
+    /// Queued jobs claimed per poll of each queue
+    pub queue_batch_size: i64,
+    /// Queues workers run, with how many of their jobs run at once, as `name:concurrency,name:concurrency`
+    pub queues: String,
+    /// Attempts a queued job gets before it is dead-lettered
+    pub max_attempts: i32,
+    /// Seconds a claimed job stays hidden from other workers before it is claimed again
+    pub visibility_timeout_secs: u64,
+}
+
+impl JobsConfig {
+    /// Load background job configuration from environment variables
+    #[must_use] pub fn load() -> Self {
+        Self {
+            shutdown_grace_secs: env::var("JOB_SHUTDOWN_GRACE_SECS")
+                .unwrap_or_else(|_| "30".to_owned())
+                .parse()
+                .unwrap_or(30),
+            queue_interval_secs: env::var("JOB_QUEUE_INTERVAL_SECS")
+                .unwrap_or_else(|_| "5".to_owned())
+                .parse()
+                .unwrap_or(5),
+            queue_batch_size: env::var("JOB_QUEUE_BATCH_SIZE")
+                .unwrap_or_else(|_| "10".to_owned())
+                .parse()
+                .unwrap_or(10),
+            queues: env::var("JOB_QUEUES")
+                .ok()
+                .filter(|queues| !queues.is_empty())
+                .unwrap_or_else(|| "default:1,webhooks:4".to_owned()),
+            max_attempts: env::var("JOB_MAX_ATTEMPTS")
+                .unwrap_or_else(|_| "5".to_owned())
+                .parse()
+                .unwrap_or(5),
+            visibility_timeout_secs: env::var("JOB_VISIBILITY_TIMEOUT_SECS")
+                .unwrap_or_else(|_| "300".to_owned())
+                .parse()
+                .unwrap_or(300),
+        }
+    }
+
+    /// Queues as name/concurrency pairs; a missing or invalid concurrency is 1 and unnamed entries are skipped
+    #[must_use]
+    pub fn queue_limits(&self) -> Vec<(String, usize)> {
+        self.queues
+            .split(',')
+            .filter_map(|entry| {
+                let (name, concurrency) = entry.split_once(':').unwrap_or((entry, "1"));
+                let name = name.trim();
+                let concurrency = concurrency.trim().parse().unwrap_or(1).max(1);
+                (!name.is_empty()).then(|| (name.to_owned(), concurrency))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_queue_limits_are_parsed() {
+        let config = JobsConfig {
+            queues: "webhooks:8, imports:2,default,broken:x,:3,empty:0".to_owned(),
+            ..JobsConfig::default()
+        };
+
+        assert_eq!(
+            config.queue_limits(),
+            [
+                ("webhooks".to_owned(), 8),
+                ("imports".to_owned(), 2),
+                ("default".to_owned(), 1),
+                ("broken".to_owned(), 1),
+                ("empty".to_owned(), 1),
+            ]
+        );
+        assert!(JobsConfig::default().queue_limits().is_empty());
+    }
+}
@@ -0,0 +1,46 @@
+//! Schema migration configuration module
+
+use std::env;
+
+use serde::Serialize;
+
+/// Expand/contract schema change configuration
+#[derive(Debug, Clone, Serialize)]
+pub struct MigrationConfig {
+    /// `users` column being renamed, as `old:new`; writes are mirrored into the new column while set
+    pub user_dual_write: Option<String>,
+    /// Seconds between backfill runs of the renamed column (0 disables the job)
+    pub backfill_interval_secs: u64,
+    /// Rows copied per backfill statement
+    pub backfill_batch_size: u32,
+}
+
+impl MigrationConfig {
+    /// Load migration configuration from environment variables
+    #[must_use] pub fn load() -> Self {
+        Self {
+            user_dual_write: env::var("USER_DUAL_WRITE").ok().filter(|spec| !spec.is_empty()),
+            backfill_interval_secs: env::var("BACKFILL_INTERVAL_SECS")
+                .unwrap_or_else(|_| "60".to_owned())
+                .parse()
+                .unwrap_or(60),
+            backfill_batch_size: env::var("BACKFILL_BATCH_SIZE")
+                .unwrap_or_else(|_| "500".to_owned())
+                .parse()
+                .unwrap_or(500)
+                .max(1),
+        }
+    }
+
+    /// Rename of the `users` column to dual-write, if one is configured
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `USER_DUAL_WRITE` is not a valid `old:new` rename.
+    pub fn user_rename(&self) -> Result<Option<crate::db::ColumnRename>, crate::db::InvalidRename> {
+        self.user_dual_write
+            .as_deref()
+            .map(|spec| crate::db::ColumnRename::parse("users", "id", spec))
+            .transpose()
+    }
+}
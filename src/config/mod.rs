@@ -2,12 +2,53 @@
 //!
 //! Organized configuration using environment variables and tracing setup.
 
+mod admin;
 mod app;
+mod auth;
+mod bank;
 mod database;
+mod dump;
+mod environment;
+mod error;
+mod health;
+mod jobs;
+mod migration;
+mod notify;
+mod pagination;
+mod partner;
+mod preference;
+mod privacy;
+pub mod redact;
+mod retention;
+mod screening;
 mod server;
+mod telemetry;
+mod tls;
+mod validation;
+mod webhook;
 pub mod tracing;
 
 // Re-export all configuration types
+pub use admin::AdminConfig;
 pub use app::AppConfig;
+pub use auth::AuthConfig;
+pub use bank::BankConfig;
 pub use database::DatabaseConfig;
-pub use server::ServerConfig;
\ No newline at end of file
+pub use dump::{ConfigDump, RouteEntry};
+pub use environment::{Environment, LogFormat, Profile};
+pub use error::ConfigError;
+pub use health::HealthConfig;
+pub use jobs::JobsConfig;
+pub use migration::MigrationConfig;
+pub use notify::NotifyConfig;
+pub use pagination::PaginationConfig;
+pub use partner::PartnerConfig;
+pub use preference::PreferenceConfig;
+pub use privacy::PrivacyConfig;
+pub use retention::RetentionConfig;
+pub use screening::ScreeningConfig;
+pub use server::ServerConfig;
+pub use telemetry::{OtlpProtocol, TelemetryConfig};
+pub use tls::TlsConfig;
+pub use validation::ValidationConfig;
+pub use webhook::WebhookConfig;
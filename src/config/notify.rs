@@ -0,0 +1,55 @@
+//! Postgres LISTEN/NOTIFY configuration module
+
+use std::env;
+
+use serde::Serialize;
+
+/// Postgres LISTEN/NOTIFY configuration
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct NotifyConfig {
+    /// Comma-separated channels to listen on; empty disables the listener
+    pub channels: String,
+    /// Longest wait in seconds between reconnection attempts after the listening connection fails
+    pub reconnect_max_secs: u64,
+}
+
+impl NotifyConfig {
+    /// Load LISTEN/NOTIFY configuration from environment variables
+    #[must_use] pub fn load() -> Self {
+        Self {
+            channels: env::var("NOTIFY_CHANNELS").unwrap_or_default(),
+            reconnect_max_secs: env::var("NOTIFY_RECONNECT_MAX_SECS")
+                .unwrap_or_else(|_| "30".to_owned())
+                .parse()
+                .unwrap_or(30),
+        }
+    }
+
+    /// Channels to listen on, trimmed and without blanks or duplicates
+    #[must_use]
+    pub fn channel_list(&self) -> Vec<String> {
+        let mut channels: Vec<String> = Vec::new();
+        for channel in self.channels.split(',').map(str::trim).filter(|channel| !channel.is_empty()) {
+            if !channels.iter().any(|known| known == channel) {
+                channels.push(channel.to_owned());
+            }
+        }
+        channels
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_channel_list_skips_blanks_and_duplicates() {
+        let config = NotifyConfig {
+            channels: " cache_invalidation, ,events,cache_invalidation,".to_owned(),
+            ..NotifyConfig::default()
+        };
+
+        assert_eq!(config.channel_list(), ["cache_invalidation", "events"]);
+        assert!(NotifyConfig::default().channel_list().is_empty());
+    }
+}
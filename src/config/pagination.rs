@@ -0,0 +1,33 @@
+//! Pagination configuration module
+
+use std::env;
+
+use serde::Serialize;
+
+/// Page size configuration of the users listing
+#[derive(Debug, Clone, Serialize)]
+pub struct PaginationConfig {
+    /// Page size when the request has no `limit`
+    pub default_limit: i32,
+    /// Largest page size a request may ask for
+    pub max_limit: i32,
+}
+
+impl PaginationConfig {
+    /// Load pagination configuration from environment variables
+    #[must_use] pub fn load() -> Self {
+        let max_limit = env::var("PAGINATION_MAX_LIMIT")
+            .unwrap_or_else(|_| "200".to_owned())
+            .parse()
+            .unwrap_or(200)
+            .max(1);
+        Self {
+            default_limit: env::var("PAGINATION_DEFAULT_LIMIT")
+                .unwrap_or_else(|_| "200".to_owned())
+                .parse()
+                .unwrap_or(200)
+                .clamp(1, max_limit),
+            max_limit,
+        }
+    }
+}
@@ -0,0 +1,34 @@
+//! Partner integration configuration module
+
+use std::env;
+
+use serde::Serialize;
+
+/// Partner request signing configuration
+#[derive(Debug, Clone, Serialize)]
+pub struct PartnerConfig {
+    /// Seconds a signed request's timestamp may differ from the server time
+    pub signature_window_secs: u64,
+    /// Seconds previous keys stay valid after a key rotation
+    pub key_rotation_grace_secs: u64,
+    /// Key encrypting the stored partner signing keys; a random per-process key is used when unset
+    #[serde(serialize_with = "super::redact::secret")]
+    pub key_encryption_key: Option<String>,
+}
+
+impl PartnerConfig {
+    /// Load partner configuration from environment variables
+    #[must_use] pub fn load() -> Self {
+        Self {
+            signature_window_secs: env::var("PARTNER_SIGNATURE_WINDOW_SECS")
+                .unwrap_or_else(|_| "300".to_owned())
+                .parse()
+                .unwrap_or(300),
+            key_rotation_grace_secs: env::var("PARTNER_KEY_ROTATION_GRACE_SECS")
+                .unwrap_or_else(|_| "86400".to_owned())
+                .parse()
+                .unwrap_or(86_400),
+            key_encryption_key: env::var("PARTNER_KEY_ENCRYPTION_KEY").ok().filter(|key| !key.is_empty()),
+        }
+    }
+}
@@ -0,0 +1,25 @@
+//! Preference configuration module
+
+use std::env;
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+/// User preference configuration
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PreferenceConfig {
+    /// Defaults overriding the built-in ones, from `PREFERENCE_DEFAULTS` as a JSON object
+    pub defaults: Map<String, Value>,
+}
+
+impl PreferenceConfig {
+    /// Load preference configuration from environment variables
+    #[must_use] pub fn load() -> Self {
+        Self {
+            defaults: env::var("PREFERENCE_DEFAULTS")
+                .ok()
+                .and_then(|defaults| serde_json::from_str(&defaults).ok())
+                .unwrap_or_default(),
+        }
+    }
+}
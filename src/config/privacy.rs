@@ -0,0 +1,41 @@
+//! Privacy configuration module
+
+use std::env;
+
+use serde::Serialize;
+
+/// Privacy (GDPR) configuration
+#[derive(Debug, Clone, Serialize)]
+pub struct PrivacyConfig {
+    /// Key signing data export download links; a random per-process key is used when unset
+    #[serde(serialize_with = "super::redact::secret")]
+    pub export_signing_key: Option<String>,
+    /// Seconds a data export download link stays valid
+    pub export_link_ttl_secs: u64,
+    /// Seconds between data export job runs (0 disables the job)
+    pub export_interval_secs: u64,
+    /// Exports built per job run
+    pub export_batch_size: u32,
+}
+
+impl PrivacyConfig {
+    /// Load privacy configuration from environment variables
+    #[must_use] pub fn load() -> Self {
+        Self {
+            export_signing_key: env::var("EXPORT_SIGNING_KEY").ok().filter(|key| !key.is_empty()),
+            export_link_ttl_secs: env::var("EXPORT_LINK_TTL_SECS")
+                .unwrap_or_else(|_| "86400".to_owned())
+                .parse()
+                .unwrap_or(86_400),
+            export_interval_secs: env::var("EXPORT_JOB_INTERVAL_SECS")
+                .unwrap_or_else(|_| "10".to_owned())
+                .parse()
+                .unwrap_or(10),
+            export_batch_size: env::var("EXPORT_BATCH_SIZE")
+                .unwrap_or_else(|_| "10".to_owned())
+                .parse()
+                .unwrap_or(10)
+                .max(1),
+        }
+    }
+}
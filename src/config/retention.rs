@@ -0,0 +1,55 @@
+//! Retention configuration module
+
+use std::env;
+
+use serde::Serialize;
+
+/// Data retention configuration
+#[derive(Debug, Clone, Serialize)]
+pub struct RetentionConfig {
+    /// Days audit events are kept (0 keeps them forever)
+    pub audit_events_days: u32,
+    /// Days data exports are kept after their download link expires (0 keeps them forever)
+    pub data_exports_days: u32,
+    /// Days webhook delivery attempts are kept (0 keeps them forever)
+    pub webhook_deliveries_days: u32,
+    /// Days entries of the users change feed are kept (0 keeps them forever)
+    pub user_changes_days: u32,
+    /// Seconds between retention purge runs (0 disables the job)
+    pub interval_secs: u64,
+    /// Rows deleted per statement
+    pub batch_size: u32,
+}
+
+impl RetentionConfig {
+    /// Load retention configuration from environment variables
+    #[must_use] pub fn load() -> Self {
+        Self {
+            audit_events_days: env::var("RETENTION_AUDIT_EVENTS_DAYS")
+                .unwrap_or_else(|_| "365".to_owned())
+                .parse()
+                .unwrap_or(365),
+            data_exports_days: env::var("RETENTION_DATA_EXPORTS_DAYS")
+                .unwrap_or_else(|_| "7".to_owned())
+                .parse()
+                .unwrap_or(7),
+            webhook_deliveries_days: env::var("RETENTION_WEBHOOK_DELIVERIES_DAYS")
+                .unwrap_or_else(|_| "30".to_owned())
+                .parse()
+                .unwrap_or(30),
+            user_changes_days: env::var("RETENTION_USER_CHANGES_DAYS")
+                .unwrap_or_else(|_| "7".to_owned())
+                .parse()
+                .unwrap_or(7),
+            interval_secs: env::var("RETENTION_INTERVAL_SECS")
+                .unwrap_or_else(|_| "3600".to_owned())
+                .parse()
+                .unwrap_or(3600),
+            batch_size: env::var("RETENTION_BATCH_SIZE")
+                .unwrap_or_else(|_| "1000".to_owned())
+                .parse()
+                .unwrap_or(1000)
+                .max(1),
+        }
+    }
+}
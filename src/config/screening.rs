@@ -0,0 +1,21 @@
+//! Name screening configuration module
+
+use std::env;
+
+use serde::Serialize;
+
+/// Name screening configuration
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ScreeningConfig {
+    /// Words and phrases user names may not contain, comma-separated
+    pub name_denylist: Option<String>,
+}
+
+impl ScreeningConfig {
+    /// Load name screening configuration from environment variables
+    #[must_use] pub fn load() -> Self {
+        Self {
+            name_denylist: env::var("NAME_DENYLIST").ok().filter(|denylist| !denylist.is_empty()),
+        }
+    }
+}
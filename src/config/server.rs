@@ -2,13 +2,32 @@
 
 use std::env;
 
+use serde::Serialize;
+
 /// Server configuration
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct ServerConfig {
     /// Server host
     pub host: String,
-    /// Server port
+    /// Server port: `SERVER_PORT`, else the `PORT` hosting platforms assign, else 3000
     pub port: u16,
+    /// Wrap responses in a `{ data, meta, errors }` envelope by default
+    pub response_envelope: bool,
+    /// Public base URL used in hypermedia links (root-relative links when unset)
+    pub public_base_url: Option<String>,
+    /// Reject mutating requests with 503 and start no background jobs, e.g. while serving from a replica
+    pub read_only: bool,
+    /// Seconds a request may take before it is answered with 503 (0 disables the limit)
+    pub request_timeout_secs: u64,
+    /// Seconds granted instead to the heavy routes (erasure, export downloads, ledger verification; 0 disables their limit)
+    pub long_request_timeout_secs: u64,
+    /// Seconds shared caches may serve the cacheable public reads (0 makes every response `no-store`)
+    pub cache_max_age_secs: u64,
+    /// Comma-separated CIDR blocks of reverse proxies whose `X-Forwarded-For` is trusted
+    pub trusted_proxies: Option<String>,
+    /// Who may read `/swagger-ui` and `/api-docs/*`: `public`, `authenticated`,
+    /// `role:<name>` or `disabled` (the environment's default when unset)
+    pub docs_access: Option<String>,
 }
 
 impl ServerConfig {
@@ -18,9 +37,29 @@ impl ServerConfig {
             host: env::var("SERVER_HOST")
                 .unwrap_or_else(|_| "0.0.0.0".to_owned()),
             port: env::var("SERVER_PORT")
+                .or_else(|_| env::var("PORT"))
                 .unwrap_or_else(|_| "3000".to_owned())
                 .parse()
                 .unwrap_or(3000),
+            response_envelope: env::var("RESPONSE_ENVELOPE")
+                .is_ok_and(|value| matches!(value.as_str(), "true" | "1")),
+            public_base_url: env::var("PUBLIC_BASE_URL").ok(),
+            read_only: env::var("READ_ONLY")
+                .is_ok_and(|value| matches!(value.as_str(), "true" | "1")),
+            request_timeout_secs: env::var("REQUEST_TIMEOUT_SECS")
+                .unwrap_or_else(|_| "30".to_owned())
+                .parse()
+                .unwrap_or(30),
+            long_request_timeout_secs: env::var("LONG_REQUEST_TIMEOUT_SECS")
+                .unwrap_or_else(|_| "300".to_owned())
+                .parse()
+                .unwrap_or(300),
+            cache_max_age_secs: env::var("CACHE_MAX_AGE_SECS")
+                .unwrap_or_else(|_| "60".to_owned())
+                .parse()
+                .unwrap_or(60),
+            trusted_proxies: env::var("TRUSTED_PROXIES").ok(),
+            docs_access: env::var("DOCS_ACCESS").ok().filter(|access| !access.trim().is_empty()),
         }
     }
 
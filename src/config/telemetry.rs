@@ -0,0 +1,148 @@
+//! OpenTelemetry exporter configuration module
+//!
+//! Read from the standard `OTEL_EXPORTER_OTLP_*` variables, so the settings
+//! documented by hosted backends (Honeycomb, Grafana Cloud, Datadog, ...) work
+//! unchanged.
+
+use std::env;
+
+use serde::Serialize;
+
+/// Wire protocol of the OTLP exporter
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub enum OtlpProtocol {
+    /// OTLP over gRPC (`grpc`), usually on port 4317
+    #[serde(rename = "grpc")]
+    Grpc,
+    /// Protobuf-encoded OTLP over HTTP (`http/protobuf`), usually on port 4318
+    #[default]
+    #[serde(rename = "http/protobuf")]
+    HttpProtobuf,
+}
+
+impl OtlpProtocol {
+    /// Parses an `OTEL_EXPORTER_OTLP_PROTOCOL` value; `None` for unsupported protocols
+    #[must_use]
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim() {
+            "grpc" => Some(Self::Grpc),
+            "http/protobuf" => Some(Self::HttpProtobuf),
+            _ => None,
+        }
+    }
+}
+
+/// OpenTelemetry exporter configuration
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct TelemetryConfig {
+    /// Collector endpoint; traces are only exported when set
+    pub endpoint: Option<String>,
+    /// Wire protocol, `http/protobuf` unless `OTEL_EXPORTER_OTLP_PROTOCOL` is `grpc`
+    pub protocol: OtlpProtocol,
+    /// Headers sent with every export as `key=value,key=value`, values percent-encoded
+    #[serde(serialize_with = "super::redact::secret")]
+    pub headers: Option<String>,
+    /// PEM file with the CA certificates the collector's certificate must chain to
+    /// (the system roots are trusted otherwise)
+    pub certificate: Option<String>,
+    /// PEM file with the client certificate presented to collectors requiring mutual TLS
+    pub client_certificate: Option<String>,
+    /// PEM file with the private key of `client_certificate`
+    pub client_key: Option<String>,
+}
+
+impl TelemetryConfig {
+    /// Load OpenTelemetry exporter configuration from environment variables
+    #[must_use] pub fn load() -> Self {
+        let non_empty = |name: &str| env::var(name).ok().filter(|value| !value.is_empty());
+        Self {
+            endpoint: non_empty("OTEL_EXPORTER_OTLP_ENDPOINT"),
+            protocol: non_empty("OTEL_EXPORTER_OTLP_PROTOCOL")
+                .and_then(|protocol| OtlpProtocol::parse(&protocol))
+                .unwrap_or_default(),
+            headers: non_empty("OTEL_EXPORTER_OTLP_HEADERS"),
+            certificate: non_empty("OTEL_EXPORTER_OTLP_CERTIFICATE"),
+            client_certificate: non_empty("OTEL_EXPORTER_OTLP_CLIENT_CERTIFICATE"),
+            client_key: non_empty("OTEL_EXPORTER_OTLP_CLIENT_KEY"),
+        }
+    }
+
+    /// Headers as name/value pairs, values percent-decoded; malformed entries are skipped
+    #[must_use]
+    pub fn header_pairs(&self) -> Vec<(String, String)> {
+        self.headers
+            .as_deref()
+            .unwrap_or_default()
+            .split(',')
+            .filter_map(|entry| {
+                let (name, value) = entry.split_once('=')?;
+                let name = name.trim();
+                (!name.is_empty()).then(|| (name.to_owned(), percent_decode(value.trim())))
+            })
+            .collect()
+    }
+}
+
+/// Decodes `%XX` escapes, leaving invalid ones as they are
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = (bytes[i] == b'%')
+            .then(|| bytes.get(i + 1..i + 3))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok());
+        if let Some(byte) = escaped {
+            decoded.push(byte);
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_protocol_parsing() {
+        assert_eq!(OtlpProtocol::parse("grpc"), Some(OtlpProtocol::Grpc));
+        assert_eq!(OtlpProtocol::parse(" http/protobuf "), Some(OtlpProtocol::HttpProtobuf));
+        assert_eq!(OtlpProtocol::parse("http/json"), None);
+    }
+
+    #[test]
+    fn test_header_pairs_are_split_and_decoded() {
+        let config = TelemetryConfig {
+            headers: Some("x-honeycomb-team=abc, Authorization=Basic%20dXNlcjpwYXNz,broken,=empty,odd=100%".to_owned()),
+            ..TelemetryConfig::default()
+        };
+
+        assert_eq!(
+            config.header_pairs(),
+            [
+                ("x-honeycomb-team".to_owned(), "abc".to_owned()),
+                ("Authorization".to_owned(), "Basic dXNlcjpwYXNz".to_owned()),
+                ("odd".to_owned(), "100%".to_owned()),
+            ]
+        );
+        assert!(TelemetryConfig::default().header_pairs().is_empty());
+    }
+
+    #[test]
+    fn test_headers_are_masked_in_dumps() {
+        let config = TelemetryConfig {
+            headers: Some("api-key=secret".to_owned()),
+            ..TelemetryConfig::default()
+        };
+
+        let dump = serde_json::to_value(&config).unwrap();
+
+        assert_eq!(dump["headers"], "***");
+        assert_eq!(dump["protocol"], "http/protobuf");
+    }
+}
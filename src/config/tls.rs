@@ -0,0 +1,40 @@
+//! TLS configuration module
+
+use std::env;
+
+use serde::Serialize;
+
+/// Mutual TLS configuration, used with the `mtls` feature
+///
+/// The server speaks plain HTTP unless all three paths are set.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct TlsConfig {
+    /// PEM file with the server certificate chain
+    pub cert_path: Option<String>,
+    /// PEM file with the server private key
+    pub key_path: Option<String>,
+    /// PEM file with the CA certificates client certificates must chain to
+    pub client_ca_path: Option<String>,
+    /// PEM file with the client certificate `healthcheck` presents
+    pub probe_cert_path: Option<String>,
+    /// PEM file with the private key of the `healthcheck` client certificate
+    pub probe_key_path: Option<String>,
+}
+
+impl TlsConfig {
+    /// Load TLS configuration from environment variables
+    #[must_use] pub fn load() -> Self {
+        Self {
+            cert_path: env::var("TLS_CERT_PATH").ok(),
+            key_path: env::var("TLS_KEY_PATH").ok(),
+            client_ca_path: env::var("TLS_CLIENT_CA_PATH").ok(),
+            probe_cert_path: env::var("TLS_PROBE_CERT_PATH").ok(),
+            probe_key_path: env::var("TLS_PROBE_KEY_PATH").ok(),
+        }
+    }
+
+    /// Whether any setting of the served TLS is present; the probe settings do not count
+    #[must_use] pub const fn is_configured(&self) -> bool {
+        self.cert_path.is_some() || self.key_path.is_some() || self.client_ca_path.is_some()
+    }
+}
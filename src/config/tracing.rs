@@ -11,8 +11,8 @@ use tracing_subscriber::{
 /// Initialize tracing/logging and observability configuration.
 ///
 /// This should be called once at application startup. It configures:
-/// - JSON format in production for structured logging
-/// - Pretty format in development for readability
+/// - JSON format in staging and production for structured logging
+/// - Pretty format in development and tests for readability
 /// - OpenTelemetry integration for distributed tracing (if configured)
 /// - Appropriate log levels based on environment
 /// - Request tracing and correlation IDs
@@ -21,17 +21,20 @@ use tracing_subscriber::{
 ///
 /// Returns an error if the tracing subscriber cannot be initialized.
 pub fn init() -> Result<(), Box<dyn std::error::Error>> {
-    // Check if OpenTelemetry endpoint is configured
-    let endpoint = std::env::var("OTEL_EXPORTER_OTLP_ENDPOINT");
+    let profile = super::Environment::load().profile();
+    let telemetry = super::TelemetryConfig::load();
     let service_name =
-        std::env::var("OTEL_SERVICE_NAME").unwrap_or_else(|_| "rust-kickstart".to_string());
+        std::env::var("OTEL_SERVICE_NAME").unwrap_or_else(|_| "rust-kickstart".to_owned());
 
     // Try to initialize OpenTelemetry if endpoint is configured
-    let otel_layer = if let Ok(_endpoint_url) = endpoint {
-        match init_opentelemetry() {
+    let otel_layer = if telemetry.endpoint.is_some() {
+        match init_opentelemetry(&telemetry) {
             Ok(tracer) => Some(tracing_opentelemetry::layer().with_tracer(tracer)),
             Err(e) => {
-                eprintln!("❌ Failed to initialize OpenTelemetry: {}", e);
+                #[allow(clippy::print_stderr)]
+                {
+                    eprintln!("❌ Failed to initialize OpenTelemetry: {e}");
+                }
                 None
             },
         }
@@ -39,10 +42,10 @@ pub fn init() -> Result<(), Box<dyn std::error::Error>> {
         None
     };
 
-    // Use JSON format in production, pretty format in development
-    let fmt_layer = if cfg!(debug_assertions) {
+    // Use JSON format in staging and production, pretty format locally
+    let fmt_layer = match profile.log_format {
         // Development: Clean format focusing on our code
-        tracing_subscriber::fmt::layer()
+        super::LogFormat::Pretty => tracing_subscriber::fmt::layer()
             .with_target(true) // Show module names (rust_kickstart::user::repository)
             .with_thread_ids(true) // Show ThreadId for debugging concurrency
             .with_thread_names(false) // Hide "tokio-runtime-worker" noise
@@ -50,40 +53,32 @@ pub fn init() -> Result<(), Box<dyn std::error::Error>> {
             .with_line_number(false) // Hide line numbers to reduce noise
             .with_level(true) // Show log level (INFO, DEBUG, etc.)
             .with_ansi(true) // Keep colors for better readability
-            .boxed()
-    } else {
+            .boxed(),
         // Production: JSON format for structured logging
-        tracing_subscriber::fmt::layer()
+        super::LogFormat::Json => tracing_subscriber::fmt::layer()
             .json()
             .with_target(true)
             .with_thread_ids(true)
             .with_current_span(false)
             .with_span_list(true)
             .flatten_event(true)
-            .boxed()
+            .boxed(),
     };
 
-    // Enhanced EnvFilter configuration with better defaults
-    let env_filter = EnvFilter::try_from_default_env().or_else(|_| {
-        // Different log levels for dev vs prod
-        if cfg!(debug_assertions) {
-            // Development: More verbose logging but filter out noisy tower_http internals
-            EnvFilter::try_new(
-                "rust_kickstart=debug,tower_http=info,tower_http::trace::on_request=info,tower_http::trace::on_response=info,axum::rejection=trace,sqlx=info"
-            )
-        } else {
-            // Production: Less verbose, focus on important events
-            EnvFilter::try_new(
-                "rust_kickstart=info,tower_http=info,sqlx=warn"
-            )
-        }
-    })?;
+    // RUST_LOG wins; otherwise the environment's default (verbose locally,
+    // focused on important events in staging and production)
+    let env_filter = EnvFilter::try_from_default_env().or_else(|_| EnvFilter::try_new(profile.log_filter))?;
 
     // Initialize the global subscriber with optional OpenTelemetry layer
     let registry = Registry::default().with(env_filter).with(fmt_layer);
+    #[cfg(feature = "profiling")]
+    let registry = registry.with(crate::profiling::SpanProfiler::global().clone());
 
     match otel_layer {
-        Some(otel) => registry.with(otel).try_init()?,
+        Some(otel) => registry
+            .with(otel)
+            .with(crate::auth::spans::PrincipalAttributes)
+            .try_init()?,
         None => registry.try_init()?,
     }
 
@@ -91,8 +86,9 @@ pub fn init() -> Result<(), Box<dyn std::error::Error>> {
     tracing::debug!("🔍 Tracing configuration initialized successfully");
 
     // Log OpenTelemetry status
-    if let Ok(endpoint_url) = std::env::var("OTEL_EXPORTER_OTLP_ENDPOINT") {
+    if let Some(endpoint_url) = &telemetry.endpoint {
         tracing::debug!("✅ OTEL_EXPORTER_OTLP_ENDPOINT found: {}", endpoint_url);
+        tracing::debug!("📡 Protocol: {:?}", telemetry.protocol);
         tracing::debug!("📡 Service name: {}", service_name);
         tracing::debug!(
             "✅ OpenTelemetry initialized successfully! Distributed tracing is now active."
@@ -111,25 +107,26 @@ pub fn init() -> Result<(), Box<dyn std::error::Error>> {
 
 /// Initialize OpenTelemetry tracer.
 ///
-/// This function sets up the OpenTelemetry tracer with OTLP exporter.
+/// This function sets up the OpenTelemetry tracer with an OTLP exporter over
+/// gRPC or HTTP, as `config.protocol` says.
 ///
 /// # Errors
 ///
 /// Returns an error if the OpenTelemetry tracer cannot be initialized.
 #[cfg(feature = "otel")]
-fn init_opentelemetry() -> Result<opentelemetry_sdk::trace::Tracer, Box<dyn std::error::Error>> {
+fn init_opentelemetry(
+    config: &super::TelemetryConfig,
+) -> Result<opentelemetry_sdk::trace::Tracer, Box<dyn std::error::Error>> {
     use opentelemetry::{global, trace::TracerProvider, KeyValue};
-    use opentelemetry_otlp::WithExportConfig;
     use opentelemetry_sdk::{
         trace::{Sampler, TracerProvider as SdkTracerProvider},
         Resource,
     };
     // Get configuration from environment
-    let endpoint = std::env::var("OTEL_EXPORTER_OTLP_ENDPOINT")?;
     let service_name =
-        std::env::var("OTEL_SERVICE_NAME").unwrap_or_else(|_| "rust-kickstart".to_string());
+        std::env::var("OTEL_SERVICE_NAME").unwrap_or_else(|_| "rust-kickstart".to_owned());
     let service_version =
-        std::env::var("OTEL_SERVICE_VERSION").unwrap_or_else(|_| "0.1.0".to_string());
+        std::env::var("OTEL_SERVICE_VERSION").unwrap_or_else(|_| "0.1.0".to_owned());
 
     // Create resource with service information
     let resource = Resource::new(vec![
@@ -138,10 +135,10 @@ fn init_opentelemetry() -> Result<opentelemetry_sdk::trace::Tracer, Box<dyn std:
     ]);
 
     // Initialize OTLP exporter
-    let exporter = opentelemetry_otlp::new_exporter()
-        .http()
-        .with_endpoint(endpoint)
-        .build_span_exporter()?;
+    let exporter = match config.protocol {
+        super::OtlpProtocol::Grpc => grpc_span_exporter(config)?,
+        super::OtlpProtocol::HttpProtobuf => http_span_exporter(config)?,
+    };
 
     // Create tracer provider with batch exporter
     let tracer_provider = SdkTracerProvider::builder()
@@ -162,9 +159,88 @@ fn init_opentelemetry() -> Result<opentelemetry_sdk::trace::Tracer, Box<dyn std:
     Ok(tracer)
 }
 
+/// OTLP/gRPC span exporter sending `config.headers` as metadata, over TLS for `https` endpoints
+#[cfg(feature = "otel")]
+fn grpc_span_exporter(
+    config: &super::TelemetryConfig,
+) -> Result<opentelemetry_otlp::SpanExporter, Box<dyn std::error::Error>> {
+    use axum::http::{HeaderMap, HeaderName, HeaderValue};
+    use opentelemetry_otlp::WithExportConfig;
+    use tonic::{
+        metadata::MetadataMap,
+        transport::{Certificate, ClientTlsConfig, Identity},
+    };
+
+    let endpoint = config.endpoint.clone().unwrap_or_default();
+    let mut headers = HeaderMap::new();
+    for (name, value) in config.header_pairs() {
+        headers.insert(HeaderName::try_from(name)?, HeaderValue::try_from(value)?);
+    }
+    let mut builder = opentelemetry_otlp::new_exporter()
+        .tonic()
+        .with_endpoint(endpoint.clone())
+        .with_metadata(MetadataMap::from_headers(headers));
+
+    if endpoint.starts_with("https://") {
+        let mut tls = ClientTlsConfig::new().with_native_roots();
+        if let Some(path) = &config.certificate {
+            tls = tls.ca_certificate(Certificate::from_pem(std::fs::read(path)?));
+        }
+        if let Some(client) = client_identity(config)? {
+            tls = tls.identity(Identity::from_pem(client.cert, client.key));
+        }
+        builder = builder.with_tls_config(tls);
+    }
+
+    Ok(builder.build_span_exporter()?)
+}
+
+/// OTLP/HTTP (protobuf) span exporter sending `config.headers`, trusting `config.certificate`
+#[cfg(feature = "otel")]
+fn http_span_exporter(
+    config: &super::TelemetryConfig,
+) -> Result<opentelemetry_otlp::SpanExporter, Box<dyn std::error::Error>> {
+    use opentelemetry_otlp::WithExportConfig;
+
+    let mut client = reqwest::Client::builder();
+    if let Some(path) = &config.certificate {
+        client = client.add_root_certificate(reqwest::Certificate::from_pem(&std::fs::read(path)?)?);
+    }
+    if let Some(ClientPem { mut cert, key }) = client_identity(config)? {
+        cert.extend(key);
+        client = client.identity(reqwest::Identity::from_pem(&cert)?);
+    }
+
+    Ok(opentelemetry_otlp::new_exporter()
+        .http()
+        .with_endpoint(config.endpoint.clone().unwrap_or_default())
+        .with_http_client(client.build()?)
+        .with_headers(config.header_pairs().into_iter().collect())
+        .build_span_exporter()?)
+}
+
+/// PEM certificate and private key presented to collectors requiring mutual TLS
+#[cfg(feature = "otel")]
+struct ClientPem {
+    cert: Vec<u8>,
+    key: Vec<u8>,
+}
+
+/// Reads the client certificate and key, when both are set
+#[cfg(feature = "otel")]
+fn client_identity(config: &super::TelemetryConfig) -> Result<Option<ClientPem>, Box<dyn std::error::Error>> {
+    match (&config.client_certificate, &config.client_key) {
+        (Some(cert), Some(key)) => Ok(Some(ClientPem { cert: std::fs::read(cert)?, key: std::fs::read(key)? })),
+        (None, None) => Ok(None),
+        _ => Err("OTEL_EXPORTER_OTLP_CLIENT_CERTIFICATE and OTEL_EXPORTER_OTLP_CLIENT_KEY must be set together".into()),
+    }
+}
+
 /// Fallback initialization when OpenTelemetry is not available
 #[cfg(not(feature = "otel"))]
-fn init_opentelemetry() -> Result<opentelemetry::trace::noop::NoopTracer, Box<dyn std::error::Error>>
+fn init_opentelemetry(
+    _config: &super::TelemetryConfig,
+) -> Result<opentelemetry::trace::noop::NoopTracer, Box<dyn std::error::Error>>
 {
     Ok(opentelemetry::trace::noop::NoopTracer::new())
 }
@@ -185,10 +261,39 @@ pub fn shutdown() {
 ///
 /// This layer provides enhanced request tracing with better logging
 /// and structured information for monitoring and debugging.
+#[must_use]
 pub fn create_http_trace_layer() -> tower_http::trace::TraceLayer<
     tower_http::classify::SharedClassifier<tower_http::classify::ServerErrorsAsFailures>,
+    RequestSpan,
 > {
-    tower_http::trace::TraceLayer::new_for_http()
+    tower_http::trace::TraceLayer::new_for_http().make_span_with(RequestSpan)
+}
+
+/// Span of an HTTP request: method, URI, version and the resolved client address
+///
+/// Every log line of the request, audit events included, carries these fields.
+/// The caller's `user_id`, `tenant_id` and `api_key_id` are recorded once
+/// authenticated (see [`crate::auth::spans`]).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RequestSpan;
+
+impl<B> tower_http::trace::MakeSpan<B> for RequestSpan {
+    fn make_span(&mut self, request: &axum::http::Request<B>) -> tracing::Span {
+        let client_ip = request
+            .extensions()
+            .get::<crate::client_ip::ClientIp>()
+            .map_or_else(|| "unknown".to_owned(), |crate::client_ip::ClientIp(ip)| ip.to_string());
+        tracing::info_span!(
+            "request",
+            method = %request.method(),
+            uri = %request.uri(),
+            version = ?request.version(),
+            client_ip,
+            user_id = tracing::field::Empty,
+            tenant_id = tracing::field::Empty,
+            api_key_id = tracing::field::Empty,
+        )
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,61 @@
+//! Validation configuration module
+
+use std::env;
+
+use serde::Serialize;
+
+/// User validation limits
+#[derive(Debug, Clone, Serialize)]
+pub struct ValidationConfig {
+    /// Longest accepted user name, in bytes (capped at the 255 the column holds)
+    pub name_max_length: usize,
+    /// Youngest accepted age
+    pub age_min: i32,
+    /// Oldest accepted age
+    pub age_max: i32,
+    /// Ages above this are accepted with a warning
+    pub unusual_age_above: i32,
+    /// Metadata keys every user must carry, comma-separated in `VALIDATION_REQUIRED_METADATA`
+    pub required_metadata_keys: Vec<String>,
+    /// Whether warnings (unusual ages, suspicious names) are rejected like errors
+    pub strict: bool,
+}
+
+impl ValidationConfig {
+    /// Load validation configuration from environment variables
+    #[must_use] pub fn load() -> Self {
+        let age_min = env::var("VALIDATION_AGE_MIN")
+            .unwrap_or_else(|_| "1".to_owned())
+            .parse()
+            .unwrap_or(1)
+            .max(0);
+        let age_max = env::var("VALIDATION_AGE_MAX")
+            .unwrap_or_else(|_| "150".to_owned())
+            .parse()
+            .unwrap_or(150)
+            .max(age_min);
+        Self {
+            name_max_length: env::var("VALIDATION_NAME_MAX_LENGTH")
+                .unwrap_or_else(|_| "100".to_owned())
+                .parse()
+                .unwrap_or(100)
+                .clamp(1, crate::user::validation::policy::NAME_COLUMN_LENGTH),
+            age_min,
+            age_max,
+            unusual_age_above: env::var("VALIDATION_UNUSUAL_AGE_ABOVE")
+                .unwrap_or_else(|_| "100".to_owned())
+                .parse()
+                .unwrap_or(100)
+                .clamp(age_min, age_max),
+            required_metadata_keys: env::var("VALIDATION_REQUIRED_METADATA")
+                .unwrap_or_default()
+                .split(',')
+                .map(str::trim)
+                .filter(|key| !key.is_empty())
+                .map(ToOwned::to_owned)
+                .collect(),
+            strict: env::var("VALIDATION_STRICT")
+                .is_ok_and(|value| matches!(value.as_str(), "true" | "1")),
+        }
+    }
+}
@@ -0,0 +1,30 @@
+//! Outgoing webhook configuration module
+
+use std::env;
+
+use serde::Serialize;
+
+/// Outgoing webhook configuration
+#[derive(Debug, Clone, Serialize)]
+pub struct WebhookConfig {
+    /// Milliseconds a receiver may take to answer a delivery before the attempt fails
+    pub timeout_ms: u64,
+    /// Seconds previous secrets keep signing deliveries after a secret rotation
+    pub secret_rotation_grace_secs: u64,
+}
+
+impl WebhookConfig {
+    /// Load webhook configuration from environment variables
+    #[must_use] pub fn load() -> Self {
+        Self {
+            timeout_ms: env::var("WEBHOOK_TIMEOUT_MS")
+                .unwrap_or_else(|_| "5000".to_owned())
+                .parse()
+                .unwrap_or(5000),
+            secret_rotation_grace_secs: env::var("WEBHOOK_SECRET_ROTATION_GRACE_SECS")
+                .unwrap_or_else(|_| "86400".to_owned())
+                .parse()
+                .unwrap_or(86_400),
+        }
+    }
+}
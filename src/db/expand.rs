@@ -0,0 +1,178 @@
+//! Expand/contract schema changes
+//!
+//! Renaming a column without downtime takes several deploys, because the old
+//! and the new version of the service run side by side during a blue/green
+//! switch:
+//!
+//! 1. **Expand**: a migration adds the new column, nullable.
+//! 2. **Dual write**: the service keeps writing the old column and mirrors
+//!    each write into the new one ([`ColumnRename::mirror`]), while a
+//!    [`BackfillJob`] copies the rows written before, or written by
+//!    instances that do not dual-write.
+//! 3. **Switch reads** to the new column once the backfill finds nothing left
+//!    to copy, still dual-writing so the previous version can take over again.
+//! 4. **Contract**: once no running version reads the old column, stop
+//!    dual-writing and drop it in a migration.
+//!
+//! `UserService` dual-writes a `users` column when `USER_DUAL_WRITE` names a
+//! rename (`name:full_name`), and `AppBuilder` then runs the backfill.
+
+use futures_util::future::BoxFuture;
+use sqlx::{PgExecutor, PgPool};
+use tracing::info;
+
+use crate::jobs::{Job, JobError};
+
+/// A table or column name that is not a plain lowercase SQL identifier
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("`{0}` is not a valid column rename (expected lowercase identifiers as `old:new`)")]
+pub struct InvalidRename(pub String);
+
+/// A column being renamed, copied from its old to its new name while both exist
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ColumnRename {
+    /// Table holding both columns
+    table: String,
+    /// Integer primary key of the table
+    key: String,
+    /// Column being renamed
+    from: String,
+    /// Column replacing it
+    to: String,
+}
+
+impl ColumnRename {
+    /// Describes the rename of `table.from` to `table.to`, rows keyed by the integer column `key`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error unless every name is a lowercase identifier, since
+    /// the names are spliced into SQL.
+    pub fn new(table: &str, key: &str, from: &str, to: &str) -> Result<Self, InvalidRename> {
+        let valid = |name: &str| {
+            name.starts_with(|c: char| c.is_ascii_lowercase() || c == '_')
+                && name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+        };
+        if [table, key, from, to].into_iter().all(valid) && from != to {
+            Ok(Self { table: table.to_owned(), key: key.to_owned(), from: from.to_owned(), to: to.to_owned() })
+        } else {
+            Err(InvalidRename(format!("{table}.{from}:{to}")))
+        }
+    }
+
+    /// Parses an `old:new` rename of a column of `table`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `spec` is not two valid identifiers separated by `:`.
+    pub fn parse(table: &str, key: &str, spec: &str) -> Result<Self, InvalidRename> {
+        let (from, to) = spec.split_once(':').ok_or_else(|| InvalidRename(spec.to_owned()))?;
+        Self::new(table, key, from.trim(), to.trim())
+    }
+
+    /// Old name of the column
+    #[must_use]
+    pub fn from(&self) -> &str {
+        &self.from
+    }
+
+    /// New name of the column
+    #[must_use]
+    pub fn to(&self) -> &str {
+        &self.to
+    }
+
+    /// Copies the old column into the new one for the rows keyed by `ids`
+    ///
+    /// Run on the executor of the write, so the copy joins its transaction.
+    pub async fn mirror<'e>(&self, executor: impl PgExecutor<'e>, ids: &[i32]) -> Result<u64, sqlx::Error> {
+        let Self { table, key, from, to } = self;
+        let sql = format!("UPDATE {table} SET {to} = {from} WHERE {key} = ANY($1) AND {to} IS DISTINCT FROM {from}");
+        Ok(sqlx::query(&sql).bind(ids).execute(executor).await?.rows_affected())
+    }
+
+    /// Copies the old column into the new one for up to `limit` rows where they differ
+    ///
+    /// Rows locked by a concurrent write are skipped; they are mirrored by
+    /// that write or copied by a later batch.
+    pub async fn backfill(&self, pool: &PgPool, limit: i64) -> Result<u64, sqlx::Error> {
+        let Self { table, key, from, to } = self;
+        let sql = format!(
+            "UPDATE {table} SET {to} = {from} WHERE {key} IN (
+                SELECT {key} FROM {table} WHERE {to} IS DISTINCT FROM {from}
+                ORDER BY {key} LIMIT $1 FOR UPDATE SKIP LOCKED
+             )"
+        );
+        Ok(sqlx::query(&sql).bind(limit).execute(pool).await?.rows_affected())
+    }
+}
+
+/// Background job copying not yet mirrored rows of a renamed column in batches
+pub struct BackfillJob {
+    /// Database holding the table
+    pool: PgPool,
+    /// Column being renamed
+    rename: ColumnRename,
+    /// Rows copied per statement
+    batch_size: i64,
+}
+
+impl BackfillJob {
+    /// Backfills `rename` in batches of `batch_size` rows
+    #[must_use]
+    pub fn new(pool: PgPool, rename: ColumnRename, batch_size: u32) -> Self {
+        Self { pool, rename, batch_size: i64::from(batch_size.max(1)) }
+    }
+
+    /// Copies batches until one comes back short; returns the rows copied
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a batch fails; batches copied before are kept.
+    pub async fn backfill(&self) -> Result<u64, sqlx::Error> {
+        let mut copied = 0;
+        loop {
+            let batch = self.rename.backfill(&self.pool, self.batch_size).await?;
+            copied += batch;
+            if batch < self.batch_size.unsigned_abs() {
+                return Ok(copied);
+            }
+        }
+    }
+}
+
+impl Job for BackfillJob {
+    fn name(&self) -> &'static str {
+        "column-backfill"
+    }
+
+    fn run(&self) -> BoxFuture<'_, Result<(), JobError>> {
+        Box::pin(async move {
+            let copied = self.backfill().await?;
+            info!(
+                target: "metrics",
+                backfill_table = %self.rename.table,
+                backfill_column = %self.rename.to,
+                backfill_copied = copied,
+                "Column backfill"
+            );
+            Ok(())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_only_plain_identifiers_are_accepted() {
+        let rename = ColumnRename::parse("users", "id", "name:full_name").unwrap();
+
+        assert_eq!((rename.from(), rename.to()), ("name", "full_name"));
+        assert!(ColumnRename::parse("users", "id", "name").is_err());
+        assert!(ColumnRename::parse("users", "id", "name:name").is_err());
+        assert!(ColumnRename::parse("users", "id", "name:x; DROP TABLE users").is_err());
+        assert!(ColumnRename::parse("users", "id", "Name:full_name").is_err());
+    }
+}
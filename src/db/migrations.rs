@@ -0,0 +1,205 @@
+//! Schema migrations, one directory per domain
+//!
+//! Each vertical slice keeps its migrations in its own directory under
+//! `./migrations` (`user`, `bank`, `audit`, `partner`, `admin`, `jobs`, `webhook`), embedded at compile time. A
+//! [`Migrations`] set merges the directories it includes into one history
+//! ordered by version, whatever order they were added in, and rejects two
+//! migrations claiming the same version. Versions are timestamps, so a
+//! slice's migrations run after those of the slices it references: bank and
+//! audit tables reference `users`, so both need the `user` slice. Forks drop
+//! a slice with [`Migrations::without`] or add their own with
+//! [`Migrations::with`].
+
+use std::collections::HashMap;
+
+use futures_util::future::BoxFuture;
+use sqlx::{
+    PgPool,
+    error::BoxDynError,
+    migrate::{MigrateError, Migration, MigrationSource, Migrator},
+};
+
+/// Users, addresses, tags and external identities
+pub static USER_MIGRATIONS: Migrator = sqlx::migrate!("./migrations/user");
+
+/// Accounts, ledger and beneficiaries
+pub static BANK_MIGRATIONS: Migrator = sqlx::migrate!("./migrations/bank");
+
+/// Audit events, user history, change log, data exports and retention indexes
+pub static AUDIT_MIGRATIONS: Migrator = sqlx::migrate!("./migrations/audit");
+
+/// Partner request signing keys
+pub static PARTNER_MIGRATIONS: Migrator = sqlx::migrate!("./migrations/partner");
+
+/// Scratch table of the admin self-test
+pub static ADMIN_MIGRATIONS: Migrator = sqlx::migrate!("./migrations/admin");
+
+/// Durable job queue
+pub static JOBS_MIGRATIONS: Migrator = sqlx::migrate!("./migrations/jobs");
+
+/// Webhook subscriptions, signing secrets and delivery attempts
+pub static WEBHOOK_MIGRATIONS: Migrator = sqlx::migrate!("./migrations/webhook");
+
+/// Two domains claiming the same migration version
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("Migration version {version} is claimed by both `{first}` and `{second}`")]
+pub struct DuplicateVersion {
+    /// The contested version
+    pub version: i64,
+    /// Domain that claimed it first, in version order
+    pub first: &'static str,
+    /// Domain that claimed it again
+    pub second: &'static str,
+}
+
+/// Set of per-domain migrations to apply together
+#[derive(Debug, Clone, Default)]
+pub struct Migrations {
+    /// Migrations of each included domain, in the order the domains were added
+    domains: Vec<(&'static str, Vec<Migration>)>,
+}
+
+impl Migrations {
+    /// Creates an empty set
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The migrations of every built-in slice
+    #[must_use]
+    pub fn builtin() -> Self {
+        Self::new()
+            .with("user", &USER_MIGRATIONS)
+            .with("bank", &BANK_MIGRATIONS)
+            .with("audit", &AUDIT_MIGRATIONS)
+            .with("partner", &PARTNER_MIGRATIONS)
+            .with("admin", &ADMIN_MIGRATIONS)
+            .with("jobs", &JOBS_MIGRATIONS)
+            .with("webhook", &WEBHOOK_MIGRATIONS)
+    }
+
+    /// Includes the migrations of `domain`, replacing any already included under that name
+    #[must_use]
+    pub fn with(self, domain: &'static str, migrator: &Migrator) -> Self {
+        let mut migrations = self.without(domain);
+        migrations.domains.push((domain, migrator.iter().cloned().collect()));
+        migrations
+    }
+
+    /// Leaves out the migrations of `domain`
+    #[must_use]
+    pub fn without(mut self, domain: &str) -> Self {
+        self.domains.retain(|(name, _)| *name != domain);
+        self
+    }
+
+    /// Names of the included domains
+    pub fn domains(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.domains.iter().map(|(name, _)| *name)
+    }
+
+    /// All included migrations in version order
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if two migrations share a version.
+    pub fn merged(&self) -> Result<Vec<Migration>, DuplicateVersion> {
+        let mut merged: Vec<(&'static str, &Migration)> = self
+            .domains
+            .iter()
+            .flat_map(|(domain, migrations)| migrations.iter().map(move |migration| (*domain, migration)))
+            .collect();
+        merged.sort_by_key(|(domain, migration)| (migration.version, *domain));
+
+        let mut owners = HashMap::new();
+        for (domain, migration) in &merged {
+            // The up and down scripts of a reversible migration share their version
+            let key = (migration.version, migration.migration_type.is_down_migration());
+            if let Some(first) = owners.insert(key, *domain) {
+                return Err(DuplicateVersion { version: migration.version, first, second: domain });
+            }
+        }
+        Ok(merged.into_iter().map(|(_, migration)| migration.clone()).collect())
+    }
+
+    /// Applies the pending migrations of the set to `pool`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if two migrations share a version, an applied
+    /// migration was changed or is not part of the set, or a migration fails.
+    pub async fn run(&self, pool: &PgPool) -> Result<(), MigrateError> {
+        Migrator::new(self).await?.run(pool).await
+    }
+}
+
+impl<'s> MigrationSource<'s> for &'s Migrations {
+    fn resolve(self) -> BoxFuture<'s, Result<Vec<Migration>, BoxDynError>> {
+        let merged = self.merged().map_err(BoxDynError::from);
+        Box::pin(async move { merged })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_domains_merge_in_version_order() {
+        let merged = Migrations::builtin().merged().unwrap();
+        let reordered = Migrations::new()
+            .with("webhook", &WEBHOOK_MIGRATIONS)
+            .with("jobs", &JOBS_MIGRATIONS)
+            .with("admin", &ADMIN_MIGRATIONS)
+            .with("partner", &PARTNER_MIGRATIONS)
+            .with("audit", &AUDIT_MIGRATIONS)
+            .with("bank", &BANK_MIGRATIONS)
+            .with("user", &USER_MIGRATIONS)
+            .merged()
+            .unwrap();
+
+        let versions: Vec<i64> = merged.iter().map(|migration| migration.version).collect();
+        assert!(versions.is_sorted());
+        assert_eq!(
+            merged.len(),
+            USER_MIGRATIONS.iter().count()
+                + BANK_MIGRATIONS.iter().count()
+                + AUDIT_MIGRATIONS.iter().count()
+                + PARTNER_MIGRATIONS.iter().count()
+                + ADMIN_MIGRATIONS.iter().count()
+                + JOBS_MIGRATIONS.iter().count()
+                + WEBHOOK_MIGRATIONS.iter().count()
+        );
+        assert_eq!(reordered.iter().map(|migration| migration.version).collect::<Vec<_>>(), versions);
+        assert_eq!(versions.first(), USER_MIGRATIONS.iter().next().map(|migration| migration.version).as_ref());
+    }
+
+    #[test]
+    fn test_domains_can_be_left_out_or_replaced() {
+        let without_bank = Migrations::builtin().without("bank");
+        let replaced = Migrations::builtin().with("bank", &BANK_MIGRATIONS);
+
+        assert_eq!(without_bank.domains().collect::<Vec<_>>(), ["user", "audit", "partner", "admin", "jobs", "webhook"]);
+        assert_eq!(
+            without_bank.merged().unwrap().len(),
+            USER_MIGRATIONS.iter().count()
+                + AUDIT_MIGRATIONS.iter().count()
+                + PARTNER_MIGRATIONS.iter().count()
+                + ADMIN_MIGRATIONS.iter().count()
+                + JOBS_MIGRATIONS.iter().count()
+                + WEBHOOK_MIGRATIONS.iter().count()
+        );
+        assert_eq!(replaced.domains().collect::<Vec<_>>(), ["user", "audit", "partner", "admin", "jobs", "webhook", "bank"]);
+    }
+
+    #[test]
+    fn test_shared_versions_are_rejected() {
+        let clash = Migrations::new().with("user", &USER_MIGRATIONS).with("fork", &USER_MIGRATIONS);
+
+        let error = clash.merged().unwrap_err();
+
+        assert_eq!((error.first, error.second), ("fork", "user"));
+        assert_eq!(Some(error.version), USER_MIGRATIONS.iter().next().map(|migration| migration.version));
+    }
+}
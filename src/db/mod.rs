@@ -0,0 +1,214 @@
+//! Database error classification
+//!
+//! A failed `sqlx` call becomes a [`DbError`] that keeps the class of the
+//! failure next to its message. Constraint violations are the client's
+//! problem (409 for duplicates, 422 for dangling references), lost
+//! connections and exhausted pools are temporary (503), and serialization
+//! failures and deadlocks succeed when retried; only the rest are reported as
+//! 500. Temporary and retryable failures carry a `Retry-After` hint.
+//! Transactions that lost a race can instead be re-run on the spot with
+//! [`retry`]. Schema migrations are kept per domain and merged by
+//! [`Migrations`]; [`expand`] supports renaming columns without downtime.
+//! [`connect`] opens the pool, reporting unreachable databases as errors;
+//! [`connect_lazy`] defers connecting to first use. [`acquire`] warns with
+//! the pool's [`PoolStats`] when waiting for a connection was slow.
+
+use std::time::Duration;
+
+use axum::{
+    Json,
+    http::{HeaderValue, StatusCode, header},
+    response::{IntoResponse, Response},
+};
+
+use crate::error_codes::{ErrorCode, ErrorResponse};
+use crate::metrics::DB_FAILURES;
+
+pub mod expand;
+mod migrations;
+mod pool;
+mod retry;
+
+pub use expand::{BackfillJob, ColumnRename, InvalidRename};
+pub use migrations::{
+    ADMIN_MIGRATIONS, AUDIT_MIGRATIONS, BANK_MIGRATIONS, DuplicateVersion, JOBS_MIGRATIONS, Migrations, PARTNER_MIGRATIONS, USER_MIGRATIONS, WEBHOOK_MIGRATIONS,
+};
+pub use pool::{PoolStats, acquire, connect, connect_lazy};
+pub use retry::{RetryCounts, RetryPolicy, RetryStats, Retryable, retry};
+
+/// How long clients are advised to wait before retrying a transient failure
+pub const RETRY_AFTER: Duration = Duration::from_secs(1);
+
+/// Class of a database failure
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DbErrorKind {
+    /// A unique constraint rejected a duplicate
+    Conflict,
+    /// A foreign key or check constraint rejected the data
+    UnprocessableEntity,
+    /// The database could not be reached or has no capacity left
+    ServiceUnavailable,
+    /// The transaction lost a serialization race or deadlock; retrying succeeds
+    Retryable,
+    /// Any other failure
+    Other,
+}
+
+impl DbErrorKind {
+    /// Classifies a `sqlx` error
+    #[must_use]
+    pub fn of(error: &sqlx::Error) -> Self {
+        if let sqlx::Error::Database(db) = error {
+            return match db.code().as_deref() {
+                // serialization_failure, deadlock_detected
+                Some("40001" | "40P01") => Self::Retryable,
+                // connection exceptions, too_many_connections, operator intervention
+                Some(code) if code.starts_with("08") || code == "53300" || code.starts_with("57P") => {
+                    Self::ServiceUnavailable
+                }
+                _ if db.is_unique_violation() => Self::Conflict,
+                _ if db.is_foreign_key_violation() || db.is_check_violation() => Self::UnprocessableEntity,
+                _ => Self::Other,
+            };
+        }
+        if matches!(
+            error,
+            sqlx::Error::Io(_) | sqlx::Error::Tls(_) | sqlx::Error::PoolTimedOut | sqlx::Error::PoolClosed
+        ) {
+            Self::ServiceUnavailable
+        } else {
+            Self::Other
+        }
+    }
+
+    /// HTTP status reporting this class of failure
+    #[must_use]
+    pub const fn status(self) -> StatusCode {
+        match self {
+            Self::Conflict => StatusCode::CONFLICT,
+            Self::UnprocessableEntity => StatusCode::UNPROCESSABLE_ENTITY,
+            Self::ServiceUnavailable | Self::Retryable => StatusCode::SERVICE_UNAVAILABLE,
+            Self::Other => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    /// Value of the `kind` label of the `db_failures_total` metric
+    #[must_use]
+    pub const fn as_label(self) -> &'static str {
+        match self {
+            Self::Conflict => "conflict",
+            Self::UnprocessableEntity => "unprocessable_entity",
+            Self::ServiceUnavailable => "service_unavailable",
+            Self::Retryable => "retryable",
+            Self::Other => "other",
+        }
+    }
+
+    /// Whether the same request may succeed when retried unchanged
+    #[must_use]
+    pub const fn is_transient(self) -> bool {
+        matches!(self, Self::ServiceUnavailable | Self::Retryable)
+    }
+
+    /// Machine-readable code reported for this class of failure
+    const fn code(self) -> ErrorCode {
+        match self {
+            Self::Conflict => ErrorCode::Conflict,
+            Self::UnprocessableEntity => ErrorCode::ConstraintViolation,
+            Self::ServiceUnavailable => ErrorCode::DatabaseUnavailable,
+            Self::Retryable => ErrorCode::ConcurrentUpdate,
+            Self::Other => ErrorCode::InternalError,
+        }
+    }
+
+    /// Client-facing message, free of database details
+    const fn message(self) -> &'static str {
+        match self {
+            Self::Conflict => "The request conflicts with existing data",
+            Self::UnprocessableEntity => "The request references data that does not exist or is not allowed",
+            Self::ServiceUnavailable => "The database is temporarily unavailable",
+            Self::Retryable => "The request conflicted with a concurrent one; retry it",
+            Self::Other => "Internal server error",
+        }
+    }
+}
+
+/// A failed database operation and its class
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("{message}")]
+pub struct DbError {
+    /// Class of the failure
+    kind: DbErrorKind,
+    /// Underlying error message, for logs only
+    message: String,
+}
+
+impl DbError {
+    /// Creates an error of the given class
+    #[must_use]
+    pub fn new(kind: DbErrorKind, message: impl Into<String>) -> Self {
+        Self { kind, message: message.into() }
+    }
+
+    /// Creates an unclassified error
+    #[must_use]
+    pub fn other(message: impl Into<String>) -> Self {
+        Self::new(DbErrorKind::Other, message)
+    }
+
+    /// Class of the failure
+    #[must_use]
+    pub const fn kind(&self) -> DbErrorKind {
+        self.kind
+    }
+}
+
+impl From<sqlx::Error> for DbError {
+    /// Classifies `error`, counting it in the `db_failures_total` metric
+    fn from(error: sqlx::Error) -> Self {
+        let kind = DbErrorKind::of(&error);
+        DB_FAILURES.increment(&[("kind", kind.as_label())]);
+        Self::new(kind, error.to_string())
+    }
+}
+
+impl IntoResponse for DbError {
+    fn into_response(self) -> Response {
+        let mut response = (
+            self.kind.status(),
+            Json(ErrorResponse::new(self.kind.code(), self.kind.message())),
+        )
+            .into_response();
+        if self.kind.is_transient() {
+            response
+                .headers_mut()
+                .insert(header::RETRY_AFTER, HeaderValue::from(RETRY_AFTER.as_secs()));
+        }
+        response
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_connection_failures_are_transient() {
+        assert_eq!(DbErrorKind::of(&sqlx::Error::PoolTimedOut), DbErrorKind::ServiceUnavailable);
+        assert_eq!(DbErrorKind::of(&sqlx::Error::PoolClosed), DbErrorKind::ServiceUnavailable);
+        assert_eq!(DbErrorKind::of(&sqlx::Error::RowNotFound), DbErrorKind::Other);
+        assert!(DbErrorKind::Retryable.is_transient());
+        assert!(!DbErrorKind::Conflict.is_transient());
+    }
+
+    #[test]
+    fn test_response_hides_details_and_hints_retries() {
+        let transient = DbError::from(sqlx::Error::PoolTimedOut).into_response();
+        let failed = DbError::other("relation \"users\" does not exist").into_response();
+
+        assert_eq!(transient.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(transient.headers()[header::RETRY_AFTER], "1");
+        assert_eq!(failed.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert!(failed.headers().get(header::RETRY_AFTER).is_none());
+    }
+}
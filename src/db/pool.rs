@@ -0,0 +1,174 @@
+//! Connection pool construction
+//!
+//! Pool sizing, timeouts and connection recycling come from
+//! [`DatabaseConfig`]. Every acquisition slower than `slow_acquire_ms` is
+//! logged by sqlx (target `sqlx::pool::acquire`); [`acquire`] additionally
+//! reports the pool's [`PoolStats`], telling an exhausted pool (`idle: 0`,
+//! `size` at `max_connections`) from a slow database.
+
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use sqlx::pool::PoolConnection;
+use sqlx::postgres::PgPoolOptions;
+use sqlx::{PgPool, Postgres};
+use tracing::warn;
+
+use crate::config::DatabaseConfig;
+
+/// Connection counts of a pool at one point in time
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct PoolStats {
+    /// Open connections, idle or in use
+    pub size: u32,
+    /// Open connections waiting to be acquired
+    pub idle: u32,
+    /// Most connections the pool opens
+    pub max_connections: u32,
+}
+
+impl PoolStats {
+    /// Current counts of `pool`
+    #[must_use]
+    pub fn of(pool: &PgPool) -> Self {
+        Self {
+            size: pool.size(),
+            idle: u32::try_from(pool.num_idle()).unwrap_or(u32::MAX),
+            max_connections: pool.options().get_max_connections(),
+        }
+    }
+
+    /// Connections currently acquired
+    #[must_use]
+    pub const fn in_use(&self) -> u32 {
+        self.size.saturating_sub(self.idle)
+    }
+}
+
+/// Pool options for `config`
+fn pool_options(config: &DatabaseConfig) -> PgPoolOptions {
+    let seconds = |secs: u64| (secs > 0).then_some(Duration::from_secs(secs));
+    // A threshold no acquisition reaches turns sqlx's slow acquisition warning off
+    let slow_acquire = match config.slow_acquire_ms {
+        0 => Duration::MAX,
+        ms => Duration::from_millis(ms),
+    };
+    PgPoolOptions::new()
+        .max_connections(config.max_connections)
+        .min_connections(config.min_connections.min(config.max_connections))
+        .acquire_timeout(Duration::from_secs(config.acquire_timeout_secs))
+        .idle_timeout(seconds(config.idle_timeout_secs))
+        .max_lifetime(seconds(config.max_lifetime_secs))
+        .acquire_slow_threshold(slow_acquire)
+}
+
+/// Opens a connection pool to `config.url`
+///
+/// # Errors
+///
+/// Returns an error if the URL is malformed or the database cannot be reached.
+pub async fn connect(config: &DatabaseConfig) -> Result<PgPool, sqlx::Error> {
+    pool_options(config).connect(&config.url).await
+}
+
+/// Creates a connection pool to `config.url` without connecting
+///
+/// Connections are opened on first use, so startup does not wait for the
+/// database: suited to serverless runtimes, where cold starts are billed and
+/// the database may not be needed by every invocation.
+///
+/// # Errors
+///
+/// Returns an error if the URL is malformed.
+pub fn connect_lazy(config: &DatabaseConfig) -> Result<PgPool, sqlx::Error> {
+    pool_options(config).connect_lazy(&config.url)
+}
+
+/// Acquires a connection from `pool`, warning with the pool's statistics when it took longer than `slow_after`
+///
+/// # Errors
+///
+/// Returns an error if no connection could be acquired within the pool's acquire timeout.
+pub async fn acquire(pool: &PgPool, slow_after: Option<Duration>) -> Result<PoolConnection<Postgres>, sqlx::Error> {
+    let started = Instant::now();
+    let connection = pool.acquire().await?;
+    let waited = started.elapsed();
+    if slow_after.is_some_and(|threshold| waited > threshold) {
+        let stats = PoolStats::of(pool);
+        warn!(
+            waited_ms = u64::try_from(waited.as_millis()).unwrap_or(u64::MAX),
+            size = stats.size,
+            idle = stats.idle,
+            in_use = stats.in_use(),
+            max_connections = stats.max_connections,
+            "Slow database connection acquisition"
+        );
+    }
+    Ok(connection)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(url: &str) -> DatabaseConfig {
+        DatabaseConfig {
+            url: url.to_owned(),
+            max_connections: 1,
+            min_connections: 0,
+            acquire_timeout_secs: 30,
+            idle_timeout_secs: 600,
+            max_lifetime_secs: 1800,
+            slow_acquire_ms: 1000,
+            run_migrations: false,
+            monitor_interval_secs: 0,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_malformed_url_is_an_error() {
+        let config = config("not a url");
+
+        assert!(connect(&config).await.is_err());
+        assert!(connect_lazy(&config).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_lazy_pool_does_not_connect() {
+        let pool = connect_lazy(&config("postgres://lazy@127.0.0.1:1/lazy")).unwrap();
+
+        assert_eq!(pool.size(), 0);
+        assert_eq!(PoolStats::of(&pool), PoolStats { size: 0, idle: 0, max_connections: 1 });
+    }
+
+    #[tokio::test]
+    async fn test_tuning_options_are_applied() {
+        let config = DatabaseConfig {
+            max_connections: 8,
+            min_connections: 2,
+            acquire_timeout_secs: 3,
+            idle_timeout_secs: 0,
+            max_lifetime_secs: 60,
+            slow_acquire_ms: 250,
+            ..config("postgres://lazy@127.0.0.1:1/lazy")
+        };
+
+        let pool = connect_lazy(&config).unwrap();
+        let options = pool.options();
+
+        assert_eq!((options.get_max_connections(), options.get_min_connections()), (8, 2));
+        assert_eq!(options.get_acquire_timeout(), Duration::from_secs(3));
+        assert_eq!(options.get_idle_timeout(), None, "0 should keep idle connections open");
+        assert_eq!(options.get_max_lifetime(), Some(Duration::from_mins(1)));
+        assert_eq!(options.get_acquire_slow_threshold(), Duration::from_millis(250));
+    }
+
+    #[tokio::test]
+    async fn test_min_connections_are_capped_at_the_maximum() {
+        let config = DatabaseConfig { min_connections: 5, ..config("postgres://lazy@127.0.0.1:1/lazy") };
+
+        let pool = connect_lazy(&config).unwrap();
+
+        assert_eq!(pool.options().get_min_connections(), 1);
+    }
+}
@@ -0,0 +1,191 @@
+//! Retries of transactions that lost a race
+//!
+//! Postgres aborts one side of a serialization conflict or deadlock; running
+//! the transaction again from the start succeeds once the other side has
+//! committed. [`retry`] re-runs such transactions with jittered exponential
+//! backoff, so concurrent attempts do not collide again in lockstep, and
+//! counts retries in a shared [`RetryStats`].
+
+use std::{
+    future::Future,
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::Duration,
+};
+
+use serde::Serialize;
+use tracing::warn;
+use utoipa::ToSchema;
+
+use super::{DbError, DbErrorKind};
+
+/// Errors that can tell whether running the transaction again may succeed
+pub trait Retryable {
+    /// Whether the failed transaction lost a serialization race or deadlock
+    fn is_retryable(&self) -> bool;
+}
+
+impl Retryable for DbError {
+    fn is_retryable(&self) -> bool {
+        self.kind() == DbErrorKind::Retryable
+    }
+}
+
+/// How often and how patiently a transaction is retried
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Attempts in total, including the first (1 disables retries)
+    pub max_attempts: u32,
+    /// Upper bound of the delay before the first retry; it doubles with each retry
+    pub base_delay: Duration,
+}
+
+impl RetryPolicy {
+    /// Retries up to `max_attempts` attempts in total with the default backoff
+    #[must_use]
+    pub fn attempts(max_attempts: u32) -> Self {
+        Self { max_attempts: max_attempts.max(1), ..Self::default() }
+    }
+
+    /// Random delay before retry number `retry` (starting at 1)
+    fn delay(self, retry: u32) -> Duration {
+        let cap = self.base_delay.saturating_mul(1 << retry.saturating_sub(1).min(16));
+        cap.mul_f64(rand::random::<f64>())
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self { max_attempts: 3, base_delay: Duration::from_millis(20) }
+    }
+}
+
+/// Retry counts since the process started
+#[derive(Serialize, ToSchema, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryCounts {
+    /// Transactions run again after a serialization failure or deadlock
+    pub retries: u64,
+    /// Transactions that still failed after their last attempt
+    pub exhausted: u64,
+}
+
+/// Shared retry counters
+///
+/// Clones share the same counters.
+#[derive(Debug, Clone, Default)]
+pub struct RetryStats {
+    retries: Arc<AtomicU64>,
+    exhausted: Arc<AtomicU64>,
+}
+
+impl RetryStats {
+    /// Current counts
+    #[must_use]
+    pub fn snapshot(&self) -> RetryCounts {
+        RetryCounts {
+            retries: self.retries.load(Ordering::Relaxed),
+            exhausted: self.exhausted.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Runs `transaction` until it succeeds, fails for good, or runs out of attempts
+///
+/// `transaction` must begin a fresh transaction on each call; only errors
+/// reporting [`Retryable::is_retryable`] are retried.
+pub async fn retry<T, E, F, Fut>(policy: RetryPolicy, stats: &RetryStats, operation: &'static str, mut transaction: F) -> Result<T, E>
+where
+    E: Retryable + std::fmt::Display,
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+{
+    let mut attempt = 1;
+    loop {
+        match transaction().await {
+            Err(e) if e.is_retryable() => {
+                if attempt >= policy.max_attempts {
+                    stats.exhausted.fetch_add(1, Ordering::Relaxed);
+                    warn!(error = %e, operation, attempt, "Transaction conflicted on its last attempt");
+                    return Err(e);
+                }
+                stats.retries.fetch_add(1, Ordering::Relaxed);
+                let delay = policy.delay(attempt);
+                warn!(error = %e, operation, attempt, ?delay, "Transaction conflicted; retrying");
+                tokio::time::sleep(delay).await;
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::AtomicU32;
+
+    use super::*;
+
+    fn conflict() -> DbError {
+        DbError::new(DbErrorKind::Retryable, "could not serialize access")
+    }
+
+    fn policy(max_attempts: u32) -> RetryPolicy {
+        RetryPolicy { max_attempts, base_delay: Duration::ZERO }
+    }
+
+    #[tokio::test]
+    async fn test_retries_conflicts_until_success() {
+        let stats = RetryStats::default();
+        let calls = AtomicU32::new(0);
+
+        let result = retry(policy(3), &stats, "test", || async {
+            if calls.fetch_add(1, Ordering::Relaxed) < 2 { Err(conflict()) } else { Ok("done") }
+        })
+        .await;
+
+        assert_eq!(result, Ok("done"));
+        assert_eq!(stats.snapshot(), RetryCounts { retries: 2, exhausted: 0 });
+    }
+
+    #[tokio::test]
+    async fn test_gives_up_after_max_attempts() {
+        let stats = RetryStats::default();
+        let calls = AtomicU32::new(0);
+
+        let result: Result<(), _> = retry(policy(2), &stats, "test", || async {
+            calls.fetch_add(1, Ordering::Relaxed);
+            Err(conflict())
+        })
+        .await;
+
+        assert_eq!(result, Err(conflict()));
+        assert_eq!(calls.load(Ordering::Relaxed), 2);
+        assert_eq!(stats.snapshot(), RetryCounts { retries: 1, exhausted: 1 });
+    }
+
+    #[tokio::test]
+    async fn test_other_errors_are_not_retried() {
+        let stats = RetryStats::default();
+        let calls = AtomicU32::new(0);
+
+        let result: Result<(), _> = retry(policy(3), &stats, "test", || async {
+            calls.fetch_add(1, Ordering::Relaxed);
+            Err(DbError::new(DbErrorKind::Conflict, "duplicate key"))
+        })
+        .await;
+
+        assert!(result.is_err());
+        assert_eq!(calls.load(Ordering::Relaxed), 1);
+        assert_eq!(stats.snapshot(), RetryCounts { retries: 0, exhausted: 0 });
+    }
+
+    #[test]
+    fn test_delay_is_bounded_by_doubling_cap() {
+        let policy = RetryPolicy { max_attempts: 5, base_delay: Duration::from_millis(10) };
+
+        assert!(policy.delay(1) <= Duration::from_millis(10));
+        assert!(policy.delay(3) <= Duration::from_millis(40));
+    }
+}
@@ -0,0 +1,221 @@
+//! Route deprecation
+//!
+//! Routes being phased out are declared once in a [`Deprecations`] registry.
+//! The router applies [`deprecation_headers`] as a route layer, which adds the
+//! `Deprecation` (RFC 9745), `Sunset` (RFC 8594) and successor `Link` headers
+//! to responses of deprecated routes, and the same registry marks their
+//! operations `deprecated: true` in the `OpenAPI` document.
+
+use std::{collections::HashMap, sync::Arc};
+
+use axum::{
+    extract::{MatchedPath, Request, State},
+    http::{HeaderName, HeaderValue, header},
+    middleware::Next,
+    response::Response,
+};
+use chrono::{DateTime, Utc};
+use utoipa::openapi::{Deprecated, OpenApi};
+
+/// `Deprecation` response header (RFC 9745)
+const DEPRECATION: HeaderName = HeaderName::from_static("deprecation");
+/// `Sunset` response header (RFC 8594)
+const SUNSET: HeaderName = HeaderName::from_static("sunset");
+
+/// Deprecation details of a single route
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Deprecation {
+    since: Option<DateTime<Utc>>,
+    sunset: Option<DateTime<Utc>>,
+    successor: Option<String>,
+}
+
+impl Deprecation {
+    /// Creates a deprecation without dates or successor (`Deprecation: true`)
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// When the route was deprecated
+    #[must_use]
+    pub const fn since(mut self, since: DateTime<Utc>) -> Self {
+        self.since = Some(since);
+        self
+    }
+
+    /// When the route will stop responding
+    #[must_use]
+    pub const fn sunset(mut self, sunset: DateTime<Utc>) -> Self {
+        self.sunset = Some(sunset);
+        self
+    }
+
+    /// URL of the route replacing this one
+    #[must_use]
+    pub fn successor(mut self, successor: impl Into<String>) -> Self {
+        self.successor = Some(successor.into());
+        self
+    }
+
+    /// Response headers announcing this deprecation
+    #[must_use]
+    pub fn headers(&self) -> Vec<(HeaderName, HeaderValue)> {
+        let deprecation = self
+            .since
+            .map_or_else(|| "true".to_owned(), |since| format!("@{}", since.timestamp()));
+
+        let mut headers = Vec::new();
+        if let Ok(value) = HeaderValue::from_str(&deprecation) {
+            headers.push((DEPRECATION, value));
+        }
+        if let Some(sunset) = self.sunset
+            && let Ok(value) = HeaderValue::from_str(&sunset.format("%a, %d %b %Y %H:%M:%S GMT").to_string())
+        {
+            headers.push((SUNSET, value));
+        }
+        if let Some(successor) = &self.successor
+            && let Ok(value) = HeaderValue::from_str(&format!("<{successor}>; rel=\"successor-version\""))
+        {
+            headers.push((header::LINK, value));
+        }
+        headers
+    }
+}
+
+/// Registry of deprecated routes, keyed by their route pattern (e.g. `/users/{id}`)
+#[derive(Debug, Clone, Default)]
+pub struct Deprecations {
+    routes: Arc<HashMap<String, Deprecation>>,
+}
+
+impl Deprecations {
+    /// Creates a registry from `(route pattern, deprecation)` pairs
+    #[must_use]
+    pub fn new(routes: impl IntoIterator<Item = (String, Deprecation)>) -> Self {
+        Self {
+            routes: Arc::new(routes.into_iter().collect()),
+        }
+    }
+
+    /// Deprecation of a route pattern, if any
+    #[must_use]
+    pub fn get(&self, path: &str) -> Option<&Deprecation> {
+        self.routes.get(path)
+    }
+
+    /// Marks every operation of the deprecated routes as `deprecated: true`
+    pub fn apply_to_openapi(&self, openapi: &mut OpenApi) {
+        for path in self.routes.keys() {
+            let Some(item) = openapi.paths.paths.get_mut(path) else {
+                continue;
+            };
+            let operations = [
+                &mut item.get,
+                &mut item.put,
+                &mut item.post,
+                &mut item.delete,
+                &mut item.options,
+                &mut item.head,
+                &mut item.patch,
+                &mut item.trace,
+            ];
+            for operation in operations.into_iter().flatten() {
+                operation.deprecated = Some(Deprecated::True);
+            }
+        }
+    }
+}
+
+/// Route-layer middleware adding deprecation headers to responses of deprecated routes
+pub async fn deprecation_headers(
+    State(deprecations): State<Deprecations>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let deprecation = request
+        .extensions()
+        .get::<MatchedPath>()
+        .and_then(|path| deprecations.get(path.as_str()))
+        .cloned();
+
+    let mut response = next.run(request).await;
+    if let Some(deprecation) = deprecation {
+        response.headers_mut().extend(deprecation.headers());
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{Router, body::Body, middleware, routing::get};
+    use chrono::TimeZone;
+    use tower::ServiceExt;
+
+    fn sample() -> Deprecation {
+        Deprecation::new()
+            .since(Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap())
+            .sunset(Utc.with_ymd_and_hms(2025, 12, 31, 23, 59, 59).unwrap())
+            .successor("/api/v1/users")
+    }
+
+    #[test]
+    fn test_headers() {
+        let headers = sample().headers();
+
+        assert_eq!(headers[0], (DEPRECATION, HeaderValue::from_static("@1735689600")));
+        assert_eq!(headers[1], (SUNSET, HeaderValue::from_static("Wed, 31 Dec 2025 23:59:59 GMT")));
+        assert_eq!(
+            headers[2],
+            (header::LINK, HeaderValue::from_static("</api/v1/users>; rel=\"successor-version\""))
+        );
+    }
+
+    #[test]
+    fn test_headers_without_details() {
+        assert_eq!(Deprecation::new().headers(), vec![(DEPRECATION, HeaderValue::from_static("true"))]);
+    }
+
+    #[tokio::test]
+    async fn test_middleware_only_marks_deprecated_routes() {
+        let deprecations = Deprecations::new([("/old/{id}".to_owned(), sample())]);
+        let app = Router::new()
+            .route("/old/{id}", get(|| async { "old" }))
+            .route("/new/{id}", get(|| async { "new" }))
+            .route_layer(middleware::from_fn_with_state(deprecations, deprecation_headers));
+
+        let old = app
+            .clone()
+            .oneshot(Request::get("/old/1").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let new = app
+            .oneshot(Request::get("/new/1").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+
+        assert_eq!(old.headers()[DEPRECATION], "@1735689600");
+        assert!(old.headers().contains_key(SUNSET));
+        assert!(!new.headers().contains_key(DEPRECATION));
+    }
+
+    #[test]
+    fn test_apply_to_openapi() {
+        let mut openapi: OpenApi = serde_json::from_value(serde_json::json!({
+            "openapi": "3.1.0",
+            "info": { "title": "test", "version": "1" },
+            "paths": {
+                "/old": { "get": { "responses": {} } },
+                "/new": { "get": { "responses": {} } }
+            }
+        }))
+        .unwrap();
+
+        Deprecations::new([("/old".to_owned(), Deprecation::new())]).apply_to_openapi(&mut openapi);
+
+        let doc = serde_json::to_value(&openapi).unwrap();
+        assert_eq!(doc["paths"]["/old"]["get"]["deprecated"], true);
+        assert!(doc["paths"]["/new"]["get"]["deprecated"].is_null());
+    }
+}
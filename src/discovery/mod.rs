@@ -0,0 +1,259 @@
+//! Capability discovery
+//!
+//! `OPTIONS` on any documented route answers with what the route supports:
+//! its methods (also sent as `Allow`), the content types its request bodies
+//! accept and its responses come in, and its documented query parameters per
+//! method. [`Capabilities`] is derived from the served `OpenAPI` document, so
+//! discovery can never disagree with the documentation; routes whose bodies
+//! go through content negotiation are declared with
+//! [`Capabilities::negotiated`] since the document only lists JSON.
+
+use std::{collections::BTreeMap, sync::Arc};
+
+use axum::{
+    Json,
+    extract::{Request, State},
+    http::{HeaderValue, Method, StatusCode, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use serde::Serialize;
+use utoipa::openapi::{
+    OpenApi, Required,
+    path::{Operation, ParameterIn},
+};
+
+use crate::negotiation::ResponseFormat;
+
+/// Request body content types decoded by the `Payload` extractor
+const NEGOTIATED_REQUEST_TYPES: [&str; 3] = ["application/json", "application/msgpack", "application/cbor"];
+
+/// A documented query parameter of a route
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct QueryParameter {
+    /// Parameter name
+    pub name: String,
+    /// Whether requests must provide it
+    pub required: bool,
+    /// What the parameter does
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+/// What a route supports, as returned by `OPTIONS`
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct RouteCapabilities {
+    /// Route pattern, e.g. `/users/{id}`
+    pub path: String,
+    /// Methods the route answers, including `HEAD` and `OPTIONS`
+    pub methods: Vec<String>,
+    /// Content types accepted for request bodies
+    pub accepts: Vec<String>,
+    /// Content types responses can be served in
+    pub produces: Vec<String>,
+    /// Documented query parameters, keyed by method
+    pub query_parameters: BTreeMap<String, Vec<QueryParameter>>,
+}
+
+impl RouteCapabilities {
+    fn from_operations(path: &str, operations: &[(Method, &Operation)]) -> Self {
+        let mut methods: Vec<String> = operations.iter().map(|(method, _)| method.to_string()).collect();
+        if operations.iter().any(|(method, _)| method == Method::GET) {
+            methods.push(Method::HEAD.to_string());
+        }
+        methods.push(Method::OPTIONS.to_string());
+
+        let mut accepts = Vec::new();
+        let mut produces = Vec::new();
+        let mut query_parameters = BTreeMap::new();
+        for (method, operation) in operations {
+            if let Some(body) = &operation.request_body {
+                accepts.extend(body.content.keys().cloned());
+            }
+            for (status, response) in &operation.responses.responses {
+                if status.starts_with('2')
+                    && let utoipa::openapi::RefOr::T(response) = response
+                {
+                    produces.extend(response.content.keys().cloned());
+                }
+            }
+            let parameters: Vec<QueryParameter> = operation
+                .parameters
+                .iter()
+                .flatten()
+                .filter(|parameter| matches!(parameter.parameter_in, ParameterIn::Query))
+                .map(|parameter| QueryParameter {
+                    name: parameter.name.clone(),
+                    required: matches!(parameter.required, Required::True),
+                    description: parameter.description.clone(),
+                })
+                .collect();
+            if !parameters.is_empty() {
+                query_parameters.insert(method.to_string(), parameters);
+            }
+        }
+
+        Self {
+            path: path.to_owned(),
+            methods,
+            accepts: sorted_unique(accepts),
+            produces: sorted_unique(produces),
+            query_parameters,
+        }
+    }
+
+    /// Whether the request path `path` is an instance of this route's pattern
+    fn matches(&self, path: &str) -> bool {
+        let pattern: Vec<&str> = self.path.split('/').collect();
+        let segments: Vec<&str> = path.split('/').collect();
+        pattern.len() == segments.len()
+            && pattern.iter().zip(&segments).all(|(expected, actual)| {
+                if expected.starts_with('{') && expected.ends_with('}') { !actual.is_empty() } else { expected == actual }
+            })
+    }
+
+    /// Number of literal segments, so `/users/stream` wins over `/users/{id}`
+    fn specificity(&self) -> usize {
+        self.path.split('/').filter(|segment| !segment.starts_with('{')).count()
+    }
+}
+
+/// Capabilities of every documented route
+#[derive(Debug, Clone, Default)]
+pub struct Capabilities {
+    routes: Arc<[RouteCapabilities]>,
+}
+
+impl Capabilities {
+    /// Derives the capabilities of every route documented in `openapi`
+    #[must_use]
+    pub fn from_openapi(openapi: &OpenApi) -> Self {
+        let routes: Arc<[RouteCapabilities]> = openapi
+            .paths
+            .paths
+            .iter()
+            .map(|(path, item)| {
+                let operations: Vec<(Method, &Operation)> = [
+                    (Method::GET, item.get.as_ref()),
+                    (Method::PUT, item.put.as_ref()),
+                    (Method::POST, item.post.as_ref()),
+                    (Method::DELETE, item.delete.as_ref()),
+                    (Method::PATCH, item.patch.as_ref()),
+                ]
+                .into_iter()
+                .filter_map(|(method, operation)| operation.map(|operation| (method, operation)))
+                .collect();
+                RouteCapabilities::from_operations(path, &operations)
+            })
+            .collect();
+        Self { routes }
+    }
+
+    /// Marks `paths` as content-negotiated: bodies may also be `MessagePack` or
+    /// CBOR, and responses come in every [`ResponseFormat`]
+    #[must_use]
+    pub fn negotiated<'a>(self, paths: impl IntoIterator<Item = &'a str>) -> Self {
+        let mut routes = self.routes.to_vec();
+        for path in paths {
+            for route in routes.iter_mut().filter(|route| route.path == path) {
+                if !route.accepts.is_empty() {
+                    route.accepts.extend(NEGOTIATED_REQUEST_TYPES.map(str::to_owned));
+                }
+                route.produces.extend(ResponseFormat::ALL.map(|format| format.content_type().to_owned()));
+                route.accepts = sorted_unique(std::mem::take(&mut route.accepts));
+                route.produces = sorted_unique(std::mem::take(&mut route.produces));
+            }
+        }
+        Self { routes: routes.into() }
+    }
+
+    /// Capabilities of the route serving the request path `path`
+    #[must_use]
+    pub fn route_for(&self, path: &str) -> Option<&RouteCapabilities> {
+        self.routes
+            .iter()
+            .filter(|route| route.matches(path))
+            .max_by_key(|route| route.specificity())
+    }
+}
+
+/// Middleware answering `OPTIONS` on documented routes with their capabilities
+///
+/// Other requests, and `OPTIONS` on undocumented paths, go to the router.
+pub async fn answer_options(State(capabilities): State<Capabilities>, request: Request, next: Next) -> Response {
+    if request.method() != Method::OPTIONS {
+        return next.run(request).await;
+    }
+    let Some(route) = capabilities.route_for(request.uri().path()) else {
+        return next.run(request).await;
+    };
+
+    let mut response = (StatusCode::OK, Json(route)).into_response();
+    if let Ok(allow) = HeaderValue::from_str(&route.methods.join(", ")) {
+        response.headers_mut().insert(header::ALLOW, allow);
+    }
+    response
+}
+
+fn sorted_unique(mut values: Vec<String>) -> Vec<String> {
+    values.sort();
+    values.dedup();
+    values
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use utoipa::openapi::{
+        ContentBuilder, HttpMethod, OpenApiBuilder, PathItem, PathsBuilder, ResponseBuilder,
+        path::{OperationBuilder, ParameterBuilder},
+        request_body::RequestBodyBuilder,
+    };
+
+    fn capabilities() -> Capabilities {
+        let json = || ContentBuilder::new().build();
+        let list = OperationBuilder::new()
+            .parameter(ParameterBuilder::new().name("limit").parameter_in(ParameterIn::Query).description(Some("Page size")))
+            .parameter(ParameterBuilder::new().name("id").parameter_in(ParameterIn::Path).required(Required::True))
+            .response("200", ResponseBuilder::new().content("application/json", json()));
+        let create = OperationBuilder::new()
+            .request_body(Some(RequestBodyBuilder::new().content("application/json", json()).build()))
+            .response("201", ResponseBuilder::new().content("application/json", json()));
+        let stream = OperationBuilder::new().response("200", ResponseBuilder::new().content("application/x-ndjson", json()));
+        let mut users = PathItem::new(HttpMethod::Get, list);
+        users.post = Some(create.build());
+        let paths = PathsBuilder::new()
+            .path("/users", users)
+            .path("/users/{id}", PathItem::new(HttpMethod::Delete, OperationBuilder::new()))
+            .path("/users/stream", PathItem::new(HttpMethod::Get, stream));
+        Capabilities::from_openapi(&OpenApiBuilder::new().paths(paths).build())
+    }
+
+    #[test]
+    fn test_routes_are_described_from_the_openapi_document() {
+        let capabilities = capabilities();
+        let users = capabilities.route_for("/users").expect("/users should be documented");
+
+        assert_eq!(users.methods, ["GET", "POST", "HEAD", "OPTIONS"]);
+        assert_eq!(users.accepts, ["application/json"]);
+        assert_eq!(users.produces, ["application/json"]);
+        assert_eq!(
+            users.query_parameters["GET"],
+            [QueryParameter { name: "limit".to_owned(), required: false, description: Some("Page size".to_owned()) }]
+        );
+        assert_eq!(capabilities.route_for("/users/7").map(|route| route.methods.clone()), Some(vec!["DELETE".to_owned(), "OPTIONS".to_owned()]));
+        assert_eq!(capabilities.route_for("/users/stream").map(|route| route.path.as_str()), Some("/users/stream"));
+        assert!(capabilities.route_for("/users//").is_none());
+        assert!(capabilities.route_for("/accounts").is_none());
+    }
+
+    #[test]
+    fn test_negotiated_routes_list_every_format() {
+        let capabilities = capabilities().negotiated(["/users", "/users/{id}"]);
+        let users = capabilities.route_for("/users").expect("/users should be documented");
+
+        assert_eq!(users.accepts, ["application/cbor", "application/json", "application/msgpack"]);
+        assert_eq!(users.produces, ["application/cbor", "application/json", "application/msgpack", "application/xml"]);
+        assert!(capabilities.route_for("/users/7").is_some_and(|route| route.accepts.is_empty()));
+    }
+}
@@ -0,0 +1,268 @@
+//! Error codes catalogue
+//!
+//! Every error the API returns carries a stable, machine-readable
+//! [`ErrorCode`] next to its English message, so clients can branch on the
+//! code instead of parsing text. Validation errors carry it on each
+//! [`ValidationError`](crate::user::domain::ValidationError); every other
+//! error body is an [`ErrorResponse`].
+//!
+//! Codes are part of the API contract: new ones may be added, but existing
+//! ones are never renamed or given a different meaning. User fields have
+//! dedicated codes (`NAME_TOO_LONG`); fields of other resources share the
+//! generic ones (`FIELD_EMPTY`, `TOO_LONG`, ...) and are told apart by
+//! `field`.
+
+use axum::{
+    Json,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use schemars::JsonSchema;
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::negotiation::ErrorBody;
+
+/// Stable machine-readable error code
+#[derive(Serialize, ToSchema, JsonSchema, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "typescript", derive(ts_rs::TS))]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ErrorCode {
+    // Requests
+    /// The request body could not be decoded or has the wrong shape
+    MalformedBody,
+    /// The request body has a content type the endpoint does not accept
+    UnsupportedMediaType,
+    /// The request lacks valid credentials
+    Unauthenticated,
+    /// The caller lacks the role the route requires
+    Forbidden,
+    /// The route does not accept the request's method
+    MethodNotAllowed,
+    /// The caller's IP address may not use the route
+    IpNotAllowed,
+
+    // Generic field rules
+    /// A required field is empty
+    FieldEmpty,
+    /// A field is shorter than allowed
+    TooShort,
+    /// A field is longer than allowed
+    TooLong,
+    /// A field contains characters that are not allowed
+    InvalidCharacters,
+    /// A number is outside its allowed range
+    OutOfRange,
+    /// An ID is not a positive integer
+    InvalidId,
+
+    // Users
+    /// The user does not exist
+    UserNotFound,
+    /// The requested version of the user does not exist
+    VersionNotFound,
+    /// The name is empty
+    NameEmpty,
+    /// The name exceeds the configured maximum length
+    NameTooLong,
+    /// The name contains digits
+    NameContainsNumbers,
+    /// The name contains a word rejected by name screening
+    NameNotAllowed,
+    /// The age is negative
+    AgeNegative,
+    /// The age is below the configured minimum
+    AgeTooLow,
+    /// The age is above the configured maximum
+    AgeTooHigh,
+    /// The external ID is empty
+    ExternalIdEmpty,
+    /// The external ID is longer than 255 characters
+    ExternalIdTooLong,
+    /// A metadata key is empty, too long or contains invalid characters
+    MetadataKeyInvalid,
+    /// The metadata has too many keys
+    MetadataTooManyKeys,
+    /// A metadata value is too long
+    MetadataValueTooLong,
+    /// A metadata key required by the validation policy is missing
+    MetadataKeyRequired,
+    /// An update provides none of the updatable fields
+    NoFieldsToUpdate,
+    /// The user cannot move from its current status to the requested one
+    InvalidStatusTransition,
+    /// The pagination token is malformed or was issued for another query
+    InvalidPaginationToken,
+    /// `next_token` and `prev_token` were both provided
+    ConflictingPaginationTokens,
+    /// A long-poll `wait` is not a duration such as `30s` or `500ms`
+    InvalidWait,
+    /// A bulk request provides no IDs
+    IdsRequired,
+    /// A bulk request provides more IDs than allowed
+    TooManyIds,
+
+    // User validation warnings
+    /// The name is written entirely in capitals
+    NameAllCapitals,
+    /// The name repeats a character many times in a row
+    NameRepeatedCharacters,
+    /// The age is valid but unusually high
+    AgeUnusual,
+
+    // Sub-resources
+    /// The address does not exist
+    AddressNotFound,
+    /// The country code is not a two-letter ISO 3166-1 code
+    InvalidCountryCode,
+    /// The postal code does not match the country's format
+    InvalidPostalCode,
+    /// The tag is not attached to the user
+    TagNotFound,
+    /// The user is not linked to the external system
+    IdentityNotFound,
+    /// The external ID is already linked to another user
+    IdentityAlreadyLinked,
+    /// The preference key is not known
+    UnknownPreference,
+    /// The preference value does not match the key's schema
+    InvalidPreferenceValue,
+
+    // Bank accounts
+    /// The account does not exist
+    AccountNotFound,
+    /// The account number is not a valid IBAN or check-digit number
+    InvalidAccountNumber,
+    /// The account number belongs to this bank; use an internal transfer
+    InternalAccountNumber,
+    /// A money amount is zero or negative
+    InvalidAmount,
+    /// A transfer's source and destination are the same account
+    SameAccountTransfer,
+    /// The account is under a compliance hold
+    AccountFrozen,
+    /// The account balance does not cover the transfer
+    InsufficientFunds,
+    /// The transfer would exceed a transfer limit
+    TransferLimitExceeded,
+    /// The destination must be a saved beneficiary
+    BeneficiaryRequired,
+    /// The beneficiary does not exist
+    BeneficiaryNotFound,
+    /// The account number is already a saved beneficiary
+    BeneficiaryExists,
+
+    // Data exports
+    /// The export does not exist or is not ready
+    ExportNotFound,
+    /// The download link's signature does not match
+    InvalidSignature,
+    /// The download link has expired
+    LinkExpired,
+
+    // Partner requests
+    /// A signed route was called without a well-formed signature
+    SignatureRequired,
+    /// The signature's timestamp is outside the replay window
+    SignatureExpired,
+    /// The signature matches none of the partner's keys
+    SignatureMismatch,
+    /// The signature was already used
+    SignatureReplayed,
+    /// The signing partner may not pay out of the account
+    AccountNotGranted,
+
+    // Webhooks
+    /// No webhook has this ID
+    WebhookNotFound,
+    /// The event type is not one the application emits
+    UnknownEventType,
+    /// The URL is not an absolute `http` or `https` URL
+    InvalidUrl,
+
+    // Administration
+    /// No named query has this name
+    QueryNotFound,
+    /// A named query parameter is missing or has the wrong type
+    InvalidQueryParameter,
+    /// A named query exceeded its timeout
+    QueryTimeout,
+    /// A profile is already being recorded
+    ProfileInProgress,
+    /// No queued job has this ID
+    JobNotFound,
+    /// Only dead-lettered jobs can be retried
+    JobNotDead,
+
+    // Service state
+    /// The request conflicts with existing data
+    Conflict,
+    /// The request references data that does not exist or is not allowed
+    ConstraintViolation,
+    /// The request conflicted with a concurrent one and may be retried
+    ConcurrentUpdate,
+    /// The database is temporarily unavailable
+    DatabaseUnavailable,
+    /// The database circuit breaker is open
+    CircuitOpen,
+    /// The service is in read-only mode
+    ReadOnly,
+    /// The request exceeded its route's timeout
+    RequestTimeout,
+    /// An unexpected server-side failure
+    InternalError,
+}
+
+/// Error response carrying a code and a human-readable message
+#[derive(Serialize, ToSchema, JsonSchema, Debug, Clone)]
+#[cfg_attr(feature = "typescript", derive(ts_rs::TS))]
+pub struct ErrorResponse {
+    /// Machine-readable error code
+    pub code: ErrorCode,
+    /// Human-readable description; may change, branch on `code` instead
+    pub message: String,
+}
+
+impl ErrorResponse {
+    /// Creates an error response
+    #[must_use]
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+impl ErrorBody for ErrorResponse {
+    type Item = Self;
+
+    fn items(&self) -> &[Self] {
+        std::slice::from_ref(self)
+    }
+}
+
+/// Responds with `status` and a JSON [`ErrorResponse`]
+pub fn error_response(status: StatusCode, code: ErrorCode, message: impl Into<String>) -> Response {
+    (status, Json(ErrorResponse::new(code, message))).into_response()
+}
+
+/// Responds with 500 and an `INTERNAL_ERROR` body free of server details
+#[must_use]
+pub fn internal_error() -> Response {
+    error_response(StatusCode::INTERNAL_SERVER_ERROR, ErrorCode::InternalError, "Internal server error")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_codes_serialize_as_screaming_snake_case() {
+        let body = serde_json::to_value(ErrorResponse::new(ErrorCode::NameTooLong, "Name cannot exceed 100 characters"))
+            .expect("Error responses should serialize");
+
+        assert_eq!(body["code"], "NAME_TOO_LONG");
+        assert_eq!(serde_json::to_value(ErrorCode::UserNotFound).ok(), Some("USER_NOT_FOUND".into()));
+    }
+}
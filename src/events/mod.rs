@@ -0,0 +1,131 @@
+//! Domain event bus
+//!
+//! Provides an in-process publish/subscribe channel for domain events.
+//! Services publish events after state changes; any number of subscribers
+//! (loggers, projections, realtime feeds) can listen without the publisher
+//! knowing about them. The bus does not survive restarts; consumers that must
+//! not miss an event read the [change log](crate::change_log) instead.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tokio::sync::broadcast;
+use tracing::debug;
+
+use crate::user::domain::UserStatus;
+
+/// Default number of events buffered per subscriber before the oldest are dropped
+const DEFAULT_CAPACITY: usize = 1024;
+
+/// Events emitted by the domain modules
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum DomainEvent {
+    /// A user moved between lifecycle states
+    UserStatusChanged {
+        /// Affected user
+        user_id: i32,
+        /// Status before the transition
+        from: UserStatus,
+        /// Status after the transition
+        to: UserStatus,
+        /// When the transition happened
+        occurred_at: DateTime<Utc>,
+    },
+}
+
+impl DomainEvent {
+    /// Every event type, as serialized in the `type` field
+    pub const TYPES: &'static [&'static str] = &["user_status_changed"];
+
+    /// Type of this event, as serialized in the `type` field
+    #[must_use]
+    pub const fn event_type(&self) -> &'static str {
+        match self {
+            Self::UserStatusChanged { .. } => "user_status_changed",
+        }
+    }
+}
+
+/// In-process event bus backed by a broadcast channel
+#[derive(Debug, Clone)]
+pub struct EventBus {
+    sender: broadcast::Sender<DomainEvent>,
+}
+
+impl EventBus {
+    /// Creates a new `EventBus` with the default buffer capacity
+    #[must_use]
+    pub fn new() -> Self {
+        Self::with_capacity(DEFAULT_CAPACITY)
+    }
+
+    /// Creates a new `EventBus` buffering up to `capacity` events per subscriber
+    #[must_use]
+    pub fn with_capacity(capacity: usize) -> Self {
+        let (sender, _) = broadcast::channel(capacity);
+        Self { sender }
+    }
+
+    /// Publishes an event to all current subscribers
+    ///
+    /// Publishing never fails: events emitted while nobody is listening are dropped.
+    pub fn publish(&self, event: DomainEvent) {
+        match self.sender.send(event) {
+            Ok(receivers) => debug!(receivers, "Event published"),
+            Err(broadcast::error::SendError(event)) => {
+                debug!(?event, "Event published with no subscribers");
+            },
+        }
+    }
+
+    /// Subscribes to all events published from now on
+    #[must_use]
+    pub fn subscribe(&self) -> broadcast::Receiver<DomainEvent> {
+        self.sender.subscribe()
+    }
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn status_event() -> DomainEvent {
+        DomainEvent::UserStatusChanged {
+            user_id: 1,
+            from: UserStatus::Active,
+            to: UserStatus::Suspended,
+            occurred_at: Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_subscriber_receives_published_event() {
+        let bus = EventBus::new();
+        let mut receiver = bus.subscribe();
+        let event = status_event();
+
+        bus.publish(event.clone());
+
+        assert_eq!(receiver.recv().await.unwrap(), event);
+    }
+
+    #[test]
+    fn test_event_type_matches_serialized_type() {
+        let event = status_event();
+
+        assert_eq!(serde_json::to_value(&event).unwrap()["type"], event.event_type());
+        assert!(DomainEvent::TYPES.contains(&event.event_type()));
+    }
+
+    #[test]
+    fn test_publish_without_subscribers_does_not_fail() {
+        let bus = EventBus::new();
+        bus.publish(status_event());
+    }
+}
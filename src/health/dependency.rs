@@ -0,0 +1,122 @@
+//! Pings of downstream HTTP dependencies
+//!
+//! A [`DependencyPing`] sends a `GET` to a URL of a service this one calls
+//! (a webhook receiver, an identity provider, a rates provider) and reports
+//! how long it took. Dependencies are not critical: an unreachable one, a
+//! slow one or one answering with an error status is listed as `degraded`
+//! while the service stays in rotation, since requests not touching it still
+//! succeed.
+
+use std::{sync::Arc, time::Duration};
+
+use futures_util::future::BoxFuture;
+use tracing::warn;
+
+use super::{HealthCheck, SharedHealthCheck};
+
+/// Health check pinging a downstream HTTP dependency
+#[derive(Debug, Clone)]
+pub struct DependencyPing {
+    name: String,
+    url: String,
+    client: reqwest::Client,
+}
+
+impl DependencyPing {
+    /// Creates a ping of `url`, giving up after `timeout`
+    #[must_use]
+    pub fn new(name: impl Into<String>, url: impl Into<String>, timeout: Duration) -> Self {
+        Self {
+            name: name.into(),
+            url: url.into(),
+            client: reqwest::Client::builder().timeout(timeout).build().unwrap_or_default(),
+        }
+    }
+
+    /// Creates pings from a `name=url;name=url` list, skipping malformed entries
+    #[must_use]
+    pub fn from_spec(spec: &str, timeout: Duration) -> Vec<SharedHealthCheck> {
+        spec.split(';')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .filter_map(|entry| {
+                let dependency = entry.split_once('=').filter(|(name, url)| !name.is_empty() && !url.is_empty());
+                if dependency.is_none() {
+                    warn!(entry, "Ignoring health dependency entry without `name=url`");
+                }
+                dependency
+            })
+            .map(|(name, url)| Arc::new(Self::new(name.trim(), url.trim(), timeout)) as SharedHealthCheck)
+            .collect()
+    }
+}
+
+impl HealthCheck for DependencyPing {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn critical(&self) -> bool {
+        false
+    }
+
+    fn check(&self) -> BoxFuture<'_, Result<(), String>> {
+        Box::pin(async move {
+            let response = self.client.get(&self.url).send().await.map_err(|e| {
+                warn!(dependency = %self.name, error = %e, "Dependency ping failed");
+                if e.is_timeout() { "Timed out".to_owned() } else { format!("Unreachable: {e}") }
+            })?;
+            let status = response.status();
+            if status.is_client_error() || status.is_server_error() {
+                warn!(dependency = %self.name, %status, "Dependency answered with an error status");
+                return Err(format!("Responded with HTTP {}", status.as_u16()));
+            }
+            Ok(())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{Router, http::StatusCode, routing::get};
+
+    async fn serve() -> String {
+        let app = Router::new()
+            .route("/ok", get(|| async { "ok" }))
+            .route("/broken", get(|| async { StatusCode::INTERNAL_SERVER_ERROR }))
+            .route(
+                "/slow",
+                get(|| async {
+                    tokio::time::sleep(Duration::from_secs(1)).await;
+                    "late"
+                }),
+            );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        format!("http://{address}")
+    }
+
+    #[tokio::test]
+    async fn test_ping_reports_status_errors_and_timeouts() {
+        let base = serve().await;
+        let ping = |path: &str| DependencyPing::new("rates", format!("{base}{path}"), Duration::from_millis(200));
+
+        assert_eq!(ping("/ok").check().await, Ok(()));
+        assert_eq!(ping("/broken").check().await, Err("Responded with HTTP 500".to_owned()));
+        assert_eq!(ping("/slow").check().await, Err("Timed out".to_owned()));
+        assert!(!ping("/ok").critical());
+    }
+
+    #[test]
+    fn test_from_spec_skips_malformed_entries() {
+        let pings = DependencyPing::from_spec(
+            "rates=http://rates/health; oidc = http://idp/.well-known/openid-configuration;broken;=http://x",
+            Duration::from_secs(1),
+        );
+
+        let names: Vec<_> = pings.iter().map(|ping| ping.name().to_owned()).collect();
+        assert_eq!(names, ["rates", "oidc"]);
+    }
+}
@@ -3,14 +3,27 @@
 //! Provides comprehensive health checking functionality for the application,
 //! including database connectivity and overall system status.
 //! This module is completely independent and doesn't depend on other business modules.
+//! Downstream HTTP dependencies can be pinged too (see [`DependencyPing`]).
+//! [`probe`] checks a running server from the outside.
 
-use axum::{extract::State, http::StatusCode, response::Json};
+use axum::{http::StatusCode, response::Json};
 use serde::Serialize;
+use futures_util::future::BoxFuture;
 use sqlx::PgPool;
+use std::sync::Arc;
 use std::time::Instant;
 use tracing::{error, info};
 use utoipa::ToSchema;
 
+use crate::circuit::DatabaseCircuit;
+use crate::clock::{SharedClock, SystemClock};
+use crate::registry::Inject;
+
+mod dependency;
+pub mod probe;
+
+pub use dependency::DependencyPing;
+
 /// Safely converts duration to milliseconds as u64, capping at `u64::MAX`
 #[allow(clippy::cast_possible_truncation)]
 fn duration_to_millis(duration: std::time::Duration) -> u64 {
@@ -53,6 +66,27 @@ pub enum HealthError {
     DatabaseError(String),
 }
 
+/// Additional component probed by `/health` and `/ready`
+///
+/// Feature modules contribute these for the dependencies they own (a queue, a
+/// third-party API); the check reports the component unhealthy on `Err`, or
+/// only degraded when the component is not critical.
+pub trait HealthCheck: Send + Sync {
+    /// Component name reported in the response
+    fn name(&self) -> &str;
+
+    /// Whether a failure takes the whole service out of rotation
+    fn critical(&self) -> bool {
+        true
+    }
+
+    /// Probes the component, returning a message describing any failure
+    fn check(&self) -> BoxFuture<'_, Result<(), String>>;
+}
+
+/// Health check shared between the registry and the health service
+pub type SharedHealthCheck = Arc<dyn HealthCheck>;
+
 /// Health repository for performing health checks
 #[derive(Clone)]
 pub struct HealthRepository {
@@ -109,6 +143,10 @@ impl HealthRepository {
 #[derive(Clone)]
 pub struct HealthService {
     repository: HealthRepository,
+    clock: SharedClock,
+    checks: Vec<SharedHealthCheck>,
+    read_only: bool,
+    circuit: DatabaseCircuit,
 }
 
 impl HealthService {
@@ -116,9 +154,37 @@ impl HealthService {
     #[must_use] pub fn new(pool: PgPool) -> Self {
         Self {
             repository: HealthRepository::new(pool),
+            clock: SystemClock::shared(),
+            checks: Vec::new(),
+            read_only: false,
+            circuit: DatabaseCircuit::default(),
         }
     }
 
+    /// Uses `clock` instead of the system clock for response timestamps
+    #[must_use] pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Adds `checks` to the components probed after the database
+    #[must_use] pub fn with_checks(mut self, checks: impl IntoIterator<Item = SharedHealthCheck>) -> Self {
+        self.checks.extend(checks);
+        self
+    }
+
+    /// Reports a degraded `read_only` component while writes are rejected
+    #[must_use] pub fn with_read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
+
+    /// Reports the database unhealthy without querying it while `circuit` is open
+    #[must_use] pub fn with_circuit(mut self, circuit: DatabaseCircuit) -> Self {
+        self.circuit = circuit;
+        self
+    }
+
     /// Performs a complete health check of all components
     #[tracing::instrument(skip(self))]
     pub async fn check_health(&self) -> HealthCheckResponse {
@@ -138,31 +204,70 @@ impl HealthService {
         };
         components.push(app_health);
 
-        // Check database status
-        let db_health = self.repository.check_database_detailed().await
-            .unwrap_or_else(|e| {
-                error!(error = %e, "Failed to perform database health check");
-                overall_healthy = false;
-                ComponentHealth {
-                    name: "database".to_owned(),
-                    status: "unhealthy".to_owned(),
-                    message: Some(format!("Health check failed: {e}")),
-                    response_time_ms: 0,
-                }
-            });
+        // Check database status; an open circuit answers without waiting on the pool
+        let db_health = if self.circuit.is_open() {
+            ComponentHealth {
+                name: "database".to_owned(),
+                status: "unhealthy".to_owned(),
+                message: Some(crate::circuit::CIRCUIT_OPEN_MESSAGE.to_owned()),
+                response_time_ms: 0,
+            }
+        } else {
+            self.repository.check_database_detailed().await
+                .unwrap_or_else(|e| {
+                    error!(error = %e, "Failed to perform database health check");
+                    ComponentHealth {
+                        name: "database".to_owned(),
+                        status: "unhealthy".to_owned(),
+                        message: Some(format!("Health check failed: {e}")),
+                        response_time_ms: 0,
+                    }
+                })
+        };
 
         if db_health.status != "healthy" {
             overall_healthy = false;
         }
         components.push(db_health);
 
+        // Read-only mode degrades the service without making it unhealthy
+        if self.read_only {
+            components.push(ComponentHealth {
+                name: "read_only".to_owned(),
+                status: "degraded".to_owned(),
+                message: Some(crate::read_only::READ_ONLY_MESSAGE.to_owned()),
+                response_time_ms: 0,
+            });
+        }
+
+        // Check components contributed by modules and configured dependencies;
+        // failing non-critical ones degrade the service without making it unhealthy
+        for check in &self.checks {
+            let check_start = Instant::now();
+            let result = check.check().await;
+            let status = match (&result, check.critical()) {
+                (Ok(()), _) => "healthy",
+                (Err(_), true) => "unhealthy",
+                (Err(_), false) => "degraded",
+            };
+            if status == "unhealthy" {
+                overall_healthy = false;
+            }
+            components.push(ComponentHealth {
+                name: check.name().to_owned(),
+                status: status.to_owned(),
+                message: result.err(),
+                response_time_ms: duration_to_millis(check_start.elapsed()),
+            });
+        }
+
         let total_time = duration_to_millis(start_time.elapsed());
         let overall_status = if overall_healthy { "healthy" } else { "unhealthy" };
 
         let response = HealthCheckResponse {
             status: overall_status.to_owned(),
             version: env!("CARGO_PKG_VERSION").to_owned(),
-            timestamp: chrono::Utc::now().to_rfc3339(),
+            timestamp: self.clock.now().to_rfc3339(),
             components,
             total_response_time_ms: total_time,
         };
@@ -192,7 +297,7 @@ impl HealthService {
         HealthCheckResponse {
             status: "healthy".to_owned(),
             version: env!("CARGO_PKG_VERSION").to_owned(),
-            timestamp: chrono::Utc::now().to_rfc3339(),
+            timestamp: self.clock.now().to_rfc3339(),
             components: vec![app_health],
             total_response_time_ms: total_time,
         }
@@ -212,11 +317,10 @@ impl HealthService {
     ),
     tag = "health"
 )]
-#[tracing::instrument(skip(app_state))]
+#[tracing::instrument(skip(health_service))]
 pub async fn health_check_handler(
-    State(app_state): State<crate::AppState>,
+    Inject(health_service): Inject<HealthService>,
 ) -> Result<Json<HealthCheckResponse>, (StatusCode, Json<HealthCheckResponse>)> {
-    let health_service = app_state.health_service;
     let response = health_service.check_health().await;
 
     if response.status == "healthy" {
@@ -240,9 +344,8 @@ pub async fn health_check_handler(
     tag = "health"
 )]
 pub async fn readiness_check_handler(
-    State(app_state): State<crate::AppState>,
+    Inject(health_service): Inject<HealthService>,
 ) -> Result<Json<HealthCheckResponse>, (StatusCode, Json<HealthCheckResponse>)> {
-    let health_service = app_state.health_service;
     // For now, readiness is the same as health check
     // In more complex applications, this might check additional conditions
     // like cache warmup, external service dependencies, etc.
@@ -267,8 +370,7 @@ pub async fn readiness_check_handler(
     ),
     tag = "health"
 )]
-pub async fn liveness_check_handler(State(app_state): State<crate::AppState>) -> Json<HealthCheckResponse> {
-    let health_service = app_state.health_service;
+pub async fn liveness_check_handler(Inject(health_service): Inject<HealthService>) -> Json<HealthCheckResponse> {
     let response = health_service.check_liveness();
     Json(response)
 }
\ No newline at end of file
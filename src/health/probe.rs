@@ -0,0 +1,115 @@
+//! Liveness probe of a running server
+//!
+//! Backs `rust-kickstart healthcheck`, which container runtimes run as their
+//! `HEALTHCHECK` so images need no `curl`. The probe calls `/live` on the
+//! configured port and succeeds on a 2xx answer. A server serving mutual TLS
+//! is probed over HTTPS with the client certificate in `TLS_PROBE_CERT_PATH`
+//! (see [`crate::mtls::probe_client_config`]).
+
+use std::time::Duration;
+
+use crate::config::{ServerConfig, TlsConfig};
+
+/// How long the probe waits for the server to answer
+pub const PROBE_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// URL of the liveness endpoint of the server `config` describes
+///
+/// A server bound to every interface is reached over loopback, and one
+/// configured with `tls` over HTTPS.
+#[must_use]
+pub fn liveness_url(config: &ServerConfig, tls: &TlsConfig) -> String {
+    let host = match config.host.as_str() {
+        "0.0.0.0" | "" => "127.0.0.1",
+        "::" | "[::]" => "[::1]",
+        host => host,
+    };
+    let scheme = if tls.is_configured() { "https" } else { "http" };
+    format!("{scheme}://{host}:{}/live", config.port)
+}
+
+/// Calls `url` and fails unless the server answers 2xx within [`PROBE_TIMEOUT`]
+///
+/// HTTPS URLs are called with the client configuration `tls` describes,
+/// which needs the `mtls` feature.
+pub async fn probe(url: &str, tls: &TlsConfig) -> Result<(), String> {
+    let builder = reqwest::Client::builder().timeout(PROBE_TIMEOUT);
+    let builder = if url.starts_with("https://") { with_client_certificate(builder, tls)? } else { builder };
+    let client = builder.build().map_err(|e| e.to_string())?;
+    let response = client.get(url).send().await.map_err(|e| format!("{url} unreachable: {e}"))?;
+    if response.status().is_success() {
+        Ok(())
+    } else {
+        Err(format!("{url} responded with HTTP {}", response.status().as_u16()))
+    }
+}
+
+/// `builder` presenting the probe's client certificate to a mutual TLS server
+#[cfg(feature = "mtls")]
+fn with_client_certificate(builder: reqwest::ClientBuilder, tls: &TlsConfig) -> Result<reqwest::ClientBuilder, String> {
+    let config = crate::mtls::probe_client_config(tls).map_err(|e| e.to_string())?;
+    Ok(builder.use_preconfigured_tls(config))
+}
+
+/// Servers only speak TLS when built with the `mtls` feature
+#[cfg(not(feature = "mtls"))]
+fn with_client_certificate(_builder: reqwest::ClientBuilder, _tls: &TlsConfig) -> Result<reqwest::ClientBuilder, String> {
+    Err("Probing over HTTPS requires building with the `mtls` feature".to_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{Router, http::StatusCode, routing::get};
+
+    fn config(host: &str, port: u16) -> ServerConfig {
+        ServerConfig {
+            host: host.to_owned(),
+            port,
+            response_envelope: false,
+            public_base_url: None,
+            read_only: false,
+            request_timeout_secs: 30,
+            long_request_timeout_secs: 300,
+            cache_max_age_secs: 60,
+            trusted_proxies: None,
+            docs_access: None,
+        }
+    }
+
+    #[test]
+    fn test_unspecified_hosts_are_probed_over_loopback() {
+        let plain = TlsConfig::default();
+        assert_eq!(liveness_url(&config("0.0.0.0", 3000), &plain), "http://127.0.0.1:3000/live");
+        assert_eq!(liveness_url(&config("::", 8080), &plain), "http://[::1]:8080/live");
+        assert_eq!(liveness_url(&config("10.0.0.5", 3000), &plain), "http://10.0.0.5:3000/live");
+    }
+
+    #[test]
+    fn test_tls_servers_are_probed_over_https() {
+        let tls = TlsConfig {
+            cert_path: Some("server.pem".to_owned()),
+            key_path: Some("server-key.pem".to_owned()),
+            client_ca_path: Some("ca.pem".to_owned()),
+            ..TlsConfig::default()
+        };
+        let probe_only = TlsConfig { probe_cert_path: Some("client.pem".to_owned()), ..TlsConfig::default() };
+
+        assert_eq!(liveness_url(&config("0.0.0.0", 8443), &tls), "https://127.0.0.1:8443/live");
+        assert_eq!(liveness_url(&config("::", 8443), &tls), "https://[::1]:8443/live");
+        assert_eq!(liveness_url(&config("0.0.0.0", 3000), &probe_only), "http://127.0.0.1:3000/live", "Probe settings alone do not enable TLS");
+    }
+
+    #[tokio::test]
+    async fn test_probe_requires_success_status() {
+        let app = Router::new()
+            .route("/live", get(|| async { "ok" }))
+            .route("/down", get(|| async { StatusCode::SERVICE_UNAVAILABLE }));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        assert_eq!(probe(&format!("http://{address}/live"), &TlsConfig::default()).await, Ok(()));
+        assert!(probe(&format!("http://{address}/down"), &TlsConfig::default()).await.is_err_and(|e| e.contains("503")));
+    }
+}
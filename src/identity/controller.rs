@@ -0,0 +1,180 @@
+//! External identity controller - HTTP handlers for identity links and lookups
+
+use axum::{
+    Json,
+    extract::Path,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use tracing::{error, warn};
+
+use crate::error_codes::{ErrorCode, ErrorResponse, internal_error};
+use crate::identity::IdentityService;
+use crate::links::LinkBuilder;
+use crate::registry::Inject;
+use crate::user::{User, UserId};
+use crate::user::domain::{ApiResponse, ValidationErrorResponse, UserError};
+
+use super::domain::{ExternalIdentity, IdentityError, LinkIdentity};
+
+/// Maps identity errors to HTTP responses
+fn error_response(error: IdentityError, user_id: Option<i32>) -> Response {
+    match error {
+        IdentityError::ValidationError(errors) => {
+            warn!(?errors, user_id, "Controller: Identity validation failed");
+            (
+                StatusCode::BAD_REQUEST,
+                Json(ValidationErrorResponse { errors }),
+            ).into_response()
+        }
+        IdentityError::UserNotFound | IdentityError::NotFound => {
+            warn!(user_id, error = %error, "Controller: Identity resource not found");
+            let code = if matches!(error, IdentityError::UserNotFound) { ErrorCode::UserNotFound } else { ErrorCode::IdentityNotFound };
+            (
+                StatusCode::NOT_FOUND,
+                Json(ErrorResponse::new(code, error.to_string())),
+            ).into_response()
+        }
+        IdentityError::AlreadyLinked { .. } => {
+            warn!(user_id, error = %error, "Controller: External ID linked to another user");
+            (
+                StatusCode::CONFLICT,
+                Json(ErrorResponse::new(ErrorCode::IdentityAlreadyLinked, error.to_string())),
+            ).into_response()
+        }
+        IdentityError::DatabaseError(msg) => {
+            error!(error = %msg, user_id, "Controller: Database error in identity operation");
+            internal_error()
+        }
+        IdentityError::UserServiceError(UserError::DatabaseError(e)) => {
+            error!(error = %e, user_id, "Controller: Database error in user lookup for identity operation");
+            e.into_response()
+        }
+        IdentityError::UserServiceError(e) => {
+            error!(error = %e, user_id, "Controller: User service error in identity operation");
+            internal_error()
+        }
+    }
+}
+
+/// HTTP handler for listing the external identities of a user
+#[utoipa::path(
+    get,
+    path = "/users/{id}/identities",
+    tag = "identities",
+    params(
+        ("id" = i32, Path, description = "User ID")
+    ),
+    responses(
+        (status = 200, description = "External identities of the user, by system", body = Vec<ExternalIdentity>),
+        (status = 400, description = "Invalid user ID", body = ValidationErrorResponse),
+        (status = 404, description = "User not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    )
+)]
+#[tracing::instrument(skip(identity_service), fields(user_id = user_id))]
+pub async fn list_identities_handler(
+    Inject(identity_service): Inject<IdentityService>,
+    UserId(user_id): UserId,
+) -> impl IntoResponse {
+    match identity_service.list_identities(user_id).await {
+        Ok(identities) => (StatusCode::OK, Json(identities)).into_response(),
+        Err(e) => error_response(e, Some(user_id)),
+    }
+}
+
+/// HTTP handler for linking a user to its ID in an external system
+///
+/// Replaces the user's previous ID in that system; linking the same ID again
+/// is a no-op.
+#[utoipa::path(
+    put,
+    path = "/users/{id}/identities/{system}",
+    tag = "identities",
+    params(
+        ("id" = i32, Path, description = "User ID"),
+        ("system" = String, Path, description = "External system name, e.g. `crm`")
+    ),
+    request_body = LinkIdentity,
+    responses(
+        (status = 200, description = "Identity linked", body = ExternalIdentity),
+        (status = 400, description = "Invalid system name or external ID", body = ValidationErrorResponse),
+        (status = 404, description = "User not found", body = ErrorResponse),
+        (status = 409, description = "External ID already linked to another user", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    )
+)]
+#[tracing::instrument(skip(identity_service, path, payload), fields(user_id = user_id, system = %path.1))]
+pub async fn link_identity_handler(
+    Inject(identity_service): Inject<IdentityService>,
+    UserId(user_id): UserId,
+    Path(path): Path<(i32, String)>,
+    Json(payload): Json<LinkIdentity>,
+) -> impl IntoResponse {
+    match identity_service.link(user_id, &path.1, &payload.external_id).await {
+        Ok(identity) => (StatusCode::OK, Json(identity)).into_response(),
+        Err(e) => error_response(e, Some(user_id)),
+    }
+}
+
+/// HTTP handler for unlinking a user from an external system
+#[utoipa::path(
+    delete,
+    path = "/users/{id}/identities/{system}",
+    tag = "identities",
+    params(
+        ("id" = i32, Path, description = "User ID"),
+        ("system" = String, Path, description = "External system name")
+    ),
+    responses(
+        (status = 200, description = "Identity unlinked", body = ApiResponse),
+        (status = 400, description = "Invalid system name", body = ValidationErrorResponse),
+        (status = 404, description = "User not found or not linked to the system", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    )
+)]
+#[tracing::instrument(skip(identity_service, path), fields(user_id = user_id, system = %path.1))]
+pub async fn unlink_identity_handler(
+    Inject(identity_service): Inject<IdentityService>,
+    UserId(user_id): UserId,
+    Path(path): Path<(i32, String)>,
+) -> impl IntoResponse {
+    let system = path.1;
+    match identity_service.unlink(user_id, &system).await {
+        Ok(()) => (
+            StatusCode::OK,
+            Json(ApiResponse {
+                message: format!("User {user_id} unlinked from {system}"),
+            }),
+        ).into_response(),
+        Err(e) => error_response(e, Some(user_id)),
+    }
+}
+
+/// HTTP handler for finding a user by its ID in an external system
+#[utoipa::path(
+    get,
+    path = "/users/by-external/{system}/{external_id}",
+    tag = "identities",
+    params(
+        ("system" = String, Path, description = "External system name"),
+        ("external_id" = String, Path, description = "ID of the user in the external system")
+    ),
+    responses(
+        (status = 200, description = "User linked to the external ID", body = User),
+        (status = 400, description = "Invalid system name or external ID", body = ValidationErrorResponse),
+        (status = 404, description = "No user linked to the external ID", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    )
+)]
+#[tracing::instrument(skip(identity_service, links, external_id), fields(system = %system))]
+pub async fn find_user_by_external_id_handler(
+    Inject(identity_service): Inject<IdentityService>,
+    Inject(links): Inject<LinkBuilder>,
+    Path((system, external_id)): Path<(String, String)>,
+) -> impl IntoResponse {
+    match identity_service.find_user(&system, &external_id).await {
+        Ok(user) => (StatusCode::OK, Json(user.with_links(&links))).into_response(),
+        Err(e) => error_response(e, None),
+    }
+}
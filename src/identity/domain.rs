@@ -0,0 +1,58 @@
+//! External identity domain models
+
+use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+use utoipa::ToSchema;
+use chrono::{DateTime, Utc};
+
+use crate::user::domain::{UserError, ValidationError};
+
+/// A user's ID in an external system
+#[derive(Serialize, ToSchema, JsonSchema, Debug, Clone, sqlx::FromRow)]
+pub struct ExternalIdentity {
+    /// Unique identity identifier
+    pub id: i32,
+    /// ID of the user
+    pub user_id: i32,
+    /// Normalized (lowercase) name of the external system, e.g. `crm`
+    pub system: String,
+    /// ID of the user in the external system
+    pub external_id: String,
+    /// When the current external ID was linked
+    pub linked_at: DateTime<Utc>,
+}
+
+/// Request payload for linking a user to an external system
+#[derive(Deserialize, ToSchema, JsonSchema, Debug, Clone)]
+pub struct LinkIdentity {
+    /// ID of the user in the external system
+    pub external_id: String,
+}
+
+/// Domain errors for external identity operations
+#[derive(Debug, thiserror::Error)]
+pub enum IdentityError {
+    /// System name or external ID is invalid
+    #[error("Validation failed")]
+    ValidationError(Vec<ValidationError>),
+    /// The user does not exist
+    #[error("User not found")]
+    UserNotFound,
+    /// No identity matches the system and ID
+    #[error("External identity not found")]
+    NotFound,
+    /// The external ID is already linked to another user
+    #[error("External ID {external_id} of {system} is already linked to another user")]
+    AlreadyLinked {
+        /// Normalized system name
+        system: String,
+        /// The conflicting external ID
+        external_id: String,
+    },
+    /// Database operation failed
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+    /// Error from the underlying user service
+    #[error("User service error: {0}")]
+    UserServiceError(UserError),
+}
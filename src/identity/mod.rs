@@ -0,0 +1,18 @@
+//! External identity module
+//!
+//! Maps users to their IDs in third-party systems (a CRM, an identity
+//! provider subject, ...), so sync pipelines can find users by the IDs they
+//! know instead of internal ones. Identities are removed with their user.
+
+pub mod domain;
+pub mod repository;
+pub mod service;
+pub mod controller;
+pub mod validation;
+
+// Public exports
+pub use service::IdentityService;
+pub use domain::{ExternalIdentity, IdentityError, LinkIdentity};
+
+// Export controller for OpenAPI documentation
+pub use controller::*;
@@ -0,0 +1,123 @@
+//! External identity repository - handles database operations
+//!
+//! This module is private to the identity module. All database access must go
+//! through `IdentityService`.
+
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use tracing::{error, info, warn};
+
+use super::domain::{ExternalIdentity, IdentityError};
+
+/// External identity repository for database operations
+#[derive(Clone)]
+pub(super) struct IdentityRepository {
+    pool: PgPool,
+}
+
+impl IdentityRepository {
+    /// Creates a new `IdentityRepository` instance
+    pub(super) fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Links a user to its ID in `system`, replacing the user's previous ID there
+    ///
+    /// Linking the same ID again is a no-op that keeps the original link time.
+    pub(super) async fn link(
+        &self,
+        user_id: i32,
+        system: &str,
+        external_id: &str,
+        linked_at: DateTime<Utc>,
+    ) -> Result<ExternalIdentity, IdentityError> {
+        info!(user_id, system, "Linking external identity in database");
+
+        let identity = sqlx::query_as!(
+            ExternalIdentity,
+            "INSERT INTO external_identities (user_id, system, external_id, linked_at) VALUES ($1, $2, $3, $4)
+             ON CONFLICT (user_id, system) DO UPDATE SET
+                 external_id = EXCLUDED.external_id,
+                 linked_at = CASE
+                     WHEN external_identities.external_id = EXCLUDED.external_id THEN external_identities.linked_at
+                     ELSE EXCLUDED.linked_at
+                 END
+             RETURNING id, user_id, system, external_id, linked_at",
+            user_id,
+            system,
+            external_id,
+            linked_at
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| {
+            // The (user_id, system) conflict is handled above, so a unique
+            // violation means another user holds this external ID
+            if e.as_database_error().is_some_and(sqlx::error::DatabaseError::is_unique_violation) {
+                warn!(user_id, system, "External ID is already linked to another user");
+                return IdentityError::AlreadyLinked { system: system.to_owned(), external_id: external_id.to_owned() };
+            }
+            error!(error = %e, user_id, system, "Failed to link external identity in database");
+            IdentityError::DatabaseError(e.to_string())
+        })?;
+
+        info!(user_id, system, "External identity linked successfully in database");
+        Ok(identity)
+    }
+
+    /// Removes a user's link to `system`, returning whether one existed
+    pub(super) async fn unlink(&self, user_id: i32, system: &str) -> Result<bool, IdentityError> {
+        info!(user_id, system, "Unlinking external identity in database");
+
+        let result = sqlx::query!(
+            "DELETE FROM external_identities WHERE user_id = $1 AND system = $2",
+            user_id,
+            system
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| {
+            error!(error = %e, user_id, system, "Failed to unlink external identity in database");
+            IdentityError::DatabaseError(e.to_string())
+        })?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Lists the external identities of a user
+    pub(super) async fn find_by_user(&self, user_id: i32) -> Result<Vec<ExternalIdentity>, IdentityError> {
+        info!(user_id, "Fetching external identities for user from database");
+
+        sqlx::query_as!(
+            ExternalIdentity,
+            "SELECT id, user_id, system, external_id, linked_at
+             FROM external_identities
+             WHERE user_id = $1
+             ORDER BY system",
+            user_id
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| {
+            error!(error = %e, user_id, "Failed to fetch external identities from database");
+            IdentityError::DatabaseError(e.to_string())
+        })
+    }
+
+    /// Finds the user linked to `external_id` in `system`
+    pub(super) async fn find_user_id(&self, system: &str, external_id: &str) -> Result<Option<i32>, IdentityError> {
+        info!(system, "Looking up user by external identity in database");
+
+        sqlx::query_scalar!(
+            "SELECT user_id FROM external_identities WHERE system = $1 AND external_id = $2",
+            system,
+            external_id
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| {
+            error!(error = %e, system, "Failed to look up external identity in database");
+            IdentityError::DatabaseError(e.to_string())
+        })
+    }
+}
@@ -0,0 +1,114 @@
+//! External identity service - business logic layer
+//!
+//! Links users to their IDs in external systems and resolves those IDs back
+//! to users. Talks to the user module only through `UserReadPort`.
+
+use std::sync::Arc;
+
+use sqlx::PgPool;
+use tracing::{info, warn};
+
+use crate::clock::{SharedClock, SystemClock};
+use crate::user::domain::UserError;
+use crate::user::{SharedUserReadPort, User, UserReadPort};
+
+use super::domain::{ExternalIdentity, IdentityError};
+use super::repository::IdentityRepository;
+use super::validation::{normalize_system, validate_identity, validate_system};
+
+/// External identity service that handles business logic for identity links
+#[derive(Clone)]
+pub struct IdentityService {
+    repository: IdentityRepository,
+    users: SharedUserReadPort,
+    clock: SharedClock,
+}
+
+impl IdentityService {
+    /// Creates a new `IdentityService` instance
+    #[must_use] pub fn new(pool: PgPool, users: impl UserReadPort + 'static) -> Self {
+        Self {
+            repository: IdentityRepository::new(pool),
+            users: Arc::new(users),
+            clock: SystemClock::shared(),
+        }
+    }
+
+    /// Uses `clock` instead of the system clock for timestamps
+    #[must_use] pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Ensures the user exists before touching its identities
+    async fn ensure_user_exists(&self, user_id: i32) -> Result<(), IdentityError> {
+        match self.users.user_exists(user_id).await {
+            Ok(true) => Ok(()),
+            Ok(false) => {
+                warn!(user_id, "IdentityService: User not found");
+                Err(IdentityError::UserNotFound)
+            }
+            Err(e) => {
+                warn!(user_id, error = ?e, "IdentityService: Error checking user existence");
+                Err(IdentityError::UserServiceError(e))
+            }
+        }
+    }
+
+    /// Links a user to its ID in `system`, replacing the user's previous ID there
+    pub async fn link(&self, user_id: i32, system: &str, external_id: &str) -> Result<ExternalIdentity, IdentityError> {
+        info!(user_id, system, "IdentityService: Linking external identity");
+
+        let system = normalize_system(system);
+        let external_id = external_id.trim();
+        validate_identity(&system, external_id).map_err(IdentityError::ValidationError)?;
+        self.ensure_user_exists(user_id).await?;
+        self.repository.link(user_id, &system, external_id, self.clock.now()).await
+    }
+
+    /// Removes a user's link to `system`
+    pub async fn unlink(&self, user_id: i32, system: &str) -> Result<(), IdentityError> {
+        info!(user_id, system, "IdentityService: Unlinking external identity");
+
+        let system = normalize_system(system);
+        validate_system(&system).map_err(IdentityError::ValidationError)?;
+        self.ensure_user_exists(user_id).await?;
+        if self.repository.unlink(user_id, &system).await? {
+            Ok(())
+        } else {
+            warn!(user_id, system = %system, "IdentityService: User not linked to system");
+            Err(IdentityError::NotFound)
+        }
+    }
+
+    /// Lists the external identities of a user
+    pub async fn list_identities(&self, user_id: i32) -> Result<Vec<ExternalIdentity>, IdentityError> {
+        info!(user_id, "IdentityService: Listing external identities");
+
+        self.ensure_user_exists(user_id).await?;
+        self.repository.find_by_user(user_id).await
+    }
+
+    /// Finds the user linked to `external_id` in `system`
+    pub async fn find_user(&self, system: &str, external_id: &str) -> Result<User, IdentityError> {
+        info!(system, "IdentityService: Looking up user by external identity");
+
+        let system = normalize_system(system);
+        validate_identity(&system, external_id).map_err(IdentityError::ValidationError)?;
+        let Some(user_id) = self.repository.find_user_id(&system, external_id).await? else {
+            warn!(system = %system, "IdentityService: External identity not found");
+            return Err(IdentityError::NotFound);
+        };
+
+        // The identity goes away with its user, so a miss here means the
+        // user was deleted since the lookup above
+        self.users.get_user_by_id(user_id).await.map_err(|e| match e {
+            UserError::NotFound => IdentityError::NotFound,
+            other @ (UserError::ValidationError(_)
+            | UserError::DatabaseError(_)
+            | UserError::InvalidToken
+            | UserError::InvalidStatusTransition { .. }
+            | UserError::VersionNotFound(_)) => IdentityError::UserServiceError(other),
+        })
+    }
+}
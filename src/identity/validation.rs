@@ -0,0 +1,76 @@
+//! External identity validation logic
+
+use crate::error_codes::ErrorCode;
+use crate::user::validation::common::{field_error, ValidationResult};
+use crate::user::validation::validate_external_id;
+
+/// Maximum length of a system name
+pub const MAX_SYSTEM_LENGTH: usize = 50;
+
+/// Normalizes a system name for storage and lookup (trimmed, lowercase)
+#[must_use]
+pub fn normalize_system(name: &str) -> String {
+    name.trim().to_lowercase()
+}
+
+/// Validates a normalized system name
+///
+/// System names may contain lowercase letters, digits, `-` and `_`.
+pub fn validate_system(system: &str) -> ValidationResult {
+    let mut errors = Vec::new();
+
+    if system.is_empty() {
+        errors.push(field_error("system", ErrorCode::FieldEmpty, "System cannot be empty"));
+    }
+
+    if system.len() > MAX_SYSTEM_LENGTH {
+        errors.push(field_error("system", ErrorCode::TooLong, format!("System cannot exceed {MAX_SYSTEM_LENGTH} characters")));
+    }
+
+    if !system
+        .chars()
+        .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_')
+    {
+        errors.push(field_error("system", ErrorCode::InvalidCharacters, "System can only contain letters, digits, '-' and '_'"));
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+/// Validates a normalized system name and an external ID
+pub fn validate_identity(system: &str, external_id: &str) -> ValidationResult {
+    let mut errors = validate_system(system).err().unwrap_or_default();
+
+    if let Err(mut id_errors) = validate_external_id(external_id, "external_id") {
+        errors.append(&mut id_errors);
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_system() {
+        assert_eq!(normalize_system("  Salesforce "), "salesforce");
+    }
+
+    #[test]
+    fn test_validate_identity() {
+        assert!(validate_identity("crm", "0015g00000abc").is_ok());
+        assert!(validate_identity("idp", "auth0|64f1").is_ok());
+        assert!(validate_identity("", "42").is_err());
+        assert!(validate_identity("my crm", "42").is_err());
+        assert!(validate_identity("crm", " ").is_err());
+    }
+}
@@ -0,0 +1,154 @@
+//! IP allow/deny filtering
+//!
+//! Guards route prefixes (the admin surface) by client address. Rules are
+//! CIDR blocks (`10.0.0.0/8`, `2001:db8::/32`) or single addresses. A request
+//! is rejected with 403 when its client matches a deny rule, or when allow
+//! rules are set and it matches none of them; rejections are logged on the
+//! `audit` target.
+//!
+//! The client address is the request's [`ClientIp`], so forwarding headers
+//! count only from trusted proxies. Without one (a router not served with
+//! `ConnectInfo<SocketAddr>`) guarded routes are rejected while rules are set.
+
+use std::{net::IpAddr, sync::Arc};
+
+use axum::{
+    extract::{MatchedPath, Request, State},
+    http::StatusCode,
+    middleware::Next,
+    response::Response,
+};
+use tracing::warn;
+
+use crate::client_ip::{ClientIp, IpNet};
+use crate::error_codes::{ErrorCode, error_response};
+
+/// Client address rules for a set of route prefixes
+#[derive(Debug, Clone, Default)]
+pub struct IpFilter {
+    prefixes: Vec<String>,
+    allow: Vec<IpNet>,
+    deny: Vec<IpNet>,
+}
+
+impl IpFilter {
+    /// Creates a filter that guards nothing and lets every address through
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Applies the rules to routes whose pattern is `prefix` or lies below it
+    #[must_use]
+    pub fn guard(mut self, prefix: &str) -> Self {
+        self.prefixes.push(prefix.to_owned());
+        self
+    }
+
+    /// Only lets clients in `blocks` through (every client when empty)
+    #[must_use]
+    pub fn allow(mut self, blocks: Vec<IpNet>) -> Self {
+        self.allow = blocks;
+        self
+    }
+
+    /// Rejects clients in `blocks`, even when allowed
+    #[must_use]
+    pub fn deny(mut self, blocks: Vec<IpNet>) -> Self {
+        self.deny = blocks;
+        self
+    }
+
+    /// Whether requests to the route pattern `path` are filtered
+    fn guards(&self, path: &str) -> bool {
+        (!self.allow.is_empty() || !self.deny.is_empty())
+            && self.prefixes.iter().any(|prefix| {
+                path.strip_prefix(prefix.as_str())
+                    .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+            })
+    }
+
+    /// Whether the rules let `ip` through
+    #[must_use]
+    pub fn permits(&self, ip: IpAddr) -> bool {
+        !self.deny.iter().any(|block| block.contains(ip))
+            && (self.allow.is_empty() || self.allow.iter().any(|block| block.contains(ip)))
+    }
+}
+
+/// Middleware rejecting requests to guarded routes from clients the [`IpFilter`] does not permit
+pub async fn filter_ips(State(filter): State<Arc<IpFilter>>, request: Request, next: Next) -> Response {
+    let guarded = request
+        .extensions()
+        .get::<MatchedPath>()
+        .is_some_and(|path| filter.guards(path.as_str()));
+    if !guarded {
+        return next.run(request).await;
+    }
+
+    let client = request.extensions().get::<ClientIp>().map(|ClientIp(client)| *client);
+    if client.is_some_and(|client| filter.permits(client)) {
+        return next.run(request).await;
+    }
+
+    warn!(
+        target: "audit",
+        client = %client.map_or_else(|| "unknown".to_owned(), |client| client.to_string()),
+        method = %request.method(),
+        uri = %request.uri(),
+        "Blocked request from a disallowed address"
+    );
+    error_response(StatusCode::FORBIDDEN, ErrorCode::IpNotAllowed, "Requests from this address may not use this route")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::SocketAddr;
+    use axum::{Router, body::Body, extract::connect_info::MockConnectInfo, middleware, routing::get};
+    use tower::ServiceExt;
+
+    use crate::client_ip::{TrustedProxies, resolve_client_ip};
+
+    fn ip(value: &str) -> IpAddr {
+        value.parse().unwrap()
+    }
+
+    fn blocks(spec: &str) -> Vec<IpNet> {
+        IpNet::parse_list(spec)
+    }
+
+    #[test]
+    fn test_deny_rules_win_over_allow_rules() {
+        let filter = IpFilter::new().allow(blocks("10.0.0.0/8")).deny(blocks("10.9.0.0/16"));
+
+        assert!(filter.permits(ip("10.1.2.3")));
+        assert!(!filter.permits(ip("10.9.2.3")));
+        assert!(!filter.permits(ip("192.0.2.1")));
+        assert!(IpFilter::new().deny(blocks("192.0.2.0/24")).permits(ip("198.51.100.1")));
+    }
+
+    async fn status(path: &str, peer: Option<&str>) -> StatusCode {
+        let filter = IpFilter::new().guard("/admin").allow(blocks("10.0.0.0/8"));
+        let mut app = Router::new()
+            .route("/admin/config", get(|| async { "admin" }))
+            .route("/administrators", get(|| async { "public" }))
+            .route_layer(middleware::from_fn_with_state(Arc::new(filter), filter_ips))
+            .layer(middleware::from_fn_with_state(Arc::new(TrustedProxies::default()), resolve_client_ip));
+        if let Some(peer) = peer {
+            app = app.layer(MockConnectInfo(SocketAddr::new(ip(peer), 4000)));
+        }
+        app.oneshot(Request::get(path).body(Body::empty()).unwrap())
+            .await
+            .unwrap()
+            .status()
+    }
+
+    #[tokio::test]
+    async fn test_only_guarded_routes_are_filtered() {
+        assert_eq!(status("/admin/config", Some("10.0.0.1")).await, StatusCode::OK);
+        assert_eq!(status("/admin/config", Some("192.0.2.1")).await, StatusCode::FORBIDDEN);
+        assert_eq!(status("/admin/config", None).await, StatusCode::FORBIDDEN);
+        assert_eq!(status("/administrators", Some("192.0.2.1")).await, StatusCode::OK);
+    }
+}
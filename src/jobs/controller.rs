@@ -0,0 +1,74 @@
+//! Job queue controller - HTTP handlers inspecting and replaying queued jobs
+
+use axum::{
+    Json,
+    extract::{Path, Query},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use tracing::warn;
+
+use crate::error_codes::{ErrorCode, ErrorResponse, internal_error};
+use crate::registry::Inject;
+
+use super::queue::{JobListParams, JobQueue, QueueError, QueuedJob};
+
+/// Maps queue errors to HTTP responses
+fn error_response(error: &QueueError) -> Response {
+    let (status, code) = match error {
+        QueueError::NotFound(_) => (StatusCode::NOT_FOUND, ErrorCode::JobNotFound),
+        QueueError::NotDead { .. } => (StatusCode::CONFLICT, ErrorCode::JobNotDead),
+        QueueError::DatabaseError(db) => return db.clone().into_response(),
+        // Only workers settle jobs, so no request loses a lease
+        QueueError::LeaseLost { .. } => return internal_error(),
+    };
+    warn!(error = %error, "Controller: Job queue request rejected");
+    (status, Json(ErrorResponse::new(code, error.to_string()))).into_response()
+}
+
+/// HTTP handler listing queued jobs, e.g. the dead-letter queue with `?status=dead`
+#[utoipa::path(
+    get,
+    path = "/admin/jobs",
+    tag = "admin",
+    params(JobListParams),
+    responses(
+        (status = 200, description = "Jobs newest first, with their attempts and last error", body = [QueuedJob]),
+        (status = 400, description = "Unknown status or invalid limit", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+        (status = 403, description = "Caller lacks the admin role", body = ErrorResponse),
+        (status = 503, description = "The database is unavailable", body = ErrorResponse)
+    )
+)]
+#[tracing::instrument(skip(job_queue))]
+pub async fn list_jobs_handler(Inject(job_queue): Inject<JobQueue>, Query(params): Query<JobListParams>) -> Response {
+    match job_queue.list(&params).await {
+        Ok(jobs) => (StatusCode::OK, Json(jobs)).into_response(),
+        Err(e) => error_response(&e),
+    }
+}
+
+/// HTTP handler queueing a dead job again with a fresh set of attempts
+#[utoipa::path(
+    post,
+    path = "/admin/jobs/{id}/retry",
+    tag = "admin",
+    params(
+        ("id" = i64, Path, description = "Job ID")
+    ),
+    responses(
+        (status = 200, description = "Job queued again; its attempts start over", body = QueuedJob),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+        (status = 403, description = "Caller lacks the admin role", body = ErrorResponse),
+        (status = 404, description = "No job has this ID", body = ErrorResponse),
+        (status = 409, description = "The job is not dead", body = ErrorResponse),
+        (status = 503, description = "The database is unavailable", body = ErrorResponse)
+    )
+)]
+#[tracing::instrument(skip(job_queue))]
+pub async fn retry_job_handler(Inject(job_queue): Inject<JobQueue>, Path(id): Path<i64>) -> Response {
+    match job_queue.retry(id).await {
+        Ok(job) => (StatusCode::OK, Json(job)).into_response(),
+        Err(e) => error_response(&e),
+    }
+}
@@ -0,0 +1,193 @@
+//! Locks shared between replicas
+//!
+//! A [`DistributedLock`] is a Postgres session-level advisory lock, so only
+//! one replica at a time holds it, and the database releases it if that
+//! replica's connection dies. [`singleton`] wraps a job so each run first
+//! takes the lock named after the job, and a replica that finds the lock
+//! taken skips the run. The lock only keeps runs from overlapping: replicas
+//! tick at different times, so holding it in turn they would each run the
+//! job once per interval. Under the lock, the wrapper therefore also records
+//! when the job last started in `job_runs`, and skips the run if any replica
+//! started it within the interval.
+
+use std::{sync::Arc, time::Duration};
+
+use sha2::{Digest, Sha256};
+use sqlx::{PgPool, Postgres, pool::PoolConnection};
+use tracing::{debug, warn};
+
+use super::{Job, JobError, SharedJob};
+
+/// Lock held by at most one connection across all replicas
+#[derive(Debug, Clone)]
+pub struct DistributedLock {
+    pool: PgPool,
+    name: String,
+    key: i64,
+}
+
+impl DistributedLock {
+    /// Lock called `name`; replicas using the same name contend for the same lock
+    #[must_use]
+    pub fn new(pool: PgPool, name: impl Into<String>) -> Self {
+        let name = name.into();
+        let key = advisory_key(&name);
+        Self { pool, name, key }
+    }
+
+    /// Name of the lock
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Takes the lock if no one holds it; `None` when another holder has it
+    ///
+    /// The lock is held on a dedicated connection until the guard is released
+    /// or dropped.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no connection is available or the query fails.
+    pub async fn try_acquire(&self) -> Result<Option<LockGuard>, sqlx::Error> {
+        let mut connection = self.pool.acquire().await?;
+        let acquired = sqlx::query_scalar!(r#"SELECT pg_try_advisory_lock($1) AS "acquired!""#, self.key)
+            .fetch_one(&mut *connection)
+            .await?;
+        Ok(acquired.then(|| LockGuard {
+            connection: Some(connection),
+            key: self.key,
+        }))
+    }
+}
+
+/// Holds a [`DistributedLock`]
+///
+/// Release it with [`LockGuard::release`]. A guard that is dropped instead
+/// closes its connection, which also releases the lock, rather than returning
+/// a connection that still holds it to the pool.
+#[derive(Debug)]
+pub struct LockGuard {
+    connection: Option<PoolConnection<Postgres>>,
+    key: i64,
+}
+
+impl LockGuard {
+    /// Releases the lock and returns the connection to the pool
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the unlock query fails; the connection is then
+    /// closed, which releases the lock.
+    pub async fn release(mut self) -> Result<(), sqlx::Error> {
+        let Some(mut connection) = self.connection.take() else {
+            return Ok(());
+        };
+        let released = sqlx::query_scalar!(r#"SELECT pg_advisory_unlock($1) AS "released!""#, self.key)
+            .fetch_one(&mut *connection)
+            .await;
+        if released.is_err() {
+            drop(connection.detach());
+        }
+        released.map(drop)
+    }
+}
+
+impl Drop for LockGuard {
+    fn drop(&mut self) {
+        if let Some(connection) = self.connection.take() {
+            drop(connection.detach());
+        }
+    }
+}
+
+/// Share of the interval during which a run on one replica makes the others
+/// skip theirs; the rest absorbs scheduling jitter, so the replica that ran
+/// last is not skipped at its own next tick
+const RECENT_RUN_SHARE: f64 = 0.9;
+
+/// Wraps `job`, scheduled every `every` on each replica, so that it runs on
+/// one replica at a time and once per `every` across the deployment
+#[must_use]
+pub fn singleton(pool: PgPool, job: SharedJob, every: Duration) -> SharedJob {
+    let lock = DistributedLock::new(pool.clone(), format!("job:{}", job.name()));
+    Arc::new(SingletonJob { job, lock, pool, every })
+}
+
+/// Job running only while holding its [`DistributedLock`], at most once per interval
+struct SingletonJob {
+    job: SharedJob,
+    lock: DistributedLock,
+    pool: PgPool,
+    /// Interval the job is scheduled at
+    every: Duration,
+}
+
+impl SingletonJob {
+    /// Records that the job starts now, unless a replica started it within the interval
+    ///
+    /// Returns whether the job is due.
+    async fn start_run(&self) -> Result<bool, sqlx::Error> {
+        let started = sqlx::query_scalar!(
+            "INSERT INTO job_runs (job, last_run_at) VALUES ($1, NOW())
+             ON CONFLICT (job) DO UPDATE SET last_run_at = NOW()
+             WHERE job_runs.last_run_at <= NOW() - make_interval(secs => $2)
+             RETURNING job",
+            self.name(),
+            self.every.as_secs_f64() * RECENT_RUN_SHARE
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(started.is_some())
+    }
+}
+
+impl Job for SingletonJob {
+    fn name(&self) -> &'static str {
+        self.job.name()
+    }
+
+    fn run(&self) -> futures_util::future::BoxFuture<'_, Result<(), JobError>> {
+        Box::pin(async move {
+            let Some(guard) = self.lock.try_acquire().await? else {
+                debug!(job = self.name(), lock = self.lock.name(), "Job is running on another replica; skipping");
+                return Ok(());
+            };
+            let result = match self.start_run().await {
+                Ok(true) => self.job.run().await,
+                Ok(false) => {
+                    debug!(job = self.name(), "Job ran on another replica within its interval; skipping");
+                    Ok(())
+                }
+                Err(e) => Err(e.into()),
+            };
+            if let Err(e) = guard.release().await {
+                warn!(job = self.name(), error = %e, "Failed to release the job lock; its connection was closed");
+            }
+            result
+        })
+    }
+
+    fn cancel(&self) {
+        self.job.cancel();
+    }
+}
+
+/// Advisory lock key of `name`: the first 8 bytes of its SHA-256 digest
+fn advisory_key(name: &str) -> i64 {
+    let digest = Sha256::digest(format!("rust_kickstart:{name}"));
+    let mut key = [0; 8];
+    key.copy_from_slice(&digest[..8]);
+    i64::from_be_bytes(key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_advisory_keys_are_stable_per_name() {
+        assert_eq!(advisory_key("job:interest-accrual"), advisory_key("job:interest-accrual"));
+        assert_ne!(advisory_key("job:interest-accrual"), advisory_key("job:ledger-verification"));
+    }
+}
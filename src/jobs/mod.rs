@@ -0,0 +1,305 @@
+//! Background jobs
+//!
+//! A [`Job`] is a named unit of background work. [`spawn_periodic`] runs a
+//! job at a fixed interval on the Tokio runtime; a failed run is logged and
+//! retried at the next tick, so jobs should be idempotent. `AppBuilder`
+//! starts the built-in jobs after the startup hooks and, on shutdown, lets
+//! the runs in progress finish within a grace period ([`JobHandle::stop`]).
+//! A [`JobTracker`] counts the runs in progress for monitoring, also in the
+//! `jobs_running` metric. With several replicas, [`singleton`] keeps a
+//! job from running on more than one of them at a time, and from running
+//! more than once per interval across them.
+//!
+//! Work that must not be lost goes through the durable [`JobQueue`] instead:
+//! a [`QueueWorker`] runs each queued job with the [`JobHandler`] of its
+//! kind, retries failures up to a maximum number of attempts, and then moves
+//! the job to the dead-letter queue, inspected with `GET /admin/jobs?status=dead`
+//! and replayed with `POST /admin/jobs/{id}/retry`.
+
+use std::{
+    error::Error,
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::Duration,
+};
+
+use futures_util::future::BoxFuture;
+use tokio::{sync::Notify, task::JoinHandle, time::MissedTickBehavior};
+use tracing::{error, info, warn};
+
+use crate::metrics::JOBS_RUNNING;
+
+pub mod controller;
+mod lock;
+pub mod queue;
+mod worker;
+
+pub use lock::{DistributedLock, LockGuard, singleton};
+pub use queue::{JobListParams, JobQueue, JobStatus, NewJob, QueueError, QueuedJob};
+pub use worker::{JobHandler, QueueWorker, SharedJobHandler};
+
+// Export controller for OpenAPI documentation
+pub use controller::*;
+
+/// Error returned by a failed job run
+pub type JobError = Box<dyn Error + Send + Sync>;
+
+/// A unit of background work
+pub trait Job: Send + Sync {
+    /// Name used in logs
+    fn name(&self) -> &'static str;
+
+    /// Runs the job once
+    fn run(&self) -> BoxFuture<'_, Result<(), JobError>>;
+
+    /// Asks the run in progress to hand back the work it has not started,
+    /// as the job is being stopped; does nothing by default
+    fn cancel(&self) {}
+}
+
+/// Job shared between schedulers
+pub type SharedJob = Arc<dyn Job>;
+
+/// Counts the runs of tracked jobs that are in progress
+///
+/// Clones share the same count. Jobs run in-process at fixed intervals, so
+/// the runs in progress are all the background work waiting to complete.
+#[derive(Debug, Clone, Default)]
+pub struct JobTracker {
+    running: Arc<AtomicU64>,
+}
+
+impl JobTracker {
+    /// Wraps `job` so that its runs are counted while in progress
+    #[must_use]
+    pub fn track(&self, job: SharedJob) -> SharedJob {
+        Arc::new(TrackedJob {
+            job,
+            running: Arc::clone(&self.running),
+        })
+    }
+
+    /// Runs of tracked jobs currently in progress
+    #[must_use]
+    pub fn running(&self) -> u64 {
+        self.running.load(Ordering::Relaxed)
+    }
+}
+
+/// Job whose runs are counted by a [`JobTracker`]
+struct TrackedJob {
+    job: SharedJob,
+    running: Arc<AtomicU64>,
+}
+
+/// Decrements the running counts when a run ends, including when it is aborted
+struct RunGuard {
+    /// Runs in progress of the tracker
+    running: Arc<AtomicU64>,
+    /// Name of the job, labelling its series of the [`JOBS_RUNNING`] gauge
+    job: &'static str,
+}
+
+impl Drop for RunGuard {
+    fn drop(&mut self) {
+        self.running.fetch_sub(1, Ordering::Relaxed);
+        JOBS_RUNNING.decrement(&[("job", self.job)]);
+    }
+}
+
+impl Job for TrackedJob {
+    fn name(&self) -> &'static str {
+        self.job.name()
+    }
+
+    fn run(&self) -> BoxFuture<'_, Result<(), JobError>> {
+        Box::pin(async move {
+            self.running.fetch_add(1, Ordering::Relaxed);
+            JOBS_RUNNING.increment(&[("job", self.name())]);
+            let _guard = RunGuard {
+                running: Arc::clone(&self.running),
+                job: self.name(),
+            };
+            self.job.run().await
+        })
+    }
+
+    fn cancel(&self) {
+        self.job.cancel();
+    }
+}
+
+/// Runs `job` immediately and then every `every`, until the handle is stopped or aborted
+///
+/// Runs never overlap: a run that overruns the interval delays the next one.
+#[must_use]
+pub fn spawn_periodic(job: SharedJob, every: Duration) -> JobHandle {
+    let name = job.name();
+    let cancel = Arc::clone(&job);
+    let stop = Arc::new(Notify::new());
+    let stopped = Arc::clone(&stop);
+    let task = tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(every);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        loop {
+            // A stop request only ends the loop between runs, never during one
+            tokio::select! {
+                biased;
+                () = stopped.notified() => break,
+                _ = ticker.tick() => {},
+            }
+            info!(job = job.name(), "Running job");
+            if let Err(e) = job.run().await {
+                error!(job = job.name(), error = %e, "Job failed; retrying at the next run");
+            }
+        }
+    });
+    JobHandle { name, job: cancel, task, stop }
+}
+
+/// Handle of a job started by [`spawn_periodic`]
+pub struct JobHandle {
+    /// Name of the job
+    name: &'static str,
+    /// The job, asked to hand back unstarted work when stopped
+    job: SharedJob,
+    /// Task running the job
+    task: JoinHandle<()>,
+    /// Signals the task to stop after the run in progress
+    stop: Arc<Notify>,
+}
+
+impl std::fmt::Debug for JobHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("JobHandle").field("name", &self.name).field("task", &self.task).finish_non_exhaustive()
+    }
+}
+
+impl JobHandle {
+    /// Name of the job
+    #[must_use]
+    pub const fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Stops the job immediately, interrupting a run in progress
+    pub fn abort(&self) {
+        self.task.abort();
+    }
+
+    /// Stops scheduling runs and waits up to `grace` for the run in progress
+    ///
+    /// The run is [cancelled](Job::cancel) first, so it hands back the work
+    /// it has not started and only finishes what it started.
+    /// A run still going after `grace` is aborted. Its open transaction rolls
+    /// back and its [`DistributedLock`] is released with its connection, so
+    /// the work it claimed is picked up again by the next run, on this or
+    /// another replica. Returns `true` when the job stopped without being
+    /// aborted.
+    pub async fn stop(mut self, grace: Duration) -> bool {
+        self.stop.notify_one();
+        self.job.cancel();
+        if tokio::time::timeout(grace, &mut self.task).await.is_ok() {
+            info!(job = self.name, "Job stopped");
+            true
+        } else {
+            self.task.abort();
+            warn!(job = self.name, grace_secs = grace.as_secs(), "Job run did not finish in time; aborted and left for the next run");
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicUsize;
+
+    struct Counter(AtomicUsize);
+
+    impl Job for Counter {
+        fn name(&self) -> &'static str {
+            "counter"
+        }
+
+        fn run(&self) -> BoxFuture<'_, Result<(), JobError>> {
+            let runs = self.0.fetch_add(1, Ordering::Relaxed);
+            Box::pin(async move {
+                if runs == 0 {
+                    Err("first run fails".into())
+                } else {
+                    Ok(())
+                }
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_periodic_job_keeps_running_after_failures() {
+        let counter = Arc::new(Counter(AtomicUsize::new(0)));
+
+        let handle = spawn_periodic(Arc::clone(&counter) as SharedJob, Duration::from_millis(20));
+        tokio::time::sleep(Duration::from_millis(90)).await;
+        handle.abort();
+
+        assert!(counter.0.load(Ordering::Relaxed) >= 3, "The job should run on every tick");
+    }
+
+    struct Blocking(tokio::sync::Notify);
+
+    impl Job for Blocking {
+        fn name(&self) -> &'static str {
+            "blocking"
+        }
+
+        fn run(&self) -> BoxFuture<'_, Result<(), JobError>> {
+            Box::pin(async move {
+                self.0.notified().await;
+                Ok(())
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_stop_lets_the_run_in_progress_finish() {
+        let blocking = Arc::new(Blocking(tokio::sync::Notify::new()));
+        let handle = spawn_periodic(Arc::clone(&blocking) as SharedJob, Duration::from_mins(1));
+        tokio::time::sleep(Duration::from_millis(20)).await;
+
+        let stopping = tokio::spawn(handle.stop(Duration::from_secs(5)));
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(!stopping.is_finished(), "Stopping should wait for the run in progress");
+        blocking.0.notify_one();
+        assert!(stopping.await.unwrap(), "A run finishing within the grace period should not be aborted");
+    }
+
+    #[tokio::test]
+    async fn test_stop_aborts_runs_exceeding_the_grace_period() {
+        let tracker = JobTracker::default();
+        let job = tracker.track(Arc::new(Blocking(tokio::sync::Notify::new())));
+        let handle = spawn_periodic(job, Duration::from_mins(1));
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert_eq!(tracker.running(), 1);
+
+        assert!(!handle.stop(Duration::from_millis(20)).await, "An overrunning run should be aborted");
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        assert_eq!(tracker.running(), 0, "An aborted run should no longer be counted");
+    }
+
+    #[tokio::test]
+    async fn test_tracker_counts_runs_in_progress() {
+        let tracker = JobTracker::default();
+        let blocking = Arc::new(Blocking(tokio::sync::Notify::new()));
+        let job = tracker.track(Arc::clone(&blocking) as SharedJob);
+
+        let run = tokio::spawn(async move { job.run().await.map_err(|e| e.to_string()) });
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert_eq!(tracker.running(), 1, "A blocked run should be counted");
+
+        blocking.0.notify_one();
+        assert!(matches!(run.await, Ok(Ok(()))));
+        assert_eq!(tracker.running(), 0, "A finished run should no longer be counted");
+    }
+}
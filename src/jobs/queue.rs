@@ -0,0 +1,461 @@
+//! Durable job queue
+//!
+//! Jobs are rows of the `jobs` table, enqueued with a kind and a JSON
+//! payload on a named queue and run by the [`QueueWorker`](super::QueueWorker)s
+//! of that queue. Workers claim the due jobs of their queue, highest
+//! [`priority`](NewJob::with_priority) first; a job can be delayed with
+//! [`NewJob::run_at`] or [`NewJob::delayed_by`]. Giving time-sensitive work a
+//! queue of its own keeps it from waiting behind bulk work. Claiming a job
+//! counts an attempt and hides the job from other workers until its
+//! visibility timeout passes, so a job whose worker dies mid-run is claimed
+//! again instead of lost. The claim is a lease on that attempt, renewed when
+//! the job starts: a worker whose job was claimed again in the meantime can
+//! no longer run or settle it. A failed job is queued again until it has used
+//! `max_attempts`, and is then dead-lettered: it stays `dead` until an
+//! operator retries it (`POST /admin/jobs/{id}/retry`).
+
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::PgPool;
+use tracing::{error, info};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::db::DbError;
+
+/// Default number of attempts before a job is dead-lettered
+pub const DEFAULT_MAX_ATTEMPTS: i32 = 5;
+
+/// Default time a claimed job stays hidden from other workers
+pub const DEFAULT_VISIBILITY_TIMEOUT: Duration = Duration::from_mins(5);
+
+/// Queue of jobs enqueued without one
+pub const DEFAULT_QUEUE: &str = "default";
+
+/// Jobs returned by a listing when no limit is given
+const DEFAULT_LIST_LIMIT: i64 = 50;
+
+/// Most jobs a listing returns
+const MAX_LIST_LIMIT: i64 = 200;
+
+/// Where a job is in its lifecycle
+#[derive(Serialize, Deserialize, ToSchema, JsonSchema, Debug, Clone, Copy, PartialEq, Eq, sqlx::Type)]
+#[sqlx(type_name = "job_status", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum JobStatus {
+    /// Waiting for a worker, including after a failed attempt
+    Queued,
+    /// Claimed by a worker until its visibility timeout
+    Running,
+    /// Ran successfully
+    Succeeded,
+    /// Failed on every attempt; kept until retried
+    Dead,
+}
+
+/// A job in the queue
+#[derive(Serialize, ToSchema, JsonSchema, Debug, Clone)]
+pub struct QueuedJob {
+    /// Unique job identifier
+    pub id: i64,
+    /// Kind of job, naming the handler that runs it
+    pub kind: String,
+    /// Input of the handler
+    pub payload: Value,
+    /// Queue whose workers run the job
+    pub queue: String,
+    /// Jobs of higher priority are claimed first
+    pub priority: i32,
+    /// When the job becomes due
+    pub run_at: DateTime<Utc>,
+    /// Where the job is in its lifecycle
+    pub status: JobStatus,
+    /// Attempts made so far, including one in progress
+    pub attempts: i32,
+    /// Attempts allowed before the job is dead-lettered
+    pub max_attempts: i32,
+    /// Error of the last failed attempt
+    pub last_error: Option<String>,
+    /// When the job was enqueued
+    pub created_at: DateTime<Utc>,
+    /// When the job last changed
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Query parameters of `GET /admin/jobs`
+#[derive(Deserialize, IntoParams, Debug, Clone, Default)]
+#[into_params(parameter_in = Query)]
+pub struct JobListParams {
+    /// Only jobs with this status, e.g. `dead`
+    pub status: Option<JobStatus>,
+    /// Only jobs of this queue
+    pub queue: Option<String>,
+    /// Most jobs to return, newest first (default 50, at most 200)
+    pub limit: Option<i64>,
+}
+
+/// A job to enqueue
+#[derive(Debug, Clone)]
+pub struct NewJob {
+    /// Kind of job, naming the handler that runs it
+    kind: String,
+    /// Input of the handler
+    payload: Value,
+    /// Queue whose workers run the job
+    queue: String,
+    /// Jobs of higher priority are claimed first
+    priority: i32,
+    /// When the job becomes due; `None` for the time of enqueueing plus `delay`
+    run_at: Option<DateTime<Utc>>,
+    /// Delay after enqueueing when `run_at` is not set
+    delay: Duration,
+}
+
+impl NewJob {
+    /// A job of `kind` on the default queue, due now, with priority 0
+    #[must_use]
+    pub fn new(kind: impl Into<String>, payload: Value) -> Self {
+        Self {
+            kind: kind.into(),
+            payload,
+            queue: DEFAULT_QUEUE.to_owned(),
+            priority: 0,
+            run_at: None,
+            delay: Duration::ZERO,
+        }
+    }
+
+    /// Runs the job on `queue`
+    #[must_use]
+    pub fn on_queue(mut self, queue: impl Into<String>) -> Self {
+        self.queue = queue.into();
+        self
+    }
+
+    /// Claims the job before due jobs of its queue with a lower priority
+    #[must_use]
+    pub const fn with_priority(mut self, priority: i32) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Keeps the job from running before `at`
+    #[must_use]
+    pub const fn run_at(mut self, at: DateTime<Utc>) -> Self {
+        self.run_at = Some(at);
+        self
+    }
+
+    /// Keeps the job from running until `delay` after it is enqueued, by the database clock
+    #[must_use]
+    pub const fn delayed_by(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+}
+
+/// Errors of queue operations
+#[derive(Debug, thiserror::Error)]
+pub enum QueueError {
+    /// No job has this ID
+    #[error("Job {0} not found")]
+    NotFound(i64),
+    /// Only dead jobs can be retried
+    #[error("Job {id} is {status:?}, only dead jobs can be retried")]
+    NotDead {
+        /// The job
+        id: i64,
+        /// Its current status
+        status: JobStatus,
+    },
+    /// The attempt's visibility timeout passed and the job was claimed again or dead-lettered
+    #[error("Job {id} is no longer leased to attempt {attempt}")]
+    LeaseLost {
+        /// The job
+        id: i64,
+        /// The attempt whose lease was lost
+        attempt: i32,
+    },
+    /// Database operation failed
+    #[error("Database error: {0}")]
+    DatabaseError(DbError),
+}
+
+/// Maps a database error to `QueueError`, logging it with `context`
+fn database_error(context: &'static str) -> impl FnOnce(sqlx::Error) -> QueueError {
+    move |e| {
+        error!(error = %e, "{context}");
+        QueueError::DatabaseError(e.into())
+    }
+}
+
+/// Enqueues, claims and settles jobs stored in the `jobs` table
+#[derive(Debug, Clone)]
+pub struct JobQueue {
+    pool: PgPool,
+    max_attempts: i32,
+    visibility_timeout: Duration,
+}
+
+impl JobQueue {
+    /// Creates a queue over the `jobs` table
+    #[must_use]
+    pub const fn new(pool: PgPool) -> Self {
+        Self {
+            pool,
+            max_attempts: DEFAULT_MAX_ATTEMPTS,
+            visibility_timeout: DEFAULT_VISIBILITY_TIMEOUT,
+        }
+    }
+
+    /// Attempts jobs enqueued from now on get before they are dead-lettered (at least 1)
+    #[must_use]
+    pub fn with_max_attempts(mut self, max_attempts: i32) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    /// How long a claimed job stays hidden from other workers; a run should finish well within it
+    #[must_use]
+    pub const fn with_visibility_timeout(mut self, timeout: Duration) -> Self {
+        self.visibility_timeout = timeout;
+        self
+    }
+
+    /// Adds a job of `kind` to the default queue, due now
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the job cannot be stored.
+    pub async fn enqueue(&self, kind: &str, payload: Value) -> Result<QueuedJob, QueueError> {
+        self.push(NewJob::new(kind, payload)).await
+    }
+
+    /// Adds `job` to its queue
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the job cannot be stored.
+    pub async fn push(&self, job: NewJob) -> Result<QueuedJob, QueueError> {
+        let job = sqlx::query_as!(
+            QueuedJob,
+            r#"INSERT INTO jobs (kind, payload, queue, priority, run_at, max_attempts)
+               VALUES ($1, $2, $3, $4, COALESCE($5, NOW() + make_interval(secs => $6)), $7)
+               RETURNING id, kind, payload, queue, priority, run_at, status AS "status: JobStatus", attempts, max_attempts, last_error, created_at, updated_at"#,
+            job.kind,
+            job.payload,
+            job.queue,
+            job.priority,
+            job.run_at,
+            job.delay.as_secs_f64(),
+            self.max_attempts
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(database_error("Failed to enqueue job"))?;
+        info!(job_id = job.id, kind = job.kind, queue = job.queue, priority = job.priority, run_at = %job.run_at, "Job enqueued");
+        Ok(job)
+    }
+
+    /// Claims up to `limit` due jobs of `queue`, highest priority first, then oldest first, counting an attempt for each
+    ///
+    /// Jobs claimed by a worker that stopped before settling them are
+    /// claimed again once their visibility timeout passes; those that had
+    /// used their last attempt are dead-lettered instead.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the jobs cannot be claimed.
+    pub async fn claim(&self, queue: &str, limit: i64) -> Result<Vec<QueuedJob>, QueueError> {
+        let abandoned = sqlx::query!(
+            "UPDATE jobs SET status = 'dead', locked_until = NULL, updated_at = NOW(),
+                 last_error = COALESCE(last_error, 'The worker stopped before the job finished')
+             WHERE queue = $1 AND status = 'running' AND locked_until < NOW() AND attempts >= max_attempts",
+            queue
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(database_error("Failed to dead-letter abandoned jobs"))?;
+        if abandoned.rows_affected() > 0 {
+            error!(jobs = abandoned.rows_affected(), queue, "Abandoned jobs used their last attempt; moved to the dead-letter queue");
+        }
+
+        sqlx::query_as!(
+            QueuedJob,
+            r#"WITH claimed AS (
+                   UPDATE jobs SET status = 'running', attempts = attempts + 1,
+                       locked_until = NOW() + make_interval(secs => $3), updated_at = NOW()
+                   WHERE id IN (
+                       SELECT id FROM jobs
+                       WHERE queue = $1
+                         AND ((status = 'queued' AND run_at <= NOW()) OR (status = 'running' AND locked_until < NOW()))
+                       ORDER BY priority DESC, run_at, id
+                       LIMIT $2
+                       FOR UPDATE SKIP LOCKED
+                   )
+                   RETURNING *
+               )
+               SELECT id AS "id!", kind AS "kind!", payload AS "payload!", queue AS "queue!", priority AS "priority!", run_at AS "run_at!",
+                      status AS "status!: JobStatus", attempts AS "attempts!", max_attempts AS "max_attempts!", last_error,
+                      created_at AS "created_at!", updated_at AS "updated_at!"
+               FROM claimed
+               ORDER BY priority DESC, run_at, id"#,
+            queue,
+            limit,
+            self.visibility_timeout.as_secs_f64()
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(database_error("Failed to claim jobs"))
+    }
+
+    /// Extends the lease of a claimed job by the visibility timeout, from now
+    ///
+    /// Workers renew a job's lease when they start it, so the time it waited
+    /// behind the other jobs of its batch does not count against its run.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`QueueError::LeaseLost`] if the job is no longer leased to
+    /// this attempt, and an error if the job cannot be updated.
+    pub async fn renew(&self, job: &QueuedJob) -> Result<(), QueueError> {
+        let renewed = sqlx::query!(
+            "UPDATE jobs SET locked_until = NOW() + make_interval(secs => $3), updated_at = NOW()
+             WHERE id = $1 AND status = 'running' AND attempts = $2",
+            job.id,
+            job.attempts,
+            self.visibility_timeout.as_secs_f64()
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(database_error("Failed to renew job lease"))?;
+        if renewed.rows_affected() == 0 {
+            return Err(QueueError::LeaseLost { id: job.id, attempt: job.attempts });
+        }
+        Ok(())
+    }
+
+    /// Hands a claimed job that was not started back to its queue, without counting the attempt
+    ///
+    /// # Errors
+    ///
+    /// Returns [`QueueError::LeaseLost`] if the job is no longer leased to
+    /// this attempt, and an error if the job cannot be updated.
+    pub async fn release(&self, job: &QueuedJob) -> Result<(), QueueError> {
+        let released = sqlx::query!(
+            "UPDATE jobs SET status = 'queued', attempts = attempts - 1, locked_until = NULL, updated_at = NOW()
+             WHERE id = $1 AND status = 'running' AND attempts = $2",
+            job.id,
+            job.attempts
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(database_error("Failed to release job"))?;
+        if released.rows_affected() == 0 {
+            return Err(QueueError::LeaseLost { id: job.id, attempt: job.attempts });
+        }
+        Ok(())
+    }
+
+    /// Marks a claimed job as succeeded
+    ///
+    /// # Errors
+    ///
+    /// Returns [`QueueError::LeaseLost`] if the job is no longer leased to
+    /// this attempt, and an error if the job cannot be updated.
+    pub async fn complete(&self, job: &QueuedJob) -> Result<(), QueueError> {
+        let completed = sqlx::query!(
+            "UPDATE jobs SET status = 'succeeded', locked_until = NULL, updated_at = NOW()
+             WHERE id = $1 AND status = 'running' AND attempts = $2",
+            job.id,
+            job.attempts
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(database_error("Failed to complete job"))?;
+        if completed.rows_affected() == 0 {
+            return Err(QueueError::LeaseLost { id: job.id, attempt: job.attempts });
+        }
+        Ok(())
+    }
+
+    /// Records a failed attempt of a claimed job, queueing it again or, after its last attempt, dead-lettering it
+    ///
+    /// Returns the new status of the job.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`QueueError::LeaseLost`] if the job is no longer leased to
+    /// this attempt, and an error if the job cannot be updated.
+    pub async fn fail(&self, job: &QueuedJob, error: &str) -> Result<JobStatus, QueueError> {
+        sqlx::query_scalar!(
+            r#"UPDATE jobs SET
+                   status = CASE WHEN attempts >= max_attempts THEN 'dead' ELSE 'queued' END::job_status,
+                   last_error = $3, locked_until = NULL, updated_at = NOW()
+               WHERE id = $1 AND status = 'running' AND attempts = $2
+               RETURNING status AS "status: JobStatus""#,
+            job.id,
+            job.attempts,
+            error
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(database_error("Failed to record job failure"))?
+        .ok_or(QueueError::LeaseLost { id: job.id, attempt: job.attempts })
+    }
+
+    /// Jobs matching `params`, newest first
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the jobs cannot be read.
+    pub async fn list(&self, params: &JobListParams) -> Result<Vec<QueuedJob>, QueueError> {
+        let limit = params.limit.unwrap_or(DEFAULT_LIST_LIMIT).clamp(1, MAX_LIST_LIMIT);
+        sqlx::query_as!(
+            QueuedJob,
+            r#"SELECT id, kind, payload, queue, priority, run_at, status AS "status: JobStatus", attempts, max_attempts, last_error, created_at, updated_at
+               FROM jobs
+               WHERE ($1::job_status IS NULL OR status = $1) AND ($2::text IS NULL OR queue = $2)
+               ORDER BY id DESC
+               LIMIT $3"#,
+            params.status as Option<JobStatus>,
+            params.queue,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(database_error("Failed to list jobs"))
+    }
+
+    /// Queues a dead job again with a fresh set of attempts, due now
+    ///
+    /// # Errors
+    ///
+    /// Returns [`QueueError::NotFound`] for unknown jobs and
+    /// [`QueueError::NotDead`] for jobs that are not dead.
+    pub async fn retry(&self, id: i64) -> Result<QueuedJob, QueueError> {
+        let retried = sqlx::query_as!(
+            QueuedJob,
+            r#"UPDATE jobs SET status = 'queued', attempts = 0, run_at = NOW(), updated_at = NOW()
+               WHERE id = $1 AND status = 'dead'
+               RETURNING id, kind, payload, queue, priority, run_at, status AS "status: JobStatus", attempts, max_attempts, last_error, created_at, updated_at"#,
+            id
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(database_error("Failed to retry job"))?;
+        if let Some(job) = retried {
+            info!(job_id = id, kind = job.kind, "Dead job queued for retry");
+            return Ok(job);
+        }
+
+        let status = sqlx::query_scalar!(r#"SELECT status AS "status: JobStatus" FROM jobs WHERE id = $1"#, id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(database_error("Failed to read job status"))?;
+        Err(status.map_or(QueueError::NotFound(id), |status| QueueError::NotDead { id, status }))
+    }
+}
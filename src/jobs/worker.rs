@@ -0,0 +1,177 @@
+//! Worker running the jobs of the durable queue
+//!
+//! A [`QueueWorker`] is itself a periodic [`Job`]: each run claims a batch
+//! of due jobs of one queue and hands each one to the [`JobHandler`]
+//! registered for its kind, running up to the worker's concurrency at once.
+//! A job's lease is renewed when it starts, and a job whose lease was lost
+//! while it waited or ran is left to the worker now holding it. A worker
+//! being stopped hands the claimed jobs it has not started back to the
+//! queue right away, without counting their attempt, instead of leaving them
+//! to its visibility timeout.
+//! Each queue has its own worker, so a queue of slow bulk jobs does not delay
+//! the others. Workers on several replicas share a queue, as claimed jobs are
+//! hidden from the others; the concurrency limit applies per replica.
+
+use std::{collections::HashMap, sync::Arc};
+
+use futures_util::future::{BoxFuture, join_all};
+use serde_json::Value;
+use tokio::sync::{Semaphore, watch};
+use tracing::{error, info, warn};
+
+use super::{Job, JobError, JobQueue, JobStatus, QueueError, QueuedJob, queue::DEFAULT_QUEUE};
+
+/// Runs the queued jobs of one kind
+pub trait JobHandler: Send + Sync {
+    /// Kind of the jobs this handler runs
+    fn kind(&self) -> &'static str;
+
+    /// Runs one job; a failure is retried until the job's attempts run out
+    fn handle(&self, payload: Value) -> BoxFuture<'_, Result<(), JobError>>;
+}
+
+/// Handler shared between workers
+pub type SharedJobHandler = Arc<dyn JobHandler>;
+
+/// Claims the due jobs of one queue and runs them with the handler of their kind
+#[derive(Clone)]
+pub struct QueueWorker {
+    queue: JobQueue,
+    /// Name of the queue whose jobs are claimed
+    queue_name: String,
+    handlers: HashMap<&'static str, SharedJobHandler>,
+    batch_size: i64,
+    /// Claimed jobs running at once
+    concurrency: usize,
+    /// Set once the worker is cancelled; shared by clones
+    cancelled: Arc<watch::Sender<bool>>,
+}
+
+impl QueueWorker {
+    /// Creates a worker of the default queue without handlers, claiming 10 jobs per run and running one at a time
+    #[must_use]
+    pub fn new(queue: JobQueue) -> Self {
+        Self {
+            queue,
+            queue_name: DEFAULT_QUEUE.to_owned(),
+            handlers: HashMap::new(),
+            batch_size: 10,
+            concurrency: 1,
+            cancelled: Arc::new(watch::Sender::new(false)),
+        }
+    }
+
+    /// Claims the jobs of `queue` instead of the default queue
+    #[must_use]
+    pub fn on_queue(mut self, queue: impl Into<String>) -> Self {
+        self.queue_name = queue.into();
+        self
+    }
+
+    /// Jobs of a run running at once (at least 1)
+    #[must_use]
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Runs jobs of `handler.kind()` with `handler`, replacing any handler of that kind
+    #[must_use]
+    pub fn with_handler(mut self, handler: SharedJobHandler) -> Self {
+        self.handlers.insert(handler.kind(), handler);
+        self
+    }
+
+    /// Jobs claimed per run (at least 1)
+    #[must_use]
+    pub fn with_batch_size(mut self, batch_size: i64) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Runs one claimed job and records its outcome
+    ///
+    /// A job whose lease was lost, because it outlived its visibility
+    /// timeout and was claimed again or dead-lettered, is not run, or has its
+    /// outcome dropped; the lease holder settles it.
+    async fn settle(&self, job: QueuedJob) -> Result<(), JobError> {
+        match self.run_leased(&job).await {
+            Err(QueueError::LeaseLost { .. }) => {
+                warn!(job_id = job.id, kind = job.kind, queue = job.queue, attempt = job.attempts, "Queued job lost its lease; left to the worker holding it");
+                Ok(())
+            }
+            settled => Ok(settled?),
+        }
+    }
+
+    /// Renews the lease of a claimed job, runs it and records its outcome
+    async fn run_leased(&self, job: &QueuedJob) -> Result<(), QueueError> {
+        self.queue.renew(job).await?;
+        let outcome = match self.handlers.get(job.kind.as_str()) {
+            Some(handler) => handler.handle(job.payload.clone()).await,
+            None => Err(format!("No handler for jobs of kind `{}`", job.kind).into()),
+        };
+        match outcome {
+            Ok(()) => {
+                self.queue.complete(job).await?;
+                info!(job_id = job.id, kind = job.kind, queue = job.queue, attempt = job.attempts, "Queued job succeeded");
+            }
+            Err(e) => {
+                if self.queue.fail(job, &e.to_string()).await? == JobStatus::Dead {
+                    error!(
+                        job_id = job.id,
+                        kind = job.kind,
+                        queue = job.queue,
+                        attempts = job.attempts,
+                        error = %e,
+                        "Queued job failed on its last attempt; moved to the dead-letter queue"
+                    );
+                } else {
+                    warn!(job_id = job.id, kind = job.kind, queue = job.queue, attempt = job.attempts, error = %e, "Queued job failed; it will be retried");
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Runs `job` once a slot is free, or hands it back unstarted if the worker is cancelled first
+    async fn start(&self, job: QueuedJob, slots: &Semaphore) -> Result<(), JobError> {
+        let mut cancelled = self.cancelled.subscribe();
+        let permit = tokio::select! {
+            biased;
+            _ = cancelled.wait_for(|cancelled| *cancelled) => None,
+            permit = slots.acquire() => Some(permit?),
+        };
+        if permit.is_some() {
+            return self.settle(job).await;
+        }
+        match self.queue.release(&job).await {
+            Ok(()) => info!(job_id = job.id, kind = job.kind, queue = job.queue, "Worker stopping; queued job handed back unstarted"),
+            Err(QueueError::LeaseLost { .. }) => {}
+            Err(e) => return Err(e.into()),
+        }
+        Ok(())
+    }
+}
+
+impl Job for QueueWorker {
+    fn name(&self) -> &'static str {
+        "job-queue"
+    }
+
+    fn run(&self) -> BoxFuture<'_, Result<(), JobError>> {
+        Box::pin(async move {
+            if *self.cancelled.borrow() {
+                return Ok(());
+            }
+            let jobs = self.queue.claim(&self.queue_name, self.batch_size).await?;
+            let slots = Semaphore::new(self.concurrency);
+            let settled = join_all(jobs.into_iter().map(|job| self.start(job, &slots))).await;
+            settled.into_iter().collect()
+        })
+    }
+
+    fn cancel(&self) {
+        self.cancelled.send_replace(true);
+    }
+}
@@ -0,0 +1,287 @@
+//! API Gateway and load balancer events
+//!
+//! Lambda receives HTTP requests as JSON events in one of two shapes: the
+//! HTTP API payload (`"version": "2.0"`), and the REST API payload that
+//! Application Load Balancers also use. [`into_request`] turns either into an
+//! `http::Request`, and [`from_response`] answers in the shape the event came
+//! in.
+
+use std::collections::HashMap;
+
+use axum::{
+    body::{Body, to_bytes},
+    http::{HeaderMap, HeaderName, HeaderValue, Request, Response, header},
+};
+use base64::{Engine, engine::general_purpose::STANDARD};
+use serde::Deserialize;
+use serde_json::{Value, json};
+
+/// Event delivered for an HTTP request
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ProxyEvent {
+    /// `2.0` for HTTP API events
+    version: Option<String>,
+    /// Path of HTTP API events
+    raw_path: Option<String>,
+    /// Query string of HTTP API events, still percent-encoded
+    raw_query_string: Option<String>,
+    /// Cookies of HTTP API events, taken out of the headers
+    cookies: Option<Vec<String>>,
+    /// Method and path of HTTP API events, and whether the caller is a load balancer
+    request_context: Option<RequestContext>,
+    /// Method of REST API and load balancer events
+    http_method: Option<String>,
+    /// Path of REST API and load balancer events
+    path: Option<String>,
+    /// Query parameters of REST API and load balancer events
+    query_string_parameters: Option<HashMap<String, String>>,
+    /// Query parameters when multi-value parameters are enabled
+    multi_value_query_string_parameters: Option<HashMap<String, Vec<String>>>,
+    /// Headers, repeated ones joined with commas by HTTP APIs
+    headers: Option<HashMap<String, String>>,
+    /// Headers when multi-value headers are enabled
+    multi_value_headers: Option<HashMap<String, Vec<String>>>,
+    /// Request body
+    body: Option<String>,
+    /// Whether `body` is base64-encoded
+    is_base64_encoded: bool,
+}
+
+/// Parts of `requestContext` the conversion needs
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct RequestContext {
+    /// Method and path of HTTP API events
+    http: Option<HttpContext>,
+    /// Present when a load balancer sent the event
+    elb: Option<Value>,
+}
+
+/// `requestContext.http` of HTTP API events
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct HttpContext {
+    /// Request method
+    method: String,
+}
+
+/// Payload shape to answer in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PayloadFormat {
+    /// HTTP API (`version` 2.0): cookies apart from the headers
+    V2,
+    /// REST API or load balancer, with single-value headers
+    V1,
+    /// REST API or load balancer, with multi-value headers
+    V1MultiValue,
+}
+
+impl ProxyEvent {
+    /// Shape responses to this event must have
+    #[must_use]
+    pub fn format(&self) -> PayloadFormat {
+        if self.version.as_deref() == Some("2.0") {
+            PayloadFormat::V2
+        } else if self.multi_value_headers.is_some() {
+            PayloadFormat::V1MultiValue
+        } else {
+            PayloadFormat::V1
+        }
+    }
+}
+
+/// Converts an event into the request it carries
+///
+/// # Errors
+///
+/// Returns a message when the event has no method, or an invalid method, URI, header or body.
+pub fn into_request(event: ProxyEvent) -> Result<Request<Body>, String> {
+    let format = event.format();
+    let method = match format {
+        PayloadFormat::V2 => event.request_context.as_ref().and_then(|context| context.http.as_ref()).map(|http| http.method.clone()),
+        PayloadFormat::V1 | PayloadFormat::V1MultiValue => event.http_method.clone(),
+    }
+    .ok_or("Event has no HTTP method")?;
+    let uri = match format {
+        PayloadFormat::V2 => with_query(event.raw_path.as_deref().unwrap_or("/"), event.raw_query_string.as_deref().unwrap_or_default()),
+        PayloadFormat::V1 | PayloadFormat::V1MultiValue => with_query(event.path.as_deref().unwrap_or("/"), &v1_query(&event)),
+    };
+
+    let mut request = Request::builder().method(method.as_str()).uri(uri);
+    let headers = request.headers_mut().ok_or("Invalid method or URI")?;
+    for (name, values) in event.multi_value_headers.unwrap_or_default() {
+        for value in values {
+            append(headers, &name, &value)?;
+        }
+    }
+    for (name, value) in event.headers.unwrap_or_default() {
+        if !headers.contains_key(name.as_str()) {
+            append(headers, &name, &value)?;
+        }
+    }
+    if let Some(cookies) = event.cookies.filter(|cookies| !cookies.is_empty()) {
+        append(headers, header::COOKIE.as_str(), &cookies.join("; "))?;
+    }
+
+    let body = match event.body {
+        Some(body) if event.is_base64_encoded => STANDARD.decode(body).map_err(|e| format!("Invalid base64 body: {e}"))?,
+        Some(body) => body.into_bytes(),
+        None => Vec::new(),
+    };
+    request.body(Body::from(body)).map_err(|e| e.to_string())
+}
+
+/// Converts a response into the payload Lambda returns for `format`
+///
+/// Bodies that are valid UTF-8 are sent as text, others base64-encoded.
+///
+/// # Errors
+///
+/// Returns a message when the body cannot be read.
+pub async fn from_response(response: Response<Body>, format: PayloadFormat) -> Result<Value, String> {
+    let (parts, body) = response.into_parts();
+    let bytes = to_bytes(body, usize::MAX).await.map_err(|e| e.to_string())?;
+    let (body, is_base64_encoded) = match String::from_utf8(bytes.to_vec()) {
+        Ok(text) => (text, false),
+        Err(_) => (STANDARD.encode(&bytes), true),
+    };
+
+    let mut multi_value: HashMap<&str, Vec<&str>> = HashMap::new();
+    for (name, value) in &parts.headers {
+        if let Ok(value) = value.to_str() {
+            multi_value.entry(name.as_str()).or_default().push(value);
+        }
+    }
+    let mut payload = json!({
+        "statusCode": parts.status.as_u16(),
+        "body": body,
+        "isBase64Encoded": is_base64_encoded,
+    });
+    match format {
+        PayloadFormat::V2 => {
+            let cookies = multi_value.remove(header::SET_COOKIE.as_str()).unwrap_or_default();
+            let headers: HashMap<&str, String> = multi_value.into_iter().map(|(name, values)| (name, values.join(","))).collect();
+            payload["headers"] = json!(headers);
+            payload["cookies"] = json!(cookies);
+        },
+        PayloadFormat::V1 => {
+            // Single-value headers keep the last value; load balancers reject repeated names
+            let headers: HashMap<&str, &str> = multi_value.into_iter().filter_map(|(name, values)| Some((name, *values.last()?))).collect();
+            payload["headers"] = json!(headers);
+        },
+        PayloadFormat::V1MultiValue => payload["multiValueHeaders"] = json!(multi_value),
+    }
+    Ok(payload)
+}
+
+/// `path?query`, or `path` when the query is empty
+fn with_query(path: &str, query: &str) -> String {
+    if query.is_empty() { path.to_owned() } else { format!("{path}?{query}") }
+}
+
+/// Query string of a REST API or load balancer event
+///
+/// API Gateway decodes parameters and is re-encoded here; load balancers pass
+/// them as sent.
+fn v1_query(event: &ProxyEvent) -> String {
+    let pairs: Vec<(&str, &str)> = match (&event.multi_value_query_string_parameters, &event.query_string_parameters) {
+        (Some(parameters), _) => parameters
+            .iter()
+            .flat_map(|(name, values)| values.iter().map(move |value| (name.as_str(), value.as_str())))
+            .collect(),
+        (None, Some(parameters)) => parameters.iter().map(|(name, value)| (name.as_str(), value.as_str())).collect(),
+        (None, None) => Vec::new(),
+    };
+    let from_load_balancer = event.request_context.as_ref().is_some_and(|context| context.elb.is_some());
+    if from_load_balancer {
+        pairs.iter().map(|(name, value)| format!("{name}={value}")).collect::<Vec<_>>().join("&")
+    } else {
+        serde_urlencoded::to_string(&pairs).unwrap_or_default()
+    }
+}
+
+/// Appends a header, rejecting invalid names and values
+fn append(headers: &mut HeaderMap, name: &str, value: &str) -> Result<(), String> {
+    let name = HeaderName::try_from(name).map_err(|e| format!("Invalid header name `{name}`: {e}"))?;
+    let value = HeaderValue::try_from(value).map_err(|e| format!("Invalid value of header `{name}`: {e}"))?;
+    headers.append(name, value);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::StatusCode;
+
+    fn event(value: Value) -> ProxyEvent {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_http_api_event_round_trip() {
+        let event = event(json!({
+            "version": "2.0",
+            "rawPath": "/users",
+            "rawQueryString": "limit=2&name=Ann%20Lee",
+            "cookies": ["a=1", "b=2"],
+            "headers": {"content-type": "application/json", "x-forwarded-for": "203.0.113.7"},
+            "requestContext": {"http": {"method": "POST"}},
+            "body": "eyJuYW1lIjoiQW5uIn0=",
+            "isBase64Encoded": true
+        }));
+        assert_eq!(event.format(), PayloadFormat::V2);
+
+        let request = into_request(event).unwrap();
+        assert_eq!((request.method().as_str(), request.uri().to_string()), ("POST", "/users?limit=2&name=Ann%20Lee".to_owned()));
+        assert_eq!(request.headers()[header::COOKIE], "a=1; b=2");
+        assert_eq!(to_bytes(request.into_body(), usize::MAX).await.unwrap(), r#"{"name":"Ann"}"#);
+
+        let response = Response::builder()
+            .status(StatusCode::CREATED)
+            .header(header::SET_COOKIE, "session=x")
+            .header("vary", "accept")
+            .header("vary", "origin")
+            .body(Body::from("created"))
+            .unwrap();
+        let payload = from_response(response, PayloadFormat::V2).await.unwrap();
+        assert_eq!(payload["statusCode"], 201);
+        assert_eq!((payload["body"].as_str(), payload["isBase64Encoded"].as_bool()), (Some("created"), Some(false)));
+        assert_eq!(payload["headers"]["vary"], "accept,origin");
+        assert_eq!(payload["cookies"], json!(["session=x"]));
+    }
+
+    #[tokio::test]
+    async fn test_rest_and_load_balancer_events() {
+        let rest = into_request(event(json!({
+            "httpMethod": "GET",
+            "path": "/users",
+            "queryStringParameters": {"name": "Ann Lee"},
+            "headers": {"accept": "application/json"},
+            "body": null
+        })))
+        .unwrap();
+        assert_eq!(rest.uri().to_string(), "/users?name=Ann+Lee");
+
+        let balanced = event(json!({
+            "httpMethod": "GET",
+            "path": "/users",
+            "multiValueQueryStringParameters": {"name": ["Ann%20Lee"]},
+            "multiValueHeaders": {"accept": ["application/json"]},
+            "requestContext": {"elb": {"targetGroupArn": "arn:aws:elasticloadbalancing:eu-west-1:123:targetgroup/kickstart"}}
+        }));
+        assert_eq!(balanced.format(), PayloadFormat::V1MultiValue);
+        assert_eq!(into_request(balanced).unwrap().uri().to_string(), "/users?name=Ann%20Lee");
+
+        let binary = Response::new(Body::from(vec![0xff, 0x00]));
+        let payload = from_response(binary, PayloadFormat::V1MultiValue).await.unwrap();
+        assert_eq!((payload["body"].as_str(), payload["isBase64Encoded"].as_bool()), (Some("/wA="), Some(true)));
+        assert!(payload["multiValueHeaders"].is_object());
+    }
+
+    #[test]
+    fn test_events_without_method_are_rejected() {
+        assert!(into_request(event(json!({"version": "2.0", "rawPath": "/"}))).is_err());
+        assert!(into_request(event(json!({"path": "/"}))).is_err());
+    }
+}
@@ -0,0 +1,308 @@
+//! AWS Lambda adapter
+//!
+//! [`run`] serves the application as a Lambda function behind API Gateway (REST
+//! or HTTP APIs) or an Application Load Balancer, speaking the Lambda Runtime
+//! API directly: it polls for invocations, converts their events with
+//! [`event`] and posts the responses back. Cold starts stay short: the pool is
+//! created with [`crate::db::connect_lazy`], so the first query opens the first
+//! connection, and no background jobs or circuit monitor are started (they
+//! would be frozen between invocations). Each invocation runs in a
+//! `lambda_invocation` span carrying the Lambda request ID; its parent is the
+//! caller's X-Ray trace, so exported spans join the trace API Gateway started.
+//!
+//! Other function-as-a-service runtimes accepting a `tower::Service` can reuse the same pieces:
+//! a lazy pool and [`crate::create_app_with_pool`].
+
+pub mod event;
+
+use axum::{Router, http::HeaderMap};
+use serde_json::{Value, json};
+use tower::ServiceExt;
+use tracing::{Instrument, error};
+
+use crate::config::ConfigError;
+use crate::{AppConfig, AppProviders, ModuleRegistry, ServiceRegistry, create_router, db};
+
+use event::ProxyEvent;
+
+/// Version prefix of the Lambda Runtime API
+const RUNTIME_API_VERSION: &str = "2018-06-01";
+
+/// Errors that stop the function
+#[derive(Debug, thiserror::Error)]
+pub enum LambdaError {
+    /// Not running on Lambda: `AWS_LAMBDA_RUNTIME_API` is not set
+    #[error("AWS_LAMBDA_RUNTIME_API must be set")]
+    MissingRuntimeApi,
+    /// Tracing could not be initialized
+    #[error("Failed to initialize tracing: {0}")]
+    Tracing(String),
+    /// Required configuration is missing
+    #[error("Invalid configuration: {0}")]
+    Config(#[from] ConfigError),
+    /// `DATABASE_URL` is malformed
+    #[error("Invalid database configuration: {0}")]
+    Database(#[from] sqlx::Error),
+    /// The Runtime API could not be reached
+    #[error("Lambda Runtime API request failed: {0}")]
+    Runtime(#[from] reqwest::Error),
+}
+
+/// Serves the application on the Lambda runtime until it shuts the function down
+///
+/// Initialization failures are reported to the runtime before returning.
+///
+/// # Errors
+///
+/// Returns an error if not running on Lambda, tracing cannot be initialized,
+/// configuration is missing, `DATABASE_URL` is malformed, or the Runtime API
+/// is unreachable.
+pub async fn run() -> Result<(), LambdaError> {
+    let runtime = RuntimeApi::from_env()?;
+    let router = match init() {
+        Ok(router) => router,
+        Err(e) => {
+            runtime.report_init_error(&e).await;
+            return Err(e);
+        },
+    };
+    let result = runtime.serve(router).await;
+    crate::config::tracing::shutdown();
+    result
+}
+
+/// Serves `router` on the Lambda runtime, for applications with their own modules or services
+///
+/// # Errors
+///
+/// Returns an error if not running on Lambda or the Runtime API is unreachable.
+pub async fn serve(router: Router) -> Result<(), LambdaError> {
+    RuntimeApi::from_env()?.serve(router).await
+}
+
+/// Initializes tracing and builds the router on a lazy pool
+fn init() -> Result<Router, LambdaError> {
+    crate::config::tracing::init().map_err(|e| LambdaError::Tracing(e.to_string()))?;
+    let config = AppConfig::try_load()?;
+    let pool = db::connect_lazy(&config.database)?;
+    Ok(create_router(pool, AppProviders::default(), &ModuleRegistry::new(), &ServiceRegistry::new(), Some(&config)))
+}
+
+/// Client of the Lambda Runtime API
+struct RuntimeApi {
+    /// HTTP client; without a timeout, as waiting for the next invocation blocks until one arrives
+    client: reqwest::Client,
+    /// `http://{AWS_LAMBDA_RUNTIME_API}/2018-06-01/runtime`
+    base: String,
+}
+
+impl RuntimeApi {
+    /// Client of the Runtime API at `AWS_LAMBDA_RUNTIME_API`
+    fn from_env() -> Result<Self, LambdaError> {
+        let host = std::env::var("AWS_LAMBDA_RUNTIME_API").map_err(|_unset| LambdaError::MissingRuntimeApi)?;
+        Ok(Self {
+            client: reqwest::Client::new(),
+            base: format!("http://{host}/{RUNTIME_API_VERSION}/runtime"),
+        })
+    }
+
+    /// Handles invocations one at a time, as Lambda sends one per instance
+    async fn serve(&self, router: Router) -> Result<(), LambdaError> {
+        loop {
+            let next = self.client.get(format!("{}/invocation/next", self.base)).send().await?.error_for_status()?;
+            let headers = next.headers().clone();
+            let request_id = header(&headers, "lambda-runtime-aws-request-id").unwrap_or_default().to_owned();
+            let event = serde_json::from_slice::<ProxyEvent>(&next.bytes().await?);
+            let span = invocation_span(&request_id, header(&headers, "lambda-runtime-trace-id"));
+            let outcome = match event {
+                Ok(event) => handle(&router, event).instrument(span.clone()).await,
+                Err(e) => Err(format!("Unsupported event: {e}")),
+            };
+            let (path, payload) = match outcome {
+                Ok(payload) => ("response", payload),
+                Err(message) => {
+                    span.in_scope(|| error!(error = %message, "Lambda invocation failed"));
+                    ("error", error_payload("InvalidEvent", &message))
+                },
+            };
+            self.post(&format!("invocation/{request_id}/{path}"), &payload).await?.error_for_status()?;
+        }
+    }
+
+    /// Posts a JSON payload to `{base}/{path}`
+    async fn post(&self, path: &str, payload: &Value) -> reqwest::Result<reqwest::Response> {
+        self.client
+            .post(format!("{}/{path}", self.base))
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(payload.to_string())
+            .send()
+            .await
+    }
+
+    /// Tells the runtime the function could not start; failures to do so are only logged
+    async fn report_init_error(&self, failure: &LambdaError) {
+        let payload = error_payload("InitError", &failure.to_string());
+        if let Err(e) = self.post("init/error", &payload).await {
+            error!(error = %e, "Failed to report the initialization error to the Lambda runtime");
+        }
+    }
+}
+
+/// Routes the request of one invocation and returns the payload of its response
+async fn handle(router: &Router, event: ProxyEvent) -> Result<Value, String> {
+    let format = event.format();
+    let request = event::into_request(event)?;
+    let Ok(response) = router.clone().oneshot(request).await;
+    event::from_response(response, format).await
+}
+
+/// Error payload of the Runtime API
+fn error_payload(kind: &str, message: &str) -> Value {
+    json!({ "errorType": kind, "errorMessage": message })
+}
+
+/// Value of a header sent by the Runtime API
+fn header<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers.get(name)?.to_str().ok()
+}
+
+/// Span of an invocation, child of the caller's X-Ray trace when there is one
+fn invocation_span(request_id: &str, xray_header: Option<&str>) -> tracing::Span {
+    let trace = xray_header.and_then(XRayTrace::parse);
+    let span = tracing::info_span!(
+        "lambda_invocation",
+        request_id,
+        traceparent = trace.map(XRayTrace::traceparent).unwrap_or_default(),
+    );
+    #[cfg(feature = "otel")]
+    if let Some(trace) = trace {
+        trace.set_parent_of(&span);
+    }
+    span
+}
+
+/// Trace context of an X-Ray trace header (`Root=1-5759e988-bd862e3fe1be46a994272793;Parent=53995c3f42cd8ad8;Sampled=1`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct XRayTrace {
+    /// Trace ID: the root's epoch and unique parts, as in the W3C trace ID X-Ray accepts
+    pub trace_id: u128,
+    /// ID of the caller's segment
+    pub parent_id: u64,
+    /// Whether the caller sampled the trace
+    pub sampled: bool,
+}
+
+impl XRayTrace {
+    /// Parses an X-Ray trace header; `None` without a valid `Root` and `Parent`
+    #[must_use]
+    pub fn parse(header: &str) -> Option<Self> {
+        let (mut trace_id, mut parent_id, mut sampled) = (None, None, false);
+        for part in header.split(';') {
+            match part.trim().split_once('=') {
+                Some(("Root", root)) => trace_id = parse_root(root),
+                Some(("Parent", parent)) if parent.len() == 16 => parent_id = u64::from_str_radix(parent, 16).ok(),
+                Some(("Sampled", flag)) => sampled = flag == "1",
+                _ => {},
+            }
+        }
+        Some(Self {
+            trace_id: trace_id?,
+            parent_id: parent_id?,
+            sampled,
+        })
+    }
+
+    /// The same context as a W3C `traceparent` header value
+    #[must_use]
+    pub fn traceparent(self) -> String {
+        format!("00-{:032x}-{:016x}-{:02x}", self.trace_id, self.parent_id, u8::from(self.sampled))
+    }
+
+    /// Makes `span` a child of the caller's segment in exported traces
+    #[cfg(feature = "otel")]
+    fn set_parent_of(self, span: &tracing::Span) {
+        use opentelemetry::trace::{SpanContext, SpanId, TraceContextExt, TraceFlags, TraceId, TraceState};
+        use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+        let flags = if self.sampled { TraceFlags::SAMPLED } else { TraceFlags::default() };
+        let parent = SpanContext::new(
+            TraceId::from_bytes(self.trace_id.to_be_bytes()),
+            SpanId::from_bytes(self.parent_id.to_be_bytes()),
+            flags,
+            true,
+            TraceState::default(),
+        );
+        span.set_parent(opentelemetry::Context::new().with_remote_span_context(parent));
+    }
+}
+
+/// Trace ID of a `1-{8 hex digit epoch}-{24 hex digit id}` root
+fn parse_root(root: &str) -> Option<u128> {
+    let mut parts = root.split('-');
+    let (Some("1"), Some(epoch), Some(id), None) = (parts.next(), parts.next(), parts.next(), parts.next()) else {
+        return None;
+    };
+    if epoch.len() != 8 || id.len() != 24 {
+        return None;
+    }
+    u128::from_str_radix(&format!("{epoch}{id}"), 16).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_xray_header_is_parsed() {
+        let trace = XRayTrace::parse("Root=1-5759e988-bd862e3fe1be46a994272793;Parent=53995c3f42cd8ad8;Sampled=1").unwrap();
+
+        assert_eq!(trace.traceparent(), "00-5759e988bd862e3fe1be46a994272793-53995c3f42cd8ad8-01");
+        assert!(!XRayTrace::parse("Root=1-5759e988-bd862e3fe1be46a994272793;Parent=53995c3f42cd8ad8").unwrap().sampled);
+    }
+
+    #[test]
+    fn test_incomplete_xray_headers_are_rejected() {
+        assert_eq!(XRayTrace::parse("Root=1-5759e988-bd862e3fe1be46a994272793;Sampled=1"), None);
+        assert_eq!(XRayTrace::parse("Root=2-5759e988-bd862e3fe1be46a994272793;Parent=53995c3f42cd8ad8"), None);
+        assert_eq!(XRayTrace::parse("Root=1-5759e988-bd86;Parent=53995c3f42cd8ad8"), None);
+        assert_eq!(XRayTrace::parse("Self=1-5759e988-bd862e3fe1be46a994272793"), None);
+    }
+
+    #[tokio::test]
+    async fn test_invocations_are_routed_and_answered() {
+        use axum::{Json, extract::Path, http::header::HeaderName, routing::{get, post}};
+        use tokio::sync::mpsc;
+
+        let (responses, mut received) = mpsc::unbounded_channel();
+        let runtime_api = Router::new()
+            .route(
+                "/2018-06-01/runtime/invocation/next",
+                get(|| async {
+                    let headers = [
+                        (HeaderName::from_static("lambda-runtime-aws-request-id"), "req-1"),
+                        (HeaderName::from_static("lambda-runtime-trace-id"), "Root=1-5759e988-bd862e3fe1be46a994272793;Parent=53995c3f42cd8ad8;Sampled=1"),
+                    ];
+                    let event = json!({"version": "2.0", "rawPath": "/hello", "requestContext": {"http": {"method": "GET"}}});
+                    (headers, Json(event))
+                }),
+            )
+            .route(
+                "/2018-06-01/runtime/invocation/{id}/{outcome}",
+                post(move |Path((id, outcome)): Path<(String, String)>, Json(payload): Json<Value>| async move {
+                    responses.send((id, outcome, payload)).ok();
+                }),
+            );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}/{RUNTIME_API_VERSION}/runtime", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, runtime_api).await });
+        let runtime = RuntimeApi { client: reqwest::Client::new(), base };
+        let app = Router::new().route("/hello", get(|| async { "hello" }));
+        let serving = tokio::spawn(async move { runtime.serve(app).await });
+
+        let (id, outcome, payload) = received.recv().await.unwrap();
+        serving.abort();
+
+        assert_eq!((id.as_str(), outcome.as_str()), ("req-1", "response"));
+        assert_eq!((payload["statusCode"].as_u64(), payload["body"].as_str()), (Some(200), Some("hello")));
+    }
+}
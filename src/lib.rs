@@ -7,64 +7,356 @@
 #![allow(clippy::missing_errors_doc)]
 #![allow(clippy::missing_panics_doc)]
 
+use std::{sync::Arc, time::Duration};
+
 use axum::{
-    response::Html, routing::{get, post},
+    http::{Method, header}, middleware, response::Html, routing::{delete, get, post, put},
     Json,
     Router,
 };
-use sqlx::postgres::PgPoolOptions;
 use sqlx::PgPool;
-use tracing::info;
 use utoipa::OpenApi;
 
+use auth::{AccessControl, StaticTokenAuthenticator};
+use cache_control::{CachePolicies, CachePolicy};
+use discovery::Capabilities;
+use client_ip::{IpNet, TrustedProxies};
+use ip_filter::IpFilter;
+use partner::{KeyEncryptionKey, SignatureVerifier, SignedRoutes};
+use pagination::PageLimits;
+use config::Environment;
+use db::{ColumnRename, RetryPolicy};
+use user::validation::{DenylistPolicy, SharedNameScreeningPolicy, ValidationContext, ValidationPolicy};
+
 // Module declarations
+pub mod address;
+pub mod admin;
+pub mod app;
+pub mod audit;
+pub mod auth;
 pub mod bank;
+pub mod build_info;
+pub mod cache_control;
+pub mod change_log;
+pub mod circuit;
+pub mod client_ip;
+pub mod clock;
+pub mod conditional;
 pub mod config;
+pub mod db;
+pub mod deprecation;
+pub mod discovery;
+pub mod error_codes;
+pub mod events;
 pub mod health;
+pub mod identity;
+pub mod ids;
+pub mod ip_filter;
+pub mod jobs;
+#[cfg(feature = "lambda")]
+pub mod lambda;
+pub mod links;
+pub mod metrics;
+pub mod module;
+#[cfg(feature = "mtls")]
+pub mod mtls;
+pub mod negotiation;
+pub mod notify;
 pub mod pagination;
+pub mod partner;
+pub mod preference;
+pub mod privacy;
+#[cfg(feature = "profiling")]
+pub mod profiling;
+pub mod read_only;
+pub mod registry;
+pub mod retention;
+pub mod schemas;
+pub mod stats;
+pub mod tag;
+pub mod timeout;
+pub mod tx;
+#[cfg(feature = "test-util")]
+pub mod testing;
 pub mod user;
+pub mod webhook;
 
 // Re-export commonly used types
-pub use bank::{BankError, BankService};
-pub use config::AppConfig;
-pub use health::HealthService;
-pub use user::{CreateUser, UpdateUser, User, UserService};
+pub use address::AddressService;
+pub use admin::{AdminService, QueryService, SelfTestService};
+pub use app::{App, AppBuilder, AppError, AppStateBuilder};
+pub use auth::{AccessPolicy, Principal, RoutePolicies};
+pub use bank::{AccountNumberScheme, AccountService, BankError, BankService, TransferLimits};
+pub use change_log::ChangeLogService;
+pub use circuit::DatabaseCircuit;
+pub use clock::{Clock, SharedClock, SystemClock};
+pub use config::{
+    AdminConfig, AppConfig, AuthConfig, BankConfig, ConfigDump, HealthConfig, JobsConfig, MigrationConfig, NotifyConfig, PaginationConfig, PartnerConfig, PreferenceConfig, PrivacyConfig, RetentionConfig, RouteEntry,
+    ScreeningConfig, ServerConfig, ValidationConfig, WebhookConfig,
+};
+pub use deprecation::{Deprecation, Deprecations};
+pub use audit::ActivityService;
+pub use events::{DomainEvent, EventBus};
+pub use health::{DependencyPing, HealthService};
+pub use jobs::{JobQueue, JobTracker};
+pub use notify::NotificationHub;
+pub use ids::{DefaultIdGenerator, IdGenerator, SharedIdGenerator};
+pub use links::{Link, LinkBuilder};
+pub use privacy::{ExportSigner, PrivacyService};
+pub use module::{Module, ModuleCatalog, ModuleInfo, ModuleRegistry};
+pub use registry::{Inject, ServiceRegistry};
+pub use retention::{RetentionPolicy, RetentionService};
+pub use stats::RequestStats;
+pub use tag::TagService;
+pub use timeout::RouteTimeouts;
+pub use identity::IdentityService;
+pub use partner::PartnerService;
+pub use preference::PreferenceService;
+pub use tx::Tx;
+pub use webhook::WebhookService;
+pub use user::{
+    CreateUser, SharedUserReadPort, SharedUserWritePort, UpdateUser, User, UserReadPort, UserService, UserWritePort,
+};
 
-/// Application state containing all services
+/// Application state shared by all handlers
+///
+/// Services are looked up by type in [`ServiceRegistry`]; handlers extract them
+/// with [`Inject`]. The registry always holds the built-in services:
+/// [`UserService`] (also as [`SharedUserReadPort`] and [`SharedUserWritePort`]), [`HealthService`], [`AddressService`], [`TagService`], [`IdentityService`], [`PreferenceService`], [`AccountService`], [`AdminService`], [`JobQueue`], [`ActivityService`], [`PrivacyService`], [`PartnerService`], [`WebhookService`], [`ChangeLogService`],
+/// [`EventBus`], [`NotificationHub`], [`LinkBuilder`], [`SharedClock`], [`SharedIdGenerator`], the [`ConfigDump`] (as `Arc<ConfigDump>`), the [`ModuleCatalog`] (as `Arc<ModuleCatalog>`)
+/// and, unless `DOCS_ACCESS` disables the docs, the `OpenAPI` document (as `Arc<OpenApi>`).
 #[derive(Clone)]
 pub struct AppState {
-    /// User service for user management operations
-    pub user_service: UserService,
-    /// Health service for health check operations
-    pub health_service: HealthService,
+    /// Services available to handlers
+    pub services: ServiceRegistry,
+    /// Whether responses are wrapped in an envelope unless `?envelope=false` is passed
+    pub envelope_by_default: bool,
+}
+
+/// Time and identifier sources, database circuit, event bus and notification hub the services are built with
+///
+/// Tests swap these for deterministic implementations.
+#[derive(Debug, Clone)]
+pub struct AppProviders {
+    /// Source of the current time
+    pub clock: SharedClock,
+    /// Source of identifiers
+    pub ids: SharedIdGenerator,
+    /// Database circuit breaker; stays closed unless a monitor updates it
+    pub circuit: DatabaseCircuit,
+    /// Counts the background job runs in progress, reported by `/admin/overview`
+    pub jobs: JobTracker,
+    /// Bus the services publish domain events on
+    pub events: EventBus,
+    /// Receives the Postgres notifications of the channels in `NOTIFY_CHANNELS`
+    pub notifications: NotificationHub,
+}
+
+impl Default for AppProviders {
+    fn default() -> Self {
+        Self {
+            clock: SystemClock::shared(),
+            ids: DefaultIdGenerator::shared(),
+            circuit: DatabaseCircuit::default(),
+            jobs: JobTracker::default(),
+            events: EventBus::new(),
+            notifications: NotificationHub::new(),
+        }
+    }
 }
 
 #[derive(OpenApi)]
 #[openapi(
     paths(
         user::create_user_handler,
+        user::upsert_user_handler,
         user::get_all_users_handler,
+        user::stream_users_handler,
+        user::user_changes_handler,
+        user::bulk_delete_users_handler,
+        user::bulk_update_users_handler,
+        user::suspend_user_handler,
+        user::activate_user_handler,
+        user::archive_user_handler,
+        user::user_history_handler,
+        user::revert_user_handler,
         user::get_user_by_id_handler,
         user::update_user_handler,
         user::delete_user_handler,
+        address::list_addresses_handler,
+        address::create_address_handler,
+        address::get_address_handler,
+        address::update_address_handler,
+        address::delete_address_handler,
+        tag::list_user_tags_handler,
+        tag::attach_tag_handler,
+        tag::detach_tag_handler,
+        tag::autocomplete_tags_handler,
+        identity::list_identities_handler,
+        identity::link_identity_handler,
+        identity::unlink_identity_handler,
+        identity::find_user_by_external_id_handler,
+        preference::get_preferences_handler,
+        preference::update_preferences_handler,
+        audit::user_activity_handler,
+        privacy::request_export_handler,
+        privacy::get_export_handler,
+        privacy::download_export_handler,
+        privacy::erase_user_handler,
+        bank::open_account_handler,
+        bank::get_account_handler,
+        bank::withdraw_handler,
+        bank::transfer_handler,
+        bank::freeze_account_handler,
+        bank::unfreeze_account_handler,
+        bank::verify_ledger_handler,
+        admin::admin_overview_handler,
+        admin::admin_runtime_handler,
+        admin::admin_config_handler,
+        admin::admin_modules_handler,
+        admin::admin_query_handler,
+        admin::admin_selftest_handler,
+        jobs::list_jobs_handler,
+        jobs::retry_job_handler,
+        partner::rotate_partner_key_handler,
+        partner::grant_partner_account_handler,
+        partner::revoke_partner_account_handler,
+        webhook::create_webhook_handler,
+        webhook::get_webhook_handler,
+        webhook::update_webhook_handler,
+        webhook::rotate_webhook_secret_handler,
+        webhook::list_webhook_deliveries_handler,
+        change_log::list_changes_handler,
+        bank::validate_account_number_handler,
+        bank::external_transfer_handler,
+        bank::transfer_limits_handler,
+        bank::list_beneficiaries_handler,
+        bank::create_beneficiary_handler,
+        bank::delete_beneficiary_handler,
+        bank::list_users_with_accounts_handler,
         health::health_check_handler,
         health::readiness_check_handler,
-        health::liveness_check_handler
+        health::liveness_check_handler,
+        build_info::version_handler,
+        metrics::metrics_handler
     ),
     components(schemas(
         user::CreateUser,
         user::UpdateUser,
+        user::domain::UpsertUser,
         user::User,
+        user::domain::UserStatus,
         user::domain::ApiResponse,
         user::domain::ValidationError,
         user::domain::ValidationErrorResponse,
+        user::domain::ValidationWarning,
+        error_codes::ErrorCode,
+        error_codes::ErrorResponse,
         user::domain::PaginationParams,
         user::domain::PaginatedUsersResponse,
+        pagination::SortOrder,
+        user::domain::UserLinks,
+        user::domain::UsersPageLinks,
+        links::Link,
+        user::domain::BulkIdsRequest,
+        user::domain::BulkUpdateUsers,
+        user::domain::BulkItemStatus,
+        user::domain::BulkItemResult,
+        user::domain::BulkOperationResponse,
+        user::domain::HistoryOperation,
+        user::domain::FieldChange,
+        user::domain::UserVersion,
+        user::domain::UserHistory,
+        user::domain::ChangeOperation,
+        user::domain::UserChange,
+        user::domain::UserChangesPage,
+        address::Address,
+        address::CreateAddress,
+        address::UpdateAddress,
+        tag::Tag,
+        tag::TagSuggestion,
+        identity::ExternalIdentity,
+        identity::LinkIdentity,
+        preference::UserPreferences,
+        preference::PreferencesPatch,
+        audit::ActivityKind,
+        audit::ActivityEntry,
+        audit::ActivityPage,
+        privacy::ExportStatus,
+        privacy::DataExport,
+        privacy::ErasureAction,
+        privacy::ErasureChange,
+        privacy::ErasureReport,
+        partner::IssuedPartnerKey,
+        partner::PartnerAccountGrant,
+        webhook::Webhook,
+        webhook::CreateWebhook,
+        webhook::UpdateWebhook,
+        webhook::CreatedWebhook,
+        webhook::IssuedWebhookSecret,
+        webhook::WebhookDelivery,
+        change_log::ChangeLogEntry,
+        change_log::ChangeLogPage,
+        bank::Account,
+        bank::UserWithAccounts,
+        bank::UsersWithAccountsPage,
+        bank::AccountKind,
+        bank::OpenAccount,
+        bank::Withdrawal,
+        bank::TransferRequest,
+        bank::Transfer,
+        bank::FreezeAccount,
+        bank::BalanceDiscrepancy,
+        bank::LedgerVerification,
+        bank::AccountNumberFormat,
+        bank::ValidateAccountNumber,
+        bank::AccountNumberCheck,
+        bank::Beneficiary,
+        bank::CreateBeneficiary,
+        bank::ExternalTransferRequest,
+        bank::ExternalTransfer,
+        bank::LimitWindow,
+        bank::LimitUsage,
+        bank::TransferLimitUsage,
+        bank::LimitExceededResponse,
+        admin::AdminOverview,
+        admin::RuntimeDiagnostics,
+        admin::RuntimeStats,
+        admin::WorkerStats,
+        admin::ProcessStats,
+        admin::AllocatorStats,
+        admin::HeapStats,
+        admin::QueryRequest,
+        admin::QueryResult,
+        admin::SelfTestReport,
+        admin::SelfTestStep,
+        admin::SelfTestStepName,
+        jobs::JobStatus,
+        jobs::QueuedJob,
+        config::ConfigDump,
+        config::RouteEntry,
+        module::ModuleCatalog,
+        module::ModuleInfo,
+        stats::RequestCounts,
+        db::RetryCounts,
         health::ComponentHealth,
-        health::HealthCheckResponse
+        health::HealthCheckResponse,
+        build_info::BuildInfo
     )),
     tags(
         (name = "users", description = "User management operations"),
+        (name = "addresses", description = "User address operations"),
+        (name = "tags", description = "User tags and tag autocompletion"),
+        (name = "identities", description = "User IDs in external systems and lookups by them"),
+        (name = "preferences", description = "Per-user preferences with configured defaults"),
+        (name = "activity", description = "Per-user timeline of audit events and transactions"),
+        (name = "privacy", description = "GDPR data subject requests"),
+        (name = "accounts", description = "Bank accounts, transfers and compliance holds"),
+        (name = "beneficiaries", description = "Saved targets of transfers to other banks"),
+        (name = "admin", description = "Administrative and correctness tools"),
+        (name = "partners", description = "Signing keys of partner integrations and the accounts they may debit"),
+        (name = "webhooks", description = "Outgoing webhook subscriptions, secrets and delivery attempts"),
+        (name = "changes", description = "Persisted domain events in commit order, for rebuilding state and incremental syncs"),
         (name = "health", description = "Health check and monitoring endpoints")
     ),
     info(
@@ -79,62 +371,761 @@ struct ApiDoc;
 /// Creates the main application router with database connection
 ///
 /// # Panics
-/// Panics if configuration cannot be loaded or database connection fails
+/// Panics if configuration cannot be loaded or database connection fails; see [`try_create_app`]
 pub async fn create_app() -> Router {
-    let config = AppConfig::load();
+    try_create_app().await.expect("Failed to build application")
+}
 
-    info!("Connecting to database...");
+/// Creates the main application router with database connection, for hosts that handle startup failures
+///
+/// # Errors
+/// Returns an error if configuration cannot be loaded, the database is unreachable or a startup hook fails
+pub async fn try_create_app() -> Result<Router, AppError> {
+    Ok(AppBuilder::new(AppConfig::try_load()?).build().await?.into_router())
+}
+
+/// Creates the application router with a provided database pool
+pub fn create_app_with_pool(pool: PgPool) -> Router {
+    create_app_with_providers(pool, AppProviders::default())
+}
 
-    let pool = PgPoolOptions::new()
-        .max_connections(config.database.max_connections)
-        .connect(&config.database.url)
-        .await
-        .expect("Failed to create pool");
+/// Creates the application router with a provided database pool, clock and ID generator
+pub fn create_app_with_providers(pool: PgPool, providers: AppProviders) -> Router {
+    create_app_with_modules(pool, providers, &ModuleRegistry::new())
+}
 
-    info!(
-        "Database connection established with {} max connections",
-        config.database.max_connections
-    );
-    create_app_with_pool(pool)
+/// Creates the application router with the built-in routes and the routes of `modules`
+pub fn create_app_with_modules(pool: PgPool, providers: AppProviders, modules: &ModuleRegistry) -> Router {
+    create_router(pool, providers, modules, &ServiceRegistry::new(), None)
 }
 
-/// Creates the application router with a provided database pool
+/// Creates the application router running with `config` instead of the environment's settings
+///
+/// The database section is not used: requests run on `pool`. No background
+/// jobs are started; use [`AppBuilder`] for those.
+pub fn create_app_with_config(pool: PgPool, config: &AppConfig) -> Router {
+    create_router(pool, AppProviders::default(), &ModuleRegistry::new(), &ServiceRegistry::new(), Some(config))
+}
+
+/// Creates the application router; `overrides` replace built-in services and `config` is what `GET /admin/config` reports
+pub(crate) fn create_router(
+    pool: PgPool,
+    providers: AppProviders,
+    modules: &ModuleRegistry,
+    overrides: &ServiceRegistry,
+    config: Option<&AppConfig>,
+) -> Router {
+    let Assembly {
+        state,
+        server_config,
+        docs_access,
+        capabilities,
+        signature_verifier,
+        request_stats,
+        circuit,
+    } = assemble(pool.clone(), providers, modules, overrides, config);
+
+    api_router(state.clone())
+        .merge(modules.routes())
+        .route_layer(middleware::from_fn_with_state(
+            deprecated_routes(),
+            deprecation::deprecation_headers,
+        ))
+        .route_layer(middleware::from_fn_with_state(
+            access_control(&section(config, |config| &config.auth, AuthConfig::load), docs_access.as_ref()),
+            auth::enforce_access,
+        ))
+        .route_layer(middleware::from_fn_with_state(
+            admin_ip_filter(&section(config, |config| &config.admin, AdminConfig::load)),
+            ip_filter::filter_ips,
+        ))
+        .route_layer(middleware::from_fn_with_state(signature_verifier, partner::verify_signatures))
+        .route_layer(middleware::from_fn_with_state(route_timeouts(&server_config), timeout::enforce_timeouts))
+        .route_layer(middleware::from_fn_with_state(cache_policies(&server_config), cache_control::cache_headers))
+        .layer(middleware::from_fn_with_state(capabilities, discovery::answer_options))
+        .layer(middleware::from_fn_with_state(server_config.read_only, read_only::reject_writes))
+        .layer(middleware::from_fn_with_state(circuit, circuit::fail_fast))
+        .layer(middleware::from_fn_with_state(pool, tx::transactions))
+        .layer(middleware::from_fn_with_state(request_stats, stats::count_responses))
+        .layer(config::tracing::create_http_trace_layer())
+        .layer(middleware::from_fn_with_state(trusted_proxies(&server_config), client_ip::resolve_client_ip))
+        .with_state(state)
+}
+
+/// Built-in API routes, without middleware or state, for nesting into another Axum application
+///
+/// Serves the documentation routes when `state` holds the `OpenAPI` document
+/// (as `Arc<OpenApi>`, present unless `DOCS_ACCESS` disables the docs); routes
+/// of modules are added with [`ModuleRegistry::routes`]. None of the
+/// kickstart's middleware is applied: the host supplies authentication and
+/// access control, and must add [`tx::transactions`] for handlers extracting
+/// [`Tx`].
+///
+/// ```no_run
+/// # async fn host(pool: sqlx::PgPool) {
+/// use rust_kickstart::{AppProviders, ModuleRegistry, api_router, create_app_state};
+///
+/// let state = create_app_state(pool, AppProviders::default(), &ModuleRegistry::new());
+/// let app: axum::Router = axum::Router::new()
+///     .nest("/kickstart", api_router(state.clone()).with_state(state));
+/// # }
+/// ```
 #[allow(clippy::needless_pass_by_value)]
-pub fn create_app_with_pool(pool: PgPool) -> Router {
+pub fn api_router(state: AppState) -> Router<AppState> {
+    let docs = state.services.get::<Arc<utoipa::openapi::OpenApi>>().cloned();
+    Router::new()
+        .route("/", get(root_handler))
+        .merge(user_routes())
+        .merge(account_routes())
+        .merge(admin_routes())
+        .merge(privacy_routes())
+        .merge(webhook_routes())
+        .route("/changes", get(change_log::list_changes_handler))
+        .merge(probe_routes())
+        .merge(docs.map(docs_routes).unwrap_or_default())
+}
+
+/// Creates the state the API routes run with: the built-in services and those of `modules`
+///
+/// Use [`AppState::builder`] to replace individual services.
+#[must_use]
+pub fn create_app_state(pool: PgPool, providers: AppProviders, modules: &ModuleRegistry) -> AppState {
+    assemble(pool, providers, modules, &ServiceRegistry::new(), None).state
+}
+
+/// Application state and the parts of the middleware stack built with it
+struct Assembly {
+    /// State handed to the handlers
+    state: AppState,
+    /// Server settings the middleware is configured from
+    server_config: ServerConfig,
+    /// Who may read the documentation; `None` when it is not served
+    docs_access: Option<AccessPolicy>,
+    /// Methods of each route, for `OPTIONS` requests
+    capabilities: Capabilities,
+    /// Signature check of partner routes
+    signature_verifier: SignatureVerifier,
+    /// Response counts reported by `/admin/overview`
+    request_stats: RequestStats,
+    /// Database circuit breaker
+    circuit: DatabaseCircuit,
+}
+
+/// Builds the services and the state holding them
+#[allow(clippy::needless_pass_by_value)]
+fn assemble(
+    pool: PgPool,
+    providers: AppProviders,
+    modules: &ModuleRegistry,
+    overrides: &ServiceRegistry,
+    config: Option<&AppConfig>,
+) -> Assembly {
+    let AppProviders { clock, ids, circuit, jobs, events: event_bus, notifications } = providers;
+
+    let server_config = section(config, |config| &config.server, ServerConfig::load);
+
     // Create services
-    let user_service = UserService::new(pool.clone());
-    let health_service = HealthService::new(pool.clone());
-    let _bank_service = BankService::new(user_service.clone()); // Available for future use
+    let pagination_config = section(config, |config| &config.pagination, PaginationConfig::load);
+    let mut user_service = UserService::with_event_bus(pool.clone(), event_bus.clone())
+        .with_clock(Arc::clone(&clock))
+        .with_id_generator(Arc::clone(&ids))
+        .with_page_limits(page_limits(&pagination_config))
+        .with_validation(validation_context(&section(config, |config| &config.validation, ValidationConfig::load)))
+        .with_name_screening(name_screening(&section(config, |config| &config.screening, ScreeningConfig::load)));
+    if let Some(rename) = user_rename(&section(config, |config| &config.migration, MigrationConfig::load)) {
+        user_service = user_service.with_dual_write(rename);
+    }
+    let health_service = HealthService::new(pool.clone())
+        .with_clock(Arc::clone(&clock))
+        .with_checks(modules.health_checks())
+        .with_checks(dependency_pings(&section(config, |config| &config.health, HealthConfig::load)))
+        .with_checks(notification_check(&notifications, &section(config, |config| &config.notify, NotifyConfig::load)))
+        .with_read_only(server_config.read_only)
+        .with_circuit(circuit.clone());
+    let address_service = AddressService::new(pool.clone(), user_service.clone())
+        .with_clock(Arc::clone(&clock))
+        .with_id_generator(Arc::clone(&ids));
+    let tag_service = TagService::new(pool.clone(), user_service.clone()).with_clock(Arc::clone(&clock));
+    let identity_service = IdentityService::new(pool.clone(), user_service.clone()).with_clock(Arc::clone(&clock));
+    let preference_service = PreferenceService::new(pool.clone(), user_service.clone())
+        .with_clock(Arc::clone(&clock))
+        .with_defaults(&section(config, |config| &config.preferences, PreferenceConfig::load).defaults);
+    let account_service = account_service(&pool, user_service.clone(), &clock, &section(config, |config| &config.bank, BankConfig::load));
+    let activity_service = ActivityService::new(pool.clone(), user_service.clone());
+    let change_log_service = ChangeLogService::new(pool.clone()).with_page_limits(page_limits(&pagination_config));
+    let privacy_service = privacy_service(&pool, user_service.clone(), &clock, &section(config, |config| &config.privacy, PrivacyConfig::load));
+    let request_stats = RequestStats::default();
+    let admin_service = AdminService::new(user_service.clone(), account_service.clone(), request_stats.clone(), jobs)
+        .with_clock(Arc::clone(&clock));
+    let query_service = query_service(&pool, &section(config, |config| &config.admin, AdminConfig::load));
+    let jobs_config = section(config, |config| &config.jobs, JobsConfig::load);
+    let job_queue = job_queue(&pool, &jobs_config);
+    let webhook_service = webhook_service(&pool, &clock, &ids, &jobs_config, &section(config, |config| &config.webhook, WebhookConfig::load));
+    let partner_config = section(config, |config| &config.partner, PartnerConfig::load);
+    let partner_service = partner_service(&pool, &clock, &partner_config);
+    let signature_verifier = signature_verifier(&partner_service, &partner_config);
+    let docs_access = docs_access(&server_config, section(config, |config| &config.environment, Environment::load));
 
-    let app_state = AppState {
-        user_service,
-        health_service,
-    };
+    let mut services = ServiceRegistry::new()
+        .with(Arc::new(user_service.clone()) as SharedUserReadPort)
+        .with(Arc::new(user_service.clone()) as SharedUserWritePort)
+        .with(user_service)
+        .with(health_service)
+        .with(address_service)
+        .with(tag_service)
+        .with(identity_service)
+        .with(preference_service)
+        .with(account_service)
+        .with(admin_service)
+        .with(query_service)
+        .with(job_queue)
+        .with(SelfTestService::new(pool.clone()).with_clock(Arc::clone(&clock)))
+        .with(activity_service)
+        .with(privacy_service)
+        .with(partner_service.clone())
+        .with(webhook_service)
+        .with(change_log_service)
+        .with(event_bus)
+        .with(notifications)
+        .with(LinkBuilder::new(server_config.public_base_url.as_deref()))
+        .with(clock)
+        .with(ids);
+    modules.register_services(&mut services);
+    services.extend(overrides);
+
+    let openapi = Arc::new(openapi_spec_with_modules(modules));
+    let capabilities = Capabilities::from_openapi(&openapi).negotiated(negotiated_routes());
+    let config_dump = ConfigDump::new(config, route_table(&openapi));
+    if config.is_some() {
+        config_dump.log();
+    }
+    services.insert(Arc::new(config_dump));
+    services.insert(Arc::new(modules.describe(route_table)));
+    if docs_access.is_some() {
+        services.insert(Arc::clone(&openapi));
+    }
 
+    Assembly {
+        state: AppState {
+            services,
+            envelope_by_default: server_config.response_envelope,
+        },
+        server_config,
+        docs_access,
+        capabilities,
+        signature_verifier,
+        request_stats,
+        circuit,
+    }
+}
+
+/// Health probes, build information and metrics
+fn probe_routes() -> Router<AppState> {
+    Router::new()
+        .route("/health", get(health::health_check_handler))
+        .route("/ready", get(health::readiness_check_handler))
+        .route("/live", get(health::liveness_check_handler))
+        .route("/version", get(build_info::version_handler))
+        .route("/metrics", get(metrics::metrics_handler))
+}
+
+/// User routes, including the address, tag, identity, preference and activity sub-resources
+fn user_routes() -> Router<AppState> {
     Router::new()
-        .route("/", get(root_handler))
         .route(
             "/users",
-            post(user::create_user_handler).get(user::get_all_users_handler),
+            post(user::create_user_handler)
+                .put(user::upsert_user_handler)
+                .get(user::get_all_users_handler)
+                .delete(user::bulk_delete_users_handler)
+                .patch(user::bulk_update_users_handler),
         )
+        .route("/users/stream", get(user::stream_users_handler))
+        .route("/users/changes", get(user::user_changes_handler))
         .route(
             "/users/{id}",
             get(user::get_user_by_id_handler)
                 .put(user::update_user_handler)
                 .delete(user::delete_user_handler),
         )
-        .route("/health", get(health::health_check_handler))
-        .route("/ready", get(health::readiness_check_handler))
-        .route("/live", get(health::liveness_check_handler))
-        .route("/api-docs/openapi.json", get(serve_openapi))
+        .route("/users/{id}/suspend", post(user::suspend_user_handler))
+        .route("/users/{id}/activate", post(user::activate_user_handler))
+        .route("/users/{id}/archive", post(user::archive_user_handler))
+        .route("/users/{id}/history", get(user::user_history_handler))
+        .route("/users/{id}/revert", post(user::revert_user_handler))
+        .route(
+            "/users/{id}/addresses",
+            get(address::list_addresses_handler).post(address::create_address_handler),
+        )
+        .route(
+            "/users/{id}/addresses/{address_id}",
+            get(address::get_address_handler)
+                .put(address::update_address_handler)
+                .delete(address::delete_address_handler),
+        )
+        .route("/users/{id}/tags", get(tag::list_user_tags_handler))
+        .route(
+            "/users/{id}/tags/{tag}",
+            put(tag::attach_tag_handler).delete(tag::detach_tag_handler),
+        )
+        .route("/tags/autocomplete", get(tag::autocomplete_tags_handler))
+        .route("/users/{id}/identities", get(identity::list_identities_handler))
+        .route(
+            "/users/{id}/identities/{system}",
+            put(identity::link_identity_handler).delete(identity::unlink_identity_handler),
+        )
+        .route("/users/by-external/{system}/{external_id}", get(identity::find_user_by_external_id_handler))
+        .route(
+            "/users/{id}/preferences",
+            get(preference::get_preferences_handler).put(preference::update_preferences_handler),
+        )
+        .route("/users/{id}/activity", get(audit::user_activity_handler))
+}
+
+/// `OpenAPI` document, JSON Schemas and Swagger UI routes
+fn docs_routes(openapi: Arc<utoipa::openapi::OpenApi>) -> Router<AppState> {
+    Router::new()
+        .route(
+            "/api-docs/openapi.json",
+            get(move || {
+                let openapi = Arc::clone(&openapi);
+                async move { Json((*openapi).clone()) }
+            }),
+        )
+        .route("/api-docs/schemas", get(schemas::list_schemas_handler))
+        .route("/api-docs/schemas/{file}", get(schemas::get_schema_handler))
         .route("/swagger-ui", get(serve_swagger_ui))
-        .layer(config::tracing::create_http_trace_layer())
-        .with_state(app_state)
 }
 
-/// Serves the `OpenAPI` specification as JSON
-async fn serve_openapi() -> Json<utoipa::openapi::OpenApi> {
-    Json(ApiDoc::openapi())
+/// Data export and erasure routes; downloads are authorized by their signed link
+fn privacy_routes() -> Router<AppState> {
+    Router::new()
+        .route("/users/{id}/export", post(privacy::request_export_handler))
+        .route("/users/{id}/exports/{export_id}", get(privacy::get_export_handler))
+        .route("/exports/{id}/download", get(privacy::download_export_handler))
+        .route("/users/{id}/erase", post(privacy::erase_user_handler))
+}
+
+/// Webhook subscription, secret rotation and delivery inspection routes
+fn webhook_routes() -> Router<AppState> {
+    Router::new()
+        .route("/webhooks", post(webhook::create_webhook_handler))
+        .route("/webhooks/{id}", get(webhook::get_webhook_handler).patch(webhook::update_webhook_handler))
+        .route("/webhooks/{id}/secrets", post(webhook::rotate_webhook_secret_handler))
+        .route("/webhooks/{id}/deliveries", get(webhook::list_webhook_deliveries_handler))
+}
+
+/// Pings of the downstream HTTP dependencies listed in `config`
+fn dependency_pings(config: &HealthConfig) -> Vec<health::SharedHealthCheck> {
+    let timeout = Duration::from_millis(config.dependency_timeout_ms);
+    config
+        .dependencies
+        .as_deref()
+        .map(|spec| DependencyPing::from_spec(spec, timeout))
+        .unwrap_or_default()
+}
+
+/// Section of `config`, or the section loaded from the environment when no configuration was given
+///
+/// Hosts that build an [`AppConfig`] in code get a router running with it,
+/// and `GET /admin/config` reports what the router actually uses.
+fn section<T: Clone>(config: Option<&AppConfig>, pick: impl FnOnce(&AppConfig) -> &T, load: impl FnOnce() -> T) -> T {
+    config.map_or_else(load, |config| pick(config).clone())
+}
+
+/// Health of the notification listener, reported only when `config` lists channels to listen on
+fn notification_check(hub: &NotificationHub, config: &NotifyConfig) -> Option<health::SharedHealthCheck> {
+    (!config.channel_list().is_empty()).then(|| Arc::new(hub.clone()) as health::SharedHealthCheck)
+}
+
+/// `users` column rename to dual-write from `config`; an invalid one is logged and ignored
+pub(crate) fn user_rename(config: &MigrationConfig) -> Option<ColumnRename> {
+    config.user_rename().unwrap_or_else(|e| {
+        tracing::warn!(error = %e, "Ignoring USER_DUAL_WRITE");
+        None
+    })
+}
+
+/// Page size bounds of the users listing and the change log from `config`
+fn page_limits(config: &PaginationConfig) -> PageLimits {
+    PageLimits { default: config.default_limit, max: config.max_limit }
+}
+
+/// User validation limits and strictness from `config`
+fn validation_context(config: &ValidationConfig) -> ValidationContext {
+    let context = if config.strict { ValidationContext::strict() } else { ValidationContext::new() };
+    context.with_policy(ValidationPolicy {
+        name_max_length: config.name_max_length,
+        age_min: config.age_min,
+        age_max: config.age_max,
+        unusual_age_above: config.unusual_age_above,
+        required_metadata_keys: config.required_metadata_keys.clone(),
+    })
+}
+
+/// Name screening policy denying the words in `config`'s denylist
+fn name_screening(config: &ScreeningConfig) -> SharedNameScreeningPolicy {
+    DenylistPolicy::from_spec(config.name_denylist.as_deref().unwrap_or_default()).shared()
+}
+
+/// Privacy service with the configured download link signing key and lifetime
+pub(crate) fn privacy_service(pool: &PgPool, users: UserService, clock: &SharedClock, config: &PrivacyConfig) -> PrivacyService {
+    PrivacyService::new(pool.clone(), users, ExportSigner::from_config(config))
+        .with_clock(Arc::clone(clock))
+        .with_link_ttl(chrono::Duration::seconds(i64::try_from(config.export_link_ttl_secs).unwrap_or(i64::MAX)))
+}
+
+/// Partner service sealing keys with the configured key-encryption key and keeping
+/// previous keys for the configured grace period after a rotation
+fn partner_service(pool: &PgPool, clock: &SharedClock, config: &PartnerConfig) -> PartnerService {
+    PartnerService::new(pool.clone())
+        .with_clock(Arc::clone(clock))
+        .with_rotation_grace(Duration::from_secs(config.key_rotation_grace_secs))
+        .with_key_encryption_key(KeyEncryptionKey::from_config(config))
+}
+
+/// Signature verifier of the [`partner_routes`] with the configured replay window
+fn signature_verifier(partners: &PartnerService, config: &PartnerConfig) -> SignatureVerifier {
+    SignatureVerifier::new(partner_routes(), partners.clone(), Duration::from_secs(config.signature_window_secs))
+}
+
+/// Admin query service with the configured row limit and timeout
+fn query_service(pool: &PgPool, config: &AdminConfig) -> QueryService {
+    QueryService::new(pool.clone())
+        .with_max_rows(config.query_max_rows)
+        .with_timeout(Duration::from_millis(config.query_timeout_ms))
+}
+
+/// Job queue dead-lettering jobs after the configured number of attempts
+pub(crate) fn job_queue(pool: &PgPool, config: &JobsConfig) -> JobQueue {
+    JobQueue::new(pool.clone())
+        .with_max_attempts(config.max_attempts)
+        .with_visibility_timeout(Duration::from_secs(config.visibility_timeout_secs))
+}
+
+/// Webhook service queueing deliveries on the job queue, with the configured timeout and secret rotation grace period
+pub(crate) fn webhook_service(
+    pool: &PgPool,
+    clock: &SharedClock,
+    ids: &SharedIdGenerator,
+    jobs: &JobsConfig,
+    config: &WebhookConfig,
+) -> WebhookService {
+    WebhookService::new(pool.clone(), job_queue(pool, jobs))
+        .with_clock(Arc::clone(clock))
+        .with_id_generator(Arc::clone(ids))
+        .with_timeout(Duration::from_millis(config.timeout_ms))
+        .with_rotation_grace(Duration::from_secs(config.secret_rotation_grace_secs))
+}
+
+/// Reverse proxies whose `Forwarded`/`X-Forwarded-For` headers identify the client
+fn trusted_proxies(server: &ServerConfig) -> Arc<TrustedProxies> {
+    Arc::new(TrustedProxies::from_spec(server.trusted_proxies.as_deref()))
+}
+
+/// Address rules of the admin surface (`/admin` and `/debug`)
+///
+/// Runs before authentication, so blocked addresses cannot probe credentials.
+fn admin_ip_filter(admin: &AdminConfig) -> Arc<IpFilter> {
+    let blocks = |spec: &Option<String>| spec.as_deref().map(IpNet::parse_list).unwrap_or_default();
+    Arc::new(
+        IpFilter::new()
+            .guard("/admin")
+            .guard("/debug")
+            .allow(blocks(&admin.ip_allowlist))
+            .deny(blocks(&admin.ip_denylist)),
+    )
+}
+
+/// Account service with the configured account number scheme and transfer limits
+fn account_service(pool: &PgPool, users: UserService, clock: &SharedClock, config: &BankConfig) -> AccountService {
+    AccountService::new(pool.clone(), users)
+        .with_clock(Arc::clone(clock))
+        .with_account_numbers(AccountNumberScheme::from_config(config))
+        .with_transfer_limits(TransferLimits::from_config(config))
+        .with_retry_policy(RetryPolicy::attempts(config.tx_max_attempts))
+}
+
+/// Bank account, transfer and compliance hold routes
+fn account_routes() -> Router<AppState> {
+    Router::new()
+        .route("/accounts", post(bank::open_account_handler))
+        .route("/accounts/validate-number", post(bank::validate_account_number_handler))
+        .route("/accounts/{id}", get(bank::get_account_handler))
+        .route("/accounts/{id}/withdraw", post(bank::withdraw_handler))
+        .route("/accounts/{id}/transfer-limits", get(bank::transfer_limits_handler))
+        .route("/accounts/{id}/freeze", post(bank::freeze_account_handler))
+        .route("/accounts/{id}/unfreeze", post(bank::unfreeze_account_handler))
+        .route("/transfers", post(bank::transfer_handler))
+        .route("/users/with-accounts", get(bank::list_users_with_accounts_handler))
+        .route("/transfers/external", post(bank::external_transfer_handler))
+        .route(
+            "/users/{id}/beneficiaries",
+            get(bank::list_beneficiaries_handler).post(bank::create_beneficiary_handler),
+        )
+        .route("/users/{id}/beneficiaries/{beneficiary_id}", delete(bank::delete_beneficiary_handler))
+}
+
+/// Admin routes: ledger verification, the dashboard overview, runtime diagnostics, the configuration dump, named queries, the job queue and profiling
+fn admin_routes() -> Router<AppState> {
+    Router::new()
+        .route("/admin/ledger/verify", get(bank::verify_ledger_handler))
+        .route("/admin/overview", get(admin::admin_overview_handler))
+        .route("/admin/runtime", get(admin::admin_runtime_handler))
+        .route("/admin/config", get(admin::admin_config_handler))
+        .route("/admin/modules", get(admin::admin_modules_handler))
+        .route("/admin/query", post(admin::admin_query_handler))
+        .route("/admin/selftest", post(admin::admin_selftest_handler))
+        .route("/admin/jobs", get(jobs::list_jobs_handler))
+        .route("/admin/jobs/{id}/retry", post(jobs::retry_job_handler))
+        .route("/admin/partners/{partner}/keys", post(partner::rotate_partner_key_handler))
+        .route(
+            "/admin/partners/{partner}/accounts/{account_id}",
+            put(partner::grant_partner_account_handler).delete(partner::revoke_partner_account_handler),
+        )
+        .merge(profiling_routes())
+}
+
+/// Profiling routes, served only with the `profiling` feature
+fn profiling_routes() -> Router<AppState> {
+    #[cfg(feature = "profiling")]
+    return Router::new().route("/debug/pprof/profile", get(profiling::profile_handler));
+    #[cfg(not(feature = "profiling"))]
+    Router::new()
+}
+
+/// Routes being phased out, keyed by route pattern
+///
+/// Responses of listed routes carry `Deprecation`/`Sunset`/`Link` headers and
+/// their operations are flagged `deprecated` in the `OpenAPI` document. The
+/// unversioned routes go here once `/api/v1` lands.
+fn deprecated_routes() -> Deprecations {
+    Deprecations::default()
+}
+
+/// Routes partner systems call with signed requests, keyed by method and route pattern
+///
+/// Listed routes reject requests without a valid `X-Signature` (see
+/// [`partner::signing`]); handlers find the caller in the [`partner::PartnerId`]
+/// extension. Partner-facing routes go here as integrations are added.
+fn partner_routes() -> SignedRoutes {
+    SignedRoutes::new().route(Method::POST, "/transfers/external")
+}
+
+/// Request timeouts: the configured default, and a longer one for the heavy routes
+///
+/// Routes that legitimately take long declare it here rather than forcing a
+/// lax default on every other route.
+fn route_timeouts(config: &ServerConfig) -> RouteTimeouts {
+    let long = Duration::from_secs(config.long_request_timeout_secs);
+    let heavy_routes = ["/users/changes", "/users/{id}/erase", "/exports/{id}/download", "/admin/ledger/verify", "/debug/pprof/profile"];
+    heavy_routes
+        .into_iter()
+        .fold(RouteTimeouts::new(Duration::from_secs(config.request_timeout_secs)), |timeouts, path| {
+            timeouts.route(path, long)
+        })
+}
+
+/// User routes whose bodies are content-negotiated (`Payload` and `Negotiate`)
+///
+/// `OPTIONS` reports the extra formats for these; the `OpenAPI` document only lists JSON.
+const fn negotiated_routes() -> [&'static str; 8] {
+    [
+        "/users",
+        "/users/changes",
+        "/users/{id}",
+        "/users/{id}/suspend",
+        "/users/{id}/activate",
+        "/users/{id}/archive",
+        "/users/{id}/history",
+        "/users/{id}/revert",
+    ]
+}
+
+/// Cache policies: public reads may be cached by shared caches, everything else is `no-store`
+///
+/// Add a route to a group here to let CDNs serve it; user reads vary on
+/// `Accept` because their representation is negotiated.
+fn cache_policies(config: &ServerConfig) -> CachePolicies {
+    let public = CachePolicy::public(Duration::from_secs(config.cache_max_age_secs));
+    let docs = ["/", "/version", "/api-docs/openapi.json", "/api-docs/schemas", "/api-docs/schemas/{file}"];
+    let user_reads = [
+        "/users",
+        "/users/{id}",
+        "/users/{id}/addresses",
+        "/users/{id}/addresses/{address_id}",
+        "/users/{id}/tags",
+        "/tags/autocomplete",
+    ];
+    CachePolicies::new()
+        .group(docs, public)
+        .group(user_reads, public)
+        .vary(header::ACCEPT)
+}
+
+/// Access requirements of the routes, keyed by method and route pattern
+///
+/// This is the single place to restrict a route: the router enforces it and
+/// the `OpenAPI` document advertises it. Routes not listed are public, but
+/// every documented route, reads included, is listed here, public ones
+/// included, or signed by partners in [`partner_routes`], so a new one cannot
+/// be left open by omission (a unit test checks this).
+fn route_policies() -> RoutePolicies {
+    RoutePolicies::new()
+        .route(Method::GET, "/admin/ledger/verify", AccessPolicy::Role("admin".to_owned()))
+        .route(Method::GET, "/admin/overview", AccessPolicy::Role("admin".to_owned()))
+        .route(Method::GET, "/admin/runtime", AccessPolicy::Role("admin".to_owned()))
+        .route(Method::GET, "/admin/config", AccessPolicy::Role("admin".to_owned()))
+        .route(Method::GET, "/admin/modules", AccessPolicy::Role("admin".to_owned()))
+        .route(Method::POST, "/admin/query", AccessPolicy::Role("admin".to_owned()))
+        .route(Method::POST, "/admin/selftest", AccessPolicy::Role("admin".to_owned()))
+        .route(Method::GET, "/admin/jobs", AccessPolicy::Role("admin".to_owned()))
+        .route(Method::POST, "/admin/jobs/{id}/retry", AccessPolicy::Role("admin".to_owned()))
+        .route(Method::POST, "/admin/partners/{partner}/keys", AccessPolicy::Role("admin".to_owned()))
+        .route(Method::PUT, "/admin/partners/{partner}/accounts/{account_id}", AccessPolicy::Role("admin".to_owned()))
+        .route(Method::DELETE, "/admin/partners/{partner}/accounts/{account_id}", AccessPolicy::Role("admin".to_owned()))
+        .route(Method::POST, "/webhooks", AccessPolicy::Role("admin".to_owned()))
+        .route(Method::GET, "/webhooks/{id}", AccessPolicy::Role("admin".to_owned()))
+        .route(Method::PATCH, "/webhooks/{id}", AccessPolicy::Role("admin".to_owned()))
+        .route(Method::POST, "/webhooks/{id}/secrets", AccessPolicy::Role("admin".to_owned()))
+        .route(Method::GET, "/webhooks/{id}/deliveries", AccessPolicy::Role("admin".to_owned()))
+        .route(Method::GET, "/debug/pprof/profile", AccessPolicy::Role("admin".to_owned()))
+        .route(Method::GET, "/metrics", AccessPolicy::Role("metrics".to_owned()))
+        .route(Method::GET, "/users/{id}/history", AccessPolicy::Role("admin".to_owned()))
+        .route(Method::POST, "/users/{id}/revert", AccessPolicy::Role("admin".to_owned()))
+        .route(Method::POST, "/users/{id}/export", AccessPolicy::Role("admin".to_owned()))
+        .route(Method::GET, "/users/{id}/exports/{export_id}", AccessPolicy::Role("admin".to_owned()))
+        .route(Method::POST, "/users/{id}/erase", AccessPolicy::Role("admin".to_owned()))
+        .route(Method::POST, "/accounts/{id}/freeze", AccessPolicy::Role("admin".to_owned()))
+        .route(Method::POST, "/accounts/{id}/unfreeze", AccessPolicy::Role("admin".to_owned()))
+        .route(Method::POST, "/accounts", AccessPolicy::Role("admin".to_owned()))
+        .route(Method::POST, "/accounts/{id}/withdraw", AccessPolicy::Role("admin".to_owned()))
+        .route(Method::POST, "/transfers", AccessPolicy::Role("admin".to_owned()))
+        .route(Method::DELETE, "/users", AccessPolicy::Role("admin".to_owned()))
+        .route(Method::PATCH, "/users", AccessPolicy::Role("admin".to_owned()))
+        .route(Method::GET, "/users/with-accounts", AccessPolicy::Role("admin".to_owned()))
+        .route(Method::GET, "/accounts/{id}", AccessPolicy::Role("admin".to_owned()))
+        .route(Method::GET, "/accounts/{id}/transfer-limits", AccessPolicy::Role("admin".to_owned()))
+        .route(Method::GET, "/users/{id}/beneficiaries", AccessPolicy::Role("admin".to_owned()))
+        .route(Method::POST, "/users/{id}/beneficiaries", AccessPolicy::Role("admin".to_owned()))
+        .route(Method::DELETE, "/users/{id}/beneficiaries/{beneficiary_id}", AccessPolicy::Role("admin".to_owned()))
+        .route(Method::GET, "/users/{id}/activity", AccessPolicy::Role("admin".to_owned()))
+        .route(Method::GET, "/changes", AccessPolicy::Role("admin".to_owned()))
+        .route(Method::DELETE, "/users/{id}", AccessPolicy::Role("admin".to_owned()))
+        .route(Method::POST, "/users/{id}/suspend", AccessPolicy::Role("admin".to_owned()))
+        .route(Method::POST, "/users/{id}/activate", AccessPolicy::Role("admin".to_owned()))
+        .route(Method::POST, "/users/{id}/archive", AccessPolicy::Role("admin".to_owned()))
+        .route(Method::POST, "/users", AccessPolicy::Public)
+        .route(Method::PUT, "/users", AccessPolicy::Public)
+        .route(Method::PUT, "/users/{id}", AccessPolicy::Public)
+        .route(Method::POST, "/users/{id}/addresses", AccessPolicy::Public)
+        .route(Method::PUT, "/users/{id}/addresses/{address_id}", AccessPolicy::Public)
+        .route(Method::DELETE, "/users/{id}/addresses/{address_id}", AccessPolicy::Public)
+        .route(Method::PUT, "/users/{id}/tags/{tag}", AccessPolicy::Public)
+        .route(Method::DELETE, "/users/{id}/tags/{tag}", AccessPolicy::Public)
+        .route(Method::PUT, "/users/{id}/identities/{system}", AccessPolicy::Public)
+        .route(Method::DELETE, "/users/{id}/identities/{system}", AccessPolicy::Public)
+        .route(Method::PUT, "/users/{id}/preferences", AccessPolicy::Public)
+        .route(Method::POST, "/accounts/validate-number", AccessPolicy::Public)
+        .route(Method::GET, "/users", AccessPolicy::Public)
+        .route(Method::GET, "/users/{id}", AccessPolicy::Public)
+        .route(Method::GET, "/users/stream", AccessPolicy::Public)
+        .route(Method::GET, "/users/changes", AccessPolicy::Public)
+        .route(Method::GET, "/users/by-external/{system}/{external_id}", AccessPolicy::Public)
+        .route(Method::GET, "/users/{id}/addresses", AccessPolicy::Public)
+        .route(Method::GET, "/users/{id}/addresses/{address_id}", AccessPolicy::Public)
+        .route(Method::GET, "/users/{id}/tags", AccessPolicy::Public)
+        .route(Method::GET, "/users/{id}/identities", AccessPolicy::Public)
+        .route(Method::GET, "/users/{id}/preferences", AccessPolicy::Public)
+        .route(Method::GET, "/tags/autocomplete", AccessPolicy::Public)
+        .route(Method::GET, "/exports/{id}/download", AccessPolicy::Public)
+        .route(Method::GET, "/health", AccessPolicy::Public)
+        .route(Method::GET, "/ready", AccessPolicy::Public)
+        .route(Method::GET, "/live", AccessPolicy::Public)
+        .route(Method::GET, "/version", AccessPolicy::Public)
+}
+
+/// Who may read the API documentation; `None` when it is not served
+///
+/// `DOCS_ACCESS` overrides the environment's default (public, except in
+/// production where the documentation is not served). Unrecognized values
+/// disable the documentation rather than expose it.
+fn docs_access(server: &ServerConfig, environment: Environment) -> Option<AccessPolicy> {
+    match server.docs_access.as_deref().map(str::trim) {
+        None => environment.profile().swagger_ui.then_some(AccessPolicy::Public),
+        Some("public") => Some(AccessPolicy::Public),
+        Some("authenticated") => Some(AccessPolicy::Authenticated),
+        Some(spec) => spec
+            .strip_prefix("role:")
+            .filter(|role| !role.is_empty())
+            .map(|role| AccessPolicy::Role(role.to_owned())),
+    }
+}
+
+/// Authentication with the configured API tokens and the route policies,
+/// documentation routes restricted to `docs`
+fn access_control(auth: &AuthConfig, docs: Option<&AccessPolicy>) -> AccessControl {
+    AccessControl::new(
+        docs_policies(route_policies(), docs),
+        StaticTokenAuthenticator::from_spec(auth.api_tokens.as_deref().unwrap_or_default()),
+    )
+}
+
+/// `policies` with the documentation routes restricted to `access`
+fn docs_policies(policies: RoutePolicies, access: Option<&AccessPolicy>) -> RoutePolicies {
+    let Some(access) = access else {
+        return policies;
+    };
+    ["/api-docs/openapi.json", "/api-docs/schemas", "/api-docs/schemas/{file}", "/swagger-ui"]
+        .into_iter()
+        .fold(policies, |policies, path| policies.route(Method::GET, path, access.clone()))
+}
+
+/// The served `OpenAPI` specification, including deprecations and security requirements
+#[must_use]
+pub fn openapi_spec() -> utoipa::openapi::OpenApi {
+    openapi_spec_with_modules(&ModuleRegistry::new())
+}
+
+/// The served `OpenAPI` specification with the documents of `modules` merged in
+#[must_use]
+pub fn openapi_spec_with_modules(modules: &ModuleRegistry) -> utoipa::openapi::OpenApi {
+    let mut openapi = ApiDoc::openapi();
+    modules.apply_to_openapi(&mut openapi);
+    deprecated_routes().apply_to_openapi(&mut openapi);
+    route_policies().apply_to_openapi(&mut openapi);
+    openapi
+}
+
+/// Documented routes of `openapi` with the access policy each one enforces
+fn route_table(openapi: &utoipa::openapi::OpenApi) -> Vec<RouteEntry> {
+    let policies = route_policies();
+    openapi
+        .paths
+        .paths
+        .iter()
+        .flat_map(|(path, item)| {
+            [
+                (Method::GET, item.get.is_some()),
+                (Method::PUT, item.put.is_some()),
+                (Method::POST, item.post.is_some()),
+                (Method::DELETE, item.delete.is_some()),
+                (Method::PATCH, item.patch.is_some()),
+            ]
+            .into_iter()
+            .filter(|(_, served)| *served)
+            .map(|(method, _)| RouteEntry {
+                access: match policies.policy_for(&method, path) {
+                    AccessPolicy::Public => "public".to_owned(),
+                    AccessPolicy::Authenticated => "authenticated".to_owned(),
+                    AccessPolicy::Role(role) => format!("role:{role}"),
+                    AccessPolicy::Denied => "denied".to_owned(),
+                },
+                path: path.clone(),
+                method: method.to_string(),
+            })
+        })
+        .collect()
 }
 
 /// Root endpoint providing API information
@@ -145,11 +1136,17 @@ async fn root_handler() -> Json<serde_json::Value> {
         "status": "running",
         "endpoints": {
             "users": "/users",
+            "users_stream": "/users/stream",
+            "users_changes": "/users/changes",
+            "changes": "/changes",
             "health": "/health",
             "readiness": "/ready",
             "liveness": "/live",
+            "version": "/version",
+            "metrics": "/metrics",
             "docs": "/swagger-ui",
-            "openapi": "/api-docs/openapi.json"
+            "openapi": "/api-docs/openapi.json",
+            "schemas": "/api-docs/schemas"
         }
     }))
 }
@@ -178,3 +1175,49 @@ async fn serve_swagger_ui() -> Html<String> {
 </body>
 </html>"#.to_owned())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_every_route_declares_its_access_policy() {
+        let policies = route_policies();
+        let signed = partner_routes();
+        let openapi = openapi_spec();
+        let mut undeclared = Vec::new();
+        for (path, item) in &openapi.paths.paths {
+            let operations = [
+                (Method::GET, &item.get),
+                (Method::POST, &item.post),
+                (Method::PUT, &item.put),
+                (Method::PATCH, &item.patch),
+                (Method::DELETE, &item.delete),
+            ];
+            for (method, operation) in operations {
+                if operation.is_some() && !policies.is_declared(&method, path) && !signed.contains(&method, path) {
+                    undeclared.push(format!("{method} {path}"));
+                }
+            }
+        }
+
+        assert!(undeclared.is_empty(), "Declare the access policy of {undeclared:?} in route_policies() or sign them in partner_routes()");
+    }
+
+    #[test]
+    fn test_account_and_audit_reads_require_admin() {
+        let policies = route_policies();
+        let sensitive = [
+            "/users/with-accounts",
+            "/accounts/{id}",
+            "/accounts/{id}/transfer-limits",
+            "/users/{id}/beneficiaries",
+            "/users/{id}/activity",
+            "/changes",
+        ];
+
+        for path in sensitive {
+            assert_eq!(policies.policy_for(&Method::GET, path), AccessPolicy::Role("admin".to_owned()), "GET {path}");
+        }
+    }
+}
@@ -0,0 +1,92 @@
+//! Hypermedia links
+//!
+//! Builds the links that go into `_links` sections of API resources. Links are
+//! absolute when a public base URL is configured (`PUBLIC_BASE_URL`) and
+//! root-relative otherwise.
+
+use schemars::JsonSchema;
+use serde::Serialize;
+use tracing::warn;
+use utoipa::ToSchema;
+
+/// A hypermedia link
+#[derive(Serialize, ToSchema, JsonSchema, Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "typescript", derive(ts_rs::TS))]
+pub struct Link {
+    /// Target URL
+    pub href: String,
+}
+
+/// Builds links relative to the configured base URL
+#[derive(Debug, Clone, Default)]
+pub struct LinkBuilder {
+    base_url: String,
+}
+
+impl LinkBuilder {
+    /// Creates a builder for the given public base URL (e.g. `https://api.example.com`)
+    #[must_use]
+    pub fn new(base_url: Option<&str>) -> Self {
+        Self {
+            base_url: base_url.unwrap_or_default().trim_end_matches('/').to_owned(),
+        }
+    }
+
+    /// Link to `path` (which must start with `/`)
+    #[must_use]
+    pub fn link(&self, path: &str) -> Link {
+        Link {
+            href: format!("{}{path}", self.base_url),
+        }
+    }
+
+    /// Link to `path` with `query` encoded as its query string
+    ///
+    /// `None` fields should be skipped by the query type so they don't show up
+    /// as empty parameters.
+    pub fn link_with_query<Q: Serialize>(&self, path: &str, query: &Q) -> Link {
+        match serde_urlencoded::to_string(query) {
+            Ok(encoded) if !encoded.is_empty() => self.link(&format!("{path}?{encoded}")),
+            Ok(_) => self.link(path),
+            Err(e) => {
+                warn!(error = %e, path, "Failed to encode link query string");
+                self.link(path)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Serialize)]
+    struct Query<'a> {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        next_token: Option<&'a str>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        limit: Option<i32>,
+    }
+
+    #[test]
+    fn test_link_relative_without_base_url() {
+        assert_eq!(LinkBuilder::default().link("/users/1").href, "/users/1");
+    }
+
+    #[test]
+    fn test_link_with_base_url() {
+        let links = LinkBuilder::new(Some("https://api.example.com/"));
+        assert_eq!(links.link("/users/1").href, "https://api.example.com/users/1");
+    }
+
+    #[test]
+    fn test_link_with_query() {
+        let links = LinkBuilder::default();
+
+        let link = links.link_with_query("/users", &Query { next_token: Some("a+b="), limit: Some(10) });
+        assert_eq!(link.href, "/users?next_token=a%2Bb%3D&limit=10");
+
+        let link = links.link_with_query("/users", &Query { next_token: None, limit: None });
+        assert_eq!(link.href, "/users", "Empty queries should not leave a trailing `?`");
+    }
+}
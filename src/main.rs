@@ -2,17 +2,28 @@
 //!
 //! Main entry point for the Rust Kickstart API server.
 //! Configures logging and starts the HTTP server.
+//!
+//! `rust-kickstart healthcheck` instead probes a running server's `/live`
+//! endpoint on the configured port, over HTTPS when `TLS_*` is set, and exits with 0 when it is alive, 1
+//! otherwise.
 
-use rust_kickstart::{create_app, AppConfig};
+use rust_kickstart::{app, health, AppBuilder, AppConfig, ServerConfig};
+use rust_kickstart::config::{Environment, TlsConfig};
+use tokio::net::TcpListener;
 use rust_kickstart::config::tracing as tracing_config;
 
 #[tokio::main]
+#[allow(clippy::print_stderr)]
 async fn main() {
+    if std::env::args().nth(1).as_deref() == Some("healthcheck") {
+        std::process::exit(healthcheck().await);
+    }
+
     eprintln!("🚀 Starting Rust Kickstart application...");
     
     // Load environment variables from .env file first
     if let Err(e) = dotenvy::dotenv() {
-        eprintln!("⚠️  Warning: Could not load .env file: {}", e);
+        eprintln!("⚠️  Warning: Could not load .env file: {e}");
         eprintln!("💡 Make sure you have a .env file in the project root");
     } else {
         eprintln!("✅ Environment variables loaded from .env file");
@@ -20,7 +31,7 @@ async fn main() {
     
     // Initialize enhanced tracing configuration
     if let Err(e) = tracing_config::init() {
-        eprintln!("Failed to initialize tracing: {}", e);
+        eprintln!("Failed to initialize tracing: {e}");
         std::process::exit(1);
     }
 
@@ -33,51 +44,94 @@ async fn main() {
         config.environment
     );
 
-    let app = create_app().await;
+    // Serve over mutual TLS when the `TLS_*` paths are set
+    #[cfg(feature = "mtls")]
+    let tls = config.tls.is_configured().then(|| {
+        rust_kickstart::mtls::server_config(&config.tls).unwrap_or_else(|e| {
+            eprintln!("Failed to load TLS configuration: {e}");
+            std::process::exit(1);
+        })
+    });
+    #[cfg(not(feature = "mtls"))]
+    if config.tls.is_configured() {
+        eprintln!("TLS_* settings require building with the `mtls` feature");
+        std::process::exit(1);
+    }
+
+    let mut builder = AppBuilder::new(config.clone());
+    if config.database.run_migrations {
+        builder = builder.on_startup("migrations", app::run_migrations);
+    }
+    if config.partner.key_encryption_key.is_some() && !config.server.read_only {
+        builder = builder.on_startup("partner-keys", app::seal_partner_keys);
+    }
+    let application = builder
+        .on_shutdown("telemetry", || async {
+            // Shutdown OpenTelemetry to flush remaining telemetry data
+            tracing_config::shutdown();
+            tracing::info!("OpenTelemetry shutdown completed");
+        })
+        .build()
+        .await
+        .unwrap_or_else(|e| {
+            eprintln!("Failed to start application: {e}");
+            std::process::exit(1);
+        });
 
     // Use configuration for server settings
     let addr = config.server.address();
 
-    let listener = tokio::net::TcpListener::bind(&addr)
+    let listener = TcpListener::bind(&addr)
         .await
         .expect("Failed to bind to address");
 
     let local_addr = listener.local_addr().expect("Failed to get local address");
+    let scheme = if config.tls.is_configured() { "https" } else { "http" };
 
     // Print clickable links
     #[allow(clippy::print_stdout)]
     {
-        let display_addr = match config.environment.as_str() {
-            "development" => format!("localhost:{}", local_addr.port()),
-            _ => local_addr.to_string(),
+        let display_addr = match config.environment {
+            Environment::Development => format!("localhost:{}", local_addr.port()),
+            Environment::Staging | Environment::Production | Environment::Test => local_addr.to_string(),
         };
 
         println!("\n🚀 Server running!");
-        println!("📍 Local:    http://{display_addr}");
-        println!("📖 Docs:     http://{display_addr}/swagger-ui");
-        println!("🔗 API:      http://{display_addr}/api-docs/openapi.json");
+        println!("📍 Local:    {scheme}://{display_addr}");
+        println!("📖 Docs:     {scheme}://{display_addr}/swagger-ui");
+        println!("🔗 API:      {scheme}://{display_addr}/api-docs/openapi.json");
         println!("\nPress Ctrl+C to stop\n");
     }
 
     tracing::info!("Server listening on {}", local_addr);
 
-    // Setup graceful shutdown
-    let shutdown_signal = async {
-        tokio::signal::ctrl_c()
-            .await
-            .expect("Failed to install CTRL+C signal handler");
-        tracing::info!("Shutdown signal received, starting graceful shutdown...");
-        
-        // Shutdown OpenTelemetry to flush remaining telemetry data
-        tracing_config::shutdown();
-        tracing::info!("OpenTelemetry shutdown completed");
+    // Run server with graceful shutdown, then the shutdown hooks
+    #[cfg(feature = "mtls")]
+    let served = match tls {
+        Some(tls) => {
+            let listener = rust_kickstart::mtls::MtlsListener::new(listener, tls).expect("Failed to listen for TLS connections");
+            application.serve_mtls(listener, app::shutdown_signal()).await
+        }
+        None => application.serve(listener, app::shutdown_signal()).await,
     };
-
-    // Run server with graceful shutdown
-    axum::serve(listener, app)
-        .with_graceful_shutdown(shutdown_signal)
-        .await
-        .expect("Server failed to start");
+    #[cfg(not(feature = "mtls"))]
+    let served = application.serve(listener, app::shutdown_signal()).await;
+    served.expect("Server failed to start");
 
     tracing::info!("Server shutdown completed");
 }
+
+/// Probes the liveness endpoint of the server on the configured port; returns the exit code
+#[allow(clippy::print_stderr)]
+async fn healthcheck() -> i32 {
+    dotenvy::dotenv().ok();
+    let tls = TlsConfig::load();
+    let url = health::probe::liveness_url(&ServerConfig::load(), &tls);
+    match health::probe::probe(&url, &tls).await {
+        Ok(()) => 0,
+        Err(e) => {
+            eprintln!("Health check failed: {e}");
+            1
+        }
+    }
+}
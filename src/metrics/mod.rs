@@ -0,0 +1,464 @@
+//! Domain metrics
+//!
+//! Business figures for product dashboards, next to the HTTP response counts
+//! in [`crate::stats`]. Each metric is a [`Metric`] constant from the catalog
+//! below; services record into it directly (`USERS_CREATED.increment(&[])`),
+//! which updates the process-wide [`MetricsRegistry`]. `GET /metrics` serves
+//! the registry in the Prometheus text format, which Prometheus scrapes and
+//! the `OpenTelemetry` Collector's Prometheus receiver forwards over OTLP.
+//!
+//! Error budget counters (`http_requests_total`, `http_server_errors_total`,
+//! `db_failures_total`) keep the trace ID of their latest increment as an
+//! exemplar, rendered when the scraper asks for the `OpenMetrics` format, so an
+//! alert on error budget burn links straight to an offending trace.
+//!
+//! Counts start at zero when the process starts. Label values must come from
+//! a small, fixed set (operation and field names, never user input), or the
+//! number of series grows without bound.
+
+use std::{
+    collections::BTreeMap,
+    sync::{Mutex, OnceLock, PoisonError},
+};
+
+use axum::{
+    http::{HeaderMap, header},
+    response::IntoResponse,
+};
+use chrono::Utc;
+
+/// Content type of the Prometheus text exposition format
+pub const PROMETHEUS_CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+/// Content type of the `OpenMetrics` text format, which carries exemplars
+pub const OPENMETRICS_CONTENT_TYPE: &str = "application/openmetrics-text; version=1.0.0; charset=utf-8";
+
+/// Users created, through `POST /users` or an upsert that inserted
+pub const USERS_CREATED: Metric = Metric::counter("users_created_total", "Users created");
+/// Transfers completed, by `kind` (`internal` or `external`)
+pub const TRANSFERS_COMPLETED: Metric =
+    Metric::counter("transfers_completed_total", "Transfers completed").labelled(&["kind"]);
+/// Money moved by completed transfers in cents, by `kind`
+pub const TRANSFER_VOLUME: Metric =
+    Metric::counter("transfer_volume_cents_total", "Money moved by completed transfers, in cents").labelled(&["kind"]);
+/// Rejected fields, by `operation` and `field` (`none` for errors about the whole request)
+pub const VALIDATION_FAILURES: Metric =
+    Metric::counter("validation_failures_total", "Validation errors returned to callers").labelled(&["operation", "field"]);
+/// Background job runs in progress, by `job`
+pub const JOBS_RUNNING: Metric = Metric::gauge("jobs_running", "Background job runs in progress").labelled(&["job"]);
+/// Responses sent, the denominator of the error budget
+pub const HTTP_REQUESTS: Metric = Metric::counter("http_requests_total", "HTTP responses sent").with_exemplars();
+/// Responses with a 5xx status, spending the error budget
+pub const HTTP_SERVER_ERRORS: Metric =
+    Metric::counter("http_server_errors_total", "HTTP responses with a 5xx status").with_exemplars();
+/// Failed database calls, by `kind` (see [`crate::db::DbErrorKind`])
+pub const DB_FAILURES: Metric =
+    Metric::counter("db_failures_total", "Failed database calls").labelled(&["kind"]).with_exemplars();
+
+/// Every metric of the catalog, described in the exposition even before it is recorded
+pub const CATALOG: [Metric; 8] = [
+    USERS_CREATED,
+    TRANSFERS_COMPLETED,
+    TRANSFER_VOLUME,
+    VALIDATION_FAILURES,
+    JOBS_RUNNING,
+    HTTP_REQUESTS,
+    HTTP_SERVER_ERRORS,
+    DB_FAILURES,
+];
+
+/// Text format of an exposition
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Exposition {
+    /// Prometheus text format 0.0.4 (default)
+    #[default]
+    Prometheus,
+    /// `OpenMetrics` 1.0, with exemplars
+    OpenMetrics,
+}
+
+impl Exposition {
+    /// Format asked for by an `Accept` header
+    #[must_use]
+    pub fn from_accept(accept: &str) -> Self {
+        if accept.contains("application/openmetrics-text") {
+            Self::OpenMetrics
+        } else {
+            Self::Prometheus
+        }
+    }
+
+    /// Content type of the format
+    #[must_use]
+    pub const fn content_type(self) -> &'static str {
+        match self {
+            Self::Prometheus => PROMETHEUS_CONTENT_TYPE,
+            Self::OpenMetrics => OPENMETRICS_CONTENT_TYPE,
+        }
+    }
+}
+
+/// How a metric's value evolves
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MetricKind {
+    /// Only goes up
+    Counter,
+    /// Goes up and down
+    Gauge,
+}
+
+impl MetricKind {
+    /// Prometheus `# TYPE` of the kind
+    const fn as_str(self) -> &'static str {
+        match self {
+            Self::Counter => "counter",
+            Self::Gauge => "gauge",
+        }
+    }
+}
+
+/// A named domain metric
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Metric {
+    /// Metric name, e.g. `users_created_total`
+    pub name: &'static str,
+    /// Description shown as `# HELP`
+    pub help: &'static str,
+    /// Counter or gauge
+    pub kind: MetricKind,
+    /// Names of the labels its series carry
+    pub labels: &'static [&'static str],
+    /// Whether increments inside a traced span keep its trace ID as an exemplar
+    pub exemplars: bool,
+}
+
+impl Metric {
+    /// Declares a counter
+    #[must_use]
+    pub const fn counter(name: &'static str, help: &'static str) -> Self {
+        Self { name, help, kind: MetricKind::Counter, labels: &[], exemplars: false }
+    }
+
+    /// Declares a gauge
+    #[must_use]
+    pub const fn gauge(name: &'static str, help: &'static str) -> Self {
+        Self { name, help, kind: MetricKind::Gauge, labels: &[], exemplars: false }
+    }
+
+    /// The metric with series labelled by `labels`
+    #[must_use]
+    pub const fn labelled(self, labels: &'static [&'static str]) -> Self {
+        Self { labels, ..self }
+    }
+
+    /// The metric with the trace ID of its latest increment kept as an exemplar
+    #[must_use]
+    pub const fn with_exemplars(self) -> Self {
+        Self { exemplars: true, ..self }
+    }
+
+    /// Adds one to the series with `labels` in the global registry
+    pub fn increment(&self, labels: &[(&'static str, &str)]) {
+        MetricsRegistry::global().adjust(self, labels, 1);
+    }
+
+    /// Adds `value` to the series with `labels` in the global registry
+    pub fn add(&self, labels: &[(&'static str, &str)], value: u64) {
+        MetricsRegistry::global().adjust(self, labels, i64::try_from(value).unwrap_or(i64::MAX));
+    }
+
+    /// Subtracts one from the series with `labels` in the global registry (gauges only)
+    pub fn decrement(&self, labels: &[(&'static str, &str)]) {
+        debug_assert_eq!(self.kind, MetricKind::Gauge, "Counters only go up");
+        MetricsRegistry::global().adjust(self, labels, -1);
+    }
+}
+
+/// Values of one metric, keyed by their label pairs
+#[derive(Debug)]
+struct Family {
+    /// The metric
+    metric: Metric,
+    /// Value per sorted label set
+    series: BTreeMap<Vec<(&'static str, String)>, i64>,
+    /// Latest exemplar per sorted label set
+    exemplars: BTreeMap<Vec<(&'static str, String)>, Exemplar>,
+}
+
+/// Trace that last incremented a series
+#[derive(Debug, Clone, PartialEq)]
+struct Exemplar {
+    /// Trace ID, 32 hex digits
+    trace_id: String,
+    /// Size of the increment
+    value: i64,
+    /// When it happened, in Unix seconds
+    timestamp: f64,
+}
+
+impl Family {
+    /// Family of `metric` in `families`, added when missing
+    fn of<'a>(families: &'a mut BTreeMap<&'static str, Self>, metric: &Metric) -> &'a mut Self {
+        families.entry(metric.name).or_insert_with(|| Self {
+            metric: *metric,
+            series: BTreeMap::new(),
+            exemplars: BTreeMap::new(),
+        })
+    }
+}
+
+/// Current value of every recorded series
+///
+/// Services record into [`MetricsRegistry::global`]; other instances are for tests.
+#[derive(Debug, Default)]
+pub struct MetricsRegistry {
+    /// Families by metric name
+    families: Mutex<BTreeMap<&'static str, Family>>,
+}
+
+impl MetricsRegistry {
+    /// Registry the [`Metric`] methods record into, with the [`CATALOG`] described
+    pub fn global() -> &'static Self {
+        static GLOBAL: OnceLock<MetricsRegistry> = OnceLock::new();
+        GLOBAL.get_or_init(|| {
+            let registry = Self::default();
+            for metric in &CATALOG {
+                registry.describe(metric);
+            }
+            registry
+        })
+    }
+
+    /// Lists `metric` in the exposition; an unlabelled metric starts at zero
+    pub fn describe(&self, metric: &Metric) {
+        let mut families = self.families.lock().unwrap_or_else(PoisonError::into_inner);
+        let family = Family::of(&mut families, metric);
+        if metric.labels.is_empty() {
+            family.series.entry(Vec::new()).or_default();
+        }
+    }
+
+    /// Adds `delta` to the series of `metric` with `labels`
+    ///
+    /// For metrics with exemplars, an increment inside a span exported with
+    /// `OpenTelemetry` becomes the series' exemplar.
+    pub fn adjust(&self, metric: &Metric, labels: &[(&'static str, &str)], delta: i64) {
+        let labels = series_key(labels);
+        let exemplar = (metric.exemplars && delta > 0).then(current_trace_id).flatten().map(|trace_id| Exemplar {
+            trace_id,
+            value: delta,
+            #[allow(clippy::cast_precision_loss)]
+            timestamp: Utc::now().timestamp_millis() as f64 / 1000.0,
+        });
+        let mut families = self.families.lock().unwrap_or_else(PoisonError::into_inner);
+        let family = Family::of(&mut families, metric);
+        if let Some(exemplar) = exemplar {
+            family.exemplars.insert(labels.clone(), exemplar);
+        }
+        let value = family.series.entry(labels).or_default();
+        *value = value.saturating_add(delta);
+    }
+
+    /// Trace ID of the exemplar of the series of `metric` with `labels`, if any
+    #[must_use]
+    pub fn exemplar(&self, metric: &Metric, labels: &[(&'static str, &str)]) -> Option<String> {
+        let labels = series_key(labels);
+        self.families
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(metric.name)
+            .and_then(|family| family.exemplars.get(&labels))
+            .map(|exemplar| exemplar.trace_id.clone())
+    }
+
+    /// Value of the series of `metric` with `labels`, if recorded
+    #[must_use]
+    pub fn value(&self, metric: &Metric, labels: &[(&'static str, &str)]) -> Option<i64> {
+        let labels = series_key(labels);
+        self.families
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(metric.name)
+            .and_then(|family| family.series.get(&labels).copied())
+    }
+
+    /// Every family in the Prometheus text exposition format
+    #[must_use]
+    pub fn render(&self) -> String {
+        self.render_as(Exposition::Prometheus)
+    }
+
+    /// Every family in `format`
+    ///
+    /// `OpenMetrics` names counter families without their `_total` suffix,
+    /// appends exemplars to their samples and ends with `# EOF`.
+    #[must_use]
+    pub fn render_as(&self, format: Exposition) -> String {
+        let open_metrics = format == Exposition::OpenMetrics;
+        let families = self.families.lock().unwrap_or_else(PoisonError::into_inner);
+        let mut lines = Vec::new();
+        for family in families.values() {
+            let Metric { name, help, kind, .. } = family.metric;
+            let family_name = if open_metrics && kind == MetricKind::Counter {
+                name.strip_suffix("_total").unwrap_or(name)
+            } else {
+                name
+            };
+            lines.push(format!("# HELP {family_name} {help}"));
+            lines.push(format!("# TYPE {family_name} {}", kind.as_str()));
+            for (labels, value) in &family.series {
+                let sample = format!("{name}{} {value}", label_set(labels));
+                match family.exemplars.get(labels).filter(|_| open_metrics) {
+                    Some(Exemplar { trace_id, value, timestamp }) => {
+                        lines.push(format!("{sample} # {{trace_id=\"{trace_id}\"}} {value} {timestamp:.3}"));
+                    }
+                    None => lines.push(sample),
+                }
+            }
+        }
+        if open_metrics {
+            lines.push("# EOF".to_owned());
+        }
+        lines.push(String::new());
+        lines.join("\n")
+    }
+}
+
+/// Label pairs sorted by name, identifying a series within its family
+fn series_key(labels: &[(&'static str, &str)]) -> Vec<(&'static str, String)> {
+    let mut key: Vec<_> = labels.iter().map(|&(name, value)| (name, value.to_owned())).collect();
+    key.sort_unstable();
+    key
+}
+
+/// `{name="value",...}`, or nothing for an unlabelled series
+fn label_set(labels: &[(&'static str, String)]) -> String {
+    if labels.is_empty() {
+        return String::new();
+    }
+    let pairs = labels
+        .iter()
+        .map(|(label, value)| format!("{label}=\"{}\"", escape(value)))
+        .collect::<Vec<_>>()
+        .join(",");
+    format!("{{{pairs}}}")
+}
+
+/// Trace ID of the current span, when it is exported with `OpenTelemetry`
+fn current_trace_id() -> Option<String> {
+    #[cfg(feature = "otel")]
+    return {
+        use opentelemetry::trace::TraceContextExt;
+        use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+        let context = tracing::Span::current().context();
+        let span = context.span();
+        let span_context = span.span_context();
+        span_context.is_valid().then(|| span_context.trace_id().to_string())
+    };
+    #[cfg(not(feature = "otel"))]
+    None
+}
+
+/// Escapes a label value for the exposition format
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+/// HTTP handler exposing the domain and error budget metrics
+///
+/// Answers in `OpenMetrics`, with exemplars, when the `Accept` header asks for it.
+#[utoipa::path(
+    get,
+    path = "/metrics",
+    responses(
+        (status = 200, description = "Domain and error budget metrics in the Prometheus text format, or OpenMetrics with exemplar trace IDs", content(
+            (String = "text/plain"),
+            (String = "application/openmetrics-text")
+        ))
+    ),
+    tag = "health"
+)]
+pub async fn metrics_handler(headers: HeaderMap) -> impl IntoResponse {
+    let format = headers
+        .get(header::ACCEPT)
+        .and_then(|accept| accept.to_str().ok())
+        .map(Exposition::from_accept)
+        .unwrap_or_default();
+    ([(header::CONTENT_TYPE, format.content_type())], MetricsRegistry::global().render_as(format))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_prometheus_text() {
+        let registry = MetricsRegistry::default();
+        registry.describe(&USERS_CREATED);
+        registry.describe(&TRANSFER_VOLUME);
+        registry.adjust(&USERS_CREATED, &[], 2);
+        registry.adjust(&TRANSFER_VOLUME, &[("kind", "internal")], 1500);
+        registry.adjust(&TRANSFER_VOLUME, &[("kind", "internal")], 500);
+        registry.adjust(&VALIDATION_FAILURES, &[("operation", "create_user"), ("field", "na\"me")], 1);
+
+        assert_eq!(registry.value(&TRANSFER_VOLUME, &[("kind", "internal")]), Some(2000));
+        assert_eq!(registry.value(&TRANSFER_VOLUME, &[]), None, "Labelled metrics have no zero series");
+        assert_eq!(
+            registry.render(),
+            "# HELP transfer_volume_cents_total Money moved by completed transfers, in cents\n\
+             # TYPE transfer_volume_cents_total counter\n\
+             transfer_volume_cents_total{kind=\"internal\"} 2000\n\
+             # HELP users_created_total Users created\n\
+             # TYPE users_created_total counter\n\
+             users_created_total 2\n\
+             # HELP validation_failures_total Validation errors returned to callers\n\
+             # TYPE validation_failures_total counter\n\
+             validation_failures_total{field=\"na\\\"me\",operation=\"create_user\"} 1\n"
+        );
+    }
+
+    #[test]
+    fn test_gauges_go_both_ways() {
+        let registry = MetricsRegistry::default();
+        registry.adjust(&JOBS_RUNNING, &[("job", "retention")], 1);
+        registry.adjust(&JOBS_RUNNING, &[("job", "retention")], 1);
+        registry.adjust(&JOBS_RUNNING, &[("job", "retention")], -1);
+
+        assert_eq!(registry.value(&JOBS_RUNNING, &[("job", "retention")]), Some(1));
+        assert!(registry.render().contains("# TYPE jobs_running gauge\njobs_running{job=\"retention\"} 1\n"));
+    }
+
+    #[cfg(feature = "otel")]
+    #[test]
+    fn test_openmetrics_links_exemplar_traces() {
+        use opentelemetry::trace::TracerProvider as _;
+        use tracing_subscriber::layer::SubscriberExt;
+
+        let provider = opentelemetry_sdk::trace::TracerProvider::builder().build();
+        let subscriber =
+            tracing_subscriber::registry().with(tracing_opentelemetry::layer().with_tracer(provider.tracer("test")));
+        let registry = MetricsRegistry::default();
+
+        let trace_id = tracing::subscriber::with_default(subscriber, || {
+            tracing::info_span!("request").in_scope(|| {
+                registry.adjust(&HTTP_SERVER_ERRORS, &[], 1);
+                registry.adjust(&USERS_CREATED, &[], 1);
+                current_trace_id()
+            })
+        })
+        .expect("The span should be traced");
+        registry.adjust(&DB_FAILURES, &[("kind", "other")], 1);
+
+        assert_eq!(registry.exemplar(&HTTP_SERVER_ERRORS, &[]), Some(trace_id.clone()));
+        assert_eq!(registry.exemplar(&USERS_CREATED, &[]), None, "Only opted-in metrics keep exemplars");
+        assert_eq!(registry.exemplar(&DB_FAILURES, &[("kind", "other")]), None, "Untraced increments have none");
+        let open_metrics = registry.render_as(Exposition::OpenMetrics);
+        assert!(open_metrics.contains("# TYPE http_server_errors counter\n"));
+        assert!(open_metrics.contains(&format!("http_server_errors_total 1 # {{trace_id=\"{trace_id}\"}} 1 ")));
+        assert!(open_metrics.contains("db_failures_total{kind=\"other\"} 1\n"));
+        assert!(open_metrics.ends_with("# EOF\n"));
+        assert!(!registry.render().contains("trace_id"), "The Prometheus format has no exemplars");
+        assert_eq!(Exposition::from_accept("application/openmetrics-text;version=1.0.0,text/plain;q=0.5"), Exposition::OpenMetrics);
+        assert_eq!(Exposition::from_accept("*/*"), Exposition::Prometheus);
+    }
+}
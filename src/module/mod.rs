@@ -0,0 +1,283 @@
+//! Pluggable feature modules
+//!
+//! A [`Module`] bundles a vertical slice (orders, inventory, …) with its routes,
+//! `OpenAPI` paths and health checks. Modules are collected in a
+//! [`ModuleRegistry`] and mounted by `create_app_with_modules` (or
+//! `AppBuilder::module`), behind the same tracing, authentication and
+//! deprecation layers as the built-in routes, so forks add slices without
+//! editing `lib.rs`. `GET /admin/modules` reports what each registered module
+//! contributes ([`ModuleCatalog`]).
+
+use std::sync::Arc;
+
+use axum::Router;
+use serde::Serialize;
+use utoipa::{
+    ToSchema,
+    openapi::{OpenApi, OpenApiBuilder},
+};
+
+use crate::{AppState, ServiceRegistry};
+use crate::config::RouteEntry;
+use crate::health::SharedHealthCheck;
+
+/// A feature slice that can be mounted on the application router
+pub trait Module: Send + Sync {
+    /// Name used in logs
+    fn name(&self) -> &'static str;
+
+    /// Routes served by the module
+    fn routes(&self) -> Router<AppState>;
+
+    /// `OpenAPI` document merged into the served specification
+    fn openapi(&self) -> OpenApi {
+        OpenApiBuilder::new().build()
+    }
+
+    /// Components added to `/health` and `/ready`
+    fn health_checks(&self) -> Vec<SharedHealthCheck> {
+        Vec::new()
+    }
+
+    /// Adds the module's services, available to handlers through `Inject`
+    fn services(&self, _services: &mut ServiceRegistry) {}
+
+    /// Cargo features or configuration flags the module is gated on
+    fn features(&self) -> Vec<&'static str> {
+        Vec::new()
+    }
+
+    /// Slices or modules the module builds on (`user`, `bank`, another module's name)
+    fn depends_on(&self) -> Vec<&'static str> {
+        Vec::new()
+    }
+}
+
+/// Modules mounted on the running instance
+#[derive(Serialize, ToSchema, Debug, Clone, Default, PartialEq, Eq)]
+pub struct ModuleCatalog {
+    /// Registered modules, in mounting order
+    pub modules: Vec<ModuleInfo>,
+}
+
+/// What a registered module contributes to the application
+#[derive(Serialize, ToSchema, Debug, Clone, PartialEq, Eq)]
+pub struct ModuleInfo {
+    /// Module name
+    pub name: String,
+    /// Routes documented in the module's `OpenAPI` document, sorted by path then method
+    pub routes: Vec<RouteEntry>,
+    /// Names of the components the module adds to `/health` and `/ready`
+    pub health_checks: Vec<String>,
+    /// Cargo features or configuration flags the module is gated on
+    pub features: Vec<String>,
+    /// Slices or modules the module builds on
+    pub depends_on: Vec<String>,
+}
+
+/// Ordered collection of modules mounted on the application
+#[derive(Clone, Default)]
+pub struct ModuleRegistry {
+    modules: Vec<Arc<dyn Module>>,
+}
+
+impl ModuleRegistry {
+    /// Creates an empty registry
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `module` to the registry
+    #[must_use]
+    pub fn with(mut self, module: impl Module + 'static) -> Self {
+        self.register(module);
+        self
+    }
+
+    /// Adds `module` to the registry in place
+    pub fn register(&mut self, module: impl Module + 'static) {
+        self.modules.push(Arc::new(module));
+    }
+
+    /// Routes of every module merged into one router
+    pub fn routes(&self) -> Router<AppState> {
+        self.modules.iter().fold(Router::new(), |router, module| {
+            tracing::info!(module = module.name(), "Mounting module");
+            router.merge(module.routes())
+        })
+    }
+
+    /// Merges the `OpenAPI` documents of every module into `openapi`
+    pub fn apply_to_openapi(&self, openapi: &mut OpenApi) {
+        for module in &self.modules {
+            openapi.merge(module.openapi());
+        }
+    }
+
+    /// Adds the services of every module to `services`
+    pub fn register_services(&self, services: &mut ServiceRegistry) {
+        for module in &self.modules {
+            module.services(services);
+        }
+    }
+
+    /// Health checks of every module
+    #[must_use]
+    pub fn health_checks(&self) -> Vec<SharedHealthCheck> {
+        self.modules.iter().flat_map(|module| module.health_checks()).collect()
+    }
+
+    /// Describes every module, listing its documented routes with `route_table`
+    #[must_use]
+    pub fn describe(&self, route_table: impl Fn(&OpenApi) -> Vec<RouteEntry>) -> ModuleCatalog {
+        let owned = |names: Vec<&'static str>| names.into_iter().map(str::to_owned).collect();
+        ModuleCatalog {
+            modules: self
+                .modules
+                .iter()
+                .map(|module| {
+                    let mut routes = route_table(&module.openapi());
+                    routes.sort();
+                    ModuleInfo {
+                        name: module.name().to_owned(),
+                        routes,
+                        health_checks: module
+                            .health_checks()
+                            .iter()
+                            .map(|check| check.name().to_owned())
+                            .collect(),
+                        features: owned(module.features()),
+                        depends_on: owned(module.depends_on()),
+                    }
+                })
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::health::HealthCheck;
+    use crate::{AppProviders, create_app_with_modules};
+    use axum::{body::Body, http::Request, routing::get};
+    use futures_util::future::BoxFuture;
+    use http_body_util::BodyExt;
+    use sqlx::postgres::PgPoolOptions;
+    use tower::ServiceExt;
+    use utoipa::openapi::path::{HttpMethod, OperationBuilder, PathItem, PathsBuilder};
+
+    struct FailingQueue;
+
+    impl HealthCheck for FailingQueue {
+        fn name(&self) -> &'static str {
+            "queue"
+        }
+
+        fn check(&self) -> BoxFuture<'_, Result<(), String>> {
+            Box::pin(async { Err("queue unreachable".to_owned()) })
+        }
+    }
+
+    struct OrdersModule;
+
+    impl Module for OrdersModule {
+        fn name(&self) -> &'static str {
+            "orders"
+        }
+
+        fn routes(&self) -> Router<AppState> {
+            Router::new().route("/orders", get(|| async { "orders" }))
+        }
+
+        fn openapi(&self) -> OpenApi {
+            let path = PathItem::new(HttpMethod::Get, OperationBuilder::new().operation_id(Some("list_orders")));
+            OpenApiBuilder::new()
+                .paths(PathsBuilder::new().path("/orders", path))
+                .build()
+        }
+
+        fn health_checks(&self) -> Vec<SharedHealthCheck> {
+            vec![Arc::new(FailingQueue)]
+        }
+
+        fn features(&self) -> Vec<&'static str> {
+            vec!["orders"]
+        }
+
+        fn depends_on(&self) -> Vec<&'static str> {
+            vec!["user", "bank"]
+        }
+    }
+
+    async fn get_body(app: Router, uri: &str) -> (axum::http::StatusCode, String) {
+        let response = app
+            .oneshot(Request::get(uri).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let status = response.status();
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        (status, String::from_utf8(body.to_vec()).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_module_routes_spec_and_health_are_mounted() {
+        let pool = PgPoolOptions::new()
+            .acquire_timeout(std::time::Duration::from_millis(100))
+            .connect_lazy("postgres://modules@127.0.0.1:1/modules")
+            .unwrap();
+        let app = create_app_with_modules(pool, AppProviders::default(), &ModuleRegistry::new().with(OrdersModule));
+
+        let (status, body) = get_body(app.clone(), "/orders").await;
+        assert_eq!(status, 200);
+        assert_eq!(body, "orders");
+
+        let (_, spec) = get_body(app.clone(), "/api-docs/openapi.json").await;
+        let spec: serde_json::Value = serde_json::from_str(&spec).unwrap();
+        assert_eq!(spec["paths"]["/orders"]["get"]["operationId"], "list_orders");
+        assert!(spec["paths"]["/users"].is_object());
+
+        let (status, health) = get_body(app, "/health").await;
+        let health: serde_json::Value = serde_json::from_str(&health).unwrap();
+        assert_eq!(status, 503);
+        let queue = health["components"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|component| component["name"] == "queue")
+            .unwrap();
+        assert_eq!(queue["status"], "unhealthy");
+        assert_eq!(queue["message"], "queue unreachable");
+    }
+
+    #[test]
+    fn test_describe_reports_routes_checks_features_and_dependencies() {
+        let registry = ModuleRegistry::new().with(OrdersModule);
+
+        let catalog = registry.describe(|openapi| {
+            openapi
+                .paths
+                .paths
+                .keys()
+                .map(|path| RouteEntry { path: path.clone(), method: "GET".to_owned(), access: "public".to_owned() })
+                .collect()
+        });
+
+        assert_eq!(
+            catalog.modules,
+            [ModuleInfo {
+                name: "orders".to_owned(),
+                routes: vec![RouteEntry {
+                    path: "/orders".to_owned(),
+                    method: "GET".to_owned(),
+                    access: "public".to_owned(),
+                }],
+                health_checks: vec!["queue".to_owned()],
+                features: vec!["orders".to_owned()],
+                depends_on: vec!["user".to_owned(), "bank".to_owned()],
+            }]
+        );
+        assert!(ModuleRegistry::new().describe(|_| Vec::new()).modules.is_empty());
+    }
+}
@@ -0,0 +1,243 @@
+//! Client certificate identity
+//!
+//! The subject is read straight from the certificate's DER encoding; the
+//! handshake has already verified the chain, so only the `Name` structure is
+//! walked here.
+
+use axum::{
+    extract::{ConnectInfo, FromRequestParts},
+    http::{StatusCode, request::Parts},
+    response::Response,
+};
+
+use crate::error_codes::{ErrorCode, error_response};
+
+/// DER `SEQUENCE` tag
+const SEQUENCE: u8 = 0x30;
+/// DER `SET` tag
+const SET: u8 = 0x31;
+/// DER `OBJECT IDENTIFIER` tag
+const OID: u8 = 0x06;
+/// Tag of the explicit `[0]` version field of a certificate
+const VERSION: u8 = 0xa0;
+/// DER tags of the string types decoded as UTF-8 (`UTF8String`, `PrintableString`, `IA5String`)
+const UTF8_STRINGS: [u8; 3] = [0x0c, 0x13, 0x16];
+
+/// Short names of the usual attribute types, keyed by their encoded OID
+const ATTRIBUTE_NAMES: [(&[u8], &str); 8] = [
+    (&[0x55, 0x04, 0x03], "CN"),
+    (&[0x55, 0x04, 0x06], "C"),
+    (&[0x55, 0x04, 0x07], "L"),
+    (&[0x55, 0x04, 0x08], "ST"),
+    (&[0x55, 0x04, 0x0a], "O"),
+    (&[0x55, 0x04, 0x0b], "OU"),
+    (&[0x09, 0x92, 0x26, 0x89, 0x93, 0xf2, 0x2c, 0x64, 0x01, 0x01], "UID"),
+    (&[0x09, 0x92, 0x26, 0x89, 0x93, 0xf2, 0x2c, 0x64, 0x01, 0x19], "DC"),
+];
+
+/// Identity of the client that opened the connection, from its certificate
+///
+/// Extracting it outside an mTLS server (or in tests without
+/// `MockConnectInfo`) rejects the request with 401.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClientIdentity {
+    /// Certificate subject as an RFC 4514 string, e.g. `CN=billing,O=Acme`
+    pub subject: String,
+    /// Subject common name, when present
+    pub common_name: Option<String>,
+}
+
+impl ClientIdentity {
+    /// Identity of a DER-encoded certificate, or `None` when its subject cannot be read
+    #[must_use]
+    pub fn from_certificate(certificate: &[u8]) -> Option<Self> {
+        let (certificate, _) = expect(certificate, SEQUENCE)?;
+        let (tbs, _) = expect(certificate, SEQUENCE)?;
+        // Skip the optional version, then the serial, signature algorithm, issuer and validity
+        let rest = if tbs.first() == Some(&VERSION) { element(tbs)?.2 } else { tbs };
+        let rest = (0..4).try_fold(rest, |rest, _| element(rest).map(|(_, _, rest)| rest))?;
+        let (name, _) = expect(rest, SEQUENCE)?;
+
+        let mut rdns = Vec::new();
+        let mut common_name = None;
+        let mut remaining = name;
+        while !remaining.is_empty() {
+            let (set, rest) = expect(remaining, SET)?;
+            remaining = rest;
+            let mut attributes = Vec::new();
+            let mut set = set;
+            while !set.is_empty() {
+                let (attribute, rest) = expect(set, SEQUENCE)?;
+                set = rest;
+                let (oid, value) = expect(attribute, OID)?;
+                let (tag, contents, _) = element(value)?;
+                let text = UTF8_STRINGS
+                    .contains(&tag)
+                    .then(|| std::str::from_utf8(contents).ok())
+                    .flatten();
+                let name = attribute_name(oid);
+                if name == "CN" && common_name.is_none() {
+                    common_name = text.map(str::to_owned);
+                }
+                attributes.push(match text {
+                    Some(text) => format!("{name}={}", escape(text)),
+                    None => format!("{name}=#{}", hex(value)),
+                });
+            }
+            rdns.push(attributes.join("+"));
+        }
+        // RFC 4514 lists the most specific RDN first
+        rdns.reverse();
+
+        Some(Self {
+            subject: rdns.join(","),
+            common_name,
+        })
+    }
+}
+
+impl<S> FromRequestParts<S> for ClientIdentity
+where
+    S: Send + Sync,
+{
+    type Rejection = Response;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        ConnectInfo::<Self>::from_request_parts(parts, state)
+            .await
+            .map(|ConnectInfo(identity)| identity)
+            .map_err(|_rejection| {
+                error_response(StatusCode::UNAUTHORIZED, ErrorCode::Unauthenticated, "A client certificate is required")
+            })
+    }
+}
+
+/// Splits the first DER element off `input` into its tag, contents and the remaining input
+fn element(input: &[u8]) -> Option<(u8, &[u8], &[u8])> {
+    let (&tag, rest) = input.split_first()?;
+    let (&length, rest) = rest.split_first()?;
+    let (length, rest) = if length < 0x80 {
+        (usize::from(length), rest)
+    } else {
+        let octets = usize::from(length & 0x7f);
+        if octets == 0 || octets > 4 || rest.len() < octets {
+            return None;
+        }
+        let (octets, rest) = rest.split_at(octets);
+        (octets.iter().fold(0, |length, &octet| (length << 8) | usize::from(octet)), rest)
+    };
+    (rest.len() >= length).then(|| {
+        let (contents, rest) = rest.split_at(length);
+        (tag, contents, rest)
+    })
+}
+
+/// Contents of the first DER element of `input` and the remaining input, if it has tag `tag`
+fn expect(input: &[u8], tag: u8) -> Option<(&[u8], &[u8])> {
+    element(input).and_then(|(found, contents, rest)| (found == tag).then_some((contents, rest)))
+}
+
+/// Short name of an attribute type, or its dotted OID
+fn attribute_name(oid: &[u8]) -> String {
+    if let Some((_, name)) = ATTRIBUTE_NAMES.iter().find(|(known, _)| *known == oid) {
+        return (*name).to_owned();
+    }
+    let mut arcs = Vec::new();
+    let mut arc = 0_u64;
+    for &octet in oid {
+        arc = (arc << 7) | u64::from(octet & 0x7f);
+        if octet & 0x80 == 0 {
+            arcs.push(arc);
+            arc = 0;
+        }
+    }
+    let first = match arcs.first() {
+        Some(&first) if first >= 80 => format!("2.{}", first - 80),
+        Some(&first) => format!("{}.{}", first / 40, first % 40),
+        None => String::new(),
+    };
+    std::iter::once(first)
+        .chain(arcs.iter().skip(1).map(ToString::to_string))
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+/// Escapes an attribute value as RFC 4514 requires
+fn escape(value: &str) -> String {
+    let last = value.chars().count().saturating_sub(1);
+    let mut escaped = String::with_capacity(value.len());
+    for (index, character) in value.chars().enumerate() {
+        let special = matches!(character, '"' | '+' | ',' | ';' | '<' | '>' | '\\')
+            || (index == 0 && matches!(character, '#' | ' '))
+            || (index == last && character == ' ');
+        if special {
+            escaped.push('\\');
+        }
+        escaped.push(character);
+    }
+    escaped
+}
+
+/// Lowercase hex of `bytes`
+fn hex(bytes: &[u8]) -> String {
+    const DIGITS: &[u8; 16] = b"0123456789abcdef";
+    bytes
+        .iter()
+        .flat_map(|byte| [DIGITS[usize::from(byte >> 4)], DIGITS[usize::from(byte & 0x0f)]])
+        .map(char::from)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{Router, body::Body, extract::connect_info::MockConnectInfo, http::Request, routing::get};
+    use tokio_rustls::rustls::pki_types::{CertificateDer, pem::PemObject};
+    use tower::ServiceExt;
+
+    fn fixture(pem: &str) -> CertificateDer<'static> {
+        CertificateDer::from_pem_slice(pem.as_bytes()).unwrap()
+    }
+
+    #[test]
+    fn test_identity_is_read_from_the_certificate_subject() {
+        let client = fixture(include_str!("../../tests/fixtures/mtls/client.pem"));
+        let server = fixture(include_str!("../../tests/fixtures/mtls/server.pem"));
+
+        assert_eq!(
+            ClientIdentity::from_certificate(&client),
+            Some(ClientIdentity {
+                subject: "CN=billing-service,OU=Payments,O=Acme".to_owned(),
+                common_name: Some("billing-service".to_owned()),
+            })
+        );
+        assert_eq!(ClientIdentity::from_certificate(&server).map(|identity| identity.subject), Some("CN=localhost".to_owned()));
+        assert_eq!(ClientIdentity::from_certificate(&client[..client.len() / 2]), None);
+        assert_eq!(ClientIdentity::from_certificate(b""), None);
+    }
+
+    #[test]
+    fn test_subject_formatting() {
+        assert_eq!(escape("Acme, Inc. "), "Acme\\, Inc.\\ ");
+        assert_eq!(escape("#1 <ops>"), "\\#1 \\<ops\\>");
+        assert_eq!(attribute_name(&[0x55, 0x04, 0x0a]), "O");
+        assert_eq!(attribute_name(&[0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x09, 0x01]), "1.2.840.113549.1.9.1");
+        assert_eq!(hex(&[0x0c, 0xff]), "0cff");
+    }
+
+    #[tokio::test]
+    async fn test_extractor_requires_a_client_certificate() {
+        let app = Router::new().route("/", get(|identity: ClientIdentity| async move { identity.subject }));
+        let identity = ClientIdentity {
+            subject: "CN=billing".to_owned(),
+            common_name: Some("billing".to_owned()),
+        };
+        let request = || Request::get("/").body(Body::empty()).unwrap();
+
+        let without = app.clone().oneshot(request()).await.unwrap();
+        let with = app.layer(MockConnectInfo(identity)).oneshot(request()).await.unwrap();
+
+        assert_eq!(without.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(with.status(), StatusCode::OK);
+    }
+}
@@ -0,0 +1,251 @@
+//! TLS listener requiring client certificates
+
+use std::{
+    io,
+    net::SocketAddr,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use axum::serve::{IncomingStream, Listener};
+use axum::extract::{ConnectInfo, Request, connect_info::Connected};
+use axum::middleware::Next;
+use axum::response::Response;
+use tokio::{
+    io::{AsyncRead, AsyncWrite, ReadBuf},
+    net::{TcpListener, TcpStream},
+    sync::mpsc,
+};
+use tokio_rustls::{
+    TlsAcceptor,
+    rustls::{
+        self, RootCertStore, ServerConfig,
+        crypto::ring,
+        pki_types::{CertificateDer, PrivateKeyDer, pem::PemObject},
+        server::{VerifierBuilderError, WebPkiClientVerifier},
+    },
+    server::TlsStream,
+};
+use tracing::warn;
+
+use crate::config::TlsConfig;
+
+use super::identity::ClientIdentity;
+
+/// How long a client gets to complete the TLS handshake
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+/// Handshaken connections waiting for the server to pick them up
+const PENDING_CONNECTIONS: usize = 64;
+
+/// Why the TLS configuration could not be loaded
+#[derive(Debug, thiserror::Error)]
+pub enum MtlsError {
+    /// Only some of the TLS settings are set
+    #[error("TLS_CERT_PATH, TLS_KEY_PATH and TLS_CLIENT_CA_PATH must all be set")]
+    Incomplete,
+    /// A PEM file could not be read
+    #[error("Failed to read {path}: {source}")]
+    Pem {
+        /// Path of the file
+        path: String,
+        /// Underlying error
+        source: rustls::pki_types::pem::Error,
+    },
+    /// A certificate file holds no certificate
+    #[error("{0} holds no certificate")]
+    NoCertificate(String),
+    /// The probe's client certificate is not configured
+    #[error("TLS_PROBE_CERT_PATH and TLS_PROBE_KEY_PATH must be set to probe a mutual TLS server")]
+    NoProbeIdentity,
+    /// The client CA file holds no usable certificate
+    #[error("Invalid client CA: {0}")]
+    ClientCa(#[from] VerifierBuilderError),
+    /// The certificate chain and key were rejected
+    #[error("Invalid TLS configuration: {0}")]
+    Tls(#[from] rustls::Error),
+}
+
+/// Reads PEM file `path` with `read`, naming the file in errors
+pub(super) fn read_pem<T>(path: &str, read: impl FnOnce(&str) -> Result<T, rustls::pki_types::pem::Error>) -> Result<T, MtlsError> {
+    read(path).map_err(|source| MtlsError::Pem {
+        path: path.to_owned(),
+        source,
+    })
+}
+
+/// Server TLS configuration requiring client certificates issued by the configured CA
+///
+/// # Errors
+///
+/// Returns an error when a setting is missing or a file cannot be read or used.
+pub fn server_config(config: &TlsConfig) -> Result<Arc<ServerConfig>, MtlsError> {
+    let (Some(cert_path), Some(key_path), Some(client_ca_path)) =
+        (&config.cert_path, &config.key_path, &config.client_ca_path)
+    else {
+        return Err(MtlsError::Incomplete);
+    };
+
+    let chain = read_pem(cert_path, |path| CertificateDer::pem_file_iter(path)?.collect::<Result<Vec<_>, _>>())?;
+    let key = read_pem(key_path, |path| PrivateKeyDer::from_pem_file(path))?;
+    let mut roots = RootCertStore::empty();
+    for ca in read_pem(client_ca_path, |path| CertificateDer::pem_file_iter(path)?.collect::<Result<Vec<_>, _>>())? {
+        roots.add(ca)?;
+    }
+
+    // Explicit provider: other dependencies may enable a second one
+    let provider = Arc::new(ring::default_provider());
+    let verifier = WebPkiClientVerifier::builder_with_provider(Arc::new(roots), Arc::clone(&provider)).build()?;
+    let mut server = ServerConfig::builder_with_provider(provider)
+        .with_safe_default_protocol_versions()?
+        .with_client_cert_verifier(verifier)
+        .with_single_cert(chain, key)?;
+    server.alpn_protocols = vec![b"http/1.1".to_vec()];
+    Ok(Arc::new(server))
+}
+
+/// Listener serving only clients that complete a mutual TLS handshake
+///
+/// Handshakes run concurrently in the background, so a slow client does not
+/// hold up the others.
+#[derive(Debug)]
+pub struct MtlsListener {
+    incoming: mpsc::Receiver<(MtlsStream, SocketAddr)>,
+    local_addr: SocketAddr,
+}
+
+impl MtlsListener {
+    /// Accepts connections on `listener`, completing the handshake with `config`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the listener's local address cannot be read.
+    pub fn new(listener: TcpListener, config: Arc<ServerConfig>) -> io::Result<Self> {
+        let local_addr = listener.local_addr()?;
+        let (sender, incoming) = mpsc::channel(PENDING_CONNECTIONS);
+        tokio::spawn(accept_connections(listener, TlsAcceptor::from(config), sender));
+        Ok(Self { incoming, local_addr })
+    }
+}
+
+impl Listener for MtlsListener {
+    type Io = MtlsStream;
+    type Addr = SocketAddr;
+
+    async fn accept(&mut self) -> (Self::Io, Self::Addr) {
+        match self.incoming.recv().await {
+            Some(connection) => connection,
+            None => std::future::pending().await,
+        }
+    }
+
+    fn local_addr(&self) -> io::Result<Self::Addr> {
+        Ok(self.local_addr)
+    }
+}
+
+/// Accepts TCP connections and hands over those completing the handshake, until the [`MtlsListener`] is dropped
+async fn accept_connections(mut listener: TcpListener, acceptor: TlsAcceptor, sender: mpsc::Sender<(MtlsStream, SocketAddr)>) {
+    loop {
+        let (stream, addr) = tokio::select! {
+            accepted = Listener::accept(&mut listener) => accepted,
+            () = sender.closed() => return,
+        };
+        let (acceptor, sender) = (acceptor.clone(), sender.clone());
+        tokio::spawn(async move {
+            let stream = match tokio::time::timeout(HANDSHAKE_TIMEOUT, acceptor.accept(stream)).await {
+                Ok(Ok(stream)) => stream,
+                Ok(Err(e)) => {
+                    warn!(%addr, error = %e, "Rejected TLS connection");
+                    return;
+                }
+                Err(_elapsed) => {
+                    warn!(%addr, "TLS handshake timed out");
+                    return;
+                }
+            };
+            let identity = stream
+                .get_ref()
+                .1
+                .peer_certificates()
+                .and_then(<[CertificateDer<'_>]>::first)
+                .and_then(|certificate| ClientIdentity::from_certificate(certificate));
+            let Some(identity) = identity else {
+                warn!(%addr, "Rejected TLS connection: unreadable client certificate subject");
+                return;
+            };
+            sender.send((MtlsStream { inner: stream, identity }, addr)).await.ok();
+        });
+    }
+}
+
+/// Connection of an authenticated client
+#[derive(Debug)]
+pub struct MtlsStream {
+    inner: TlsStream<TcpStream>,
+    identity: ClientIdentity,
+}
+
+impl MtlsStream {
+    /// Identity of the client certificate
+    #[must_use]
+    pub const fn identity(&self) -> &ClientIdentity {
+        &self.identity
+    }
+}
+
+impl AsyncRead for MtlsStream {
+    fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().inner).poll_read(cx, buf)
+    }
+}
+
+impl AsyncWrite for MtlsStream {
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.get_mut().inner).poll_write(cx, buf)
+    }
+
+    fn poll_write_vectored(self: Pin<&mut Self>, cx: &mut Context<'_>, bufs: &[io::IoSlice<'_>]) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.get_mut().inner).poll_write_vectored(cx, bufs)
+    }
+
+    fn is_write_vectored(&self) -> bool {
+        self.inner.is_write_vectored()
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().inner).poll_shutdown(cx)
+    }
+}
+
+/// Peer of an mTLS connection, the connect info of [`App::serve_mtls`](crate::App::serve_mtls)
+#[derive(Debug, Clone)]
+pub struct MtlsPeer {
+    /// Address of the peer
+    pub addr: SocketAddr,
+    /// Identity of its client certificate
+    pub identity: ClientIdentity,
+}
+
+impl Connected<IncomingStream<'_, MtlsListener>> for MtlsPeer {
+    fn connect_info(stream: IncomingStream<'_, MtlsListener>) -> Self {
+        Self {
+            addr: *stream.remote_addr(),
+            identity: stream.io().identity.clone(),
+        }
+    }
+}
+
+/// Middleware exposing the [`MtlsPeer`] as `ConnectInfo<SocketAddr>` and `ConnectInfo<ClientIdentity>`
+///
+/// So handlers and middleware read the peer the same way with or without mTLS.
+pub async fn expose_peer(ConnectInfo(peer): ConnectInfo<MtlsPeer>, mut request: Request, next: Next) -> Response {
+    request.extensions_mut().insert(ConnectInfo(peer.addr));
+    request.extensions_mut().insert(ConnectInfo(peer.identity));
+    next.run(request).await
+}
@@ -0,0 +1,19 @@
+//! Mutual TLS (built with the `mtls` feature)
+//!
+//! For zero-trust internal deployments where services authenticate with
+//! client certificates instead of JWTs. [`MtlsListener`] terminates TLS,
+//! requires every client to present a certificate chaining to the configured
+//! CA, and drops connections that fail the handshake. Handlers read the
+//! certificate subject through the [`ClientIdentity`] extractor.
+//!
+//! Served when `TLS_CERT_PATH`, `TLS_KEY_PATH` and `TLS_CLIENT_CA_PATH` are
+//! all set; see [`server_config`]. The `healthcheck` subcommand then probes
+//! with the client certificate in `TLS_PROBE_CERT_PATH`; see [`probe_client_config`].
+
+mod identity;
+mod listener;
+mod probe;
+
+pub use identity::ClientIdentity;
+pub use listener::{MtlsError, MtlsListener, MtlsPeer, MtlsStream, expose_peer, server_config};
+pub use probe::probe_client_config;
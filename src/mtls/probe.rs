@@ -0,0 +1,100 @@
+//! TLS client of the `healthcheck` probe
+
+use std::sync::Arc;
+
+use tokio_rustls::rustls::{
+    self, CertificateError, ClientConfig, DigitallySignedStruct, SignatureScheme,
+    client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier},
+    crypto::{CryptoProvider, ring, verify_tls12_signature, verify_tls13_signature},
+    pki_types::{CertificateDer, PrivateKeyDer, ServerName, UnixTime, pem::PemObject},
+};
+
+use crate::config::TlsConfig;
+
+use super::listener::{MtlsError, read_pem};
+
+/// Accepts only the server's own certificate
+///
+/// The probe dials the configured host, usually loopback, which the server
+/// certificate does not name, so it pins the certificate in `TLS_CERT_PATH`
+/// instead of checking a chain and host name.
+#[derive(Debug)]
+struct PinnedServer {
+    certificate: CertificateDer<'static>,
+    provider: Arc<CryptoProvider>,
+}
+
+impl ServerCertVerifier for PinnedServer {
+    fn verify_server_cert(
+        &self,
+        end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        if end_entity.as_ref() == self.certificate.as_ref() {
+            Ok(ServerCertVerified::assertion())
+        } else {
+            Err(rustls::Error::InvalidCertificate(CertificateError::UnknownIssuer))
+        }
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        verify_tls12_signature(message, cert, dss, &self.provider.signature_verification_algorithms)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        verify_tls13_signature(message, cert, dss, &self.provider.signature_verification_algorithms)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.provider.signature_verification_algorithms.supported_schemes()
+    }
+}
+
+/// Client TLS configuration of the `healthcheck` probe
+///
+/// Presents the certificate in `TLS_PROBE_CERT_PATH`, which must be issued by
+/// the CA in `TLS_CLIENT_CA_PATH`, and only trusts the server certificate in
+/// `TLS_CERT_PATH`.
+///
+/// # Errors
+///
+/// Returns an error when a setting is missing or a file cannot be read or used.
+pub fn probe_client_config(config: &TlsConfig) -> Result<ClientConfig, MtlsError> {
+    let Some(cert_path) = &config.cert_path else {
+        return Err(MtlsError::Incomplete);
+    };
+    let (Some(probe_cert_path), Some(probe_key_path)) = (&config.probe_cert_path, &config.probe_key_path) else {
+        return Err(MtlsError::NoProbeIdentity);
+    };
+
+    let read_chain = |path: &str| read_pem(path, |path| CertificateDer::pem_file_iter(path)?.collect::<Result<Vec<_>, _>>());
+    let certificate = read_chain(cert_path)?
+        .into_iter()
+        .next()
+        .ok_or_else(|| MtlsError::NoCertificate(cert_path.clone()))?;
+    let chain = read_chain(probe_cert_path)?;
+    let key = read_pem(probe_key_path, |path| PrivateKeyDer::from_pem_file(path))?;
+
+    let provider = Arc::new(ring::default_provider());
+    let verifier = PinnedServer { certificate, provider: Arc::clone(&provider) };
+    let mut client = ClientConfig::builder_with_provider(provider)
+        .with_safe_default_protocol_versions()?
+        .dangerous()
+        .with_custom_certificate_verifier(Arc::new(verifier))
+        .with_client_auth_cert(chain, key)?;
+    client.alpn_protocols = vec![b"http/1.1".to_vec()];
+    Ok(client)
+}
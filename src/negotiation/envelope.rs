@@ -0,0 +1,161 @@
+//! Optional response envelope
+//!
+//! When enabled (`RESPONSE_ENVELOPE=true` or `?envelope=true` per request),
+//! bodies are wrapped as `{ data, meta: { request_id, duration_ms }, errors }`.
+//! `?envelope=false` opts a single request out when the server default is on.
+
+use std::time::Instant;
+
+use axum::http::{HeaderMap, request::Parts};
+use serde::Serialize;
+
+use crate::ids::IdGenerator;
+
+/// Header carrying a caller-supplied request identifier
+const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// Error payloads that can be listed under `errors` in an envelope
+pub trait ErrorBody: Serialize {
+    /// Individual error entry
+    type Item: Serialize;
+
+    /// Errors to list in the envelope
+    fn items(&self) -> &[Self::Item];
+}
+
+/// Per-request data needed to fill in the envelope metadata
+#[derive(Debug, Clone)]
+pub struct EnvelopeContext {
+    request_id: String,
+    started_at: Instant,
+}
+
+impl EnvelopeContext {
+    /// Starts timing a request, reusing its `x-request-id` when present
+    /// and generating one with `ids` otherwise
+    #[must_use]
+    pub fn from_headers(headers: &HeaderMap, ids: &dyn IdGenerator) -> Self {
+        let request_id = headers
+            .get(REQUEST_ID_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .filter(|value| !value.is_empty())
+            .map_or_else(|| ids.next_uuid().to_string(), ToOwned::to_owned);
+
+        Self {
+            request_id,
+            started_at: Instant::now(),
+        }
+    }
+
+    /// Snapshot of the metadata at the time the response is built
+    fn meta(&self) -> EnvelopeMeta {
+        EnvelopeMeta {
+            request_id: self.request_id.clone(),
+            duration_ms: u64::try_from(self.started_at.elapsed().as_millis()).unwrap_or(u64::MAX),
+        }
+    }
+}
+
+/// Resolves whether a request wants an envelope
+///
+/// The `envelope` query parameter wins over the server default.
+#[must_use]
+pub fn envelope_requested(parts: &Parts, default: bool) -> bool {
+    parts
+        .uri
+        .query()
+        .into_iter()
+        .flat_map(|query| query.split('&'))
+        .filter_map(|pair| pair.strip_prefix("envelope="))
+        .find_map(|value| match value {
+            "true" | "1" => Some(true),
+            "false" | "0" => Some(false),
+            _ => None,
+        })
+        .unwrap_or(default)
+}
+
+/// Envelope metadata
+#[derive(Serialize, Debug, Clone)]
+struct EnvelopeMeta {
+    request_id: String,
+    duration_ms: u64,
+}
+
+/// Wire format of an enveloped response
+#[derive(Serialize)]
+struct Envelope<'a, T, E> {
+    data: Option<&'a T>,
+    meta: EnvelopeMeta,
+    errors: &'a [E],
+}
+
+/// Wraps a successful body
+pub(super) fn wrap_data<'a, T: Serialize>(
+    context: &EnvelopeContext,
+    body: &'a T,
+) -> impl Serialize + 'a {
+    Envelope::<T, ()> {
+        data: Some(body),
+        meta: context.meta(),
+        errors: &[],
+    }
+}
+
+/// Wraps an error body
+pub(super) fn wrap_errors<B: ErrorBody>(context: &EnvelopeContext, body: &B) -> impl Serialize {
+    Envelope::<(), B::Item> {
+        data: None,
+        meta: context.meta(),
+        errors: body.items(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::Request;
+    use crate::ids::{DefaultIdGenerator, SequentialIdGenerator};
+
+    fn parts(uri: &str) -> Parts {
+        Request::builder()
+            .uri(uri)
+            .body(())
+            .expect("Failed to build request")
+            .into_parts()
+            .0
+    }
+
+    #[test]
+    fn test_envelope_requested() {
+        assert!(envelope_requested(&parts("/users?envelope=true"), false));
+        assert!(envelope_requested(&parts("/users?limit=5&envelope=1"), false));
+        assert!(!envelope_requested(&parts("/users?envelope=false"), true));
+        assert!(!envelope_requested(&parts("/users"), false));
+        assert!(envelope_requested(&parts("/users"), true), "Server default applies without a parameter");
+    }
+
+    #[test]
+    fn test_request_id_from_header() {
+        let mut headers = HeaderMap::new();
+        headers.insert(REQUEST_ID_HEADER, "abc-123".parse().expect("Valid header value"));
+
+        assert_eq!(EnvelopeContext::from_headers(&headers, &DefaultIdGenerator).request_id, "abc-123");
+        assert_eq!(
+            EnvelopeContext::from_headers(&HeaderMap::new(), &SequentialIdGenerator::new()).request_id,
+            "00000000-0000-0000-0000-000000000001"
+        );
+    }
+
+    #[test]
+    fn test_wrap_data() {
+        let context = EnvelopeContext::from_headers(&HeaderMap::new(), &DefaultIdGenerator);
+        let value = serde_json::to_value(wrap_data(&context, &serde_json::json!({ "id": 1 })))
+            .expect("Envelope should serialize");
+
+        assert_eq!(value["data"]["id"], 1);
+        assert_eq!(value["errors"], serde_json::json!([]));
+        assert_eq!(value["meta"]["request_id"], context.request_id);
+        assert!(value["meta"]["duration_ms"].is_u64());
+    }
+}
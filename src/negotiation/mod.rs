@@ -0,0 +1,352 @@
+//! Content negotiation
+//!
+//! Picks the response representation from the request's `Accept` header.
+//! JSON stays the default; XML is offered for legacy consumers that cannot
+//! speak JSON, and `MessagePack` / `CBOR` for high-volume machine-to-machine
+//! callers that want smaller payloads. Handlers extract a [`ResponseFormat`]
+//! and wrap their bodies in [`Negotiate`] instead of `Json`; request bodies in
+//! any of the binary formats are read with the [`Payload`] extractor.
+//! Responses can optionally be wrapped in an envelope (`{ data, meta, errors }`).
+
+mod envelope;
+mod payload;
+
+pub use envelope::{EnvelopeContext, ErrorBody, envelope_requested};
+pub use payload::Payload;
+
+use std::convert::Infallible;
+
+use axum::{
+    extract::FromRequestParts,
+    http::{HeaderMap, HeaderValue, header, request::Parts},
+    response::{IntoResponse, Response},
+};
+use serde::Serialize;
+use tracing::error;
+
+use crate::error_codes::internal_error;
+
+use envelope::{wrap_data, wrap_errors};
+
+/// Declaration prepended to every XML document
+const XML_DECLARATION: &str = r#"<?xml version="1.0" encoding="UTF-8"?>"#;
+
+/// Representation a response body is serialized to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ResponseFormat {
+    /// `application/json` (default)
+    #[default]
+    Json,
+    /// `application/xml`
+    Xml,
+    /// `application/msgpack`
+    MessagePack,
+    /// `application/cbor`
+    Cbor,
+}
+
+impl ResponseFormat {
+    /// Every supported format
+    pub const ALL: [Self; 4] = [Self::Json, Self::Xml, Self::MessagePack, Self::Cbor];
+
+    /// Content type sent with responses in this format
+    #[must_use]
+    pub const fn content_type(self) -> &'static str {
+        match self {
+            Self::Json => "application/json",
+            Self::Xml => "application/xml",
+            Self::MessagePack => "application/msgpack",
+            Self::Cbor => "application/cbor",
+        }
+    }
+
+    /// Maps a single media type to a format, if supported
+    pub(crate) fn from_media_type(media_type: &str) -> Option<Self> {
+        match media_type {
+            "application/json" | "application/*" | "*/*" => Some(Self::Json),
+            "application/xml" | "text/xml" => Some(Self::Xml),
+            "application/msgpack" | "application/x-msgpack" => Some(Self::MessagePack),
+            "application/cbor" => Some(Self::Cbor),
+            _ => None,
+        }
+    }
+
+    /// Chooses the format from an `Accept` header value
+    ///
+    /// The supported media type with the highest quality wins; ties go to the
+    /// one listed first. Falls back to JSON when nothing supported is listed.
+    #[must_use]
+    pub fn from_accept(accept: &str) -> Self {
+        let mut best: Option<(Self, f32)> = None;
+
+        for entry in accept.split(',') {
+            let mut parts = entry.split(';').map(str::trim);
+            let media_type = parts.next().unwrap_or_default().to_ascii_lowercase();
+            let quality = parts
+                .find_map(|param| param.strip_prefix("q="))
+                .and_then(|q| q.parse::<f32>().ok())
+                .unwrap_or(1.0);
+
+            let Some(format) = Self::from_media_type(&media_type) else {
+                continue;
+            };
+            if quality > 0.0 && best.is_none_or(|(_, best_quality)| quality > best_quality) {
+                best = Some((format, quality));
+            }
+        }
+
+        best.map(|(format, _)| format).unwrap_or_default()
+    }
+
+    /// Chooses the format from the request headers
+    #[must_use]
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        headers
+            .get(header::ACCEPT)
+            .and_then(|value| value.to_str().ok())
+            .map(Self::from_accept)
+            .unwrap_or_default()
+    }
+}
+
+impl<S> FromRequestParts<S> for ResponseFormat
+where
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(Self::from_headers(&parts.headers))
+    }
+}
+
+/// Everything a handler needs to shape its response: the negotiated format and,
+/// when requested, the envelope context
+#[derive(Debug, Clone, Default)]
+pub struct ResponseContext {
+    format: ResponseFormat,
+    envelope: Option<EnvelopeContext>,
+}
+
+impl ResponseContext {
+    /// Negotiated response format
+    #[must_use]
+    pub const fn format(&self) -> ResponseFormat {
+        self.format
+    }
+
+    /// Whether responses are wrapped in an envelope
+    #[must_use]
+    pub const fn is_enveloped(&self) -> bool {
+        self.envelope.is_some()
+    }
+}
+
+impl From<ResponseFormat> for ResponseContext {
+    fn from(format: ResponseFormat) -> Self {
+        Self { format, envelope: None }
+    }
+}
+
+impl FromRequestParts<crate::AppState> for ResponseContext {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, state: &crate::AppState) -> Result<Self, Self::Rejection> {
+        let envelope = envelope_requested(parts, state.envelope_by_default).then(|| {
+            let ids = state.services.get::<crate::SharedIdGenerator>();
+            EnvelopeContext::from_headers(&parts.headers, ids.map_or(&crate::DefaultIdGenerator, |ids| &**ids))
+        });
+
+        Ok(Self {
+            format: ResponseFormat::from_headers(&parts.headers),
+            envelope,
+        })
+    }
+}
+
+/// Serializes a body in the given format
+fn serialize_body<T: Serialize>(format: ResponseFormat, body: &T) -> Response {
+    let serialized = match format {
+        ResponseFormat::Json => serde_json::to_vec(body).map_err(|e| e.to_string()),
+        ResponseFormat::Xml => quick_xml::se::to_string(body)
+            .map(|xml| format!("{XML_DECLARATION}{xml}").into_bytes())
+            .map_err(|e| e.to_string()),
+        // Named encoding keeps field names, so payloads mirror the JSON shape
+        ResponseFormat::MessagePack => rmp_serde::to_vec_named(body).map_err(|e| e.to_string()),
+        ResponseFormat::Cbor => {
+            let mut bytes = Vec::new();
+            ciborium::into_writer(body, &mut bytes)
+                .map(|()| bytes)
+                .map_err(|e| e.to_string())
+        }
+    };
+
+    match serialized {
+        Ok(bytes) => (
+            [(header::CONTENT_TYPE, HeaderValue::from_static(format.content_type()))],
+            bytes,
+        )
+            .into_response(),
+        Err(e) => {
+            error!(error = %e, format = ?format, "Failed to serialize response body");
+            internal_error()
+        }
+    }
+}
+
+/// Responder serializing its body in the negotiated format
+///
+/// XML documents use the type name as the root element, so the body must be a
+/// struct (or enum), not a bare sequence.
+#[derive(Debug, Clone)]
+pub struct Negotiate<T> {
+    context: ResponseContext,
+    body: T,
+}
+
+impl<T> Negotiate<T> {
+    /// Wraps a response body to be serialized according to `context`
+    pub fn new(context: impl Into<ResponseContext>, body: T) -> Self {
+        Self { context: context.into(), body }
+    }
+}
+
+impl<T: Serialize> IntoResponse for Negotiate<T> {
+    fn into_response(self) -> Response {
+        match &self.context.envelope {
+            Some(envelope) => serialize_body(self.context.format, &wrap_data(envelope, &self.body)),
+            None => serialize_body(self.context.format, &self.body),
+        }
+    }
+}
+
+/// Responder for error bodies; in envelope mode they are listed under `errors`
+#[derive(Debug, Clone)]
+pub struct NegotiateError<B> {
+    context: ResponseContext,
+    body: B,
+}
+
+impl<B> NegotiateError<B> {
+    /// Wraps an error body to be serialized according to `context`
+    pub fn new(context: impl Into<ResponseContext>, body: B) -> Self {
+        Self { context: context.into(), body }
+    }
+}
+
+impl<B: ErrorBody> IntoResponse for NegotiateError<B> {
+    fn into_response(self) -> Response {
+        match &self.context.envelope {
+            Some(envelope) => serialize_body(self.context.format, &wrap_errors(envelope, &self.body)),
+            None => serialize_body(self.context.format, &self.body),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Serialize)]
+    struct Sample {
+        id: i32,
+        name: String,
+    }
+
+    #[test]
+    fn test_from_accept_defaults_to_json() {
+        assert_eq!(ResponseFormat::from_accept(""), ResponseFormat::Json);
+        assert_eq!(ResponseFormat::from_accept("*/*"), ResponseFormat::Json);
+        assert_eq!(ResponseFormat::from_accept("text/html"), ResponseFormat::Json);
+    }
+
+    #[test]
+    fn test_from_accept_xml() {
+        assert_eq!(ResponseFormat::from_accept("application/xml"), ResponseFormat::Xml);
+        assert_eq!(ResponseFormat::from_accept("text/xml"), ResponseFormat::Xml);
+        assert_eq!(ResponseFormat::from_accept("Application/XML"), ResponseFormat::Xml);
+    }
+
+    #[test]
+    fn test_from_accept_binary() {
+        assert_eq!(ResponseFormat::from_accept("application/msgpack"), ResponseFormat::MessagePack);
+        assert_eq!(ResponseFormat::from_accept("application/x-msgpack"), ResponseFormat::MessagePack);
+        assert_eq!(ResponseFormat::from_accept("application/cbor"), ResponseFormat::Cbor);
+    }
+
+    #[test]
+    fn test_from_accept_quality() {
+        assert_eq!(
+            ResponseFormat::from_accept("application/json;q=0.5, application/xml"),
+            ResponseFormat::Xml
+        );
+        assert_eq!(
+            ResponseFormat::from_accept("application/xml;q=0.8, application/json;q=0.9"),
+            ResponseFormat::Json
+        );
+        assert_eq!(
+            ResponseFormat::from_accept("application/xml, application/json"),
+            ResponseFormat::Xml,
+            "Ties should go to the first listed type"
+        );
+        assert_eq!(
+            ResponseFormat::from_accept("application/xml;q=0"),
+            ResponseFormat::Json,
+            "q=0 means not acceptable"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_negotiate_serializes_xml() {
+        let response = Negotiate::new(
+            ResponseFormat::Xml,
+            Sample { id: 1, name: "Ann Lee".to_owned() },
+        )
+        .into_response();
+
+        assert_eq!(response.headers()[header::CONTENT_TYPE], "application/xml");
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("Failed to read body");
+        assert_eq!(
+            std::str::from_utf8(&body).expect("Body should be UTF-8"),
+            r#"<?xml version="1.0" encoding="UTF-8"?><Sample><id>1</id><name>Ann Lee</name></Sample>"#
+        );
+    }
+
+    #[tokio::test]
+    async fn test_negotiate_serializes_json() {
+        let response = Negotiate::new(
+            ResponseFormat::Json,
+            Sample { id: 1, name: "Ann Lee".to_owned() },
+        )
+        .into_response();
+
+        assert_eq!(response.headers()[header::CONTENT_TYPE], "application/json");
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("Failed to read body");
+        assert_eq!(&body[..], br#"{"id":1,"name":"Ann Lee"}"#);
+    }
+
+    #[tokio::test]
+    async fn test_negotiate_serializes_binary_formats() {
+        let sample = Sample { id: 1, name: "Ann Lee".to_owned() };
+
+        let response = Negotiate::new(ResponseFormat::MessagePack, &sample).into_response();
+        assert_eq!(response.headers()[header::CONTENT_TYPE], "application/msgpack");
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("Failed to read body");
+        let decoded: serde_json::Value = rmp_serde::from_slice(&body).expect("Body should be MessagePack");
+        assert_eq!(decoded, serde_json::json!({ "id": 1, "name": "Ann Lee" }));
+
+        let response = Negotiate::new(ResponseFormat::Cbor, &sample).into_response();
+        assert_eq!(response.headers()[header::CONTENT_TYPE], "application/cbor");
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("Failed to read body");
+        let decoded: serde_json::Value = ciborium::from_reader(&body[..]).expect("Body should be CBOR");
+        assert_eq!(decoded, serde_json::json!({ "id": 1, "name": "Ann Lee" }));
+    }
+}
@@ -0,0 +1,156 @@
+//! Request body extractor accepting JSON, `MessagePack` or `CBOR`
+
+use axum::{
+    Json,
+    body::Bytes,
+    extract::{FromRequest, Request, rejection::JsonRejection},
+    http::{StatusCode, header},
+    response::{IntoResponse, Response},
+};
+use serde::de::DeserializeOwned;
+use tracing::warn;
+
+use crate::error_codes::{ErrorCode, error_response};
+
+use super::ResponseFormat;
+
+/// Request body decoded according to its `Content-Type`
+///
+/// `application/msgpack` and `application/cbor` bodies are decoded directly;
+/// everything else goes through axum's `Json` extractor, so JSON requests keep
+/// their existing behavior and rejections.
+#[derive(Debug, Clone)]
+pub struct Payload<T>(pub T);
+
+/// Rejects a binary body that could not be decoded
+fn decode_rejection(format: ResponseFormat, error: &impl std::fmt::Display) -> Response {
+    warn!(error = %error, format = ?format, "Failed to decode request body");
+    error_response(
+        StatusCode::BAD_REQUEST,
+        ErrorCode::MalformedBody,
+        format!("Failed to decode the {} body: {error}", format.content_type()),
+    )
+}
+
+/// Rejects a JSON body, keeping axum's status and message
+fn json_rejection(rejection: &JsonRejection) -> Response {
+    let code = if rejection.status() == StatusCode::UNSUPPORTED_MEDIA_TYPE {
+        ErrorCode::UnsupportedMediaType
+    } else {
+        ErrorCode::MalformedBody
+    };
+    error_response(rejection.status(), code, rejection.body_text())
+}
+
+impl<T, S> FromRequest<S> for Payload<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = Response;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let format = req
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.split(';').next())
+            .map(|media_type| media_type.trim().to_ascii_lowercase())
+            .and_then(|media_type| ResponseFormat::from_media_type(&media_type));
+
+        match format {
+            Some(format @ ResponseFormat::MessagePack) => {
+                let bytes = Bytes::from_request(req, state).await.map_err(IntoResponse::into_response)?;
+                rmp_serde::from_slice(&bytes)
+                    .map(Payload)
+                    .map_err(|e| decode_rejection(format, &e))
+            }
+            Some(format @ ResponseFormat::Cbor) => {
+                let bytes = Bytes::from_request(req, state).await.map_err(IntoResponse::into_response)?;
+                ciborium::from_reader(&bytes[..])
+                    .map(Payload)
+                    .map_err(|e| decode_rejection(format, &e))
+            }
+            Some(ResponseFormat::Json | ResponseFormat::Xml) | None => Json::<T>::from_request(req, state)
+                .await
+                .map(|Json(value)| Payload(value))
+                .map_err(|rejection| json_rejection(&rejection)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use serde::Deserialize;
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Sample {
+        id: i32,
+        name: String,
+    }
+
+    fn request(content_type: &str, body: Vec<u8>) -> Request {
+        Request::builder()
+            .header(header::CONTENT_TYPE, content_type)
+            .body(Body::from(body))
+            .expect("Failed to build request")
+    }
+
+    fn sample_value() -> serde_json::Value {
+        serde_json::json!({ "id": 1, "name": "Ann Lee" })
+    }
+
+    #[tokio::test]
+    async fn test_payload_decodes_msgpack() {
+        let body = rmp_serde::to_vec_named(&sample_value()).expect("Failed to encode");
+
+        let Payload(sample) = Payload::<Sample>::from_request(request("application/msgpack", body), &())
+            .await
+            .expect("MessagePack body should decode");
+
+        assert_eq!(sample, Sample { id: 1, name: "Ann Lee".to_owned() });
+    }
+
+    #[tokio::test]
+    async fn test_payload_decodes_cbor() {
+        let mut body = Vec::new();
+        ciborium::into_writer(&sample_value(), &mut body).expect("Failed to encode");
+
+        let Payload(sample) = Payload::<Sample>::from_request(request("application/cbor", body), &())
+            .await
+            .expect("CBOR body should decode");
+
+        assert_eq!(sample, Sample { id: 1, name: "Ann Lee".to_owned() });
+    }
+
+    #[tokio::test]
+    async fn test_payload_decodes_json() {
+        let body = sample_value().to_string().into_bytes();
+
+        let Payload(sample) = Payload::<Sample>::from_request(request("application/json", body), &())
+            .await
+            .expect("JSON body should decode");
+
+        assert_eq!(sample.id, 1);
+    }
+
+    #[tokio::test]
+    async fn test_payload_rejects_malformed_binary() {
+        let rejection = Payload::<Sample>::from_request(request("application/cbor", vec![0xff, 0x00]), &())
+            .await
+            .expect_err("Malformed CBOR should be rejected");
+
+        assert_eq!(rejection.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_payload_rejects_unsupported_content_type() {
+        let rejection = Payload::<Sample>::from_request(request("text/plain", b"hi".to_vec()), &())
+            .await
+            .expect_err("Unsupported content types should be rejected");
+
+        assert_eq!(rejection.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+    }
+}
@@ -0,0 +1,194 @@
+//! Notification hub - in-process fan-out of received notifications
+
+use std::sync::{Arc, Mutex, PoisonError};
+
+use futures_util::future::BoxFuture;
+use tokio::sync::broadcast;
+use tracing::debug;
+
+use crate::health::HealthCheck;
+
+use super::Notification;
+
+/// Default number of notifications buffered per subscriber before the oldest are dropped
+const DEFAULT_CAPACITY: usize = 1024;
+
+/// State of the listening connection
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ListenerState {
+    /// No listener was started
+    #[default]
+    Stopped,
+    /// Opening the connection and subscribing to the channels
+    Connecting,
+    /// Subscribed to every channel and receiving notifications
+    Listening,
+    /// The connection failed; waiting before opening a new one
+    Reconnecting,
+}
+
+/// State of the listener and what it went through
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ListenerStatus {
+    /// Current state
+    pub state: ListenerState,
+    /// Channels listened on
+    pub channels: Vec<String>,
+    /// Connections lost or failed since the listener started
+    pub reconnects: u64,
+    /// Error of the last lost or failed connection
+    pub last_error: Option<String>,
+}
+
+/// Hands the notifications received by the listener to in-process subscribers
+///
+/// Clones share the same subscribers and status.
+#[derive(Debug, Clone)]
+pub struct NotificationHub {
+    /// Channel the notifications are broadcast on
+    sender: broadcast::Sender<Notification>,
+    /// State reported by the listener
+    status: Arc<Mutex<ListenerStatus>>,
+}
+
+impl NotificationHub {
+    /// Creates a new `NotificationHub` with the default buffer capacity
+    #[must_use]
+    pub fn new() -> Self {
+        Self::with_capacity(DEFAULT_CAPACITY)
+    }
+
+    /// Creates a new `NotificationHub` buffering up to `capacity` notifications per subscriber
+    #[must_use]
+    pub fn with_capacity(capacity: usize) -> Self {
+        let (sender, _) = broadcast::channel(capacity);
+        Self {
+            sender,
+            status: Arc::default(),
+        }
+    }
+
+    /// Subscribes to the notifications received from now on, on every channel
+    #[must_use]
+    pub fn subscribe(&self) -> broadcast::Receiver<Notification> {
+        self.sender.subscribe()
+    }
+
+    /// Current state of the listener
+    #[must_use]
+    pub fn status(&self) -> ListenerStatus {
+        self.lock().clone()
+    }
+
+    /// Hands a received notification to the subscribers; dropped when nobody listens
+    pub(super) fn deliver(&self, notification: Notification) {
+        if let Err(broadcast::error::SendError(notification)) = self.sender.send(notification) {
+            debug!(channel = %notification.channel, "Notification received with no subscribers");
+        }
+    }
+
+    /// Records that the listener is (re)connecting to `channels`
+    pub(super) fn record_connecting(&self, channels: &[String]) {
+        let mut status = self.lock();
+        channels.clone_into(&mut status.channels);
+        if status.state == ListenerState::Stopped {
+            status.state = ListenerState::Connecting;
+        }
+    }
+
+    /// Records that the listener receives notifications on every channel
+    pub(super) fn record_listening(&self) {
+        self.lock().state = ListenerState::Listening;
+    }
+
+    /// Records a lost or failed connection
+    pub(super) fn record_lost(&self, error: String) {
+        let mut status = self.lock();
+        status.state = ListenerState::Reconnecting;
+        status.reconnects += 1;
+        status.last_error = Some(error);
+    }
+
+    /// Locks the status, which stays usable if a holder panicked
+    fn lock(&self) -> std::sync::MutexGuard<'_, ListenerStatus> {
+        self.status.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl Default for NotificationHub {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HealthCheck for NotificationHub {
+    fn name(&self) -> &'static str {
+        "notifications"
+    }
+
+    /// Requests do not depend on notifications, so a lost listener only degrades the service
+    fn critical(&self) -> bool {
+        false
+    }
+
+    fn check(&self) -> BoxFuture<'_, Result<(), String>> {
+        let status = self.status();
+        Box::pin(async move {
+            match status.state {
+                ListenerState::Listening => Ok(()),
+                ListenerState::Stopped => Err("Not listening".to_owned()),
+                ListenerState::Connecting => Err(format!("Connecting to {}", status.channels.join(", "))),
+                ListenerState::Reconnecting => Err(format!(
+                    "Reconnecting after: {}",
+                    status.last_error.as_deref().unwrap_or("connection lost")
+                )),
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn notification(payload: &str) -> Notification {
+        Notification {
+            channel: "cache_invalidation".to_owned(),
+            payload: payload.to_owned(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_subscribers_receive_delivered_notifications() {
+        let hub = NotificationHub::new();
+        let mut first = hub.subscribe();
+        let mut second = hub.subscribe();
+
+        hub.deliver(notification("users:7"));
+
+        assert_eq!(first.recv().await.unwrap(), notification("users:7"));
+        assert_eq!(second.recv().await.unwrap(), notification("users:7"));
+        hub.deliver(notification("dropped without subscribers"));
+    }
+
+    #[tokio::test]
+    async fn test_health_follows_the_listener_state() {
+        let hub = NotificationHub::new();
+        let channels = ["cache_invalidation".to_owned()];
+        assert_eq!(hub.check().await, Err("Not listening".to_owned()));
+        assert!(!hub.critical());
+
+        hub.record_connecting(&channels);
+        assert_eq!(hub.check().await, Err("Connecting to cache_invalidation".to_owned()));
+        hub.record_listening();
+        assert_eq!(hub.check().await, Ok(()));
+        hub.record_lost("connection reset".to_owned());
+        hub.record_connecting(&channels);
+        assert_eq!(hub.check().await, Err("Reconnecting after: connection reset".to_owned()));
+        hub.record_listening();
+
+        let status = hub.status();
+        assert_eq!((status.state, status.reconnects, status.channels.as_slice()), (ListenerState::Listening, 1, channels.as_slice()));
+        assert_eq!(status.last_error.as_deref(), Some("connection reset"));
+    }
+}
@@ -0,0 +1,108 @@
+//! Notify listener - the connection listening on the configured channels
+
+use std::io;
+use std::time::Duration;
+
+use sqlx::postgres::PgListener;
+use sqlx::PgPool;
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+use super::{hub::NotificationHub, Notification};
+
+/// Wait before the first reconnection attempt, doubled after every failed one
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+
+/// Default longest wait between reconnection attempts
+const DEFAULT_MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// Listens on channels and forwards the notifications to a [`NotificationHub`]
+///
+/// The listening connection is taken from the pool and held for as long as
+/// the listener runs, leaving one connection fewer for requests.
+#[derive(Debug)]
+pub struct NotifyListener {
+    /// Pool the listening connection is taken from
+    pool: PgPool,
+    /// Channels to listen on
+    channels: Vec<String>,
+    /// Hub the notifications are forwarded to
+    hub: NotificationHub,
+    /// Longest wait between reconnection attempts
+    max_backoff: Duration,
+}
+
+impl NotifyListener {
+    /// Creates a new `NotifyListener` listening on `channels` with the connections of `pool`
+    #[must_use]
+    pub fn new(pool: PgPool, channels: Vec<String>, hub: NotificationHub) -> Self {
+        Self {
+            pool,
+            channels,
+            hub,
+            max_backoff: DEFAULT_MAX_BACKOFF,
+        }
+    }
+
+    /// Sets the longest wait between reconnection attempts
+    #[must_use]
+    pub fn with_max_backoff(mut self, max_backoff: Duration) -> Self {
+        self.max_backoff = max_backoff.max(INITIAL_BACKOFF);
+        self
+    }
+
+    /// Listens in the background until the task is aborted or the pool is closed
+    #[must_use = "the listener stops when the handle is aborted"]
+    pub fn spawn(self) -> JoinHandle<()> {
+        tokio::spawn(self.run())
+    }
+
+    /// Listens, reopening the connection whenever it is lost
+    async fn run(self) {
+        let mut backoff = INITIAL_BACKOFF;
+        loop {
+            self.hub.record_connecting(&self.channels);
+            let error = match self.listen().await {
+                Ok(listener) => {
+                    info!(channels = ?self.channels, "Listening for notifications");
+                    self.hub.record_listening();
+                    backoff = INITIAL_BACKOFF;
+                    self.forward(listener).await
+                }
+                Err(error) => error,
+            };
+            if matches!(error, sqlx::Error::PoolClosed) {
+                info!("Stopped listening for notifications, the pool is closed");
+                return;
+            }
+
+            warn!(error = %error, retry_in_ms = backoff.as_millis(), "Notification listener connection lost");
+            self.hub.record_lost(error.to_string());
+            tokio::time::sleep(backoff).await;
+            backoff = (backoff * 2).min(self.max_backoff);
+        }
+    }
+
+    /// Opens a connection listening on every channel
+    async fn listen(&self) -> Result<PgListener, sqlx::Error> {
+        let mut listener = PgListener::connect_with(&self.pool).await?;
+        // A lost connection is reopened by `run`, which records it and waits between attempts
+        listener.eager_reconnect(false);
+        listener.listen_all(self.channels.iter().map(String::as_str)).await?;
+        Ok(listener)
+    }
+
+    /// Forwards notifications until the connection is lost, returning why
+    async fn forward(&self, mut listener: PgListener) -> sqlx::Error {
+        loop {
+            match listener.try_recv().await {
+                Ok(Some(notification)) => self.hub.deliver(Notification {
+                    channel: notification.channel().to_owned(),
+                    payload: notification.payload().to_owned(),
+                }),
+                Ok(None) => return sqlx::Error::Io(io::ErrorKind::UnexpectedEof.into()),
+                Err(error) => return error,
+            }
+        }
+    }
+}
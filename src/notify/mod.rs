@@ -0,0 +1,48 @@
+//! Postgres LISTEN/NOTIFY
+//!
+//! Lets replicas signal each other through the database they already share,
+//! without an external broker: one replica sends a [`notify`] on a channel
+//! (`cache_invalidation`, `events`) and every replica listening on it,
+//! itself included, receives the payload. A [`NotifyListener`] holds one
+//! connection listening on the channels of `NOTIFY_CHANNELS` and forwards
+//! what arrives to the [`NotificationHub`], where in-process consumers (cache
+//! invalidators, realtime feeds) subscribe. A lost connection is reopened
+//! with a capped exponential backoff; notifications sent in the meantime are
+//! missed, so consumers needing every change read the
+//! [change log](crate::change_log) instead. The hub reports the listener's
+//! state as the non-critical `notifications` health component.
+
+mod hub;
+mod listener;
+
+pub use hub::{ListenerState, ListenerStatus, NotificationHub};
+pub use listener::NotifyListener;
+
+use serde::Serialize;
+use sqlx::PgExecutor;
+
+/// A payload received on a channel
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct Notification {
+    /// Channel the payload was sent on
+    pub channel: String,
+    /// Payload as sent, often JSON; empty when none was given
+    pub payload: String,
+}
+
+/// Sends `payload` to the listeners of `channel`
+///
+/// Sent on a transaction, the notification is only delivered when the
+/// transaction commits. Postgres limits payloads to 8000 bytes.
+///
+/// # Errors
+///
+/// Returns an error if the payload is too long or the database fails.
+pub async fn notify(executor: impl PgExecutor<'_>, channel: &str, payload: &str) -> Result<(), sqlx::Error> {
+    sqlx::query("SELECT pg_notify($1, $2)")
+        .bind(channel)
+        .bind(payload)
+        .execute(executor)
+        .await?;
+    Ok(())
+}
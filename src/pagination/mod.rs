@@ -1,10 +1,13 @@
 //! Pagination token system
 //!
 //! Provides opaque pagination tokens for cursor-based pagination,
+//! either for `(id, timestamp)` cursors or for any serializable cursor type,
+//! and the page size bounds requests are clamped to.
 
 use base64::{engine::general_purpose, Engine as _};
 use chrono::{DateTime, Utc};
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use utoipa::ToSchema;
 
 /// Internal cursor data structure
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -33,38 +36,90 @@ pub enum TokenError {
 impl PaginationToken {
     /// Encodes pagination information into an opaque token
     pub fn encode(last_id: i32, timestamp: DateTime<Utc>) -> Result<String, TokenError> {
-        let cursor_data = CursorData {
+        Self::encode_cursor(&CursorData {
             id: last_id,
             timestamp,
-        };
+        })
+    }
+
+    /// Decodes a pagination token to extract cursor information
+    pub fn decode(token: &str) -> Result<(i32, DateTime<Utc>), TokenError> {
+        let cursor_data: CursorData = Self::decode_cursor(token)?;
+        Ok((cursor_data.id, cursor_data.timestamp))
+    }
 
-        let json = serde_json::to_string(&cursor_data)
+    /// Encodes an arbitrary cursor into an opaque token
+    pub fn encode_cursor<T: Serialize>(cursor: &T) -> Result<String, TokenError> {
+        let json = serde_json::to_string(cursor)
             .map_err(|e| TokenError::EncodingError(e.to_string()))?;
 
         let token = general_purpose::URL_SAFE_NO_PAD.encode(json.as_bytes());
         Ok(token)
     }
 
-    /// Decodes a pagination token to extract cursor information
-    pub fn decode(token: &str) -> Result<(i32, DateTime<Utc>), TokenError> {
+    /// Decodes a token produced by [`PaginationToken::encode_cursor`]
+    pub fn decode_cursor<T: DeserializeOwned>(token: &str) -> Result<T, TokenError> {
         let decoded_bytes = general_purpose::URL_SAFE_NO_PAD
             .decode(token)
-            .map_err(|_| TokenError::InvalidToken)?;
+            .map_err(|_e| TokenError::InvalidToken)?;
 
-        let json = String::from_utf8(decoded_bytes).map_err(|_| TokenError::InvalidToken)?;
+        let json = String::from_utf8(decoded_bytes).map_err(|_e| TokenError::InvalidToken)?;
 
-        let cursor_data: CursorData =
-            serde_json::from_str(&json).map_err(|_| TokenError::InvalidToken)?;
-
-        Ok((cursor_data.id, cursor_data.timestamp))
+        serde_json::from_str(&json).map_err(|_e| TokenError::InvalidToken)
     }
 
     /// Validates if a token is well-formed (without fully decoding)
+    #[must_use]
     pub fn is_valid(token: &str) -> bool {
         Self::decode(token).is_ok()
     }
 }
 
+/// Direction a listing is sorted in
+#[derive(Serialize, Deserialize, ToSchema, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+    /// Oldest first
+    #[default]
+    Asc,
+    /// Newest first
+    Desc,
+}
+
+impl SortOrder {
+    /// The opposite direction, used to scan backwards from a cursor
+    #[must_use]
+    pub fn reversed(self) -> Self {
+        match self {
+            Self::Asc => Self::Desc,
+            Self::Desc => Self::Asc,
+        }
+    }
+}
+
+/// Page size bounds of a paginated listing
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PageLimits {
+    /// Page size when the request has no `limit`
+    pub default: i32,
+    /// Largest page size a request may ask for
+    pub max: i32,
+}
+
+impl PageLimits {
+    /// Page size for a request asking for `requested` records
+    #[must_use]
+    pub fn resolve(self, requested: Option<i32>) -> i32 {
+        requested.unwrap_or(self.default).clamp(1, self.max.max(1))
+    }
+}
+
+impl Default for PageLimits {
+    fn default() -> Self {
+        Self { default: 200, max: 200 }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -93,4 +148,25 @@ mod tests {
         let token = PaginationToken::encode(456, Utc::now()).unwrap();
         assert!(PaginationToken::is_valid(&token));
     }
+
+    #[test]
+    fn test_page_limits_resolve() {
+        let limits = PageLimits { default: 25, max: 100 };
+
+        assert_eq!(limits.resolve(None), 25);
+        assert_eq!(limits.resolve(Some(50)), 50);
+        assert_eq!(limits.resolve(Some(500)), 100);
+        assert_eq!(limits.resolve(Some(0)), 1);
+    }
+
+    #[test]
+    fn test_custom_cursor_encode_decode() {
+        let cursor = ("ledger:42".to_owned(), Utc::now());
+
+        let token = PaginationToken::encode_cursor(&cursor).unwrap();
+        let decoded: (String, DateTime<Utc>) = PaginationToken::decode_cursor(&token).unwrap();
+
+        assert_eq!(cursor, decoded);
+        assert!(PaginationToken::decode_cursor::<(String, DateTime<Utc>)>("invalid_token").is_err());
+    }
 }
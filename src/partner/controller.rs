@@ -0,0 +1,137 @@
+//! Partner controller - HTTP handlers for partner signing keys and the
+//! accounts partners may debit
+
+use axum::{
+    Json,
+    extract::Path,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use tracing::{error, warn};
+
+use crate::error_codes::{ErrorCode, ErrorResponse, internal_error};
+use crate::registry::Inject;
+use crate::user::domain::{ApiResponse, ValidationErrorResponse};
+
+use super::domain::{IssuedPartnerKey, PartnerAccountGrant, PartnerError};
+use super::service::PartnerService;
+
+/// Maps partner errors to HTTP responses
+fn error_response(error: PartnerError, partner: &str) -> Response {
+    match error {
+        PartnerError::ValidationError(errors) => {
+            warn!(?errors, partner, "Controller: Partner validation failed");
+            (
+                StatusCode::BAD_REQUEST,
+                Json(ValidationErrorResponse { errors }),
+            ).into_response()
+        }
+        PartnerError::AccountNotFound => {
+            warn!(partner, "Controller: Account to grant not found");
+            (
+                StatusCode::NOT_FOUND,
+                Json(ErrorResponse::new(ErrorCode::AccountNotFound, error.to_string())),
+            ).into_response()
+        }
+        PartnerError::AccountNotGranted { account_id, .. } => {
+            warn!(partner, account_id, "Controller: Account not granted to partner");
+            (
+                StatusCode::NOT_FOUND,
+                Json(ErrorResponse::new(ErrorCode::AccountNotGranted, error.to_string())),
+            ).into_response()
+        }
+        PartnerError::DatabaseError(msg) => {
+            error!(error = %msg, partner, "Controller: Database error in partner operation");
+            internal_error()
+        }
+    }
+}
+
+/// HTTP handler issuing a new signing key for a partner
+///
+/// The partner's previous keys keep working until `previous_keys_expire_at`.
+#[utoipa::path(
+    post,
+    path = "/admin/partners/{partner}/keys",
+    tag = "partners",
+    params(
+        ("partner" = String, Path, description = "Partner name, e.g. `acme`")
+    ),
+    responses(
+        (status = 201, description = "Key issued; the secret is not shown again", body = IssuedPartnerKey),
+        (status = 400, description = "Invalid partner name", body = ValidationErrorResponse),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+        (status = 403, description = "Caller lacks the admin role", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    )
+)]
+#[tracing::instrument(skip(partner_service))]
+pub async fn rotate_partner_key_handler(
+    Inject(partner_service): Inject<PartnerService>,
+    Path(partner): Path<String>,
+) -> impl IntoResponse {
+    match partner_service.rotate_key(&partner).await {
+        Ok(key) => (StatusCode::CREATED, Json(key)).into_response(),
+        Err(e) => error_response(e, &partner),
+    }
+}
+
+/// HTTP handler letting a partner pay out of an account with signed requests
+#[utoipa::path(
+    put,
+    path = "/admin/partners/{partner}/accounts/{account_id}",
+    tag = "partners",
+    params(
+        ("partner" = String, Path, description = "Partner name, e.g. `acme`"),
+        ("account_id" = i32, Path, description = "Account the partner may debit")
+    ),
+    responses(
+        (status = 200, description = "Account granted; granting it again keeps the first grant", body = PartnerAccountGrant),
+        (status = 400, description = "Invalid partner name", body = ValidationErrorResponse),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+        (status = 403, description = "Caller lacks the admin role", body = ErrorResponse),
+        (status = 404, description = "Account not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    )
+)]
+#[tracing::instrument(skip(partner_service))]
+pub async fn grant_partner_account_handler(
+    Inject(partner_service): Inject<PartnerService>,
+    Path((partner, account_id)): Path<(String, i32)>,
+) -> impl IntoResponse {
+    match partner_service.grant_account(&partner, account_id).await {
+        Ok(grant) => (StatusCode::OK, Json(grant)).into_response(),
+        Err(e) => error_response(e, &partner),
+    }
+}
+
+/// HTTP handler stopping a partner from paying out of an account
+#[utoipa::path(
+    delete,
+    path = "/admin/partners/{partner}/accounts/{account_id}",
+    tag = "partners",
+    params(
+        ("partner" = String, Path, description = "Partner name, e.g. `acme`"),
+        ("account_id" = i32, Path, description = "Account to revoke")
+    ),
+    responses(
+        (status = 200, description = "Grant revoked", body = ApiResponse),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+        (status = 403, description = "Caller lacks the admin role", body = ErrorResponse),
+        (status = 404, description = "The account is not granted to the partner", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    )
+)]
+#[tracing::instrument(skip(partner_service))]
+pub async fn revoke_partner_account_handler(
+    Inject(partner_service): Inject<PartnerService>,
+    Path((partner, account_id)): Path<(String, i32)>,
+) -> impl IntoResponse {
+    match partner_service.revoke_account(&partner, account_id).await {
+        Ok(()) => (
+            StatusCode::OK,
+            Json(ApiResponse { message: format!("Account {account_id} revoked from partner {partner}") }),
+        ).into_response(),
+        Err(e) => error_response(e, &partner),
+    }
+}
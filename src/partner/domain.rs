@@ -0,0 +1,62 @@
+//! Partner domain models
+
+use serde::Serialize;
+use schemars::JsonSchema;
+use utoipa::ToSchema;
+use chrono::{DateTime, Utc};
+
+use crate::user::domain::ValidationError;
+
+/// A newly issued partner signing key
+///
+/// The secret is only ever returned here; hand it to the partner over a
+/// secure channel.
+#[derive(Serialize, ToSchema, JsonSchema, Debug, Clone)]
+pub struct IssuedPartnerKey {
+    /// Partner the key belongs to
+    pub partner: String,
+    /// Secret to derive the signing key from (SHA-256 of its UTF-8 bytes)
+    pub secret: String,
+    /// When the key was issued
+    pub created_at: DateTime<Utc>,
+    /// When the partner's previous keys stop being accepted, if it had any
+    pub previous_keys_expire_at: Option<DateTime<Utc>>,
+}
+
+/// Account a partner may pay out of with signed requests
+#[derive(Serialize, ToSchema, JsonSchema, Debug, Clone)]
+pub struct PartnerAccountGrant {
+    /// Partner the account is granted to
+    pub partner: String,
+    /// Account the partner may debit
+    pub account_id: i32,
+    /// When the account was granted
+    pub created_at: DateTime<Utc>,
+}
+
+/// Partner that signed the request, available to handlers of signed routes
+/// as a request extension
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PartnerId(pub String);
+
+/// Domain errors for partner operations
+#[derive(Debug, thiserror::Error)]
+pub enum PartnerError {
+    /// Partner name is invalid
+    #[error("Validation failed")]
+    ValidationError(Vec<ValidationError>),
+    /// The account to grant does not exist
+    #[error("Account not found")]
+    AccountNotFound,
+    /// The account is not granted to the partner
+    #[error("Account {account_id} is not granted to partner {partner}")]
+    AccountNotGranted {
+        /// The signing partner
+        partner: String,
+        /// The account it tried to debit
+        account_id: i32,
+    },
+    /// Database operation failed
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
@@ -0,0 +1,31 @@
+//! Partner integration module
+//!
+//! Partner systems call designated routes with HMAC-signed requests instead
+//! of bearer tokens. Each partner holds one or more signing keys, the SHA-256
+//! digest of the issued secret, which the database stores sealed with the
+//! server's key-encryption key (see [`sealing`]), so reading `partner_keys`
+//! does not allow signing requests as the partner.
+//! `POST /admin/partners/{partner}/keys` issues a new key and lets the
+//! previous ones expire after a grace period, and the [`verify_signatures`]
+//! route layer checks signatures, timestamps and replays on the routes listed
+//! in a [`SignedRoutes`] table. A signature only identifies the partner:
+//! `PUT /admin/partners/{partner}/accounts/{account_id}` grants the accounts
+//! it may pay out of, which handlers check with
+//! [`PartnerService::authorize_debit`].
+
+pub mod controller;
+pub mod domain;
+pub mod repository;
+pub mod sealing;
+pub mod service;
+pub mod signing;
+pub mod validation;
+
+// Public exports
+pub use domain::{IssuedPartnerKey, PartnerAccountGrant, PartnerError, PartnerId};
+pub use sealing::KeyEncryptionKey;
+pub use service::PartnerService;
+pub use signing::{SignatureVerifier, SignedRoutes, sign, verify_signatures};
+
+// Export controller for OpenAPI documentation
+pub use controller::*;
@@ -0,0 +1,190 @@
+//! Partner repository - handles database operations
+//!
+//! This module is private to the partner module. All database access must go
+//! through `PartnerService`.
+
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use tracing::{error, info, warn};
+
+use crate::db::DbError;
+
+use super::domain::{PartnerAccountGrant, PartnerError};
+
+/// A stored signing key: sealed, or in the clear when stored before keys were sealed
+pub(super) struct StoredKey {
+    pub(super) key_digest: Option<Vec<u8>>,
+    pub(super) sealed_key: Option<Vec<u8>>,
+}
+
+/// Partner repository for database operations
+#[derive(Clone)]
+pub(super) struct PartnerRepository {
+    pool: PgPool,
+}
+
+impl PartnerRepository {
+    /// Creates a new `PartnerRepository` instance
+    pub(super) fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Stores a new sealed signing key for `partner` and makes its other live
+    /// keys expire by `previous_expire_at`
+    ///
+    /// Returns the latest expiry given to a previous key, if the partner had any.
+    pub(super) async fn rotate(
+        &self,
+        partner: &str,
+        sealed_key: &[u8],
+        created_at: DateTime<Utc>,
+        previous_expire_at: DateTime<Utc>,
+    ) -> Result<Option<DateTime<Utc>>, PartnerError> {
+        info!(partner, "Rotating partner key in database");
+
+        let map_err = |e: sqlx::Error| {
+            error!(error = %e, partner, "Failed to rotate partner key in database");
+            PartnerError::DatabaseError(DbError::from(e).to_string())
+        };
+        let mut tx = self.pool.begin().await.map_err(map_err)?;
+
+        let previous = sqlx::query_scalar!(
+            r#"UPDATE partner_keys SET expires_at = LEAST(COALESCE(expires_at, $3), $3)
+             WHERE partner = $1 AND (expires_at IS NULL OR expires_at > $2)
+             RETURNING expires_at AS "expires_at!""#,
+            partner,
+            created_at,
+            previous_expire_at
+        )
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(map_err)?;
+
+        sqlx::query!(
+            "INSERT INTO partner_keys (partner, sealed_key, created_at) VALUES ($1, $2, $3)",
+            partner,
+            sealed_key,
+            created_at
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(map_err)?;
+
+        tx.commit().await.map_err(map_err)?;
+        Ok(previous.into_iter().max())
+    }
+
+    /// Signing keys of `partner` that are live at `at`, newest first
+    pub(super) async fn live_keys(&self, partner: &str, at: DateTime<Utc>) -> Result<Vec<StoredKey>, PartnerError> {
+        sqlx::query_as!(
+            StoredKey,
+            "SELECT key_digest, sealed_key FROM partner_keys
+             WHERE partner = $1 AND (expires_at IS NULL OR expires_at > $2)
+             ORDER BY created_at DESC",
+            partner,
+            at
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| {
+            error!(error = %e, partner, "Failed to fetch partner keys from database");
+            PartnerError::DatabaseError(DbError::from(e).to_string())
+        })
+    }
+
+    /// Replaces every signing key stored in the clear by `seal(partner, key)`,
+    /// returning how many were sealed
+    pub(super) async fn seal_keys_in_the_clear(&self, seal: impl Fn(&str, &[u8]) -> Vec<u8>) -> Result<usize, PartnerError> {
+        let map_err = |e: sqlx::Error| {
+            error!(error = %e, "Failed to seal partner keys in database");
+            PartnerError::DatabaseError(DbError::from(e).to_string())
+        };
+        let mut tx = self.pool.begin().await.map_err(map_err)?;
+
+        let keys = sqlx::query!(
+            r#"SELECT id, partner, key_digest AS "key_digest!" FROM partner_keys WHERE key_digest IS NOT NULL FOR UPDATE"#
+        )
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(map_err)?;
+
+        for key in &keys {
+            sqlx::query!(
+                "UPDATE partner_keys SET sealed_key = $2, key_digest = NULL WHERE id = $1",
+                key.id,
+                seal(&key.partner, &key.key_digest)
+            )
+            .execute(&mut *tx)
+            .await
+            .map_err(map_err)?;
+        }
+
+        tx.commit().await.map_err(map_err)?;
+        info!(sealed = keys.len(), "Sealed partner keys stored in the clear");
+        Ok(keys.len())
+    }
+
+    /// Lets `partner` debit `account_id`, keeping the original grant time if
+    /// it already could
+    pub(super) async fn grant_account(
+        &self,
+        partner: &str,
+        account_id: i32,
+        created_at: DateTime<Utc>,
+    ) -> Result<PartnerAccountGrant, PartnerError> {
+        info!(partner, account_id, "Granting account to partner in database");
+
+        sqlx::query_as!(
+            PartnerAccountGrant,
+            "INSERT INTO partner_accounts (partner, account_id, created_at) VALUES ($1, $2, $3)
+             ON CONFLICT (partner, account_id) DO UPDATE SET created_at = partner_accounts.created_at
+             RETURNING partner, account_id, created_at",
+            partner,
+            account_id,
+            created_at
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| {
+            if e.as_database_error().is_some_and(sqlx::error::DatabaseError::is_foreign_key_violation) {
+                warn!(partner, account_id, "Account to grant does not exist");
+                return PartnerError::AccountNotFound;
+            }
+            error!(error = %e, partner, account_id, "Failed to grant account to partner in database");
+            PartnerError::DatabaseError(DbError::from(e).to_string())
+        })
+    }
+
+    /// Stops `partner` from debiting `account_id`, returning whether it could
+    pub(super) async fn revoke_account(&self, partner: &str, account_id: i32) -> Result<bool, PartnerError> {
+        info!(partner, account_id, "Revoking partner account grant in database");
+
+        let result = sqlx::query!(
+            "DELETE FROM partner_accounts WHERE partner = $1 AND account_id = $2",
+            partner,
+            account_id
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| {
+            error!(error = %e, partner, account_id, "Failed to revoke partner account grant in database");
+            PartnerError::DatabaseError(DbError::from(e).to_string())
+        })?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Whether `partner` may debit `account_id`
+    pub(super) async fn may_debit(&self, partner: &str, account_id: i32) -> Result<bool, PartnerError> {
+        sqlx::query_scalar!(
+            r#"SELECT EXISTS (SELECT 1 FROM partner_accounts WHERE partner = $1 AND account_id = $2) AS "granted!""#,
+            partner,
+            account_id
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| {
+            error!(error = %e, partner, account_id, "Failed to check partner account grant in database");
+            PartnerError::DatabaseError(DbError::from(e).to_string())
+        })
+    }
+}
@@ -0,0 +1,97 @@
+//! Encryption of stored partner signing keys
+//!
+//! Signing keys are stored sealed with AES-256-GCM under a key-encryption
+//! key (KEK) held by the server, with the partner's name as associated data.
+//! A copy of `partner_keys` alone therefore cannot sign requests, and a
+//! sealed key moved to another partner's row does not open. A sealed key is
+//! the 12-byte nonce followed by the ciphertext and tag.
+
+use std::sync::{Arc, OnceLock};
+
+use ring::aead::{AES_256_GCM, Aad, LessSafeKey, NONCE_LEN, Nonce, UnboundKey};
+use sha2::{Digest, Sha256};
+use tracing::warn;
+
+use crate::config::PartnerConfig;
+
+/// Random key-encryption key shared by this process when none is configured
+static PROCESS_KEK: OnceLock<[u8; 32]> = OnceLock::new();
+
+/// Server-side key sealing the partner signing keys stored in the database
+#[derive(Clone)]
+pub struct KeyEncryptionKey {
+    key: Arc<LessSafeKey>,
+}
+
+impl KeyEncryptionKey {
+    /// KEK derived from `secret`; its SHA-256 digest is the AES-256 key
+    #[must_use]
+    pub fn new(secret: impl AsRef<[u8]>) -> Self {
+        let key = UnboundKey::new(&AES_256_GCM, &Sha256::digest(secret.as_ref())).expect("SHA-256 digests are AES-256 keys");
+        Self { key: Arc::new(LessSafeKey::new(key)) }
+    }
+
+    /// KEK from `PARTNER_KEY_ENCRYPTION_KEY`, or a random per-process one
+    ///
+    /// Keys sealed with the random KEK no longer open when the process
+    /// restarts or on other instances, so partners must rotate them.
+    #[must_use]
+    pub fn from_config(config: &PartnerConfig) -> Self {
+        config.key_encryption_key.as_deref().map_or_else(
+            || {
+                warn!("PARTNER_KEY_ENCRYPTION_KEY is not set; partner keys issued now only verify on this instance until it restarts");
+                Self::process()
+            },
+            Self::new,
+        )
+    }
+
+    /// Random KEK shared by this process
+    pub(super) fn process() -> Self {
+        Self::new(PROCESS_KEK.get_or_init(rand::random))
+    }
+
+    /// Seals the signing key of `partner` for storage
+    pub(super) fn seal(&self, partner: &str, signing_key: &[u8]) -> Vec<u8> {
+        let nonce: [u8; NONCE_LEN] = rand::random();
+        let mut sealed = signing_key.to_vec();
+        self.key
+            .seal_in_place_append_tag(Nonce::assume_unique_for_key(nonce), Aad::from(partner.as_bytes()), &mut sealed)
+            .expect("Signing keys are far below the AES-GCM size limit");
+        [nonce.as_slice(), &sealed].concat()
+    }
+
+    /// Signing key of `partner` sealed in `sealed`, or `None` when it was
+    /// sealed under another KEK or for another partner
+    pub(super) fn open(&self, partner: &str, sealed: &[u8]) -> Option<Vec<u8>> {
+        let (nonce, ciphertext) = sealed.split_at_checked(NONCE_LEN)?;
+        let nonce = Nonce::try_assume_unique_for_key(nonce).ok()?;
+        let mut ciphertext = ciphertext.to_vec();
+        let key = self.key.open_in_place(nonce, Aad::from(partner.as_bytes()), &mut ciphertext).ok()?;
+        Some(key.to_vec())
+    }
+}
+
+impl std::fmt::Debug for KeyEncryptionKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("KeyEncryptionKey").finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sealed_keys_only_open_for_their_partner_and_kek() {
+        let kek = KeyEncryptionKey::new("kek");
+        let sealed = kek.seal("acme", b"signing key");
+
+        assert_eq!(kek.open("acme", &sealed).as_deref(), Some(b"signing key".as_slice()));
+        assert!(!sealed.windows(11).any(|window| window == b"signing key"), "The key should not be stored in the clear");
+        assert_ne!(kek.seal("acme", b"signing key"), sealed, "Every seal should use a fresh nonce");
+        assert_eq!(kek.open("globex", &sealed), None);
+        assert_eq!(KeyEncryptionKey::new("other").open("acme", &sealed), None);
+        assert_eq!(kek.open("acme", &sealed[..8]), None);
+    }
+}
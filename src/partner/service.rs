@@ -0,0 +1,148 @@
+//! Partner service - business logic layer
+//!
+//! Issues partner signing keys, stores them sealed with the server's
+//! key-encryption key and opens the live ones when a signed request comes in,
+//! and keeps track of the accounts each partner may debit.
+
+use std::time::Duration;
+
+use base64::{Engine as _, engine::general_purpose};
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use tracing::{info, warn};
+
+use crate::clock::{SharedClock, SystemClock};
+
+use super::domain::{IssuedPartnerKey, PartnerAccountGrant, PartnerError};
+use super::repository::{PartnerRepository, StoredKey};
+use super::sealing::KeyEncryptionKey;
+use super::signing::signing_key;
+use super::validation::validate_partner;
+
+/// How long previous keys stay valid after a rotation by default
+const DEFAULT_ROTATION_GRACE: Duration = Duration::from_hours(24);
+
+/// Partner service that handles business logic for signing keys
+#[derive(Clone)]
+pub struct PartnerService {
+    repository: PartnerRepository,
+    clock: SharedClock,
+    rotation_grace: Duration,
+    kek: KeyEncryptionKey,
+}
+
+impl PartnerService {
+    /// Creates a new `PartnerService` instance
+    #[must_use] pub fn new(pool: PgPool) -> Self {
+        Self {
+            repository: PartnerRepository::new(pool),
+            clock: SystemClock::shared(),
+            rotation_grace: DEFAULT_ROTATION_GRACE,
+            kek: KeyEncryptionKey::process(),
+        }
+    }
+
+    /// Uses `clock` instead of the system clock for timestamps
+    #[must_use] pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Keeps previous keys valid for `grace` after a rotation
+    #[must_use] pub const fn with_rotation_grace(mut self, grace: Duration) -> Self {
+        self.rotation_grace = grace;
+        self
+    }
+
+    /// Seals stored signing keys with `kek` instead of a random per-process key
+    #[must_use] pub fn with_key_encryption_key(mut self, kek: KeyEncryptionKey) -> Self {
+        self.kek = kek;
+        self
+    }
+
+    /// Current time of the service's clock
+    pub(super) fn now(&self) -> DateTime<Utc> {
+        self.clock.now()
+    }
+
+    /// Issues a new signing key for `partner`
+    ///
+    /// The partner's previous keys keep working for the rotation grace
+    /// period, so it can switch to the new key without failed requests. The
+    /// database stores the signing key derived from the secret sealed with the
+    /// key-encryption key; the secret itself is only returned here.
+    pub async fn rotate_key(&self, partner: &str) -> Result<IssuedPartnerKey, PartnerError> {
+        info!(target: "audit", partner, "PartnerService: Rotating partner key");
+
+        validate_partner(partner).map_err(PartnerError::ValidationError)?;
+        let secret = general_purpose::URL_SAFE_NO_PAD.encode(rand::random::<[u8; 32]>());
+        let created_at = self.clock.now();
+        let grace = chrono::Duration::from_std(self.rotation_grace).unwrap_or(chrono::Duration::MAX);
+        let previous_keys_expire_at = self
+            .repository
+            .rotate(partner, &self.kek.seal(partner, &signing_key(&secret)), created_at, created_at.checked_add_signed(grace).unwrap_or(DateTime::<Utc>::MAX_UTC))
+            .await?;
+
+        Ok(IssuedPartnerKey {
+            partner: partner.to_owned(),
+            secret,
+            created_at,
+            previous_keys_expire_at,
+        })
+    }
+
+    /// Signing keys of `partner` that are currently accepted, newest first
+    ///
+    /// Keys that do not open with the service's key-encryption key (sealed
+    /// under another one) are skipped.
+    pub(super) async fn live_keys(&self, partner: &str) -> Result<Vec<Vec<u8>>, PartnerError> {
+        let keys = self.repository.live_keys(partner, self.clock.now()).await?;
+        Ok(keys
+            .into_iter()
+            .filter_map(|StoredKey { key_digest, sealed_key }| {
+                let key = sealed_key.map_or(key_digest, |sealed| self.kek.open(partner, &sealed));
+                if key.is_none() {
+                    warn!(partner, "Partner key does not open with the key-encryption key");
+                }
+                key
+            })
+            .collect())
+    }
+
+    /// Seals the signing keys stored in the clear before keys were sealed,
+    /// returning how many were sealed
+    pub async fn seal_stored_keys(&self) -> Result<usize, PartnerError> {
+        self.repository.seal_keys_in_the_clear(|partner, key| self.kek.seal(partner, key)).await
+    }
+
+    /// Lets `partner` pay out of `account_id` with signed requests
+    ///
+    /// Granting an account twice keeps the first grant.
+    pub async fn grant_account(&self, partner: &str, account_id: i32) -> Result<PartnerAccountGrant, PartnerError> {
+        info!(target: "audit", partner, account_id, "PartnerService: Granting account to partner");
+
+        validate_partner(partner).map_err(PartnerError::ValidationError)?;
+        self.repository.grant_account(partner, account_id, self.clock.now()).await
+    }
+
+    /// Stops `partner` from paying out of `account_id`
+    pub async fn revoke_account(&self, partner: &str, account_id: i32) -> Result<(), PartnerError> {
+        info!(target: "audit", partner, account_id, "PartnerService: Revoking partner account grant");
+
+        if self.repository.revoke_account(partner, account_id).await? {
+            Ok(())
+        } else {
+            Err(PartnerError::AccountNotGranted { partner: partner.to_owned(), account_id })
+        }
+    }
+
+    /// Checks that `partner` may pay out of `account_id`, failing with
+    /// [`PartnerError::AccountNotGranted`] otherwise
+    pub async fn authorize_debit(&self, partner: &str, account_id: i32) -> Result<(), PartnerError> {
+        if self.repository.may_debit(partner, account_id).await? {
+            Ok(())
+        } else {
+            Err(PartnerError::AccountNotGranted { partner: partner.to_owned(), account_id })
+        }
+    }
+}
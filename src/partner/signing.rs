@@ -0,0 +1,250 @@
+//! Partner request signatures
+//!
+//! A signed request carries three headers:
+//!
+//! - `X-Partner-Id`: the partner's name
+//! - `X-Timestamp`: when it was signed, in Unix seconds
+//! - `X-Signature`: base64url (unpadded) HMAC-SHA256 of
+//!   `{timestamp}\n{METHOD}\n{path and query}\n{body}`
+//!
+//! keyed with the SHA-256 digest of the partner's secret. That digest is the
+//! signing key; it is stored sealed (see [`super::sealing`]). Requests signed
+//! further than the replay window from now are rejected, and so is a
+//! signature seen before within that window. Seen signatures are remembered
+//! per instance, so deployments with several instances rely on the window
+//! alone across instances.
+
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use axum::{
+    body::{Body, to_bytes},
+    extract::{MatchedPath, Request, State},
+    http::{HeaderMap, HeaderName, Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use base64::{Engine as _, engine::general_purpose};
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256};
+use tracing::{error, warn};
+
+use crate::error_codes::{ErrorCode, error_response, internal_error};
+
+use super::domain::{PartnerError, PartnerId};
+use super::service::PartnerService;
+
+/// HMAC-SHA256
+type HmacSha256 = Hmac<Sha256>;
+
+/// Header naming the partner that signed the request
+pub const PARTNER_ID_HEADER: HeaderName = HeaderName::from_static("x-partner-id");
+/// Header carrying the signing time in Unix seconds
+pub const TIMESTAMP_HEADER: HeaderName = HeaderName::from_static("x-timestamp");
+/// Header carrying the request signature
+pub const SIGNATURE_HEADER: HeaderName = HeaderName::from_static("x-signature");
+
+/// Largest request body read for signature verification
+const MAX_SIGNED_BODY_BYTES: usize = 2 * 1024 * 1024;
+
+/// HMAC key derived from a partner secret, which is what gets stored, sealed
+///
+/// The key signs requests by itself, so it is as sensitive as the secret.
+#[must_use]
+pub fn signing_key(secret: &str) -> Vec<u8> {
+    Sha256::digest(secret.as_bytes()).to_vec()
+}
+
+/// MAC over the signed parts of a request
+fn mac(key: &[u8], timestamp: i64, method: &Method, path_and_query: &str, body: &[u8]) -> HmacSha256 {
+    let mut mac = HmacSha256::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(format!("{timestamp}\n{method}\n{path_and_query}\n").as_bytes());
+    mac.update(body);
+    mac
+}
+
+/// `X-Signature` of a request signed with the secret `secret` at `timestamp`
+#[must_use]
+pub fn sign(secret: &str, timestamp: i64, method: &Method, path_and_query: &str, body: &[u8]) -> String {
+    let mac = mac(&signing_key(secret), timestamp, method, path_and_query, body);
+    general_purpose::URL_SAFE_NO_PAD.encode(mac.finalize().into_bytes())
+}
+
+/// Routes that only accept signed partner requests, keyed by method and route pattern
+#[derive(Debug, Clone, Default)]
+pub struct SignedRoutes {
+    routes: HashSet<(Method, String)>,
+}
+
+impl SignedRoutes {
+    /// Creates an empty table (no route requires a signature)
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Requires signed requests on a route
+    #[must_use]
+    pub fn route(mut self, method: Method, path: &str) -> Self {
+        self.routes.insert((method, path.to_owned()));
+        self
+    }
+
+    /// Whether a route requires signed requests
+    #[must_use]
+    pub fn contains(&self, method: &Method, path: &str) -> bool {
+        self.routes.contains(&(method.clone(), path.to_owned()))
+    }
+}
+
+/// Why a signed request was rejected
+#[derive(Debug)]
+enum SignatureError {
+    /// A signature header is missing or malformed
+    Missing,
+    /// The timestamp is outside the replay window
+    Expired,
+    /// The signature matches none of the partner's live keys
+    Mismatch,
+    /// The signature was already used
+    Replayed,
+    /// The partner's keys could not be loaded
+    Unavailable(PartnerError),
+}
+
+impl IntoResponse for SignatureError {
+    fn into_response(self) -> Response {
+        let (code, message) = match self {
+            Self::Missing => (ErrorCode::SignatureRequired, "Request must be signed with X-Partner-Id, X-Timestamp and X-Signature"),
+            Self::Expired => (ErrorCode::SignatureExpired, "Request timestamp is outside the allowed window"),
+            Self::Mismatch => (ErrorCode::SignatureMismatch, "Request signature does not match"),
+            Self::Replayed => (ErrorCode::SignatureReplayed, "Request signature was already used"),
+            Self::Unavailable(e) => {
+                error!(error = %e, "Failed to load partner keys");
+                return internal_error();
+            }
+        };
+        error_response(StatusCode::UNAUTHORIZED, code, message)
+    }
+}
+
+/// Checks partner signatures on the routes of a [`SignedRoutes`] table
+#[derive(Clone)]
+pub struct SignatureVerifier {
+    routes: Arc<SignedRoutes>,
+    partners: PartnerService,
+    window: chrono::Duration,
+    /// Signatures already accepted, with when they fall out of the window
+    seen: Arc<Mutex<HashMap<String, DateTime<Utc>>>>,
+}
+
+impl SignatureVerifier {
+    /// Verifier accepting requests signed at most `window` away from now
+    #[must_use]
+    pub fn new(routes: SignedRoutes, partners: PartnerService, window: Duration) -> Self {
+        Self {
+            routes: Arc::new(routes),
+            partners,
+            window: chrono::Duration::from_std(window).unwrap_or(chrono::Duration::MAX),
+            seen: Arc::default(),
+        }
+    }
+
+    /// Verifies a request, returning the partner that signed it
+    async fn verify(&self, headers: &HeaderMap, method: &Method, path_and_query: &str, body: &[u8]) -> Result<PartnerId, SignatureError> {
+        let header = |name: &HeaderName| headers.get(name).and_then(|value| value.to_str().ok());
+        let (Some(partner), Some(timestamp), Some(signature)) = (
+            header(&PARTNER_ID_HEADER),
+            header(&TIMESTAMP_HEADER).and_then(|timestamp| timestamp.parse::<i64>().ok()),
+            header(&SIGNATURE_HEADER).and_then(|signature| general_purpose::URL_SAFE_NO_PAD.decode(signature).ok()),
+        ) else {
+            return Err(SignatureError::Missing);
+        };
+
+        let now = self.partners.now();
+        let signed_at = DateTime::from_timestamp(timestamp, 0).ok_or(SignatureError::Expired)?;
+        if (now - signed_at).abs() > self.window {
+            return Err(SignatureError::Expired);
+        }
+
+        let keys = self.partners.live_keys(partner).await.map_err(SignatureError::Unavailable)?;
+        if !keys
+            .iter()
+            .any(|key| mac(key, timestamp, method, path_and_query, body).verify_slice(&signature).is_ok())
+        {
+            return Err(SignatureError::Mismatch);
+        }
+
+        let mut seen = self.seen.lock().unwrap_or_else(std::sync::PoisonError::into_inner);
+        seen.retain(|_, forget_at| *forget_at > now);
+        if seen.insert(general_purpose::STANDARD.encode(&signature), signed_at + self.window).is_some() {
+            return Err(SignatureError::Replayed);
+        }
+        Ok(PartnerId(partner.to_owned()))
+    }
+}
+
+/// Route-layer middleware rejecting unsigned or badly signed requests to signed routes
+///
+/// Accepted requests carry the signing partner as a [`PartnerId`] extension.
+pub async fn verify_signatures(State(verifier): State<SignatureVerifier>, request: Request, next: Next) -> Response {
+    let signed = request
+        .extensions()
+        .get::<MatchedPath>()
+        .is_some_and(|path| verifier.routes.contains(request.method(), path.as_str()));
+    if !signed {
+        return next.run(request).await;
+    }
+
+    let (mut parts, body) = request.into_parts();
+    let Ok(body) = to_bytes(body, MAX_SIGNED_BODY_BYTES).await else {
+        return error_response(StatusCode::BAD_REQUEST, ErrorCode::MalformedBody, "Request body could not be read");
+    };
+    let path_and_query = parts.uri.path_and_query().map_or_else(|| parts.uri.path(), |path| path.as_str());
+
+    match verifier.verify(&parts.headers, &parts.method, path_and_query, &body).await {
+        Ok(partner) => {
+            parts.extensions.insert(partner);
+            next.run(Request::from_parts(parts, Body::from(body))).await
+        }
+        Err(e) => {
+            warn!(error = ?e, "Rejected partner request");
+            e.into_response()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signatures_cover_every_signed_part() {
+        let signature = sign("secret", 1_700_000_000, &Method::POST, "/partner/sync?full=true", b"{}");
+        let key = signing_key("secret");
+        let verifies = |timestamp, method: &Method, path, body: &[u8]| {
+            let signature = general_purpose::URL_SAFE_NO_PAD.decode(&signature).unwrap();
+            mac(&key, timestamp, method, path, body).verify_slice(&signature).is_ok()
+        };
+
+        assert!(verifies(1_700_000_000, &Method::POST, "/partner/sync?full=true", b"{}"));
+        assert!(!verifies(1_700_000_001, &Method::POST, "/partner/sync?full=true", b"{}"));
+        assert!(!verifies(1_700_000_000, &Method::PUT, "/partner/sync?full=true", b"{}"));
+        assert!(!verifies(1_700_000_000, &Method::POST, "/partner/sync", b"{}"));
+        assert!(!verifies(1_700_000_000, &Method::POST, "/partner/sync?full=true", b"[]"));
+        assert_ne!(sign("other", 1_700_000_000, &Method::POST, "/partner/sync?full=true", b"{}"), signature);
+    }
+
+    #[test]
+    fn test_signed_routes_match_method_and_pattern() {
+        let routes = SignedRoutes::new().route(Method::PUT, "/users");
+
+        assert!(routes.contains(&Method::PUT, "/users"));
+        assert!(!routes.contains(&Method::GET, "/users"));
+        assert!(!routes.contains(&Method::PUT, "/users/{id}"));
+    }
+}
@@ -0,0 +1,35 @@
+//! Partner validation logic
+
+use crate::error_codes::ErrorCode;
+use crate::user::validation::common::{field_error, ValidationResult};
+
+/// Maximum length of a partner name
+pub const MAX_PARTNER_LENGTH: usize = 50;
+
+/// Validates a partner name
+///
+/// Partner names may contain lowercase letters, digits, `-` and `_`.
+pub fn validate_partner(partner: &str) -> ValidationResult {
+    let mut errors = Vec::new();
+
+    if partner.is_empty() {
+        errors.push(field_error("partner", ErrorCode::FieldEmpty, "Partner cannot be empty"));
+    }
+
+    if partner.len() > MAX_PARTNER_LENGTH {
+        errors.push(field_error("partner", ErrorCode::TooLong, format!("Partner cannot exceed {MAX_PARTNER_LENGTH} characters")));
+    }
+
+    if !partner
+        .chars()
+        .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_')
+    {
+        errors.push(field_error("partner", ErrorCode::InvalidCharacters, "Partner can only contain lowercase letters, digits, '-' and '_'"));
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
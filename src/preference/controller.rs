@@ -0,0 +1,104 @@
+//! Preference controller - HTTP handlers for user preferences
+
+use axum::{
+    Json,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use tracing::{error, warn};
+
+use crate::error_codes::{ErrorCode, ErrorResponse, internal_error};
+use crate::preference::PreferenceService;
+use crate::registry::Inject;
+use crate::user::UserId;
+use crate::user::domain::{ValidationErrorResponse, UserError};
+
+use super::domain::{PreferenceError, PreferencesPatch, UserPreferences};
+
+/// Maps preference errors to HTTP responses
+fn error_response(error: PreferenceError, user_id: i32) -> Response {
+    match error {
+        PreferenceError::ValidationError(errors) => {
+            warn!(?errors, user_id, "Controller: Preference validation failed");
+            (
+                StatusCode::BAD_REQUEST,
+                Json(ValidationErrorResponse { errors }),
+            ).into_response()
+        }
+        PreferenceError::UserNotFound => {
+            warn!(user_id, "Controller: User not found for preferences");
+            (
+                StatusCode::NOT_FOUND,
+                Json(ErrorResponse::new(ErrorCode::UserNotFound, error.to_string())),
+            ).into_response()
+        }
+        PreferenceError::DatabaseError(msg) => {
+            error!(error = %msg, user_id, "Controller: Database error in preference operation");
+            internal_error()
+        }
+        PreferenceError::UserServiceError(UserError::DatabaseError(e)) => {
+            error!(error = %e, user_id, "Controller: Database error in user lookup for preference operation");
+            e.into_response()
+        }
+        PreferenceError::UserServiceError(e) => {
+            error!(error = %e, user_id, "Controller: User service error in preference operation");
+            internal_error()
+        }
+    }
+}
+
+/// HTTP handler returning a user's preferences
+#[utoipa::path(
+    get,
+    path = "/users/{id}/preferences",
+    tag = "preferences",
+    params(
+        ("id" = i32, Path, description = "User ID")
+    ),
+    responses(
+        (status = 200, description = "Every known preference, with defaults for the keys the user has not set", body = UserPreferences),
+        (status = 400, description = "Invalid user ID", body = ValidationErrorResponse),
+        (status = 404, description = "User not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    )
+)]
+#[tracing::instrument(skip(preference_service), fields(user_id = user_id))]
+pub async fn get_preferences_handler(
+    Inject(preference_service): Inject<PreferenceService>,
+    UserId(user_id): UserId,
+) -> impl IntoResponse {
+    match preference_service.get_preferences(user_id).await {
+        Ok(preferences) => (StatusCode::OK, Json(preferences)).into_response(),
+        Err(e) => error_response(e, user_id),
+    }
+}
+
+/// HTTP handler merging values into a user's preferences
+///
+/// Keys absent from the body keep their value; `null` resets a key to its default.
+#[utoipa::path(
+    put,
+    path = "/users/{id}/preferences",
+    tag = "preferences",
+    params(
+        ("id" = i32, Path, description = "User ID")
+    ),
+    request_body = PreferencesPatch,
+    responses(
+        (status = 200, description = "Preferences after the merge", body = UserPreferences),
+        (status = 400, description = "Unknown key or invalid value", body = ValidationErrorResponse),
+        (status = 404, description = "User not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    )
+)]
+#[tracing::instrument(skip(preference_service), fields(user_id = user_id))]
+pub async fn update_preferences_handler(
+    Inject(preference_service): Inject<PreferenceService>,
+    UserId(user_id): UserId,
+    Json(patch): Json<PreferencesPatch>,
+) -> impl IntoResponse {
+    match preference_service.update_preferences(user_id, patch).await {
+        Ok(preferences) => (StatusCode::OK, Json(preferences)).into_response(),
+        Err(e) => error_response(e, user_id),
+    }
+}
@@ -0,0 +1,45 @@
+//! Preference domain models
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use utoipa::ToSchema;
+
+use crate::user::domain::{UserError, ValidationError};
+
+/// Effective preferences of a user
+#[derive(Serialize, ToSchema, Debug, Clone, PartialEq)]
+pub struct UserPreferences {
+    /// ID of the user
+    pub user_id: i32,
+    /// Every known preference: the user's value, or the default when unset
+    #[schema(value_type = Object)]
+    pub preferences: Map<String, Value>,
+    /// Keys the user has set, sorted
+    pub customized: Vec<String>,
+    /// When the user last changed a preference
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+/// Preference values to merge into a user's preferences; `null` resets a key to its default
+#[derive(Deserialize, ToSchema, Debug, Clone, Default)]
+#[serde(transparent)]
+#[schema(value_type = Object)]
+pub struct PreferencesPatch(pub Map<String, Value>);
+
+/// Domain errors for preference operations
+#[derive(Debug, thiserror::Error)]
+pub enum PreferenceError {
+    /// A key is unknown or its value does not match the key's schema
+    #[error("Validation failed")]
+    ValidationError(Vec<ValidationError>),
+    /// The user does not exist
+    #[error("User not found")]
+    UserNotFound,
+    /// Database operation failed
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+    /// Error from the underlying user service
+    #[error("User service error: {0}")]
+    UserServiceError(UserError),
+}
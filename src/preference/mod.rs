@@ -0,0 +1,19 @@
+//! Preference module
+//!
+//! Per-user preferences (theme, language, ...) stored as one JSONB object per
+//! user. Only the keys of [`validation::KNOWN_PREFERENCES`] are accepted, an
+//! update merges into the stored object, and keys a user never set fall back
+//! to the configured defaults.
+
+pub mod domain;
+pub mod repository;
+pub mod service;
+pub mod controller;
+pub mod validation;
+
+// Public exports
+pub use service::PreferenceService;
+pub use domain::{PreferenceError, PreferencesPatch, UserPreferences};
+
+// Export controller for OpenAPI documentation
+pub use controller::*;
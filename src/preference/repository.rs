@@ -0,0 +1,96 @@
+//! Preference repository - handles database operations
+//!
+//! This module is private to the preference module. All database access must
+//! go through `PreferenceService`.
+
+use chrono::{DateTime, Utc};
+use serde_json::{Map, Value};
+use sqlx::PgPool;
+use tracing::{error, info};
+
+use super::domain::PreferenceError;
+
+/// Preferences a user has set and when they last changed
+pub(super) struct StoredPreferences {
+    /// Values set by the user, by key
+    pub(super) values: Map<String, Value>,
+    /// When the user last changed a preference
+    pub(super) updated_at: DateTime<Utc>,
+}
+
+impl StoredPreferences {
+    /// Reads the stored JSONB object; anything else counts as empty
+    fn new(values: Value, updated_at: DateTime<Utc>) -> Self {
+        let values = match values {
+            Value::Object(values) => values,
+            Value::Null | Value::Bool(_) | Value::Number(_) | Value::String(_) | Value::Array(_) => Map::new(),
+        };
+        Self { values, updated_at }
+    }
+}
+
+/// Preference repository for database operations
+#[derive(Clone)]
+pub(super) struct PreferenceRepository {
+    pool: PgPool,
+}
+
+impl PreferenceRepository {
+    /// Creates a new `PreferenceRepository` instance
+    pub(super) fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Fetches the preferences a user has set, `None` if they never set one
+    pub(super) async fn find(&self, user_id: i32) -> Result<Option<StoredPreferences>, PreferenceError> {
+        info!(user_id, "Fetching user preferences from database");
+
+        let row = sqlx::query!(
+            "SELECT preferences, updated_at FROM user_preferences WHERE user_id = $1",
+            user_id
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| {
+            error!(error = %e, user_id, "Failed to fetch user preferences from database");
+            PreferenceError::DatabaseError(e.to_string())
+        })?;
+
+        Ok(row.map(|row| StoredPreferences::new(row.preferences, row.updated_at)))
+    }
+
+    /// Merges `set` into a user's preferences and removes the `reset` keys, in one statement
+    ///
+    /// The merge happens in the database, so concurrent updates of different
+    /// keys do not overwrite each other.
+    pub(super) async fn merge(
+        &self,
+        user_id: i32,
+        set: Map<String, Value>,
+        reset: &[String],
+        updated_at: DateTime<Utc>,
+    ) -> Result<StoredPreferences, PreferenceError> {
+        info!(user_id, set = set.len(), reset = reset.len(), "Merging user preferences in database");
+
+        let row = sqlx::query!(
+            "INSERT INTO user_preferences (user_id, preferences, updated_at) VALUES ($1, $2::JSONB - $3::TEXT[], $4)
+             ON CONFLICT (user_id) DO UPDATE SET
+                 preferences = (user_preferences.preferences || EXCLUDED.preferences) - $3::TEXT[],
+                 updated_at = EXCLUDED.updated_at
+             RETURNING preferences, updated_at",
+            user_id,
+            Value::Object(set),
+            reset,
+            updated_at
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| {
+            error!(error = %e, user_id, "Failed to merge user preferences in database");
+            PreferenceError::DatabaseError(e.to_string())
+        })?;
+
+        info!(user_id, "User preferences merged successfully in database");
+        Ok(StoredPreferences::new(row.preferences, row.updated_at))
+    }
+}
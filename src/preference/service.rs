@@ -0,0 +1,115 @@
+//! Preference service - business logic layer
+//!
+//! Validates preference updates against the known keys and resolves a user's
+//! effective preferences from their own values and the defaults. Talks to the
+//! user module only through `UserReadPort`.
+
+use std::sync::Arc;
+
+use serde_json::{Map, Value};
+use sqlx::PgPool;
+use tracing::{info, warn};
+
+use crate::clock::{SharedClock, SystemClock};
+use crate::user::{SharedUserReadPort, UserReadPort};
+
+use super::domain::{PreferenceError, PreferencesPatch, UserPreferences};
+use super::repository::{PreferenceRepository, StoredPreferences};
+use super::validation::{builtin_defaults, validate_patch, validate_preference};
+
+/// Preference service that handles business logic for user preferences
+#[derive(Clone)]
+pub struct PreferenceService {
+    repository: PreferenceRepository,
+    users: SharedUserReadPort,
+    clock: SharedClock,
+    defaults: Arc<Map<String, Value>>,
+}
+
+impl PreferenceService {
+    /// Creates a new `PreferenceService` instance with the built-in defaults
+    #[must_use] pub fn new(pool: PgPool, users: impl UserReadPort + 'static) -> Self {
+        Self {
+            repository: PreferenceRepository::new(pool),
+            users: Arc::new(users),
+            clock: SystemClock::shared(),
+            defaults: Arc::new(builtin_defaults()),
+        }
+    }
+
+    /// Uses `clock` instead of the system clock for timestamps
+    #[must_use] pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Overrides the built-in defaults with `defaults`; invalid entries are logged and ignored
+    #[must_use] pub fn with_defaults(mut self, defaults: &Map<String, Value>) -> Self {
+        let mut merged = builtin_defaults();
+        for (key, value) in defaults {
+            if let Err(errors) = validate_preference(key, value) {
+                warn!(key, ?errors, "PreferenceService: Ignoring invalid preference default");
+            } else {
+                merged.insert(key.clone(), value.clone());
+            }
+        }
+        self.defaults = Arc::new(merged);
+        self
+    }
+
+    /// Ensures the user exists before touching its preferences
+    async fn ensure_user_exists(&self, user_id: i32) -> Result<(), PreferenceError> {
+        match self.users.user_exists(user_id).await {
+            Ok(true) => Ok(()),
+            Ok(false) => {
+                warn!(user_id, "PreferenceService: User not found");
+                Err(PreferenceError::UserNotFound)
+            }
+            Err(e) => {
+                warn!(user_id, error = ?e, "PreferenceService: Error checking user existence");
+                Err(PreferenceError::UserServiceError(e))
+            }
+        }
+    }
+
+    /// Returns a user's effective preferences
+    pub async fn get_preferences(&self, user_id: i32) -> Result<UserPreferences, PreferenceError> {
+        info!(user_id, "PreferenceService: Fetching preferences");
+
+        self.ensure_user_exists(user_id).await?;
+        let stored = self.repository.find(user_id).await?;
+        Ok(self.resolve(user_id, stored))
+    }
+
+    /// Merges `patch` into a user's preferences; `null` values reset keys to their defaults
+    pub async fn update_preferences(&self, user_id: i32, patch: PreferencesPatch) -> Result<UserPreferences, PreferenceError> {
+        info!(user_id, keys = patch.0.len(), "PreferenceService: Updating preferences");
+
+        validate_patch(&patch.0).map_err(|errors| {
+            warn!(user_id, ?errors, "PreferenceService: Validation failed for preferences");
+            PreferenceError::ValidationError(errors)
+        })?;
+        self.ensure_user_exists(user_id).await?;
+        let (reset, set): (Map<String, Value>, Map<String, Value>) = patch.0.into_iter().partition(|(_, value)| value.is_null());
+        let reset: Vec<String> = reset.into_iter().map(|(key, _)| key).collect();
+        let stored = self.repository.merge(user_id, set, &reset, self.clock.now()).await?;
+        Ok(self.resolve(user_id, Some(stored)))
+    }
+
+    /// Overlays the valid stored values on the defaults
+    ///
+    /// Values stored before a key was dropped or its schema narrowed are skipped.
+    fn resolve(&self, user_id: i32, stored: Option<StoredPreferences>) -> UserPreferences {
+        let mut preferences = Map::clone(&self.defaults);
+        let mut customized = Vec::new();
+        let updated_at = stored.as_ref().map(|stored| stored.updated_at);
+        for (key, value) in stored.map(|stored| stored.values).unwrap_or_default() {
+            if validate_preference(&key, &value).is_ok() {
+                customized.push(key.clone());
+                preferences.insert(key, value);
+            }
+        }
+        customized.sort_unstable();
+        UserPreferences { user_id, preferences, customized, updated_at }
+    }
+}
@@ -0,0 +1,118 @@
+//! Preference validation logic
+
+use serde_json::{Map, Value};
+
+use crate::error_codes::ErrorCode;
+use crate::user::validation::common::{field_error, ValidationResult};
+
+/// Schema of a preference value
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PreferenceKind {
+    /// `true` or `false`
+    Boolean,
+    /// One of the listed strings
+    Choice(&'static [&'static str]),
+    /// A non-empty string of at most this many characters
+    Text(usize),
+    /// An integer within the bounds, inclusive
+    Integer(i64, i64),
+}
+
+/// Keys users may set and the schema of their values
+pub const KNOWN_PREFERENCES: &[(&str, PreferenceKind)] = &[
+    ("theme", PreferenceKind::Choice(&["light", "dark", "system"])),
+    ("language", PreferenceKind::Text(35)),
+    ("timezone", PreferenceKind::Text(64)),
+    ("email_notifications", PreferenceKind::Boolean),
+    ("page_size", PreferenceKind::Integer(1, 200)),
+];
+
+/// Defaults of the known preferences unless configured otherwise
+#[must_use]
+pub fn builtin_defaults() -> Map<String, Value> {
+    [
+        ("theme", Value::from("system")),
+        ("language", Value::from("en")),
+        ("email_notifications", Value::from(true)),
+    ]
+    .into_iter()
+    .map(|(key, value)| (key.to_owned(), value))
+    .collect()
+}
+
+/// Checks a value against the schema of `key`; unknown keys are rejected
+pub fn validate_preference(key: &str, value: &Value) -> ValidationResult {
+    let Some((_, kind)) = KNOWN_PREFERENCES.iter().find(|(known, _)| *known == key) else {
+        return Err(vec![field_error(key, ErrorCode::UnknownPreference, "Unknown preference")]);
+    };
+    let valid = match *kind {
+        PreferenceKind::Boolean => value.is_boolean(),
+        PreferenceKind::Choice(choices) => value.as_str().is_some_and(|choice| choices.contains(&choice)),
+        PreferenceKind::Text(max) => value.as_str().is_some_and(|text| !text.trim().is_empty() && text.chars().count() <= max),
+        PreferenceKind::Integer(min, max) => value.as_i64().is_some_and(|number| (min..=max).contains(&number)),
+    };
+    if valid {
+        return Ok(());
+    }
+    let message = match *kind {
+        PreferenceKind::Boolean => "Must be true or false".to_owned(),
+        PreferenceKind::Choice(choices) => format!("Must be one of: {}", choices.join(", ")),
+        PreferenceKind::Text(max) => format!("Must be a non-empty string of at most {max} characters"),
+        PreferenceKind::Integer(min, max) => format!("Must be an integer between {min} and {max}"),
+    };
+    Err(vec![field_error(key, ErrorCode::InvalidPreferenceValue, message)])
+}
+
+/// Validates a patch: every key known, every value valid or `null`
+pub fn validate_patch(patch: &Map<String, Value>) -> ValidationResult {
+    let errors: Vec<_> = patch
+        .iter()
+        .filter_map(|(key, value)| {
+            if value.is_null() {
+                (!is_known(key)).then(|| vec![field_error(key, ErrorCode::UnknownPreference, "Unknown preference")])
+            } else {
+                validate_preference(key, value).err()
+            }
+        })
+        .flatten()
+        .collect();
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+/// Whether `key` is a known preference
+#[must_use]
+pub fn is_known(key: &str) -> bool {
+    KNOWN_PREFERENCES.iter().any(|(known, _)| *known == key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn patch(value: &Value) -> Map<String, Value> {
+        value.as_object().cloned().unwrap()
+    }
+
+    #[test]
+    fn test_validate_patch() {
+        assert!(validate_patch(&patch(&json!({ "theme": "dark", "page_size": 50, "language": null }))).is_ok());
+        assert!(validate_patch(&patch(&json!({ "theme": "pink" }))).is_err());
+        assert!(validate_patch(&patch(&json!({ "page_size": 0 }))).is_err());
+        assert!(validate_patch(&patch(&json!({ "email_notifications": "yes" }))).is_err());
+        assert!(validate_patch(&patch(&json!({ "language": " " }))).is_err());
+        assert_eq!(validate_patch(&patch(&json!({ "favorite_color": null, "shoe_size": 42 }))).unwrap_err().len(), 2);
+    }
+
+    #[test]
+    fn test_builtin_defaults_are_valid() {
+        for (key, value) in builtin_defaults() {
+            assert!(validate_preference(&key, &value).is_ok(), "{key}");
+        }
+    }
+}
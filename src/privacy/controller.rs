@@ -0,0 +1,191 @@
+//! Privacy controller - HTTP handlers for data subject requests
+
+use axum::{
+    Json,
+    extract::{Path, Query},
+    http::{StatusCode, header},
+    response::{IntoResponse, Response},
+};
+use tracing::{error, warn};
+
+use crate::error_codes::{ErrorCode, ErrorResponse, internal_error};
+use crate::links::LinkBuilder;
+use crate::privacy::PrivacyService;
+use crate::registry::Inject;
+use crate::user::UserId;
+use crate::user::domain::{UserError, ValidationErrorResponse};
+
+use super::domain::{DataExport, DownloadQuery, EraseParams, ErasureReport, PrivacyError};
+
+/// Maps privacy errors to HTTP responses
+fn error_response(error: PrivacyError, user_id: Option<i32>) -> Response {
+    match error {
+        PrivacyError::UserNotFound | PrivacyError::ExportNotFound => {
+            warn!(user_id, error = %error, "Controller: Privacy resource not found");
+            let code = if matches!(error, PrivacyError::UserNotFound) { ErrorCode::UserNotFound } else { ErrorCode::ExportNotFound };
+            (
+                StatusCode::NOT_FOUND,
+                Json(ErrorResponse::new(code, error.to_string())),
+            ).into_response()
+        }
+        PrivacyError::InvalidSignature => {
+            warn!(user_id, "Controller: Download link with invalid signature");
+            (
+                StatusCode::FORBIDDEN,
+                Json(ErrorResponse::new(ErrorCode::InvalidSignature, error.to_string())),
+            ).into_response()
+        }
+        PrivacyError::LinkExpired => {
+            warn!(user_id, "Controller: Download link expired");
+            (
+                StatusCode::GONE,
+                Json(ErrorResponse::new(ErrorCode::LinkExpired, error.to_string())),
+            ).into_response()
+        }
+        PrivacyError::DatabaseError(msg) => {
+            error!(error = %msg, user_id, "Controller: Database error in privacy operation");
+            internal_error()
+        }
+        PrivacyError::UserServiceError(UserError::DatabaseError(e)) => {
+            error!(error = %e, user_id, "Controller: Database error in user lookup for privacy operation");
+            e.into_response()
+        }
+        PrivacyError::UserServiceError(e) => {
+            error!(error = %e, user_id, "Controller: User service error in privacy operation");
+            internal_error()
+        }
+    }
+}
+
+/// HTTP handler for requesting an export of a user's data
+///
+/// The export is built in the background; poll the returned export until its
+/// `download_url` appears.
+#[utoipa::path(
+    post,
+    path = "/users/{id}/export",
+    tag = "privacy",
+    params(
+        ("id" = i32, Path, description = "User ID")
+    ),
+    responses(
+        (status = 202, description = "Export queued", body = DataExport),
+        (status = 400, description = "Invalid user ID", body = ValidationErrorResponse),
+        (status = 404, description = "User not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    )
+)]
+#[tracing::instrument(skip(privacy_service), fields(user_id = user_id))]
+pub async fn request_export_handler(
+    Inject(privacy_service): Inject<PrivacyService>,
+    UserId(user_id): UserId,
+) -> impl IntoResponse {
+    match privacy_service.request_export(user_id).await {
+        Ok(export) => (
+            StatusCode::ACCEPTED,
+            [(header::LOCATION, format!("/users/{user_id}/exports/{}", export.id))],
+            Json(export),
+        ).into_response(),
+        Err(e) => error_response(e, Some(user_id)),
+    }
+}
+
+/// HTTP handler for checking a data export
+#[utoipa::path(
+    get,
+    path = "/users/{id}/exports/{export_id}",
+    tag = "privacy",
+    params(
+        ("id" = i32, Path, description = "User ID"),
+        ("export_id" = i32, Path, description = "Export ID")
+    ),
+    responses(
+        (status = 200, description = "Export status, with a signed download link once ready", body = DataExport),
+        (status = 400, description = "Invalid user ID", body = ValidationErrorResponse),
+        (status = 404, description = "Export not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    )
+)]
+#[tracing::instrument(skip(privacy_service, links, path), fields(user_id = user_id, export_id = path.1))]
+pub async fn get_export_handler(
+    Inject(privacy_service): Inject<PrivacyService>,
+    Inject(links): Inject<LinkBuilder>,
+    UserId(user_id): UserId,
+    Path(path): Path<(i32, i32)>,
+) -> impl IntoResponse {
+    match privacy_service.get_export(user_id, path.1).await {
+        Ok(export) => (StatusCode::OK, Json(export.with_download_url(&links))).into_response(),
+        Err(e) => error_response(e, Some(user_id)),
+    }
+}
+
+/// HTTP handler for downloading a data export bundle through its signed link
+#[utoipa::path(
+    get,
+    path = "/exports/{id}/download",
+    tag = "privacy",
+    params(
+        ("id" = i32, Path, description = "Export ID"),
+        ("expires" = i64, Query, description = "Link expiry as a Unix timestamp"),
+        ("signature" = String, Query, description = "Link signature")
+    ),
+    responses(
+        (status = 200, description = "JSON bundle of everything stored about the user", body = Object),
+        (status = 400, description = "Missing link parameters"),
+        (status = 403, description = "Invalid signature", body = ErrorResponse),
+        (status = 404, description = "Export not found or not ready", body = ErrorResponse),
+        (status = 410, description = "Download link expired", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    )
+)]
+#[tracing::instrument(skip(privacy_service, query), fields(export_id = export_id))]
+pub async fn download_export_handler(
+    Inject(privacy_service): Inject<PrivacyService>,
+    Path(export_id): Path<i32>,
+    Query(query): Query<DownloadQuery>,
+) -> impl IntoResponse {
+    match privacy_service.download_export(export_id, &query).await {
+        Ok(bundle) => (
+            StatusCode::OK,
+            [(
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"export-{export_id}.json\""),
+            )],
+            Json(bundle),
+        ).into_response(),
+        Err(e) => error_response(e, None),
+    }
+}
+
+/// HTTP handler for erasing a user's personal data (right to erasure)
+///
+/// Anonymizes the user and deletes or blanks their personal data in one
+/// transaction; accounts and ledger entries are kept, so balances and ledger
+/// sums are unchanged. With `dry_run=true` nothing is changed and the report
+/// shows what an erasure would do.
+#[utoipa::path(
+    post,
+    path = "/users/{id}/erase",
+    tag = "privacy",
+    params(
+        ("id" = i32, Path, description = "User ID"),
+        ("dry_run" = Option<bool>, Query, description = "Only report what would change (default: false)")
+    ),
+    responses(
+        (status = 200, description = "Rows changed per table", body = ErasureReport),
+        (status = 400, description = "Invalid user ID", body = ValidationErrorResponse),
+        (status = 404, description = "User not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    )
+)]
+#[tracing::instrument(skip(privacy_service, params), fields(user_id = user_id, dry_run = params.dry_run))]
+pub async fn erase_user_handler(
+    Inject(privacy_service): Inject<PrivacyService>,
+    UserId(user_id): UserId,
+    Query(params): Query<EraseParams>,
+) -> impl IntoResponse {
+    match privacy_service.erase_user(user_id, params.dry_run.unwrap_or(false)).await {
+        Ok(report) => (StatusCode::OK, Json(report)).into_response(),
+        Err(e) => error_response(e, Some(user_id)),
+    }
+}
@@ -0,0 +1,128 @@
+//! Privacy domain models
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::links::LinkBuilder;
+use crate::user::domain::UserError;
+
+/// Progress of a data export
+#[derive(Serialize, Deserialize, ToSchema, Debug, Clone, Copy, PartialEq, Eq, sqlx::Type)]
+#[sqlx(type_name = "export_status", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum ExportStatus {
+    /// Waiting for the export job
+    Pending,
+    /// Bundle built and downloadable until the link expires
+    Ready,
+    /// The export job could not build the bundle
+    Failed,
+}
+
+/// A data export request and, once ready, its download link
+#[derive(Serialize, ToSchema, Debug, Clone)]
+pub struct DataExport {
+    /// Unique export identifier
+    pub id: i32,
+    /// User whose data is exported
+    pub user_id: i32,
+    /// Progress of the export
+    pub status: ExportStatus,
+    /// When the export was requested
+    pub requested_at: DateTime<Utc>,
+    /// When the bundle was built
+    pub completed_at: Option<DateTime<Utc>>,
+    /// When the download link stops working
+    pub expires_at: Option<DateTime<Utc>>,
+    /// Signed download link, while the bundle is ready and not expired
+    pub download_url: Option<String>,
+    /// Signed query of the download link, filled in by the service
+    #[serde(skip)]
+    pub(super) download: Option<DownloadQuery>,
+}
+
+impl DataExport {
+    /// Fills in `download_url` from the signed download query, if any
+    #[must_use]
+    pub fn with_download_url(mut self, links: &LinkBuilder) -> Self {
+        self.download_url = self
+            .download
+            .as_ref()
+            .map(|query| links.link_with_query(&format!("/exports/{}/download", self.id), query).href);
+        self
+    }
+}
+
+/// Query string of a signed download link
+#[derive(Serialize, Deserialize, ToSchema, Debug, Clone, PartialEq, Eq)]
+pub struct DownloadQuery {
+    /// Expiry of the link as a Unix timestamp
+    pub expires: i64,
+    /// Signature over the export ID and expiry
+    pub signature: String,
+}
+
+/// What an erasure does to the affected rows of a table
+#[derive(Serialize, ToSchema, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ErasureAction {
+    /// Personal data in the rows is overwritten; the rows stay
+    Anonymized,
+    /// The rows are removed
+    Deleted,
+}
+
+/// Rows of one table changed by an erasure
+#[derive(Serialize, ToSchema, Debug, Clone, PartialEq, Eq)]
+pub struct ErasureChange {
+    /// Table name
+    pub table: &'static str,
+    /// What happens to the rows
+    pub action: ErasureAction,
+    /// Number of rows affected
+    pub rows: u64,
+}
+
+/// Outcome of erasing a user's personal data
+#[derive(Serialize, ToSchema, Debug, Clone)]
+pub struct ErasureReport {
+    /// Erased user
+    pub user_id: i32,
+    /// Whether this was a dry run, in which case nothing was changed
+    pub dry_run: bool,
+    /// Rows changed (or, for a dry run, that would be changed) per table
+    pub changes: Vec<ErasureChange>,
+    /// When the data was erased (or, for a dry run, when the report was made)
+    pub erased_at: DateTime<Utc>,
+}
+
+/// Query parameters for erasing a user
+#[derive(Deserialize, ToSchema, Debug, Clone, Default)]
+pub struct EraseParams {
+    /// Only report what would change (default: false)
+    pub dry_run: Option<bool>,
+}
+
+/// Domain errors for privacy operations
+#[derive(Debug, thiserror::Error)]
+pub enum PrivacyError {
+    /// The user does not exist
+    #[error("User not found")]
+    UserNotFound,
+    /// The export does not exist or is not ready
+    #[error("Export not found")]
+    ExportNotFound,
+    /// The download link was not signed by this service
+    #[error("Invalid download signature")]
+    InvalidSignature,
+    /// The download link has expired
+    #[error("Download link expired")]
+    LinkExpired,
+    /// Database operation failed
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+    /// Error from the underlying user service
+    #[error("User service error: {0}")]
+    UserServiceError(UserError),
+}
@@ -0,0 +1,43 @@
+//! Data export job
+//!
+//! Builds queued data exports through
+//! [`PrivacyService::build_pending_exports`]. Each export is claimed and
+//! built in one transaction, so a run interrupted mid-way leaves the
+//! unfinished export queued for the next run.
+
+use futures_util::future::BoxFuture;
+
+use crate::jobs::{Job, JobError};
+
+use super::PrivacyService;
+
+/// Periodic job building queued data exports
+#[derive(Clone)]
+pub struct DataExportJob {
+    privacy: PrivacyService,
+    batch_size: u32,
+}
+
+impl DataExportJob {
+    /// Builds up to `batch_size` exports per run
+    #[must_use]
+    pub fn new(privacy: PrivacyService, batch_size: u32) -> Self {
+        Self {
+            privacy,
+            batch_size: batch_size.max(1),
+        }
+    }
+}
+
+impl Job for DataExportJob {
+    fn name(&self) -> &'static str {
+        "data-export"
+    }
+
+    fn run(&self) -> BoxFuture<'_, Result<(), JobError>> {
+        Box::pin(async move {
+            self.privacy.build_pending_exports(self.batch_size).await?;
+            Ok(())
+        })
+    }
+}
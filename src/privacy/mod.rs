@@ -0,0 +1,23 @@
+//! Privacy module
+//!
+//! GDPR data subject rights. `POST /users/{id}/export` queues a data export
+//! (right of access); a background job gathers everything stored about the
+//! user into a JSON bundle, downloadable through a signed, expiring link.
+//! `POST /users/{id}/erase` anonymizes the user's personal data (right to
+//! erasure) while keeping accounts and the ledger intact.
+
+pub mod controller;
+pub mod domain;
+pub mod export;
+pub mod repository;
+pub mod service;
+pub mod signing;
+
+// Public exports
+pub use domain::{DataExport, DownloadQuery, ErasureAction, ErasureChange, ErasureReport, ExportStatus, PrivacyError};
+pub use export::DataExportJob;
+pub use service::PrivacyService;
+pub use signing::ExportSigner;
+
+// Export controller for OpenAPI documentation
+pub use controller::*;
@@ -0,0 +1,354 @@
+//! Privacy repository - handles database operations
+//!
+//! This module is private to the privacy module. All database access must go
+//! through `PrivacyService`. Building a bundle reads the tables of the user,
+//! address, tag, bank and audit modules directly, in a single statement, so
+//! the bundle is a consistent snapshot.
+
+use chrono::{DateTime, Utc};
+use serde_json::{Value, json};
+use sqlx::{PgPool, Postgres, Transaction};
+use tracing::{error, info};
+
+use crate::audit::{self, AuditRecord};
+use crate::db::DbError;
+
+use super::domain::{DataExport, ErasureAction, ErasureChange, ErasureReport, ExportStatus, PrivacyError};
+
+/// Placeholder replacing erased names and free-text fields
+const ERASED: &str = "[erased]";
+
+/// Maps a database error to `PrivacyError`, logging it with `context`
+fn database_error(context: &'static str) -> impl FnOnce(sqlx::Error) -> PrivacyError {
+    move |e| {
+        error!(error = %e, "{context}");
+        PrivacyError::DatabaseError(DbError::from(e).to_string())
+    }
+}
+
+/// A `data_exports` row without its bundle; converted into `DataExport` before leaving the repository
+struct DataExportRow {
+    id: i32,
+    user_id: i32,
+    status: ExportStatus,
+    requested_at: DateTime<Utc>,
+    completed_at: Option<DateTime<Utc>>,
+    expires_at: Option<DateTime<Utc>>,
+}
+
+impl From<DataExportRow> for DataExport {
+    fn from(row: DataExportRow) -> Self {
+        Self {
+            id: row.id,
+            user_id: row.user_id,
+            status: row.status,
+            requested_at: row.requested_at,
+            completed_at: row.completed_at,
+            expires_at: row.expires_at,
+            download_url: None,
+            download: None,
+        }
+    }
+}
+
+/// Privacy repository for database operations
+#[derive(Clone)]
+pub(super) struct PrivacyRepository {
+    pool: PgPool,
+}
+
+impl PrivacyRepository {
+    /// Creates a new `PrivacyRepository` instance
+    pub(super) fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Queues a data export for a user and records the request in the audit log
+    pub(super) async fn insert_export(&self, user_id: i32, requested_at: DateTime<Utc>) -> Result<DataExport, PrivacyError> {
+        info!(user_id, "Queueing data export in database");
+
+        let mut tx = self.pool.begin().await.map_err(database_error("Failed to begin transaction for data export"))?;
+        let export = sqlx::query_as!(
+            DataExportRow,
+            r#"INSERT INTO data_exports (user_id, requested_at) VALUES ($1, $2)
+             RETURNING id, user_id, status AS "status: ExportStatus", requested_at, completed_at, expires_at"#,
+            user_id,
+            requested_at
+        )
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(database_error("Failed to insert data export into database"))
+        .map(DataExport::from)?;
+
+        let event = AuditRecord {
+            user_id: Some(user_id),
+            account_id: None,
+            action: audit::recorder::DATA_EXPORT_REQUESTED,
+            details: json!({ "export_id": export.id }),
+        };
+        audit::record(&mut *tx, event, requested_at)
+            .await
+            .map_err(database_error("Failed to record data export in audit log"))?;
+        tx.commit().await.map_err(database_error("Failed to commit data export"))?;
+        Ok(export)
+    }
+
+    /// Finds an export of a user
+    pub(super) async fn find_export(&self, user_id: i32, export_id: i32) -> Result<Option<DataExport>, PrivacyError> {
+        sqlx::query_as!(
+            DataExportRow,
+            r#"SELECT id, user_id, status AS "status: ExportStatus", requested_at, completed_at, expires_at
+             FROM data_exports WHERE id = $1 AND user_id = $2"#,
+            export_id,
+            user_id
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(database_error("Failed to fetch data export from database"))
+        .map(|row| row.map(DataExport::from))
+    }
+
+    /// Returns the bundle of a ready export
+    pub(super) async fn find_bundle(&self, export_id: i32) -> Result<Option<Value>, PrivacyError> {
+        sqlx::query_scalar!(
+            r#"SELECT bundle AS "bundle!" FROM data_exports WHERE id = $1 AND status = 'ready' AND bundle IS NOT NULL"#,
+            export_id
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(database_error("Failed to fetch data export bundle from database"))
+    }
+
+    /// Builds the bundle of the oldest pending export, returning its ID
+    ///
+    /// The export row is claimed with `SKIP LOCKED`, so concurrent job runs
+    /// (or instances) build different exports. Returns `None` when no export
+    /// is pending. An export whose bundle cannot be built is marked failed,
+    /// so it does not hold up the queue.
+    pub(super) async fn build_next_export(
+        &self,
+        completed_at: DateTime<Utc>,
+        expires_at: DateTime<Utc>,
+    ) -> Result<Option<i32>, PrivacyError> {
+        let mut tx = self.pool.begin().await.map_err(database_error("Failed to begin transaction for export build"))?;
+        let claimed = sqlx::query!(
+            "SELECT id, user_id FROM data_exports WHERE status = 'pending' ORDER BY id LIMIT 1 FOR UPDATE SKIP LOCKED"
+        )
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(database_error("Failed to claim pending data export"))?;
+        let Some(claimed) = claimed else {
+            return Ok(None);
+        };
+
+        info!(export_id = claimed.id, user_id = claimed.user_id, "Building data export bundle in database");
+        match Self::store_bundle(&mut tx, claimed.id, claimed.user_id, completed_at, expires_at).await {
+            Ok(()) => {
+                tx.commit().await.map_err(database_error("Failed to commit data export bundle"))?;
+                Ok(Some(claimed.id))
+            }
+            Err(e) => {
+                drop(tx);
+                sqlx::query!("UPDATE data_exports SET status = 'failed', completed_at = $2 WHERE id = $1", claimed.id, completed_at)
+                    .execute(&self.pool)
+                    .await
+                    .map_err(database_error("Failed to mark data export as failed"))?;
+                Err(e)
+            }
+        }
+    }
+
+    /// Gathers everything stored about `user_id` and stores it as the bundle of `export_id`
+    async fn store_bundle(
+        tx: &mut Transaction<'static, Postgres>,
+        export_id: i32,
+        user_id: i32,
+        completed_at: DateTime<Utc>,
+        expires_at: DateTime<Utc>,
+    ) -> Result<(), PrivacyError> {
+        let bundle = sqlx::query_scalar!(
+            r#"SELECT jsonb_build_object(
+                 'profile', (SELECT to_jsonb(u) FROM users u WHERE u.id = $1),
+                 'addresses', COALESCE((SELECT jsonb_agg(to_jsonb(a) ORDER BY a.id) FROM addresses a WHERE a.user_id = $1), '[]'),
+                 'tags', COALESCE((SELECT jsonb_agg(t.name ORDER BY t.name)
+                                   FROM tags t JOIN user_tags ut ON ut.tag_id = t.id WHERE ut.user_id = $1), '[]'),
+                 'accounts', COALESCE((SELECT jsonb_agg(to_jsonb(acc) ORDER BY acc.id) FROM accounts acc WHERE acc.user_id = $1), '[]'),
+                 'beneficiaries', COALESCE((SELECT jsonb_agg(to_jsonb(b) ORDER BY b.id) FROM beneficiaries b WHERE b.user_id = $1), '[]'),
+                 'external_identities', COALESCE((SELECT jsonb_agg(to_jsonb(x) ORDER BY x.system)
+                                                  FROM external_identities x WHERE x.user_id = $1), '[]'),
+                 'preferences', COALESCE((SELECT p.preferences FROM user_preferences p WHERE p.user_id = $1), '{}'),
+                 'ledger_entries', COALESCE((SELECT jsonb_agg(jsonb_build_object(
+                                                 'id', e.id,
+                                                 'transaction_id', t.id,
+                                                 'kind', t.kind,
+                                                 'account_id', e.account_id,
+                                                 'amount_cents', e.amount_cents,
+                                                 'created_at', t.created_at
+                                             ) ORDER BY e.id)
+                                             FROM ledger_entries e
+                                             JOIN ledger_transactions t ON t.id = e.transaction_id
+                                             JOIN accounts acc ON acc.id = e.account_id
+                                             WHERE acc.user_id = $1), '[]'),
+                 'audit_events', COALESCE((SELECT jsonb_agg(to_jsonb(ev) ORDER BY ev.id) FROM audit_events ev WHERE ev.user_id = $1), '[]')
+             ) AS "bundle!""#,
+            user_id
+        )
+        .fetch_one(&mut **tx)
+        .await
+        .map_err(database_error("Failed to gather data export bundle"))?;
+
+        let bundle = json!({
+            "export_id": export_id,
+            "user_id": user_id,
+            "generated_at": completed_at,
+            "data": bundle,
+        });
+        sqlx::query!(
+            "UPDATE data_exports SET status = 'ready', bundle = $2, completed_at = $3, expires_at = $4 WHERE id = $1",
+            export_id,
+            bundle,
+            completed_at,
+            expires_at
+        )
+        .execute(&mut **tx)
+        .await
+        .map_err(database_error("Failed to store data export bundle"))?;
+        Ok(())
+    }
+
+    /// Erases the personal data of a user in one transaction, returning `None` when the user does not exist
+    ///
+    /// The user row, accounts and ledger entries stay, so balances, ledger
+    /// sums and foreign keys are unchanged: the user is anonymized (including
+    /// its external ID) and archived, free-text account hold reasons and audit
+    /// event details are blanked, and addresses, tags, beneficiaries, external
+    /// identities, preferences, data exports and previous versions of the user are deleted. A dry run
+    /// performs the same statements and rolls them back, so the report counts
+    /// exactly the rows an erasure would change.
+    pub(super) async fn erase_user(
+        &self,
+        user_id: i32,
+        erased_at: DateTime<Utc>,
+        dry_run: bool,
+    ) -> Result<Option<ErasureReport>, PrivacyError> {
+        info!(user_id, dry_run, "Erasing user data in database");
+
+        let mut tx = self.pool.begin().await.map_err(database_error("Failed to begin transaction for erasure"))?;
+        let users = sqlx::query!(
+            "UPDATE users SET name = $2, age = 0, status = 'archived', external_id = NULL, metadata = NULL, erased_at = $3, updated_at = $3 WHERE id = $1",
+            user_id,
+            ERASED,
+            erased_at
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(database_error("Failed to anonymize user"))?
+        .rows_affected();
+        if users == 0 {
+            return Ok(None);
+        }
+
+        let owned = Self::delete_owned_rows(&mut tx, user_id).await?;
+        let accounts = sqlx::query!(
+            "UPDATE accounts SET frozen_reason = $2 WHERE user_id = $1 AND frozen_reason IS NOT NULL AND frozen_reason <> $2",
+            user_id,
+            ERASED
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(database_error("Failed to anonymize account hold reasons"))?
+        .rows_affected();
+        let audit_events = sqlx::query!(
+            "UPDATE audit_events SET details = '{}' WHERE user_id = $1 AND details <> '{}'",
+            user_id
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(database_error("Failed to anonymize audit events"))?
+        .rows_affected();
+        let exports = sqlx::query!("DELETE FROM data_exports WHERE user_id = $1", user_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(database_error("Failed to delete data exports"))?
+            .rows_affected();
+        // Runs last: anonymizing the user above recorded its previous version
+        let history = sqlx::query!("DELETE FROM users_history WHERE user_id = $1", user_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(database_error("Failed to delete user history"))?
+            .rows_affected();
+
+        let change = |table, action, rows| ErasureChange { table, action, rows };
+        let report = ErasureReport {
+            user_id,
+            dry_run,
+            changes: std::iter::once(change("users", ErasureAction::Anonymized, users))
+                .chain(owned)
+                .chain([
+                    change("accounts", ErasureAction::Anonymized, accounts),
+                    change("audit_events", ErasureAction::Anonymized, audit_events),
+                    change("data_exports", ErasureAction::Deleted, exports),
+                    change("users_history", ErasureAction::Deleted, history),
+                ])
+                .collect(),
+            erased_at,
+        };
+
+        if dry_run {
+            tx.rollback().await.map_err(database_error("Failed to roll back erasure dry run"))?;
+        } else {
+            let event = AuditRecord {
+                user_id: Some(user_id),
+                account_id: None,
+                action: audit::recorder::USER_ERASED,
+                details: json!({}),
+            };
+            audit::record(&mut *tx, event, erased_at)
+                .await
+                .map_err(database_error("Failed to record erasure in audit log"))?;
+            tx.commit().await.map_err(database_error("Failed to commit erasure"))?;
+        }
+        Ok(Some(report))
+    }
+
+    /// Deletes the rows that only describe `user_id` (addresses, tags, beneficiaries, identities, preferences)
+    async fn delete_owned_rows(
+        tx: &mut Transaction<'static, Postgres>,
+        user_id: i32,
+    ) -> Result<[ErasureChange; 5], PrivacyError> {
+        let addresses = sqlx::query!("DELETE FROM addresses WHERE user_id = $1", user_id)
+            .execute(&mut **tx)
+            .await
+            .map_err(database_error("Failed to delete addresses"))?
+            .rows_affected();
+        let tags = sqlx::query!("DELETE FROM user_tags WHERE user_id = $1", user_id)
+            .execute(&mut **tx)
+            .await
+            .map_err(database_error("Failed to detach tags"))?
+            .rows_affected();
+        let beneficiaries = sqlx::query!("DELETE FROM beneficiaries WHERE user_id = $1", user_id)
+            .execute(&mut **tx)
+            .await
+            .map_err(database_error("Failed to delete beneficiaries"))?
+            .rows_affected();
+        let identities = sqlx::query!("DELETE FROM external_identities WHERE user_id = $1", user_id)
+            .execute(&mut **tx)
+            .await
+            .map_err(database_error("Failed to delete external identities"))?
+            .rows_affected();
+        let preferences = sqlx::query!("DELETE FROM user_preferences WHERE user_id = $1", user_id)
+            .execute(&mut **tx)
+            .await
+            .map_err(database_error("Failed to delete preferences"))?
+            .rows_affected();
+
+        let deleted = |table, rows| ErasureChange { table, action: ErasureAction::Deleted, rows };
+        Ok([
+            deleted("addresses", addresses),
+            deleted("user_tags", tags),
+            deleted("beneficiaries", beneficiaries),
+            deleted("external_identities", identities),
+            deleted("user_preferences", preferences),
+        ])
+    }
+}
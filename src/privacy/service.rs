@@ -0,0 +1,154 @@
+//! Privacy service - business logic layer
+//!
+//! Queues data exports, builds them from the export job and serves their
+//! bundles through signed download links, and erases users' personal data.
+//! Talks to the user module only through `UserReadPort`.
+
+use std::sync::Arc;
+
+use chrono::Duration;
+use serde_json::Value;
+use sqlx::PgPool;
+use tracing::{info, warn};
+
+use crate::clock::{SharedClock, SystemClock};
+use crate::user::{SharedUserReadPort, UserReadPort};
+
+use super::domain::{DataExport, DownloadQuery, ErasureReport, ExportStatus, PrivacyError};
+use super::repository::PrivacyRepository;
+use super::signing::ExportSigner;
+
+/// Default time a download link stays valid
+const DEFAULT_LINK_TTL_HOURS: i64 = 24;
+
+/// Privacy service that handles data subject requests
+#[derive(Clone)]
+pub struct PrivacyService {
+    repository: PrivacyRepository,
+    users: SharedUserReadPort,
+    signer: ExportSigner,
+    clock: SharedClock,
+    link_ttl: Duration,
+}
+
+impl PrivacyService {
+    /// Creates a new `PrivacyService` instance signing download links with `signer`
+    #[must_use] pub fn new(pool: PgPool, users: impl UserReadPort + 'static, signer: ExportSigner) -> Self {
+        Self {
+            repository: PrivacyRepository::new(pool),
+            users: Arc::new(users),
+            signer,
+            clock: SystemClock::shared(),
+            link_ttl: Duration::hours(DEFAULT_LINK_TTL_HOURS),
+        }
+    }
+
+    /// Uses `clock` instead of the system clock for timestamps and link expiry
+    #[must_use] pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Keeps download links valid for `ttl` after the bundle is built
+    #[must_use] pub fn with_link_ttl(mut self, ttl: Duration) -> Self {
+        self.link_ttl = ttl;
+        self
+    }
+
+    /// Ensures the user exists before touching its data
+    async fn ensure_user_exists(&self, user_id: i32) -> Result<(), PrivacyError> {
+        match self.users.user_exists(user_id).await {
+            Ok(true) => Ok(()),
+            Ok(false) => {
+                warn!(user_id, "PrivacyService: User not found");
+                Err(PrivacyError::UserNotFound)
+            }
+            Err(e) => {
+                warn!(user_id, error = ?e, "PrivacyService: Error checking user existence");
+                Err(PrivacyError::UserServiceError(e))
+            }
+        }
+    }
+
+    /// Queues an export of everything stored about a user
+    pub async fn request_export(&self, user_id: i32) -> Result<DataExport, PrivacyError> {
+        info!(user_id, "PrivacyService: Requesting data export");
+
+        self.ensure_user_exists(user_id).await?;
+        let export = self.repository.insert_export(user_id, self.clock.now()).await?;
+        info!(target: "audit", user_id, export_id = export.id, "Data export requested");
+        Ok(export)
+    }
+
+    /// Returns an export of a user, with a signed download query while it can be downloaded
+    pub async fn get_export(&self, user_id: i32, export_id: i32) -> Result<DataExport, PrivacyError> {
+        let mut export = self
+            .repository
+            .find_export(user_id, export_id)
+            .await?
+            .ok_or(PrivacyError::ExportNotFound)?;
+
+        if export.status == ExportStatus::Ready {
+            export.download = export
+                .expires_at
+                .filter(|expires_at| *expires_at > self.clock.now())
+                .map(|expires_at| DownloadQuery {
+                    expires: expires_at.timestamp(),
+                    signature: self.signer.sign(export.id, expires_at.timestamp()),
+                });
+        }
+        Ok(export)
+    }
+
+    /// Builds up to `batch_size` pending exports, returning how many were built
+    pub async fn build_pending_exports(&self, batch_size: u32) -> Result<u32, PrivacyError> {
+        let mut built = 0;
+        while built < batch_size {
+            let now = self.clock.now();
+            let Some(export_id) = self.repository.build_next_export(now, now + self.link_ttl).await? else {
+                break;
+            };
+            info!(export_id, "PrivacyService: Data export built");
+            built += 1;
+        }
+        Ok(built)
+    }
+
+    /// Returns the bundle of an export for a signed download link
+    pub async fn download_export(&self, export_id: i32, query: &DownloadQuery) -> Result<Value, PrivacyError> {
+        if !self.signer.verify(export_id, query.expires, &query.signature) {
+            warn!(export_id, "PrivacyService: Invalid download signature");
+            return Err(PrivacyError::InvalidSignature);
+        }
+        if query.expires <= self.clock.now().timestamp() {
+            warn!(export_id, "PrivacyService: Download link expired");
+            return Err(PrivacyError::LinkExpired);
+        }
+
+        let bundle = self
+            .repository
+            .find_bundle(export_id)
+            .await?
+            .ok_or(PrivacyError::ExportNotFound)?;
+        info!(target: "audit", export_id, "Data export downloaded");
+        Ok(bundle)
+    }
+
+    /// Erases a user's personal data, or with `dry_run` only reports what would change
+    pub async fn erase_user(&self, user_id: i32, dry_run: bool) -> Result<ErasureReport, PrivacyError> {
+        info!(user_id, dry_run, "PrivacyService: Erasing user data");
+
+        let report = self
+            .repository
+            .erase_user(user_id, self.clock.now(), dry_run)
+            .await?
+            .ok_or_else(|| {
+                warn!(user_id, "PrivacyService: User not found");
+                PrivacyError::UserNotFound
+            })?;
+        if !dry_run {
+            info!(target: "audit", user_id, "User data erased");
+        }
+        Ok(report)
+    }
+}
@@ -0,0 +1,93 @@
+//! Download link signing
+//!
+//! Links carry an expiry and an HMAC-SHA256 over the export ID and that
+//! expiry, so they can be handed out without authentication and cannot be
+//! extended or pointed at another export.
+
+use std::sync::{Arc, OnceLock};
+
+use base64::{Engine as _, engine::general_purpose};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use tracing::warn;
+
+use crate::config::PrivacyConfig;
+
+/// HMAC-SHA256
+type HmacSha256 = Hmac<Sha256>;
+
+/// Random key shared by all signers of this process when no key is configured
+static PROCESS_KEY: OnceLock<[u8; 32]> = OnceLock::new();
+
+/// Signs and verifies data export download links
+#[derive(Clone)]
+pub struct ExportSigner {
+    key: Arc<[u8]>,
+}
+
+impl ExportSigner {
+    /// Signer using `key`
+    #[must_use]
+    pub fn new(key: impl AsRef<[u8]>) -> Self {
+        Self { key: Arc::from(key.as_ref()) }
+    }
+
+    /// Signer using the configured key, or a random per-process key
+    ///
+    /// Links signed with the random key stop working when the process restarts
+    /// and are not accepted by other instances.
+    #[must_use]
+    pub fn from_config(config: &PrivacyConfig) -> Self {
+        config.export_signing_key.as_deref().map_or_else(
+            || {
+                warn!("EXPORT_SIGNING_KEY is not set; export download links only work on this instance until it restarts");
+                Self::new(PROCESS_KEY.get_or_init(rand::random))
+            },
+            Self::new,
+        )
+    }
+
+    /// MAC over the export ID and link expiry
+    fn mac(&self, export_id: i32, expires: i64) -> HmacSha256 {
+        let mut mac = HmacSha256::new_from_slice(&self.key).expect("HMAC accepts keys of any length");
+        mac.update(format!("{export_id}:{expires}").as_bytes());
+        mac
+    }
+
+    /// Signature of the download link of `export_id` expiring at `expires` (Unix seconds)
+    #[must_use]
+    pub fn sign(&self, export_id: i32, expires: i64) -> String {
+        general_purpose::URL_SAFE_NO_PAD.encode(self.mac(export_id, expires).finalize().into_bytes())
+    }
+
+    /// Whether `signature` was produced by [`ExportSigner::sign`] for this export and expiry
+    #[must_use]
+    pub fn verify(&self, export_id: i32, expires: i64, signature: &str) -> bool {
+        general_purpose::URL_SAFE_NO_PAD
+            .decode(signature)
+            .is_ok_and(|signature| self.mac(export_id, expires).verify_slice(&signature).is_ok())
+    }
+}
+
+impl std::fmt::Debug for ExportSigner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ExportSigner").finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signature_binds_export_and_expiry() {
+        let signer = ExportSigner::new("secret");
+        let signature = signer.sign(7, 1_700_000_000);
+
+        assert!(signer.verify(7, 1_700_000_000, &signature));
+        assert!(!signer.verify(8, 1_700_000_000, &signature), "Another export");
+        assert!(!signer.verify(7, 1_700_000_001, &signature), "Extended expiry");
+        assert!(!ExportSigner::new("other").verify(7, 1_700_000_000, &signature), "Another key");
+        assert!(!signer.verify(7, 1_700_000_000, "not base64!"));
+    }
+}
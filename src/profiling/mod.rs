@@ -0,0 +1,186 @@
+//! On-demand profiling (feature `profiling`)
+//!
+//! `GET /debug/pprof/profile?seconds=10` records for the requested time and
+//! returns a profile in the folded-stack format read by `inferno`,
+//! `flamegraph.pl` and speedscope. Native stack sampling (`pprof-rs`) is not
+//! part of this build, so [`SpanProfiler`] profiles at span granularity
+//! instead: it is a tracing layer adding up the time spent inside each stack
+//! of spans (handlers, services and repositories are instrumented), which
+//! shows where requests spend their time, serialization and pagination
+//! included. Outside a recording the layer only checks a flag.
+
+use std::{
+    collections::HashMap,
+    sync::{
+        Arc, Mutex, OnceLock, PoisonError,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+use axum::{
+    Json,
+    extract::Query,
+    http::{StatusCode, header},
+    response::{IntoResponse, Response},
+};
+use serde::Deserialize;
+use tracing::{Subscriber, span::Id};
+use tracing_subscriber::{Layer, layer::Context, registry::LookupSpan};
+
+use crate::error_codes::{ErrorCode, ErrorResponse};
+
+/// Longest recording a single request may ask for, in seconds
+pub const MAX_PROFILE_SECS: u64 = 60;
+
+/// Recording time used when the request does not give one, in seconds
+const DEFAULT_PROFILE_SECS: u64 = 10;
+
+/// Tracing layer measuring the time spent in each stack of spans while recording
+///
+/// Clones share the same recording.
+#[derive(Debug, Clone, Default)]
+pub struct SpanProfiler {
+    /// Whether a recording is in progress
+    recording: Arc<AtomicBool>,
+    /// Self time per folded stack (`root;child;leaf`), in microseconds
+    stacks: Arc<Mutex<HashMap<String, u64>>>,
+}
+
+/// Time of the current entry into a span, stored in the span's extensions
+struct Entered {
+    /// When the span was entered
+    at: Instant,
+    /// Time spent in child spans since then
+    children: Duration,
+}
+
+impl SpanProfiler {
+    /// Profiler installed in the global subscriber by `config::tracing::init`
+    pub fn global() -> &'static Self {
+        static GLOBAL: OnceLock<SpanProfiler> = OnceLock::new();
+        GLOBAL.get_or_init(Self::default)
+    }
+
+    /// Starts a recording; `false` when one is already in progress
+    pub fn start(&self) -> bool {
+        let started = self
+            .recording
+            .compare_exchange(false, true, Ordering::AcqRel, Ordering::Acquire)
+            .is_ok();
+        if started {
+            self.stacks.lock().unwrap_or_else(PoisonError::into_inner).clear();
+        }
+        started
+    }
+
+    /// Stops the recording and returns it as folded stacks, one `stack micros` line each
+    pub fn finish(&self) -> String {
+        self.recording.store(false, Ordering::Release);
+        let stacks = std::mem::take(&mut *self.stacks.lock().unwrap_or_else(PoisonError::into_inner));
+        let mut lines: Vec<_> = stacks
+            .into_iter()
+            .filter(|(_, micros)| *micros > 0)
+            .map(|(stack, micros)| format!("{stack} {micros}\n"))
+            .collect();
+        lines.sort_unstable();
+        lines.concat()
+    }
+
+    /// Records for `duration`; `None` when a recording is already in progress
+    pub async fn profile(&self, duration: Duration) -> Option<String> {
+        if !self.start() {
+            return None;
+        }
+        tokio::time::sleep(duration).await;
+        Some(self.finish())
+    }
+}
+
+impl<S> Layer<S> for SpanProfiler
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_enter(&self, id: &Id, ctx: Context<'_, S>) {
+        if !self.recording.load(Ordering::Relaxed) {
+            return;
+        }
+        if let Some(span) = ctx.span(id) {
+            span.extensions_mut().replace(Entered { at: Instant::now(), children: Duration::ZERO });
+        }
+    }
+
+    fn on_exit(&self, id: &Id, ctx: Context<'_, S>) {
+        if !self.recording.load(Ordering::Relaxed) {
+            return;
+        }
+        let Some(span) = ctx.span(id) else { return };
+        let Some(entered) = span.extensions_mut().remove::<Entered>() else { return };
+        let elapsed = entered.at.elapsed();
+        if let Some(parent) = span.parent()
+            && let Some(parent_entered) = parent.extensions_mut().get_mut::<Entered>()
+        {
+            parent_entered.children += elapsed;
+        }
+        let stack = span.scope().from_root().map(|span| span.name()).collect::<Vec<_>>().join(";");
+        let micros = u64::try_from(elapsed.saturating_sub(entered.children).as_micros()).unwrap_or(u64::MAX);
+        *self
+            .stacks
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .entry(stack)
+            .or_default() += micros;
+    }
+}
+
+/// Query parameters of a profile request
+#[derive(Deserialize, Debug)]
+pub struct ProfileParams {
+    /// Recording time in seconds (default 10, at most [`MAX_PROFILE_SECS`])
+    pub seconds: Option<u64>,
+}
+
+/// HTTP handler recording a profile and returning it as folded stacks
+#[tracing::instrument]
+pub async fn profile_handler(Query(params): Query<ProfileParams>) -> Response {
+    let seconds = params.seconds.unwrap_or(DEFAULT_PROFILE_SECS).clamp(1, MAX_PROFILE_SECS);
+    match SpanProfiler::global().profile(Duration::from_secs(seconds)).await {
+        Some(folded) => ([(header::CONTENT_TYPE, "text/plain; charset=utf-8")], folded).into_response(),
+        None => (
+            StatusCode::CONFLICT,
+            Json(ErrorResponse::new(ErrorCode::ProfileInProgress, "A profile is already being recorded".to_owned())),
+        )
+            .into_response(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracing_subscriber::{Registry, layer::SubscriberExt};
+
+    #[test]
+    fn test_records_self_time_per_stack() {
+        let profiler = SpanProfiler::default();
+        let subscriber = Registry::default().with(profiler.clone());
+
+        let folded = tracing::subscriber::with_default(subscriber, || {
+            tracing::info_span!("unrecorded").in_scope(|| std::thread::sleep(Duration::from_millis(1)));
+            assert!(profiler.start());
+            assert!(!profiler.start(), "Only one recording may run at a time");
+            tracing::info_span!("list_users").in_scope(|| {
+                tracing::info_span!("serialize").in_scope(|| std::thread::sleep(Duration::from_millis(5)));
+            });
+            profiler.finish()
+        });
+
+        let micros = |stack: &str| {
+            folded
+                .lines()
+                .find_map(|line| line.strip_prefix(stack)?.strip_prefix(' ')?.parse::<u64>().ok())
+        };
+        assert!(micros("list_users;serialize").is_some_and(|micros| micros >= 5000));
+        assert!(micros("list_users").is_none_or(|micros| micros < 5000), "Child time is not self time");
+        assert!(!folded.contains("unrecorded"));
+    }
+}
@@ -0,0 +1,82 @@
+//! Read-only mode
+//!
+//! With `READ_ONLY` set, every mutating request (`POST`, `PUT`, `PATCH`,
+//! `DELETE`) is rejected with 503 and `Retry-After` before reaching a
+//! handler, and `/health` reports a degraded `read_only` component. Operators switch it on to keep
+//! serving reads from a replica while the primary fails over. `AppBuilder::build` starts no
+//! background jobs in this mode, since they all write.
+
+use std::time::Duration;
+
+use axum::{
+    Json,
+    extract::{Request, State},
+    http::{HeaderValue, Method, StatusCode, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use tracing::warn;
+
+use crate::error_codes::{ErrorCode, ErrorResponse};
+
+/// Message returned for rejected writes and reported in `/health`
+pub const READ_ONLY_MESSAGE: &str = "The service is in read-only mode; writes are temporarily disabled";
+
+/// How long clients are advised to wait before retrying a rejected write
+pub const RETRY_AFTER: Duration = Duration::from_secs(30);
+
+/// Whether `method` can change state
+fn is_mutating(method: &Method) -> bool {
+    matches!(*method, Method::POST | Method::PUT | Method::PATCH | Method::DELETE)
+}
+
+/// Middleware rejecting mutating requests with 503 while `read_only` is set
+pub async fn reject_writes(State(read_only): State<bool>, request: Request, next: Next) -> Response {
+    if read_only && is_mutating(request.method()) {
+        warn!(method = %request.method(), uri = %request.uri(), "Rejected write in read-only mode");
+        return (
+            StatusCode::SERVICE_UNAVAILABLE,
+            [(header::RETRY_AFTER, HeaderValue::from(RETRY_AFTER.as_secs()))],
+            Json(ErrorResponse::new(ErrorCode::ReadOnly, READ_ONLY_MESSAGE.to_owned())),
+        )
+            .into_response();
+    }
+    next.run(request).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{Router, body::Body, middleware, routing::get};
+    use tower::ServiceExt;
+
+    async fn send(read_only: bool, method: Method) -> Response {
+        let app = Router::new()
+            .route("/users", get(|| async { "read" }).post(|| async { "write" }).delete(|| async { "delete" }))
+            .layer(middleware::from_fn_with_state(read_only, reject_writes));
+        app.oneshot(Request::builder().method(method).uri("/users").body(Body::empty()).unwrap())
+            .await
+            .unwrap()
+    }
+
+    async fn status(read_only: bool, method: Method) -> StatusCode {
+        send(read_only, method).await.status()
+    }
+
+    #[tokio::test]
+    async fn test_read_only_rejects_only_writes() {
+        assert_eq!(status(true, Method::GET).await, StatusCode::OK);
+        assert_eq!(status(true, Method::POST).await, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(status(true, Method::DELETE).await, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(status(false, Method::POST).await, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_rejected_writes_advise_when_to_retry() {
+        let rejected = send(true, Method::POST).await;
+        let served = send(true, Method::GET).await;
+
+        assert_eq!(rejected.headers()[header::RETRY_AFTER], "30");
+        assert!(!served.headers().contains_key(header::RETRY_AFTER));
+    }
+}
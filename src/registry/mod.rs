@@ -0,0 +1,142 @@
+//! Typed service registry
+//!
+//! Services live in a [`ServiceRegistry`] keyed by their type instead of in
+//! dedicated `AppState` fields. Handlers ask for what they use with the
+//! [`Inject`] extractor, so adding a service (cache, mailer, job queue) means
+//! registering it once in `create_app_with_modules` or from a module's
+//! `Module::services`, with no change to `AppState` or unrelated handlers.
+
+use std::{
+    any::{Any, TypeId, type_name},
+    collections::HashMap,
+    sync::Arc,
+};
+
+use axum::{
+    extract::FromRequestParts,
+    http::request::Parts,
+    response::{IntoResponse, Response},
+};
+use tracing::error;
+
+use crate::AppState;
+use crate::error_codes::internal_error;
+
+/// Services keyed by type
+///
+/// Cloning is cheap: clones share the registered services.
+#[derive(Clone, Default)]
+pub struct ServiceRegistry {
+    services: Arc<HashMap<TypeId, Arc<dyn Any + Send + Sync>>>,
+}
+
+impl std::fmt::Debug for ServiceRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ServiceRegistry").field("len", &self.services.len()).finish()
+    }
+}
+
+impl ServiceRegistry {
+    /// Creates an empty registry
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `service`, replacing any service of the same type
+    #[must_use]
+    pub fn with<T: Send + Sync + 'static>(mut self, service: T) -> Self {
+        self.insert(service);
+        self
+    }
+
+    /// Adds `service` in place, replacing any service of the same type
+    pub fn insert<T: Send + Sync + 'static>(&mut self, service: T) {
+        Arc::make_mut(&mut self.services).insert(TypeId::of::<T>(), Arc::new(service));
+    }
+
+    /// Adds every service of `other`, replacing those of the same types
+    pub fn extend(&mut self, other: &Self) {
+        Arc::make_mut(&mut self.services).extend(other.services.iter().map(|(id, service)| (*id, Arc::clone(service))));
+    }
+
+    /// The registered service of type `T`, if any
+    #[must_use]
+    pub fn get<T: Send + Sync + 'static>(&self) -> Option<&T> {
+        self.services
+            .get(&TypeId::of::<T>())
+            .and_then(|service| service.downcast_ref())
+    }
+}
+
+/// Extracts a clone of the registered service of type `T`
+///
+/// Responds with 500 when no such service is registered, which is a wiring
+/// bug rather than a client error.
+#[derive(Debug, Clone)]
+pub struct Inject<T>(pub T);
+
+/// Rejection used when a handler asks for a service that was never registered
+#[derive(Debug)]
+pub struct MissingService(&'static str);
+
+impl IntoResponse for MissingService {
+    fn into_response(self) -> Response {
+        error!(service = self.0, "Handler requested a service that is not registered");
+        internal_error()
+    }
+}
+
+impl<T: Clone + Send + Sync + 'static> FromRequestParts<AppState> for Inject<T> {
+    type Rejection = MissingService;
+
+    async fn from_request_parts(_parts: &mut Parts, state: &AppState) -> Result<Self, Self::Rejection> {
+        state
+            .services
+            .get::<T>()
+            .cloned()
+            .map(Self)
+            .ok_or(MissingService(type_name::<T>()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::StatusCode;
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Mailer(&'static str);
+
+    #[test]
+    fn test_services_are_keyed_by_type() {
+        let services = ServiceRegistry::new().with(Mailer("smtp")).with(42_u32);
+
+        assert_eq!(services.get::<Mailer>(), Some(&Mailer("smtp")));
+        assert_eq!(services.get::<u32>(), Some(&42));
+        assert_eq!(services.get::<String>(), None);
+    }
+
+    #[test]
+    fn test_insert_replaces_and_leaves_clones_untouched() {
+        let original = ServiceRegistry::new().with(Mailer("smtp"));
+        let mut replaced = original.clone();
+        replaced.insert(Mailer("ses"));
+
+        assert_eq!(original.get::<Mailer>(), Some(&Mailer("smtp")));
+        assert_eq!(replaced.get::<Mailer>(), Some(&Mailer("ses")));
+    }
+
+    #[tokio::test]
+    async fn test_missing_service_is_a_server_error() {
+        let state = AppState {
+            services: ServiceRegistry::new(),
+            envelope_by_default: false,
+        };
+        let (mut parts, ()) = axum::http::Request::new(()).into_parts();
+
+        let rejection = Inject::<Mailer>::from_request_parts(&mut parts, &state).await.unwrap_err();
+
+        assert_eq!(rejection.into_response().status(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+}
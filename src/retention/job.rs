@@ -0,0 +1,46 @@
+//! Retention purge job
+//!
+//! Runs [`RetentionService::purge`] and reports the rows purged per table as
+//! structured events on the `metrics` log target. Purging is idempotent, so
+//! an interrupted run is simply continued by the next one.
+
+use futures_util::future::BoxFuture;
+use tracing::info;
+
+use crate::jobs::{Job, JobError};
+
+use super::RetentionService;
+
+/// Periodic job enforcing the retention policies
+#[derive(Clone)]
+pub struct RetentionJob {
+    retention: RetentionService,
+}
+
+impl RetentionJob {
+    /// Enforces the policies of `retention`
+    #[must_use]
+    pub fn new(retention: RetentionService) -> Self {
+        Self { retention }
+    }
+}
+
+impl Job for RetentionJob {
+    fn name(&self) -> &'static str {
+        "retention-purge"
+    }
+
+    fn run(&self) -> BoxFuture<'_, Result<(), JobError>> {
+        Box::pin(async move {
+            for outcome in self.retention.purge().await? {
+                info!(
+                    target: "metrics",
+                    retention_table = outcome.target.table(),
+                    retention_purged = outcome.purged,
+                    "Retention purge"
+                );
+            }
+            Ok(())
+        })
+    }
+}
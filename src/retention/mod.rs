@@ -0,0 +1,242 @@
+//! Data retention
+//!
+//! A [`RetentionPolicy`] says how long rows of a table are kept. The
+//! [`RetentionService`] purges rows past their policy in batches, one short
+//! statement per batch, so purging a large backlog never holds long locks;
+//! [`RetentionJob`] runs it on the scheduler and reports the purged rows per
+//! table on the `metrics` log target (`retention_table`, `retention_purged`).
+//!
+//! Audit events, data exports, webhook delivery logs and the users change
+//! feed have policies. Users are never purged: erased users keep their row so
+//! accounts and the ledger stay intact. Neither is the change log, which
+//! consumers rebuild their state from. There are no soft-deleted users in
+//! this application; such tables get a [`RetentionTarget`] when they are
+//! introduced.
+
+mod job;
+
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use sqlx::PgPool;
+use tracing::{error, info};
+
+use crate::clock::{SharedClock, SystemClock};
+use crate::config::RetentionConfig;
+
+pub use job::RetentionJob;
+
+/// Default number of rows deleted per statement
+const DEFAULT_BATCH_SIZE: u32 = 1000;
+
+/// Table a retention policy applies to
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RetentionTarget {
+    /// `audit_events`, aged by `occurred_at`
+    AuditEvents,
+    /// `data_exports` that were built or failed, aged by link expiry (or completion)
+    DataExports,
+    /// `webhook_deliveries`, aged by `attempted_at`
+    WebhookDeliveries,
+    /// `user_changes`, aged by `changed_at`
+    UserChanges,
+}
+
+impl RetentionTarget {
+    /// Name of the table
+    #[must_use]
+    pub const fn table(self) -> &'static str {
+        match self {
+            Self::AuditEvents => "audit_events",
+            Self::DataExports => "data_exports",
+            Self::WebhookDeliveries => "webhook_deliveries",
+            Self::UserChanges => "user_changes",
+        }
+    }
+}
+
+/// How long rows of a table are kept
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetentionPolicy {
+    /// Table the policy applies to
+    pub target: RetentionTarget,
+    /// Rows older than this are purged
+    pub max_age: Duration,
+}
+
+impl RetentionPolicy {
+    /// The policies configured by `config`; tables configured with 0 days are kept forever
+    #[must_use]
+    pub fn from_config(config: &RetentionConfig) -> Vec<Self> {
+        [
+            (RetentionTarget::AuditEvents, config.audit_events_days),
+            (RetentionTarget::DataExports, config.data_exports_days),
+            (RetentionTarget::WebhookDeliveries, config.webhook_deliveries_days),
+            (RetentionTarget::UserChanges, config.user_changes_days),
+        ]
+        .into_iter()
+        .filter(|(_, days)| *days > 0)
+        .map(|(target, days)| Self { target, max_age: Duration::days(i64::from(days)) })
+        .collect()
+    }
+}
+
+/// Rows purged from one table in one run
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PurgeOutcome {
+    /// Table purged
+    pub target: RetentionTarget,
+    /// Rows deleted
+    pub purged: u64,
+}
+
+/// Errors from purging
+#[derive(Debug, thiserror::Error)]
+pub enum RetentionError {
+    /// Database operation failed
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+/// Purges rows past their retention policy
+#[derive(Clone)]
+pub struct RetentionService {
+    pool: PgPool,
+    policies: Vec<RetentionPolicy>,
+    batch_size: u32,
+    clock: SharedClock,
+}
+
+impl RetentionService {
+    /// Creates a new `RetentionService` enforcing `policies`
+    #[must_use]
+    pub fn new(pool: PgPool, policies: Vec<RetentionPolicy>) -> Self {
+        Self {
+            pool,
+            policies,
+            batch_size: DEFAULT_BATCH_SIZE,
+            clock: SystemClock::shared(),
+        }
+    }
+
+    /// Uses `clock` instead of the system clock for ages
+    #[must_use]
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Deletes at most `batch_size` rows per statement
+    #[must_use]
+    pub fn with_batch_size(mut self, batch_size: u32) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// The policies being enforced
+    #[must_use]
+    pub fn policies(&self) -> &[RetentionPolicy] {
+        &self.policies
+    }
+
+    /// Purges every table past its policy, returning the rows deleted per table
+    pub async fn purge(&self) -> Result<Vec<PurgeOutcome>, RetentionError> {
+        let now = self.clock.now();
+        let mut outcomes = Vec::with_capacity(self.policies.len());
+        for policy in &self.policies {
+            let mut purged = 0;
+            loop {
+                let deleted = self.delete_batch(policy.target, now - policy.max_age).await?;
+                purged += deleted;
+                if deleted < u64::from(self.batch_size) {
+                    break;
+                }
+            }
+            info!(table = policy.target.table(), purged, "RetentionService: Purged expired rows");
+            outcomes.push(PurgeOutcome { target: policy.target, purged });
+        }
+        Ok(outcomes)
+    }
+
+    /// Deletes up to one batch of rows of `target` older than `cutoff`
+    async fn delete_batch(&self, target: RetentionTarget, cutoff: DateTime<Utc>) -> Result<u64, RetentionError> {
+        let limit = i64::from(self.batch_size);
+        let result = match target {
+            RetentionTarget::AuditEvents => {
+                sqlx::query!(
+                    "DELETE FROM audit_events WHERE id IN (
+                         SELECT id FROM audit_events WHERE occurred_at < $1 ORDER BY occurred_at LIMIT $2
+                     )",
+                    cutoff,
+                    limit
+                )
+                .execute(&self.pool)
+                .await
+            }
+            RetentionTarget::DataExports => {
+                sqlx::query!(
+                    "DELETE FROM data_exports WHERE id IN (
+                         SELECT id FROM data_exports
+                         WHERE status <> 'pending' AND COALESCE(expires_at, completed_at) < $1
+                         LIMIT $2
+                     )",
+                    cutoff,
+                    limit
+                )
+                .execute(&self.pool)
+                .await
+            }
+            RetentionTarget::WebhookDeliveries => {
+                sqlx::query!(
+                    "DELETE FROM webhook_deliveries WHERE id IN (
+                         SELECT id FROM webhook_deliveries WHERE attempted_at < $1 ORDER BY attempted_at LIMIT $2
+                     )",
+                    cutoff,
+                    limit
+                )
+                .execute(&self.pool)
+                .await
+            }
+            RetentionTarget::UserChanges => {
+                sqlx::query!(
+                    "DELETE FROM user_changes WHERE seq IN (
+                         SELECT seq FROM user_changes WHERE changed_at < $1 ORDER BY changed_at LIMIT $2
+                     )",
+                    cutoff,
+                    limit
+                )
+                .execute(&self.pool)
+                .await
+            }
+        };
+
+        result.map(|result| result.rows_affected()).map_err(|e| {
+            error!(error = %e, table = target.table(), "Failed to purge expired rows");
+            RetentionError::DatabaseError(e.to_string())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_policies_from_config_skip_disabled_tables() {
+        let config = RetentionConfig {
+            audit_events_days: 90,
+            data_exports_days: 0,
+            webhook_deliveries_days: 0,
+            user_changes_days: 0,
+            interval_secs: 3600,
+            batch_size: 100,
+        };
+
+        let policies = RetentionPolicy::from_config(&config);
+
+        assert_eq!(
+            policies,
+            [RetentionPolicy { target: RetentionTarget::AuditEvents, max_age: Duration::days(90) }]
+        );
+    }
+}
@@ -0,0 +1,116 @@
+//! JSON Schema module
+//!
+//! Serves standalone JSON Schemas (draft 2020-12) for the domain models at
+//! `GET /api-docs/schemas/{name}.json`, for consumers that validate payloads
+//! outside of `OpenAPI` tooling. Schemas are derived with `schemars`.
+
+use axum::{
+    extract::Path,
+    http::StatusCode,
+    response::{IntoResponse, Json, Response},
+};
+use schemars::{JsonSchema, Schema, schema_for};
+use tracing::warn;
+
+use crate::{address, error_codes, tag, user};
+
+/// Builds a schema generator for a model type
+type SchemaFn = fn() -> Schema;
+
+/// Models exposed as standalone schemas, keyed by their public name
+const SCHEMAS: &[(&str, SchemaFn)] = &[
+    ("User", schema::<user::User>),
+    ("CreateUser", schema::<user::CreateUser>),
+    ("UpdateUser", schema::<user::UpdateUser>),
+    ("UserStatus", schema::<user::domain::UserStatus>),
+    ("PaginatedUsersResponse", schema::<user::domain::PaginatedUsersResponse>),
+    ("ApiResponse", schema::<user::domain::ApiResponse>),
+    ("ValidationError", schema::<user::domain::ValidationError>),
+    ("ValidationErrorResponse", schema::<user::domain::ValidationErrorResponse>),
+    ("ErrorResponse", schema::<error_codes::ErrorResponse>),
+    ("BulkIdsRequest", schema::<user::domain::BulkIdsRequest>),
+    ("BulkUpdateUsers", schema::<user::domain::BulkUpdateUsers>),
+    ("BulkOperationResponse", schema::<user::domain::BulkOperationResponse>),
+    ("Address", schema::<address::Address>),
+    ("CreateAddress", schema::<address::CreateAddress>),
+    ("UpdateAddress", schema::<address::UpdateAddress>),
+    ("Tag", schema::<tag::Tag>),
+    ("TagSuggestion", schema::<tag::TagSuggestion>),
+];
+
+/// Generates the root schema for `T`
+fn schema<T: JsonSchema>() -> Schema {
+    schema_for!(T)
+}
+
+/// Names of all models with a published schema
+#[must_use]
+pub fn schema_names() -> Vec<&'static str> {
+    SCHEMAS.iter().map(|(name, _)| *name).collect()
+}
+
+/// Looks up the JSON Schema for a model by name
+#[must_use]
+pub fn schema_by_name(name: &str) -> Option<Schema> {
+    SCHEMAS
+        .iter()
+        .find(|(candidate, _)| *candidate == name)
+        .map(|(_, generate)| generate())
+}
+
+/// HTTP handler listing the available schemas
+pub async fn list_schemas_handler() -> Json<Vec<&'static str>> {
+    Json(schema_names())
+}
+
+/// HTTP handler serving a single schema (`{name}.json`)
+#[tracing::instrument]
+pub async fn get_schema_handler(Path(file): Path<String>) -> Response {
+    if let Some(schema) = file.strip_suffix(".json").and_then(schema_by_name) {
+        (StatusCode::OK, Json(schema)).into_response()
+    } else {
+        warn!(file = %file, "Unknown JSON schema requested");
+        StatusCode::NOT_FOUND.into_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_schema_by_name() {
+        let schema = schema_by_name("CreateUser").expect("CreateUser schema should exist");
+        let value = schema.as_value();
+
+        assert_eq!(value["title"], "CreateUser");
+        assert_eq!(value["type"], "object");
+        assert!(value["properties"]["name"].is_object());
+        assert!(value["properties"]["age"].is_object());
+    }
+
+    #[test]
+    fn test_schema_by_name_unknown() {
+        assert!(schema_by_name("UserError").is_none());
+        assert!(schema_by_name("user").is_none(), "Names are case-sensitive");
+    }
+
+    #[test]
+    fn test_all_schemas_generate() {
+        for name in schema_names() {
+            let schema = schema_by_name(name).expect("Listed schema should exist");
+            assert_eq!(schema.as_value()["title"], name, "Schema title should match its name");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_get_schema_handler_requires_json_suffix() {
+        let found = get_schema_handler(Path("User.json".to_owned())).await;
+        let missing_suffix = get_schema_handler(Path("User".to_owned())).await;
+        let unknown = get_schema_handler(Path("Nope.json".to_owned())).await;
+
+        assert_eq!(found.status(), StatusCode::OK);
+        assert_eq!(missing_suffix.status(), StatusCode::NOT_FOUND);
+        assert_eq!(unknown.status(), StatusCode::NOT_FOUND);
+    }
+}
@@ -0,0 +1,110 @@
+//! Request statistics
+//!
+//! [`count_responses`] counts every response by status class in a shared
+//! [`RequestStats`], which dashboards read to report error rates, and in the
+//! error budget counters of [`crate::metrics`]. Counts start at zero when the
+//! process starts.
+
+use std::sync::{
+    Arc,
+    atomic::{AtomicU64, Ordering},
+};
+
+use axum::{
+    extract::{Request, State},
+    middleware::Next,
+    response::Response,
+};
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::metrics::{HTTP_REQUESTS, HTTP_SERVER_ERRORS};
+
+/// Response counts since the process started
+#[derive(Serialize, ToSchema, Debug, Clone, Copy, PartialEq)]
+pub struct RequestCounts {
+    /// Responses sent
+    pub requests: u64,
+    /// 4xx responses
+    pub client_errors: u64,
+    /// 5xx responses
+    pub server_errors: u64,
+    /// Share of responses that were 5xx (0 when nothing was served yet)
+    pub error_rate: f64,
+}
+
+/// Shared response counters
+///
+/// Clones share the same counters.
+#[derive(Debug, Clone, Default)]
+pub struct RequestStats {
+    requests: Arc<AtomicU64>,
+    client_errors: Arc<AtomicU64>,
+    server_errors: Arc<AtomicU64>,
+}
+
+impl RequestStats {
+    /// Counts one response with the given status code
+    pub fn record(&self, status: u16) {
+        self.requests.fetch_add(1, Ordering::Relaxed);
+        match status {
+            400..=499 => self.client_errors.fetch_add(1, Ordering::Relaxed),
+            500..=599 => self.server_errors.fetch_add(1, Ordering::Relaxed),
+            _ => 0,
+        };
+    }
+
+    /// Current counts
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn snapshot(&self) -> RequestCounts {
+        let requests = self.requests.load(Ordering::Relaxed);
+        let server_errors = self.server_errors.load(Ordering::Relaxed);
+        RequestCounts {
+            requests,
+            client_errors: self.client_errors.load(Ordering::Relaxed),
+            server_errors,
+            error_rate: if requests == 0 { 0.0 } else { server_errors as f64 / requests as f64 },
+        }
+    }
+}
+
+/// Middleware counting every response in `stats` and the error budget metrics
+pub async fn count_responses(State(stats): State<RequestStats>, request: Request, next: Next) -> Response {
+    let response = next.run(request).await;
+    stats.record(response.status().as_u16());
+    HTTP_REQUESTS.increment(&[]);
+    if response.status().is_server_error() {
+        HTTP_SERVER_ERRORS.increment(&[]);
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{Router, body::Body, http::StatusCode, middleware, routing::get};
+    use tower::ServiceExt;
+
+    use crate::metrics::MetricsRegistry;
+
+    #[tokio::test]
+    async fn test_responses_are_counted_by_status_class() {
+        let stats = RequestStats::default();
+        let server_errors = || MetricsRegistry::global().value(&HTTP_SERVER_ERRORS, &[]).unwrap_or_default();
+        let before = server_errors();
+        let app = Router::new()
+            .route("/ok", get(|| async { "ok" }))
+            .route("/fail", get(|| async { StatusCode::INTERNAL_SERVER_ERROR }))
+            .layer(middleware::from_fn_with_state(stats.clone(), count_responses));
+
+        for uri in ["/ok", "/fail", "/missing", "/ok"] {
+            app.clone().oneshot(Request::get(uri).body(Body::empty()).unwrap()).await.unwrap();
+        }
+
+        let counts = stats.snapshot();
+        assert_eq!((counts.requests, counts.client_errors, counts.server_errors), (4, 1, 1));
+        assert!((counts.error_rate - 0.25).abs() < f64::EPSILON);
+        assert!(server_errors() > before, "5xx responses should spend the error budget");
+    }
+}
@@ -0,0 +1,167 @@
+//! Tag controller - HTTP handlers for user tags and tag autocompletion
+
+use axum::{
+    Json,
+    extract::{Path, Query},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use tracing::{error, warn};
+
+use crate::error_codes::{ErrorCode, ErrorResponse, internal_error};
+use crate::registry::Inject;
+use crate::tag::TagService;
+use crate::user::UserId;
+use crate::user::domain::{ApiResponse, ValidationErrorResponse, UserError};
+
+use super::domain::{Tag, TagAutocompleteParams, TagError, TagSuggestion};
+
+/// Maps tag errors to HTTP responses
+fn error_response(error: TagError, user_id: Option<i32>) -> Response {
+    match error {
+        TagError::ValidationError(errors) => {
+            warn!(?errors, user_id, "Controller: Tag validation failed");
+            (
+                StatusCode::BAD_REQUEST,
+                Json(ValidationErrorResponse { errors }),
+            ).into_response()
+        }
+        TagError::UserNotFound | TagError::NotFound => {
+            warn!(user_id, error = %error, "Controller: Tag resource not found");
+            let code = if matches!(error, TagError::UserNotFound) { ErrorCode::UserNotFound } else { ErrorCode::TagNotFound };
+            (
+                StatusCode::NOT_FOUND,
+                Json(ErrorResponse::new(code, error.to_string())),
+            ).into_response()
+        }
+        TagError::DatabaseError(msg) => {
+            error!(error = %msg, user_id, "Controller: Database error in tag operation");
+            internal_error()
+        }
+        TagError::UserServiceError(UserError::DatabaseError(e)) => {
+            error!(error = %e, user_id, "Controller: Database error in user lookup for tag operation");
+            e.into_response()
+        }
+        TagError::UserServiceError(e) => {
+            error!(error = %e, user_id, "Controller: User service error in tag operation");
+            internal_error()
+        }
+    }
+}
+
+/// HTTP handler for listing the tags of a user
+#[utoipa::path(
+    get,
+    path = "/users/{id}/tags",
+    tag = "tags",
+    params(
+        ("id" = i32, Path, description = "User ID")
+    ),
+    responses(
+        (status = 200, description = "Tags attached to the user", body = Vec<Tag>),
+        (status = 400, description = "Invalid user ID", body = ValidationErrorResponse),
+        (status = 404, description = "User not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    )
+)]
+#[tracing::instrument(skip(tag_service), fields(user_id = user_id))]
+pub async fn list_user_tags_handler(
+    Inject(tag_service): Inject<TagService>,
+    UserId(user_id): UserId,
+) -> impl IntoResponse {
+    match tag_service.list_user_tags(user_id).await {
+        Ok(tags) => (StatusCode::OK, Json(tags)).into_response(),
+        Err(e) => error_response(e, Some(user_id)),
+    }
+}
+
+/// HTTP handler for attaching a tag to a user (idempotent)
+#[utoipa::path(
+    put,
+    path = "/users/{id}/tags/{tag}",
+    tag = "tags",
+    params(
+        ("id" = i32, Path, description = "User ID"),
+        ("tag" = String, Path, description = "Tag name")
+    ),
+    responses(
+        (status = 200, description = "Tag attached", body = Tag),
+        (status = 400, description = "Invalid tag name", body = ValidationErrorResponse),
+        (status = 404, description = "User not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    )
+)]
+#[tracing::instrument(skip(tag_service, path), fields(user_id = user_id, tag = %path.1))]
+pub async fn attach_tag_handler(
+    Inject(tag_service): Inject<TagService>,
+    UserId(user_id): UserId,
+    Path(path): Path<(i32, String)>,
+) -> impl IntoResponse {
+    let tag = path.1;
+    match tag_service.attach_tag(user_id, &tag).await {
+        Ok(tag) => (StatusCode::OK, Json(tag)).into_response(),
+        Err(e) => error_response(e, Some(user_id)),
+    }
+}
+
+/// HTTP handler for detaching a tag from a user
+#[utoipa::path(
+    delete,
+    path = "/users/{id}/tags/{tag}",
+    tag = "tags",
+    params(
+        ("id" = i32, Path, description = "User ID"),
+        ("tag" = String, Path, description = "Tag name")
+    ),
+    responses(
+        (status = 200, description = "Tag detached", body = ApiResponse),
+        (status = 400, description = "Invalid tag name", body = ValidationErrorResponse),
+        (status = 404, description = "User not found or tag not attached", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    )
+)]
+#[tracing::instrument(skip(tag_service, path), fields(user_id = user_id, tag = %path.1))]
+pub async fn detach_tag_handler(
+    Inject(tag_service): Inject<TagService>,
+    UserId(user_id): UserId,
+    Path(path): Path<(i32, String)>,
+) -> impl IntoResponse {
+    let tag = path.1;
+    match tag_service.detach_tag(user_id, &tag).await {
+        Ok(()) => (
+            StatusCode::OK,
+            Json(ApiResponse {
+                message: format!("Tag {tag} detached from user {user_id}"),
+            }),
+        ).into_response(),
+        Err(e) => error_response(e, Some(user_id)),
+    }
+}
+
+/// HTTP handler for tag autocompletion
+#[utoipa::path(
+    get,
+    path = "/tags/autocomplete",
+    tag = "tags",
+    params(
+        ("prefix" = Option<String>, Query, description = "Prefix the tag name must start with"),
+        ("limit" = Option<i32>, Query, description = "Maximum number of suggestions (default: 10, max: 50)")
+    ),
+    responses(
+        (status = 200, description = "Matching tags, most used first", body = Vec<TagSuggestion>),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    )
+)]
+#[tracing::instrument(skip(tag_service), fields(prefix = params.prefix.as_deref(), limit = params.limit))]
+pub async fn autocomplete_tags_handler(
+    Inject(tag_service): Inject<TagService>,
+    Query(params): Query<TagAutocompleteParams>,
+) -> impl IntoResponse {
+    match tag_service
+        .autocomplete(params.prefix.as_deref(), params.limit)
+        .await
+    {
+        Ok(suggestions) => (StatusCode::OK, Json(suggestions)).into_response(),
+        Err(e) => error_response(e, None),
+    }
+}
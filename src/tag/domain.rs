@@ -0,0 +1,57 @@
+//! Tag domain models
+
+use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+use utoipa::ToSchema;
+use chrono::{DateTime, Utc};
+
+use crate::user::domain::{UserError, ValidationError};
+
+/// Tag entity returned by the API
+#[derive(Serialize, ToSchema, JsonSchema, Debug, Clone, sqlx::FromRow)]
+pub struct Tag {
+    /// Unique tag identifier
+    pub id: i32,
+    /// Normalized (lowercase) tag name
+    pub name: String,
+    /// When the tag was first used
+    pub created_at: DateTime<Utc>,
+}
+
+/// Tag suggestion returned by autocompletion
+#[derive(Serialize, ToSchema, JsonSchema, Debug, Clone)]
+pub struct TagSuggestion {
+    /// Tag name
+    pub name: String,
+    /// Number of users carrying this tag
+    pub user_count: i64,
+}
+
+/// Query parameters for tag autocompletion
+#[derive(Deserialize, ToSchema, Debug, Clone)]
+pub struct TagAutocompleteParams {
+    /// Prefix the tag name must start with
+    pub prefix: Option<String>,
+    /// Maximum number of suggestions (default: 10, max: 50)
+    pub limit: Option<i32>,
+}
+
+/// Domain errors for tag operations
+#[derive(Debug, thiserror::Error)]
+pub enum TagError {
+    /// Tag name is invalid
+    #[error("Validation failed")]
+    ValidationError(Vec<ValidationError>),
+    /// The user does not exist
+    #[error("User not found")]
+    UserNotFound,
+    /// The tag is not attached to the user
+    #[error("Tag not found")]
+    NotFound,
+    /// Database operation failed
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+    /// Error from the underlying user service
+    #[error("User service error: {0}")]
+    UserServiceError(UserError),
+}
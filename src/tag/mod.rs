@@ -0,0 +1,17 @@
+//! Tag module
+//!
+//! Labels that can be attached to users (many-to-many), with autocompletion.
+//! Filtering users by tag (`GET /users?tag=vip`) is handled by the user module.
+
+pub mod domain;
+pub mod repository;
+pub mod service;
+pub mod controller;
+pub mod validation;
+
+// Public exports
+pub use service::TagService;
+pub use domain::{Tag, TagError, TagSuggestion};
+
+// Export controller for OpenAPI documentation
+pub use controller::*;
@@ -0,0 +1,153 @@
+//! Tag repository - handles database operations
+//!
+//! This module is private to the tag module. All database access must go
+//! through `TagService`.
+
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use tracing::{error, info};
+
+use super::domain::{Tag, TagError, TagSuggestion};
+
+/// Tag repository for database operations
+#[derive(Clone)]
+pub(super) struct TagRepository {
+    pool: PgPool,
+}
+
+/// Escapes `LIKE` wildcards so user input is matched literally
+fn escape_like(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_")
+}
+
+impl TagRepository {
+    /// Creates a new `TagRepository` instance
+    pub(super) fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Attaches a tag (creating it if needed) to a user; attaching twice is a no-op
+    pub(super) async fn attach(&self, user_id: i32, name: &str, attached_at: DateTime<Utc>) -> Result<Tag, TagError> {
+        info!(user_id, tag = name, "Attaching tag to user in database");
+
+        let mut tx = self.pool.begin().await.map_err(|e| {
+            error!(error = %e, "Failed to begin transaction for tag attach");
+            TagError::DatabaseError(e.to_string())
+        })?;
+
+        // DO UPDATE (instead of DO NOTHING) so RETURNING yields the existing row
+        let tag = sqlx::query_as!(
+            Tag,
+            "INSERT INTO tags (name, created_at) VALUES ($1, $2)
+             ON CONFLICT (name) DO UPDATE SET name = EXCLUDED.name
+             RETURNING id, name, created_at",
+            name,
+            attached_at
+        )
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| {
+            error!(error = %e, tag = name, "Failed to upsert tag in database");
+            TagError::DatabaseError(e.to_string())
+        })?;
+
+        sqlx::query!(
+            "INSERT INTO user_tags (user_id, tag_id, created_at) VALUES ($1, $2, $3) ON CONFLICT DO NOTHING",
+            user_id,
+            tag.id,
+            attached_at
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| {
+            error!(error = %e, user_id, tag = name, "Failed to attach tag in database");
+            TagError::DatabaseError(e.to_string())
+        })?;
+
+        tx.commit().await.map_err(|e| {
+            error!(error = %e, "Failed to commit tag attach");
+            TagError::DatabaseError(e.to_string())
+        })?;
+
+        info!(user_id, tag = name, "Tag attached successfully in database");
+        Ok(tag)
+    }
+
+    /// Detaches a tag from a user, returning whether it was attached
+    pub(super) async fn detach(&self, user_id: i32, name: &str) -> Result<bool, TagError> {
+        info!(user_id, tag = name, "Detaching tag from user in database");
+
+        let result = sqlx::query!(
+            "DELETE FROM user_tags
+             USING tags
+             WHERE user_tags.tag_id = tags.id AND user_tags.user_id = $1 AND tags.name = $2",
+            user_id,
+            name
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| {
+            error!(error = %e, user_id, tag = name, "Failed to detach tag in database");
+            TagError::DatabaseError(e.to_string())
+        })?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Lists the tags attached to a user
+    pub(super) async fn find_by_user(&self, user_id: i32) -> Result<Vec<Tag>, TagError> {
+        info!(user_id, "Fetching tags for user from database");
+
+        sqlx::query_as!(
+            Tag,
+            "SELECT t.id, t.name, t.created_at
+             FROM tags t
+             JOIN user_tags ut ON ut.tag_id = t.id
+             WHERE ut.user_id = $1
+             ORDER BY t.name",
+            user_id
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| {
+            error!(error = %e, user_id, "Failed to fetch user tags from database");
+            TagError::DatabaseError(e.to_string())
+        })
+    }
+
+    /// Suggests tags starting with `prefix`, most used first
+    pub(super) async fn autocomplete(&self, prefix: &str, limit: i32) -> Result<Vec<TagSuggestion>, TagError> {
+        info!(prefix, limit, "Fetching tag suggestions from database");
+
+        let pattern = format!("{}%", escape_like(prefix));
+        sqlx::query_as!(
+            TagSuggestion,
+            r#"SELECT t.name, COUNT(ut.user_id) AS "user_count!"
+             FROM tags t
+             LEFT JOIN user_tags ut ON ut.tag_id = t.id
+             WHERE t.name LIKE $1
+             GROUP BY t.id, t.name
+             ORDER BY COUNT(ut.user_id) DESC, t.name
+             LIMIT $2"#,
+            pattern,
+            i64::from(limit)
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| {
+            error!(error = %e, prefix, "Failed to fetch tag suggestions from database");
+            TagError::DatabaseError(e.to_string())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escape_like() {
+        assert_eq!(escape_like("early_adopter"), "early\\_adopter");
+        assert_eq!(escape_like("50%"), "50\\%");
+    }
+}
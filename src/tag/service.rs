@@ -0,0 +1,110 @@
+//! Tag service - business logic layer
+//!
+//! Manages the many-to-many relation between users and tags. Talks to the
+//! user module only through `UserReadPort`.
+
+use std::sync::Arc;
+
+use sqlx::PgPool;
+use tracing::{info, warn};
+
+use crate::clock::{SharedClock, SystemClock};
+use crate::user::{SharedUserReadPort, UserReadPort};
+
+use super::domain::{Tag, TagError, TagSuggestion};
+use super::repository::TagRepository;
+use super::validation::{normalize_tag, validate_tag};
+
+/// Default number of autocompletion suggestions
+const DEFAULT_SUGGESTION_LIMIT: i32 = 10;
+/// Maximum number of autocompletion suggestions
+const MAX_SUGGESTION_LIMIT: i32 = 50;
+
+/// Tag service that handles business logic for user tags
+#[derive(Clone)]
+pub struct TagService {
+    repository: TagRepository,
+    users: SharedUserReadPort,
+    clock: SharedClock,
+}
+
+impl TagService {
+    /// Creates a new `TagService` instance
+    #[must_use] pub fn new(pool: PgPool, users: impl UserReadPort + 'static) -> Self {
+        Self {
+            repository: TagRepository::new(pool),
+            users: Arc::new(users),
+            clock: SystemClock::shared(),
+        }
+    }
+
+    /// Uses `clock` instead of the system clock for timestamps
+    #[must_use] pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Ensures the user exists before touching its tags
+    async fn ensure_user_exists(&self, user_id: i32) -> Result<(), TagError> {
+        match self.users.user_exists(user_id).await {
+            Ok(true) => Ok(()),
+            Ok(false) => {
+                warn!(user_id, "TagService: User not found");
+                Err(TagError::UserNotFound)
+            }
+            Err(e) => {
+                warn!(user_id, error = ?e, "TagService: Error checking user existence");
+                Err(TagError::UserServiceError(e))
+            }
+        }
+    }
+
+    /// Normalizes and validates a tag name
+    fn prepare_tag(name: &str) -> Result<String, TagError> {
+        let name = normalize_tag(name);
+        validate_tag(&name, "tag").map_err(TagError::ValidationError)?;
+        Ok(name)
+    }
+
+    /// Attaches a tag to a user, creating the tag on first use
+    pub async fn attach_tag(&self, user_id: i32, name: &str) -> Result<Tag, TagError> {
+        info!(user_id, tag = name, "TagService: Attaching tag");
+
+        let name = Self::prepare_tag(name)?;
+        self.ensure_user_exists(user_id).await?;
+        self.repository.attach(user_id, &name, self.clock.now()).await
+    }
+
+    /// Detaches a tag from a user
+    pub async fn detach_tag(&self, user_id: i32, name: &str) -> Result<(), TagError> {
+        info!(user_id, tag = name, "TagService: Detaching tag");
+
+        let name = Self::prepare_tag(name)?;
+        self.ensure_user_exists(user_id).await?;
+        if self.repository.detach(user_id, &name).await? {
+            Ok(())
+        } else {
+            warn!(user_id, tag = %name, "TagService: Tag not attached to user");
+            Err(TagError::NotFound)
+        }
+    }
+
+    /// Lists the tags attached to a user
+    pub async fn list_user_tags(&self, user_id: i32) -> Result<Vec<Tag>, TagError> {
+        info!(user_id, "TagService: Listing user tags");
+
+        self.ensure_user_exists(user_id).await?;
+        self.repository.find_by_user(user_id).await
+    }
+
+    /// Suggests existing tags starting with the given prefix
+    pub async fn autocomplete(&self, prefix: Option<&str>, limit: Option<i32>) -> Result<Vec<TagSuggestion>, TagError> {
+        let prefix = prefix.map(normalize_tag).unwrap_or_default();
+        let limit = limit
+            .unwrap_or(DEFAULT_SUGGESTION_LIMIT)
+            .clamp(1, MAX_SUGGESTION_LIMIT);
+
+        info!(prefix = %prefix, limit, "TagService: Autocompleting tags");
+        self.repository.autocomplete(&prefix, limit).await
+    }
+}
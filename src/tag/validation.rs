@@ -0,0 +1,68 @@
+//! Tag validation logic
+
+use crate::error_codes::ErrorCode;
+use crate::user::validation::common::{field_error, ValidationResult};
+
+/// Maximum length of a tag name
+pub const MAX_TAG_LENGTH: usize = 50;
+
+/// Normalizes a tag name for storage and lookup (trimmed, lowercase)
+#[must_use]
+pub fn normalize_tag(name: &str) -> String {
+    name.trim().to_lowercase()
+}
+
+/// Validates a normalized tag name
+///
+/// Tags may contain lowercase letters, digits, `-` and `_`.
+pub fn validate_tag(name: &str, field_name: &str) -> ValidationResult {
+    let mut errors = Vec::new();
+
+    if name.is_empty() {
+        errors.push(field_error(field_name, ErrorCode::FieldEmpty, "Tag cannot be empty"));
+    }
+
+    if name.len() > MAX_TAG_LENGTH {
+        errors.push(field_error(field_name, ErrorCode::TooLong, format!("Tag cannot exceed {MAX_TAG_LENGTH} characters")));
+    }
+
+    if !name
+        .chars()
+        .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_')
+    {
+        errors.push(field_error(
+            field_name,
+            ErrorCode::InvalidCharacters,
+            "Tag can only contain letters, digits, '-' and '_'",
+        ));
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_tag() {
+        assert_eq!(normalize_tag("  VIP "), "vip");
+    }
+
+    #[test]
+    fn test_validate_tag_valid() {
+        assert!(validate_tag("vip", "tag").is_ok());
+        assert!(validate_tag("early-adopter_2", "tag").is_ok());
+    }
+
+    #[test]
+    fn test_validate_tag_invalid() {
+        assert!(validate_tag("", "tag").is_err());
+        assert!(validate_tag("has space", "tag").is_err());
+        assert!(validate_tag(&"a".repeat(51), "tag").is_err());
+    }
+}
@@ -0,0 +1,208 @@
+//! Test fixtures and assertion helpers
+//!
+//! Available with the `test-util` feature, which the integration tests enable
+//! through the crate's dev-dependency on itself. [`SpanCapture`] records
+//! spans and events so tests can check instrumentation too, and
+//! [`QueryCounter`] the statements sent to the database.
+
+mod queries;
+mod spans;
+
+pub use queries::QueryCounter;
+pub use spans::{CapturedEvent, CapturedSpan, SpanCapture};
+
+use axum::{
+    Router,
+    body::Body,
+    http::{Request, StatusCode, header, request},
+};
+use http_body_util::BodyExt;
+use serde_json::Value;
+use sqlx::PgPool;
+use tower::ServiceExt;
+
+use crate::user::domain::Metadata;
+use crate::user::{CreateUser, User, UserService, UserWritePort};
+
+/// Builds users with sensible defaults, overriding only what a test cares about
+#[derive(Debug, Clone)]
+pub struct UserBuilder {
+    name: String,
+    age: i32,
+    metadata: Option<Metadata>,
+}
+
+impl Default for UserBuilder {
+    fn default() -> Self {
+        Self {
+            name: "Test User".to_owned(),
+            age: 30,
+            metadata: None,
+        }
+    }
+}
+
+impl UserBuilder {
+    /// Starts from the defaults (`Test User`, 30)
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the name
+    #[must_use]
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = name.into();
+        self
+    }
+
+    /// Sets the age
+    #[must_use]
+    pub const fn age(mut self, age: i32) -> Self {
+        self.age = age;
+        self
+    }
+
+    /// Adds a metadata label
+    #[must_use]
+    pub fn metadata(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.metadata.get_or_insert_with(Metadata::new).insert(key.into(), value.into());
+        self
+    }
+
+    /// Creation payload for the user
+    #[must_use]
+    pub fn build(self) -> CreateUser {
+        CreateUser {
+            name: self.name,
+            age: self.age,
+            metadata: self.metadata,
+        }
+    }
+
+    /// Creation payload as a JSON request body
+    #[must_use]
+    pub fn json(&self) -> Value {
+        match &self.metadata {
+            Some(metadata) => serde_json::json!({ "name": self.name, "age": self.age, "metadata": metadata }),
+            None => serde_json::json!({ "name": self.name, "age": self.age }),
+        }
+    }
+
+    /// Creates the user through `UserService`
+    ///
+    /// # Panics
+    ///
+    /// Panics when the user cannot be created (invalid fields or database errors).
+    pub async fn insert(self, pool: &PgPool) -> User {
+        UserService::new(pool.clone())
+            .create_user(self.build())
+            .await
+            .expect("Failed to insert user fixture")
+    }
+}
+
+/// Sends a request to `app` and returns the status code and JSON body
+///
+/// `body` is sent as JSON. Responses without a JSON body yield `Value::Null`.
+///
+/// # Panics
+///
+/// Panics when the request cannot be built or the body cannot be read.
+pub async fn send(app: &Router, method: &str, uri: &str, body: Option<Value>) -> (StatusCode, Value) {
+    dispatch(app, Request::builder().method(method).uri(uri), body).await
+}
+
+/// Sends a request to `app` with `token` as bearer credentials (see [`send`])
+///
+/// # Panics
+///
+/// Panics when the request cannot be built or the body cannot be read.
+pub async fn send_as(app: &Router, token: &str, method: &str, uri: &str, body: Option<Value>) -> (StatusCode, Value) {
+    let builder = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header(header::AUTHORIZATION, format!("Bearer {token}"));
+    dispatch(app, builder, body).await
+}
+
+/// Sends the request started in `builder` with `body` as JSON
+async fn dispatch(app: &Router, builder: request::Builder, body: Option<Value>) -> (StatusCode, Value) {
+    let request = match body {
+        Some(body) => builder
+            .header("content-type", "application/json")
+            .body(Body::from(body.to_string())),
+        None => builder.body(Body::empty()),
+    }
+    .expect("Failed to build request");
+
+    let response = app.clone().oneshot(request).await.expect("Request failed");
+    let status = response.status();
+    let bytes = response.into_body().collect().await.expect("Failed to read body").to_bytes();
+    (status, serde_json::from_slice(&bytes).unwrap_or(Value::Null))
+}
+
+/// Asserts that `body` is a validation error response reporting `field`
+///
+/// # Panics
+///
+/// Panics when no error in `body["errors"]` refers to `field`.
+#[track_caller]
+pub fn assert_validation_error(body: &Value, field: &str) {
+    let errors = body["errors"].as_array().map(Vec::as_slice).unwrap_or_default();
+    assert!(
+        errors.iter().any(|error| error["field"] == field),
+        "Expected a validation error for `{field}`, got {body}"
+    );
+}
+
+/// Asserts that every field of `expected` appears in `actual` with the same value
+///
+/// Objects are compared recursively, so `actual` may carry extra fields such
+/// as generated IDs or timestamps.
+///
+/// # Panics
+///
+/// Panics on the first missing or differing field.
+#[track_caller]
+pub fn assert_json_includes(actual: &Value, expected: &Value) {
+    assert!(json_includes(actual, expected), "Expected {actual} to include {expected}");
+}
+
+/// Whether `actual` includes `expected` (see [`assert_json_includes`])
+fn json_includes(actual: &Value, expected: &Value) -> bool {
+    match (actual, expected) {
+        (Value::Object(actual), Value::Object(expected)) => expected
+            .iter()
+            .all(|(key, value)| actual.get(key).is_some_and(|actual| json_includes(actual, value))),
+        _ => actual == expected,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_user_builder_defaults_and_overrides() {
+        let user = UserBuilder::new().name("Ann Lee").build();
+        assert_eq!((user.name.as_str(), user.age), ("Ann Lee", 30));
+        assert_eq!(UserBuilder::new().age(41).json(), json!({ "name": "Test User", "age": 41 }));
+    }
+
+    #[test]
+    fn test_json_includes() {
+        let actual = json!({ "id": 7, "name": "Ann Lee", "_links": { "self": { "href": "/users/7" } } });
+
+        assert!(json_includes(&actual, &json!({ "name": "Ann Lee" })));
+        assert!(json_includes(&actual, &json!({ "_links": { "self": { "href": "/users/7" } } })));
+        assert!(!json_includes(&actual, &json!({ "name": "Bob Ray" })));
+        assert!(!json_includes(&actual, &json!({ "age": 30 })));
+    }
+
+    #[test]
+    fn test_assert_validation_error() {
+        assert_validation_error(&json!({ "errors": [{ "field": "age", "message": "too old" }] }), "age");
+    }
+}
@@ -0,0 +1,125 @@
+//! Counted database queries
+//!
+//! [`QueryCounter`] records the statements sqlx executes on the current
+//! thread while its guard is alive, so tests pin how many round trips an
+//! operation costs and catch N+1 regressions ("listing 10 users with their
+//! accounts runs as many queries as listing 1"). It is built on
+//! [`SpanCapture`] and shares its limits: run such tests on a current-thread
+//! runtime (`#[tokio::test]`). Statements a pool connection runs for itself
+//! (`SET search_path` after connecting, sqlx's `pg_catalog` type lookups the
+//! first time it meets an enum) are not counted, since they depend on which
+//! connection the pool hands out rather than on the code under test.
+
+use tracing::subscriber::DefaultGuard;
+
+use super::spans::{CapturedEvent, SpanCapture};
+
+/// Target of the event sqlx emits for every executed statement
+const QUERY_TARGET: &str = "sqlx::query";
+
+/// Whether `sql` is run by a connection for itself rather than by the caller
+fn is_connection_setup(sql: &str) -> bool {
+    sql.starts_with("SET ") || sql.contains("pg_catalog.")
+}
+
+/// Records the statements executed while installed
+///
+/// Clones share the same records.
+#[derive(Debug, Clone, Default)]
+pub struct QueryCounter {
+    capture: SpanCapture,
+}
+
+impl QueryCounter {
+    /// Starts with nothing recorded
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records the statements executed on the current thread until the guard is dropped
+    #[must_use = "counting stops when the guard is dropped"]
+    pub fn install(&self) -> DefaultGuard {
+        self.capture.install()
+    }
+
+    /// Statements executed so far, in order
+    #[must_use]
+    pub fn statements(&self) -> Vec<String> {
+        self.capture.events().iter().filter_map(statement).collect()
+    }
+
+    /// Number of statements executed so far
+    #[must_use]
+    pub fn count(&self) -> usize {
+        self.statements().len()
+    }
+
+    /// Number of statements executed so far that contain `fragment`, e.g. `FROM accounts`
+    #[must_use]
+    pub fn count_matching(&self, fragment: &str) -> usize {
+        self.statements().iter().filter(|sql| sql.contains(fragment)).count()
+    }
+
+    /// Asserts that exactly `expected` statements were executed
+    ///
+    /// # Panics
+    ///
+    /// Panics when the count differs, listing the statements that were executed.
+    #[track_caller]
+    pub fn assert_count(&self, expected: usize) {
+        let statements = self.statements();
+        assert!(
+            statements.len() == expected,
+            "Expected {expected} queries, executed {}: {statements:#?}",
+            statements.len()
+        );
+    }
+}
+
+/// Full SQL of a counted statement event; sqlx only attaches it when it is longer than the summary
+fn statement(event: &CapturedEvent) -> Option<String> {
+    if event.target != QUERY_TARGET {
+        return None;
+    }
+    let full = event.fields.get("db.statement").map(|sql| sql.trim()).filter(|sql| !sql.is_empty());
+    full.or_else(|| event.fields.get("summary").map(String::as_str))
+        .filter(|sql| !is_connection_setup(sql))
+        .map(str::to_owned)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counts_sqlx_statements_except_connection_setup() {
+        let counter = QueryCounter::new();
+        {
+            let _guard = counter.install();
+            tracing::debug!(target: "sqlx::query", summary = "SET search_path TO test_1,", db.statement = "\n\nSET search_path TO test_1, public\n", "");
+            tracing::debug!(target: "sqlx::query", summary = "SELECT 1", db.statement = "", "");
+            tracing::debug!(target: "sqlx::query", summary = "SELECT enumlabel FROM pg_catalog.pg_enum", db.statement = "\n\nSELECT enumlabel FROM pg_catalog.pg_enum WHERE enumtypid = $1\n", "");
+            tracing::debug!(target: "sqlx::query", summary = "SELECT id, user_id FROM", db.statement = "\n\nSELECT id, user_id FROM accounts WHERE user_id = ANY($1)\n", "");
+            tracing::info!(user_id = 1, "AccountService: Listing users with accounts");
+        }
+        tracing::debug!(target: "sqlx::query", summary = "SELECT 2", db.statement = "", "");
+
+        assert_eq!(counter.statements(), ["SELECT 1", "SELECT id, user_id FROM accounts WHERE user_id = ANY($1)"]);
+        assert_eq!(counter.count_matching("FROM accounts"), 1);
+        counter.assert_count(2);
+    }
+
+    #[test]
+    #[should_panic(expected = "Expected 1 queries, executed 2")]
+    fn test_assert_count_lists_statements() {
+        let counter = QueryCounter::new();
+        {
+            let _guard = counter.install();
+            tracing::debug!(target: "sqlx::query", summary = "SELECT 1", db.statement = "", "");
+            tracing::debug!(target: "sqlx::query", summary = "SELECT 2", db.statement = "", "");
+        }
+
+        counter.assert_count(1);
+    }
+}
@@ -0,0 +1,255 @@
+//! Captured spans and events
+//!
+//! [`SpanCapture`] records the spans and events emitted on the current thread
+//! while its guard is alive, so tests assert on instrumentation ("a span named
+//! `create_user_handler` with field `user_name` existed") the way they assert
+//! on responses. Run such tests on a current-thread runtime (`#[tokio::test]`):
+//! work executed on other threads is not captured.
+
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex, PoisonError};
+
+use tracing::{
+    Event, Level, Subscriber,
+    field::{Field, Visit},
+    span::{Attributes, Id, Record},
+    subscriber::DefaultGuard,
+};
+use tracing_subscriber::{
+    Layer,
+    layer::{Context, SubscriberExt},
+    registry::LookupSpan,
+};
+
+/// A span opened while capturing
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CapturedSpan {
+    /// Span name (the function name for `#[tracing::instrument]`)
+    pub name: String,
+    /// Module path or explicit target
+    pub target: String,
+    /// Verbosity level
+    pub level: Level,
+    /// Recorded fields, at creation or later through `Span::record`; fields
+    /// declared `Empty` and never recorded are absent
+    pub fields: BTreeMap<String, String>,
+    /// Name of the enclosing span
+    pub parent: Option<String>,
+}
+
+impl CapturedSpan {
+    /// Value of `field`, strings unquoted and other values as their `Debug` output
+    #[must_use]
+    pub fn field(&self, field: &str) -> Option<&str> {
+        self.fields.get(field).map(String::as_str)
+    }
+}
+
+/// An event emitted while capturing
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CapturedEvent {
+    /// Module path or explicit target (e.g. `audit`)
+    pub target: String,
+    /// Verbosity level
+    pub level: Level,
+    /// Formatted message
+    pub message: String,
+    /// Fields other than the message
+    pub fields: BTreeMap<String, String>,
+    /// Name of the innermost span the event was emitted in
+    pub span: Option<String>,
+}
+
+#[derive(Debug, Default)]
+struct Captured {
+    spans: Vec<CapturedSpan>,
+    events: Vec<CapturedEvent>,
+}
+
+/// Position of a span in [`Captured::spans`], kept in the span's extensions
+struct SpanIndex(usize);
+
+/// Layer recording spans and events in memory
+///
+/// Clones share the same records.
+#[derive(Debug, Clone, Default)]
+pub struct SpanCapture {
+    captured: Arc<Mutex<Captured>>,
+}
+
+impl SpanCapture {
+    /// Starts with nothing captured
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Captures everything emitted on the current thread until the guard is dropped
+    #[must_use = "capturing stops when the guard is dropped"]
+    pub fn install(&self) -> DefaultGuard {
+        tracing::subscriber::set_default(tracing_subscriber::registry().with(self.clone()))
+    }
+
+    /// Spans captured so far, in the order they were opened
+    #[must_use]
+    pub fn spans(&self) -> Vec<CapturedSpan> {
+        self.lock().spans.clone()
+    }
+
+    /// Events captured so far, in the order they were emitted
+    #[must_use]
+    pub fn events(&self) -> Vec<CapturedEvent> {
+        self.lock().events.clone()
+    }
+
+    /// First span named `name` that recorded every field in `fields`
+    #[must_use]
+    pub fn find_span(&self, name: &str, fields: &[&str]) -> Option<CapturedSpan> {
+        self.lock()
+            .spans
+            .iter()
+            .find(|span| span.name == name && fields.iter().all(|field| span.fields.contains_key(*field)))
+            .cloned()
+    }
+
+    /// First event whose message contains `message`
+    #[must_use]
+    pub fn find_event(&self, message: &str) -> Option<CapturedEvent> {
+        self.lock().events.iter().find(|event| event.message.contains(message)).cloned()
+    }
+
+    /// Asserts that a span named `name` recorded every field in `fields`
+    ///
+    /// # Panics
+    ///
+    /// Panics when no captured span matches, listing the spans that were captured.
+    #[track_caller]
+    pub fn assert_span(&self, name: &str, fields: &[&str]) {
+        let captured: Vec<String> = self.spans().iter().map(|span| format!("{}{:?}", span.name, span.fields)).collect();
+        assert!(
+            self.find_span(name, fields).is_some(),
+            "Expected a span named `{name}` with fields {fields:?}, captured {captured:?}"
+        );
+    }
+
+    /// Asserts that an event whose message contains `message` was emitted
+    ///
+    /// # Panics
+    ///
+    /// Panics when no captured event matches, listing the messages that were captured.
+    #[track_caller]
+    pub fn assert_event(&self, message: &str) {
+        let captured: Vec<String> = self.events().into_iter().map(|event| event.message).collect();
+        assert!(
+            self.find_event(message).is_some(),
+            "Expected an event containing `{message}`, captured {captured:?}"
+        );
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Captured> {
+        self.captured.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl<S> Layer<S> for SpanCapture
+where
+    S: Subscriber + for<'span> LookupSpan<'span>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        let mut fields = Fields::default();
+        attrs.record(&mut fields);
+        let mut captured = self.lock();
+        captured.spans.push(CapturedSpan {
+            name: span.name().to_owned(),
+            target: span.metadata().target().to_owned(),
+            level: *span.metadata().level(),
+            fields: fields.0,
+            parent: span.parent().map(|parent| parent.name().to_owned()),
+        });
+        span.extensions_mut().insert(SpanIndex(captured.spans.len() - 1));
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        let Some(&SpanIndex(index)) = span.extensions().get::<SpanIndex>() else {
+            return;
+        };
+        let mut fields = Fields::default();
+        values.record(&mut fields);
+        if let Some(captured) = self.lock().spans.get_mut(index) {
+            captured.fields.extend(fields.0);
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let mut fields = Fields::default();
+        event.record(&mut fields);
+        let message = fields.0.remove("message").unwrap_or_default();
+        self.lock().events.push(CapturedEvent {
+            target: event.metadata().target().to_owned(),
+            level: *event.metadata().level(),
+            message,
+            fields: fields.0,
+            span: ctx.event_span(event).map(|span| span.name().to_owned()),
+        });
+    }
+}
+
+/// Field values as strings
+#[derive(Default)]
+struct Fields(BTreeMap<String, String>);
+
+impl Visit for Fields {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name().to_owned(), value.to_owned());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        self.0.insert(field.name().to_owned(), format!("{value:?}"));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_captures_spans_fields_and_events() {
+        let capture = SpanCapture::new();
+        {
+            let _guard = capture.install();
+            let outer = tracing::info_span!("create_user", user_id = tracing::field::Empty, name = "Ann");
+            let _entered = outer.enter();
+            outer.record("user_id", 7);
+            tracing::debug_span!("insert").in_scope(|| tracing::warn!(target: "audit", rows = 1, "User created"));
+        }
+        tracing::info_span!("after_guard").in_scope(|| {});
+
+        capture.assert_span("create_user", &["user_id", "name"]);
+        capture.assert_event("User created");
+        let span = capture.find_span("create_user", &[]).unwrap();
+        assert_eq!((span.field("user_id"), span.field("name")), (Some("7"), Some("Ann")));
+        assert_eq!(capture.find_span("insert", &[]).unwrap().parent.as_deref(), Some("create_user"));
+        let event = capture.find_event("User created").unwrap();
+        assert_eq!((event.target.as_str(), event.level, event.span.as_deref()), ("audit", Level::WARN, Some("insert")));
+        assert_eq!(event.fields.get("rows").map(String::as_str), Some("1"));
+        assert_eq!(capture.spans().len(), 2, "Spans after the guard is dropped are not captured");
+    }
+
+    #[test]
+    #[should_panic(expected = "Expected a span named `create_user` with fields [\"user_id\"]")]
+    fn test_assert_span_reports_missing_fields() {
+        let capture = SpanCapture::new();
+        {
+            let _guard = capture.install();
+            tracing::info_span!("create_user", user_id = tracing::field::Empty).in_scope(|| {});
+        }
+
+        capture.assert_span("create_user", &["user_id"]);
+    }
+}
@@ -0,0 +1,128 @@
+//! Per-route request timeouts
+//!
+//! Every route gets the default timeout of a [`RouteTimeouts`] registry unless
+//! it declares its own, so a few heavy endpoints can take longer without
+//! loosening the limit for everything else. The router applies
+//! [`enforce_timeouts`] as a route layer; a handler still running when its
+//! time is up is dropped and the client gets a 503 with `Retry-After`. Only
+//! producing the response is timed: a streamed body may take as long as it
+//! needs.
+
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+use axum::{
+    Json,
+    extract::{MatchedPath, Request, State},
+    http::{HeaderValue, StatusCode, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use tracing::warn;
+
+use crate::error_codes::{ErrorCode, ErrorResponse};
+
+/// Message returned when a request runs out of time
+pub const TIMEOUT_MESSAGE: &str = "The request took too long to process";
+
+/// How long clients are advised to wait before retrying a timed-out request
+pub const RETRY_AFTER: Duration = Duration::from_secs(5);
+
+/// Registry of request timeouts, keyed by route pattern (e.g. `/users/{id}`)
+///
+/// A zero timeout means no limit, which is also what an empty registry applies.
+#[derive(Debug, Clone, Default)]
+pub struct RouteTimeouts {
+    default: Duration,
+    routes: Arc<HashMap<String, Duration>>,
+}
+
+impl RouteTimeouts {
+    /// Creates a registry applying `default` to every route
+    #[must_use]
+    pub fn new(default: Duration) -> Self {
+        Self { default, routes: Arc::default() }
+    }
+
+    /// Gives the route `path` its own timeout instead of the default
+    #[must_use]
+    pub fn route(mut self, path: impl Into<String>, timeout: Duration) -> Self {
+        Arc::make_mut(&mut self.routes).insert(path.into(), timeout);
+        self
+    }
+
+    /// Timeout of a route pattern, if it is limited
+    #[must_use]
+    pub fn get(&self, path: &str) -> Option<Duration> {
+        Some(self.routes.get(path).copied().unwrap_or(self.default)).filter(|timeout| !timeout.is_zero())
+    }
+}
+
+/// Route-layer middleware answering 503 when the handler exceeds its route's timeout
+pub async fn enforce_timeouts(State(timeouts): State<RouteTimeouts>, request: Request, next: Next) -> Response {
+    let Some((path, timeout)) = request
+        .extensions()
+        .get::<MatchedPath>()
+        .and_then(|path| timeouts.get(path.as_str()).map(|timeout| (path.as_str().to_owned(), timeout)))
+    else {
+        return next.run(request).await;
+    };
+
+    if let Ok(response) = tokio::time::timeout(timeout, next.run(request)).await {
+        response
+    } else {
+        warn!(route = %path, ?timeout, "Request timed out");
+        (
+            StatusCode::SERVICE_UNAVAILABLE,
+            [(header::RETRY_AFTER, HeaderValue::from(RETRY_AFTER.as_secs()))],
+            Json(ErrorResponse::new(ErrorCode::RequestTimeout, TIMEOUT_MESSAGE.to_owned())),
+        )
+            .into_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{Router, body::Body, middleware, routing::get};
+    use tower::ServiceExt;
+
+    async fn send(timeouts: RouteTimeouts, uri: &str) -> Response {
+        let slow = || async {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            "done"
+        };
+        let app = Router::new()
+            .route("/slow", get(slow))
+            .route("/export", get(slow))
+            .route_layer(middleware::from_fn_with_state(timeouts, enforce_timeouts));
+        app.oneshot(Request::get(uri).body(Body::empty()).unwrap())
+            .await
+            .unwrap()
+    }
+
+    async fn status(timeouts: RouteTimeouts, uri: &str) -> StatusCode {
+        send(timeouts, uri).await.status()
+    }
+
+    #[tokio::test]
+    async fn test_routes_can_override_the_default_timeout() {
+        let timeouts = RouteTimeouts::new(Duration::from_millis(20)).route("/export", Duration::from_secs(5));
+        let unlimited_export = RouteTimeouts::new(Duration::from_millis(20)).route("/export", Duration::ZERO);
+
+        assert_eq!(status(timeouts.clone(), "/slow").await, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(status(timeouts, "/export").await, StatusCode::OK);
+        assert_eq!(status(unlimited_export, "/export").await, StatusCode::OK);
+        assert_eq!(status(RouteTimeouts::default(), "/slow").await, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_timed_out_requests_advise_when_to_retry() {
+        let timeouts = RouteTimeouts::new(Duration::from_millis(20)).route("/export", Duration::from_secs(5));
+
+        let timed_out = send(timeouts.clone(), "/slow").await;
+        let served = send(timeouts, "/export").await;
+
+        assert_eq!(timed_out.headers()[header::RETRY_AFTER], "5");
+        assert!(!served.headers().contains_key(header::RETRY_AFTER));
+    }
+}
@@ -0,0 +1,128 @@
+//! Per-request database transactions
+//!
+//! The [`transactions`] middleware gives each request a transaction slot, and
+//! the [`Tx`] extractor reserves it. The transaction begins on first use, so
+//! requests that never ask for one, or are rejected by a later extractor,
+//! cost nothing. Once the handler has returned, the transaction is committed
+//! if the response is 2xx and rolled back otherwise, making multi-step
+//! handler logic atomic without explicit commit calls.
+//!
+//! Services take part through methods accepting a `&mut PgConnection`, which
+//! [`Tx::conn`] returns (e.g. `UserService::create_user_in(tx.conn().await?, …)`).
+
+use std::sync::Arc;
+
+use axum::{
+    extract::{FromRequestParts, Request, State},
+    http::request::Parts,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use sqlx::{PgConnection, PgPool, Postgres, Transaction};
+use tokio::sync::{Mutex, OwnedMutexGuard};
+use tracing::{error, warn};
+
+use crate::error_codes::internal_error;
+
+type TxCell = Arc<Mutex<Option<Transaction<'static, Postgres>>>>;
+
+/// Request extension holding the pool and the request's transaction, once begun
+#[derive(Clone)]
+struct TxSlot {
+    pool: PgPool,
+    tx: TxCell,
+}
+
+/// The request's database transaction
+///
+/// [`Tx::conn`] begins it and returns its connection, which can be passed to
+/// `sqlx` queries and to services taking a connection. Only one `Tx` can be
+/// held per request; it must not outlive the handler.
+pub struct Tx {
+    pool: PgPool,
+    tx: OwnedMutexGuard<Option<Transaction<'static, Postgres>>>,
+}
+
+impl std::fmt::Debug for Tx {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Tx").field("begun", &self.tx.is_some()).finish_non_exhaustive()
+    }
+}
+
+impl Tx {
+    /// Connection of the request's transaction, beginning it on the first call
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the transaction cannot be started.
+    pub async fn conn(&mut self) -> Result<&mut PgConnection, TxError> {
+        let tx = match self.tx.take() {
+            Some(tx) => tx,
+            None => self.pool.begin().await?,
+        };
+        Ok(&mut **self.tx.insert(tx))
+    }
+}
+
+/// Reasons a [`Tx`] cannot be extracted
+#[derive(Debug, thiserror::Error)]
+pub enum TxError {
+    /// The route is not behind the [`transactions`] middleware
+    #[error("Transaction middleware is not installed")]
+    MissingLayer,
+    /// The handler already holds the request's transaction
+    #[error("Transaction already extracted for this request")]
+    AlreadyExtracted(#[from] tokio::sync::TryLockError),
+    /// The transaction could not be started
+    #[error("Failed to begin transaction: {0}")]
+    Begin(#[from] sqlx::Error),
+}
+
+impl IntoResponse for TxError {
+    fn into_response(self) -> Response {
+        error!(error = %self, "Request transaction unavailable");
+        internal_error()
+    }
+}
+
+impl<S: Send + Sync> FromRequestParts<S> for Tx {
+    type Rejection = TxError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let slot = parts.extensions.get::<TxSlot>().ok_or(TxError::MissingLayer)?;
+        let tx = Arc::clone(&slot.tx).try_lock_owned()?;
+        Ok(Self { pool: slot.pool.clone(), tx })
+    }
+}
+
+/// Middleware committing the request's transaction on 2xx and rolling it back otherwise
+///
+/// A failed commit turns the response into a 500, since the handler's writes
+/// were not persisted.
+pub async fn transactions(State(pool): State<PgPool>, mut request: Request, next: Next) -> Response {
+    let slot = TxSlot {
+        pool,
+        tx: Arc::default(),
+    };
+    request.extensions_mut().insert(slot.clone());
+
+    let response = next.run(request).await;
+
+    let Ok(mut guard) = slot.tx.try_lock() else {
+        error!("Request transaction still held after the handler returned; it will be rolled back");
+        return internal_error();
+    };
+    let Some(tx) = guard.take() else {
+        return response;
+    };
+
+    if response.status().is_success() {
+        if let Err(e) = tx.commit().await {
+            error!(error = %e, "Failed to commit request transaction");
+            return internal_error();
+        }
+    } else if let Err(e) = tx.rollback().await {
+        warn!(error = %e, "Failed to roll back request transaction");
+    }
+    response
+}
@@ -4,17 +4,35 @@
 //! It should only be used internally by the user module's router setup.
 
 use axum::{
-    Json,
-    extract::{Path, State, Query},
-    http::StatusCode,
+    body::{Body, Bytes},
+    extract::Query,
+    http::{HeaderMap, StatusCode, header},
     response::IntoResponse,
 };
+use futures_util::StreamExt;
 use tracing::{error, warn};
 
-use super::domain::{User, CreateUser, UpdateUser, ValidationErrorResponse, ApiResponse, UserError, PaginationParams, PaginatedUsersResponse};
+use super::domain::{
+    User, CreateUser, UpdateUser, ValidationErrorResponse, ApiResponse, UserError, PaginationParams,
+    PaginatedUsersResponse, BulkIdsQuery, BulkIdsRequest, BulkUpdateUsers, BulkOperationResponse, UserHistory, RevertParams, UpsertUser, UpsertedUser,
+    UserChangesPage, UserChangesParams, METADATA_FILTER_PREFIX,
+};
+use super::extract::UserId;
+use super::validation::{common::field_error, parse_id_list};
+use crate::conditional::{not_modified_since, with_last_modified};
+use crate::error_codes::{ErrorCode, ErrorResponse, internal_error};
+use crate::links::LinkBuilder;
+use crate::pagination::SortOrder;
+use crate::registry::Inject;
+use crate::tx::Tx;
+use crate::user::{SharedUserReadPort, SharedUserWritePort, UserService};
+use crate::negotiation::{Negotiate, NegotiateError, Payload, ResponseContext};
 
 
 /// HTTP handler for creating a new user
+///
+/// Runs in the request's transaction, so the user and its dual-written
+/// column copy are committed together.
 #[utoipa::path(
     post,
     path = "/users",
@@ -23,31 +41,83 @@ use super::domain::{User, CreateUser, UpdateUser, ValidationErrorResponse, ApiRe
     responses(
         (status = 200, description = "User created successfully", body = User),
         (status = 400, description = "Validation errors", body = ValidationErrorResponse),
-        (status = 500, description = "Internal server error")
+        (status = 500, description = "Internal server error", body = ErrorResponse)
     )
 )]
-#[tracing::instrument(skip(app_state, payload), fields(user_name = %payload.name, user_age = payload.age))]
+#[tracing::instrument(skip(user_service, links, tx, payload), fields(user_name = %payload.name, user_age = payload.age))]
 pub async fn create_user_handler(
-    State(app_state): State<crate::AppState>,
-    Json(payload): Json<CreateUser>,
+    Inject(user_service): Inject<UserService>,
+    Inject(links): Inject<LinkBuilder>,
+    mut tx: Tx,
+    response_ctx: ResponseContext,
+    Payload(payload): Payload<CreateUser>,
 ) -> impl IntoResponse {
-    let user_service = app_state.user_service;
-    match user_service.create_user(payload).await {
-        Ok(user) => (StatusCode::OK, Json(user)).into_response(),
+    let conn = match tx.conn().await {
+        Ok(conn) => conn,
+        Err(e) => return e.into_response(),
+    };
+    match user_service.create_user_in(conn, payload).await {
+        Ok(user) => (StatusCode::OK, Negotiate::new(response_ctx, user.with_links(&links))).into_response(),
         Err(UserError::ValidationError(errors)) => {
             warn!(?errors, "Controller: Validation failed for create user");
             (
                 StatusCode::BAD_REQUEST,
-                Json(ValidationErrorResponse { errors }),
+                NegotiateError::new(response_ctx, ValidationErrorResponse { errors }),
             ).into_response()
         }
-        Err(UserError::DatabaseError(msg)) => {
-            error!(error = %msg, "Controller: Database error in create user");
-            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        Err(UserError::DatabaseError(err)) => {
+            error!(error = %err, "Controller: Database error in create user");
+            err.into_response()
         }
-        Err(UserError::NotFound | UserError::InvalidToken) => {
+        Err(UserError::NotFound | UserError::InvalidToken | UserError::InvalidStatusTransition { .. } | UserError::VersionNotFound(_)) => {
             // These shouldn't happen in create, but handle them anyway
-            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+            internal_error()
+        }
+    }
+}
+
+/// HTTP handler for creating or updating a user by its external identifier
+///
+/// Safe to retry and to run concurrently: all requests for one `external_id`
+/// end up on the same user.
+#[utoipa::path(
+    put,
+    path = "/users",
+    tag = "users",
+    request_body = UpsertUser,
+    responses(
+        (status = 200, description = "Existing user updated", body = User),
+        (status = 201, description = "User created", body = User),
+        (status = 400, description = "Validation errors", body = ValidationErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    )
+)]
+#[tracing::instrument(skip(user_service, links, payload), fields(external_id = %payload.external_id))]
+pub async fn upsert_user_handler(
+    Inject(user_service): Inject<SharedUserWritePort>,
+    Inject(links): Inject<LinkBuilder>,
+    response_ctx: ResponseContext,
+    Payload(payload): Payload<UpsertUser>,
+) -> impl IntoResponse {
+    match user_service.upsert_user(payload).await {
+        Ok(UpsertedUser { user, created }) => {
+            let status = if created { StatusCode::CREATED } else { StatusCode::OK };
+            (status, Negotiate::new(response_ctx, user.with_links(&links))).into_response()
+        }
+        Err(UserError::ValidationError(errors)) => {
+            warn!(?errors, "Controller: Validation failed for upsert user");
+            (
+                StatusCode::BAD_REQUEST,
+                NegotiateError::new(response_ctx, ValidationErrorResponse { errors }),
+            ).into_response()
+        }
+        Err(UserError::DatabaseError(err)) => {
+            error!(error = %err, "Controller: Database error in upsert user");
+            err.into_response()
+        }
+        Err(UserError::NotFound | UserError::InvalidToken | UserError::InvalidStatusTransition { .. } | UserError::VersionNotFound(_)) => {
+            // These shouldn't happen in upsert, but handle them anyway
+            internal_error()
         }
     }
 }
@@ -59,37 +129,146 @@ pub async fn create_user_handler(
     tag = "users",
     params(
         ("next_token" = Option<String>, Query, description = "Pagination token from previous page (opaque cursor)"),
-        ("limit" = Option<i32>, Query, description = "Number of records to return (default: 200, max: 200)")
+        ("prev_token" = Option<String>, Query, description = "Pagination token for the page before the current one; cannot be combined with `next_token`"),
+        ("order" = Option<SortOrder>, Query, description = "Sort order by creation time (default `asc`)"),
+        ("limit" = Option<i32>, Query, description = "Number of records to return (default `PAGINATION_DEFAULT_LIMIT`, capped at `PAGINATION_MAX_LIMIT`; 200 each unless configured)"),
+        ("tag" = Option<String>, Query, description = "Only return users carrying this tag"),
+        ("include_estimated_total" = Option<bool>, Query, description = "Include `estimated_total`, a fast approximate count of all matching users"),
+        ("metadata.{key}" = Option<String>, Query, description = "Only return users whose metadata label `key` equals this value, e.g. `metadata.department=eng`; repeat with other keys to require several labels")
     ),
     responses(
         (status = 200, description = "Paginated list of users", body = PaginatedUsersResponse),
-        (status = 500, description = "Internal server error")
+        (status = 400, description = "Both tokens provided or an invalid metadata filter; an invalid pagination token answers `INVALID_PAGINATION_TOKEN` in an `ErrorResponse`", body = ValidationErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
     )
 )]
-#[tracing::instrument(skip(app_state), fields(next_token = params.next_token.as_deref(), limit = params.limit))]
+#[tracing::instrument(skip(user_service, links), fields(next_token = params.next_token.as_deref(), prev_token = params.prev_token.as_deref(), limit = params.limit, tag = params.tag.as_deref()))]
 pub async fn get_all_users_handler(
-    State(app_state): State<crate::AppState>,
-    Query(params): Query<PaginationParams>,
+    Inject(user_service): Inject<SharedUserReadPort>,
+    Inject(links): Inject<LinkBuilder>,
+    response_ctx: ResponseContext,
+    Query(mut params): Query<PaginationParams>,
+    Query(query): Query<Vec<(String, String)>>,
 ) -> impl IntoResponse {
-    let user_service = app_state.user_service;
-    match user_service.get_users_paginated(params).await {
-        Ok(response) => (StatusCode::OK, Json(response)).into_response(),
-        Err(UserError::InvalidToken) => {
+    params.metadata = query
+        .into_iter()
+        .filter_map(|(key, value)| Some((key.strip_prefix(METADATA_FILTER_PREFIX)?.to_owned(), value)))
+        .collect();
+
+    match user_service.get_users_paginated(params.clone()).await {
+        Ok(response) => (
+            StatusCode::OK,
+            Negotiate::new(response_ctx, response.with_links(&links, &params)),
+        ).into_response(),
+        Err(e @ UserError::InvalidToken) => {
             warn!("Controller: Invalid pagination token provided");
-            StatusCode::BAD_REQUEST.into_response()
+            (
+                StatusCode::BAD_REQUEST,
+                NegotiateError::new(response_ctx, ErrorResponse::new(ErrorCode::InvalidPaginationToken, e.to_string())),
+            ).into_response()
+        }
+        Err(UserError::ValidationError(errors)) => {
+            warn!(?errors, "Controller: Invalid pagination parameters");
+            (
+                StatusCode::BAD_REQUEST,
+                NegotiateError::new(response_ctx, ValidationErrorResponse { errors }),
+            ).into_response()
         }
-        Err(UserError::DatabaseError(msg)) => {
-            error!(error = %msg, "Controller: Database error in get users");
-            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        Err(UserError::DatabaseError(err)) => {
+            error!(error = %err, "Controller: Database error in get users");
+            err.into_response()
         }
         Err(_) => {
             // Other errors shouldn't happen in get_users, but handle them anyway
-            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+            internal_error()
         }
     }
 }
 
 
+/// HTTP handler for streaming all users as newline-delimited JSON
+///
+/// Each line of the response body is a single `User` object. Rows are streamed
+/// straight from the database, so arbitrarily large tables can be exported with
+/// constant memory on both the server and the client.
+#[utoipa::path(
+    get,
+    path = "/users/stream",
+    tag = "users",
+    responses(
+        (status = 200, description = "Stream of users, one JSON object per line", body = User, content_type = "application/x-ndjson")
+    )
+)]
+#[tracing::instrument(skip(user_service))]
+pub async fn stream_users_handler(Inject(user_service): Inject<SharedUserReadPort>) -> impl IntoResponse {
+    let lines = user_service.stream_users().map(
+        |result| -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>> {
+            let user = result.inspect_err(|e| {
+                error!(error = %e, "Controller: Database error while streaming users");
+            })?;
+            let mut line = serde_json::to_vec(&user)?;
+            line.push(b'\n');
+            Ok(line)
+        },
+    );
+
+    (
+        [(header::CONTENT_TYPE, "application/x-ndjson")],
+        Body::from_stream(lines),
+    )
+}
+
+/// HTTP handler for long-polling the users change feed
+///
+/// For clients that cannot hold a WebSocket or event stream open: each poll
+/// answers as soon as users changed after `since`, or with no changes once
+/// `wait` runs out, and returns the cursor to poll with next. Changes are
+/// kept for `RETENTION_USER_CHANGES_DAYS` days.
+#[utoipa::path(
+    get,
+    path = "/users/changes",
+    tag = "users",
+    params(
+        ("since" = Option<String>, Query, description = "Cursor from the previous poll (opaque); without it the poll returns at once with a cursor at the end of the feed"),
+        ("wait" = Option<String>, Query, description = "How long to wait for a change, e.g. `30s` or `500ms` (default 0, capped at 60 seconds)"),
+        ("limit" = Option<i32>, Query, description = "Number of changes to return (default `PAGINATION_DEFAULT_LIMIT`, capped at `PAGINATION_MAX_LIMIT`)")
+    ),
+    responses(
+        (status = 200, description = "Changes after the cursor, oldest first, and the cursor to poll with next", body = UserChangesPage),
+        (status = 400, description = "Invalid wait; an invalid cursor answers `INVALID_PAGINATION_TOKEN` in an `ErrorResponse`", body = ValidationErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    )
+)]
+#[tracing::instrument(skip(user_service), fields(since = params.since.as_deref(), wait = params.wait.as_deref(), limit = params.limit))]
+pub async fn user_changes_handler(
+    Inject(user_service): Inject<SharedUserReadPort>,
+    response_ctx: ResponseContext,
+    Query(params): Query<UserChangesParams>,
+) -> impl IntoResponse {
+    match user_service.poll_user_changes(params).await {
+        Ok(page) => (StatusCode::OK, Negotiate::new(response_ctx, page)).into_response(),
+        Err(e @ UserError::InvalidToken) => {
+            warn!("Controller: Invalid change feed cursor provided");
+            (
+                StatusCode::BAD_REQUEST,
+                NegotiateError::new(response_ctx, ErrorResponse::new(ErrorCode::InvalidPaginationToken, e.to_string())),
+            ).into_response()
+        }
+        Err(UserError::ValidationError(errors)) => {
+            warn!(?errors, "Controller: Invalid change feed parameters");
+            (
+                StatusCode::BAD_REQUEST,
+                NegotiateError::new(response_ctx, ValidationErrorResponse { errors }),
+            ).into_response()
+        }
+        Err(UserError::DatabaseError(err)) => {
+            error!(error = %err, "Controller: Database error in poll user changes");
+            err.into_response()
+        }
+        Err(_) => internal_error(),
+    }
+}
+
 
 /// HTTP handler for retrieving a specific user by ID
 #[utoipa::path(
@@ -97,33 +276,47 @@ pub async fn get_all_users_handler(
     path = "/users/{id}",
     tag = "users",
     params(
-        ("id" = i32, Path, description = "User ID")
+        ("id" = i32, Path, description = "User ID"),
+        ("If-Modified-Since" = Option<String>, Header, description = "HTTP date of the cached copy; answers 304 when the user has not changed since")
     ),
     responses(
-        (status = 200, description = "User found", body = User),
-        (status = 404, description = "User not found"),
-        (status = 500, description = "Internal server error")
+        (status = 200, description = "User found; `Last-Modified` carries its `updated_at`", body = User),
+        (status = 304, description = "User not modified since `If-Modified-Since`"),
+        (status = 400, description = "Invalid user ID", body = ValidationErrorResponse),
+        (status = 404, description = "User not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
     )
 )]
-#[tracing::instrument(skip(app_state), fields(user_id = id))]
+#[tracing::instrument(skip(user_service, links), fields(user_id = id))]
 pub async fn get_user_by_id_handler(
-    State(app_state): State<crate::AppState>,
-    Path(id): Path<i32>,
+    Inject(user_service): Inject<SharedUserReadPort>,
+    Inject(links): Inject<LinkBuilder>,
+    response_ctx: ResponseContext,
+    headers: HeaderMap,
+    UserId(id): UserId,
 ) -> impl IntoResponse {
-    let user_service = app_state.user_service;
     match user_service.get_user_by_id(id).await {
-        Ok(user) => (StatusCode::OK, Json(user)).into_response(),
-        Err(UserError::NotFound) => {
+        Ok(user) if not_modified_since(&headers, user.updated_at) => {
+            with_last_modified(StatusCode::NOT_MODIFIED.into_response(), user.updated_at)
+        }
+        Ok(user) => {
+            let updated_at = user.updated_at;
+            with_last_modified((StatusCode::OK, Negotiate::new(response_ctx, user.with_links(&links))).into_response(), updated_at)
+        }
+        Err(e @ UserError::NotFound) => {
             warn!(user_id = id, "Controller: User not found");
-            StatusCode::NOT_FOUND.into_response()
+            (
+                StatusCode::NOT_FOUND,
+                NegotiateError::new(response_ctx, ErrorResponse::new(ErrorCode::UserNotFound, e.to_string())),
+            ).into_response()
         }
-        Err(UserError::DatabaseError(msg)) => {
-            error!(error = %msg, user_id = id, "Controller: Database error in get user by id");
-            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        Err(UserError::DatabaseError(err)) => {
+            error!(error = %err, user_id = id, "Controller: Database error in get user by id");
+            err.into_response()
         }
         Err(_) => {
             // Other errors shouldn't happen here, but handle them anyway
-            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+            internal_error()
         }
     }
 }
@@ -140,37 +333,41 @@ pub async fn get_user_by_id_handler(
     responses(
         (status = 200, description = "User updated successfully", body = User),
         (status = 400, description = "Validation errors", body = ValidationErrorResponse),
-        (status = 404, description = "User not found"),
-        (status = 500, description = "Internal server error")
+        (status = 404, description = "User not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
     )
 )]
-#[tracing::instrument(skip(app_state, payload), fields(user_id = id, update_name = payload.name.as_deref(), update_age = payload.age))]
+#[tracing::instrument(skip(user_service, links, payload), fields(user_id = id, update_name = payload.name.as_deref(), update_age = payload.age))]
 pub async fn update_user_handler(
-    State(app_state): State<crate::AppState>,
-    Path(id): Path<i32>,
-    Json(payload): Json<UpdateUser>,
+    Inject(user_service): Inject<SharedUserWritePort>,
+    Inject(links): Inject<LinkBuilder>,
+    response_ctx: ResponseContext,
+    UserId(id): UserId,
+    Payload(payload): Payload<UpdateUser>,
 ) -> impl IntoResponse {
-    let user_service = app_state.user_service;
     match user_service.update_user(id, payload).await {
-        Ok(user) => (StatusCode::OK, Json(user)).into_response(),
+        Ok(user) => (StatusCode::OK, Negotiate::new(response_ctx, user.with_links(&links))).into_response(),
         Err(UserError::ValidationError(errors)) => {
             warn!(?errors, user_id = id, "Controller: Validation failed for update user");
             (
                 StatusCode::BAD_REQUEST,
-                Json(ValidationErrorResponse { errors }),
+                NegotiateError::new(response_ctx, ValidationErrorResponse { errors }),
             ).into_response()
         }
-        Err(UserError::NotFound) => {
+        Err(e @ UserError::NotFound) => {
             warn!(user_id = id, "Controller: User not found for update");
-            StatusCode::NOT_FOUND.into_response()
+            (
+                StatusCode::NOT_FOUND,
+                NegotiateError::new(response_ctx, ErrorResponse::new(ErrorCode::UserNotFound, e.to_string())),
+            ).into_response()
         }
-        Err(UserError::DatabaseError(msg)) => {
-            error!(error = %msg, user_id = id, "Controller: Database error in update user");
-            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        Err(UserError::DatabaseError(err)) => {
+            error!(error = %err, user_id = id, "Controller: Database error in update user");
+            err.into_response()
         }
-        Err(UserError::InvalidToken) => {
-            // This shouldn't happen in update, but handle it anyway
-            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        Err(UserError::InvalidToken | UserError::InvalidStatusTransition { .. } | UserError::VersionNotFound(_)) => {
+            // These shouldn't happen in update, but handle them anyway
+            internal_error()
         }
     }
 }
@@ -185,29 +382,364 @@ pub async fn update_user_handler(
     ),
     responses(
         (status = 200, description = "User deleted successfully", body = ApiResponse),
-        (status = 404, description = "User not found"),
-        (status = 500, description = "Internal server error")
+        (status = 400, description = "Invalid user ID", body = ValidationErrorResponse),
+        (status = 404, description = "User not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
     )
 )]
-#[tracing::instrument(skip(app_state), fields(user_id = id))]
+#[tracing::instrument(skip(user_service), fields(user_id = id))]
 pub async fn delete_user_handler(
-    State(app_state): State<crate::AppState>,
-    Path(id): Path<i32>,
+    Inject(user_service): Inject<SharedUserWritePort>,
+    response_ctx: ResponseContext,
+    UserId(id): UserId,
 ) -> impl IntoResponse {
-    let user_service = app_state.user_service;
     match user_service.delete_user(id).await {
-        Ok(response) => (StatusCode::OK, Json(response)).into_response(),
-        Err(UserError::NotFound) => {
+        Ok(response) => (StatusCode::OK, Negotiate::new(response_ctx, response)).into_response(),
+        Err(e @ UserError::NotFound) => {
             warn!(user_id = id, "Controller: User not found for deletion");
-            StatusCode::NOT_FOUND.into_response()
+            (
+                StatusCode::NOT_FOUND,
+                NegotiateError::new(response_ctx, ErrorResponse::new(ErrorCode::UserNotFound, e.to_string())),
+            ).into_response()
         }
-        Err(UserError::DatabaseError(msg)) => {
-            error!(error = %msg, user_id = id, "Controller: Database error in delete user");
-            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        Err(UserError::DatabaseError(err)) => {
+            error!(error = %err, user_id = id, "Controller: Database error in delete user");
+            err.into_response()
         }
         Err(_) => {
             // Other errors shouldn't happen here, but handle them anyway
-            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+            internal_error()
         }
     }
-}
\ No newline at end of file
+}
+
+/// Resolves the target IDs of a bulk delete from the `ids` query parameter or a JSON body
+fn resolve_bulk_ids(query: BulkIdsQuery, body: &Bytes) -> Result<Vec<i32>, UserError> {
+    if let Some(raw) = query.ids {
+        return parse_id_list(&raw, "ids").map_err(UserError::ValidationError);
+    }
+
+    if body.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    serde_json::from_slice::<BulkIdsRequest>(body)
+        .map(|request| request.ids)
+        .map_err(|e| {
+            UserError::ValidationError(vec![field_error(
+                "ids",
+                ErrorCode::MalformedBody,
+                format!("Request body must be a JSON object with an `ids` array: {e}"),
+            )])
+        })
+}
+
+/// HTTP handler for deleting many users at once
+///
+/// IDs are taken from the `ids` query parameter (`?ids=1,2,3`) or, when absent,
+/// from a JSON body of the form `{"ids": [1, 2, 3]}`. All matching users are
+/// removed in a single statement and the outcome is reported per ID.
+#[utoipa::path(
+    delete,
+    path = "/users",
+    tag = "users",
+    params(
+        ("ids" = Option<String>, Query, description = "Comma-separated list of user IDs")
+    ),
+    request_body(content = BulkIdsRequest, description = "Alternative to the `ids` query parameter"),
+    responses(
+        (status = 200, description = "Per-ID deletion results", body = BulkOperationResponse),
+        (status = 400, description = "Validation errors", body = ValidationErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    )
+)]
+#[tracing::instrument(skip(user_service, body), fields(ids = query.ids.as_deref()))]
+pub async fn bulk_delete_users_handler(
+    Inject(user_service): Inject<SharedUserWritePort>,
+    response_ctx: ResponseContext,
+    Query(query): Query<BulkIdsQuery>,
+    body: Bytes,
+) -> impl IntoResponse {
+    let result = match resolve_bulk_ids(query, &body) {
+        Ok(ids) => user_service.delete_users(ids).await,
+        Err(e) => Err(e),
+    };
+
+    match result {
+        Ok(response) => (StatusCode::OK, Negotiate::new(response_ctx, response)).into_response(),
+        Err(UserError::ValidationError(errors)) => {
+            warn!(?errors, "Controller: Validation failed for bulk delete");
+            (
+                StatusCode::BAD_REQUEST,
+                NegotiateError::new(response_ctx, ValidationErrorResponse { errors }),
+            ).into_response()
+        }
+        Err(UserError::DatabaseError(err)) => {
+            error!(error = %err, "Controller: Database error in bulk delete");
+            err.into_response()
+        }
+        Err(_) => {
+            // Other errors shouldn't happen here, but handle them anyway
+            internal_error()
+        }
+    }
+}
+
+/// HTTP handler for applying the same partial update to many users
+///
+/// All listed users are updated in a single statement and the outcome is
+/// reported per ID, including the updated user for each match.
+#[utoipa::path(
+    patch,
+    path = "/users",
+    tag = "users",
+    request_body = BulkUpdateUsers,
+    responses(
+        (status = 200, description = "Per-ID update results", body = BulkOperationResponse),
+        (status = 400, description = "Validation errors", body = ValidationErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    )
+)]
+#[tracing::instrument(skip(user_service, links, payload), fields(count = payload.ids.len()))]
+pub async fn bulk_update_users_handler(
+    Inject(user_service): Inject<SharedUserWritePort>,
+    Inject(links): Inject<LinkBuilder>,
+    response_ctx: ResponseContext,
+    Payload(payload): Payload<BulkUpdateUsers>,
+) -> impl IntoResponse {
+    match user_service.update_users(payload).await {
+        Ok(response) => (
+            StatusCode::OK,
+            Negotiate::new(response_ctx, response.with_links(&links)),
+        ).into_response(),
+        Err(UserError::ValidationError(errors)) => {
+            warn!(?errors, "Controller: Validation failed for bulk update");
+            (
+                StatusCode::BAD_REQUEST,
+                NegotiateError::new(response_ctx, ValidationErrorResponse { errors }),
+            ).into_response()
+        }
+        Err(UserError::DatabaseError(err)) => {
+            error!(error = %err, "Controller: Database error in bulk update");
+            err.into_response()
+        }
+        Err(_) => {
+            // Other errors shouldn't happen here, but handle them anyway
+            internal_error()
+        }
+    }
+}
+
+/// Maps the result of a lifecycle transition to an HTTP response
+fn lifecycle_response(
+    result: Result<User, UserError>,
+    id: i32,
+    response_ctx: ResponseContext,
+    links: &LinkBuilder,
+) -> axum::response::Response {
+    match result {
+        Ok(user) => (StatusCode::OK, Negotiate::new(response_ctx, user.with_links(links))).into_response(),
+        Err(e @ UserError::NotFound) => {
+            warn!(user_id = id, "Controller: User not found for status transition");
+            (
+                StatusCode::NOT_FOUND,
+                NegotiateError::new(response_ctx, ErrorResponse::new(ErrorCode::UserNotFound, e.to_string())),
+            ).into_response()
+        }
+        Err(e @ UserError::InvalidStatusTransition { .. }) => {
+            warn!(user_id = id, error = %e, "Controller: Invalid status transition");
+            (
+                StatusCode::CONFLICT,
+                NegotiateError::new(response_ctx, ErrorResponse::new(ErrorCode::InvalidStatusTransition, e.to_string())),
+            ).into_response()
+        }
+        Err(UserError::DatabaseError(err)) => {
+            error!(error = %err, user_id = id, "Controller: Database error in status transition");
+            err.into_response()
+        }
+        Err(_) => {
+            // Other errors shouldn't happen here, but handle them anyway
+            internal_error()
+        }
+    }
+}
+
+/// HTTP handler for suspending an active user
+#[utoipa::path(
+    post,
+    path = "/users/{id}/suspend",
+    tag = "users",
+    params(
+        ("id" = i32, Path, description = "User ID")
+    ),
+    responses(
+        (status = 200, description = "User suspended", body = User),
+        (status = 400, description = "Invalid user ID", body = ValidationErrorResponse),
+        (status = 404, description = "User not found", body = ErrorResponse),
+        (status = 409, description = "User cannot be suspended from its current status", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    )
+)]
+#[tracing::instrument(skip(user_service, links), fields(user_id = id))]
+pub async fn suspend_user_handler(
+    Inject(user_service): Inject<SharedUserWritePort>,
+    Inject(links): Inject<LinkBuilder>,
+    response_ctx: ResponseContext,
+    UserId(id): UserId,
+) -> impl IntoResponse {
+    lifecycle_response(user_service.suspend_user(id).await, id, response_ctx, &links)
+}
+
+/// HTTP handler for reactivating a suspended user
+#[utoipa::path(
+    post,
+    path = "/users/{id}/activate",
+    tag = "users",
+    params(
+        ("id" = i32, Path, description = "User ID")
+    ),
+    responses(
+        (status = 200, description = "User activated", body = User),
+        (status = 400, description = "Invalid user ID", body = ValidationErrorResponse),
+        (status = 404, description = "User not found", body = ErrorResponse),
+        (status = 409, description = "User cannot be activated from its current status", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    )
+)]
+#[tracing::instrument(skip(user_service, links), fields(user_id = id))]
+pub async fn activate_user_handler(
+    Inject(user_service): Inject<SharedUserWritePort>,
+    Inject(links): Inject<LinkBuilder>,
+    response_ctx: ResponseContext,
+    UserId(id): UserId,
+) -> impl IntoResponse {
+    lifecycle_response(user_service.activate_user(id).await, id, response_ctx, &links)
+}
+
+/// HTTP handler for archiving a user
+#[utoipa::path(
+    post,
+    path = "/users/{id}/archive",
+    tag = "users",
+    params(
+        ("id" = i32, Path, description = "User ID")
+    ),
+    responses(
+        (status = 200, description = "User archived", body = User),
+        (status = 400, description = "Invalid user ID", body = ValidationErrorResponse),
+        (status = 404, description = "User not found", body = ErrorResponse),
+        (status = 409, description = "User is already archived", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    )
+)]
+#[tracing::instrument(skip(user_service, links), fields(user_id = id))]
+pub async fn archive_user_handler(
+    Inject(user_service): Inject<SharedUserWritePort>,
+    Inject(links): Inject<LinkBuilder>,
+    response_ctx: ResponseContext,
+    UserId(id): UserId,
+) -> impl IntoResponse {
+    lifecycle_response(user_service.archive_user(id).await, id, response_ctx, &links)
+}
+
+/// HTTP handler for listing the previous versions of a user
+#[utoipa::path(
+    get,
+    path = "/users/{id}/history",
+    tag = "users",
+    params(
+        ("id" = i32, Path, description = "User ID")
+    ),
+    responses(
+        (status = 200, description = "Previous versions, newest first, with the fields each change altered", body = UserHistory),
+        (status = 400, description = "Invalid user ID", body = ValidationErrorResponse),
+        (status = 404, description = "User never existed", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    )
+)]
+#[tracing::instrument(skip(user_service, links), fields(user_id = id))]
+pub async fn user_history_handler(
+    Inject(user_service): Inject<SharedUserReadPort>,
+    Inject(links): Inject<LinkBuilder>,
+    response_ctx: ResponseContext,
+    UserId(id): UserId,
+) -> impl IntoResponse {
+    match user_service.get_user_history(id).await {
+        Ok(history) => {
+            let history = UserHistory { current: history.current.map(|user| user.with_links(&links)), ..history };
+            (StatusCode::OK, Negotiate::new(response_ctx, history)).into_response()
+        }
+        Err(e @ UserError::NotFound) => {
+            warn!(user_id = id, "Controller: User not found for history");
+            (
+                StatusCode::NOT_FOUND,
+                NegotiateError::new(response_ctx, ErrorResponse::new(ErrorCode::UserNotFound, e.to_string())),
+            ).into_response()
+        }
+        Err(UserError::DatabaseError(err)) => {
+            error!(error = %err, user_id = id, "Controller: Database error in get user history");
+            err.into_response()
+        }
+        Err(_) => internal_error(),
+    }
+}
+
+/// HTTP handler for restoring a previous version of a user
+///
+/// The version is applied as a new update, so the replaced version shows up
+/// in the history and the revert in the activity timeline.
+#[utoipa::path(
+    post,
+    path = "/users/{id}/revert",
+    tag = "users",
+    params(
+        ("id" = i32, Path, description = "User ID"),
+        ("version" = i32, Query, description = "Version to restore, as listed in the user's history")
+    ),
+    responses(
+        (status = 200, description = "User restored to the version", body = User),
+        (status = 400, description = "Invalid user ID or the version fails current validation rules", body = ValidationErrorResponse),
+        (status = 404, description = "User or version not found", body = ErrorResponse),
+        (status = 409, description = "The version's status cannot be reached from the current status", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    )
+)]
+#[tracing::instrument(skip(user_service, links, params), fields(user_id = id, version = params.version))]
+pub async fn revert_user_handler(
+    Inject(user_service): Inject<SharedUserWritePort>,
+    Inject(links): Inject<LinkBuilder>,
+    response_ctx: ResponseContext,
+    UserId(id): UserId,
+    Query(params): Query<RevertParams>,
+) -> impl IntoResponse {
+    match user_service.revert_user(id, params.version).await {
+        Ok(user) => (StatusCode::OK, Negotiate::new(response_ctx, user.with_links(&links))).into_response(),
+        Err(UserError::ValidationError(errors)) => {
+            warn!(?errors, user_id = id, "Controller: Restored version fails validation");
+            (
+                StatusCode::BAD_REQUEST,
+                NegotiateError::new(response_ctx, ValidationErrorResponse { errors }),
+            ).into_response()
+        }
+        Err(e @ (UserError::NotFound | UserError::VersionNotFound(_))) => {
+            warn!(user_id = id, version = params.version, "Controller: User or version not found for revert");
+            let code = if matches!(e, UserError::NotFound) { ErrorCode::UserNotFound } else { ErrorCode::VersionNotFound };
+            (
+                StatusCode::NOT_FOUND,
+                NegotiateError::new(response_ctx, ErrorResponse::new(code, e.to_string())),
+            ).into_response()
+        }
+        Err(e @ UserError::InvalidStatusTransition { .. }) => {
+            warn!(user_id = id, error = %e, "Controller: Restored status not reachable");
+            (
+                StatusCode::CONFLICT,
+                NegotiateError::new(response_ctx, ErrorResponse::new(ErrorCode::InvalidStatusTransition, e.to_string())),
+            ).into_response()
+        }
+        Err(UserError::DatabaseError(err)) => {
+            error!(error = %err, user_id = id, "Controller: Database error in revert user");
+            err.into_response()
+        }
+        Err(UserError::InvalidToken) => internal_error(),
+    }
+}
@@ -1,29 +1,120 @@
 //! User domain models and validation logic
 
 use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
 use utoipa::ToSchema;
 use chrono::{DateTime, Utc};
+use std::collections::BTreeMap;
+
+use crate::db::DbError;
+use crate::error_codes::ErrorCode;
+use crate::links::{Link, LinkBuilder};
+use crate::pagination::SortOrder;
+use crate::negotiation::ErrorBody;
 
 /// Request payload for creating a new user
-#[derive(Deserialize, ToSchema, Debug, Clone)]
+#[derive(Deserialize, ToSchema, JsonSchema, Debug, Clone)]
+#[cfg_attr(feature = "typescript", derive(ts_rs::TS))]
 pub struct CreateUser {
     /// User's full name
     pub name: String,
     /// User's age in years
     pub age: i32,
+    /// Free-form string labels, e.g. `{"department": "eng"}`
+    #[serde(default)]
+    #[schema(value_type = Option<HashMap<String, String>>)]
+    #[cfg_attr(feature = "typescript", ts(optional))]
+    pub metadata: Option<Metadata>,
 }
 
+/// Free-form string labels attached to a user, filterable on listings
+pub type Metadata = BTreeMap<String, String>;
+
+/// Prefix of the query parameters filtering users by metadata label
+pub const METADATA_FILTER_PREFIX: &str = "metadata.";
+
 /// Request payload for updating an existing user
-#[derive(Deserialize, ToSchema, Debug, Clone)]
+#[derive(Deserialize, ToSchema, JsonSchema, Debug, Clone)]
+#[cfg_attr(feature = "typescript", derive(ts_rs::TS))]
 pub struct UpdateUser {
     /// Updated user name (optional)
     pub name: Option<String>,
     /// Updated user age (optional)
     pub age: Option<i32>,
+    /// Replacement metadata (optional); replaces all existing labels
+    #[serde(default)]
+    #[schema(value_type = Option<HashMap<String, String>>)]
+    #[cfg_attr(feature = "typescript", ts(optional))]
+    pub metadata: Option<Metadata>,
+}
+
+/// Request payload for creating or updating a user by its external identifier
+#[derive(Deserialize, ToSchema, JsonSchema, Debug, Clone)]
+#[cfg_attr(feature = "typescript", derive(ts_rs::TS))]
+pub struct UpsertUser {
+    /// Identifier of the user in the syncing system, e.g. an email address
+    pub external_id: String,
+    /// User's full name
+    pub name: String,
+    /// User's age in years
+    pub age: i32,
+}
+
+/// Result of an upsert by external identifier
+#[derive(Debug, Clone)]
+pub struct UpsertedUser {
+    /// The user as stored
+    pub user: User,
+    /// Whether the upsert inserted a new user rather than updating one
+    pub created: bool,
+}
+
+/// Lifecycle status of a user account
+#[derive(Serialize, Deserialize, ToSchema, JsonSchema, Debug, Clone, Copy, PartialEq, Eq, sqlx::Type)]
+#[sqlx(type_name = "user_status", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+#[cfg_attr(feature = "typescript", derive(ts_rs::TS))]
+pub enum UserStatus {
+    /// Regular, fully usable account
+    Active,
+    /// Temporarily disabled account that can be reactivated
+    Suspended,
+    /// Retired account; this is a terminal state
+    Archived,
+}
+
+impl UserStatus {
+    /// Returns the states this status may transition from to reach `self`
+    #[must_use]
+    pub fn allowed_sources(self) -> &'static [UserStatus] {
+        match self {
+            Self::Active => &[Self::Suspended],
+            Self::Suspended => &[Self::Active],
+            Self::Archived => &[Self::Active, Self::Suspended],
+        }
+    }
+
+    /// Checks whether a transition from `self` to `target` is permitted
+    #[must_use]
+    pub fn can_transition_to(self, target: UserStatus) -> bool {
+        target.allowed_sources().contains(&self)
+    }
+}
+
+impl std::fmt::Display for UserStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let value = match self {
+            Self::Active => "active",
+            Self::Suspended => "suspended",
+            Self::Archived => "archived",
+        };
+        f.write_str(value)
+    }
 }
 
 /// User entity returned by the API
-#[derive(Serialize, ToSchema, Debug, Clone, sqlx::FromRow)]
+#[derive(Serialize, ToSchema, JsonSchema, Debug, Clone)]
+#[cfg_attr(feature = "typescript", derive(ts_rs::TS))]
 pub struct User {
     /// Unique user identifier
     pub id: i32,
@@ -33,51 +124,456 @@ pub struct User {
     pub age: i32,
     /// When the user was created
     pub created_at: DateTime<Utc>,
+    /// When the user was last modified (served as `Last-Modified`)
+    pub updated_at: DateTime<Utc>,
+    /// Current lifecycle status
+    pub status: UserStatus,
+    /// Free-form string labels (absent when none were set)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<HashMap<String, String>>)]
+    #[cfg_attr(feature = "typescript", ts(optional))]
+    pub metadata: Option<Metadata>,
+    /// Non-blocking findings about the submitted data (create and update responses only)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[cfg_attr(feature = "typescript", ts(as = "Option<Vec<ValidationWarning>>", optional))]
+    pub warnings: Vec<ValidationWarning>,
+    /// Hypermedia links to this user and its sub-resources
+    #[serde(rename = "_links", skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "typescript", ts(optional))]
+    pub links: Option<UserLinks>,
+}
+
+impl User {
+    /// Attaches the hypermedia links for this user
+    #[must_use]
+    pub fn with_links(self, links: &LinkBuilder) -> Self {
+        let base = format!("/users/{}", self.id);
+        Self {
+            links: Some(UserLinks {
+                self_link: links.link(&base),
+                addresses: links.link(&format!("{base}/addresses")),
+                tags: links.link(&format!("{base}/tags")),
+            }),
+            ..self
+        }
+    }
+
+    /// Attaches the validation warnings raised by the request that produced this user
+    #[must_use]
+    pub fn with_warnings(self, warnings: Vec<ValidationWarning>) -> Self {
+        Self { warnings, ..self }
+    }
+}
+
+/// Hypermedia links of a user
+#[derive(Serialize, ToSchema, JsonSchema, Debug, Clone)]
+#[cfg_attr(feature = "typescript", derive(ts_rs::TS))]
+pub struct UserLinks {
+    /// This user
+    #[serde(rename = "self")]
+    pub self_link: Link,
+    /// The user's addresses
+    pub addresses: Link,
+    /// The user's tags
+    pub tags: Link,
 }
 
 /// Individual validation error
-#[derive(Serialize, ToSchema, Debug, Clone)]
+#[derive(Serialize, ToSchema, JsonSchema, Debug, Clone)]
+#[cfg_attr(feature = "typescript", derive(ts_rs::TS))]
 pub struct ValidationError {
+    /// Machine-readable error code
+    pub code: ErrorCode,
     /// Error message describing the validation failure
     pub message: String,
     /// Field name that caused the validation error (if applicable)
     pub field: Option<String>,
 }
 
+/// Individual validation warning: unusual but accepted data
+#[derive(Serialize, ToSchema, JsonSchema, Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "typescript", derive(ts_rs::TS))]
+pub struct ValidationWarning {
+    /// Machine-readable warning code
+    pub code: ErrorCode,
+    /// Message describing what looks unusual
+    pub message: String,
+    /// Field name the warning is about (if applicable)
+    pub field: Option<String>,
+}
+
+impl From<ValidationWarning> for ValidationError {
+    fn from(warning: ValidationWarning) -> Self {
+        Self { code: warning.code, message: warning.message, field: warning.field }
+    }
+}
+
 /// Response containing validation errors
-#[derive(Serialize, ToSchema, Debug, Clone)]
+#[derive(Serialize, ToSchema, JsonSchema, Debug, Clone)]
+#[cfg_attr(feature = "typescript", derive(ts_rs::TS))]
 pub struct ValidationErrorResponse {
     /// List of validation errors
     pub errors: Vec<ValidationError>,
 }
 
 /// Generic API response with a message
-#[derive(Serialize, ToSchema, Debug, Clone)]
+#[derive(Serialize, ToSchema, JsonSchema, Debug, Clone)]
+#[cfg_attr(feature = "typescript", derive(ts_rs::TS))]
 pub struct ApiResponse {
     /// Response message
     pub message: String,
 }
 
+impl ErrorBody for ValidationErrorResponse {
+    type Item = ValidationError;
+
+    fn items(&self) -> &[ValidationError] {
+        &self.errors
+    }
+}
+
 /// Pagination parameters for user queries
 #[derive(Deserialize, ToSchema, Debug, Clone)]
 pub struct PaginationParams {
-    /// Pagination token from previous page (opaque cursor)
+    /// Token of the page to continue after (opaque cursor)
     pub next_token: Option<String>,
-    /// Number of records to return (default: 200, max: 200)
+    /// Token of the page to continue before (opaque cursor); excludes `next_token`
+    pub prev_token: Option<String>,
+    /// Sort order by creation time (default: asc)
+    pub order: Option<SortOrder>,
+    /// Number of records to return (default `PAGINATION_DEFAULT_LIMIT`, capped at `PAGINATION_MAX_LIMIT`; 200 each unless configured)
     pub limit: Option<i32>,
+    /// Only return users carrying this tag
+    pub tag: Option<String>,
+    /// Include `estimated_total`, a fast approximate count of all matching users
+    pub include_estimated_total: Option<bool>,
+    /// Only return users whose metadata contains all of these labels
+    ///
+    /// Passed as `metadata.<key>=<value>` query parameters, which serde
+    /// cannot collect into a field; the handler fills this in.
+    #[serde(skip)]
+    pub metadata: Metadata,
 }
 
 /// Paginated response for users
-#[derive(Serialize, ToSchema, Debug, Clone)]
+#[derive(Serialize, ToSchema, JsonSchema, Debug, Clone)]
+#[cfg_attr(feature = "typescript", derive(ts_rs::TS))]
 pub struct PaginatedUsersResponse {
     /// List of users for this page
     pub users: Vec<User>,
     /// Token for the next page (opaque cursor)
     pub next_token: Option<String>,
-    /// Whether there are more users available
+    /// Token for the previous page (absent on the first page)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "typescript", ts(optional))]
+    pub prev_token: Option<String>,
+    /// Whether there are more users available after this page
     pub has_more: bool,
     /// Total number of users returned in this page
     pub count: usize,
+    /// Approximate number of users across all pages, when requested with
+    /// `include_estimated_total=true`; may lag recent writes
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "typescript", ts(optional))]
+    pub estimated_total: Option<i64>,
+    /// Hypermedia links to this page and its neighbours
+    #[serde(rename = "_links", skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "typescript", ts(optional))]
+    pub links: Option<UsersPageLinks>,
+}
+
+/// Query string of a users page link
+#[derive(Serialize)]
+struct PageQuery<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    next_token: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    prev_token: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    order: Option<SortOrder>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    limit: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tag: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    include_estimated_total: Option<bool>,
+    /// Metadata filters, keyed `metadata.<key>`
+    #[serde(flatten)]
+    metadata: BTreeMap<String, &'a str>,
+}
+
+impl PaginatedUsersResponse {
+    /// Attaches page links (and links on every user) for the request made with `params`
+    #[must_use]
+    pub fn with_links(self, links: &LinkBuilder, params: &PaginationParams) -> Self {
+        let page = |next_token: Option<&str>, prev_token: Option<&str>| {
+            links.link_with_query(
+                "/users",
+                &PageQuery {
+                    next_token,
+                    prev_token,
+                    order: params.order,
+                    limit: params.limit,
+                    tag: params.tag.as_deref(),
+                    include_estimated_total: params.include_estimated_total,
+                    metadata: params
+                        .metadata
+                        .iter()
+                        .map(|(key, value)| (format!("{METADATA_FILTER_PREFIX}{key}"), value.as_str()))
+                        .collect(),
+                },
+            )
+        };
+        let page_links = UsersPageLinks {
+            self_link: page(params.next_token.as_deref(), params.prev_token.as_deref()),
+            first: page(None, None),
+            next: self.next_token.as_deref().map(|token| page(Some(token), None)),
+            prev: self.prev_token.as_deref().map(|token| page(None, Some(token))),
+        };
+
+        Self {
+            users: self.users.into_iter().map(|user| user.with_links(links)).collect(),
+            links: Some(page_links),
+            ..self
+        }
+    }
+}
+
+/// Hypermedia links of a users page
+///
+/// Cursors work in both directions, so `next` and `prev` stay valid while
+/// users are added or removed between requests.
+#[derive(Serialize, ToSchema, JsonSchema, Debug, Clone)]
+#[cfg_attr(feature = "typescript", derive(ts_rs::TS))]
+pub struct UsersPageLinks {
+    /// This page
+    #[serde(rename = "self")]
+    pub self_link: Link,
+    /// The first page
+    pub first: Link,
+    /// The next page (absent on the last page)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "typescript", ts(optional))]
+    pub next: Option<Link>,
+    /// The previous page (absent on the first page)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "typescript", ts(optional))]
+    pub prev: Option<Link>,
+}
+
+/// Query parameters for bulk operations addressed by ID
+#[derive(Deserialize, ToSchema, Debug, Clone, Default)]
+pub struct BulkIdsQuery {
+    /// Comma-separated list of user IDs (e.g. `1,2,3`)
+    pub ids: Option<String>,
+}
+
+/// Request body carrying a list of user IDs
+#[derive(Deserialize, ToSchema, JsonSchema, Debug, Clone)]
+pub struct BulkIdsRequest {
+    /// User IDs to operate on
+    pub ids: Vec<i32>,
+}
+
+/// Request payload for applying the same partial update to many users
+#[derive(Deserialize, ToSchema, JsonSchema, Debug, Clone)]
+pub struct BulkUpdateUsers {
+    /// User IDs to update
+    pub ids: Vec<i32>,
+    /// Changes applied to every listed user
+    pub changes: UpdateUser,
+}
+
+/// Outcome of a bulk operation for a single user
+#[derive(Serialize, ToSchema, JsonSchema, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum BulkItemStatus {
+    /// The user was deleted
+    Deleted,
+    /// The user was updated
+    Updated,
+    /// No user exists with this ID
+    NotFound,
+}
+
+/// Per-ID result of a bulk operation
+#[derive(Serialize, ToSchema, JsonSchema, Debug, Clone)]
+pub struct BulkItemResult {
+    /// User ID this result refers to
+    pub id: i32,
+    /// What happened to this user
+    pub status: BulkItemStatus,
+    /// Updated user (only present for successful updates)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user: Option<User>,
+}
+
+/// User totals for dashboards
+#[derive(Serialize, ToSchema, JsonSchema, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UserCounts {
+    /// All users
+    pub total: i64,
+    /// Users created since the requested instant
+    pub created_since: i64,
+}
+
+/// Response for bulk delete and bulk update operations
+#[derive(Serialize, ToSchema, JsonSchema, Debug, Clone)]
+pub struct BulkOperationResponse {
+    /// Per-ID results in request order
+    pub results: Vec<BulkItemResult>,
+    /// Number of users successfully processed
+    pub succeeded: usize,
+    /// Number of IDs that did not match any user
+    pub not_found: usize,
+}
+
+impl BulkOperationResponse {
+    /// Attaches hypermedia links to every user in the results
+    #[must_use]
+    pub fn with_links(self, links: &LinkBuilder) -> Self {
+        Self {
+            results: self
+                .results
+                .into_iter()
+                .map(|result| BulkItemResult {
+                    user: result.user.map(|user| user.with_links(links)),
+                    ..result
+                })
+                .collect(),
+            ..self
+        }
+    }
+
+    /// Builds a response from per-ID results, computing the summary counters
+    #[must_use]
+    pub fn from_results(results: Vec<BulkItemResult>) -> Self {
+        let not_found = results
+            .iter()
+            .filter(|r| r.status == BulkItemStatus::NotFound)
+            .count();
+        Self {
+            succeeded: results.len() - not_found,
+            not_found,
+            results,
+        }
+    }
+}
+
+/// Change that replaced a historical version of a user
+#[derive(Serialize, ToSchema, JsonSchema, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum HistoryOperation {
+    /// The user was updated
+    Update,
+    /// The user was deleted
+    Delete,
+}
+
+/// A field whose value differs between a version and the one that replaced it
+#[derive(Serialize, ToSchema, JsonSchema, Debug, Clone, PartialEq)]
+pub struct FieldChange {
+    /// Name of the changed field
+    pub field: String,
+    /// Value in this version
+    pub from: serde_json::Value,
+    /// Value after the change
+    pub to: serde_json::Value,
+}
+
+/// A previous version of a user, as it was before a change
+#[derive(Serialize, ToSchema, JsonSchema, Debug, Clone)]
+pub struct UserVersion {
+    /// Sequence number of the version, starting at 1 for the first change
+    pub version: i32,
+    /// Change that replaced this version
+    pub operation: HistoryOperation,
+    /// User's name in this version
+    pub name: String,
+    /// User's age in this version
+    pub age: i32,
+    /// Lifecycle status in this version
+    pub status: UserStatus,
+    /// When the user was created
+    pub created_at: DateTime<Utc>,
+    /// When this version was replaced
+    pub replaced_at: DateTime<Utc>,
+    /// Fields changed by the replacing update; empty for deletions
+    pub changes: Vec<FieldChange>,
+}
+
+/// Previous versions of a user, newest first
+#[derive(Serialize, ToSchema, JsonSchema, Debug, Clone)]
+pub struct UserHistory {
+    /// ID of the user
+    pub user_id: i32,
+    /// The current version, absent once the user is deleted
+    pub current: Option<User>,
+    /// Previous versions, newest first
+    pub versions: Vec<UserVersion>,
+}
+
+/// Query parameters for reverting a user to a previous version
+#[derive(Deserialize, ToSchema, Debug, Clone)]
+pub struct RevertParams {
+    /// Version to restore, as listed in the user's history
+    pub version: i32,
+}
+
+/// Kind of write recorded in the users change feed
+#[derive(Serialize, ToSchema, JsonSchema, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeOperation {
+    /// The user was created
+    Create,
+    /// The user was updated
+    Update,
+    /// The user was deleted
+    Delete,
+}
+
+/// A write to a user, as recorded in the change feed
+#[derive(Serialize, ToSchema, JsonSchema, Debug, Clone, PartialEq, Eq)]
+pub struct UserChange {
+    /// ID of the changed user
+    pub user_id: i32,
+    /// Kind of write
+    pub operation: ChangeOperation,
+    /// When the write happened
+    pub changed_at: DateTime<Utc>,
+}
+
+/// Query parameters for long-polling the users change feed
+#[derive(Deserialize, ToSchema, Debug, Clone, Default)]
+pub struct UserChangesParams {
+    /// Cursor returned by the previous poll (opaque); without it the poll
+    /// returns no changes and a cursor at the current end of the feed
+    pub since: Option<String>,
+    /// How long to wait for a change when there is none yet, e.g. `30s` or
+    /// `500ms` (default 0, capped at 60 seconds)
+    pub wait: Option<String>,
+    /// Number of changes to return (default `PAGINATION_DEFAULT_LIMIT`, capped at `PAGINATION_MAX_LIMIT`)
+    pub limit: Option<i32>,
+}
+
+/// Position in the change feed after which the next poll starts
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(super) struct ChangeCursor {
+    /// ID of the transaction that wrote the last change returned
+    pub txid: i64,
+    /// Sequence number of the last change returned
+    pub seq: i64,
+}
+
+/// Changes of users after a cursor, oldest first
+#[derive(Serialize, ToSchema, JsonSchema, Debug, Clone)]
+pub struct UserChangesPage {
+    /// Changes after the cursor; empty when the wait ran out
+    pub changes: Vec<UserChange>,
+    /// Cursor to pass as `since` on the next poll (opaque)
+    pub next_cursor: String,
+    /// Whether more changes are available right away
+    pub has_more: bool,
 }
 
 /// Domain errors for user operations
@@ -91,9 +587,40 @@ pub enum UserError {
     NotFound,
     /// Database operation failed
     #[error("Database error: {0}")]
-    DatabaseError(String),
+    DatabaseError(DbError),
     /// Invalid pagination token
     #[error("Invalid pagination token")]
     InvalidToken,
+    /// Requested lifecycle transition is not allowed from the current status
+    #[error("Cannot transition user from {from} to {to}")]
+    InvalidStatusTransition {
+        /// Current status of the user
+        from: UserStatus,
+        /// Requested target status
+        to: UserStatus,
+    },
+    /// The user has no previous version with this number
+    #[error("User version {0} not found")]
+    VersionNotFound(i32),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_user_status_allowed_transitions() {
+        assert!(UserStatus::Active.can_transition_to(UserStatus::Suspended));
+        assert!(UserStatus::Active.can_transition_to(UserStatus::Archived));
+        assert!(UserStatus::Suspended.can_transition_to(UserStatus::Active));
+        assert!(UserStatus::Suspended.can_transition_to(UserStatus::Archived));
+    }
+
+    #[test]
+    fn test_user_status_rejected_transitions() {
+        assert!(!UserStatus::Active.can_transition_to(UserStatus::Active));
+        assert!(!UserStatus::Archived.can_transition_to(UserStatus::Active));
+        assert!(!UserStatus::Archived.can_transition_to(UserStatus::Suspended));
+    }
 }
 
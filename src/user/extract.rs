@@ -0,0 +1,73 @@
+//! User request extractors
+
+use std::collections::HashMap;
+
+use axum::{
+    Json,
+    extract::{FromRequestParts, Path},
+    http::{StatusCode, request::Parts},
+    response::{IntoResponse, Response},
+};
+use tracing::warn;
+
+use crate::error_codes::ErrorCode;
+
+use super::domain::ValidationErrorResponse;
+use super::validation::{common::field_error, validate_id};
+
+/// Path parameter holding the user ID
+const USER_ID_PARAM: &str = "id";
+
+/// User ID taken from the `{id}` path segment
+///
+/// Rejects non-numeric and non-positive IDs with a 400 validation body before
+/// any service or database call is made.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UserId(pub i32);
+
+impl<S> FromRequestParts<S> for UserId
+where
+    S: Send + Sync,
+{
+    type Rejection = Response;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let Path(params) = Path::<HashMap<String, String>>::from_request_parts(parts, state)
+            .await
+            .map_err(IntoResponse::into_response)?;
+        let raw = params.get(USER_ID_PARAM).map(String::as_str).unwrap_or_default();
+
+        let validated = raw
+            .parse::<i32>()
+            .map_err(|_e| vec![field_error(USER_ID_PARAM, ErrorCode::InvalidId, "ID must be a positive integer")])
+            .and_then(|id| validate_id(id, USER_ID_PARAM).map(|()| id));
+
+        validated.map(Self).map_err(|errors| {
+            warn!(raw_id = raw, "Rejected invalid user ID in path");
+            (StatusCode::BAD_REQUEST, Json(ValidationErrorResponse { errors })).into_response()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{Router, body::Body, http::Request, routing::get};
+    use tower::ServiceExt;
+
+    async fn status(uri: &str) -> StatusCode {
+        let app = Router::new().route("/users/{id}", get(|UserId(id): UserId| async move { id.to_string() }));
+        app.oneshot(Request::get(uri).body(Body::empty()).unwrap())
+            .await
+            .unwrap()
+            .status()
+    }
+
+    #[tokio::test]
+    async fn test_user_id_extractor() {
+        assert_eq!(status("/users/1").await, StatusCode::OK);
+        assert_eq!(status("/users/0").await, StatusCode::BAD_REQUEST);
+        assert_eq!(status("/users/-5").await, StatusCode::BAD_REQUEST);
+        assert_eq!(status("/users/abc").await, StatusCode::BAD_REQUEST);
+    }
+}
@@ -8,15 +8,21 @@
 pub mod domain;
 pub mod repository;
 pub mod service;
+pub mod ports;
 pub mod services;
 pub mod controller;
+pub mod extract;
 pub mod validation;
 
-// Public exports - only UserService is exposed to other modules
+// Public exports - only UserService and its ports are exposed to other modules
 pub use service::UserService;
+pub use ports::{SharedUserReadPort, SharedUserWritePort, UserReadPort, UserWritePort};
 
 // Re-export domain types that other modules might need
-pub use domain::{User, CreateUser, UpdateUser};
+pub use domain::{User, CreateUser, UpdateUser, UserCounts};
+
+// Path extractors shared by routes nested under /users/{id}
+pub use extract::UserId;
 
 // Export controller for OpenAPI documentation (but discourage direct use)
 pub use controller::*;
\ No newline at end of file
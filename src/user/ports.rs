@@ -0,0 +1,86 @@
+//! User ports - the use cases the user module offers to the rest of the application
+//!
+//! Controllers and other modules (addresses, tags, bank) depend on these
+//! traits rather than on the concrete `UserService`, so an implementation can
+//! be swapped (a cache in front of the database, an in-memory fake in unit
+//! tests) without touching the callers. `UserService` implements both ports.
+
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use futures_util::future::BoxFuture;
+use futures_util::stream::BoxStream;
+
+use super::domain::{
+    ApiResponse, BulkOperationResponse, BulkUpdateUsers, CreateUser, PaginatedUsersResponse, PaginationParams,
+    UpdateUser, UpsertUser, UpsertedUser, User, UserChangesPage, UserChangesParams, UserCounts, UserError, UserHistory,
+};
+
+/// Read-side user use cases
+pub trait UserReadPort: Send + Sync {
+    /// Retrieves all users
+    fn get_all_users(&self) -> BoxFuture<'_, Result<Vec<User>, UserError>>;
+
+    /// Streams all users with constant memory usage
+    fn stream_users(&self) -> BoxStream<'static, Result<User, UserError>>;
+
+    /// Retrieves users with pagination
+    fn get_users_paginated(&self, params: PaginationParams) -> BoxFuture<'_, Result<PaginatedUsersResponse, UserError>>;
+
+    /// Retrieves a specific user by ID
+    fn get_user_by_id(&self, id: i32) -> BoxFuture<'_, Result<User, UserError>>;
+
+    /// Checks if a user exists
+    fn user_exists(&self, id: i32) -> BoxFuture<'_, Result<bool, UserError>>;
+
+    /// Gets a user's name by ID
+    fn get_user_name(&self, id: i32) -> BoxFuture<'_, Result<String, UserError>>;
+
+    /// Counts all users and those created since `since`
+    fn count_users(&self, since: DateTime<Utc>) -> BoxFuture<'_, Result<UserCounts, UserError>>;
+
+    /// Retrieves the previous versions of a user, newest first
+    fn get_user_history(&self, id: i32) -> BoxFuture<'_, Result<UserHistory, UserError>>;
+
+    /// Long-polls the users change feed for the changes after a cursor
+    fn poll_user_changes(&self, params: UserChangesParams) -> BoxFuture<'_, Result<UserChangesPage, UserError>>;
+}
+
+/// Write-side user use cases
+pub trait UserWritePort: Send + Sync {
+    /// Creates a new user with validation
+    fn create_user(&self, user_data: CreateUser) -> BoxFuture<'_, Result<User, UserError>>;
+
+    /// Updates an existing user with validation
+    fn update_user(&self, id: i32, user_data: UpdateUser) -> BoxFuture<'_, Result<User, UserError>>;
+
+    /// Creates or updates the user with the payload's external ID in one atomic step
+    fn upsert_user(&self, user_data: UpsertUser) -> BoxFuture<'_, Result<UpsertedUser, UserError>>;
+
+    /// Deletes a user
+    fn delete_user(&self, id: i32) -> BoxFuture<'_, Result<ApiResponse, UserError>>;
+
+    /// Deletes many users at once, reporting the outcome per ID
+    fn delete_users(&self, ids: Vec<i32>) -> BoxFuture<'_, Result<BulkOperationResponse, UserError>>;
+
+    /// Applies the same partial update to many users at once
+    fn update_users(&self, request: BulkUpdateUsers) -> BoxFuture<'_, Result<BulkOperationResponse, UserError>>;
+
+    /// Suspends an active user
+    fn suspend_user(&self, id: i32) -> BoxFuture<'_, Result<User, UserError>>;
+
+    /// Reactivates a suspended user
+    fn activate_user(&self, id: i32) -> BoxFuture<'_, Result<User, UserError>>;
+
+    /// Archives a user; archived users cannot be reactivated
+    fn archive_user(&self, id: i32) -> BoxFuture<'_, Result<User, UserError>>;
+
+    /// Restores a previous version of a user as a new update
+    fn revert_user(&self, id: i32, version: i32) -> BoxFuture<'_, Result<User, UserError>>;
+}
+
+/// Read port shared between services and the service registry
+pub type SharedUserReadPort = Arc<dyn UserReadPort>;
+
+/// Write port shared between services and the service registry
+pub type SharedUserWritePort = Arc<dyn UserWritePort>;
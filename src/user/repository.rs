@@ -3,89 +3,375 @@
 //! This module is private to the user module and cannot be accessed directly
 //! by other modules. All database access must go through `UserService`.
 
-use sqlx::PgPool;
+use async_stream::try_stream;
+use futures_util::{Stream, TryStreamExt};
+use sqlx::types::Json;
+use sqlx::{PgConnection, PgExecutor, PgPool};
 use tracing::{error, info, warn};
 use chrono::{DateTime, Utc};
+use serde_json::json;
 
-use super::domain::{User, CreateUser, UpdateUser, UserError};
+use crate::audit::{self, AuditRecord};
+use crate::change_log;
+use crate::db::ColumnRename;
+use crate::events::DomainEvent;
+use crate::pagination::SortOrder;
+
+use super::domain::{User, CreateUser, UpdateUser, Metadata, UpsertUser, UpsertedUser, UserCounts, UserError, UserStatus, UserVersion, HistoryOperation, ChangeOperation, UserChange};
+
+/// A `users` row; converted into the `User` entity before leaving the repository
+#[derive(sqlx::FromRow)]
+struct UserRow {
+    id: i32,
+    name: String,
+    age: i32,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+    status: UserStatus,
+    metadata: Option<Json<Metadata>>,
+}
+
+impl From<UserRow> for User {
+    fn from(row: UserRow) -> Self {
+        Self {
+            id: row.id,
+            name: row.name,
+            age: row.age,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+            status: row.status,
+            metadata: row.metadata.map(|Json(metadata)| metadata),
+            warnings: Vec::new(),
+            links: None,
+        }
+    }
+}
+
+/// A `users_history` row; diffs are filled in by the history service
+#[derive(sqlx::FromRow)]
+struct UserVersionRow {
+    version: i32,
+    operation: String,
+    name: String,
+    age: i32,
+    status: UserStatus,
+    created_at: DateTime<Utc>,
+    changed_at: DateTime<Utc>,
+}
+
+impl From<UserVersionRow> for UserVersion {
+    fn from(row: UserVersionRow) -> Self {
+        Self {
+            version: row.version,
+            operation: if row.operation == "delete" { HistoryOperation::Delete } else { HistoryOperation::Update },
+            name: row.name,
+            age: row.age,
+            status: row.status,
+            created_at: row.created_at,
+            replaced_at: row.changed_at,
+            changes: Vec::new(),
+        }
+    }
+}
+
+/// Position of a change in the feed: the writing transaction's ID, then the sequence number
+pub(super) type ChangePosition = (i64, i64);
+
+/// A settled `user_changes` row
+#[derive(sqlx::FromRow)]
+struct UserChangeRow {
+    txid: i64,
+    seq: i64,
+    user_id: i32,
+    operation: String,
+    changed_at: DateTime<Utc>,
+}
+
+impl From<UserChangeRow> for (ChangePosition, UserChange) {
+    fn from(row: UserChangeRow) -> Self {
+        let operation = match row.operation.as_str() {
+            "create" => ChangeOperation::Create,
+            "delete" => ChangeOperation::Delete,
+            _ => ChangeOperation::Update,
+        };
+        ((row.txid, row.seq), UserChange { user_id: row.user_id, operation, changed_at: row.changed_at })
+    }
+}
 
 /// User repository for database operations
 #[derive(Clone)]
 pub(super) struct UserRepository {
     pool: PgPool,
+    /// Column being renamed, mirrored after every write while set
+    dual_write: Option<ColumnRename>,
 }
 
 impl UserRepository {
     /// Creates a new `UserRepository` instance
     pub(super) fn new(pool: PgPool) -> Self {
-        Self { pool }
+        Self { pool, dual_write: None }
+    }
+
+    /// Mirrors every write of `rename`'s old column into its new one
+    pub(super) fn with_dual_write(mut self, rename: ColumnRename) -> Self {
+        self.dual_write = Some(rename);
+        self
+    }
+
+    /// Copies the renamed column of the written rows when dual-writing
+    ///
+    /// Writes committed without the copy (a failure in between, a writer
+    /// without dual-write) are left to the backfill job.
+    async fn mirror<'e>(&self, executor: impl PgExecutor<'e>, ids: &[i32]) -> Result<(), UserError> {
+        let Some(rename) = &self.dual_write else { return Ok(()) };
+        rename.mirror(executor, ids).await.map(drop).map_err(|e| {
+            error!(error = %e, column = rename.to(), "Failed to dual-write renamed user column");
+            UserError::DatabaseError(e.into())
+        })
     }
 
     /// Creates a new user in the database
-    pub(super) async fn create(&self, user_data: &CreateUser) -> Result<User, UserError> {
+    ///
+    /// Without an explicit `id` the users sequence assigns one.
+    pub(super) async fn create(
+        &self,
+        user_data: &CreateUser,
+        id: Option<i32>,
+        created_at: DateTime<Utc>,
+    ) -> Result<User, UserError> {
+        let user = Self::insert(&self.pool, user_data, id, created_at).await?;
+        self.mirror(&self.pool, &[user.id]).await?;
+        Ok(user)
+    }
+
+    /// Creates a new user on `conn`, typically inside the caller's transaction
+    pub(super) async fn create_in(
+        &self,
+        conn: &mut PgConnection,
+        user_data: &CreateUser,
+        id: Option<i32>,
+        created_at: DateTime<Utc>,
+    ) -> Result<User, UserError> {
+        let user = Self::insert(&mut *conn, user_data, id, created_at).await?;
+        self.mirror(conn, &[user.id]).await?;
+        Ok(user)
+    }
+
+    async fn insert<'e>(
+        executor: impl PgExecutor<'e>,
+        user_data: &CreateUser,
+        id: Option<i32>,
+        created_at: DateTime<Utc>,
+    ) -> Result<User, UserError> {
         info!(?user_data, "Creating new user in database");
 
         let user = sqlx::query_as!(
-            User,
-            "INSERT INTO users (name, age) VALUES ($1, $2) RETURNING id, name, age, created_at",
+            UserRow,
+            r#"INSERT INTO users (id, name, age, created_at, updated_at, metadata)
+               VALUES (COALESCE($1::INT4, nextval(pg_get_serial_sequence('users', 'id'))::INT4), $2, $3, $4, $4, $5)
+               RETURNING id, name, age, created_at, updated_at, status AS "status: UserStatus", metadata AS "metadata: Json<Metadata>""#,
+            id,
             user_data.name.trim(),
-            user_data.age
+            user_data.age,
+            created_at,
+            user_data.metadata.as_ref().map(Json) as Option<Json<&Metadata>>
         )
-        .fetch_one(&self.pool)
+        .fetch_one(executor)
         .await
         .map_err(|e| {
             error!(error = %e, "Failed to create user in database");
-            UserError::DatabaseError(e.to_string())
+            UserError::DatabaseError(e.into())
         })?;
 
         info!(user_id = user.id, "User created successfully in database");
-        Ok(user)
+        Ok(user.into())
+    }
+
+    /// Inserts a user or updates the one with the same external ID in a single statement
+    ///
+    /// `ON CONFLICT` makes concurrent upserts of one external ID converge on
+    /// one row. An update keeps the user's ID, creation time and status; `id`
+    /// is only used for an insert.
+    pub(super) async fn upsert(
+        &self,
+        user_data: &UpsertUser,
+        id: Option<i32>,
+        created_at: DateTime<Utc>,
+    ) -> Result<UpsertedUser, UserError> {
+        info!(?user_data, "Upserting user in database");
+
+        let row = sqlx::query!(
+            r#"INSERT INTO users (id, external_id, name, age, created_at, updated_at)
+               VALUES (COALESCE($1::INT4, nextval(pg_get_serial_sequence('users', 'id'))::INT4), $2, $3, $4, $5, $5)
+               ON CONFLICT (external_id) DO UPDATE SET name = EXCLUDED.name, age = EXCLUDED.age,
+                   updated_at = CASE WHEN (users.name, users.age) IS DISTINCT FROM (EXCLUDED.name, EXCLUDED.age)
+                                     THEN EXCLUDED.updated_at ELSE users.updated_at END
+               RETURNING id, name, age, created_at, updated_at, status AS "status: UserStatus", metadata AS "metadata: Json<Metadata>", (xmax = 0) AS "created!""#,
+            id,
+            user_data.external_id.trim(),
+            user_data.name.trim(),
+            user_data.age,
+            created_at
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| {
+            error!(error = %e, "Failed to upsert user in database");
+            UserError::DatabaseError(e.into())
+        })?;
+
+        self.mirror(&self.pool, &[row.id]).await?;
+        info!(user_id = row.id, created = row.created, "User upserted successfully in database");
+        Ok(UpsertedUser {
+            user: User {
+                id: row.id,
+                name: row.name,
+                age: row.age,
+                created_at: row.created_at,
+                updated_at: row.updated_at,
+                status: row.status,
+                metadata: row.metadata.map(|Json(metadata)| metadata),
+                warnings: Vec::new(),
+                links: None,
+            },
+            created: row.created,
+        })
     }
 
     /// Retrieves all users from the database
     pub(super) async fn find_all(&self) -> Result<Vec<User>, UserError> {
         info!("Fetching all users from database");
 
-        let users = sqlx::query_as!(User, "SELECT id, name, age, created_at FROM users ORDER BY created_at, id")
+        let users = sqlx::query_as!(UserRow, r#"SELECT id, name, age, created_at, updated_at, status AS "status: UserStatus", metadata AS "metadata: Json<Metadata>" FROM users ORDER BY created_at, id"#)
             .fetch_all(&self.pool)
             .await
             .map_err(|e| {
                 error!(error = %e, "Failed to fetch users from database");
-                UserError::DatabaseError(e.to_string())
+                UserError::DatabaseError(e.into())
             })?;
 
         info!(count = users.len(), "Users fetched successfully from database");
-        Ok(users)
+        Ok(users.into_iter().map(User::from).collect())
+    }
+
+    /// Streams all users from the database without buffering the full result set
+    ///
+    /// Rows are decoded one at a time as they arrive from the connection, so memory
+    /// usage stays constant regardless of how many users exist.
+    pub(super) fn stream_all(&self) -> impl Stream<Item = Result<User, UserError>> + Send + 'static {
+        info!("Streaming all users from database");
+
+        let pool = self.pool.clone();
+        try_stream! {
+            let mut rows = sqlx::query_as!(
+                UserRow,
+                r#"SELECT id, name, age, created_at, updated_at, status AS "status: UserStatus", metadata AS "metadata: Json<Metadata>" FROM users ORDER BY created_at, id"#
+            )
+            .fetch(&pool);
+
+            while let Some(user) = rows.try_next().await.map_err(|e| {
+                error!(error = %e, "Failed to stream users from database");
+                UserError::DatabaseError(e.into())
+            })? {
+                yield User::from(user);
+            }
+        }
     }
 
     /// Retrieves users with pagination from the database using cursor-based pagination
-    pub(super) async fn find_paginated(&self, cursor: Option<(i32, DateTime<Utc>)>, limit: i32) -> Result<Vec<User>, UserError> {
-        info!(cursor = ?cursor, limit = limit, "Fetching paginated users from database");
+    ///
+    /// Users are scanned by `(created_at, id)` in `scan` order, starting after
+    /// `cursor` in that order. When `tag` is given, only users carrying that
+    /// tag are returned; when `metadata` is given, only users whose metadata
+    /// contains all of its labels.
+    pub(super) async fn find_paginated(
+        &self,
+        cursor: Option<(i32, DateTime<Utc>)>,
+        limit: i32,
+        tag: Option<&str>,
+        metadata: Option<&Metadata>,
+        scan: SortOrder,
+    ) -> Result<Vec<User>, UserError> {
+        info!(cursor = ?cursor, limit = limit, tag, ?metadata, ?scan, "Fetching paginated users from database");
 
-        let limit_i64 = limit as i64;
+        let limit_i64 = i64::from(limit);
+        let metadata = metadata.map(Json);
 
-        let users = match cursor {
-            Some((last_id, last_timestamp)) => {
+        let users = match (cursor, scan) {
+            (Some((last_id, last_timestamp)), SortOrder::Asc) => {
                 sqlx::query_as!(
-                    User,
-                    "SELECT id, name, age, created_at FROM users 
+                    UserRow,
+                    r#"SELECT id, name, age, created_at, updated_at, status AS "status: UserStatus", metadata AS "metadata: Json<Metadata>" FROM users 
                      WHERE (created_at, id) > ($1, $2) 
+                       AND ($4::text IS NULL OR id IN (
+                           SELECT ut.user_id FROM user_tags ut
+                           JOIN tags t ON t.id = ut.tag_id
+                           WHERE t.name = $4))
+                       AND ($5::jsonb IS NULL OR metadata @> $5)
                      ORDER BY created_at, id 
-                     LIMIT $3",
+                     LIMIT $3"#,
                     last_timestamp,
                     last_id,
-                    limit_i64
+                    limit_i64,
+                    tag,
+                    metadata as Option<Json<&Metadata>>
                 )
                 .fetch_all(&self.pool)
                 .await
             }
-            None => {
+            (Some((last_id, last_timestamp)), SortOrder::Desc) => {
                 sqlx::query_as!(
-                    User,
-                    "SELECT id, name, age, created_at FROM users 
+                    UserRow,
+                    r#"SELECT id, name, age, created_at, updated_at, status AS "status: UserStatus", metadata AS "metadata: Json<Metadata>" FROM users 
+                     WHERE (created_at, id) < ($1, $2) 
+                       AND ($4::text IS NULL OR id IN (
+                           SELECT ut.user_id FROM user_tags ut
+                           JOIN tags t ON t.id = ut.tag_id
+                           WHERE t.name = $4))
+                       AND ($5::jsonb IS NULL OR metadata @> $5)
+                     ORDER BY created_at DESC, id DESC 
+                     LIMIT $3"#,
+                    last_timestamp,
+                    last_id,
+                    limit_i64,
+                    tag,
+                    metadata as Option<Json<&Metadata>>
+                )
+                .fetch_all(&self.pool)
+                .await
+            }
+            (None, SortOrder::Asc) => {
+                sqlx::query_as!(
+                    UserRow,
+                    r#"SELECT id, name, age, created_at, updated_at, status AS "status: UserStatus", metadata AS "metadata: Json<Metadata>" FROM users 
+                     WHERE ($2::text IS NULL OR id IN (
+                         SELECT ut.user_id FROM user_tags ut
+                         JOIN tags t ON t.id = ut.tag_id
+                         WHERE t.name = $2))
+                       AND ($3::jsonb IS NULL OR metadata @> $3)
                      ORDER BY created_at, id 
-                     LIMIT $1",
-                    limit_i64
+                     LIMIT $1"#,
+                    limit_i64,
+                    tag,
+                    metadata as Option<Json<&Metadata>>
+                )
+                .fetch_all(&self.pool)
+                .await
+            }
+            (None, SortOrder::Desc) => {
+                sqlx::query_as!(
+                    UserRow,
+                    r#"SELECT id, name, age, created_at, updated_at, status AS "status: UserStatus", metadata AS "metadata: Json<Metadata>" FROM users 
+                     WHERE ($2::text IS NULL OR id IN (
+                         SELECT ut.user_id FROM user_tags ut
+                         JOIN tags t ON t.id = ut.tag_id
+                         WHERE t.name = $2))
+                       AND ($3::jsonb IS NULL OR metadata @> $3)
+                     ORDER BY created_at DESC, id DESC 
+                     LIMIT $1"#,
+                    limit_i64,
+                    tag,
+                    metadata as Option<Json<&Metadata>>
                 )
                 .fetch_all(&self.pool)
                 .await
@@ -93,24 +379,24 @@ impl UserRepository {
         }
         .map_err(|e| {
             error!(error = %e, cursor = ?cursor, limit = limit, "Failed to fetch paginated users from database");
-            UserError::DatabaseError(e.to_string())
+            UserError::DatabaseError(e.into())
         })?;
 
         info!(count = users.len(), cursor = ?cursor, limit = limit, "Paginated users fetched successfully from database");
-        Ok(users)
+        Ok(users.into_iter().map(User::from).collect())
     }
 
     /// Retrieves a specific user by ID from the database
     pub(super) async fn find_by_id(&self, id: i32) -> Result<Option<User>, UserError> {
         info!(user_id = id, "Fetching user by ID from database");
 
-        let user = sqlx::query_as::<_, User>(include_str!("sql/find_user_by_id.sql"))
+        let user = sqlx::query_as::<_, UserRow>(include_str!("sql/find_user_by_id.sql"))
             .bind(id)
             .fetch_optional(&self.pool)
             .await
             .map_err(|e| {
                 error!(error = %e, user_id = id, "Failed to fetch user from database");
-                UserError::DatabaseError(e.to_string())
+                UserError::DatabaseError(e.into())
             })?;
 
         if user.is_some() {
@@ -119,11 +405,247 @@ impl UserRepository {
             warn!(user_id = id, "User not found in database");
         }
 
-        Ok(user)
+        Ok(user.map(User::from))
+    }
+
+    /// Lists the previous versions of a user recorded by the history triggers, newest first
+    pub(super) async fn find_history(&self, id: i32) -> Result<Vec<UserVersion>, UserError> {
+        info!(user_id = id, "Fetching user history from database");
+
+        let rows = sqlx::query_as!(
+            UserVersionRow,
+            r#"SELECT version, operation, name, age, status AS "status: UserStatus", created_at, changed_at
+             FROM users_history
+             WHERE user_id = $1
+             ORDER BY version DESC"#,
+            id
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| {
+            error!(error = %e, user_id = id, "Failed to fetch user history from database");
+            UserError::DatabaseError(e.into())
+        })?;
+
+        Ok(rows.into_iter().map(UserVersion::from).collect())
+    }
+
+    /// Position of the newest settled change, `None` while the feed is empty
+    ///
+    /// A change is settled once every transaction that was running when it
+    /// was written has finished, so no change can later appear before it.
+    pub(super) async fn latest_change_position(&self) -> Result<Option<ChangePosition>, UserError> {
+        let row = sqlx::query!(
+            r#"SELECT txid::text::bigint AS "txid!", seq
+             FROM user_changes
+             WHERE txid < pg_snapshot_xmin(pg_current_snapshot())
+             ORDER BY txid DESC, seq DESC
+             LIMIT 1"#
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| {
+            error!(error = %e, "Failed to fetch the latest user change from database");
+            UserError::DatabaseError(e.into())
+        })?;
+
+        Ok(row.map(|row| (row.txid, row.seq)))
+    }
+
+    /// Retrieves up to `limit` settled changes after `after`, oldest first
+    pub(super) async fn find_changes_after(&self, after: ChangePosition, limit: i32) -> Result<Vec<(ChangePosition, UserChange)>, UserError> {
+        let (txid, seq) = after;
+        let rows = sqlx::query_as!(
+            UserChangeRow,
+            r#"SELECT txid::text::bigint AS "txid!", seq, user_id, operation, changed_at
+             FROM user_changes
+             WHERE txid < pg_snapshot_xmin(pg_current_snapshot())
+               AND (txid, seq) > ($1::BIGINT::TEXT::xid8, $2)
+             ORDER BY txid, seq
+             LIMIT $3"#,
+            txid,
+            seq,
+            i64::from(limit)
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| {
+            error!(error = %e, txid, seq, "Failed to fetch user changes from database");
+            UserError::DatabaseError(e.into())
+        })?;
+
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+
+    /// Retrieves one previous version of a user
+    pub(super) async fn find_version(&self, id: i32, version: i32) -> Result<Option<UserVersion>, UserError> {
+        info!(user_id = id, version, "Fetching user version from database");
+
+        let row = sqlx::query_as!(
+            UserVersionRow,
+            r#"SELECT version, operation, name, age, status AS "status: UserStatus", created_at, changed_at
+             FROM users_history
+             WHERE user_id = $1 AND version = $2"#,
+            id,
+            version
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| {
+            error!(error = %e, user_id = id, version, "Failed to fetch user version from database");
+            UserError::DatabaseError(e.into())
+        })?;
+
+        Ok(row.map(UserVersion::from))
+    }
+
+    /// Restores the fields of `version`, returning `None` if the user's status is no longer `expected_status`
+    ///
+    /// The restore is recorded in the audit log, and `domain_event` in the
+    /// change log, in the same transaction; the history triggers record the
+    /// replaced version like for any update.
+    pub(super) async fn revert(
+        &self,
+        id: i32,
+        version: &UserVersion,
+        expected_status: UserStatus,
+        at: DateTime<Utc>,
+        domain_event: Option<&DomainEvent>,
+    ) -> Result<Option<User>, UserError> {
+        info!(user_id = id, version = version.version, "Reverting user in database");
+
+        let mut tx = self.pool.begin().await.map_err(|e| {
+            error!(error = %e, "Failed to begin transaction for user revert");
+            UserError::DatabaseError(e.into())
+        })?;
+
+        let user = sqlx::query_as!(
+            UserRow,
+            r#"UPDATE users SET name = $2, age = $3, status = $4,
+                 updated_at = CASE WHEN (name, age, status) IS DISTINCT FROM ($2::VARCHAR, $3, $4) THEN $6 ELSE updated_at END
+             WHERE id = $1 AND status = $5
+             RETURNING id, name, age, created_at, updated_at, status AS "status: UserStatus", metadata AS "metadata: Json<Metadata>""#,
+            id,
+            version.name,
+            version.age,
+            version.status as UserStatus,
+            expected_status as UserStatus,
+            at
+        )
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| {
+            error!(error = %e, user_id = id, "Failed to revert user in database");
+            UserError::DatabaseError(e.into())
+        })?;
+
+        if user.is_some() {
+            self.mirror(&mut *tx, &[id]).await?;
+            let event = AuditRecord {
+                user_id: Some(id),
+                account_id: None,
+                action: audit::recorder::USER_REVERTED,
+                details: json!({ "version": version.version }),
+            };
+            audit::record(&mut *tx, event, at).await.map_err(|e| {
+                error!(error = %e, user_id = id, "Failed to record user revert in audit log");
+                UserError::DatabaseError(e.into())
+            })?;
+            if let Some(domain_event) = domain_event {
+                change_log::record(&mut tx, domain_event).await.map_err(|e| {
+                    error!(error = %e, user_id = id, "Failed to record user revert in change log");
+                    UserError::DatabaseError(e.into())
+                })?;
+            }
+        }
+
+        tx.commit().await.map_err(|e| {
+            error!(error = %e, "Failed to commit user revert");
+            UserError::DatabaseError(e.into())
+        })?;
+
+        Ok(user.map(User::from))
+    }
+
+    /// Counts all users and those created since `since`
+    pub(super) async fn count(&self, since: DateTime<Utc>) -> Result<UserCounts, UserError> {
+        info!(%since, "Counting users in database");
+
+        let counts = sqlx::query!(
+            r#"SELECT COUNT(*) AS "total!", COUNT(*) FILTER (WHERE created_at >= $1) AS "created_since!" FROM users"#,
+            since
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| {
+            error!(error = %e, "Failed to count users in database");
+            UserError::DatabaseError(e.into())
+        })?;
+
+        Ok(UserCounts { total: counts.total, created_since: counts.created_since })
+    }
+
+    /// Estimates how many users a paginated listing covers
+    ///
+    /// Unfiltered listings read the planner's row estimate from `pg_class`,
+    /// which costs nothing on large tables; tables never analyzed report no
+    /// estimate, so those are counted exactly. Tag filters count the tag's
+    /// links, which only touches the rows of that tag. Metadata filters are
+    /// counted exactly through the metadata index.
+    pub(super) async fn estimate_total(&self, tag: Option<&str>, metadata: Option<&Metadata>) -> Result<i64, UserError> {
+        info!(tag, ?metadata, "Estimating users total in database");
+
+        let estimate = match (tag, metadata) {
+            (_, Some(metadata)) => {
+                sqlx::query_scalar!(
+                    r#"SELECT COUNT(*) AS "total!" FROM users
+                     WHERE metadata @> $2
+                       AND ($1::text IS NULL OR id IN (
+                           SELECT ut.user_id FROM user_tags ut
+                           JOIN tags t ON t.id = ut.tag_id
+                           WHERE t.name = $1))"#,
+                    tag,
+                    Json(metadata) as Json<&Metadata>
+                )
+                .fetch_one(&self.pool)
+                .await
+            }
+            (Some(tag), None) => {
+                sqlx::query_scalar!(
+                    r#"SELECT COUNT(*) AS "total!" FROM user_tags ut
+                     JOIN tags t ON t.id = ut.tag_id
+                     WHERE t.name = $1"#,
+                    tag
+                )
+                .fetch_one(&self.pool)
+                .await
+            }
+            (None, None) => {
+                sqlx::query_scalar!(
+                    r#"SELECT CASE WHEN c.reltuples >= 0 THEN c.reltuples::BIGINT
+                            ELSE (SELECT COUNT(*) FROM users) END AS "estimate!"
+                     FROM pg_class c WHERE c.oid = 'users'::regclass"#
+                )
+                .fetch_one(&self.pool)
+                .await
+            }
+        }
+        .map_err(|e| {
+            error!(error = %e, tag, "Failed to estimate users total in database");
+            UserError::DatabaseError(e.into())
+        })?;
+
+        Ok(estimate)
     }
 
-    /// Updates an existing user in the database
-    pub(super) async fn update(&self, id: i32, user_data: &UpdateUser, existing_user: &User) -> Result<User, UserError> {
+    /// Updates an existing user in the database, stamping `updated_at` with `at` if anything changed
+    pub(super) async fn update(
+        &self,
+        id: i32,
+        user_data: &UpdateUser,
+        existing_user: &User,
+        at: DateTime<Utc>,
+    ) -> Result<User, UserError> {
         info!(user_id = id, ?user_data, "Updating user in database");
 
         // Use existing values if not provided in update, trim name if provided
@@ -133,21 +655,147 @@ impl UserRepository {
         let age = user_data.age.unwrap_or(existing_user.age);
 
         let updated_user = sqlx::query_as!(
-            User,
-            "UPDATE users SET name = $1, age = $2 WHERE id = $3 RETURNING id, name, age, created_at",
+            UserRow,
+            r#"UPDATE users SET name = $1, age = $2, metadata = COALESCE($4, metadata),
+                 updated_at = CASE WHEN (name, age, metadata) IS DISTINCT FROM ($1::VARCHAR, $2, COALESCE($4, metadata)) THEN $5 ELSE updated_at END
+             WHERE id = $3
+             RETURNING id, name, age, created_at, updated_at, status AS "status: UserStatus", metadata AS "metadata: Json<Metadata>""#,
             name,
             age,
-            id
+            id,
+            user_data.metadata.as_ref().map(Json) as Option<Json<&Metadata>>,
+            at
         )
         .fetch_one(&self.pool)
         .await
         .map_err(|e| {
             error!(error = %e, user_id = id, "Failed to update user in database");
-            UserError::DatabaseError(e.to_string())
+            UserError::DatabaseError(e.into())
         })?;
 
+        self.mirror(&self.pool, &[id]).await?;
         info!(user_id = id, "User updated successfully in database");
-        Ok(updated_user)
+        Ok(updated_user.into())
+    }
+
+    /// Updates many users in a single statement, returning the rows that matched
+    ///
+    /// Only rows the update actually changes get `updated_at` set to `at`.
+    pub(super) async fn update_many(&self, ids: &[i32], user_data: &UpdateUser, at: DateTime<Utc>) -> Result<Vec<User>, UserError> {
+        info!(count = ids.len(), ?user_data, "Bulk updating users in database");
+
+        let name = user_data.name.as_deref().map(str::trim);
+
+        let users = sqlx::query_as!(
+            UserRow,
+            r#"UPDATE users SET name = COALESCE($1, name), age = COALESCE($2, age), metadata = COALESCE($4, metadata),
+                 updated_at = CASE WHEN (name, age, metadata) IS DISTINCT FROM (COALESCE($1, name), COALESCE($2, age), COALESCE($4, metadata))
+                                   THEN $5 ELSE updated_at END
+             WHERE id = ANY($3)
+             RETURNING id, name, age, created_at, updated_at, status AS "status: UserStatus", metadata AS "metadata: Json<Metadata>""#,
+            name,
+            user_data.age,
+            ids,
+            user_data.metadata.as_ref().map(Json) as Option<Json<&Metadata>>,
+            at
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| {
+            error!(error = %e, "Failed to bulk update users in database");
+            UserError::DatabaseError(e.into())
+        })?;
+
+        let updated: Vec<i32> = users.iter().map(|user| user.id).collect();
+        self.mirror(&self.pool, &updated).await?;
+        info!(requested = ids.len(), updated = users.len(), "Bulk update completed in database");
+        Ok(users.into_iter().map(User::from).collect())
+    }
+
+    /// Deletes many users in a single statement, returning the IDs that were removed
+    pub(super) async fn delete_many(&self, ids: &[i32]) -> Result<Vec<i32>, UserError> {
+        info!(count = ids.len(), "Bulk deleting users from database");
+
+        let deleted = sqlx::query_scalar!("DELETE FROM users WHERE id = ANY($1) RETURNING id", ids)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| {
+                error!(error = %e, "Failed to bulk delete users from database");
+                UserError::DatabaseError(e.into())
+            })?;
+
+        info!(requested = ids.len(), deleted = deleted.len(), "Bulk delete completed in database");
+        Ok(deleted)
+    }
+
+    /// Moves a user to `target` status if its current status is one of `allowed_from`
+    ///
+    /// The check and the update happen in a single statement, so concurrent transitions
+    /// cannot both succeed. Returns `None` when no row matched (missing user or
+    /// disallowed current status). A successful transition is recorded in the
+    /// audit log and, as `domain_event`, in the change log in the same transaction.
+    pub(super) async fn transition_status(
+        &self,
+        id: i32,
+        target: UserStatus,
+        allowed_from: &[UserStatus],
+        at: DateTime<Utc>,
+        domain_event: &DomainEvent,
+    ) -> Result<Option<User>, UserError> {
+        info!(user_id = id, %target, "Transitioning user status in database");
+
+        let mut tx = self.pool.begin().await.map_err(|e| {
+            error!(error = %e, "Failed to begin transaction for status transition");
+            UserError::DatabaseError(e.into())
+        })?;
+
+        let user = sqlx::query_as!(
+            UserRow,
+            r#"UPDATE users SET status = $2, updated_at = CASE WHEN status IS DISTINCT FROM $2 THEN $4 ELSE updated_at END
+             WHERE id = $1 AND status = ANY($3)
+             RETURNING id, name, age, created_at, updated_at, status AS "status: UserStatus", metadata AS "metadata: Json<Metadata>""#,
+            id,
+            target as UserStatus,
+            allowed_from as &[UserStatus],
+            at
+        )
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| {
+            error!(error = %e, user_id = id, "Failed to transition user status in database");
+            UserError::DatabaseError(e.into())
+        })?;
+
+        if user.is_some() {
+            self.mirror(&mut *tx, &[id]).await?;
+            let event = AuditRecord {
+                user_id: Some(id),
+                account_id: None,
+                action: audit::recorder::USER_STATUS_CHANGED,
+                details: json!({ "to": target.to_string() }),
+            };
+            audit::record(&mut *tx, event, at).await.map_err(|e| {
+                error!(error = %e, user_id = id, "Failed to record status transition in audit log");
+                UserError::DatabaseError(e.into())
+            })?;
+            change_log::record(&mut tx, domain_event).await.map_err(|e| {
+                error!(error = %e, user_id = id, "Failed to record status transition in change log");
+                UserError::DatabaseError(e.into())
+            })?;
+        }
+
+        tx.commit().await.map_err(|e| {
+            error!(error = %e, "Failed to commit status transition");
+            UserError::DatabaseError(e.into())
+        })?;
+
+        if user.is_some() {
+            info!(user_id = id, %target, "User status transitioned successfully in database");
+        } else {
+            warn!(user_id = id, %target, "User status transition matched no rows");
+        }
+
+        Ok(user.map(User::from))
     }
 
     /// Deletes a user from the database
@@ -159,7 +807,7 @@ impl UserRepository {
             .await
             .map_err(|e| {
                 error!(error = %e, user_id = id, "Failed to delete user from database");
-                UserError::DatabaseError(e.to_string())
+                UserError::DatabaseError(e.into())
             })?;
 
         let deleted = result.rows_affected() > 0;
@@ -1,72 +1,191 @@
 //! User service - business logic layer
 //! 
-//! This is the main interface that other modules should use to interact with user functionality.
-//! It encapsulates business logic and coordinates between domain validation and repository operations.
+//! `UserService` is the database-backed implementation of the user ports
+//! (`UserReadPort`/`UserWritePort`). It encapsulates business logic and
+//! coordinates between domain validation and repository operations.
 //! 
 //! The service is now modularized with separate service modules for each operation type,
 //! improving maintainability and following Rust best practices.
 
-use sqlx::PgPool;
+use chrono::{DateTime, Utc};
+use futures_util::future::BoxFuture;
+use futures_util::stream::BoxStream;
+use sqlx::{PgConnection, PgPool};
 
-use super::domain::{User, CreateUser, UpdateUser, UserError, ApiResponse, PaginationParams, PaginatedUsersResponse};
+use super::domain::{User, CreateUser, UpdateUser, UserError, ApiResponse, PaginationParams, PaginatedUsersResponse, BulkUpdateUsers, BulkOperationResponse, UserCounts, UserHistory, UserStatus, UpsertUser, UpsertedUser, UserChangesPage, UserChangesParams};
+use super::ports::{UserReadPort, UserWritePort};
 use super::repository::UserRepository;
+use super::validation::{DenylistPolicy, SharedNameScreeningPolicy, ValidationContext, ValidationPolicy};
 use super::services::{
     CreateUserService, ReadUserService, UpdateUserService, 
-    DeleteUserService, UserUtilsService
+    DeleteUserService, UserUtilsService, BulkUserService, UserLifecycleService, UserHistoryService, UpsertUserService, UserChangeFeedService
 };
+use crate::clock::{SharedClock, SystemClock};
+use crate::db::ColumnRename;
+use crate::events::EventBus;
+use crate::ids::{DefaultIdGenerator, SharedIdGenerator};
+use crate::pagination::PageLimits;
 
 /// User service that handles business logic and coordinates operations
 #[derive(Clone)]
 pub struct UserService {
     repository: UserRepository,
+    events: EventBus,
+    clock: SharedClock,
+    ids: SharedIdGenerator,
+    page_limits: PageLimits,
+    validation: ValidationContext,
+    screening: SharedNameScreeningPolicy,
 }
 
 impl UserService {
     /// Creates a new `UserService` instance
     #[must_use] pub fn new(pool: PgPool) -> Self {
+        Self::with_event_bus(pool, EventBus::new())
+    }
+
+    /// Creates a new `UserService` that publishes domain events on the given bus
+    #[must_use] pub fn with_event_bus(pool: PgPool, events: EventBus) -> Self {
         Self {
             repository: UserRepository::new(pool),
+            events,
+            clock: SystemClock::shared(),
+            ids: DefaultIdGenerator::shared(),
+            page_limits: PageLimits::default(),
+            validation: ValidationContext::new(),
+            screening: DenylistPolicy::default().shared(),
         }
     }
 
-    /// Creates a new user with validation
-    pub async fn create_user(&self, user_data: CreateUser) -> Result<User, UserError> {
-        CreateUserService::create_user(&self.repository, user_data).await
+    /// Uses `clock` instead of the system clock for timestamps
+    #[must_use] pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Uses `ids` instead of the database sequence for new user IDs
+    #[must_use] pub fn with_id_generator(mut self, ids: SharedIdGenerator) -> Self {
+        self.ids = ids;
+        self
+    }
+
+    /// Uses `limits` for the page size of user listings instead of 200/200
+    #[must_use] pub fn with_page_limits(mut self, limits: PageLimits) -> Self {
+        self.page_limits = limits;
+        self
+    }
+
+    /// Validates names, ages and metadata against `policy` instead of the default limits
+    #[must_use] pub fn with_validation_policy(mut self, policy: ValidationPolicy) -> Self {
+        self.validation.policy = policy;
+        self
+    }
+
+    /// Validates with `context`: its policy's limits, and in a strict context rejects warnings as errors
+    #[must_use] pub fn with_validation(mut self, context: ValidationContext) -> Self {
+        self.validation = context;
+        self
+    }
+
+    /// Screens names on create and update with `policy` instead of allowing every name
+    #[must_use] pub fn with_name_screening(mut self, policy: SharedNameScreeningPolicy) -> Self {
+        self.screening = policy;
+        self
+    }
+
+    /// Mirrors every write of `rename`'s old `users` column into its new one
+    ///
+    /// Enable it between the expand and contract migrations of a column
+    /// rename, see [`crate::db::expand`].
+    #[must_use] pub fn with_dual_write(mut self, rename: ColumnRename) -> Self {
+        self.repository = self.repository.with_dual_write(rename);
+        self
+    }
+
+    /// Creates a new user with validation on `conn`
+    ///
+    /// Pass a request's `Tx` to make the insert part of its transaction.
+    pub async fn create_user_in(&self, conn: &mut PgConnection, user_data: CreateUser) -> Result<User, UserError> {
+        CreateUserService::create_user_in(&self.repository, conn, &*self.clock, &*self.ids, &self.validation, &*self.screening, user_data).await
+    }
+}
+
+impl UserReadPort for UserService {
+    fn get_all_users(&self) -> BoxFuture<'_, Result<Vec<User>, UserError>> {
+        Box::pin(ReadUserService::get_all_users(&self.repository))
+    }
+
+    fn stream_users(&self) -> BoxStream<'static, Result<User, UserError>> {
+        Box::pin(ReadUserService::stream_users(&self.repository))
     }
 
-    /// Retrieves all users
-    pub async fn get_all_users(&self) -> Result<Vec<User>, UserError> {
-        ReadUserService::get_all_users(&self.repository).await
+    fn get_users_paginated(&self, params: PaginationParams) -> BoxFuture<'_, Result<PaginatedUsersResponse, UserError>> {
+        Box::pin(ReadUserService::get_users_paginated(&self.repository, self.page_limits, params))
     }
 
-    /// Retrieves users with pagination
-    pub async fn get_users_paginated(&self, params: PaginationParams) -> Result<PaginatedUsersResponse, UserError> {
-        ReadUserService::get_users_paginated(&self.repository, params).await
+    fn get_user_by_id(&self, id: i32) -> BoxFuture<'_, Result<User, UserError>> {
+        Box::pin(ReadUserService::get_user_by_id(&self.repository, id))
     }
 
-    /// Retrieves a specific user by ID
-    pub async fn get_user_by_id(&self, id: i32) -> Result<User, UserError> {
-        ReadUserService::get_user_by_id(&self.repository, id).await
+    fn user_exists(&self, id: i32) -> BoxFuture<'_, Result<bool, UserError>> {
+        Box::pin(UserUtilsService::user_exists(&self.repository, id))
     }
 
-    /// Updates an existing user with validation
-    pub async fn update_user(&self, id: i32, user_data: UpdateUser) -> Result<User, UserError> {
-        UpdateUserService::update_user(&self.repository, id, user_data).await
+    fn get_user_name(&self, id: i32) -> BoxFuture<'_, Result<String, UserError>> {
+        Box::pin(UserUtilsService::get_user_name(&self.repository, id))
     }
 
-    /// Deletes a user
-    pub async fn delete_user(&self, id: i32) -> Result<ApiResponse, UserError> {
-        DeleteUserService::delete_user(&self.repository, id).await
+    fn count_users(&self, since: DateTime<Utc>) -> BoxFuture<'_, Result<UserCounts, UserError>> {
+        Box::pin(UserUtilsService::count_users(&self.repository, since))
     }
 
-    /// Checks if a user exists (utility method for other modules)
-    pub async fn user_exists(&self, id: i32) -> Result<bool, UserError> {
-        UserUtilsService::user_exists(&self.repository, id).await
+    fn get_user_history(&self, id: i32) -> BoxFuture<'_, Result<UserHistory, UserError>> {
+        Box::pin(UserHistoryService::get_user_history(&self.repository, id))
     }
 
-    /// Gets user name by ID (utility method for other modules)
-    pub async fn get_user_name(&self, id: i32) -> Result<String, UserError> {
-        UserUtilsService::get_user_name(&self.repository, id).await
+    fn poll_user_changes(&self, params: UserChangesParams) -> BoxFuture<'_, Result<UserChangesPage, UserError>> {
+        Box::pin(UserChangeFeedService::poll(&self.repository, self.page_limits, params))
+    }
+}
+
+impl UserWritePort for UserService {
+    fn create_user(&self, user_data: CreateUser) -> BoxFuture<'_, Result<User, UserError>> {
+        Box::pin(CreateUserService::create_user(&self.repository, &*self.clock, &*self.ids, &self.validation, &*self.screening, user_data))
+    }
+
+    fn update_user(&self, id: i32, user_data: UpdateUser) -> BoxFuture<'_, Result<User, UserError>> {
+        Box::pin(UpdateUserService::update_user(&self.repository, &*self.clock, &self.validation, &*self.screening, id, user_data))
+    }
+
+    fn upsert_user(&self, user_data: UpsertUser) -> BoxFuture<'_, Result<UpsertedUser, UserError>> {
+        Box::pin(UpsertUserService::upsert_user(&self.repository, &*self.clock, &*self.ids, &self.validation, &*self.screening, user_data))
+    }
+
+    fn delete_user(&self, id: i32) -> BoxFuture<'_, Result<ApiResponse, UserError>> {
+        Box::pin(DeleteUserService::delete_user(&self.repository, id))
+    }
+
+    fn delete_users(&self, ids: Vec<i32>) -> BoxFuture<'_, Result<BulkOperationResponse, UserError>> {
+        Box::pin(BulkUserService::delete_users(&self.repository, ids))
     }
 
-}
\ No newline at end of file
+    fn update_users(&self, request: BulkUpdateUsers) -> BoxFuture<'_, Result<BulkOperationResponse, UserError>> {
+        Box::pin(BulkUserService::update_users(&self.repository, &*self.clock, &self.validation, &*self.screening, request))
+    }
+
+    fn suspend_user(&self, id: i32) -> BoxFuture<'_, Result<User, UserError>> {
+        Box::pin(UserLifecycleService::transition(&self.repository, &self.events, &*self.clock, id, UserStatus::Suspended))
+    }
+
+    fn activate_user(&self, id: i32) -> BoxFuture<'_, Result<User, UserError>> {
+        Box::pin(UserLifecycleService::transition(&self.repository, &self.events, &*self.clock, id, UserStatus::Active))
+    }
+
+    fn archive_user(&self, id: i32) -> BoxFuture<'_, Result<User, UserError>> {
+        Box::pin(UserLifecycleService::transition(&self.repository, &self.events, &*self.clock, id, UserStatus::Archived))
+    }
+
+    fn revert_user(&self, id: i32, version: i32) -> BoxFuture<'_, Result<User, UserError>> {
+        Box::pin(UserHistoryService::revert(&self.repository, &self.events, &*self.clock, &self.validation, &*self.screening, id, version))
+    }
+}
@@ -0,0 +1,107 @@
+//! User bulk operations service
+//!
+//! Handles the business logic for deleting or updating many users at once.
+
+use std::collections::{HashMap, HashSet};
+
+use tracing::{info, warn};
+
+use crate::clock::Clock;
+use crate::user::domain::{
+    BulkItemResult, BulkItemStatus, BulkOperationResponse, BulkUpdateUsers, UserError,
+};
+use crate::user::validation::{validate_bulk_ids, validate_bulk_update_with_context, NameScreeningPolicy, ValidationContext};
+use crate::user::validation::common::count_failures;
+use crate::user::repository::UserRepository;
+
+/// Service for bulk user operations
+pub struct BulkUserService;
+
+impl BulkUserService {
+    /// Deletes many users in one statement, reporting the outcome for each ID
+    pub(in crate::user) async fn delete_users(
+        repository: &UserRepository,
+        ids: Vec<i32>,
+    ) -> Result<BulkOperationResponse, UserError> {
+        info!(count = ids.len(), "BulkUserService: Deleting users");
+
+        if let Err(validation_errors) = validate_bulk_ids(&ids, "ids") {
+            warn!(?validation_errors, "BulkUserService: Validation failed for bulk delete");
+            return Err(UserError::ValidationError(validation_errors));
+        }
+
+        let ids = dedup_preserving_order(ids);
+        let deleted: HashSet<i32> = repository.delete_many(&ids).await?.into_iter().collect();
+
+        let results = ids
+            .into_iter()
+            .map(|id| BulkItemResult {
+                id,
+                status: if deleted.contains(&id) {
+                    BulkItemStatus::Deleted
+                } else {
+                    BulkItemStatus::NotFound
+                },
+                user: None,
+            })
+            .collect();
+
+        Ok(BulkOperationResponse::from_results(results))
+    }
+
+    /// Applies the same partial update to many users in one statement
+    pub(in crate::user) async fn update_users(
+        repository: &UserRepository,
+        clock: &dyn Clock,
+        validation: &ValidationContext,
+        screening: &dyn NameScreeningPolicy,
+        request: BulkUpdateUsers,
+    ) -> Result<BulkOperationResponse, UserError> {
+        info!(count = request.ids.len(), changes = ?request.changes, "BulkUserService: Updating users");
+
+        let warnings = validate_bulk_update_with_context(&request, validation).map_err(|validation_errors| {
+            warn!(?validation_errors, "BulkUserService: Validation failed for bulk update");
+            count_failures("bulk_update_users", &validation_errors);
+            UserError::ValidationError(validation_errors)
+        })?;
+
+        if let Some(name) = &request.changes.name
+            && let Err(validation_errors) = screening.screen(name, "name").await {
+            warn!(?validation_errors, "BulkUserService: Name rejected by screening");
+            count_failures("bulk_update_users", &validation_errors);
+            return Err(UserError::ValidationError(validation_errors));
+        }
+
+        let ids = dedup_preserving_order(request.ids);
+        let mut updated: HashMap<i32, _> = repository
+            .update_many(&ids, &request.changes, clock.now())
+            .await?
+            .into_iter()
+            .map(|user| (user.id, user))
+            .collect();
+
+        let results = ids
+            .into_iter()
+            .map(|id| match updated.remove(&id) {
+                Some(user) => BulkItemResult {
+                    id,
+                    status: BulkItemStatus::Updated,
+                    user: Some(user.with_warnings(warnings.clone())),
+                },
+                None => BulkItemResult {
+                    id,
+                    status: BulkItemStatus::NotFound,
+                    user: None,
+                },
+            })
+            .collect();
+
+        Ok(BulkOperationResponse::from_results(results))
+    }
+}
+
+/// Removes duplicate IDs while keeping the first occurrence of each
+fn dedup_preserving_order(ids: Vec<i32>) -> Vec<i32> {
+    let mut seen = HashSet::with_capacity(ids.len());
+    ids.into_iter().filter(|id| seen.insert(*id)).collect()
+}
@@ -0,0 +1,110 @@
+//! User change feed service
+//!
+//! Long-polls the `user_changes` feed written by triggers on `users`: a poll
+//! returns the settled changes after its cursor right away, or waits up to
+//! the requested time for the first one, checking the feed every
+//! [`POLL_INTERVAL`] without holding a connection in between.
+
+use std::time::Duration;
+
+use tokio::time::{Instant, sleep};
+use tracing::{info, warn};
+
+use crate::db::DbError;
+use crate::error_codes::ErrorCode;
+use crate::pagination::{PageLimits, PaginationToken};
+use crate::user::domain::{ChangeCursor, UserChange, UserChangesPage, UserChangesParams, UserError};
+use crate::user::repository::UserRepository;
+use crate::user::validation::common::field_error;
+
+/// How often a waiting poll checks the feed
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+/// Longest a poll may wait; longer requests are clamped
+const MAX_WAIT: Duration = Duration::from_mins(1);
+
+/// Service for the users change feed
+pub struct UserChangeFeedService;
+
+impl UserChangeFeedService {
+    /// Returns the changes after `params.since`, waiting up to `params.wait` for one
+    ///
+    /// Without `since` the poll returns at once with a cursor at the end of
+    /// the feed, so clients start with the changes made from then on.
+    #[tracing::instrument(skip(repository), fields(since = params.since.as_deref(), wait = params.wait.as_deref(), limit = params.limit))]
+    pub(in crate::user) async fn poll(
+        repository: &UserRepository,
+        limits: PageLimits,
+        params: UserChangesParams,
+    ) -> Result<UserChangesPage, UserError> {
+        let limit = limits.resolve(params.limit);
+        let wait = params.wait.as_deref().map(parse_wait).transpose()?.unwrap_or_default();
+
+        let Some(since) = params.since else {
+            let (txid, seq) = repository.latest_change_position().await?.unwrap_or_default();
+            info!(txid, seq, "UserChangeFeedService: Starting at the end of the feed");
+            return page(Vec::new(), ChangeCursor { txid, seq }, false);
+        };
+        let cursor: ChangeCursor = PaginationToken::decode_cursor(&since).map_err(|_e| UserError::InvalidToken)?;
+
+        let deadline = Instant::now() + wait;
+        loop {
+            // Fetch one extra change to check if there are more
+            let mut changes = repository.find_changes_after((cursor.txid, cursor.seq), limit + 1).await?;
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if changes.is_empty() && !remaining.is_zero() {
+                sleep(POLL_INTERVAL.min(remaining)).await;
+                continue;
+            }
+
+            let has_more = changes.len() > usize::try_from(limit).unwrap_or_default();
+            changes.truncate(usize::try_from(limit).unwrap_or_default());
+            let next = changes.last().map_or(cursor, |&((txid, seq), _)| ChangeCursor { txid, seq });
+            info!(count = changes.len(), has_more, "UserChangeFeedService: Changes fetched");
+            return page(changes.into_iter().map(|(_, change)| change).collect(), next, has_more);
+        }
+    }
+}
+
+/// Builds a page of `changes` continuing after `next`
+fn page(changes: Vec<UserChange>, next: ChangeCursor, has_more: bool) -> Result<UserChangesPage, UserError> {
+    let next_cursor = PaginationToken::encode_cursor(&next).map_err(|e| UserError::DatabaseError(DbError::other(e.to_string())))?;
+    Ok(UserChangesPage { changes, next_cursor, has_more })
+}
+
+/// Parses a wait such as `30s`, `500ms` or `30` (seconds), clamped to [`MAX_WAIT`]
+fn parse_wait(wait: &str) -> Result<Duration, UserError> {
+    let wait = wait.trim();
+    let parsed = if let Some(millis) = wait.strip_suffix("ms") {
+        millis.parse().map(Duration::from_millis)
+    } else {
+        wait.strip_suffix('s').unwrap_or(wait).parse().map(Duration::from_secs)
+    };
+
+    parsed.map(|duration| duration.min(MAX_WAIT)).map_err(|_e| {
+        warn!(wait, "UserChangeFeedService: Invalid wait");
+        UserError::ValidationError(vec![field_error(
+            "wait",
+            ErrorCode::InvalidWait,
+            "wait must be a duration such as 30s or 500ms",
+        )])
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wait_accepts_seconds_and_milliseconds() {
+        assert_eq!(parse_wait("30s").ok(), Some(Duration::from_secs(30)));
+        assert_eq!(parse_wait("500ms").ok(), Some(Duration::from_millis(500)));
+        assert_eq!(parse_wait("5").ok(), Some(Duration::from_secs(5)));
+        assert_eq!(parse_wait("10m").ok(), None);
+        assert_eq!(parse_wait("-1s").ok(), None);
+    }
+
+    #[test]
+    fn test_wait_is_clamped() {
+        assert_eq!(parse_wait("3600s").ok(), Some(MAX_WAIT));
+    }
+}
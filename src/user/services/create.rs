@@ -2,10 +2,15 @@
 //! 
 //! Handles the business logic for creating new users with proper validation.
 
+use sqlx::PgConnection;
 use tracing::{info, warn};
 
-use crate::user::domain::{User, CreateUser, UserError};
-use crate::user::validation::validate_create_user;
+use crate::clock::Clock;
+use crate::ids::IdGenerator;
+use crate::metrics::USERS_CREATED;
+use crate::user::domain::{User, CreateUser, UserError, ValidationWarning};
+use crate::user::validation::{validate_create_user_with_context, NameScreeningPolicy, ValidationContext};
+use crate::user::validation::common::count_failures;
 use crate::user::repository::UserRepository;
 
 /// Service for creating users
@@ -15,17 +20,59 @@ impl CreateUserService {
     /// Creates a new user with validation
     pub(in crate::user) async fn create_user(
         repository: &UserRepository,
+        clock: &dyn Clock,
+        ids: &dyn IdGenerator,
+        validation: &ValidationContext,
+        screening: &dyn NameScreeningPolicy,
         user_data: CreateUser,
     ) -> Result<User, UserError> {
         info!(?user_data, "CreateUserService: Creating new user");
 
-        // Validate input
-        if let Err(validation_errors) = validate_create_user(&user_data) {
-            warn!(?validation_errors, "CreateUserService: Validation failed for create user");
-            return Err(UserError::ValidationError(validation_errors));
-        }
+        let warnings = Self::validate(validation, screening, &user_data).await?;
 
         // Delegate to repository
-        repository.create(&user_data).await
+        let user = repository.create(&user_data, ids.next_id(), clock.now()).await?;
+        USERS_CREATED.increment(&[]);
+        Ok(user.with_warnings(warnings))
+    }
+
+    /// Creates a new user with validation on `conn`
+    pub(in crate::user) async fn create_user_in(
+        repository: &UserRepository,
+        conn: &mut PgConnection,
+        clock: &dyn Clock,
+        ids: &dyn IdGenerator,
+        validation: &ValidationContext,
+        screening: &dyn NameScreeningPolicy,
+        user_data: CreateUser,
+    ) -> Result<User, UserError> {
+        info!(?user_data, "CreateUserService: Creating new user in transaction");
+
+        let warnings = Self::validate(validation, screening, &user_data).await?;
+
+        let user = repository.create_in(conn, &user_data, ids.next_id(), clock.now()).await?;
+        USERS_CREATED.increment(&[]);
+        Ok(user.with_warnings(warnings))
+    }
+
+    /// Validates and screens `user_data`, returning its warnings
+    async fn validate(
+        validation: &ValidationContext,
+        screening: &dyn NameScreeningPolicy,
+        user_data: &CreateUser,
+    ) -> Result<Vec<ValidationWarning>, UserError> {
+        let warnings = validate_create_user_with_context(user_data, validation).map_err(|validation_errors| {
+            warn!(?validation_errors, "CreateUserService: Validation failed for create user");
+            count_failures("create_user", &validation_errors);
+            UserError::ValidationError(validation_errors)
+        })?;
+
+        screening.screen(&user_data.name, "name").await.map_err(|validation_errors| {
+            warn!(?validation_errors, "CreateUserService: Name rejected by screening");
+            count_failures("create_user", &validation_errors);
+            UserError::ValidationError(validation_errors)
+        })?;
+
+        Ok(warnings)
     }
 }
\ No newline at end of file
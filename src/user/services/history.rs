@@ -0,0 +1,171 @@
+//! User history service
+//!
+//! Assembles the previous versions of a user recorded by the `users_history`
+//! triggers, computes what each change altered, and restores old versions.
+
+use serde_json::json;
+use tracing::{info, warn};
+
+use crate::clock::Clock;
+use crate::events::{DomainEvent, EventBus};
+use crate::user::domain::{FieldChange, HistoryOperation, UpdateUser, User, UserError, UserHistory, UserStatus, UserVersion};
+use crate::user::repository::UserRepository;
+use crate::user::validation::{validate_update_user_with_context, NameScreeningPolicy, ValidationContext};
+
+/// Service for user version history
+pub struct UserHistoryService;
+
+impl UserHistoryService {
+    /// Retrieves the previous versions of a user with the changes that replaced them
+    ///
+    /// Deleted users keep their history, so only users that never existed are
+    /// reported as not found.
+    #[tracing::instrument(skip(repository), fields(user_id = id))]
+    pub(in crate::user) async fn get_user_history(repository: &UserRepository, id: i32) -> Result<UserHistory, UserError> {
+        info!(user_id = id, "UserHistoryService: Fetching user history");
+
+        let current = repository.find_by_id(id).await?;
+        let mut versions = repository.find_history(id).await?;
+        if current.is_none() && versions.is_empty() {
+            warn!(user_id = id, "UserHistoryService: User not found");
+            return Err(UserError::NotFound);
+        }
+
+        // Versions are newest first: each one was replaced by its predecessor
+        // in the list, the newest one by the current row
+        let mut next = current.as_ref().map(Snapshot::from);
+        for version in &mut versions {
+            if let (HistoryOperation::Update, Some(next)) = (version.operation, &next) {
+                version.changes = Snapshot::from(&*version).diff(next);
+            }
+            next = Some(Snapshot::from(&*version));
+        }
+
+        info!(user_id = id, versions = versions.len(), "UserHistoryService: User history fetched");
+        Ok(UserHistory { user_id: id, current, versions })
+    }
+
+    /// Restores a previous version of a user as a new update
+    ///
+    /// The restored name and age must pass today's validation rules and name
+    /// screening, and a restored status must be reachable through the
+    /// lifecycle state machine.
+    #[tracing::instrument(skip(repository, events, clock, validation, screening), fields(user_id = id, version))]
+    pub(in crate::user) async fn revert(
+        repository: &UserRepository,
+        events: &EventBus,
+        clock: &dyn Clock,
+        validation: &ValidationContext,
+        screening: &dyn NameScreeningPolicy,
+        id: i32,
+        version: i32,
+    ) -> Result<User, UserError> {
+        info!(user_id = id, version, "UserHistoryService: Reverting user");
+
+        let Some(existing_user) = repository.find_by_id(id).await? else {
+            warn!(user_id = id, "UserHistoryService: User not found for revert");
+            return Err(UserError::NotFound);
+        };
+        let Some(target) = repository.find_version(id, version).await? else {
+            warn!(user_id = id, version, "UserHistoryService: Version not found for revert");
+            return Err(UserError::VersionNotFound(version));
+        };
+
+        let restored = UpdateUser { name: Some(target.name.clone()), age: Some(target.age), metadata: None };
+        let warnings = validate_update_user_with_context(&restored, validation).map_err(|validation_errors| {
+            warn!(?validation_errors, "UserHistoryService: Restored version fails validation");
+            UserError::ValidationError(validation_errors)
+        })?;
+        if let Err(validation_errors) = screening.screen(&target.name, "name").await {
+            warn!(?validation_errors, "UserHistoryService: Restored name rejected by screening");
+            return Err(UserError::ValidationError(validation_errors));
+        }
+
+        let from = existing_user.status;
+        if from != target.status && !from.can_transition_to(target.status) {
+            warn!(user_id = id, %from, to = %target.status, "UserHistoryService: Restored status not reachable");
+            return Err(UserError::InvalidStatusTransition { from, to: target.status });
+        }
+
+        let now = clock.now();
+        let event = (from != target.status).then_some(DomainEvent::UserStatusChanged {
+            user_id: id,
+            from,
+            to: target.status,
+            occurred_at: now,
+        });
+
+        // The repository re-checks the status atomically, so a concurrent
+        // transition between the read above and this update is reported as a conflict
+        let Some(user) = repository.revert(id, &target, from, now, event.as_ref()).await? else {
+            warn!(user_id = id, "UserHistoryService: Status changed concurrently");
+            return Err(UserError::InvalidStatusTransition { from, to: target.status });
+        };
+
+        if let Some(event) = event {
+            events.publish(event);
+        }
+
+        info!(user_id = id, version, "UserHistoryService: User reverted successfully");
+        Ok(user.with_warnings(warnings))
+    }
+}
+
+/// The tracked fields of a user version
+struct Snapshot<'a> {
+    /// User's name
+    name: &'a str,
+    /// User's age
+    age: i32,
+    /// Lifecycle status
+    status: UserStatus,
+}
+
+impl<'a> From<&'a User> for Snapshot<'a> {
+    fn from(user: &'a User) -> Self {
+        Self { name: &user.name, age: user.age, status: user.status }
+    }
+}
+
+impl<'a> From<&'a UserVersion> for Snapshot<'a> {
+    fn from(version: &'a UserVersion) -> Self {
+        Self { name: &version.name, age: version.age, status: version.status }
+    }
+}
+
+impl Snapshot<'_> {
+    /// Lists the fields that differ from `next`, in declaration order
+    fn diff(&self, next: &Snapshot<'_>) -> Vec<FieldChange> {
+        let fields = [
+            ("name", json!(self.name), json!(next.name)),
+            ("age", json!(self.age), json!(next.age)),
+            ("status", json!(self.status), json!(next.status)),
+        ];
+        fields
+            .into_iter()
+            .filter(|(_, from, to)| from != to)
+            .map(|(field, from, to)| FieldChange { field: field.to_owned(), from, to })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff_lists_only_changed_fields() {
+        let before = Snapshot { name: "Ada", age: 36, status: UserStatus::Active };
+        let after = Snapshot { name: "Ada", age: 37, status: UserStatus::Suspended };
+
+        let changes = before.diff(&after);
+
+        assert_eq!(
+            changes,
+            vec![
+                FieldChange { field: "age".to_owned(), from: json!(36), to: json!(37) },
+                FieldChange { field: "status".to_owned(), from: json!("active"), to: json!("suspended") },
+            ]
+        );
+    }
+}
@@ -0,0 +1,62 @@
+//! User lifecycle service
+//!
+//! Enforces the user status state machine (active → suspended → archived) and
+//! records and publishes a domain event for every successful transition.
+
+use tracing::{info, warn};
+
+use crate::clock::Clock;
+use crate::events::{DomainEvent, EventBus};
+use crate::user::domain::{User, UserError, UserStatus};
+use crate::user::repository::UserRepository;
+
+/// Service for user lifecycle transitions
+pub struct UserLifecycleService;
+
+impl UserLifecycleService {
+    /// Moves a user to the `target` status if the state machine allows it
+    pub(in crate::user) async fn transition(
+        repository: &UserRepository,
+        events: &EventBus,
+        clock: &dyn Clock,
+        id: i32,
+        target: UserStatus,
+    ) -> Result<User, UserError> {
+        info!(user_id = id, %target, "UserLifecycleService: Transitioning user");
+
+        let Some(existing_user) = repository.find_by_id(id).await? else {
+            warn!(user_id = id, "UserLifecycleService: User not found for transition");
+            return Err(UserError::NotFound);
+        };
+
+        let from = existing_user.status;
+        if !from.can_transition_to(target) {
+            warn!(user_id = id, %from, %target, "UserLifecycleService: Invalid status transition");
+            return Err(UserError::InvalidStatusTransition { from, to: target });
+        }
+
+        let now = clock.now();
+        let event = DomainEvent::UserStatusChanged {
+            user_id: id,
+            from,
+            to: target,
+            occurred_at: now,
+        };
+
+        // The repository re-checks the status read above atomically, so a concurrent
+        // transition between the read and this update is reported as a conflict and
+        // the event's `from` is always the status actually replaced
+        let Some(user) = repository
+            .transition_status(id, target, &[from], now, &event)
+            .await?
+        else {
+            warn!(user_id = id, %from, %target, "UserLifecycleService: Status changed concurrently");
+            return Err(UserError::InvalidStatusTransition { from, to: target });
+        };
+
+        events.publish(event);
+
+        info!(user_id = id, %from, %target, "UserLifecycleService: User transitioned successfully");
+        Ok(user)
+    }
+}
@@ -8,9 +8,19 @@ pub mod read;
 pub mod update;
 pub mod delete;
 pub mod utils;
+pub mod bulk;
+pub mod lifecycle;
+pub mod history;
+pub mod upsert;
+pub mod changes;
 
 pub(super) use create::CreateUserService;
 pub(super) use read::ReadUserService;
 pub(super) use update::UpdateUserService;
 pub(super) use delete::DeleteUserService;
-pub(super) use utils::UserUtilsService;
\ No newline at end of file
+pub(super) use utils::UserUtilsService;
+pub(super) use bulk::BulkUserService;
+pub(super) use lifecycle::UserLifecycleService;
+pub(super) use history::UserHistoryService;
+pub(super) use upsert::UpsertUserService;
+pub(super) use changes::UserChangeFeedService;
\ No newline at end of file
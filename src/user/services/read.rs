@@ -2,11 +2,15 @@
 //! 
 //! Handles the business logic for reading/retrieving user data.
 
+use futures_util::Stream;
 use tracing::{info, warn};
 
+use crate::error_codes::ErrorCode;
 use crate::user::domain::{User, UserError, PaginationParams, PaginatedUsersResponse};
 use crate::user::repository::UserRepository;
-use crate::pagination::PaginationToken;
+use crate::pagination::{PageLimits, PaginationToken};
+use crate::user::validation::common::field_error;
+use crate::user::validation::rules::validate_metadata;
 
 /// Service for reading users
 pub struct ReadUserService;
@@ -21,6 +25,14 @@ impl ReadUserService {
         Ok(users)
     }
 
+    /// Streams all users without loading them into memory
+    pub(in crate::user) fn stream_users(
+        repository: &UserRepository,
+    ) -> impl Stream<Item = Result<User, UserError>> + Send + 'static {
+        info!("ReadUserService: Streaming all users");
+        repository.stream_all()
+    }
+
     /// Retrieves a specific user by ID
     #[tracing::instrument(skip(repository), fields(user_id = id))]
     pub(in crate::user) async fn get_user_by_id(
@@ -39,43 +51,79 @@ impl ReadUserService {
     }
 
     /// Retrieves users with pagination using cursor tokens
-    #[tracing::instrument(skip(repository), fields(next_token = params.next_token.as_deref(), limit = params.limit))]
+    ///
+    /// `next_token` pages forward in the requested order and `prev_token`
+    /// pages backward; each page returns the tokens to continue either way.
+    #[tracing::instrument(skip(repository), fields(next_token = params.next_token.as_deref(), prev_token = params.prev_token.as_deref(), limit = params.limit, tag = params.tag.as_deref()))]
     pub(in crate::user) async fn get_users_paginated(
         repository: &UserRepository,
+        limits: PageLimits,
         params: PaginationParams,
     ) -> Result<PaginatedUsersResponse, UserError> {
-        // Default limit is 200, max is 200
-        let limit = params.limit.unwrap_or(200).min(200).max(1);
+        let limit = limits.resolve(params.limit);
+        let order = params.order.unwrap_or_default();
         
-        info!(next_token = params.next_token.as_deref(), limit = limit, "ReadUserService: Fetching paginated users");
+        info!(next_token = params.next_token.as_deref(), limit = limit, ?order, "ReadUserService: Fetching paginated users");
+
+        if params.next_token.is_some() && params.prev_token.is_some() {
+            warn!("ReadUserService: Both next_token and prev_token provided");
+            return Err(UserError::ValidationError(vec![field_error(
+                "prev_token",
+                ErrorCode::ConflictingPaginationTokens,
+                "prev_token cannot be combined with next_token",
+            )]));
+        }
+
+        // A prev token scans backward from the first user of the current page
+        let backward = params.prev_token.is_some();
+        let scan = if backward { order.reversed() } else { order };
 
         // Decode the pagination token if provided
-        let cursor = match params.next_token {
+        let cursor = match params.next_token.or(params.prev_token) {
             Some(token) => {
                 let (last_id, last_timestamp) = PaginationToken::decode(&token)
-                    .map_err(|_| UserError::InvalidToken)?;
+                    .map_err(|_e| UserError::InvalidToken)?;
                 Some((last_id, last_timestamp))
             }
             None => None,
         };
 
+        // Tags are stored normalized (trimmed, lowercase)
+        let tag = params.tag.as_deref().map(|t| t.trim().to_lowercase());
+
+        let metadata = (!params.metadata.is_empty()).then_some(&params.metadata);
+        if let Some(metadata) = metadata
+            && let Err(errors) = validate_metadata(metadata, "metadata") {
+            warn!("ReadUserService: Invalid metadata filter");
+            return Err(UserError::ValidationError(errors));
+        }
+
         // Fetch one extra record to check if there are more pages
-        let users = repository.find_paginated(cursor, limit + 1).await?;
+        let mut result_users = repository.find_paginated(cursor, limit + 1, tag.as_deref(), metadata, scan).await?;
         
-        let has_more = users.len() > limit as usize;
-        let mut result_users = users;
+        let has_extra = result_users.len() > usize::try_from(limit).unwrap_or_default();
         
         // Remove the extra record if we have more than the limit
-        if has_more {
+        if has_extra {
             result_users.pop();
         }
+        if backward {
+            result_users.reverse();
+        }
         
-        // Generate next token if there are more pages
-        let next_token = if has_more {
-            result_users.last().map(|user| {
-                PaginationToken::encode(user.id, user.created_at)
-                    .unwrap_or_else(|_| String::new())
-            }).filter(|token| !token.is_empty())
+        // Going forward, users remain ahead only past the extra record and
+        // behind only past the cursor; going backward it is the other way round
+        let (more_ahead, more_behind) = if backward {
+            (true, has_extra)
+        } else {
+            (has_extra, cursor.is_some())
+        };
+        let next_token = more_ahead.then(|| result_users.last().and_then(encode_token)).flatten();
+        let prev_token = more_behind.then(|| result_users.first().and_then(encode_token)).flatten();
+        let has_more = next_token.is_some();
+
+        let estimated_total = if params.include_estimated_total.unwrap_or(false) {
+            Some(repository.estimate_total(tag.as_deref(), metadata).await?)
         } else {
             None
         };
@@ -86,14 +134,23 @@ impl ReadUserService {
             count = count,
             has_more = has_more,
             next_token = next_token.as_deref(),
+            prev_token = prev_token.as_deref(),
             "ReadUserService: Successfully fetched paginated users"
         );
 
         Ok(PaginatedUsersResponse {
             users: result_users,
             next_token,
+            prev_token,
             has_more,
             count,
+            estimated_total,
+            links: None,
         })
     }
-}
\ No newline at end of file
+}
+
+/// Encodes the pagination cursor pointing at `user`
+fn encode_token(user: &User) -> Option<String> {
+    PaginationToken::encode(user.id, user.created_at).ok()
+}
@@ -4,8 +4,10 @@
 
 use tracing::{info, warn};
 
+use crate::clock::Clock;
 use crate::user::domain::{User, UpdateUser, UserError};
-use crate::user::validation::validate_update_user;
+use crate::user::validation::{validate_update_user_with_context, NameScreeningPolicy, ValidationContext};
+use crate::user::validation::common::count_failures;
 use crate::user::repository::UserRepository;
 
 /// Service for updating users
@@ -15,14 +17,25 @@ impl UpdateUserService {
     /// Updates an existing user with validation
     pub(in crate::user) async fn update_user(
         repository: &UserRepository,
+        clock: &dyn Clock,
+        validation: &ValidationContext,
+        screening: &dyn NameScreeningPolicy,
         id: i32,
         user_data: UpdateUser,
     ) -> Result<User, UserError> {
         info!(user_id = id, ?user_data, "UpdateUserService: Updating user");
 
         // Validate input
-        if let Err(validation_errors) = validate_update_user(&user_data) {
+        let warnings = validate_update_user_with_context(&user_data, validation).map_err(|validation_errors| {
             warn!(?validation_errors, "UpdateUserService: Validation failed for update user");
+            count_failures("update_user", &validation_errors);
+            UserError::ValidationError(validation_errors)
+        })?;
+
+        if let Some(name) = &user_data.name
+            && let Err(validation_errors) = screening.screen(name, "name").await {
+            warn!(?validation_errors, "UpdateUserService: Name rejected by screening");
+            count_failures("update_user", &validation_errors);
             return Err(UserError::ValidationError(validation_errors));
         }
 
@@ -33,6 +46,7 @@ impl UpdateUserService {
         };
 
         // Delegate to repository
-        repository.update(id, &user_data, &existing_user).await
+        let user = repository.update(id, &user_data, &existing_user, clock.now()).await?;
+        Ok(user.with_warnings(warnings))
     }
 }
\ No newline at end of file
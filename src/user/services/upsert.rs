@@ -0,0 +1,49 @@
+//! User upsert service
+//!
+//! Handles the business logic for idempotently syncing users by their
+//! external identifier.
+
+use tracing::{info, warn};
+
+use crate::clock::Clock;
+use crate::ids::IdGenerator;
+use crate::metrics::USERS_CREATED;
+use crate::user::domain::{UpsertUser, UpsertedUser, UserError};
+use crate::user::repository::UserRepository;
+use crate::user::validation::{validate_upsert_user_with_context, NameScreeningPolicy, ValidationContext};
+use crate::user::validation::common::count_failures;
+
+/// Service for upserting users
+pub struct UpsertUserService;
+
+impl UpsertUserService {
+    /// Creates the user with `external_id`, or updates it if it already exists
+    pub(in crate::user) async fn upsert_user(
+        repository: &UserRepository,
+        clock: &dyn Clock,
+        ids: &dyn IdGenerator,
+        validation: &ValidationContext,
+        screening: &dyn NameScreeningPolicy,
+        user_data: UpsertUser,
+    ) -> Result<UpsertedUser, UserError> {
+        info!(?user_data, "UpsertUserService: Upserting user");
+
+        let warnings = validate_upsert_user_with_context(&user_data, validation).map_err(|validation_errors| {
+            warn!(?validation_errors, "UpsertUserService: Validation failed for upsert user");
+            count_failures("upsert_user", &validation_errors);
+            UserError::ValidationError(validation_errors)
+        })?;
+
+        if let Err(validation_errors) = screening.screen(&user_data.name, "name").await {
+            warn!(?validation_errors, "UpsertUserService: Name rejected by screening");
+            count_failures("upsert_user", &validation_errors);
+            return Err(UserError::ValidationError(validation_errors));
+        }
+
+        let upserted = repository.upsert(&user_data, ids.next_id(), clock.now()).await?;
+        if upserted.created {
+            USERS_CREATED.increment(&[]);
+        }
+        Ok(UpsertedUser { user: upserted.user.with_warnings(warnings), ..upserted })
+    }
+}
@@ -2,9 +2,10 @@
 //! 
 //! Contains utility methods that other modules might need for user operations.
 
+use chrono::{DateTime, Utc};
 use tracing::info;
 
-use crate::user::domain::{UserError};
+use crate::user::domain::{UserCounts, UserError};
 use crate::user::repository::UserRepository;
 
 /// Service for user utility operations
@@ -30,4 +31,11 @@ impl UserUtilsService {
             None => Err(UserError::NotFound),
         }
     }
-}
\ No newline at end of file
+
+    /// Counts all users and those created since `since` (for dashboards)
+    pub(in crate::user) async fn count_users(repository: &UserRepository, since: DateTime<Utc>) -> Result<UserCounts, UserError> {
+        info!(%since, "UserUtilsService: Counting users");
+
+        repository.count(since).await
+    }
+}
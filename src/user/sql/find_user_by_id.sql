@@ -1,2 +1,2 @@
 -- Find user by ID
-SELECT id, name, age, created_at FROM users WHERE id = $1
\ No newline at end of file
+SELECT id, name, age, created_at, updated_at, status, metadata FROM users WHERE id = $1
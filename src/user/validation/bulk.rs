@@ -0,0 +1,116 @@
+//! Bulk operation validation logic
+//!
+//! Contains validation rules for operations that target many users at once.
+
+use crate::error_codes::ErrorCode;
+use crate::user::domain::{BulkUpdateUsers, ValidationError, ValidationWarning};
+use super::common::{field_error, ValidationResult, ValidationContext};
+use super::update::validate_update_user_with_context;
+
+/// Maximum number of IDs accepted by a single bulk operation
+pub const MAX_BULK_IDS: usize = 1000;
+
+/// Parses a comma-separated list of user IDs (e.g. `1,2,3`)
+pub fn parse_id_list(raw: &str, field_name: &str) -> Result<Vec<i32>, Vec<ValidationError>> {
+    let mut ids = Vec::new();
+    let mut errors = Vec::new();
+
+    for part in raw.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+        match part.parse::<i32>() {
+            Ok(id) => ids.push(id),
+            Err(_e) => errors.push(field_error(field_name, ErrorCode::InvalidId, format!("Invalid user ID: {part}"))),
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(ids)
+    } else {
+        Err(errors)
+    }
+}
+
+/// Validates the list of IDs targeted by a bulk operation
+pub fn validate_bulk_ids(ids: &[i32], field_name: &str) -> ValidationResult {
+    let mut errors = Vec::new();
+
+    if ids.is_empty() {
+        errors.push(field_error(field_name, ErrorCode::IdsRequired, "At least one ID must be provided"));
+    }
+
+    if ids.len() > MAX_BULK_IDS {
+        errors.push(field_error(
+            field_name,
+            ErrorCode::TooManyIds,
+            format!("Cannot process more than {MAX_BULK_IDS} IDs at once"),
+        ));
+    }
+
+    if ids.iter().any(|id| *id <= 0) {
+        errors.push(field_error(field_name, ErrorCode::InvalidId, "IDs must be positive integers"));
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+/// Validates a bulk update request (IDs and the shared changes), returning the warnings of valid changes
+pub fn validate_bulk_update(request: &BulkUpdateUsers) -> ValidationResult<Vec<ValidationWarning>> {
+    validate_bulk_update_with_context(request, &ValidationContext::new())
+}
+
+/// Validates a bulk update request with additional context
+pub fn validate_bulk_update_with_context(request: &BulkUpdateUsers, context: &ValidationContext) -> ValidationResult<Vec<ValidationWarning>> {
+    let ids = validate_bulk_ids(&request.ids, "ids");
+    let changes = validate_update_user_with_context(&request.changes, context);
+
+    match (ids, changes) {
+        (Ok(()), Ok(warnings)) => Ok(warnings),
+        (ids, changes) => Err(ids.err().into_iter().chain(changes.err()).flatten().collect()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::user::domain::UpdateUser;
+
+    #[test]
+    fn test_parse_id_list_valid() {
+        assert_eq!(parse_id_list("1,2, 3", "ids").unwrap(), vec![1, 2, 3]);
+        assert!(parse_id_list("", "ids").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_parse_id_list_invalid() {
+        let errors = parse_id_list("1,abc", "ids").unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].message.contains("abc"));
+    }
+
+    #[test]
+    fn test_validate_bulk_ids_empty() {
+        let errors = validate_bulk_ids(&[], "ids").unwrap_err();
+        assert!(errors[0].message.contains("At least one ID"));
+    }
+
+    #[test]
+    fn test_validate_bulk_ids_too_many() {
+        let ids: Vec<i32> = (1..=1001).collect();
+        let errors = validate_bulk_ids(&ids, "ids").unwrap_err();
+        assert!(errors.iter().any(|e| e.message.contains("more than 1000")));
+    }
+
+    #[test]
+    fn test_validate_bulk_update_collects_all_errors() {
+        let request = BulkUpdateUsers {
+            ids: vec![],
+            changes: UpdateUser { name: Some(String::new()), age: None, metadata: None },
+        };
+        let errors = validate_bulk_update(&request).unwrap_err();
+        assert!(errors.iter().any(|e| e.field.as_deref() == Some("ids")));
+        assert!(errors.iter().any(|e| e.field.as_deref() == Some("name")));
+    }
+}
@@ -2,18 +2,26 @@
 //! 
 //! Shared validation functionality used across different validation modules.
 
-use crate::user::domain::ValidationError;
+use crate::error_codes::ErrorCode;
+use crate::metrics::VALIDATION_FAILURES;
+use crate::user::domain::{ValidationError, ValidationWarning};
+use super::policy::ValidationPolicy;
 
 /// Type alias for validation results
-pub type ValidationResult = Result<(), Vec<ValidationError>>;
+///
+/// Rules return `ValidationResult`; whole-payload validators return
+/// `ValidationResult<Vec<ValidationWarning>>`, the warnings of data that passed.
+pub type ValidationResult<T = ()> = Result<T, Vec<ValidationError>>;
 
 /// Validation context for passing additional information to validators
-#[derive(Debug, Default)]
+#[derive(Debug, Clone, Default)]
 pub struct ValidationContext {
-    /// Whether this is a strict validation (e.g., for API endpoints)
+    /// Whether this is a strict validation, which fails on warnings too
     pub strict: bool,
     /// Additional context data that validators might need
     pub metadata: std::collections::HashMap<String, String>,
+    /// Limits the rules apply
+    pub policy: ValidationPolicy,
 }
 
 impl ValidationContext {
@@ -30,6 +38,13 @@ impl ValidationContext {
         }
     }
     
+    /// Applies `policy` instead of the default limits
+    #[must_use]
+    pub fn with_policy(mut self, policy: ValidationPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+    
     /// Adds metadata to the context
     #[must_use]
     pub fn with_metadata(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
@@ -39,21 +54,61 @@ impl ValidationContext {
 }
 
 /// Helper function to create a validation error
-pub fn validation_error(message: impl Into<String>, field: Option<impl Into<String>>) -> ValidationError {
+pub fn validation_error(code: ErrorCode, message: impl Into<String>, field: Option<impl Into<String>>) -> ValidationError {
     ValidationError {
+        code,
         message: message.into(),
         field: field.map(std::convert::Into::into),
     }
 }
 
 /// Helper function to create a field-specific validation error
-pub fn field_error(field: impl Into<String>, message: impl Into<String>) -> ValidationError {
-    validation_error(message, Some(field))
+pub fn field_error(field: impl Into<String>, code: ErrorCode, message: impl Into<String>) -> ValidationError {
+    validation_error(code, message, Some(field))
+}
+
+/// Helper function to create a field-specific validation warning
+pub fn field_warning(field: impl Into<String>, code: ErrorCode, message: impl Into<String>) -> ValidationWarning {
+    ValidationWarning {
+        code,
+        message: message.into(),
+        field: Some(field.into()),
+    }
+}
+
+/// Concludes a validation from its errors and warnings
+///
+/// Any error fails it; otherwise the warnings are returned, or in a strict
+/// `context` escalated to errors.
+pub fn conclude(
+    mut errors: Vec<ValidationError>,
+    warnings: Vec<ValidationWarning>,
+    context: &ValidationContext,
+) -> ValidationResult<Vec<ValidationWarning>> {
+    let warnings = if context.strict {
+        errors.extend(warnings.into_iter().map(ValidationError::from));
+        Vec::new()
+    } else {
+        warnings
+    };
+
+    if errors.is_empty() {
+        Ok(warnings)
+    } else {
+        Err(errors)
+    }
+}
+
+/// Counts `errors` in the validation failure metric, by `operation` and field
+pub fn count_failures(operation: &'static str, errors: &[ValidationError]) {
+    for error in errors {
+        VALIDATION_FAILURES.increment(&[("operation", operation), ("field", error.field.as_deref().unwrap_or("none"))]);
+    }
 }
 
 /// Helper function to create a general validation error
-pub fn general_error(message: impl Into<String>) -> ValidationError {
-    validation_error(message, None::<String>)
+pub fn general_error(code: ErrorCode, message: impl Into<String>) -> ValidationError {
+    validation_error(code, message, None::<String>)
 }
 
 #[cfg(test)]
@@ -80,27 +135,38 @@ mod tests {
             .with_metadata("key1", "value1")
             .with_metadata("key2", "value2");
         
-        assert_eq!(context.metadata.get("key1"), Some(&"value1".to_string()));
-        assert_eq!(context.metadata.get("key2"), Some(&"value2".to_string()));
+        assert_eq!(context.metadata.get("key1"), Some(&"value1".to_owned()));
+        assert_eq!(context.metadata.get("key2"), Some(&"value2".to_owned()));
+    }
+
+    #[test]
+    fn test_conclude_escalates_warnings_in_strict_mode() {
+        let warnings = || vec![field_warning("age", ErrorCode::AgeUnusual, "Unusual")];
+
+        assert_eq!(conclude(Vec::new(), warnings(), &ValidationContext::new()).unwrap(), warnings());
+        let errors = conclude(Vec::new(), warnings(), &ValidationContext::strict()).unwrap_err();
+        assert_eq!(errors[0].field.as_deref(), Some("age"));
+        assert!(conclude(vec![field_error("name", ErrorCode::NameEmpty, "Bad")], warnings(), &ValidationContext::new()).is_err());
     }
 
     #[test]
     fn test_validation_error_creation() {
-        let error = validation_error("Test message", Some("test_field"));
+        let error = validation_error(ErrorCode::FieldEmpty, "Test message", Some("test_field"));
+        assert_eq!(error.code, ErrorCode::FieldEmpty);
         assert_eq!(error.message, "Test message");
-        assert_eq!(error.field, Some("test_field".to_string()));
+        assert_eq!(error.field, Some("test_field".to_owned()));
     }
 
     #[test]
     fn test_field_error_creation() {
-        let error = field_error("username", "Username is required");
+        let error = field_error("username", ErrorCode::FieldEmpty, "Username is required");
         assert_eq!(error.message, "Username is required");
-        assert_eq!(error.field, Some("username".to_string()));
+        assert_eq!(error.field, Some("username".to_owned()));
     }
 
     #[test]
     fn test_general_error_creation() {
-        let error = general_error("General validation failed");
+        let error = general_error(ErrorCode::NoFieldsToUpdate, "General validation failed");
         assert_eq!(error.message, "General validation failed");
         assert!(error.field.is_none());
     }
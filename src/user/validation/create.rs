@@ -2,40 +2,51 @@
 //! 
 //! Contains validation rules specific to user creation operations.
 
-use crate::user::domain::{CreateUser, ValidationError};
-use super::common::{ValidationResult, ValidationContext};
-use super::rules::{validate_name, validate_age};
+use crate::user::domain::{CreateUser, ValidationError, ValidationWarning};
+use super::common::{conclude, ValidationResult, ValidationContext};
+use super::rules::{validate_name, validate_age, validate_metadata, validate_required_metadata, name_warnings, age_warnings};
 
-/// Validates user creation data
-pub fn validate_create_user(user: &CreateUser) -> ValidationResult {
+/// Validates user creation data, returning the warnings of valid data
+pub fn validate_create_user(user: &CreateUser) -> ValidationResult<Vec<ValidationWarning>> {
     validate_create_user_with_context(user, &ValidationContext::new())
 }
 
 /// Validates user creation data with additional context
 pub fn validate_create_user_with_context(
     user: &CreateUser, 
-    _context: &ValidationContext
-) -> ValidationResult {
+    context: &ValidationContext
+) -> ValidationResult<Vec<ValidationWarning>> {
     let mut all_errors = Vec::new();
     
     // Validate name
-    if let Err(mut errors) = validate_name(&user.name, "name") {
+    if let Err(mut errors) = validate_name(&user.name, "name", &context.policy) {
         all_errors.append(&mut errors);
     }
     
     // Validate age
-    if let Err(mut errors) = validate_age(user.age, "age") {
+    if let Err(mut errors) = validate_age(user.age, "age", &context.policy) {
         all_errors.append(&mut errors);
     }
     
+    // Validate metadata if provided
+    if let Some(ref metadata) = user.metadata
+        && let Err(mut errors) = validate_metadata(metadata, "metadata") {
+        all_errors.append(&mut errors);
+    }
+    
+    // Validate metadata keys the policy requires
+    if let Err(mut errors) = validate_required_metadata(user.metadata.as_ref(), "metadata", &context.policy) {
+        all_errors.append(&mut errors);
+    }
+    
+    // Unusual but valid data is accepted with warnings
+    let mut warnings = name_warnings(&user.name, "name");
+    warnings.extend(age_warnings(user.age, "age", &context.policy));
+    
     // Additional create-specific validations can be added here
     // For example, checking for duplicate names, business rules, etc.
     
-    if all_errors.is_empty() {
-        Ok(())
-    } else {
-        Err(all_errors)
-    }
+    conclude(all_errors, warnings, context)
 }
 
 /// Validates user creation data for batch operations
@@ -62,8 +73,9 @@ mod tests {
     #[test]
     fn test_valid_create_user() {
         let user = CreateUser {
-            name: "John Doe".to_string(),
+            name: "John Doe".to_owned(),
             age: 25,
+            metadata: None,
         };
         
         assert!(validate_create_user(&user).is_ok());
@@ -72,8 +84,9 @@ mod tests {
     #[test]
     fn test_invalid_create_user_empty_name() {
         let user = CreateUser {
-            name: "".to_string(),
+            name: String::new(),
             age: 25,
+            metadata: None,
         };
         
         let result = validate_create_user(&user);
@@ -81,20 +94,21 @@ mod tests {
         
         let errors = result.unwrap_err();
         assert_eq!(errors.len(), 1);
-        assert_eq!(errors[0].field, Some("name".to_string()));
+        assert_eq!(errors[0].field, Some("name".to_owned()));
     }
     
     #[test]
     fn test_invalid_create_user_negative_age() {
         let user = CreateUser {
-            name: "John Doe".to_string(),
+            name: "John Doe".to_owned(),
             age: -5,
+            metadata: None,
         };
         
         let result = validate_create_user(&user);
         assert!(result.is_err());
         
         let errors = result.unwrap_err();
-        assert!(errors.iter().any(|e| e.field == Some("age".to_string())));
+        assert!(errors.iter().any(|e| e.field == Some("age".to_owned())));
     }
 }
\ No newline at end of file
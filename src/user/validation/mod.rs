@@ -5,11 +5,19 @@
 
 pub mod create;
 pub mod update;
+pub mod bulk;
+pub mod upsert;
 pub mod common;
 pub mod rules;
+pub mod policy;
+pub mod screening;
 
 // Re-export main validation functions for easy access
-pub use create::validate_create_user;
-pub use update::validate_update_user;
+pub use create::{validate_create_user, validate_create_user_with_context};
+pub use update::{validate_update_user, validate_update_user_with_context};
+pub use upsert::{validate_upsert_user, validate_upsert_user_with_context};
+pub use bulk::{parse_id_list, validate_bulk_ids, validate_bulk_update, validate_bulk_update_with_context};
 pub use common::{ValidationResult, ValidationContext};
+pub use policy::ValidationPolicy;
+pub use screening::{DenylistPolicy, NameScreeningPolicy, SharedNameScreeningPolicy};
 pub use rules::*;
\ No newline at end of file
@@ -0,0 +1,37 @@
+//! Tunable validation policy
+//!
+//! The limits that deployments commonly adjust live in a [`ValidationPolicy`]
+//! rather than in the rules themselves. `UserService` carries one in its
+//! [`ValidationContext`](super::ValidationContext), built from
+//! `ValidationConfig` at startup, so policy changes are configuration rather
+//! than code.
+
+/// Longest name the `users.name` column can hold
+pub const NAME_COLUMN_LENGTH: usize = 255;
+
+/// Limits applied by the user validation rules
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationPolicy {
+    /// Longest accepted name, in bytes (at most [`NAME_COLUMN_LENGTH`])
+    pub name_max_length: usize,
+    /// Youngest accepted age
+    pub age_min: i32,
+    /// Oldest accepted age
+    pub age_max: i32,
+    /// Ages above this are accepted with a warning
+    pub unusual_age_above: i32,
+    /// Metadata keys every user must carry when metadata is written
+    pub required_metadata_keys: Vec<String>,
+}
+
+impl Default for ValidationPolicy {
+    fn default() -> Self {
+        Self {
+            name_max_length: 100,
+            age_min: 1,
+            age_max: 150,
+            unusual_age_above: 100,
+            required_metadata_keys: Vec::new(),
+        }
+    }
+}
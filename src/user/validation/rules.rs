@@ -3,23 +3,26 @@
 //! Contains reusable validation rules that can be applied to different fields and contexts.
 
 
-use super::common::{field_error, ValidationResult};
+use crate::error_codes::ErrorCode;
+use crate::user::domain::{Metadata, ValidationWarning};
+use super::common::{field_error, field_warning, ValidationResult};
+use super::policy::ValidationPolicy;
 
-/// Validates a name field
-pub fn validate_name(name: &str, field_name: &str) -> ValidationResult {
+/// Validates a name field against the length limit of `policy`
+pub fn validate_name(name: &str, field_name: &str, policy: &ValidationPolicy) -> ValidationResult {
     let mut errors = Vec::new();
     
     if name.trim().is_empty() {
-        errors.push(field_error(field_name, "Name cannot be empty"));
+        errors.push(field_error(field_name, ErrorCode::NameEmpty, "Name cannot be empty"));
     }
     
-    if name.len() > 100 {
-        errors.push(field_error(field_name, "Name cannot exceed 100 characters"));
+    if name.len() > policy.name_max_length {
+        errors.push(field_error(field_name, ErrorCode::NameTooLong, format!("Name cannot exceed {} characters", policy.name_max_length)));
     }
     
     // Additional name validation rules can be added here
     if name.chars().any(char::is_numeric) {
-        errors.push(field_error(field_name, "Name cannot contain numbers"));
+        errors.push(field_error(field_name, ErrorCode::NameContainsNumbers, "Name cannot contain numbers"));
     }
     
     if errors.is_empty() {
@@ -29,23 +32,155 @@ pub fn validate_name(name: &str, field_name: &str) -> ValidationResult {
     }
 }
 
-/// Validates an age field
-pub fn validate_age(age: i32, field_name: &str) -> ValidationResult {
+/// Validates an age field against the age range of `policy`
+pub fn validate_age(age: i32, field_name: &str, policy: &ValidationPolicy) -> ValidationResult {
     let mut errors = Vec::new();
     
     if age < 0 {
-        errors.push(field_error(field_name, "Age cannot be negative"));
+        errors.push(field_error(field_name, ErrorCode::AgeNegative, "Age cannot be negative"));
+    } else if age < policy.age_min {
+        errors.push(field_error(field_name, ErrorCode::AgeTooLow, format!("Age must be greater than {}", policy.age_min - 1)));
     }
     
-    if age > 150 {
-        errors.push(field_error(field_name, "Age cannot exceed 150 years"));
+    if age > policy.age_max {
+        errors.push(field_error(field_name, ErrorCode::AgeTooHigh, format!("Age cannot exceed {} years", policy.age_max)));
     }
     
-    // Additional age validation rules can be added here
-    if age == 0 {
-        errors.push(field_error(field_name, "Age must be greater than 0"));
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
     }
-    
+}
+
+/// Longest run of one repeated character a name may have without a warning
+pub const NAME_MAX_REPEATED_CHARS: usize = 3;
+
+/// Warns about names that look like placeholders or keyboard mashing
+///
+/// Names written entirely in capitals, or repeating a character more than
+/// [`NAME_MAX_REPEATED_CHARS`] times in a row, are accepted with a warning.
+#[must_use]
+pub fn name_warnings(name: &str, field_name: &str) -> Vec<ValidationWarning> {
+    let mut warnings = Vec::new();
+
+    let letters = name.chars().filter(|c| c.is_alphabetic()).count();
+    if letters > 1 && !name.chars().any(char::is_lowercase) {
+        warnings.push(field_warning(field_name, ErrorCode::NameAllCapitals, "Name is written entirely in capitals"));
+    }
+
+    let mut run = 0;
+    let mut previous = None;
+    for c in name.chars().flat_map(char::to_lowercase) {
+        run = if previous == Some(c) { run + 1 } else { 1 };
+        previous = Some(c);
+        if run > NAME_MAX_REPEATED_CHARS && !c.is_whitespace() {
+            warnings.push(field_warning(field_name, ErrorCode::NameRepeatedCharacters, format!("Name repeats a character more than {NAME_MAX_REPEATED_CHARS} times in a row")));
+            break;
+        }
+    }
+
+    warnings
+}
+
+/// Warns about valid but unusually high ages, above the threshold of `policy`
+#[must_use]
+pub fn age_warnings(age: i32, field_name: &str, policy: &ValidationPolicy) -> Vec<ValidationWarning> {
+    if age > policy.unusual_age_above && age <= policy.age_max {
+        vec![field_warning(field_name, ErrorCode::AgeUnusual, format!("Age {age} is unusually high"))]
+    } else {
+        Vec::new()
+    }
+}
+
+/// Validates a database identifier (must be positive)
+pub fn validate_id(id: i32, field_name: &str) -> ValidationResult {
+    if id > 0 {
+        Ok(())
+    } else {
+        Err(vec![field_error(field_name, ErrorCode::InvalidId, "ID must be a positive integer")])
+    }
+}
+
+/// Validates an identifier assigned by an external system
+pub fn validate_external_id(value: &str, field_name: &str) -> ValidationResult {
+    let mut errors = Vec::new();
+
+    if value.trim().is_empty() {
+        errors.push(field_error(field_name, ErrorCode::ExternalIdEmpty, "External ID cannot be empty"));
+    }
+
+    if value.trim().len() > 255 {
+        errors.push(field_error(field_name, ErrorCode::ExternalIdTooLong, "External ID cannot exceed 255 characters"));
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+/// Maximum number of metadata labels on one user
+pub const METADATA_MAX_KEYS: usize = 50;
+
+/// Maximum length of a metadata key
+pub const METADATA_MAX_KEY_LENGTH: usize = 40;
+
+/// Maximum length of a metadata value
+pub const METADATA_MAX_VALUE_LENGTH: usize = 500;
+
+/// Validates a metadata key: 1 to 40 ASCII letters, digits, `_` or `-`
+pub fn validate_metadata_key(key: &str, field_name: &str) -> ValidationResult {
+    if key.is_empty() || key.len() > METADATA_MAX_KEY_LENGTH {
+        return Err(vec![field_error(
+            field_name,
+            ErrorCode::MetadataKeyInvalid,
+            format!("Metadata keys must be 1 to {METADATA_MAX_KEY_LENGTH} characters long"),
+        )]);
+    }
+
+    if key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-') {
+        Ok(())
+    } else {
+        Err(vec![field_error(field_name, ErrorCode::MetadataKeyInvalid, format!("Metadata key '{key}' may only contain letters, digits, '_' and '-'"))])
+    }
+}
+
+/// Validates a metadata map: key count, key format and value length
+pub fn validate_metadata(metadata: &Metadata, field_name: &str) -> ValidationResult {
+    let mut errors = Vec::new();
+
+    if metadata.len() > METADATA_MAX_KEYS {
+        errors.push(field_error(field_name, ErrorCode::MetadataTooManyKeys, format!("Metadata cannot have more than {METADATA_MAX_KEYS} keys")));
+    }
+
+    for (key, value) in metadata {
+        let field = format!("{field_name}.{key}");
+        if let Err(mut key_errors) = validate_metadata_key(key, &field) {
+            errors.append(&mut key_errors);
+        }
+        if value.chars().count() > METADATA_MAX_VALUE_LENGTH {
+            errors.push(field_error(&field, ErrorCode::MetadataValueTooLong, format!("Metadata values cannot exceed {METADATA_MAX_VALUE_LENGTH} characters")));
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+/// Validates that `metadata` carries every key `policy` requires
+pub fn validate_required_metadata(metadata: Option<&Metadata>, field_name: &str, policy: &ValidationPolicy) -> ValidationResult {
+    let errors: Vec<_> = policy
+        .required_metadata_keys
+        .iter()
+        .filter(|key| !metadata.is_some_and(|metadata| metadata.contains_key(*key)))
+        .map(|key| field_error(format!("{field_name}.{key}"), ErrorCode::MetadataKeyRequired, format!("Metadata key '{key}' is required")))
+        .collect();
+
     if errors.is_empty() {
         Ok(())
     } else {
@@ -58,7 +193,7 @@ pub fn validate_allowed_characters(value: &str, field_name: &str, allowed_chars:
     if value.chars().all(|c| allowed_chars.contains(c) || c.is_alphabetic() || c.is_whitespace()) {
         Ok(())
     } else {
-        Err(vec![field_error(field_name, format!("Field contains invalid characters. Allowed: {allowed_chars}"))])
+        Err(vec![field_error(field_name, ErrorCode::InvalidCharacters, format!("Field contains invalid characters. Allowed: {allowed_chars}"))])
     }
 }
 
@@ -67,7 +202,7 @@ pub fn validate_min_length(value: &str, field_name: &str, min_length: usize) ->
     if value.len() >= min_length {
         Ok(())
     } else {
-        Err(vec![field_error(field_name, format!("Field must be at least {min_length} characters long"))])
+        Err(vec![field_error(field_name, ErrorCode::TooShort, format!("Field must be at least {min_length} characters long"))])
     }
 }
 
@@ -76,7 +211,7 @@ pub fn validate_max_length(value: &str, field_name: &str, max_length: usize) ->
     if value.len() <= max_length {
         Ok(())
     } else {
-        Err(vec![field_error(field_name, format!("Field cannot exceed {max_length} characters"))])
+        Err(vec![field_error(field_name, ErrorCode::TooLong, format!("Field cannot exceed {max_length} characters"))])
     }
 }
 
@@ -90,11 +225,11 @@ pub fn validate_range<T: PartialOrd + std::fmt::Display>(
     let mut errors = Vec::new();
     
     if value < min {
-        errors.push(field_error(field_name, format!("Value must be at least {min}")));
+        errors.push(field_error(field_name, ErrorCode::OutOfRange, format!("Value must be at least {min}")));
     }
     
     if value > max {
-        errors.push(field_error(field_name, format!("Value cannot exceed {max}")));
+        errors.push(field_error(field_name, ErrorCode::OutOfRange, format!("Value cannot exceed {max}")));
     }
     
     if errors.is_empty() {
@@ -108,26 +243,72 @@ pub fn validate_range<T: PartialOrd + std::fmt::Display>(
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_validate_id() {
+        assert!(validate_id(1, "id").is_ok());
+        assert!(validate_id(0, "id").is_err());
+        assert!(validate_id(-5, "id").is_err());
+    }
+
+    #[test]
+    fn test_name_and_age_warnings() {
+        assert!(name_warnings("Ada Lovelace", "name").is_empty());
+        assert!(name_warnings("Aaaron", "name").is_empty());
+        assert!(name_warnings("J", "name").is_empty());
+        assert_eq!(name_warnings("ADA LOVELACE", "name").len(), 1);
+        assert_eq!(name_warnings("Annnnna", "name").len(), 1);
+        assert_eq!(name_warnings("ZZZZ", "name").len(), 2);
+
+        let policy = ValidationPolicy::default();
+        assert!(age_warnings(100, "age", &policy).is_empty());
+        assert_eq!(age_warnings(120, "age", &policy)[0].field.as_deref(), Some("age"));
+        assert!(age_warnings(200, "age", &policy).is_empty());
+    }
+
+    #[test]
+    fn test_validate_external_id() {
+        assert!(validate_external_id("ada@example.com", "external_id").is_ok());
+        assert!(validate_external_id("  ", "external_id").is_err());
+        assert!(validate_external_id(&"x".repeat(256), "external_id").is_err());
+    }
+
+    #[test]
+    fn test_validate_metadata() {
+        let valid = Metadata::from([("department".to_owned(), "eng".to_owned()), ("cost-center_2".to_owned(), String::new())]);
+        assert!(validate_metadata(&valid, "metadata").is_ok());
+
+        let bad_key = Metadata::from([("team lead".to_owned(), "ada".to_owned())]);
+        let errors = validate_metadata(&bad_key, "metadata").unwrap_err();
+        assert_eq!(errors[0].field.as_deref(), Some("metadata.team lead"));
+
+        let long_value = Metadata::from([("note".to_owned(), "x".repeat(METADATA_MAX_VALUE_LENGTH + 1))]);
+        assert!(validate_metadata(&long_value, "metadata").is_err());
+
+        let too_many: Metadata = (0..=METADATA_MAX_KEYS).map(|i| (format!("k{i}"), String::new())).collect();
+        assert!(validate_metadata(&too_many, "metadata").is_err());
+        assert!(validate_metadata_key(&"k".repeat(METADATA_MAX_KEY_LENGTH + 1), "key").is_err());
+    }
+
     #[test]
     fn test_validate_name_valid() {
-        assert!(validate_name("John Doe", "name").is_ok());
-        assert!(validate_name("Mary Jane", "name").is_ok());
-        assert!(validate_name("José", "name").is_ok());
+        assert!(validate_name("John Doe", "name", &ValidationPolicy::default()).is_ok());
+        assert!(validate_name("Mary Jane", "name", &ValidationPolicy::default()).is_ok());
+        assert!(validate_name("José", "name", &ValidationPolicy::default()).is_ok());
     }
 
     #[test]
     fn test_validate_name_empty() {
-        let result = validate_name("", "name");
+        let result = validate_name("", "name", &ValidationPolicy::default());
         assert!(result.is_err());
         let errors = result.unwrap_err();
         assert_eq!(errors.len(), 1);
-        assert_eq!(errors[0].field, Some("name".to_string()));
+        assert_eq!(errors[0].field, Some("name".to_owned()));
         assert!(errors[0].message.contains("cannot be empty"));
     }
 
     #[test]
     fn test_validate_name_whitespace_only() {
-        let result = validate_name("   ", "name");
+        let result = validate_name("   ", "name", &ValidationPolicy::default());
         assert!(result.is_err());
         let errors = result.unwrap_err();
         assert_eq!(errors.len(), 1);
@@ -137,7 +318,7 @@ mod tests {
     #[test]
     fn test_validate_name_too_long() {
         let long_name = "a".repeat(101);
-        let result = validate_name(&long_name, "name");
+        let result = validate_name(&long_name, "name", &ValidationPolicy::default());
         assert!(result.is_err());
         let errors = result.unwrap_err();
         assert!(errors.iter().any(|e| e.message.contains("cannot exceed 100 characters")));
@@ -145,7 +326,7 @@ mod tests {
 
     #[test]
     fn test_validate_name_with_numbers() {
-        let result = validate_name("John123", "name");
+        let result = validate_name("John123", "name", &ValidationPolicy::default());
         assert!(result.is_err());
         let errors = result.unwrap_err();
         assert!(errors.iter().any(|e| e.message.contains("cannot contain numbers")));
@@ -153,7 +334,7 @@ mod tests {
 
     #[test]
     fn test_validate_name_multiple_errors() {
-        let result = validate_name("123", "name");
+        let result = validate_name("123", "name", &ValidationPolicy::default());
         assert!(result.is_err());
         let errors = result.unwrap_err();
         assert_eq!(errors.len(), 1); // Only the numbers error since it's not empty
@@ -162,14 +343,14 @@ mod tests {
 
     #[test]
     fn test_validate_age_valid() {
-        assert!(validate_age(25, "age").is_ok());
-        assert!(validate_age(1, "age").is_ok());
-        assert!(validate_age(150, "age").is_ok());
+        assert!(validate_age(25, "age", &ValidationPolicy::default()).is_ok());
+        assert!(validate_age(1, "age", &ValidationPolicy::default()).is_ok());
+        assert!(validate_age(150, "age", &ValidationPolicy::default()).is_ok());
     }
 
     #[test]
     fn test_validate_age_negative() {
-        let result = validate_age(-1, "age");
+        let result = validate_age(-1, "age", &ValidationPolicy::default());
         assert!(result.is_err());
         let errors = result.unwrap_err();
         assert!(errors.iter().any(|e| e.message.contains("cannot be negative")));
@@ -177,7 +358,7 @@ mod tests {
 
     #[test]
     fn test_validate_age_zero() {
-        let result = validate_age(0, "age");
+        let result = validate_age(0, "age", &ValidationPolicy::default());
         assert!(result.is_err());
         let errors = result.unwrap_err();
         assert!(errors.iter().any(|e| e.message.contains("must be greater than 0")));
@@ -185,12 +366,36 @@ mod tests {
 
     #[test]
     fn test_validate_age_too_high() {
-        let result = validate_age(151, "age");
+        let result = validate_age(151, "age", &ValidationPolicy::default());
         assert!(result.is_err());
         let errors = result.unwrap_err();
         assert!(errors.iter().any(|e| e.message.contains("cannot exceed 150 years")));
     }
 
+    #[test]
+    fn test_rules_follow_the_policy() {
+        let policy = ValidationPolicy {
+            name_max_length: 5,
+            age_min: 18,
+            age_max: 65,
+            unusual_age_above: 60,
+            required_metadata_keys: vec!["department".to_owned()],
+        };
+
+        assert!(validate_name("Alice", "name", &policy).is_ok());
+        let errors = validate_name("Alicia", "name", &policy).unwrap_err();
+        assert_eq!(errors[0].message, "Name cannot exceed 5 characters");
+        assert!(validate_age(18, "age", &policy).is_ok());
+        assert_eq!(validate_age(17, "age", &policy).unwrap_err()[0].message, "Age must be greater than 17");
+        assert_eq!(validate_age(66, "age", &policy).unwrap_err()[0].message, "Age cannot exceed 65 years");
+
+        let labelled = Metadata::from([("department".to_owned(), "eng".to_owned())]);
+        assert!(validate_required_metadata(Some(&labelled), "metadata", &policy).is_ok());
+        let errors = validate_required_metadata(None, "metadata", &policy).unwrap_err();
+        assert_eq!(errors[0].field.as_deref(), Some("metadata.department"));
+        assert!(validate_required_metadata(None, "metadata", &ValidationPolicy::default()).is_ok());
+    }
+
     #[test]
     fn test_validate_allowed_characters_valid() {
         assert!(validate_allowed_characters("Hello World", "field", "!@#").is_ok());
@@ -0,0 +1,115 @@
+//! Name screening
+//!
+//! Names that pass the format rules are screened by a [`NameScreeningPolicy`]
+//! before they are stored, so public-facing deployments can reject offensive
+//! or reserved words. [`DenylistPolicy`] is the built-in policy, configured
+//! from `NAME_DENYLIST`; the trait is async so that a policy backed by an
+//! external moderation service can be plugged in with
+//! `UserService::with_name_screening`.
+
+use std::sync::Arc;
+
+use futures_util::future::{self, BoxFuture};
+
+use crate::error_codes::ErrorCode;
+
+use super::common::{field_error, ValidationResult};
+
+/// Decides whether a name may be stored
+pub trait NameScreeningPolicy: Send + Sync {
+    /// Screens `name`, reporting a rejection as an error on `field_name`
+    fn screen<'a>(&'a self, name: &'a str, field_name: &'a str) -> BoxFuture<'a, ValidationResult>;
+}
+
+/// Screening policy shared between the user services
+pub type SharedNameScreeningPolicy = Arc<dyn NameScreeningPolicy>;
+
+/// Policy rejecting names that contain a denylisted word or phrase
+///
+/// Matching ignores case and punctuation and compares whole words, so the
+/// term `ass` rejects "Ass Hat" but not "Cassandra". An empty denylist
+/// allows every name.
+#[derive(Debug, Clone, Default)]
+pub struct DenylistPolicy {
+    terms: Vec<Vec<String>>,
+}
+
+impl DenylistPolicy {
+    /// Creates a policy denying each of `terms`
+    #[must_use]
+    pub fn new<I, S>(terms: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        Self {
+            terms: terms.into_iter().map(|term| words(term.as_ref())).filter(|term| !term.is_empty()).collect(),
+        }
+    }
+
+    /// Parses a comma-separated denylist, e.g. `badword,bad phrase`
+    #[must_use]
+    pub fn from_spec(spec: &str) -> Self {
+        Self::new(spec.split(','))
+    }
+
+    /// This policy as a [`SharedNameScreeningPolicy`]
+    #[must_use]
+    pub fn shared(self) -> SharedNameScreeningPolicy {
+        Arc::new(self)
+    }
+
+    /// Whether `name` contains one of the denied terms
+    #[must_use]
+    pub fn denies(&self, name: &str) -> bool {
+        let words = words(name);
+        self.terms
+            .iter()
+            .any(|term| words.windows(term.len()).any(|window| window == term.as_slice()))
+    }
+}
+
+impl NameScreeningPolicy for DenylistPolicy {
+    fn screen<'a>(&'a self, name: &'a str, field_name: &'a str) -> BoxFuture<'a, ValidationResult> {
+        let result = if self.denies(name) {
+            Err(vec![field_error(field_name, ErrorCode::NameNotAllowed, "Name contains a word that is not allowed")])
+        } else {
+            Ok(())
+        };
+        Box::pin(future::ready(result))
+    }
+}
+
+/// Lowercase words of `text`, split on anything but letters and digits
+fn words(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_denylist_matches_whole_words_ignoring_case() {
+        let policy = DenylistPolicy::from_spec("ass, bad phrase,,");
+
+        assert!(policy.denies("Ass Hat"));
+        assert!(policy.denies("mr. ASS"));
+        assert!(policy.denies("A Bad-Phrase Here"));
+        assert!(!policy.denies("Cassandra"));
+        assert!(!policy.denies("Bad Luck Phrase"));
+        assert!(!DenylistPolicy::default().denies("Ass Hat"));
+    }
+
+    #[tokio::test]
+    async fn test_denylist_screen_reports_field_error() {
+        let policy = DenylistPolicy::new(["admin"]);
+
+        assert!(policy.screen("Ada Lovelace", "name").await.is_ok());
+        let errors = policy.screen("The Admin", "name").await.unwrap_err();
+        assert_eq!(errors[0].field.as_deref(), Some("name"));
+    }
+}
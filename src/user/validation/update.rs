@@ -2,52 +2,67 @@
 //! 
 //! Contains validation rules specific to user update operations.
 
-use crate::user::domain::UpdateUser;
-use super::common::{ValidationResult, ValidationContext, general_error};
-use super::rules::{validate_name, validate_age};
+use crate::error_codes::ErrorCode;
+use crate::user::domain::{UpdateUser, ValidationWarning};
+use super::common::{conclude, ValidationResult, ValidationContext, general_error};
+use super::rules::{validate_name, validate_age, validate_metadata, validate_required_metadata, name_warnings, age_warnings};
 
-/// Validates user update data
-pub fn validate_update_user(user: &UpdateUser) -> ValidationResult {
+/// Validates user update data, returning the warnings of valid data
+pub fn validate_update_user(user: &UpdateUser) -> ValidationResult<Vec<ValidationWarning>> {
     validate_update_user_with_context(user, &ValidationContext::new())
 }
 
 /// Validates user update data with additional context
 pub fn validate_update_user_with_context(
     user: &UpdateUser, 
-    _context: &ValidationContext
-) -> ValidationResult {
+    context: &ValidationContext
+) -> ValidationResult<Vec<ValidationWarning>> {
     let mut all_errors = Vec::new();
     
     // Check if at least one field is provided for update
-    if user.name.is_none() && user.age.is_none() {
-        all_errors.push(general_error("At least one field (name or age) must be provided for update"));
+    if user.name.is_none() && user.age.is_none() && user.metadata.is_none() {
+        all_errors.push(general_error(ErrorCode::NoFieldsToUpdate, "At least one field (name, age or metadata) must be provided for update"));
         return Err(all_errors);
     }
     
     // Validate name if provided
     if let Some(ref name) = user.name
-        && let Err(mut errors) = validate_name(name, "name") {
+        && let Err(mut errors) = validate_name(name, "name", &context.policy) {
             all_errors.append(&mut errors);
         }
     
     // Validate age if provided
     if let Some(age) = user.age
-        && let Err(mut errors) = validate_age(age, "age") {
+        && let Err(mut errors) = validate_age(age, "age", &context.policy) {
         all_errors.append(&mut errors);
         }
     
+    // Validate metadata if provided; it replaces all labels, so it must carry the required ones
+    if let Some(ref metadata) = user.metadata {
+        if let Err(mut errors) = validate_metadata(metadata, "metadata") {
+            all_errors.append(&mut errors);
+        }
+        if let Err(mut errors) = validate_required_metadata(Some(metadata), "metadata", &context.policy) {
+            all_errors.append(&mut errors);
+        }
+    }
+    
     // Additional update-specific validations can be added here
     // For example, checking if the update would create duplicates, etc.
     
-    if all_errors.is_empty() {
-        Ok(())
-    } else {
-        Err(all_errors)
-    }
+    conclude(all_errors, update_warnings(user, context), context)
 }
 
 /// Validates partial update data (allows empty updates for specific use cases)
-pub fn validate_partial_update_user(user: &UpdateUser) -> ValidationResult {
+pub fn validate_partial_update_user(user: &UpdateUser) -> ValidationResult<Vec<ValidationWarning>> {
+    validate_partial_update_user_with_context(user, &ValidationContext::new())
+}
+
+/// Validates partial update data with additional context
+pub fn validate_partial_update_user_with_context(
+    user: &UpdateUser,
+    context: &ValidationContext
+) -> ValidationResult<Vec<ValidationWarning>> {
     let mut all_errors = Vec::new();
     
     // For partial updates, we don't require at least one field
@@ -55,21 +70,36 @@ pub fn validate_partial_update_user(user: &UpdateUser) -> ValidationResult {
     
     // Validate name if provided
     if let Some(ref name) = user.name
-        && let Err(mut errors) = validate_name(name, "name") {
+        && let Err(mut errors) = validate_name(name, "name", &context.policy) {
             all_errors.append(&mut errors);
         }
     
     // Validate age if provided
     if let Some(age) = user.age
-        && let Err(mut errors) = validate_age(age, "age") {
+        && let Err(mut errors) = validate_age(age, "age", &context.policy) {
             all_errors.append(&mut errors);
         }
     
-    if all_errors.is_empty() {
-        Ok(())
-    } else {
-        Err(all_errors)
+    // Validate metadata if provided; it replaces all labels, so it must carry the required ones
+    if let Some(ref metadata) = user.metadata {
+        if let Err(mut errors) = validate_metadata(metadata, "metadata") {
+            all_errors.append(&mut errors);
+        }
+        if let Err(mut errors) = validate_required_metadata(Some(metadata), "metadata", &context.policy) {
+            all_errors.append(&mut errors);
+        }
+    }
+    
+    conclude(all_errors, update_warnings(user, context), context)
+}
+
+/// Warnings about the unusual but valid fields of an update
+fn update_warnings(user: &UpdateUser, context: &ValidationContext) -> Vec<ValidationWarning> {
+    let mut warnings = user.name.as_deref().map(|name| name_warnings(name, "name")).unwrap_or_default();
+    if let Some(age) = user.age {
+        warnings.extend(age_warnings(age, "age", &context.policy));
     }
+    warnings
 }
 
 #[cfg(test)]
@@ -79,8 +109,9 @@ mod tests {
     #[test]
     fn test_valid_update_user() {
         let user = UpdateUser {
-            name: Some("Jane Doe".to_string()),
+            name: Some("Jane Doe".to_owned()),
             age: Some(30),
+            metadata: None,
         };
         
         assert!(validate_update_user(&user).is_ok());
@@ -89,8 +120,9 @@ mod tests {
     #[test]
     fn test_valid_update_user_name_only() {
         let user = UpdateUser {
-            name: Some("Jane Doe".to_string()),
+            name: Some("Jane Doe".to_owned()),
             age: None,
+            metadata: None,
         };
         
         assert!(validate_update_user(&user).is_ok());
@@ -101,6 +133,7 @@ mod tests {
         let user = UpdateUser {
             name: None,
             age: None,
+            metadata: None,
         };
         
         let result = validate_update_user(&user);
@@ -114,15 +147,16 @@ mod tests {
     #[test]
     fn test_invalid_update_user_empty_name() {
         let user = UpdateUser {
-            name: Some("".to_string()),
+            name: Some(String::new()),
             age: Some(25),
+            metadata: None,
         };
         
         let result = validate_update_user(&user);
         assert!(result.is_err());
         
         let errors = result.unwrap_err();
-        assert!(errors.iter().any(|e| e.field == Some("name".to_string())));
+        assert!(errors.iter().any(|e| e.field == Some("name".to_owned())));
     }
     
     #[test]
@@ -130,6 +164,7 @@ mod tests {
         let user = UpdateUser {
             name: None,
             age: None,
+            metadata: None,
         };
         
         assert!(validate_partial_update_user(&user).is_ok());
@@ -0,0 +1,64 @@
+//! `UpsertUser` validation logic
+//!
+//! Contains validation rules specific to upserts by external identifier.
+
+use crate::user::domain::{UpsertUser, ValidationWarning};
+use super::common::{conclude, ValidationResult, ValidationContext};
+use super::rules::{age_warnings, name_warnings, validate_age, validate_external_id, validate_name};
+
+/// Validates user upsert data, returning the warnings of valid data
+pub fn validate_upsert_user(user: &UpsertUser) -> ValidationResult<Vec<ValidationWarning>> {
+    validate_upsert_user_with_context(user, &ValidationContext::new())
+}
+
+/// Validates user upsert data with additional context
+pub fn validate_upsert_user_with_context(user: &UpsertUser, context: &ValidationContext) -> ValidationResult<Vec<ValidationWarning>> {
+    let mut all_errors = Vec::new();
+
+    if let Err(mut errors) = validate_external_id(&user.external_id, "external_id") {
+        all_errors.append(&mut errors);
+    }
+
+    if let Err(mut errors) = validate_name(&user.name, "name", &context.policy) {
+        all_errors.append(&mut errors);
+    }
+
+    if let Err(mut errors) = validate_age(user.age, "age", &context.policy) {
+        all_errors.append(&mut errors);
+    }
+
+    let mut warnings = name_warnings(&user.name, "name");
+    warnings.extend(age_warnings(user.age, "age", &context.policy));
+
+    conclude(all_errors, warnings, context)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_valid_upsert_user() {
+        let user = UpsertUser {
+            external_id: "crm-42".to_owned(),
+            name: "John Doe".to_owned(),
+            age: 25,
+        };
+
+        assert!(validate_upsert_user(&user).is_ok());
+    }
+
+    #[test]
+    fn test_invalid_upsert_user_reports_every_field() {
+        let user = UpsertUser {
+            external_id: String::new(),
+            name: String::new(),
+            age: -1,
+        };
+
+        let errors = validate_upsert_user(&user).expect_err("Upsert should be rejected");
+
+        let fields: Vec<_> = errors.iter().filter_map(|e| e.field.as_deref()).collect();
+        assert_eq!(fields, ["external_id", "name", "age"]);
+    }
+}
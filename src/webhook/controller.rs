@@ -0,0 +1,172 @@
+//! Webhook controller - HTTP handlers for webhook subscriptions, secrets and deliveries
+
+use axum::{
+    Json,
+    extract::{Path, Query},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use tracing::{error, warn};
+
+use crate::error_codes::{ErrorCode, ErrorResponse, internal_error};
+use crate::registry::Inject;
+use crate::user::domain::ValidationErrorResponse;
+
+use super::domain::{CreateWebhook, CreatedWebhook, DeliveryListParams, IssuedWebhookSecret, UpdateWebhook, Webhook, WebhookDelivery, WebhookError};
+use super::service::WebhookService;
+
+/// Maps webhook errors to HTTP responses
+fn error_response(error: WebhookError) -> Response {
+    match error {
+        WebhookError::ValidationError(errors) => {
+            warn!(?errors, "Controller: Webhook validation failed");
+            (StatusCode::BAD_REQUEST, Json(ValidationErrorResponse { errors })).into_response()
+        }
+        WebhookError::NotFound(id) => {
+            warn!(webhook_id = id, "Controller: Webhook not found");
+            (
+                StatusCode::NOT_FOUND,
+                Json(ErrorResponse::new(ErrorCode::WebhookNotFound, format!("Webhook {id} not found"))),
+            )
+                .into_response()
+        }
+        WebhookError::DatabaseError(db) => db.into_response(),
+        WebhookError::QueueError(e) => {
+            error!(error = %e, "Controller: Queue error in webhook operation");
+            internal_error()
+        }
+    }
+}
+
+/// HTTP handler creating a webhook subscription
+#[utoipa::path(
+    post,
+    path = "/webhooks",
+    tag = "webhooks",
+    request_body = CreateWebhook,
+    responses(
+        (status = 201, description = "Webhook created; the secret is not shown again", body = CreatedWebhook),
+        (status = 400, description = "Invalid URL or unknown event type", body = ValidationErrorResponse),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+        (status = 403, description = "Caller lacks the admin role", body = ErrorResponse),
+        (status = 503, description = "The database is unavailable", body = ErrorResponse)
+    )
+)]
+#[tracing::instrument(skip(webhook_service))]
+pub async fn create_webhook_handler(Inject(webhook_service): Inject<WebhookService>, Json(request): Json<CreateWebhook>) -> Response {
+    match webhook_service.create(request).await {
+        Ok(created) => (StatusCode::CREATED, Json(created)).into_response(),
+        Err(e) => error_response(e),
+    }
+}
+
+/// HTTP handler returning a webhook subscription
+#[utoipa::path(
+    get,
+    path = "/webhooks/{id}",
+    tag = "webhooks",
+    params(
+        ("id" = i32, Path, description = "Webhook ID")
+    ),
+    responses(
+        (status = 200, description = "The webhook", body = Webhook),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+        (status = 403, description = "Caller lacks the admin role", body = ErrorResponse),
+        (status = 404, description = "No webhook has this ID", body = ErrorResponse),
+        (status = 503, description = "The database is unavailable", body = ErrorResponse)
+    )
+)]
+#[tracing::instrument(skip(webhook_service))]
+pub async fn get_webhook_handler(Inject(webhook_service): Inject<WebhookService>, Path(id): Path<i32>) -> Response {
+    match webhook_service.get(id).await {
+        Ok(webhook) => (StatusCode::OK, Json(webhook)).into_response(),
+        Err(e) => error_response(e),
+    }
+}
+
+/// HTTP handler changing the URL or event types of a webhook
+#[utoipa::path(
+    patch,
+    path = "/webhooks/{id}",
+    tag = "webhooks",
+    params(
+        ("id" = i32, Path, description = "Webhook ID")
+    ),
+    request_body = UpdateWebhook,
+    responses(
+        (status = 200, description = "Webhook updated", body = Webhook),
+        (status = 400, description = "Invalid URL or unknown event type", body = ValidationErrorResponse),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+        (status = 403, description = "Caller lacks the admin role", body = ErrorResponse),
+        (status = 404, description = "No webhook has this ID", body = ErrorResponse),
+        (status = 503, description = "The database is unavailable", body = ErrorResponse)
+    )
+)]
+#[tracing::instrument(skip(webhook_service))]
+pub async fn update_webhook_handler(
+    Inject(webhook_service): Inject<WebhookService>,
+    Path(id): Path<i32>,
+    Json(request): Json<UpdateWebhook>,
+) -> Response {
+    match webhook_service.update(id, request).await {
+        Ok(webhook) => (StatusCode::OK, Json(webhook)).into_response(),
+        Err(e) => error_response(e),
+    }
+}
+
+/// HTTP handler issuing a new signing secret for a webhook
+///
+/// Deliveries keep carrying signatures of the previous secrets until
+/// `previous_secrets_expire_at`.
+#[utoipa::path(
+    post,
+    path = "/webhooks/{id}/secrets",
+    tag = "webhooks",
+    params(
+        ("id" = i32, Path, description = "Webhook ID")
+    ),
+    responses(
+        (status = 201, description = "Secret issued; it is not shown again", body = IssuedWebhookSecret),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+        (status = 403, description = "Caller lacks the admin role", body = ErrorResponse),
+        (status = 404, description = "No webhook has this ID", body = ErrorResponse),
+        (status = 503, description = "The database is unavailable", body = ErrorResponse)
+    )
+)]
+#[tracing::instrument(skip(webhook_service))]
+pub async fn rotate_webhook_secret_handler(Inject(webhook_service): Inject<WebhookService>, Path(id): Path<i32>) -> Response {
+    match webhook_service.rotate_secret(id).await {
+        Ok(secret) => (StatusCode::CREATED, Json(secret)).into_response(),
+        Err(e) => error_response(e),
+    }
+}
+
+/// HTTP handler listing the delivery attempts of a webhook, for debugging its receiver
+#[utoipa::path(
+    get,
+    path = "/webhooks/{id}/deliveries",
+    tag = "webhooks",
+    params(
+        ("id" = i32, Path, description = "Webhook ID"),
+        DeliveryListParams
+    ),
+    responses(
+        (status = 200, description = "Delivery attempts newest first, with the receiver's response status", body = [WebhookDelivery]),
+        (status = 400, description = "Invalid limit", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+        (status = 403, description = "Caller lacks the admin role", body = ErrorResponse),
+        (status = 404, description = "No webhook has this ID", body = ErrorResponse),
+        (status = 503, description = "The database is unavailable", body = ErrorResponse)
+    )
+)]
+#[tracing::instrument(skip(webhook_service))]
+pub async fn list_webhook_deliveries_handler(
+    Inject(webhook_service): Inject<WebhookService>,
+    Path(id): Path<i32>,
+    Query(params): Query<DeliveryListParams>,
+) -> Response {
+    match webhook_service.deliveries(id, params.limit).await {
+        Ok(deliveries) => (StatusCode::OK, Json(deliveries)).into_response(),
+        Err(e) => error_response(e),
+    }
+}
@@ -0,0 +1,123 @@
+//! Webhook deliveries
+//!
+//! [`forward_events`] queues a `webhook-delivery` job per subscriber for each
+//! published event, and the [`WebhookDeliveryHandler`] posts it. A delivery
+//! is a `POST` of the event's JSON with these headers:
+//!
+//! - `X-Webhook-Id`: identifier of the delivery, the same on every retry
+//! - `X-Webhook-Event`: the event type
+//! - `X-Timestamp`: when it was signed, in Unix seconds
+//! - `X-Webhook-Signature`: comma-separated signatures, one per live secret
+//!   (newest first), each the base64url (unpadded) HMAC-SHA256 of
+//!   `{timestamp}\n{body}` keyed with the SHA-256 digest of the secret
+//!
+//! A receiver accepts the delivery when any signature matches its secret, so
+//! it keeps working while a rotated secret is phased out.
+
+use axum::http::HeaderName;
+use base64::{Engine as _, engine::general_purpose};
+use futures_util::future::BoxFuture;
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::Sha256;
+use tokio::{sync::broadcast::error::RecvError, task::JoinHandle};
+use tracing::{error, info, warn};
+
+use crate::events::EventBus;
+use crate::jobs::{JobError, JobHandler};
+use crate::partner::signing::signing_key;
+
+use super::service::WebhookService;
+
+/// HMAC-SHA256
+type HmacSha256 = Hmac<Sha256>;
+
+/// Kind of the jobs delivering an event to one webhook
+pub const DELIVERY_JOB: &str = "webhook-delivery";
+
+/// Queue deliveries are put on, so they do not wait behind bulk jobs
+pub const WEBHOOK_QUEUE: &str = "webhooks";
+
+/// Header carrying the delivery identifier
+pub const ID_HEADER: HeaderName = HeaderName::from_static("x-webhook-id");
+/// Header carrying the event type
+pub const EVENT_HEADER: HeaderName = HeaderName::from_static("x-webhook-event");
+/// Header carrying the signing time in Unix seconds
+pub const TIMESTAMP_HEADER: HeaderName = HeaderName::from_static("x-timestamp");
+/// Header carrying the delivery signatures
+pub const SIGNATURE_HEADER: HeaderName = HeaderName::from_static("x-webhook-signature");
+
+/// Payload of a delivery job
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub(super) struct DeliveryJob {
+    /// Webhook to deliver to
+    pub(super) webhook_id: i32,
+    /// Identifier shared by the attempts of this delivery
+    pub(super) delivery_id: String,
+    /// Type of the event
+    pub(super) event_type: String,
+    /// The event, as delivered
+    pub(super) event: Value,
+}
+
+/// Signature of a delivery body with the key derived from a secret
+pub(super) fn signature(key: &[u8], timestamp: i64, body: &[u8]) -> String {
+    let mut mac = HmacSha256::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(format!("{timestamp}\n").as_bytes());
+    mac.update(body);
+    general_purpose::URL_SAFE_NO_PAD.encode(mac.finalize().into_bytes())
+}
+
+/// Signature of a delivery body signed at `timestamp` with `secret`, as receivers compute it
+#[must_use]
+pub fn sign(secret: &str, timestamp: i64, body: &[u8]) -> String {
+    signature(&signing_key(secret), timestamp, body)
+}
+
+/// Runs `webhook-delivery` jobs
+pub struct WebhookDeliveryHandler {
+    service: WebhookService,
+}
+
+impl WebhookDeliveryHandler {
+    /// Creates a handler delivering with `service`
+    #[must_use]
+    pub const fn new(service: WebhookService) -> Self {
+        Self { service }
+    }
+}
+
+impl JobHandler for WebhookDeliveryHandler {
+    fn kind(&self) -> &'static str {
+        DELIVERY_JOB
+    }
+
+    fn handle(&self, payload: Value) -> BoxFuture<'_, Result<(), JobError>> {
+        Box::pin(async move {
+            let job: DeliveryJob = serde_json::from_value(payload)?;
+            self.service.deliver(&job).await
+        })
+    }
+}
+
+/// Queues deliveries of the events published on `events` until the task is aborted
+///
+/// Events published while the deliveries of earlier ones are being queued are
+/// buffered by the bus; if it overflows, the oldest are skipped with a warning.
+#[must_use]
+pub fn forward_events(service: WebhookService, events: &EventBus) -> JoinHandle<()> {
+    let mut receiver = events.subscribe();
+    tokio::spawn(async move {
+        loop {
+            match receiver.recv().await {
+                Ok(event) => match service.dispatch(&event).await {
+                    Ok(queued) => info!(event_type = event.event_type(), queued, "Webhook deliveries queued"),
+                    Err(e) => error!(event_type = event.event_type(), error = %e, "Failed to queue webhook deliveries"),
+                },
+                Err(RecvError::Lagged(skipped)) => warn!(skipped, "Webhook forwarding fell behind; events were not delivered"),
+                Err(RecvError::Closed) => break,
+            }
+        }
+    })
+}
@@ -0,0 +1,123 @@
+//! Webhook domain models
+
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::db::DbError;
+use crate::jobs::QueueError;
+use crate::user::domain::ValidationError;
+
+/// A webhook subscription
+#[derive(Serialize, ToSchema, JsonSchema, Debug, Clone, PartialEq, Eq)]
+pub struct Webhook {
+    /// Unique webhook identifier
+    pub id: i32,
+    /// URL deliveries are posted to
+    pub url: String,
+    /// Event types delivered, e.g. `user_status_changed`; empty for every type
+    pub event_types: Vec<String>,
+    /// When the webhook was created
+    pub created_at: DateTime<Utc>,
+    /// When the webhook last changed
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Webhook {
+    /// Whether events of `event_type` are delivered to this webhook
+    #[must_use]
+    pub fn receives(&self, event_type: &str) -> bool {
+        self.event_types.is_empty() || self.event_types.iter().any(|subscribed| subscribed == event_type)
+    }
+}
+
+/// Request body for creating a webhook
+#[derive(Deserialize, ToSchema, JsonSchema, Debug, Clone)]
+pub struct CreateWebhook {
+    /// Absolute `http` or `https` URL deliveries are posted to
+    pub url: String,
+    /// Event types to deliver; omit or leave empty for every type
+    #[serde(default)]
+    pub event_types: Vec<String>,
+}
+
+/// Request body for updating a webhook; omitted fields keep their value
+#[derive(Deserialize, ToSchema, JsonSchema, Debug, Clone, Default)]
+pub struct UpdateWebhook {
+    /// New delivery URL
+    pub url: Option<String>,
+    /// New event types; an empty list subscribes to every type
+    pub event_types: Option<Vec<String>>,
+}
+
+/// A newly created webhook with its signing secret
+///
+/// The secret is only ever returned here and by a rotation; hand it to the
+/// receiver over a secure channel.
+#[derive(Serialize, ToSchema, JsonSchema, Debug, Clone)]
+pub struct CreatedWebhook {
+    /// The webhook
+    pub webhook: Webhook,
+    /// Secret to derive the signature key from (SHA-256 of its UTF-8 bytes)
+    pub secret: String,
+}
+
+/// A newly issued webhook signing secret
+#[derive(Serialize, ToSchema, JsonSchema, Debug, Clone)]
+pub struct IssuedWebhookSecret {
+    /// Webhook the secret signs deliveries of
+    pub webhook_id: i32,
+    /// Secret to derive the signature key from (SHA-256 of its UTF-8 bytes)
+    pub secret: String,
+    /// When the secret was issued
+    pub created_at: DateTime<Utc>,
+    /// When deliveries stop carrying signatures of the previous secrets
+    pub previous_secrets_expire_at: Option<DateTime<Utc>>,
+}
+
+/// One attempt at delivering an event to a webhook
+#[derive(Serialize, ToSchema, JsonSchema, Debug, Clone)]
+pub struct WebhookDelivery {
+    /// Unique attempt identifier
+    pub id: i64,
+    /// Identifier shared by the attempts of one event, sent as `X-Webhook-Id`
+    pub delivery_id: String,
+    /// Type of the delivered event
+    pub event_type: String,
+    /// Attempt number, starting at 1
+    pub attempt: i32,
+    /// HTTP status the receiver answered with; absent when no response came
+    pub response_status: Option<i32>,
+    /// Why the attempt failed, if it did
+    pub error: Option<String>,
+    /// Milliseconds until the receiver answered or the attempt failed
+    pub duration_ms: i32,
+    /// When the attempt was made
+    pub attempted_at: DateTime<Utc>,
+}
+
+/// Query parameters of the delivery listing
+#[derive(Deserialize, IntoParams, Debug, Clone, Default)]
+#[into_params(parameter_in = Query)]
+pub struct DeliveryListParams {
+    /// Most attempts to return, newest first (default 50, at most 200)
+    pub limit: Option<i64>,
+}
+
+/// Domain errors for webhook operations
+#[derive(Debug, thiserror::Error)]
+pub enum WebhookError {
+    /// URL or event types are invalid
+    #[error("Validation failed")]
+    ValidationError(Vec<ValidationError>),
+    /// No webhook has this ID
+    #[error("Webhook {0} not found")]
+    NotFound(i32),
+    /// A delivery could not be queued
+    #[error("Queue error: {0}")]
+    QueueError(QueueError),
+    /// Database operation failed
+    #[error("Database error: {0}")]
+    DatabaseError(DbError),
+}
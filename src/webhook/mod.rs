@@ -0,0 +1,27 @@
+//! Outgoing webhook module
+//!
+//! Subscribers register a URL with `POST /webhooks` and choose the
+//! [`DomainEvent`](crate::events::DomainEvent) types they receive (all of them
+//! when the list is empty). Events published on the [`EventBus`](crate::events::EventBus)
+//! are queued as `webhook-delivery` jobs on the `webhooks` queue, so a failed
+//! delivery is retried by the job queue and dead-lettered like any other job.
+//! Each delivery is signed with every live secret of the subscription:
+//! `POST /webhooks/{id}/secrets` issues a new secret and lets the previous
+//! ones expire after a grace period, so receivers can switch over without
+//! rejecting deliveries. Every attempt is recorded with the receiver's
+//! response code and listed by `GET /webhooks/{id}/deliveries`.
+
+pub mod controller;
+pub mod delivery;
+pub mod domain;
+pub mod repository;
+pub mod service;
+pub mod validation;
+
+// Public exports
+pub use delivery::{DELIVERY_JOB, WEBHOOK_QUEUE, WebhookDeliveryHandler, forward_events, sign};
+pub use domain::{CreateWebhook, CreatedWebhook, IssuedWebhookSecret, UpdateWebhook, Webhook, WebhookDelivery, WebhookError};
+pub use service::WebhookService;
+
+// Export controller for OpenAPI documentation
+pub use controller::*;
@@ -0,0 +1,219 @@
+//! Webhook repository - handles database operations
+//!
+//! This module is private to the webhook module. All database access must go
+//! through `WebhookService`.
+
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use tracing::error;
+
+use crate::db::DbError;
+
+use super::domain::{Webhook, WebhookDelivery, WebhookError};
+
+/// Logs a failed database operation and wraps it in a [`WebhookError`]
+fn database_error(operation: &'static str) -> impl Fn(sqlx::Error) -> WebhookError {
+    move |e| {
+        error!(error = %e, operation, "Webhook database operation failed");
+        WebhookError::DatabaseError(DbError::from(e))
+    }
+}
+
+/// Outcome of one delivery attempt, as recorded
+pub(super) struct DeliveryAttempt<'a> {
+    /// Webhook the event was delivered to
+    pub webhook_id: i32,
+    /// Identifier shared by the attempts of one event
+    pub delivery_id: &'a str,
+    /// Type of the delivered event
+    pub event_type: &'a str,
+    /// HTTP status of the response, if one came
+    pub response_status: Option<i32>,
+    /// Why the attempt failed, if it did
+    pub error: Option<&'a str>,
+    /// Milliseconds the attempt took
+    pub duration_ms: i32,
+    /// When the attempt was made
+    pub attempted_at: DateTime<Utc>,
+}
+
+/// Webhook repository for database operations
+#[derive(Clone)]
+pub(super) struct WebhookRepository {
+    pool: PgPool,
+}
+
+impl WebhookRepository {
+    /// Creates a new `WebhookRepository` instance
+    pub(super) fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Stores a webhook with its first secret's digest
+    pub(super) async fn create(
+        &self,
+        url: &str,
+        event_types: &[String],
+        key_digest: &[u8],
+        created_at: DateTime<Utc>,
+    ) -> Result<Webhook, WebhookError> {
+        let map_err = database_error("create webhook");
+        let mut tx = self.pool.begin().await.map_err(&map_err)?;
+
+        let webhook = sqlx::query_as!(
+            Webhook,
+            "INSERT INTO webhooks (url, event_types, created_at, updated_at) VALUES ($1, $2, $3, $3)
+             RETURNING id, url, event_types, created_at, updated_at",
+            url,
+            event_types,
+            created_at
+        )
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(&map_err)?;
+
+        sqlx::query!(
+            "INSERT INTO webhook_secrets (webhook_id, key_digest, created_at) VALUES ($1, $2, $3)",
+            webhook.id,
+            key_digest,
+            created_at
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(&map_err)?;
+
+        tx.commit().await.map_err(&map_err)?;
+        Ok(webhook)
+    }
+
+    /// Webhook with ID `id`, if any
+    pub(super) async fn find(&self, id: i32) -> Result<Option<Webhook>, WebhookError> {
+        sqlx::query_as!(Webhook, "SELECT id, url, event_types, created_at, updated_at FROM webhooks WHERE id = $1", id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(database_error("find webhook"))
+    }
+
+    /// Replaces the given fields of webhook `id`; `None` when it does not exist
+    pub(super) async fn update(
+        &self,
+        id: i32,
+        url: Option<&str>,
+        event_types: Option<&[String]>,
+        updated_at: DateTime<Utc>,
+    ) -> Result<Option<Webhook>, WebhookError> {
+        sqlx::query_as!(
+            Webhook,
+            "UPDATE webhooks SET url = COALESCE($2, url), event_types = COALESCE($3, event_types), updated_at = $4
+             WHERE id = $1
+             RETURNING id, url, event_types, created_at, updated_at",
+            id,
+            url,
+            event_types,
+            updated_at
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(database_error("update webhook"))
+    }
+
+    /// IDs of the webhooks receiving events of `event_type`
+    pub(super) async fn subscribers(&self, event_type: &str) -> Result<Vec<i32>, WebhookError> {
+        sqlx::query_scalar!(
+            "SELECT id FROM webhooks WHERE cardinality(event_types) = 0 OR $1 = ANY(event_types) ORDER BY id",
+            event_type
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(database_error("find webhook subscribers"))
+    }
+
+    /// Stores a new secret digest for webhook `id` and makes its other live
+    /// secrets expire by `previous_expire_at`
+    ///
+    /// Returns the latest expiry given to a previous secret, if it had any.
+    pub(super) async fn rotate(
+        &self,
+        id: i32,
+        key_digest: &[u8],
+        created_at: DateTime<Utc>,
+        previous_expire_at: DateTime<Utc>,
+    ) -> Result<Option<DateTime<Utc>>, WebhookError> {
+        let map_err = database_error("rotate webhook secret");
+        let mut tx = self.pool.begin().await.map_err(&map_err)?;
+
+        let previous = sqlx::query_scalar!(
+            r#"UPDATE webhook_secrets SET expires_at = LEAST(COALESCE(expires_at, $3), $3)
+             WHERE webhook_id = $1 AND (expires_at IS NULL OR expires_at > $2)
+             RETURNING expires_at AS "expires_at!""#,
+            id,
+            created_at,
+            previous_expire_at
+        )
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(&map_err)?;
+
+        sqlx::query!(
+            "INSERT INTO webhook_secrets (webhook_id, key_digest, created_at) VALUES ($1, $2, $3)",
+            id,
+            key_digest,
+            created_at
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(&map_err)?;
+
+        tx.commit().await.map_err(&map_err)?;
+        Ok(previous.into_iter().max())
+    }
+
+    /// Digests of the secrets of webhook `id` that are live at `at`, newest first
+    pub(super) async fn live_key_digests(&self, id: i32, at: DateTime<Utc>) -> Result<Vec<Vec<u8>>, WebhookError> {
+        sqlx::query_scalar!(
+            "SELECT key_digest FROM webhook_secrets
+             WHERE webhook_id = $1 AND (expires_at IS NULL OR expires_at > $2)
+             ORDER BY created_at DESC, id DESC",
+            id,
+            at
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(database_error("fetch webhook secrets"))
+    }
+
+    /// Records a delivery attempt, numbering it after the earlier attempts of its delivery
+    pub(super) async fn record_attempt(&self, attempt: &DeliveryAttempt<'_>) -> Result<i32, WebhookError> {
+        sqlx::query_scalar!(
+            r#"INSERT INTO webhook_deliveries
+                 (webhook_id, delivery_id, event_type, attempt, response_status, error, duration_ms, attempted_at)
+             SELECT $1, $2, $3, COUNT(*)::INT + 1, $4, $5, $6, $7 FROM webhook_deliveries WHERE delivery_id = $2
+             RETURNING attempt"#,
+            attempt.webhook_id,
+            attempt.delivery_id,
+            attempt.event_type,
+            attempt.response_status,
+            attempt.error,
+            attempt.duration_ms,
+            attempt.attempted_at
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(database_error("record webhook delivery"))
+    }
+
+    /// Latest `limit` delivery attempts of webhook `id`, newest first
+    pub(super) async fn deliveries(&self, id: i32, limit: i64) -> Result<Vec<WebhookDelivery>, WebhookError> {
+        sqlx::query_as!(
+            WebhookDelivery,
+            "SELECT id, delivery_id, event_type, attempt, response_status, error, duration_ms, attempted_at
+             FROM webhook_deliveries WHERE webhook_id = $1
+             ORDER BY id DESC LIMIT $2",
+            id,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(database_error("list webhook deliveries"))
+    }
+}
@@ -0,0 +1,272 @@
+//! Webhook service - business logic layer
+//!
+//! Manages subscriptions and their secrets, queues a delivery per subscriber
+//! when an event is published, and makes the delivery attempts.
+
+use std::time::Duration;
+
+use base64::{Engine as _, engine::general_purpose};
+use chrono::{DateTime, Utc};
+use reqwest::header::CONTENT_TYPE;
+use serde_json::json;
+use sqlx::PgPool;
+use tokio::time::Instant;
+use tracing::{info, warn};
+
+use crate::clock::{SharedClock, SystemClock};
+use crate::events::DomainEvent;
+use crate::ids::{DefaultIdGenerator, SharedIdGenerator};
+use crate::jobs::{JobError, JobQueue, NewJob};
+use crate::partner::signing::signing_key;
+
+use super::delivery::{
+    DELIVERY_JOB, DeliveryJob, EVENT_HEADER, ID_HEADER, SIGNATURE_HEADER, TIMESTAMP_HEADER, WEBHOOK_QUEUE, signature,
+};
+use super::domain::{CreateWebhook, CreatedWebhook, IssuedWebhookSecret, UpdateWebhook, Webhook, WebhookDelivery, WebhookError};
+use super::repository::{DeliveryAttempt, WebhookRepository};
+use super::validation::{validate_event_types, validate_url};
+
+/// How long previous secrets keep signing deliveries after a rotation by default
+const DEFAULT_ROTATION_GRACE: Duration = Duration::from_hours(24);
+
+/// How long a receiver may take to answer by default
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Delivery attempts returned by a listing when no limit is given
+const DEFAULT_LIST_LIMIT: i64 = 50;
+
+/// Most delivery attempts a listing returns
+const MAX_LIST_LIMIT: i64 = 200;
+
+/// Webhook service that handles subscriptions and deliveries
+#[derive(Clone)]
+pub struct WebhookService {
+    repository: WebhookRepository,
+    queue: JobQueue,
+    client: reqwest::Client,
+    clock: SharedClock,
+    ids: SharedIdGenerator,
+    rotation_grace: Duration,
+    timeout: Duration,
+}
+
+impl WebhookService {
+    /// Creates a new `WebhookService` queueing deliveries on `queue`
+    #[must_use] pub fn new(pool: PgPool, queue: JobQueue) -> Self {
+        Self {
+            repository: WebhookRepository::new(pool),
+            queue,
+            client: reqwest::Client::new(),
+            clock: SystemClock::shared(),
+            ids: DefaultIdGenerator::shared(),
+            rotation_grace: DEFAULT_ROTATION_GRACE,
+            timeout: DEFAULT_TIMEOUT,
+        }
+    }
+
+    /// Uses `clock` instead of the system clock for timestamps
+    #[must_use] pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Uses `ids` for delivery identifiers
+    #[must_use] pub fn with_id_generator(mut self, ids: SharedIdGenerator) -> Self {
+        self.ids = ids;
+        self
+    }
+
+    /// Keeps previous secrets signing deliveries for `grace` after a rotation
+    #[must_use] pub const fn with_rotation_grace(mut self, grace: Duration) -> Self {
+        self.rotation_grace = grace;
+        self
+    }
+
+    /// Fails a delivery attempt when the receiver takes longer than `timeout` to answer
+    #[must_use] pub const fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Creates a webhook and issues its first secret
+    pub async fn create(&self, request: CreateWebhook) -> Result<CreatedWebhook, WebhookError> {
+        info!(target: "audit", url = request.url, event_types = ?request.event_types, "WebhookService: Creating webhook");
+
+        let event_types = normalized(request.event_types);
+        validate(Some(&request.url), Some(&event_types))?;
+        let secret = new_secret();
+        let webhook = self
+            .repository
+            .create(&request.url, &event_types, &signing_key(&secret), self.clock.now())
+            .await?;
+
+        Ok(CreatedWebhook { webhook, secret })
+    }
+
+    /// Webhook with ID `id`
+    pub async fn get(&self, id: i32) -> Result<Webhook, WebhookError> {
+        self.repository.find(id).await?.ok_or(WebhookError::NotFound(id))
+    }
+
+    /// Changes the URL or event types of webhook `id`
+    ///
+    /// Deliveries already queued for event types no longer subscribed to are dropped.
+    pub async fn update(&self, id: i32, request: UpdateWebhook) -> Result<Webhook, WebhookError> {
+        info!(target: "audit", webhook_id = id, ?request, "WebhookService: Updating webhook");
+
+        let event_types = request.event_types.map(normalized);
+        validate(request.url.as_deref(), event_types.as_deref())?;
+        self.repository
+            .update(id, request.url.as_deref(), event_types.as_deref(), self.clock.now())
+            .await?
+            .ok_or(WebhookError::NotFound(id))
+    }
+
+    /// Issues a new signing secret for webhook `id`
+    ///
+    /// Deliveries keep carrying signatures of the previous secrets for the
+    /// rotation grace period, so receivers can switch to the new secret
+    /// without rejecting deliveries.
+    pub async fn rotate_secret(&self, id: i32) -> Result<IssuedWebhookSecret, WebhookError> {
+        info!(target: "audit", webhook_id = id, "WebhookService: Rotating webhook secret");
+
+        self.get(id).await?;
+        let secret = new_secret();
+        let created_at = self.clock.now();
+        let grace = chrono::Duration::from_std(self.rotation_grace).unwrap_or(chrono::Duration::MAX);
+        let previous_secrets_expire_at = self
+            .repository
+            .rotate(id, &signing_key(&secret), created_at, created_at.checked_add_signed(grace).unwrap_or(DateTime::<Utc>::MAX_UTC))
+            .await?;
+
+        Ok(IssuedWebhookSecret {
+            webhook_id: id,
+            secret,
+            created_at,
+            previous_secrets_expire_at,
+        })
+    }
+
+    /// Latest delivery attempts of webhook `id`, newest first
+    pub async fn deliveries(&self, id: i32, limit: Option<i64>) -> Result<Vec<WebhookDelivery>, WebhookError> {
+        self.get(id).await?;
+        self.repository
+            .deliveries(id, limit.unwrap_or(DEFAULT_LIST_LIMIT).clamp(1, MAX_LIST_LIMIT))
+            .await
+    }
+
+    /// Queues a delivery of `event` to every webhook receiving its type
+    ///
+    /// Returns the number of deliveries queued.
+    pub async fn dispatch(&self, event: &DomainEvent) -> Result<usize, WebhookError> {
+        let event_type = event.event_type();
+        let subscribers = self.repository.subscribers(event_type).await?;
+        let event = serde_json::to_value(event).expect("Domain events serialize to JSON");
+        for webhook_id in &subscribers {
+            let job = DeliveryJob {
+                webhook_id: *webhook_id,
+                delivery_id: self.ids.next_uuid().to_string(),
+                event_type: event_type.to_owned(),
+                event: event.clone(),
+            };
+            self.queue
+                .push(NewJob::new(DELIVERY_JOB, json!(job)).on_queue(WEBHOOK_QUEUE))
+                .await
+                .map_err(WebhookError::QueueError)?;
+        }
+        Ok(subscribers.len())
+    }
+
+    /// Makes one attempt at a queued delivery and records its outcome
+    ///
+    /// A delivery whose webhook was deleted or no longer receives the event
+    /// type is dropped. Fails unless the receiver answers with a 2xx status,
+    /// so the job queue retries the delivery.
+    pub(super) async fn deliver(&self, job: &DeliveryJob) -> Result<(), JobError> {
+        let Some(webhook) = self.repository.find(job.webhook_id).await? else {
+            info!(webhook_id = job.webhook_id, delivery_id = job.delivery_id, "Webhook deleted; dropping its delivery");
+            return Ok(());
+        };
+        if !webhook.receives(&job.event_type) {
+            info!(webhook_id = webhook.id, event_type = job.event_type, "Webhook unsubscribed from the event type; dropping its delivery");
+            return Ok(());
+        }
+
+        let now = self.clock.now();
+        let body = serde_json::to_vec(&job.event)?;
+        let signatures: Vec<String> = self
+            .repository
+            .live_key_digests(webhook.id, now)
+            .await?
+            .iter()
+            .map(|key| signature(key, now.timestamp(), &body))
+            .collect();
+
+        let started = Instant::now();
+        let response = self
+            .client
+            .post(&webhook.url)
+            .timeout(self.timeout)
+            .header(CONTENT_TYPE, "application/json")
+            .header(ID_HEADER, &job.delivery_id)
+            .header(EVENT_HEADER, &job.event_type)
+            .header(TIMESTAMP_HEADER, now.timestamp())
+            .header(SIGNATURE_HEADER, signatures.join(","))
+            .body(body)
+            .send()
+            .await;
+        let duration_ms = i32::try_from(started.elapsed().as_millis()).unwrap_or(i32::MAX);
+
+        let (response_status, error) = match response {
+            Ok(response) if response.status().is_success() => (Some(i32::from(response.status().as_u16())), None),
+            Ok(response) => (
+                Some(i32::from(response.status().as_u16())),
+                Some(format!("Receiver answered {}", response.status())),
+            ),
+            Err(e) => (None, Some(e.without_url().to_string())),
+        };
+        let attempt = self
+            .repository
+            .record_attempt(&DeliveryAttempt {
+                webhook_id: webhook.id,
+                delivery_id: &job.delivery_id,
+                event_type: &job.event_type,
+                response_status,
+                error: error.as_deref(),
+                duration_ms,
+                attempted_at: now,
+            })
+            .await?;
+
+        if let Some(error) = error {
+            warn!(webhook_id = webhook.id, delivery_id = job.delivery_id, attempt, response_status, error, "Webhook delivery failed");
+            return Err(error.into());
+        }
+        info!(webhook_id = webhook.id, delivery_id = job.delivery_id, attempt, response_status, "Webhook delivered");
+        Ok(())
+    }
+}
+
+/// Event types without duplicates, in a stable order
+fn normalized(mut event_types: Vec<String>) -> Vec<String> {
+    event_types.sort();
+    event_types.dedup();
+    event_types
+}
+
+/// Validates the given URL and event types
+fn validate(url: Option<&str>, event_types: Option<&[String]>) -> Result<(), WebhookError> {
+    let errors: Vec<_> = [url.map(validate_url), event_types.map(validate_event_types)]
+        .into_iter()
+        .flatten()
+        .filter_map(Result::err)
+        .flatten()
+        .collect();
+    if errors.is_empty() { Ok(()) } else { Err(WebhookError::ValidationError(errors)) }
+}
+
+/// A random secret, base64url encoded
+fn new_secret() -> String {
+    general_purpose::URL_SAFE_NO_PAD.encode(rand::random::<[u8; 32]>())
+}
+
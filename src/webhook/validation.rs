@@ -0,0 +1,65 @@
+//! Webhook validation logic
+
+use crate::error_codes::ErrorCode;
+use crate::events::DomainEvent;
+use crate::user::validation::common::{ValidationResult, field_error};
+
+/// Maximum length of a webhook URL
+pub const MAX_URL_LENGTH: usize = 2048;
+
+/// Validates a delivery URL: an absolute `http` or `https` URL with a host
+pub fn validate_url(url: &str) -> ValidationResult {
+    if url.len() > MAX_URL_LENGTH {
+        return Err(vec![field_error("url", ErrorCode::TooLong, format!("URL cannot exceed {MAX_URL_LENGTH} characters"))]);
+    }
+    match reqwest::Url::parse(url) {
+        Ok(parsed) if matches!(parsed.scheme(), "http" | "https") && parsed.has_host() => Ok(()),
+        _ => Err(vec![field_error("url", ErrorCode::InvalidUrl, "URL must be an absolute http or https URL")]),
+    }
+}
+
+/// Validates that every event type is one the application emits
+pub fn validate_event_types(event_types: &[String]) -> ValidationResult {
+    let errors: Vec<_> = event_types
+        .iter()
+        .filter(|event_type| !DomainEvent::TYPES.contains(&event_type.as_str()))
+        .map(|event_type| {
+            field_error(
+                "event_types",
+                ErrorCode::UnknownEventType,
+                format!("Unknown event type `{event_type}`; expected one of {}", DomainEvent::TYPES.join(", ")),
+            )
+        })
+        .collect();
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_urls_must_be_absolute_http() {
+        assert!(validate_url("https://hooks.example.com/kickstart").is_ok());
+        assert!(validate_url("http://localhost:8080/hook").is_ok());
+        assert!(validate_url("ftp://example.com/hook").is_err());
+        assert!(validate_url("/relative").is_err());
+        assert!(validate_url(&format!("https://example.com/{}", "a".repeat(MAX_URL_LENGTH))).is_err());
+    }
+
+    #[test]
+    fn test_unknown_event_types_are_rejected() {
+        assert!(validate_event_types(&["user_status_changed".to_owned()]).is_ok());
+        assert!(validate_event_types(&[]).is_ok());
+
+        let errors = validate_event_types(&["user_status_changed".to_owned(), "user_deleted".to_owned()]).unwrap_err();
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].code, ErrorCode::UnknownEventType);
+    }
+}
@@ -1,12 +1,37 @@
-use rust_kickstart::create_app_with_pool;
+use rust_kickstart::config::DatabaseConfig;
+use rust_kickstart::{AppConfig, AuthConfig, create_app_with_config, create_app_with_pool};
+use rust_kickstart::db::Migrations;
 use sqlx::PgPool;
 use sqlx::postgres::PgPoolOptions;
+use testcontainers_modules::postgres::Postgres;
+use testcontainers_modules::testcontainers::{Container, ImageExt, runners::SyncRunner};
 use tracing::info;
 use uuid::Uuid;
-use std::sync::Once;
+use std::sync::{Once, OnceLock};
 
 static INIT: Once = Once::new();
 
+/// Bearer token of the admin principal accepted by [`TestContext::admin_app`]
+#[allow(dead_code)]
+pub const ADMIN_TOKEN: &str = "test-admin-token";
+
+/// Bearer token of a principal without roles accepted by [`TestContext::admin_app`]
+#[allow(dead_code)]
+pub const USER_TOKEN: &str = "test-user-token";
+
+/// Postgres container shared by all tests of a test binary, started on first use.
+/// It is never dropped; the testcontainers reaper removes it when the process exits.
+static POSTGRES: OnceLock<Container<Postgres>> = OnceLock::new();
+
+/// Initializes the tracing subscriber once per test binary
+fn init_tracing() {
+    INIT.call_once(|| {
+        tracing_subscriber::fmt()
+            .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+            .init();
+    });
+}
+
 #[allow(dead_code)]
 pub struct TestContext {
     pub app: axum::Router,
@@ -16,17 +41,41 @@ pub struct TestContext {
 }
 
 impl TestContext {
+    /// Creates a context on the database at `DATABASE_URL`, falling back to
+    /// [`TestContext::with_container`] when it is not set
     pub async fn new() -> Self {
-        // Initialize tracing subscriber once
-        INIT.call_once(|| {
-            tracing_subscriber::fmt()
-                .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
-                .init();
-        });
-
+        init_tracing();
         dotenvy::dotenv().ok();
-        let database_url = std::env::var("DATABASE_URL").expect("DATABASE_URL must be set");
+        match std::env::var("DATABASE_URL") {
+            Ok(database_url) => Self::with_database_url(&database_url).await,
+            Err(_) => Self::with_container().await,
+        }
+    }
 
+    /// Creates a context on a throwaway Postgres container (requires Docker)
+    pub async fn with_container() -> Self {
+        init_tracing();
+        let database_url = tokio::task::spawn_blocking(|| {
+            let container = POSTGRES.get_or_init(|| {
+                info!("[TEST_SETUP] Starting Postgres container");
+                Postgres::default()
+                    .with_tag("16-alpine")
+                    .start()
+                    .expect("Failed to start Postgres container (is Docker running?)")
+            });
+            format!(
+                "postgres://postgres:postgres@{}:{}/postgres",
+                container.get_host().expect("Failed to get container host"),
+                container.get_host_port_ipv4(5432).expect("Failed to get container port"),
+            )
+        })
+        .await
+        .expect("Failed to start Postgres container");
+
+        Self::with_database_url(&database_url).await
+    }
+
+    async fn with_database_url(database_url: &str) -> Self {
         // Generate unique schema name using UUID v7
         let schema_name = format!("test_{}", Uuid::now_v7().simple());
 
@@ -35,7 +84,7 @@ impl TestContext {
         // Connect to database
         let admin_pool = PgPoolOptions::new()
             .max_connections(1)
-            .connect(&database_url)
+            .connect(database_url)
             .await
             .expect("Failed to connect to database");
 
@@ -61,7 +110,7 @@ impl TestContext {
                     Ok(())
                 })
             })
-            .connect(&database_url)
+            .connect(database_url)
             .await
             .expect("Failed to create test pool");
 
@@ -89,7 +138,7 @@ impl TestContext {
 
         // Run migrations from the ./migrations folder - single source of truth
         // This ensures tests use the same schema definition as production
-        sqlx::migrate!("./migrations")
+        Migrations::builtin()
             .run(pool)
             .await
             .expect("Failed to run migrations in test schema");
@@ -97,13 +146,42 @@ impl TestContext {
         info!("[TEST_SETUP] ✅ Migrations completed for schema: {}", schema_name);
     }
 
+    /// Router accepting [`ADMIN_TOKEN`] as an admin's credentials, for routes restricted to admins,
+    /// and [`USER_TOKEN`] as the credentials of a caller without roles
+    #[allow(dead_code)]
+    pub fn admin_app(&self) -> axum::Router {
+        self.admin_app_with(|_| {})
+    }
+
+    /// [`Self::admin_app`] with its configuration adjusted by `configure`
+    #[allow(dead_code)]
+    pub fn admin_app_with(&self, configure: impl FnOnce(&mut AppConfig)) -> axum::Router {
+        let database = DatabaseConfig {
+            url: String::new(),
+            max_connections: 5,
+            min_connections: 0,
+            acquire_timeout_secs: 30,
+            idle_timeout_secs: 600,
+            max_lifetime_secs: 1800,
+            slow_acquire_ms: 1000,
+            run_migrations: false,
+            monitor_interval_secs: 0,
+        };
+        let mut config = AppConfig {
+            auth: AuthConfig { api_tokens: Some(format!("{ADMIN_TOKEN}=test-admin:admin; {USER_TOKEN}=test-user")) },
+            ..AppConfig::load_with_database(database)
+        };
+        configure(&mut config);
+        create_app_with_config(self.test_pool.clone(), &config)
+    }
+
     /// Returns a reference to the test database pool for service creation
     #[allow(dead_code)]
     pub fn get_test_pool(&self) -> &PgPool {
         &self.test_pool
     }
 
-    #[allow(clippy::print_stderr)]
+    #[allow(dead_code, clippy::print_stderr)]
     pub async fn cleanup(&self) {
         info!("[TEST_CLEANUP] Starting cleanup for test schema: {}", self.schema_name);
 
@@ -0,0 +1,238 @@
+//! Property-based fuzz tests
+//!
+//! Feeds arbitrary input through request deserialization, validation,
+//! pagination tokens and the router, asserting that nothing panics and that
+//! failures are always classified the same way.
+//!
+//! The router tests only hit routes that never reach the database, so they run
+//! against a lazy pool pointing nowhere and need no `DATABASE_URL`.
+
+use std::{sync::LazyLock, time::Duration};
+
+use axum::{
+    Router,
+    body::Body,
+    http::{HeaderName, HeaderValue, Method, Request},
+};
+use chrono::{DateTime, Utc};
+use proptest::prelude::*;
+use rust_kickstart::{
+    CreateUser, UpdateUser, create_app_with_pool,
+    pagination::{PaginationToken, TokenError},
+    user::validation::{validate_create_user, validate_update_user},
+};
+use sqlx::postgres::PgPoolOptions;
+use tokio::runtime::Runtime;
+use tower::ServiceExt;
+
+/// Runtime and router shared by all router properties
+static ROUTER: LazyLock<(Runtime, Router)> = LazyLock::new(|| {
+    let runtime = Runtime::new().expect("Failed to build runtime");
+    let app = runtime.block_on(async {
+        let pool = PgPoolOptions::new()
+            .acquire_timeout(Duration::from_secs(1))
+            .connect_lazy("postgres://fuzz@127.0.0.1:1/fuzz")
+            .expect("Failed to build lazy pool");
+        create_app_with_pool(pool)
+    });
+    (runtime, app)
+});
+
+/// Whether `name` passes the name rules
+fn name_is_valid(name: &str) -> bool {
+    !name.trim().is_empty() && name.len() <= 100 && !name.chars().any(char::is_numeric)
+}
+
+/// Whether `age` passes the age rules
+fn age_is_valid(age: i32) -> bool {
+    (1..=150).contains(&age)
+}
+
+/// Names mixing plausible and hostile input
+fn name_strategy() -> impl Strategy<Value = String> {
+    prop_oneof![
+        "[A-Za-z ]{0,30}",
+        "\\PC{0,120}",
+        ".{0,10}",
+        Just(String::new()),
+        Just("a".repeat(101)),
+    ]
+}
+
+/// Ages clustered around the valid range, plus the extremes
+fn age_strategy() -> impl Strategy<Value = i32> {
+    prop_oneof![-5..160, any::<i32>()]
+}
+
+/// Header names the app reacts to, plus arbitrary ones
+fn header_name_strategy() -> impl Strategy<Value = HeaderName> {
+    prop_oneof![
+        Just(HeaderName::from_static("accept")),
+        Just(HeaderName::from_static("content-type")),
+        Just(HeaderName::from_static("authorization")),
+        Just(HeaderName::from_static("x-request-id")),
+        "[a-z][a-z0-9-]{0,24}".prop_map(|name| HeaderName::from_bytes(name.as_bytes()).expect("Valid header name")),
+    ]
+}
+
+/// Arbitrary header values, including invalid UTF-8
+fn header_value_strategy() -> impl Strategy<Value = HeaderValue> {
+    prop_oneof![
+        "[ -~]{0,64}".prop_map(|value| HeaderValue::from_str(&value).expect("Valid header value")),
+        proptest::collection::vec(0x80u8..=0xff, 0..16)
+            .prop_map(|bytes| HeaderValue::from_bytes(&bytes).expect("Valid opaque header value")),
+    ]
+}
+
+/// Sends `request` through the shared router and returns the status code
+fn send(request: Request<Body>) -> u16 {
+    let (runtime, app) = &*ROUTER;
+    runtime
+        .block_on(app.clone().oneshot(request))
+        .expect("Router should not fail")
+        .status()
+        .as_u16()
+}
+
+proptest! {
+    #[test]
+    fn create_user_round_trips_through_json(name in name_strategy(), age in any::<i32>()) {
+        let json = serde_json::json!({ "name": name, "age": age });
+        let user: CreateUser = serde_json::from_value(json).expect("Well-typed JSON should deserialize");
+
+        prop_assert_eq!(user.name, name);
+        prop_assert_eq!(user.age, age);
+    }
+
+    #[test]
+    fn update_user_round_trips_through_json(name in proptest::option::of(name_strategy()), age in proptest::option::of(any::<i32>())) {
+        let json = serde_json::json!({ "name": name, "age": age });
+        let user: UpdateUser = serde_json::from_value(json).expect("Well-typed JSON should deserialize");
+
+        prop_assert_eq!(user.name, name);
+        prop_assert_eq!(user.age, age);
+    }
+
+    #[test]
+    fn arbitrary_bodies_decode_or_fail_cleanly(bytes in proptest::collection::vec(any::<u8>(), 0..256)) {
+        let creates = [
+            serde_json::from_slice::<CreateUser>(&bytes).ok(),
+            rmp_serde::from_slice::<CreateUser>(&bytes).ok(),
+            ciborium::from_reader::<CreateUser, _>(&bytes[..]).ok(),
+        ];
+        for user in creates.into_iter().flatten() {
+            prop_assert_eq!(validate_create_user(&user).is_ok(), name_is_valid(&user.name) && age_is_valid(user.age));
+        }
+
+        let updates = [
+            serde_json::from_slice::<UpdateUser>(&bytes).ok(),
+            rmp_serde::from_slice::<UpdateUser>(&bytes).ok(),
+            ciborium::from_reader::<UpdateUser, _>(&bytes[..]).ok(),
+        ];
+        for user in updates.into_iter().flatten() {
+            let valid = (user.name.is_some() || user.age.is_some())
+                && user.name.as_deref().is_none_or(name_is_valid)
+                && user.age.is_none_or(age_is_valid);
+            prop_assert_eq!(validate_update_user(&user).is_ok(), valid);
+        }
+    }
+
+    #[test]
+    fn create_user_validation_is_consistent(name in name_strategy(), age in age_strategy()) {
+        let user = CreateUser { name: name.clone(), age, metadata: None };
+
+        match validate_create_user(&user) {
+            Ok(_) => prop_assert!(name_is_valid(&name) && age_is_valid(age)),
+            Err(errors) => {
+                prop_assert!(!errors.is_empty());
+                for error in &errors {
+                    prop_assert!(!error.message.is_empty());
+                    prop_assert!(matches!(error.field.as_deref(), Some("name" | "age")));
+                }
+                let has_name_error = errors.iter().any(|e| e.field.as_deref() == Some("name"));
+                let has_age_error = errors.iter().any(|e| e.field.as_deref() == Some("age"));
+                prop_assert_eq!(has_name_error, !name_is_valid(&name));
+                prop_assert_eq!(has_age_error, !age_is_valid(age));
+            }
+        }
+    }
+
+    #[test]
+    fn update_user_validation_is_consistent(name in proptest::option::of(name_strategy()), age in proptest::option::of(age_strategy())) {
+        let user = UpdateUser { name: name.clone(), age, metadata: None };
+        let result = validate_update_user(&user);
+
+        if name.is_none() && age.is_none() {
+            let errors = result.expect_err("Empty updates should be rejected");
+            prop_assert_eq!(errors.len(), 1);
+            prop_assert!(errors[0].field.is_none());
+        } else {
+            let errors = result.err().unwrap_or_default();
+            let has_name_error = errors.iter().any(|e| e.field.as_deref() == Some("name"));
+            let has_age_error = errors.iter().any(|e| e.field.as_deref() == Some("age"));
+            prop_assert!(errors.iter().all(|e| matches!(e.field.as_deref(), Some("name" | "age"))));
+            prop_assert_eq!(has_name_error, name.as_deref().is_some_and(|n| !name_is_valid(n)));
+            prop_assert_eq!(has_age_error, age.is_some_and(|a| !age_is_valid(a)));
+        }
+    }
+
+    #[test]
+    fn pagination_tokens_round_trip(id in any::<i32>(), seconds in 0i64..4_102_444_800, nanos in 0u32..1_000_000_000) {
+        let timestamp = DateTime::<Utc>::from_timestamp(seconds, nanos).expect("Timestamp in range");
+
+        let token = PaginationToken::encode(id, timestamp).expect("Encoding should succeed");
+        let decoded = PaginationToken::decode(&token).expect("Encoded tokens should decode");
+
+        prop_assert_eq!(decoded, (id, timestamp));
+        prop_assert!(token.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'));
+    }
+
+    #[test]
+    fn arbitrary_tokens_are_rejected_cleanly(token in prop_oneof!["\\PC{0,64}", "[A-Za-z0-9_-]{0,96}"]) {
+        if let Err(error) = PaginationToken::decode(&token) {
+            prop_assert!(matches!(error, TokenError::InvalidToken));
+            prop_assert!(!PaginationToken::is_valid(&token));
+        }
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(64))]
+
+    #[test]
+    fn arbitrary_headers_never_cause_server_errors(
+        headers in proptest::collection::vec((header_name_strategy(), header_value_strategy()), 0..8),
+        route in prop_oneof![Just("/"), Just("/live"), Just("/api-docs/schemas"), Just("/users/0")],
+    ) {
+        let mut request = Request::builder().uri(route);
+        for (name, value) in headers {
+            request = request.header(name, value);
+        }
+        let status = send(request.body(Body::empty()).expect("Failed to build request"));
+
+        prop_assert!(status < 500, "{route} answered {status}");
+    }
+
+    #[test]
+    fn arbitrary_bodies_are_rejected_by_the_router(
+        content_type in prop_oneof![
+            Just("application/json"),
+            Just("application/msgpack"),
+            Just("application/cbor"),
+            Just("text/plain"),
+        ],
+        method in prop_oneof![Just(Method::POST), Just(Method::PUT)],
+        bytes in proptest::collection::vec(any::<u8>(), 0..128),
+    ) {
+        let uri = if method == Method::POST { "/users" } else { "/users/1" };
+        let request = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("content-type", content_type)
+            .body(Body::from(bytes))
+            .expect("Failed to build request");
+        let status = send(request);
+
+        prop_assert!((400..500).contains(&status), "{uri} answered {status}");
+    }
+}
@@ -0,0 +1,721 @@
+//! Integration tests for bank accounts
+//!
+//! Verifies opening accounts, withdrawals and transfers and that they require
+//! the `admin` role, that compliance holds
+//! (freeze/unfreeze) are restricted to admins and block and release money
+//! movement, and that interest accrual credits savings accounts once per day
+//! with balanced ledger entries, that ledger verification reports stored balances drifting from the ledger,
+//! that new accounts get valid account numbers, that only admins manage
+//! beneficiaries, that transfers to other
+//! banks require a saved beneficiary, a partner signature and an account
+//! granted to the signing partner, that transfer limits hold over
+//! rolling windows and are reported in `RateLimit-*` headers, and that
+//! transfers are counted in the domain metrics.
+
+mod common;
+
+use axum::Router;
+use axum::body::Body;
+use axum::http::{Method, Request, StatusCode};
+use chrono::{Duration, TimeZone, Utc};
+use common::{ADMIN_TOKEN, TestContext, USER_TOKEN};
+use rust_kickstart::clock::MockClock;
+use rust_kickstart::testing::{UserBuilder, assert_validation_error, send, send_as};
+use rust_kickstart::bank::{BankError, LimitUsage, LimitWindow, TransferRequest};
+use rust_kickstart::metrics::{MetricsRegistry, TRANSFER_VOLUME, TRANSFERS_COMPLETED, VALIDATION_FAILURES};
+use rust_kickstart::partner::sign;
+use rust_kickstart::{AccountService, PartnerService, TransferLimits, UserService};
+use http_body_util::BodyExt;
+use serde_json::{Value, json};
+use tower::ServiceExt;
+
+/// Opens an account of `kind` with `balance_cents` for a new user, returning its ID
+async fn open_account_of_kind(ctx: &TestContext, kind: &str, balance_cents: i64) -> i64 {
+    let user_id = UserBuilder::new().insert(&ctx.test_pool).await.id;
+    let (status, account) = send_as(
+        &ctx.admin_app(),
+        ADMIN_TOKEN,
+        "POST",
+        "/accounts",
+        Some(json!({ "user_id": user_id, "kind": kind, "initial_balance_cents": balance_cents })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED, "Opening an account should succeed");
+    assert_eq!(account["kind"], kind);
+    account["id"].as_i64().expect("Account should have an ID")
+}
+
+/// Opens a checking account with `balance_cents` for a new user, returning its ID
+async fn open_account(ctx: &TestContext, balance_cents: i64) -> i64 {
+    open_account_of_kind(ctx, "checking", balance_cents).await
+}
+
+async fn balance(ctx: &TestContext, account_id: i64) -> i64 {
+    let (_, account) = send_as(&ctx.admin_app(), ADMIN_TOKEN, "GET", &format!("/accounts/{account_id}"), None).await;
+    account["balance_cents"].as_i64().expect("Account should have a balance")
+}
+
+async fn withdraw(ctx: &TestContext, account_id: i64, amount_cents: i64) -> (StatusCode, Value) {
+    send_as(
+        &ctx.admin_app(),
+        ADMIN_TOKEN,
+        "POST",
+        &format!("/accounts/{account_id}/withdraw"),
+        Some(json!({ "amount_cents": amount_cents })),
+    )
+    .await
+}
+
+async fn transfer(ctx: &TestContext, from: i64, to: i64, amount_cents: i64) -> (StatusCode, Value) {
+    send_as(
+        &ctx.admin_app(),
+        ADMIN_TOKEN,
+        "POST",
+        "/transfers",
+        Some(json!({ "from_account_id": from, "to_account_id": to, "amount_cents": amount_cents })),
+    )
+    .await
+}
+
+/// Issues a signing key to the `acme` partner, returning its secret
+async fn partner_secret(ctx: &TestContext) -> String {
+    let key = PartnerService::new(ctx.test_pool.clone()).rotate_key("acme").await;
+    key.expect("Key rotation should succeed").secret
+}
+
+/// Lets the `acme` partner pay out of `account_id`
+async fn grant_to_partner(ctx: &TestContext, account_id: i64) {
+    let uri = format!("/admin/partners/acme/accounts/{account_id}");
+    let (status, _) = send_as(&ctx.admin_app(), ADMIN_TOKEN, "PUT", &uri, None).await;
+    assert_eq!(status, StatusCode::OK, "Granting the account should succeed");
+}
+
+/// Pays an account at another bank as the `acme` partner, signing with `secret` when given
+async fn external_transfer(ctx: &TestContext, secret: Option<&str>, from: i64, to: &Value, amount_cents: i64) -> (StatusCode, Value) {
+    let uri = "/transfers/external";
+    let body = json!({ "from_account_id": from, "to_account_number": to, "amount_cents": amount_cents }).to_string();
+    let mut request = Request::builder().method("POST").uri(uri).header("content-type", "application/json");
+    if let Some(secret) = secret {
+        let timestamp = Utc::now().timestamp();
+        request = request
+            .header("x-partner-id", "acme")
+            .header("x-timestamp", timestamp.to_string())
+            .header("x-signature", sign(secret, timestamp, &Method::POST, uri, body.as_bytes()));
+    }
+    let request = request.body(Body::from(body)).expect("Failed to build request");
+    let response = ctx.app.clone().oneshot(request).await.expect("Failed to send request");
+    let status = response.status();
+    let body = response.into_body().collect().await.expect("Failed to read body").to_bytes();
+    (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+}
+
+/// Sends an admin request, returning its status and its `RateLimit-Limit`,
+/// `RateLimit-Remaining` and `RateLimit-Reset` headers, when present
+async fn send_rate_limited(app: &Router, method: &str, uri: &str, body: Option<Value>) -> (StatusCode, Vec<i64>) {
+    let request = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header("authorization", format!("Bearer {ADMIN_TOKEN}"))
+        .header("content-type", "application/json")
+        .body(body.map_or_else(Body::empty, |body| Body::from(body.to_string())))
+        .expect("Failed to build request");
+    let response = app.clone().oneshot(request).await.expect("Failed to send request");
+    let headers = ["ratelimit-limit", "ratelimit-remaining", "ratelimit-reset"]
+        .iter()
+        .filter_map(|name| response.headers().get(*name))
+        .map(|value| value.to_str().expect("Header should be text").parse().expect("Header should be a number"))
+        .collect();
+    (response.status(), headers)
+}
+
+#[tokio::test]
+async fn test_withdraw_and_transfer() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    let from = open_account(&ctx, 10_000).await;
+    let to = open_account(&ctx, 0).await;
+
+    // Act & Assert - Withdraw
+    let (status, account) = withdraw(&ctx, from, 2_500).await;
+    assert_eq!(status, StatusCode::OK, "Withdrawal should succeed");
+    assert_eq!(account["balance_cents"], 7_500);
+
+    // Act & Assert - Transfer
+    let (status, receipt) = transfer(&ctx, from, to, 5_000).await;
+    assert_eq!(status, StatusCode::OK, "Transfer should succeed");
+    assert_eq!(receipt["from"]["balance_cents"], 2_500);
+    assert_eq!(receipt["to"]["balance_cents"], 5_000);
+
+    // Act & Assert - Overdraw
+    let (status, _) = withdraw(&ctx, from, 2_501).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY, "Overdrawing should be rejected");
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_frozen_account_blocks_withdrawals_and_transfers() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    let frozen = open_account(&ctx, 10_000).await;
+    let other = open_account(&ctx, 10_000).await;
+
+    let admin = ctx.admin_app();
+
+    // Act & Assert - Freeze
+    let (status, account) = send_as(
+        &admin,
+        ADMIN_TOKEN,
+        "POST",
+        &format!("/accounts/{frozen}/freeze"),
+        Some(json!({ "reason": "Sanctions screening match" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "Freezing should succeed");
+    assert_eq!(account["frozen"], true);
+    assert_eq!(account["frozen_reason"], "Sanctions screening match");
+
+    // Act & Assert - Blocked in every direction
+    let (status, body) = withdraw(&ctx, frozen, 100).await;
+    assert_eq!(status, StatusCode::CONFLICT, "Withdrawals from frozen accounts should be blocked");
+    assert_eq!(body["message"], format!("Account {frozen} is frozen"));
+    let (status, _) = transfer(&ctx, frozen, other, 100).await;
+    assert_eq!(status, StatusCode::CONFLICT, "Transfers from frozen accounts should be blocked");
+    let (status, _) = transfer(&ctx, other, frozen, 100).await;
+    assert_eq!(status, StatusCode::CONFLICT, "Transfers to frozen accounts should be blocked");
+
+    let (_, account) = send_as(&ctx.admin_app(), ADMIN_TOKEN, "GET", &format!("/accounts/{frozen}"), None).await;
+    assert_eq!(account["balance_cents"], 10_000, "Blocked operations should not move money");
+    let (_, account) = send_as(&ctx.admin_app(), ADMIN_TOKEN, "GET", &format!("/accounts/{other}"), None).await;
+    assert_eq!(account["balance_cents"], 10_000, "A blocked transfer should not debit the sender");
+
+    // Act & Assert - Unfreeze
+    let (status, account) = send_as(&admin, ADMIN_TOKEN, "POST", &format!("/accounts/{frozen}/unfreeze"), None).await;
+    assert_eq!(status, StatusCode::OK, "Unfreezing should succeed");
+    assert_eq!(account["frozen"], false);
+    assert_eq!(account["frozen_reason"], Value::Null);
+    let (status, _) = withdraw(&ctx, frozen, 100).await;
+    assert_eq!(status, StatusCode::OK, "Withdrawals should resume once unfrozen");
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_freeze_validation_and_missing_account() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    let account_id = open_account(&ctx, 0).await;
+    let admin = ctx.admin_app();
+
+    // Act
+    let (blank_status, blank_body) =
+        send_as(&admin, ADMIN_TOKEN, "POST", &format!("/accounts/{account_id}/freeze"), Some(json!({ "reason": " " }))).await;
+    let (missing_status, _) =
+        send_as(&admin, ADMIN_TOKEN, "POST", "/accounts/999999/freeze", Some(json!({ "reason": "Fraud review" }))).await;
+
+    // Assert
+    assert_eq!(blank_status, StatusCode::BAD_REQUEST, "A hold needs a reason");
+    assert_validation_error(&blank_body, "reason");
+    assert_eq!(missing_status, StatusCode::NOT_FOUND, "Freezing a missing account should be 404");
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_compliance_holds_require_admin() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    let account_id = open_account(&ctx, 1_000).await;
+
+    // Act
+    let (freeze, _) =
+        send(&ctx.app, "POST", &format!("/accounts/{account_id}/freeze"), Some(json!({ "reason": "Fraud review" }))).await;
+    let (unfreeze, _) = send(&ctx.app, "POST", &format!("/accounts/{account_id}/unfreeze"), None).await;
+    let (_, account) = send_as(&ctx.admin_app(), ADMIN_TOKEN, "GET", &format!("/accounts/{account_id}"), None).await;
+
+    // Assert
+    assert_eq!(freeze, StatusCode::UNAUTHORIZED, "Placing a hold should require credentials");
+    assert_eq!(unfreeze, StatusCode::UNAUTHORIZED, "Lifting a hold should require credentials");
+    assert_eq!(account["frozen"], false, "A rejected hold should not freeze the account");
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_money_movement_requires_credentials() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    let user_id = UserBuilder::new().insert(&ctx.test_pool).await.id;
+    let from = open_account(&ctx, 10_000).await;
+    let to = open_account(&ctx, 0).await;
+
+    // Act
+    let (open, _) =
+        send(&ctx.app, "POST", "/accounts", Some(json!({ "user_id": user_id, "initial_balance_cents": 1_000_000 }))).await;
+    let (withdrawal, _) = send(&ctx.app, "POST", &format!("/accounts/{from}/withdraw"), Some(json!({ "amount_cents": 2_500 }))).await;
+    let (transfer, _) = send(
+        &ctx.app,
+        "POST",
+        "/transfers",
+        Some(json!({ "from_account_id": from, "to_account_id": to, "amount_cents": 2_500 })),
+    )
+    .await;
+    let (read, _) = send(&ctx.app, "GET", &format!("/accounts/{from}"), None).await;
+
+    // Assert
+    assert_eq!(open, StatusCode::UNAUTHORIZED, "Opening an account with a balance should require credentials");
+    assert_eq!(withdrawal, StatusCode::UNAUTHORIZED, "Withdrawing should require credentials");
+    assert_eq!(transfer, StatusCode::UNAUTHORIZED, "Transferring should require credentials");
+    assert_eq!(read, StatusCode::UNAUTHORIZED, "Reading an account should require credentials");
+    assert_eq!((balance(&ctx, from).await, balance(&ctx, to).await), (10_000, 0), "Rejected requests should not move money");
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_money_movement_requires_admin() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    let from = open_account(&ctx, 10_000).await;
+    let to = open_account(&ctx, 0).await;
+    let app = ctx.admin_app();
+
+    // Act
+    let (withdrawal, _) =
+        send_as(&app, USER_TOKEN, "POST", &format!("/accounts/{from}/withdraw"), Some(json!({ "amount_cents": 2_500 }))).await;
+    let (transfer, _) = send_as(
+        &app,
+        USER_TOKEN,
+        "POST",
+        "/transfers",
+        Some(json!({ "from_account_id": from, "to_account_id": to, "amount_cents": 2_500 })),
+    )
+    .await;
+
+    // Assert
+    assert_eq!(withdrawal, StatusCode::FORBIDDEN, "A token should not debit an account it does not own");
+    assert_eq!(transfer, StatusCode::FORBIDDEN, "A token should not transfer out of an account it does not own");
+    assert_eq!((balance(&ctx, from).await, balance(&ctx, to).await), (10_000, 0), "Rejected requests should not move money");
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_opened_accounts_get_valid_account_numbers() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    let first = open_account(&ctx, 0).await;
+    let second = open_account(&ctx, 0).await;
+
+    // Act
+    let (_, first) = send_as(&ctx.admin_app(), ADMIN_TOKEN, "GET", &format!("/accounts/{first}"), None).await;
+    let (_, second) = send_as(&ctx.admin_app(), ADMIN_TOKEN, "GET", &format!("/accounts/{second}"), None).await;
+    let (status, check) = send(
+        &ctx.app,
+        "POST",
+        "/accounts/validate-number",
+        Some(json!({ "account_number": first["account_number"] })),
+    )
+    .await;
+
+    // Assert
+    assert!(first["account_number"].is_string(), "New accounts should have a number: {first}");
+    assert_ne!(first["account_number"], second["account_number"], "Account numbers should be unique");
+    assert_eq!(status, StatusCode::OK, "Generated numbers should validate");
+    assert_eq!(check["format"], "luhn");
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_validate_account_number() {
+    // Arrange
+    let ctx = TestContext::new().await;
+
+    // Act
+    let (iban_status, iban) = send(
+        &ctx.app,
+        "POST",
+        "/accounts/validate-number",
+        Some(json!({ "account_number": "de89 3704 0044 0532 0130 00" })),
+    )
+    .await;
+    let (invalid_status, invalid) = send(
+        &ctx.app,
+        "POST",
+        "/accounts/validate-number",
+        Some(json!({ "account_number": "DE89370400440532013001" })),
+    )
+    .await;
+
+    // Assert
+    assert_eq!(iban_status, StatusCode::OK, "A valid IBAN should pass");
+    assert_eq!(iban["account_number"], "DE89370400440532013000");
+    assert_eq!(iban["format"], "iban");
+    assert_eq!(invalid_status, StatusCode::BAD_REQUEST, "A wrong check digit should fail");
+    assert_validation_error(&invalid, "account_number");
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_beneficiary_crud() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    let user_id = UserBuilder::new().insert(&ctx.test_pool).await.id;
+    let path = format!("/users/{user_id}/beneficiaries");
+    let payload = json!({ "name": "Erika Mustermann", "account_number": "DE89 3704 0044 0532 0130 00" });
+    let app = ctx.admin_app();
+
+    // Act
+    let (anonymous_status, _) = send(&ctx.app, "POST", &path, Some(payload.clone())).await;
+    let (created_status, created) = send_as(&app, ADMIN_TOKEN, "POST", &path, Some(payload.clone())).await;
+    let (duplicate_status, _) = send_as(&app, ADMIN_TOKEN, "POST", &path, Some(payload)).await;
+    let (invalid_status, invalid) =
+        send_as(&app, ADMIN_TOKEN, "POST", &path, Some(json!({ "name": " ", "account_number": "DE89370400440532013000" }))).await;
+    let (missing_user_status, _) = send_as(
+        &app,
+        ADMIN_TOKEN,
+        "POST",
+        "/users/999999/beneficiaries",
+        Some(json!({ "name": "Erika Mustermann", "account_number": "DE89370400440532013000" })),
+    )
+    .await;
+    let (_, listed) = send_as(&app, ADMIN_TOKEN, "GET", &path, None).await;
+    let beneficiary_path = format!("{path}/{}", created["id"]);
+    let (anonymous_delete_status, _) = send(&ctx.app, "DELETE", &beneficiary_path, None).await;
+    let (deleted_status, _) = send_as(&app, ADMIN_TOKEN, "DELETE", &beneficiary_path, None).await;
+    let (deleted_again_status, _) = send_as(&app, ADMIN_TOKEN, "DELETE", &beneficiary_path, None).await;
+    let (_, listed_after) = send_as(&app, ADMIN_TOKEN, "GET", &path, None).await;
+
+    // Assert
+    assert_eq!(anonymous_status, StatusCode::UNAUTHORIZED, "Saving a beneficiary should require credentials");
+    assert_eq!(anonymous_delete_status, StatusCode::UNAUTHORIZED, "Removing a beneficiary should require credentials");
+    assert_eq!(created_status, StatusCode::CREATED, "Saving a beneficiary should succeed");
+    assert_eq!(created["account_number"], "DE89370400440532013000");
+    assert_eq!(duplicate_status, StatusCode::CONFLICT, "An account number can be saved once per user");
+    assert_eq!(invalid_status, StatusCode::BAD_REQUEST, "A beneficiary needs a name");
+    assert_validation_error(&invalid, "name");
+    assert_eq!(missing_user_status, StatusCode::NOT_FOUND, "Beneficiaries need an existing user");
+    assert_eq!(listed.as_array().map(Vec::len), Some(1));
+    assert_eq!(deleted_status, StatusCode::OK, "Removing a beneficiary should succeed");
+    assert_eq!(deleted_again_status, StatusCode::NOT_FOUND, "A removed beneficiary is gone");
+    assert_eq!(listed_after, json!([]));
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_external_transfer_requires_beneficiary() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    let from = open_account(&ctx, 10_000).await;
+    let other = open_account(&ctx, 0).await;
+    let (_, account) = send_as(&ctx.admin_app(), ADMIN_TOKEN, "GET", &format!("/accounts/{from}"), None).await;
+    let (_, other) = send_as(&ctx.admin_app(), ADMIN_TOKEN, "GET", &format!("/accounts/{other}"), None).await;
+    let external = "DE89370400440532013000";
+    let secret = partner_secret(&ctx).await;
+    grant_to_partner(&ctx, from).await;
+
+    // Act
+    let (unsaved_status, unsaved) = external_transfer(&ctx, Some(&secret), from, &json!(external), 2_000).await;
+    send_as(
+        &ctx.admin_app(),
+        ADMIN_TOKEN,
+        "POST",
+        &format!("/users/{}/beneficiaries", account["user_id"]),
+        Some(json!({ "name": "Erika Mustermann", "account_number": external })),
+    )
+    .await;
+    let (paid_status, paid) = external_transfer(&ctx, Some(&secret), from, &json!(external), 2_500).await;
+    let (internal_status, internal) = external_transfer(&ctx, Some(&secret), from, &other["account_number"], 2_500).await;
+
+    // Assert
+    assert_eq!(unsaved_status, StatusCode::UNPROCESSABLE_ENTITY, "Unsaved account numbers cannot be paid");
+    assert!(unsaved["message"].as_str().is_some_and(|message| message.contains("beneficiary")));
+    assert_eq!(paid_status, StatusCode::OK, "Paying a saved beneficiary should succeed");
+    assert_eq!(paid["from"]["balance_cents"], 7_500);
+    assert_eq!(paid["beneficiary"]["name"], "Erika Mustermann");
+    assert_eq!(internal_status, StatusCode::BAD_REQUEST, "Internal accounts are paid with /transfers");
+    assert_validation_error(&internal, "to_account_number");
+    assert_eq!(balance(&ctx, from).await, 7_500);
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_external_transfer_requires_partner_signature() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    let from = open_account(&ctx, 10_000).await;
+    let (_, account) = send_as(&ctx.admin_app(), ADMIN_TOKEN, "GET", &format!("/accounts/{from}"), None).await;
+    let external = "DE89370400440532013000";
+    send_as(
+        &ctx.admin_app(),
+        ADMIN_TOKEN,
+        "POST",
+        &format!("/users/{}/beneficiaries", account["user_id"]),
+        Some(json!({ "name": "Erika Mustermann", "account_number": external })),
+    )
+    .await;
+    partner_secret(&ctx).await;
+    grant_to_partner(&ctx, from).await;
+
+    // Act
+    let (unsigned_status, unsigned) = external_transfer(&ctx, None, from, &json!(external), 2_500).await;
+    let (forged_status, forged) = external_transfer(&ctx, Some("not-the-secret"), from, &json!(external), 2_500).await;
+
+    // Assert
+    assert_eq!((unsigned_status, &unsigned["code"]), (StatusCode::UNAUTHORIZED, &json!("SIGNATURE_REQUIRED")));
+    assert_eq!((forged_status, &forged["code"]), (StatusCode::UNAUTHORIZED, &json!("SIGNATURE_MISMATCH")));
+    assert_eq!(balance(&ctx, from).await, 10_000, "Rejected transfers should not move money");
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_external_transfer_requires_granted_account() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    let granted = open_account(&ctx, 10_000).await;
+    let other = open_account(&ctx, 10_000).await;
+    let (_, account) = send_as(&ctx.admin_app(), ADMIN_TOKEN, "GET", &format!("/accounts/{other}"), None).await;
+    let external = "DE89370400440532013000";
+    send_as(
+        &ctx.admin_app(),
+        ADMIN_TOKEN,
+        "POST",
+        &format!("/users/{}/beneficiaries", account["user_id"]),
+        Some(json!({ "name": "Erika Mustermann", "account_number": external })),
+    )
+    .await;
+    let secret = partner_secret(&ctx).await;
+    grant_to_partner(&ctx, granted).await;
+
+    // Act
+    let (status, body) = external_transfer(&ctx, Some(&secret), other, &json!(external), 2_500).await;
+
+    // Assert
+    assert_eq!((status, &body["code"]), (StatusCode::FORBIDDEN, &json!("ACCOUNT_NOT_GRANTED")));
+    assert_eq!(balance(&ctx, other).await, 10_000, "A partner should not debit accounts it was not granted");
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_transfer_limits_over_rolling_windows() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    let from = i32::try_from(open_account(&ctx, 100_000).await).expect("Account ID should fit in i32");
+    let to = i32::try_from(open_account(&ctx, 0).await).expect("Account ID should fit in i32");
+    let clock = MockClock::new(Utc.with_ymd_and_hms(2025, 3, 1, 12, 0, 0).unwrap());
+    let accounts = AccountService::new(ctx.test_pool.clone(), UserService::new(ctx.test_pool.clone()))
+        .with_clock(clock.shared())
+        .with_transfer_limits(TransferLimits { daily_cents: Some(5_000), monthly_cents: Some(8_000) });
+    let send_money = |amount_cents| {
+        accounts.transfer(TransferRequest { from_account_id: from, to_account_id: to, amount_cents })
+    };
+
+    // Act & Assert - The daily limit caps transfers within 24 hours
+    send_money(4_000).await.expect("A transfer within the limits should succeed");
+    let daily = send_money(2_000).await;
+    assert!(
+        matches!(&daily, Err(BankError::LimitExceeded(LimitUsage { window: LimitWindow::Daily, remaining_cents: 1_000, .. }))),
+        "The daily limit should be reported with its remaining quota: {daily:?}"
+    );
+
+    // Act & Assert - The daily window rolls over, the monthly one does not
+    clock.advance(Duration::hours(25));
+    send_money(3_000).await.expect("The daily quota should be available again");
+    let monthly = send_money(2_000).await;
+    assert!(
+        matches!(
+            &monthly,
+            Err(BankError::LimitExceeded(LimitUsage { window: LimitWindow::Monthly, remaining_cents: 1_000, resets_in_secs: 2_502_000, .. }))
+        ),
+        "The monthly limit should be reported with its remaining quota and when the first transfer leaves the window: {monthly:?}"
+    );
+    let usage = accounts.transfer_limit_usage(from).await.expect("Usage should be available");
+    assert_eq!(
+        usage.limits.iter().map(|limit| (limit.window, limit.used_cents, limit.remaining_cents)).collect::<Vec<_>>(),
+        vec![(LimitWindow::Daily, 3_000, 2_000), (LimitWindow::Monthly, 7_000, 1_000)]
+    );
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_transfer_limits_are_reported_in_rate_limit_headers() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    let from = open_account(&ctx, 100_000).await;
+    let to = open_account(&ctx, 0).await;
+    let app = ctx.admin_app_with(|config| {
+        config.bank.daily_transfer_limit_cents = 5_000;
+        config.bank.monthly_transfer_limit_cents = 8_000;
+    });
+    let transfer = |amount_cents: i64| json!({ "from_account_id": from, "to_account_id": to, "amount_cents": amount_cents });
+
+    // Act
+    let (sent, sent_headers) = send_rate_limited(&app, "POST", "/transfers", Some(transfer(4_000))).await;
+    let (exceeded, exceeded_headers) = send_rate_limited(&app, "POST", "/transfers", Some(transfer(2_000))).await;
+    let (usage, usage_headers) = send_rate_limited(&app, "GET", &format!("/accounts/{from}/transfer-limits"), None).await;
+    let (unlimited, unlimited_headers) = send_rate_limited(&ctx.admin_app(), "GET", &format!("/accounts/{from}/transfer-limits"), None).await;
+
+    // Assert
+    assert_eq!(sent, StatusCode::OK);
+    assert_eq!(sent_headers, vec![5_000, 1_000, 86_400], "The daily limit has the least left once the transfer is made");
+    assert_eq!(exceeded, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(&exceeded_headers[..2], &[5_000, 1_000], "The exceeded limit should be reported");
+    assert_eq!(usage, StatusCode::OK);
+    assert_eq!(&usage_headers[..2], &[5_000, 1_000]);
+    assert!((86_000..=86_400).contains(&usage_headers[2]), "The quota frees up when the transfer leaves the daily window");
+    assert_eq!((unlimited, unlimited_headers), (StatusCode::OK, vec![]), "Without limits no quota is reported");
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_transfer_limits_endpoint() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    let account_id = open_account(&ctx, 0).await;
+
+    // Act
+    let (status, usage) = send_as(&ctx.admin_app(), ADMIN_TOKEN, "GET", &format!("/accounts/{account_id}/transfer-limits"), None).await;
+    let (missing_status, _) = send_as(&ctx.admin_app(), ADMIN_TOKEN, "GET", "/accounts/999999/transfer-limits", None).await;
+
+    // Assert
+    assert_eq!(status, StatusCode::OK, "Limit usage should be available");
+    assert_eq!(usage, json!({ "account_id": account_id, "limits": [] }), "No limits are configured by default");
+    assert_eq!(missing_status, StatusCode::NOT_FOUND);
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_interest_accrues_once_per_day_on_savings_accounts() {
+    // Arrange - 3.65% a year is 0.01% a day
+    let ctx = TestContext::new().await;
+    let first = open_account_of_kind(&ctx, "savings", 1_000_000).await;
+    let second = open_account_of_kind(&ctx, "savings", 2_000_000).await;
+    let checking = open_account(&ctx, 1_000_000).await;
+    let clock = MockClock::new(Utc.with_ymd_and_hms(2025, 3, 1, 2, 0, 0).unwrap());
+    let accounts = AccountService::new(ctx.test_pool.clone(), UserService::new(ctx.test_pool.clone()))
+        .with_clock(clock.shared());
+
+    // Act - Run twice on the same day, one account per batch
+    let accrual = accounts.accrue_interest(365, 1).await.expect("Accrual should succeed");
+    let rerun = accounts.accrue_interest(365, 1).await.expect("Re-running should succeed");
+
+    // Assert
+    assert_eq!((accrual.accounts_credited, accrual.total_cents), (2, 300));
+    assert_eq!((rerun.accounts_credited, rerun.total_cents), (0, 0), "A day should be accrued only once");
+    assert_eq!(balance(&ctx, first).await, 1_000_100);
+    assert_eq!(balance(&ctx, second).await, 2_000_200);
+    assert_eq!(balance(&ctx, checking).await, 1_000_000, "Checking accounts should not accrue interest");
+
+    // Act & Assert - The next day accrues again
+    clock.advance(Duration::days(1));
+    let next_day = accounts.accrue_interest(365, 1).await.expect("Accrual should succeed");
+    assert_eq!(next_day.accounts_credited, 2);
+    assert_eq!(balance(&ctx, first).await, 1_000_200);
+
+    // Assert - Every ledger transaction balances, and the ledger matches the stored balance
+    let unbalanced: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM (SELECT transaction_id FROM ledger_entries GROUP BY transaction_id HAVING SUM(amount_cents) <> 0) t",
+    )
+    .fetch_one(&ctx.test_pool)
+    .await
+    .expect("Failed to check ledger");
+    assert_eq!(unbalanced, 0, "Ledger transactions should sum to zero");
+    let ledger_balance: i64 = sqlx::query_scalar("SELECT SUM(amount_cents)::BIGINT FROM ledger_entries WHERE account_id = $1")
+        .bind(i32::try_from(first).expect("Account ID should fit in i32"))
+        .fetch_one(&ctx.test_pool)
+        .await
+        .expect("Failed to sum ledger");
+    assert_eq!(ledger_balance, 1_000_200);
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_ledger_verification_reports_discrepancies() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    let from = open_account(&ctx, 10_000).await;
+    let to = open_account(&ctx, 0).await;
+    transfer(&ctx, from, to, 4_000).await;
+    withdraw(&ctx, to, 1_000).await;
+    let accounts = AccountService::new(ctx.test_pool.clone(), UserService::new(ctx.test_pool.clone()));
+
+    // Act & Assert - Consistent after regular operations
+    let verification = accounts.verify_ledger().await.expect("Verification should succeed");
+    assert!(verification.consistent, "Regular operations should keep the ledger consistent: {verification:?}");
+    assert_eq!(verification.accounts_checked, 2);
+
+    // Act & Assert - A balance changed behind the ledger's back is reported
+    sqlx::query("UPDATE accounts SET balance_cents = balance_cents + 1 WHERE id = $1")
+        .bind(i32::try_from(to).expect("Account ID should fit in i32"))
+        .execute(&ctx.test_pool)
+        .await
+        .expect("Failed to tamper with balance");
+    let verification = accounts.verify_ledger().await.expect("Verification should succeed");
+    assert!(!verification.consistent);
+    assert_eq!(verification.discrepancies.len(), 1);
+    assert_eq!(
+        (verification.discrepancies[0].stored_balance_cents, verification.discrepancies[0].ledger_balance_cents),
+        (3_001, 3_000)
+    );
+    assert!(verification.unbalanced_transactions.is_empty());
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_ledger_verification_requires_admin() {
+    // Arrange
+    let ctx = TestContext::new().await;
+
+    // Act
+    let (status, _) = send(&ctx.app, "GET", "/admin/ledger/verify", None).await;
+
+    // Assert
+    assert_eq!(status, StatusCode::UNAUTHORIZED, "Ledger verification should require credentials");
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_transfers_are_counted_in_domain_metrics() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    let from = open_account(&ctx, 10_000).await;
+    let to = open_account(&ctx, 0).await;
+    let metrics = MetricsRegistry::global();
+    let internal = [("kind", "internal")];
+    let rejected_amount = [("operation", "transfer"), ("field", "amount_cents")];
+    let value = |metric, labels| metrics.value(metric, labels).unwrap_or_default();
+    let (completed, volume, failures) = (
+        value(&TRANSFERS_COMPLETED, &internal),
+        value(&TRANSFER_VOLUME, &internal),
+        value(&VALIDATION_FAILURES, &rejected_amount),
+    );
+
+    // Act
+    let (status, _) = transfer(&ctx, from, to, 2_500).await;
+    let (invalid_status, _) = transfer(&ctx, from, to, -5).await;
+    let (scrape_status, _) = send(&ctx.app, "GET", "/metrics", None).await;
+
+    // Assert
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(invalid_status, StatusCode::BAD_REQUEST);
+    // Tests run concurrently against the same registry, so only lower bounds hold
+    assert!(value(&TRANSFERS_COMPLETED, &internal) > completed, "The transfer should be counted");
+    assert!(value(&TRANSFER_VOLUME, &internal) >= volume + 2_500, "The amount should be added to the volume");
+    assert!(value(&VALIDATION_FAILURES, &rejected_amount) > failures, "The rejected amount should be counted");
+    assert!(metrics.render().contains("# TYPE transfers_completed_total counter"));
+    assert_eq!(scrape_status, StatusCode::UNAUTHORIZED, "Scraping should require credentials");
+
+    ctx.cleanup().await;
+}
@@ -0,0 +1,113 @@
+//! Integration tests for the user activity timeline
+//!
+//! Verifies that status changes, compliance holds and transactions show up in
+//! a user's timeline newest first, that pages chain through cursor tokens
+//! without gaps or repeats, and that anonymous callers, bad tokens and
+//! unknown users are rejected.
+
+mod common;
+
+use std::collections::HashSet;
+
+use axum::http::StatusCode;
+use common::{ADMIN_TOKEN, TestContext};
+use rust_kickstart::testing::{UserBuilder, send, send_as};
+use serde_json::{Value, json};
+
+/// Opens a checking account with `balance_cents` for `user_id`, returning its ID
+async fn open_account(ctx: &TestContext, user_id: i32, balance_cents: i64) -> i64 {
+    let (status, account) = send_as(
+        &ctx.admin_app(),
+        ADMIN_TOKEN,
+        "POST",
+        "/accounts",
+        Some(json!({ "user_id": user_id, "initial_balance_cents": balance_cents })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED, "Opening an account should succeed");
+    account["id"].as_i64().expect("Account should have an ID")
+}
+
+/// Sends a POST as an admin, since status changes and compliance holds are restricted to admins
+async fn post(ctx: &TestContext, path: &str, body: Option<Value>) {
+    let (status, _) = send_as(&ctx.admin_app(), ADMIN_TOKEN, "POST", path, body).await;
+    assert_eq!(status, StatusCode::OK, "POST {path} should succeed");
+}
+
+#[tokio::test]
+async fn test_activity_merges_audit_events_and_transactions() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    let user_id = UserBuilder::new().insert(&ctx.test_pool).await.id;
+    let other_user_id = UserBuilder::new().insert(&ctx.test_pool).await.id;
+    let account = open_account(&ctx, user_id, 10_000).await;
+    let other_account = open_account(&ctx, other_user_id, 0).await;
+    post(&ctx, &format!("/users/{user_id}/suspend"), None).await;
+    post(&ctx, &format!("/users/{user_id}/activate"), None).await;
+    post(&ctx, &format!("/accounts/{account}/freeze"), Some(json!({ "reason": "Fraud review" }))).await;
+    post(&ctx, &format!("/accounts/{account}/unfreeze"), None).await;
+    post(
+        &ctx,
+        "/transfers",
+        Some(json!({ "from_account_id": account, "to_account_id": other_account, "amount_cents": 1_000 })),
+    )
+    .await;
+
+    // Act
+    let (status, first) = send_as(&ctx.admin_app(), ADMIN_TOKEN, "GET", &format!("/users/{user_id}/activity?limit=4"), None).await;
+    let token = first["next_token"].as_str().expect("First page should have a next token");
+    let (_, second) = send_as(&ctx.admin_app(), ADMIN_TOKEN, "GET", &format!("/users/{user_id}/activity?limit=4&next_token={token}"), None).await;
+
+    // Assert
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(first["has_more"], true);
+    assert_eq!(second["has_more"], false);
+    assert!(second["next_token"].is_null());
+
+    let entries: Vec<&Value> = first["entries"]
+        .as_array()
+        .into_iter()
+        .chain(second["entries"].as_array())
+        .flatten()
+        .collect();
+    let actions: Vec<&str> = entries.iter().filter_map(|entry| entry["action"].as_str()).collect();
+    assert_eq!(
+        actions,
+        ["transfer", "account.unfrozen", "account.frozen", "user.status_changed", "user.status_changed", "opening"],
+        "Entries should be newest first and exclude other users' postings"
+    );
+    let ids: HashSet<&str> = entries.iter().filter_map(|entry| entry["id"].as_str()).collect();
+    assert_eq!(ids.len(), entries.len(), "Pages should not repeat entries");
+
+    assert_eq!(entries[0]["kind"], "transaction");
+    assert_eq!(entries[0]["amount_cents"], -1_000);
+    assert_eq!(entries[0]["account_id"], account);
+    assert_eq!(entries[2]["kind"], "audit");
+    assert_eq!(entries[2]["details"]["reason"], "Fraud review");
+    assert_eq!(entries[3]["details"]["to"], "active");
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_activity_rejects_bad_token_and_unknown_user() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    let user_id = UserBuilder::new().insert(&ctx.test_pool).await.id;
+
+    // Act
+    let (bad_token, _) = send_as(&ctx.admin_app(), ADMIN_TOKEN, "GET", &format!("/users/{user_id}/activity?next_token=garbage"), None).await;
+    let (unknown, _) = send_as(&ctx.admin_app(), ADMIN_TOKEN, "GET", "/users/999999/activity", None).await;
+    let (empty_status, empty) = send_as(&ctx.admin_app(), ADMIN_TOKEN, "GET", &format!("/users/{user_id}/activity"), None).await;
+    let (anonymous, _) = send(&ctx.app, "GET", &format!("/users/{user_id}/activity"), None).await;
+
+    // Assert
+    assert_eq!(bad_token, StatusCode::BAD_REQUEST);
+    assert_eq!(unknown, StatusCode::NOT_FOUND);
+    assert_eq!(empty_status, StatusCode::OK);
+    assert_eq!(empty["entries"], json!([]));
+    assert_eq!(empty["has_more"], false);
+    assert_eq!(anonymous, StatusCode::UNAUTHORIZED, "The timeline should require the admin role");
+
+    ctx.cleanup().await;
+}
@@ -0,0 +1,136 @@
+//! Integration tests for the address sub-resource
+//!
+//! Verifies CRUD on `/users/{id}/addresses`, country-specific validation,
+//! and cascade deletion together with the owning user.
+
+mod common;
+
+use axum::http::StatusCode;
+use common::{ADMIN_TOKEN, TestContext};
+use rust_kickstart::testing::{UserBuilder, assert_validation_error, send, send_as};
+use serde_json::json;
+
+#[tokio::test]
+async fn test_address_crud_workflow() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    let user_id = UserBuilder::new().insert(&ctx.test_pool).await.id;
+    let base = format!("/users/{user_id}/addresses");
+
+    // Act & Assert - Create
+    let (status, address) = send(
+        &ctx.app,
+        "POST",
+        &base,
+        Some(json!({
+            "line1": "1 Main Street",
+            "city": "Springfield",
+            "postal_code": "12345",
+            "country_code": "us"
+        })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED, "Address creation should succeed");
+    assert_eq!(address["country_code"], "US", "Country code should be normalized");
+    let address_id = address["id"].as_i64().unwrap();
+
+    // Act & Assert - List
+    let (status, addresses) = send(&ctx.app, "GET", &base, None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(addresses.as_array().unwrap().len(), 1, "User should have one address");
+
+    // Act & Assert - Update with a postal code valid for the new country
+    let (status, updated) = send(
+        &ctx.app,
+        "PUT",
+        &format!("{base}/{address_id}"),
+        Some(json!({ "postal_code": "10115", "country_code": "DE" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "Address update should succeed");
+    assert_eq!(updated["country_code"], "DE");
+    assert_eq!(updated["line1"], "1 Main Street", "Unchanged fields should be kept");
+
+    // Act & Assert - Delete
+    let (status, _) = send(&ctx.app, "DELETE", &format!("{base}/{address_id}"), None).await;
+    assert_eq!(status, StatusCode::OK, "Address deletion should succeed");
+
+    let (status, _) = send(&ctx.app, "GET", &format!("{base}/{address_id}"), None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND, "Deleted address should not be found");
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_address_validation_errors() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    let user_id = UserBuilder::new().insert(&ctx.test_pool).await.id;
+
+    // Act
+    let (status, body) = send(
+        &ctx.app,
+        "POST",
+        &format!("/users/{user_id}/addresses"),
+        Some(json!({
+            "line1": "1 Main Street",
+            "city": "Springfield",
+            "postal_code": "ABC",
+            "country_code": "US"
+        })),
+    )
+    .await;
+
+    // Assert
+    assert_eq!(status, StatusCode::BAD_REQUEST, "Invalid postal code should be rejected");
+    assert_validation_error(&body, "postal_code");
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_address_for_missing_user() {
+    // Arrange
+    let ctx = TestContext::new().await;
+
+    // Act
+    let (status, _) = send(&ctx.app, "GET", "/users/999/addresses", None).await;
+
+    // Assert
+    assert_eq!(status, StatusCode::NOT_FOUND, "Addresses of a missing user should be 404");
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_addresses_are_deleted_with_user() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    let user_id = UserBuilder::new().insert(&ctx.test_pool).await.id;
+    send(
+        &ctx.app,
+        "POST",
+        &format!("/users/{user_id}/addresses"),
+        Some(json!({
+            "line1": "1 Main Street",
+            "city": "Paris",
+            "postal_code": "75001",
+            "country_code": "FR"
+        })),
+    )
+    .await;
+
+    // Act
+    let (status, _) = send_as(&ctx.admin_app(), ADMIN_TOKEN, "DELETE", &format!("/users/{user_id}"), None).await;
+
+    // Assert
+    assert_eq!(status, StatusCode::OK, "User deletion should succeed");
+    let remaining: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM addresses WHERE user_id = $1")
+        .bind(user_id)
+        .fetch_one(ctx.get_test_pool())
+        .await
+        .unwrap();
+    assert_eq!(remaining, 0, "Addresses should be removed together with their user");
+
+    ctx.cleanup().await;
+}
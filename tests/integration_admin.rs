@@ -0,0 +1,262 @@
+//! Integration tests for the admin overview, runtime diagnostics,
+//! configuration dump, module catalog, named queries and self-test
+//!
+//! Verifies the dashboard counts, that overviews are cached briefly, that
+//! runtime figures are gathered, that named queries are limited, typed,
+//! read-only and time-limited, that the self-test cleans up after itself,
+//! and that the endpoints are restricted to admins, HEAD requests included.
+
+mod common;
+
+use axum::http::StatusCode;
+use chrono::{Duration, Utc};
+use common::{ADMIN_TOKEN, TestContext};
+use rust_kickstart::clock::MockClock;
+use rust_kickstart::testing::{UserBuilder, send, send_as};
+use rust_kickstart::admin::query::{NamedQuery, ParamKind, QueryParam};
+use rust_kickstart::admin::{QueryError, QueryRequest, SelfTestStepName};
+use rust_kickstart::{
+    AccountService, AdminService, JobTracker, QueryService, RequestStats, SelfTestService, UserService,
+};
+use serde_json::json;
+
+#[tokio::test]
+async fn test_overview_counts_and_caching() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    let old_user = UserBuilder::new().insert(&ctx.test_pool).await.id;
+    UserBuilder::new().insert(&ctx.test_pool).await;
+    sqlx::query("UPDATE users SET created_at = NOW() - INTERVAL '2 days' WHERE id = $1")
+        .bind(old_user)
+        .execute(&ctx.test_pool)
+        .await
+        .expect("Failed to backdate user");
+    send_as(&ctx.admin_app(), ADMIN_TOKEN, "POST", "/accounts", Some(json!({ "user_id": old_user }))).await;
+    let clock = MockClock::new(Utc::now());
+    let requests = RequestStats::default();
+    requests.record(200);
+    requests.record(500);
+    let users = UserService::new(ctx.test_pool.clone());
+    let admin = AdminService::new(
+        users.clone(),
+        AccountService::new(ctx.test_pool.clone(), users),
+        requests,
+        JobTracker::default(),
+    )
+    .with_clock(clock.shared());
+
+    // Act
+    let overview = admin.overview().await.expect("Overview should succeed");
+    UserBuilder::new().insert(&ctx.test_pool).await;
+    let cached = admin.overview().await.expect("Overview should succeed");
+    clock.advance(Duration::seconds(6));
+    let refreshed = admin.overview().await.expect("Overview should succeed");
+
+    // Assert
+    assert_eq!((overview.users, overview.recent_signups, overview.accounts), (2, 1, 1));
+    assert_eq!((overview.requests.requests, overview.requests.server_errors), (2, 1));
+    assert_eq!(overview.job_queue_depth, 0);
+    assert_eq!((overview.transaction_retries.retries, overview.transaction_retries.exhausted), (0, 0));
+    assert_eq!(cached, overview, "A fresh overview should be served from the cache");
+    assert_eq!(refreshed.users, 3, "An expired overview should be gathered again");
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_overview_requires_admin() {
+    // Arrange
+    let ctx = TestContext::new().await;
+
+    // Act
+    let (status, _) = send(&ctx.app, "GET", "/admin/overview", None).await;
+    let (runtime_status, _) = send(&ctx.app, "GET", "/admin/runtime", None).await;
+    let (config_status, _) = send(&ctx.app, "GET", "/admin/config", None).await;
+    let (modules_status, _) = send(&ctx.app, "GET", "/admin/modules", None).await;
+    let query = json!({ "query": "pending_data_exports" });
+    let (query_status, _) = send(&ctx.app, "POST", "/admin/query", Some(query)).await;
+    let (selftest_status, _) = send(&ctx.app, "POST", "/admin/selftest", None).await;
+
+    // Assert
+    assert_eq!(status, StatusCode::UNAUTHORIZED, "The overview should require credentials");
+    assert_eq!(runtime_status, StatusCode::UNAUTHORIZED, "Runtime diagnostics should require credentials");
+    assert_eq!(config_status, StatusCode::UNAUTHORIZED, "The configuration dump should require credentials");
+    assert_eq!(modules_status, StatusCode::UNAUTHORIZED, "The module catalog should require credentials");
+    assert_eq!(query_status, StatusCode::UNAUTHORIZED, "Named queries should require credentials");
+    assert_eq!(selftest_status, StatusCode::UNAUTHORIZED, "The self-test should require credentials");
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_head_requests_get_the_get_policy() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    let paths = ["/admin/config", "/admin/runtime", "/admin/jobs", "/metrics", "/changes"];
+
+    // Act
+    let mut anonymous = Vec::new();
+    for path in paths {
+        anonymous.push(send(&ctx.app, "HEAD", path, None).await.0);
+    }
+    let (admin_status, _) = send_as(&ctx.admin_app(), ADMIN_TOKEN, "HEAD", "/admin/config", None).await;
+
+    // Assert
+    for (path, status) in paths.iter().zip(anonymous) {
+        assert_eq!(status, StatusCode::UNAUTHORIZED, "HEAD {path} should require the same credentials as GET");
+    }
+    assert_eq!(admin_status, StatusCode::OK, "Admins should be able to send HEAD requests");
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_runtime_reports_workers_and_process() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    let now = Utc::now();
+    let clock = MockClock::new(now);
+    let users = UserService::new(ctx.test_pool.clone());
+    let admin = AdminService::new(
+        users.clone(),
+        AccountService::new(ctx.test_pool.clone(), users),
+        RequestStats::default(),
+        JobTracker::default(),
+    )
+    .with_clock(clock.shared());
+
+    // Act
+    let diagnostics = admin.runtime(true);
+
+    // Assert
+    assert_eq!(diagnostics.runtime.workers.len(), 2, "Every worker should be reported");
+    assert!(diagnostics.runtime.alive_tasks >= 1, "Pool connections run as tasks");
+    assert_eq!(diagnostics.generated_at, now);
+    if cfg!(target_os = "linux") {
+        assert!(diagnostics.process.resident_memory_bytes.is_some(), "RSS should be read from /proc");
+        assert!(diagnostics.allocator.heap.is_some(), "Heap figures were requested");
+    }
+
+    ctx.cleanup().await;
+}
+
+fn query_request(body: serde_json::Value) -> QueryRequest {
+    serde_json::from_value(body).expect("Valid query request")
+}
+
+#[tokio::test]
+async fn test_named_query_returns_limited_rows() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    for name in ["Ada", "Grace", "Linus"] {
+        UserBuilder::new().name(name).insert(&ctx.test_pool).await;
+    }
+    let queries = QueryService::new(ctx.test_pool.clone()).with_max_rows(2);
+
+    // Act
+    let capped = queries
+        .run(query_request(json!({ "query": "users_by_status", "params": { "status": "active" }, "limit": 50 })))
+        .await
+        .expect("Query should succeed");
+    let limited = queries
+        .run(query_request(json!({ "query": "users_by_status", "params": { "status": "active" }, "limit": 1 })))
+        .await
+        .expect("Query should succeed");
+    let none = queries
+        .run(query_request(json!({ "query": "users_by_status", "params": { "status": "archived" } })))
+        .await
+        .expect("Query should succeed");
+
+    // Assert
+    assert_eq!(capped.rows.len(), 2, "The configured row limit caps the requested one");
+    assert!(capped.truncated);
+    assert_eq!(capped.rows[0]["name"], "Ada");
+    assert_eq!(capped.rows[0]["status"], "active");
+    assert_eq!(limited.rows.len(), 1);
+    assert!(none.rows.is_empty() && !none.truncated);
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_named_query_rejects_unknown_queries_and_bad_parameters() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    let queries = QueryService::new(ctx.test_pool.clone());
+
+    // Act
+    let unknown = queries.run(query_request(json!({ "query": "DROP TABLE users" }))).await;
+    let missing = queries.run(query_request(json!({ "query": "user_accounts" }))).await;
+    let mistyped = queries
+        .run(query_request(json!({ "query": "user_audit_trail", "params": { "user_id": 1, "since": "yesterday" } })))
+        .await;
+
+    // Assert
+    assert!(matches!(unknown, Err(QueryError::UnknownQuery(_))));
+    assert!(matches!(missing, Err(QueryError::InvalidParameter { name: "user_id", .. })));
+    assert!(matches!(mistyped, Err(QueryError::InvalidParameter { name: "since", .. })));
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_named_query_is_read_only_and_time_limited() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    let queries = QueryService::new(ctx.test_pool.clone())
+        .with_timeout(std::time::Duration::from_millis(50))
+        .with_query(NamedQuery {
+            name: "slow",
+            sql: "SELECT pg_sleep($1)",
+            params: &[QueryParam { name: "seconds", kind: ParamKind::Integer }],
+        })
+        .with_query(NamedQuery {
+            name: "writes",
+            sql: "SELECT nextval(pg_get_serial_sequence('users', 'id'))",
+            params: &[],
+        });
+
+    // Act
+    let slow = queries.run(query_request(json!({ "query": "slow", "params": { "seconds": 5 } }))).await;
+    let writes = queries.run(query_request(json!({ "query": "writes" }))).await;
+
+    // Assert
+    assert!(matches!(slow, Err(QueryError::Timeout(50))), "Slow queries should be cancelled");
+    assert!(matches!(writes, Err(QueryError::DatabaseError(_))), "Queries should not be able to write");
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_selftest_runs_every_step_and_leaves_no_rows() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    let users_before = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM users")
+        .fetch_one(&ctx.test_pool)
+        .await
+        .expect("Failed to count users");
+
+    // Act
+    let report = SelfTestService::new(ctx.test_pool.clone()).run().await;
+
+    // Assert
+    assert!(report.passed, "The self-test should pass against a healthy database: {report:?}");
+    let steps: Vec<_> = report.steps.iter().map(|step| step.step).collect();
+    assert_eq!(
+        steps,
+        [SelfTestStepName::Create, SelfTestStepName::Read, SelfTestStepName::Update, SelfTestStepName::Delete]
+    );
+    assert!(report.steps.iter().all(|step| step.ok && step.error.is_none()));
+    let scratch = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM selftest_scratch")
+        .fetch_one(&ctx.test_pool)
+        .await
+        .expect("Failed to count scratch rows");
+    let users_after = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM users")
+        .fetch_one(&ctx.test_pool)
+        .await
+        .expect("Failed to count users");
+    assert_eq!(scratch, 0, "The scratch row should be deleted");
+    assert_eq!(users_after, users_before, "The self-test should not touch users");
+
+    ctx.cleanup().await;
+}
@@ -1,25 +1,27 @@
 //! Integration test demonstrating bank and user module interaction
 //! 
-//! This test creates a user and then uses BankService to create an account,
+//! This test creates a user and then uses `BankService` to create an account,
 //! demonstrating the modular architecture in action.
 
+#![allow(clippy::panic, clippy::float_cmp)]
+
 mod common;
 
-use rust_kickstart::{BankError, CreateUser, UserService, BankService};
+use rust_kickstart::{BankError, BankService, CreateUser, UserReadPort, UserService, UserWritePort};
 use common::TestContext;
 
-/// Creates a UserService instance using the test database pool
+/// Creates a `UserService` instance using the test database pool
 fn create_user_service(ctx: &TestContext) -> UserService {
     UserService::new(ctx.get_test_pool().clone())
 }
 
-/// Creates a BankService instance using the test database pool
+/// Creates a `BankService` instance using the test database pool
 fn create_bank_service(ctx: &TestContext) -> BankService {
     let user_service = create_user_service(ctx);
     BankService::new(user_service)
 }
 
-/// Creates both UserService and BankService instances for convenience
+/// Creates both `UserService` and `BankService` instances for convenience
 fn create_services(ctx: &TestContext) -> (UserService, BankService) {
     let user_service = create_user_service(ctx);
     let bank_service = BankService::new(user_service.clone());
@@ -32,8 +34,9 @@ async fn test_bank_user_integration() {
     let ctx = TestContext::new().await;
     let (user_service, bank_service) = create_services(&ctx);
     let create_user_data = CreateUser {
-        name: "John Doe".to_string(),
+        name: "John Doe".to_owned(),
         age: 30,
+        metadata: None,
     };
     let initial_balance = 10.00;
 
@@ -103,7 +106,7 @@ async fn test_bank_service_with_nonexistent_user() {
         Err(BankError::UserNotFound) => {
             // Expected behavior - test passes
         }
-        Err(other_error) => panic!("Expected UserNotFound error, got: {:?}", other_error),
+        Err(other_error) => panic!("Expected UserNotFound error, got: {other_error:?}"),
         Ok(_) => panic!("Expected error but operation succeeded"),
     }
     
@@ -116,10 +119,11 @@ async fn test_bank_service_update_account_holder() {
     let ctx = TestContext::new().await;
     let (user_service, bank_service) = create_services(&ctx);
     let create_user_data = CreateUser {
-        name: "Jane Smith".to_string(),
+        name: "Jane Smith".to_owned(),
         age: 25,
+        metadata: None,
     };
-    let new_name = "Jane Doe".to_string();
+    let new_name = "Jane Doe".to_owned();
 
     // Act
     let created_user = user_service
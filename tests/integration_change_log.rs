@@ -0,0 +1,107 @@
+//! Integration tests for the change log
+//!
+//! Verifies that status transitions and status-changing reverts are stored
+//! as numbered domain events in the same transaction as the change, that
+//! rejected changes store nothing, and that `GET /changes` pages through the
+//! log after a sequence number and is restricted to admins.
+
+mod common;
+
+use axum::http::StatusCode;
+use common::{ADMIN_TOKEN, TestContext};
+use rust_kickstart::testing::{UserBuilder, send, send_as};
+use rust_kickstart::{UserService, UserWritePort};
+use serde_json::{Value, json};
+
+/// `(seq, event type, from, to)` of each entry of a change log page
+fn entries(page: &Value) -> Vec<(i64, String, String, String)> {
+    page["entries"]
+        .as_array()
+        .expect("The page should list entries")
+        .iter()
+        .map(|entry| {
+            let text = |value: &Value| value.as_str().expect("Entry fields should be strings").to_owned();
+            (entry["seq"].as_i64().expect("seq"), text(&entry["event_type"]), text(&entry["event"]["from"]), text(&entry["event"]["to"]))
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn test_status_changes_are_logged_in_order() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    let user = UserBuilder::new().insert(&ctx.test_pool).await;
+    let users = UserService::new(ctx.test_pool.clone());
+
+    // Act
+    users.suspend_user(user.id).await.expect("Suspending should succeed");
+    users.activate_user(user.id).await.expect("Activating should succeed");
+    let (status, page) = send_as(&ctx.admin_app(), ADMIN_TOKEN, "GET", "/changes", None).await;
+    let (anonymous, _) = send(&ctx.app, "GET", "/changes", None).await;
+
+    // Assert
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(anonymous, StatusCode::UNAUTHORIZED, "The change log should require the admin role");
+    let logged = entries(&page);
+    let transitions: Vec<_> = logged.iter().map(|(_, kind, from, to)| (kind.as_str(), from.as_str(), to.as_str())).collect();
+    assert_eq!(transitions, [("user_status_changed", "active", "suspended"), ("user_status_changed", "suspended", "active")]);
+    assert!(logged[0].0 < logged[1].0, "Sequence numbers should increase");
+    assert_eq!(page["entries"][0]["event"]["user_id"], json!(user.id));
+    assert_eq!((page["last_seq"].as_i64(), page["has_more"].as_bool()), (Some(logged[1].0), Some(false)));
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_changes_are_read_after_a_sequence_number() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    let users = UserService::new(ctx.test_pool.clone());
+    for _ in 0..3 {
+        let user = UserBuilder::new().insert(&ctx.test_pool).await;
+        users.suspend_user(user.id).await.expect("Suspending should succeed");
+    }
+
+    // Act
+    let (_, first) = send_as(&ctx.admin_app(), ADMIN_TOKEN, "GET", "/changes?limit=2", None).await;
+    let last_seq = first["last_seq"].as_i64().expect("The page should return last_seq");
+    let (_, second) = send_as(&ctx.admin_app(), ADMIN_TOKEN, "GET", &format!("/changes?after_seq={last_seq}&limit=2"), None).await;
+    let (_, caught_up) = send_as(&ctx.admin_app(), ADMIN_TOKEN, "GET", &format!("/changes?after_seq={}", second["last_seq"]), None).await;
+
+    // Assert
+    assert_eq!((entries(&first).len(), first["has_more"].as_bool()), (2, Some(true)));
+    assert_eq!((entries(&second).len(), second["has_more"].as_bool()), (1, Some(false)));
+    assert!(entries(&second)[0].0 > last_seq);
+    assert_eq!(caught_up["entries"], json!([]));
+    assert_eq!(caught_up["last_seq"], second["last_seq"], "An empty page should keep the sequence number");
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_rejected_changes_and_status_preserving_reverts_are_not_logged() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    let user = UserBuilder::new().insert(&ctx.test_pool).await;
+    let users = UserService::new(ctx.test_pool.clone());
+    users.suspend_user(user.id).await.expect("Suspending should succeed");
+    users.activate_user(user.id).await.expect("Activating should succeed");
+    send(&ctx.app, "PUT", &format!("/users/{}", user.id), Some(json!({ "name": "Renamed User" }))).await;
+
+    // Act
+    let rejected = users.activate_user(user.id).await;
+    // Version 3 is the active user before the rename
+    users.revert_user(user.id, 3).await.expect("Reverting the rename should succeed");
+    // Version 1 is the active user before the suspension
+    users.suspend_user(user.id).await.expect("Suspending should succeed");
+    users.revert_user(user.id, 1).await.expect("Reverting the suspension should succeed");
+    let (_, page) = send_as(&ctx.admin_app(), ADMIN_TOKEN, "GET", "/changes", None).await;
+
+    // Assert
+    assert!(rejected.is_err(), "Activating an active user should be rejected");
+    let transitions: Vec<_> = entries(&page).into_iter().map(|(_, _, from, to)| (from, to)).collect();
+    let expected = [("active", "suspended"), ("suspended", "active"), ("active", "suspended"), ("suspended", "active")];
+    assert_eq!(transitions, expected.map(|(from, to)| (from.to_owned(), to.to_owned())));
+
+    ctx.cleanup().await;
+}
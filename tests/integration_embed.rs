@@ -0,0 +1,29 @@
+//! Integration tests for nesting the API into a host application
+
+use axum::{Router, http::StatusCode, middleware};
+use rust_kickstart::testing::{UserBuilder, send};
+use rust_kickstart::{AppProviders, ModuleRegistry, api_router, create_app_state, tx};
+
+mod common;
+
+#[tokio::test]
+async fn test_api_router_nests_under_a_prefix() {
+    let ctx = common::TestContext::new().await;
+    let state = create_app_state(ctx.pool.clone(), AppProviders::default(), &ModuleRegistry::new());
+    let host = Router::new()
+        .route("/", axum::routing::get(|| async { "host" }))
+        .nest(
+            "/kickstart",
+            api_router(state.clone())
+                .layer(middleware::from_fn_with_state(ctx.pool.clone(), tx::transactions))
+                .with_state(state),
+        );
+
+    let (status, created) = send(&host, "POST", "/kickstart/users", Some(UserBuilder::new().name("Ann Lee").json())).await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, user) = send(&host, "GET", &format!("/kickstart/users/{}", created["id"]), None).await;
+    assert_eq!((status, user["name"].as_str()), (StatusCode::OK, Some("Ann Lee")));
+
+    assert_eq!(send(&host, "GET", "/kickstart/live", None).await.0, StatusCode::OK);
+    assert_eq!(send(&host, "GET", "/users", None).await.0, StatusCode::NOT_FOUND);
+}
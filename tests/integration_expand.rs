@@ -0,0 +1,59 @@
+//! Integration tests for expand/contract column renames
+//!
+//! Verifies that a dual-writing `UserService` mirrors its writes into the new
+//! column and that the backfill copies the rows written without it.
+
+mod common;
+
+use common::TestContext;
+use rust_kickstart::db::{BackfillJob, ColumnRename};
+use rust_kickstart::testing::UserBuilder;
+use rust_kickstart::{CreateUser, UpdateUser, UserService, UserWritePort};
+
+async fn full_name(ctx: &TestContext, id: i32) -> Option<String> {
+    sqlx::query_scalar("SELECT full_name FROM users WHERE id = $1")
+        .bind(id)
+        .fetch_one(&ctx.test_pool)
+        .await
+        .expect("Failed to read full_name")
+}
+
+#[tokio::test]
+async fn test_dual_write_mirrors_and_backfill_copies_the_rest() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    sqlx::query("ALTER TABLE users ADD COLUMN full_name TEXT")
+        .execute(&ctx.test_pool)
+        .await
+        .expect("Failed to expand users");
+    let rename = ColumnRename::parse("users", "id", "name:full_name").expect("Valid rename");
+    let users = UserService::new(ctx.test_pool.clone()).with_dual_write(rename.clone());
+    let before: Vec<i32> = [
+        UserBuilder::new().name("Ada").insert(&ctx.test_pool).await.id,
+        UserBuilder::new().name("Grace").insert(&ctx.test_pool).await.id,
+        UserBuilder::new().name("Linus").insert(&ctx.test_pool).await.id,
+    ]
+    .into();
+
+    // Act
+    let created = users
+        .create_user(CreateUser { name: "Barbara".to_owned(), age: 30, metadata: None })
+        .await
+        .expect("Create should succeed");
+    let updated = users
+        .update_user(before[0], UpdateUser { name: Some("Ada L.".to_owned()), age: None, metadata: None })
+        .await
+        .expect("Update should succeed");
+    let copied = BackfillJob::new(ctx.test_pool.clone(), rename, 1)
+        .backfill()
+        .await
+        .expect("Backfill should succeed");
+
+    // Assert
+    assert_eq!(full_name(&ctx, created.id).await.as_deref(), Some("Barbara"), "Creates should be mirrored");
+    assert_eq!(full_name(&ctx, updated.id).await.as_deref(), Some("Ada L."), "Updates should be mirrored");
+    assert_eq!(copied, 2, "Only rows written without dual-write should be backfilled, in batches");
+    assert_eq!(full_name(&ctx, before[2]).await.as_deref(), Some("Linus"));
+
+    ctx.cleanup().await;
+}
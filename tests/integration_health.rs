@@ -176,4 +176,85 @@ async fn test_all_health_endpoints_in_root() {
     assert_eq!(endpoints["health"], "/health");
     assert_eq!(endpoints["readiness"], "/ready");
     assert_eq!(endpoints["liveness"], "/live");
-}
\ No newline at end of file
+}
+
+#[tokio::test]
+async fn test_health_reports_read_only_mode_as_degraded() {
+    let test_ctx = common::TestContext::new().await;
+    let health_service = rust_kickstart::HealthService::new(test_ctx.test_pool.clone()).with_read_only(true);
+
+    let response = health_service.check_health().await;
+
+    assert_eq!(response.status, "healthy", "Read-only mode should keep the service in rotation");
+    let read_only = response
+        .components
+        .iter()
+        .find(|component| component.name == "read_only")
+        .expect("Health should report the read-only component");
+    assert_eq!(read_only.status, "degraded");
+
+    test_ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_health_fails_fast_while_database_circuit_is_open() {
+    let test_ctx = common::TestContext::new().await;
+    let circuit = rust_kickstart::DatabaseCircuit::default();
+    circuit.record(false);
+    let health_service = rust_kickstart::HealthService::new(test_ctx.test_pool.clone()).with_circuit(circuit);
+
+    let response = health_service.check_health().await;
+
+    assert_eq!(response.status, "unhealthy", "An open circuit should take the service out of rotation");
+    let database = response
+        .components
+        .iter()
+        .find(|component| component.name == "database")
+        .expect("Health should report the database component");
+    assert_eq!(database.status, "unhealthy");
+
+    test_ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_health_reports_unreachable_dependency_as_degraded() {
+    let test_ctx = common::TestContext::new().await;
+    let pings = rust_kickstart::DependencyPing::from_spec("rates=http://127.0.0.1:9/health", std::time::Duration::from_millis(500));
+    let health_service = rust_kickstart::HealthService::new(test_ctx.test_pool.clone()).with_checks(pings);
+
+    let response = health_service.check_health().await;
+
+    assert_eq!(response.status, "healthy", "A failing dependency should not take the service out of rotation");
+    let rates = response
+        .components
+        .iter()
+        .find(|component| component.name == "rates")
+        .expect("Health should report the dependency");
+    assert_eq!(rates.status, "degraded");
+    assert!(rates.message.as_deref().is_some_and(|message| message.starts_with("Unreachable")));
+
+    test_ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_version_endpoint_reports_build() {
+    let test_ctx = common::TestContext::new().await;
+
+    let response = test_ctx
+        .app
+        .clone()
+        .oneshot(Request::builder().uri("/version").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let version: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(version["version"], env!("CARGO_PKG_VERSION"));
+    for field in ["git_sha", "build_timestamp", "rustc_version"] {
+        assert!(version[field].as_str().is_some_and(|value| !value.is_empty()), "{field} should be reported");
+    }
+    assert!(version["features"].is_array());
+
+    test_ctx.cleanup().await;
+}
@@ -0,0 +1,148 @@
+//! Integration tests for the user version history
+//!
+//! Verifies that updates and status changes record previous versions with the
+//! fields they changed, that deletions keep the history reachable, that
+//! reverts restore a version under current rules, and that the endpoints are
+//! restricted to admins.
+
+mod common;
+
+use axum::http::StatusCode;
+use common::{ADMIN_TOKEN, TestContext};
+use rust_kickstart::testing::{UserBuilder, send, send_as};
+use rust_kickstart::user::domain::{HistoryOperation, UserError, UserStatus};
+use rust_kickstart::{UserReadPort, UserService, UserWritePort};
+use serde_json::json;
+
+#[tokio::test]
+async fn test_history_records_versions_with_diffs() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    let user = UserBuilder::new().insert(&ctx.test_pool).await;
+    let base = format!("/users/{}", user.id);
+    send(&ctx.app, "PUT", &base, Some(json!({ "name": "Renamed User" }))).await;
+    send(&ctx.app, "PUT", &base, Some(json!({ "name": "Renamed User" }))).await;
+    send_as(&ctx.admin_app(), ADMIN_TOKEN, "POST", &format!("{base}/suspend"), None).await;
+    let users = UserService::new(ctx.test_pool.clone());
+
+    // Act
+    let history = users.get_user_history(user.id).await.expect("History should load");
+
+    // Assert
+    let current = history.current.expect("The user should still exist");
+    assert_eq!(current.status, UserStatus::Suspended);
+    let versions: Vec<_> = history.versions.iter().map(|v| (v.version, v.operation)).collect();
+    assert_eq!(
+        versions,
+        [(2, HistoryOperation::Update), (1, HistoryOperation::Update)],
+        "Unchanged updates should not record a version"
+    );
+    let suspension = &history.versions[0];
+    assert_eq!(suspension.status, UserStatus::Active);
+    assert_eq!(suspension.changes.len(), 1);
+    assert_eq!((suspension.changes[0].from.clone(), suspension.changes[0].to.clone()), (json!("active"), json!("suspended")));
+    let rename = &history.versions[1];
+    assert_eq!(rename.name, user.name);
+    assert_eq!(rename.changes[0].field, "name");
+    assert_eq!(rename.changes[0].to, json!("Renamed User"));
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_history_survives_deletion() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    let user = UserBuilder::new().insert(&ctx.test_pool).await;
+    send_as(&ctx.admin_app(), ADMIN_TOKEN, "DELETE", &format!("/users/{}", user.id), None).await;
+    let users = UserService::new(ctx.test_pool.clone());
+
+    // Act
+    let history = users.get_user_history(user.id).await.expect("History should load");
+    let unknown = users.get_user_history(user.id + 1_000).await;
+
+    // Assert
+    assert!(history.current.is_none());
+    assert_eq!(history.versions.len(), 1);
+    assert_eq!(history.versions[0].operation, HistoryOperation::Delete);
+    assert_eq!(history.versions[0].name, user.name);
+    assert!(history.versions[0].changes.is_empty(), "Deletions should not list changes");
+    assert!(matches!(unknown, Err(UserError::NotFound)));
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_revert_restores_version_as_new_update() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    let user = UserBuilder::new().insert(&ctx.test_pool).await;
+    let base = format!("/users/{}", user.id);
+    send(&ctx.app, "PUT", &base, Some(json!({ "name": "Renamed User", "age": 40 }))).await;
+    send_as(&ctx.admin_app(), ADMIN_TOKEN, "POST", &format!("{base}/suspend"), None).await;
+    let users = UserService::new(ctx.test_pool.clone());
+
+    // Act
+    let reverted = users.revert_user(user.id, 1).await.expect("Revert should succeed");
+
+    // Assert
+    assert_eq!((reverted.name.as_str(), reverted.age, reverted.status), (user.name.as_str(), user.age, UserStatus::Active));
+    let history = users.get_user_history(user.id).await.expect("History should load");
+    assert_eq!(history.versions.len(), 3, "The revert should record the replaced version");
+    assert_eq!(history.versions[0].status, UserStatus::Suspended);
+    let (_, activity) = send_as(&ctx.admin_app(), ADMIN_TOKEN, "GET", &format!("{base}/activity"), None).await;
+    assert_eq!(activity["entries"][0]["action"], "user.reverted");
+    assert_eq!(activity["entries"][0]["details"], json!({ "version": 1 }));
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_revert_applies_current_rules() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    let user = UserBuilder::new().insert(&ctx.test_pool).await;
+    // Versions written before today's age limit was enforced
+    for age in [200, 30] {
+        sqlx::query("UPDATE users SET age = $2 WHERE id = $1")
+            .bind(user.id)
+            .bind(age)
+            .execute(&ctx.test_pool)
+            .await
+            .expect("Failed to update user");
+    }
+    send_as(&ctx.admin_app(), ADMIN_TOKEN, "POST", &format!("/users/{}/archive", user.id), None).await;
+    let users = UserService::new(ctx.test_pool.clone());
+
+    // Act
+    let invalid = users.revert_user(user.id, 2).await;
+    let unreachable = users.revert_user(user.id, 1).await;
+    let missing = users.revert_user(user.id, 99).await;
+
+    // Assert
+    assert!(matches!(invalid, Err(UserError::ValidationError(_))));
+    assert!(matches!(
+        unreachable,
+        Err(UserError::InvalidStatusTransition { from: UserStatus::Archived, to: UserStatus::Active })
+    ));
+    assert!(matches!(missing, Err(UserError::VersionNotFound(99))));
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_history_and_revert_require_admin() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    let user = UserBuilder::new().insert(&ctx.test_pool).await;
+
+    // Act
+    let (history, _) = send(&ctx.app, "GET", &format!("/users/{}/history", user.id), None).await;
+    let (revert, _) = send(&ctx.app, "POST", &format!("/users/{}/revert?version=1", user.id), None).await;
+
+    // Assert
+    assert_eq!(history, StatusCode::UNAUTHORIZED, "The history should require credentials");
+    assert_eq!(revert, StatusCode::UNAUTHORIZED, "Reverts should require credentials");
+
+    ctx.cleanup().await;
+}
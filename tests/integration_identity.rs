@@ -0,0 +1,72 @@
+//! Integration tests for external identities
+//!
+//! Verifies linking, relinking and unlinking external system IDs, lookups of
+//! users by those IDs, and that an ID cannot be linked to two users.
+
+mod common;
+
+use axum::http::StatusCode;
+use common::TestContext;
+use rust_kickstart::testing::{UserBuilder, send};
+use serde_json::json;
+
+#[tokio::test]
+async fn test_link_lookup_and_unlink_identity() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    let user = UserBuilder::new().insert(&ctx.test_pool).await;
+    let base = format!("/users/{}/identities", user.id);
+
+    // Act & Assert - Link
+    let (status, identity) = send(&ctx.app, "PUT", &format!("{base}/CRM"), Some(json!({ "external_id": "0015g00000abc" }))).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(identity["system"], "crm", "System names should be normalized");
+    send(&ctx.app, "PUT", &format!("{base}/idp"), Some(json!({ "external_id": "auth0|64f1" }))).await;
+    let (_, identities) = send(&ctx.app, "GET", &base, None).await;
+    assert_eq!(identities.as_array().map(Vec::len), Some(2));
+
+    // Act & Assert - Lookup
+    let (status, found) = send(&ctx.app, "GET", "/users/by-external/crm/0015g00000abc", None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(found["id"], user.id);
+    assert_eq!(found["_links"]["self"]["href"], format!("/users/{}", user.id));
+
+    // Act & Assert - Relink
+    let (_, relinked) = send(&ctx.app, "PUT", &format!("{base}/crm"), Some(json!({ "external_id": "0015g00000xyz" }))).await;
+    assert_eq!(relinked["id"], identity["id"], "Relinking should replace the ID in place");
+    let (status, _) = send(&ctx.app, "GET", "/users/by-external/crm/0015g00000abc", None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND, "The replaced ID should no longer resolve");
+
+    // Act & Assert - Unlink
+    let (status, _) = send(&ctx.app, "DELETE", &format!("{base}/crm"), None).await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = send(&ctx.app, "DELETE", &format!("{base}/crm"), None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (status, _) = send(&ctx.app, "GET", "/users/by-external/crm/0015g00000xyz", None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_identity_conflicts_and_validation() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    let user = UserBuilder::new().insert(&ctx.test_pool).await;
+    let other_user = UserBuilder::new().insert(&ctx.test_pool).await;
+    let link = json!({ "external_id": "0015g00000abc" });
+    send(&ctx.app, "PUT", &format!("/users/{}/identities/crm", user.id), Some(link.clone())).await;
+
+    // Act
+    let (taken, _) = send(&ctx.app, "PUT", &format!("/users/{}/identities/crm", other_user.id), Some(link.clone())).await;
+    let (invalid, errors) = send(&ctx.app, "PUT", &format!("/users/{}/identities/my%20crm", user.id), Some(link.clone())).await;
+    let (missing_user, _) = send(&ctx.app, "PUT", "/users/999999/identities/crm", Some(link)).await;
+
+    // Assert
+    assert_eq!(taken, StatusCode::CONFLICT, "An external ID should belong to one user");
+    assert_eq!(invalid, StatusCode::BAD_REQUEST);
+    assert_eq!(errors["errors"][0]["field"], "system");
+    assert_eq!(missing_user, StatusCode::NOT_FOUND);
+
+    ctx.cleanup().await;
+}
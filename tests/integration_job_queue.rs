@@ -0,0 +1,327 @@
+//! Integration tests for the durable job queue
+//!
+//! Verifies that failed jobs are retried until their attempts run out and are
+//! then dead-lettered, that dead jobs can be listed and replayed, that jobs
+//! abandoned by a stopped worker are claimed again, that only the attempt
+//! holding a job's lease can renew or settle it, that workers claim the due
+//! jobs of their own queue by priority and run them up to their concurrency,
+//! that a stopped worker hands back the jobs it has not started, and that the
+//! inspection endpoints are restricted to admins.
+
+mod common;
+
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+    time::Duration,
+};
+
+use axum::http::StatusCode;
+use futures_util::future::BoxFuture;
+use rust_kickstart::jobs::{Job, JobError, JobHandler, JobListParams, JobQueue, JobStatus, NewJob, QueueError, QueueWorker, spawn_periodic};
+use rust_kickstart::testing::send;
+use serde_json::{Value, json};
+
+/// Handler failing its first `failures` calls
+struct Flaky {
+    calls: Arc<AtomicUsize>,
+    failures: usize,
+}
+
+impl JobHandler for Flaky {
+    fn kind(&self) -> &'static str {
+        "flaky"
+    }
+
+    fn handle(&self, _payload: Value) -> BoxFuture<'_, Result<(), JobError>> {
+        let call = self.calls.fetch_add(1, Ordering::Relaxed);
+        Box::pin(async move {
+            if call < self.failures {
+                Err(format!("failure {}", call + 1).into())
+            } else {
+                Ok(())
+            }
+        })
+    }
+}
+
+fn dead() -> JobListParams {
+    JobListParams {
+        status: Some(JobStatus::Dead),
+        ..JobListParams::default()
+    }
+}
+
+#[tokio::test]
+async fn test_failing_jobs_are_retried_then_dead_lettered_and_replayed() {
+    // Arrange
+    let ctx = common::TestContext::new().await;
+    let queue = JobQueue::new(ctx.test_pool.clone()).with_max_attempts(2);
+    let calls = Arc::new(AtomicUsize::new(0));
+    let worker = QueueWorker::new(queue.clone()).with_handler(Arc::new(Flaky {
+        calls: Arc::clone(&calls),
+        failures: 2,
+    }));
+    let job = queue.enqueue("flaky", json!({ "user_id": 1 })).await.unwrap();
+
+    // Act
+    worker.run().await.unwrap();
+    let after_first = queue.list(&JobListParams::default()).await.unwrap();
+    worker.run().await.unwrap();
+    let dead_jobs = queue.list(&dead()).await.unwrap();
+
+    // Assert
+    assert_eq!((after_first[0].status, after_first[0].attempts), (JobStatus::Queued, 1), "A failed job should be queued again");
+    assert_eq!(dead_jobs.len(), 1, "A job failing every attempt should be dead-lettered");
+    assert_eq!((dead_jobs[0].id, dead_jobs[0].attempts), (job.id, 2));
+    assert_eq!(dead_jobs[0].last_error.as_deref(), Some("failure 2"));
+
+    // Act: replay the dead job
+    let retried = queue.retry(job.id).await.unwrap();
+    worker.run().await.unwrap();
+
+    // Assert
+    assert_eq!((retried.status, retried.attempts), (JobStatus::Queued, 0), "A retried job should start over");
+    let succeeded = queue.list(&JobListParams::default()).await.unwrap();
+    assert_eq!(succeeded[0].status, JobStatus::Succeeded);
+    assert!(queue.list(&dead()).await.unwrap().is_empty());
+    assert_eq!(calls.load(Ordering::Relaxed), 3);
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_only_dead_jobs_can_be_retried() {
+    let ctx = common::TestContext::new().await;
+    let queue = JobQueue::new(ctx.test_pool.clone());
+    let job = queue.enqueue("flaky", json!({})).await.unwrap();
+
+    assert!(matches!(queue.retry(job.id).await, Err(QueueError::NotDead { status: JobStatus::Queued, .. })));
+    assert!(matches!(queue.retry(job.id + 1).await, Err(QueueError::NotFound(_))));
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_jobs_without_a_handler_are_dead_lettered() {
+    let ctx = common::TestContext::new().await;
+    let queue = JobQueue::new(ctx.test_pool.clone()).with_max_attempts(1);
+    queue.enqueue("unknown", json!({})).await.unwrap();
+
+    QueueWorker::new(queue.clone()).run().await.unwrap();
+
+    let dead_jobs = queue.list(&dead()).await.unwrap();
+    assert_eq!(dead_jobs[0].last_error.as_deref(), Some("No handler for jobs of kind `unknown`"));
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_abandoned_jobs_are_claimed_again_after_the_visibility_timeout() {
+    // Arrange: a worker claims the job and stops without settling it
+    let ctx = common::TestContext::new().await;
+    let queue = JobQueue::new(ctx.test_pool.clone())
+        .with_max_attempts(2)
+        .with_visibility_timeout(Duration::from_millis(500));
+    let job = queue.enqueue("flaky", json!({})).await.unwrap();
+    assert_eq!(queue.claim("default", 10).await.unwrap().len(), 1);
+
+    // Act & Assert
+    assert!(queue.claim("default", 10).await.unwrap().is_empty(), "A claimed job should be hidden from other workers");
+    tokio::time::sleep(Duration::from_millis(600)).await;
+    let reclaimed = queue.claim("default", 10).await.unwrap();
+    assert_eq!((reclaimed[0].id, reclaimed[0].attempts), (job.id, 2), "An abandoned job should be claimed again");
+
+    tokio::time::sleep(Duration::from_millis(600)).await;
+    assert!(queue.claim("default", 10).await.unwrap().is_empty());
+    let dead_jobs = queue.list(&dead()).await.unwrap();
+    assert_eq!(dead_jobs[0].id, job.id, "An abandoned job out of attempts should be dead-lettered");
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_only_the_leased_attempt_settles_a_job() {
+    // Arrange: the first attempt outlives its visibility timeout and the job is claimed again
+    let ctx = common::TestContext::new().await;
+    let queue = JobQueue::new(ctx.test_pool.clone()).with_visibility_timeout(Duration::from_millis(500));
+    queue.enqueue("flaky", json!({})).await.unwrap();
+    let first = queue.claim("default", 1).await.unwrap().remove(0);
+    tokio::time::sleep(Duration::from_millis(600)).await;
+    let second = queue.claim("default", 1).await.unwrap().remove(0);
+
+    // Act
+    let stale_complete = queue.complete(&first).await;
+    let stale_fail = queue.fail(&first, "too late").await;
+    queue.complete(&second).await.unwrap();
+
+    // Assert
+    assert!(matches!(stale_complete, Err(QueueError::LeaseLost { attempt: 1, .. })), "{stale_complete:?}");
+    assert!(matches!(stale_fail, Err(QueueError::LeaseLost { attempt: 1, .. })), "{stale_fail:?}");
+    let jobs = queue.list(&JobListParams::default()).await.unwrap();
+    assert_eq!((jobs[0].status, jobs[0].attempts), (JobStatus::Succeeded, 2));
+    assert_eq!(jobs[0].last_error, None, "A stale attempt should not record its outcome");
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_renewed_leases_hide_jobs_from_other_workers() {
+    // Arrange: a worker claims two jobs and starts the second one late
+    let ctx = common::TestContext::new().await;
+    let queue = JobQueue::new(ctx.test_pool.clone()).with_visibility_timeout(Duration::from_millis(500));
+    for _ in 0..2 {
+        queue.enqueue("flaky", json!({})).await.unwrap();
+    }
+    let claimed = queue.claim("default", 10).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(300)).await;
+
+    // Act
+    queue.renew(&claimed[1]).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    let reclaimed = queue.claim("default", 10).await.unwrap();
+    let stale_renew = queue.renew(&claimed[0]).await;
+
+    // Assert
+    assert_eq!(reclaimed.iter().map(|job| job.id).collect::<Vec<_>>(), [claimed[0].id], "Only the job whose lease expired should be claimed again");
+    assert!(matches!(stale_renew, Err(QueueError::LeaseLost { attempt: 1, .. })), "{stale_renew:?}");
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_due_jobs_of_a_queue_are_claimed_by_priority() {
+    // Arrange
+    let ctx = common::TestContext::new().await;
+    let queue = JobQueue::new(ctx.test_pool.clone());
+    let bulk = queue.push(NewJob::new("import", json!({})).on_queue("imports")).await.unwrap();
+    let low = queue.push(NewJob::new("deliver", json!({})).on_queue("webhooks")).await.unwrap();
+    let high = queue.push(NewJob::new("deliver", json!({})).on_queue("webhooks").with_priority(10)).await.unwrap();
+    let delayed = queue
+        .push(NewJob::new("deliver", json!({})).on_queue("webhooks").with_priority(20).delayed_by(Duration::from_mins(1)))
+        .await
+        .unwrap();
+    let past = queue
+        .push(NewJob::new("deliver", json!({})).on_queue("webhooks").run_at(chrono::Utc::now() - chrono::Duration::minutes(1)))
+        .await
+        .unwrap();
+
+    // Act
+    let claimed: Vec<i64> = queue.claim("webhooks", 10).await.unwrap().iter().map(|job| job.id).collect();
+
+    // Assert
+    assert_eq!(claimed, [high.id, past.id, low.id], "Higher priority first, then the job due first");
+    assert!(delayed.run_at > chrono::Utc::now(), "A delayed job should not be due yet");
+    let imports: Vec<i64> = queue.claim("imports", 10).await.unwrap().iter().map(|job| job.id).collect();
+    assert_eq!(imports, [bulk.id], "Workers should claim the jobs of their own queue only");
+
+    ctx.cleanup().await;
+}
+
+/// Handler waiting until `expected` jobs run at the same time
+struct Rendezvous(Arc<tokio::sync::Barrier>);
+
+impl JobHandler for Rendezvous {
+    fn kind(&self) -> &'static str {
+        "rendezvous"
+    }
+
+    fn handle(&self, _payload: Value) -> BoxFuture<'_, Result<(), JobError>> {
+        Box::pin(async move {
+            tokio::time::timeout(Duration::from_secs(2), self.0.wait()).await?;
+            Ok(())
+        })
+    }
+}
+
+#[tokio::test]
+async fn test_workers_run_jobs_up_to_their_concurrency() {
+    let ctx = common::TestContext::new().await;
+    let queue = JobQueue::new(ctx.test_pool.clone()).with_max_attempts(1);
+    for _ in 0..2 {
+        queue.push(NewJob::new("rendezvous", json!({})).on_queue("webhooks")).await.unwrap();
+    }
+    let worker = QueueWorker::new(queue.clone())
+        .on_queue("webhooks")
+        .with_concurrency(2)
+        .with_handler(Arc::new(Rendezvous(Arc::new(tokio::sync::Barrier::new(2)))));
+
+    worker.run().await.unwrap();
+
+    let jobs = queue.list(&JobListParams::default()).await.unwrap();
+    assert!(jobs.iter().all(|job| job.status == JobStatus::Succeeded), "Both jobs should run at once: {jobs:?}");
+
+    ctx.cleanup().await;
+}
+
+/// Handler signalling when it starts and finishing once the gate opens
+struct Gated {
+    started: Arc<tokio::sync::Notify>,
+    gate: Arc<tokio::sync::Notify>,
+}
+
+impl JobHandler for Gated {
+    fn kind(&self) -> &'static str {
+        "gated"
+    }
+
+    fn handle(&self, _payload: Value) -> BoxFuture<'_, Result<(), JobError>> {
+        Box::pin(async move {
+            self.started.notify_one();
+            self.gate.notified().await;
+            Ok(())
+        })
+    }
+}
+
+#[tokio::test]
+async fn test_stopped_workers_hand_back_unstarted_jobs() {
+    // Arrange: a worker claims three jobs and runs the first one
+    let ctx = common::TestContext::new().await;
+    let queue = JobQueue::new(ctx.test_pool.clone());
+    for _ in 0..3 {
+        queue.enqueue("gated", json!({})).await.unwrap();
+    }
+    let (started, gate) = (Arc::new(tokio::sync::Notify::new()), Arc::new(tokio::sync::Notify::new()));
+    let worker = QueueWorker::new(queue.clone()).with_handler(Arc::new(Gated {
+        started: Arc::clone(&started),
+        gate: Arc::clone(&gate),
+    }));
+    let handle = spawn_periodic(Arc::new(worker), Duration::from_mins(1));
+    started.notified().await;
+
+    // Act
+    let stopping = tokio::spawn(handle.stop(Duration::from_secs(5)));
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    let while_stopping = queue.list(&JobListParams::default()).await.unwrap();
+    gate.notify_one();
+    let finished = stopping.await.unwrap();
+
+    // Assert
+    let states = |jobs: &[rust_kickstart::jobs::QueuedJob]| jobs.iter().map(|job| (job.status, job.attempts)).collect::<Vec<_>>();
+    assert_eq!(
+        states(&while_stopping),
+        [(JobStatus::Queued, 0), (JobStatus::Queued, 0), (JobStatus::Running, 1)],
+        "Unstarted jobs should be queued again without counting an attempt"
+    );
+    assert!(finished, "The started job should finish within the grace period");
+    let jobs = queue.list(&JobListParams::default()).await.unwrap();
+    assert_eq!(states(&jobs), [(JobStatus::Queued, 0), (JobStatus::Queued, 0), (JobStatus::Succeeded, 1)]);
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_job_endpoints_require_admin() {
+    let ctx = common::TestContext::new().await;
+
+    let (list_status, _) = send(&ctx.app, "GET", "/admin/jobs?status=dead", None).await;
+    let (retry_status, _) = send(&ctx.app, "POST", "/admin/jobs/1/retry", None).await;
+
+    assert_eq!(list_status, StatusCode::UNAUTHORIZED, "Listing jobs should require credentials");
+    assert_eq!(retry_status, StatusCode::UNAUTHORIZED, "Retrying jobs should require credentials");
+
+    ctx.cleanup().await;
+}
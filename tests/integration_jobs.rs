@@ -0,0 +1,77 @@
+//! Integration tests for coordinating jobs between replicas
+
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+    time::Duration,
+};
+
+use futures_util::future::BoxFuture;
+use rust_kickstart::jobs::{DistributedLock, Job, JobError, singleton};
+
+mod common;
+
+struct Counter(Arc<AtomicUsize>);
+
+impl Job for Counter {
+    fn name(&self) -> &'static str {
+        "counter"
+    }
+
+    fn run(&self) -> BoxFuture<'_, Result<(), JobError>> {
+        self.0.fetch_add(1, Ordering::Relaxed);
+        Box::pin(async { Ok(()) })
+    }
+}
+
+#[tokio::test]
+async fn test_lock_is_held_by_one_holder_at_a_time() {
+    let ctx = common::TestContext::new().await;
+    let replica_a = DistributedLock::new(ctx.test_pool.clone(), "test:exclusive");
+    let replica_b = DistributedLock::new(ctx.test_pool.clone(), "test:exclusive");
+
+    let guard = replica_a.try_acquire().await.unwrap().expect("Free lock should be acquired");
+    assert!(replica_b.try_acquire().await.unwrap().is_none(), "Held lock should not be acquired");
+    assert!(
+        DistributedLock::new(ctx.test_pool.clone(), "test:other").try_acquire().await.unwrap().is_some(),
+        "Locks with other names are independent"
+    );
+
+    guard.release().await.unwrap();
+    assert!(replica_b.try_acquire().await.unwrap().is_some(), "Released lock should be acquired");
+}
+
+#[tokio::test]
+async fn test_singleton_job_skips_runs_while_another_replica_holds_it() {
+    let ctx = common::TestContext::new().await;
+    let runs = Arc::new(AtomicUsize::new(0));
+    let job = singleton(ctx.test_pool.clone(), Arc::new(Counter(Arc::clone(&runs))), Duration::ZERO);
+
+    let other_replica = DistributedLock::new(ctx.test_pool.clone(), "job:counter").try_acquire().await.unwrap().unwrap();
+    job.run().await.unwrap();
+    assert_eq!(runs.load(Ordering::Relaxed), 0, "Run should be skipped while the lock is held elsewhere");
+
+    other_replica.release().await.unwrap();
+    job.run().await.unwrap();
+    job.run().await.unwrap();
+    assert_eq!(runs.load(Ordering::Relaxed), 2, "Lock should be released after each run");
+}
+
+#[tokio::test]
+async fn test_singleton_job_runs_once_per_interval_across_replicas() {
+    let ctx = common::TestContext::new().await;
+    let runs = Arc::new(AtomicUsize::new(0));
+    let replica_a = singleton(ctx.test_pool.clone(), Arc::new(Counter(Arc::clone(&runs))), Duration::from_hours(1));
+    let replica_b = singleton(ctx.test_pool.clone(), Arc::new(Counter(Arc::clone(&runs))), Duration::from_hours(1));
+
+    replica_a.run().await.unwrap();
+    replica_b.run().await.unwrap();
+    replica_a.run().await.unwrap();
+    assert_eq!(runs.load(Ordering::Relaxed), 1, "Runs within the interval should be skipped on every replica");
+
+    sqlx::query("UPDATE job_runs SET last_run_at = NOW() - INTERVAL '2 hours'").execute(&ctx.test_pool).await.unwrap();
+    replica_b.run().await.unwrap();
+    assert_eq!(runs.load(Ordering::Relaxed), 2, "The job should run again once its interval passed");
+}
@@ -0,0 +1,163 @@
+//! Integration tests for mutual TLS
+//!
+//! Serves a router over a real TLS socket and verifies that clients with a
+//! certificate issued by the configured CA reach handlers with their
+//! identity, while clients without one or with a certificate from another CA
+//! are turned away during the handshake, and that the `healthcheck` probe
+//! reaches such a server with its own client certificate.
+//!
+//! Gated behind the `mtls` feature; run with `cargo test --features mtls --test integration_mtls`.
+
+use std::{net::SocketAddr, sync::Arc};
+
+use axum::extract::ConnectInfo;
+use axum::serve::Listener;
+use axum::{Router, middleware, routing::get};
+use rust_kickstart::config::TlsConfig;
+use rust_kickstart::health::probe::probe;
+use rust_kickstart::mtls::{ClientIdentity, MtlsError, MtlsListener, MtlsPeer, expose_peer, probe_client_config, server_config};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio_rustls::TlsConnector;
+use tokio_rustls::rustls::{
+    ClientConfig, RootCertStore,
+    crypto::ring,
+    pki_types::{CertificateDer, PrivateKeyDer, ServerName, pem::PemObject},
+};
+
+/// Path of a file in the mTLS fixtures directory
+fn fixture(name: &str) -> String {
+    format!("{}/tests/fixtures/mtls/{name}", env!("CARGO_MANIFEST_DIR"))
+}
+
+/// TLS settings pointing at the fixtures
+fn tls_config() -> TlsConfig {
+    TlsConfig {
+        cert_path: Some(fixture("server.pem")),
+        key_path: Some(fixture("server-key.pem")),
+        client_ca_path: Some(fixture("ca.pem")),
+        probe_cert_path: Some(fixture("client.pem")),
+        probe_key_path: Some(fixture("client-key.pem")),
+    }
+}
+
+/// Serves a router echoing the client identity and address over mTLS, returning its port
+async fn serve() -> u16 {
+    let app = Router::new().route(
+        "/whoami",
+        get(|identity: ClientIdentity, ConnectInfo(peer): ConnectInfo<SocketAddr>| async move {
+            format!("{}|{}|{}", identity.subject, identity.common_name.unwrap_or_default(), peer.ip())
+        }),
+    );
+    let tcp = TcpListener::bind("127.0.0.1:0").await.expect("Failed to bind");
+    let listener = MtlsListener::new(tcp, server_config(&tls_config()).expect("Fixtures should load")).expect("Failed to listen");
+    let port = Listener::local_addr(&listener).expect("Listener should be bound").port();
+    tokio::spawn(async move {
+        let app = app.layer(middleware::from_fn(expose_peer));
+        axum::serve(listener, app.into_make_service_with_connect_info::<MtlsPeer>())
+            .await
+            .expect("Server failed");
+    });
+    port
+}
+
+/// Requests `/whoami` presenting the given client certificate, returning the raw response or the I/O error
+async fn whoami(port: u16, client: Option<&str>) -> Result<String, std::io::Error> {
+    let mut roots = RootCertStore::empty();
+    roots
+        .add(CertificateDer::from_pem_file(fixture("ca.pem")).expect("CA should load"))
+        .expect("CA should be valid");
+    let builder = ClientConfig::builder_with_provider(Arc::new(ring::default_provider()))
+        .with_safe_default_protocol_versions()
+        .expect("Protocol versions should be supported")
+        .with_root_certificates(roots);
+    let config = match client {
+        Some(name) => builder
+            .with_client_auth_cert(
+                vec![CertificateDer::from_pem_file(fixture(&format!("{name}.pem"))).expect("Certificate should load")],
+                PrivateKeyDer::from_pem_file(fixture(&format!("{name}-key.pem"))).expect("Key should load"),
+            )
+            .expect("Client certificate should be usable"),
+        None => builder.with_no_client_auth(),
+    };
+
+    let tcp = TcpStream::connect(("127.0.0.1", port)).await?;
+    let server_name = ServerName::try_from("localhost").expect("Valid server name");
+    let mut stream = TlsConnector::from(Arc::new(config)).connect(server_name, tcp).await?;
+    stream
+        .write_all(b"GET /whoami HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n")
+        .await?;
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await?;
+    Ok(response)
+}
+
+#[tokio::test]
+async fn test_clients_are_identified_by_their_certificate() {
+    // Arrange
+    let port = serve().await;
+
+    // Act
+    let trusted = whoami(port, Some("client")).await;
+    let rogue = whoami(port, Some("rogue-client")).await;
+    let anonymous = whoami(port, None).await;
+
+    // Assert
+    let trusted = trusted.expect("A client with a trusted certificate should be served");
+    assert!(trusted.starts_with("HTTP/1.1 200"), "Unexpected response: {trusted}");
+    assert!(trusted.ends_with("CN=billing-service,OU=Payments,O=Acme|billing-service|127.0.0.1"), "Unexpected response: {trusted}");
+    assert!(!rogue.is_ok_and(|response| !response.is_empty()), "A certificate from another CA should be rejected");
+    assert!(!anonymous.is_ok_and(|response| !response.is_empty()), "A client without a certificate should be rejected");
+}
+
+#[tokio::test]
+async fn test_incomplete_or_invalid_settings_are_reported() {
+    // Arrange
+    let without_ca = TlsConfig {
+        client_ca_path: None,
+        ..tls_config()
+    };
+    let missing_key = TlsConfig {
+        key_path: Some(fixture("missing-key.pem")),
+        ..tls_config()
+    };
+
+    // Act
+    let incomplete = server_config(&without_ca);
+    let unreadable = server_config(&missing_key);
+
+    // Assert
+    assert!(matches!(incomplete, Err(MtlsError::Incomplete)));
+    assert!(matches!(unreadable, Err(MtlsError::Pem { path, .. }) if path.ends_with("missing-key.pem")));
+}
+
+#[tokio::test]
+async fn test_healthcheck_probes_with_its_client_certificate() {
+    // Arrange
+    let port = serve().await;
+    let url = format!("https://127.0.0.1:{port}/whoami");
+    let rogue_probe = TlsConfig {
+        probe_cert_path: Some(fixture("rogue-client.pem")),
+        probe_key_path: Some(fixture("rogue-client-key.pem")),
+        ..tls_config()
+    };
+    let other_server = TlsConfig {
+        cert_path: Some(fixture("client.pem")),
+        ..tls_config()
+    };
+    let without_probe = TlsConfig {
+        probe_cert_path: None,
+        ..tls_config()
+    };
+
+    // Act
+    let probed = probe(&url, &tls_config()).await;
+    let rogue = probe(&url, &rogue_probe).await;
+    let unpinned = probe(&url, &other_server).await;
+
+    // Assert
+    assert_eq!(probed, Ok(()), "The probe's certificate should be accepted");
+    assert!(rogue.is_err(), "A probe certificate from another CA should be rejected");
+    assert!(unpinned.is_err(), "The probe should only trust the configured server certificate");
+    assert!(matches!(probe_client_config(&without_probe), Err(MtlsError::NoProbeIdentity)));
+}
@@ -0,0 +1,97 @@
+//! Integration tests for Postgres LISTEN/NOTIFY
+//!
+//! Verifies that the listener forwards notifications to the hub's
+//! subscribers, reports its state as a health check, and reconnects after its
+//! connection is terminated.
+
+mod common;
+
+use std::time::Duration;
+
+use common::TestContext;
+use rust_kickstart::health::HealthCheck;
+use rust_kickstart::notify::{self, ListenerState, Notification, NotificationHub, NotifyListener};
+use tokio::sync::broadcast;
+
+/// Waits until the listener reports `state`
+async fn wait_for(hub: &NotificationHub, state: ListenerState) {
+    let reached = tokio::time::timeout(Duration::from_secs(10), async {
+        while hub.status().state != state {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+    })
+    .await;
+    assert!(reached.is_ok(), "The listener should become {state:?}, status: {:?}", hub.status());
+}
+
+/// Receives the next notification forwarded by the hub
+async fn next(receiver: &mut broadcast::Receiver<Notification>) -> Notification {
+    tokio::time::timeout(Duration::from_secs(10), receiver.recv())
+        .await
+        .expect("A notification should arrive")
+        .expect("The hub should stay open")
+}
+
+#[tokio::test]
+async fn test_listener_forwards_notifications() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    let channel = ctx.schema_name.clone();
+    let hub = NotificationHub::new();
+    let mut receiver = hub.subscribe();
+    let listener = NotifyListener::new(ctx.test_pool.clone(), vec![channel.clone()], hub.clone()).spawn();
+    wait_for(&hub, ListenerState::Listening).await;
+
+    // Act
+    notify::notify(&ctx.test_pool, &channel, r#"{"user_id":7}"#).await.expect("Notifying should succeed");
+    notify::notify(&ctx.test_pool, "not_listened_on", "ignored").await.expect("Notifying should succeed");
+    notify::notify(&ctx.test_pool, &channel, "").await.expect("Notifying should succeed");
+
+    // Assert
+    assert_eq!(next(&mut receiver).await, Notification { channel: channel.clone(), payload: r#"{"user_id":7}"#.to_owned() });
+    assert_eq!(next(&mut receiver).await, Notification { channel: channel.clone(), payload: String::new() });
+    assert_eq!(hub.check().await, Ok(()));
+    assert_eq!((hub.status().channels, hub.status().reconnects), (vec![channel], 0));
+
+    listener.abort();
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_listener_reconnects_after_losing_its_connection() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    let channel = ctx.schema_name.clone();
+    let hub = NotificationHub::new();
+    let mut receiver = hub.subscribe();
+    let listener = NotifyListener::new(ctx.test_pool.clone(), vec![channel.clone()], hub.clone()).spawn();
+    wait_for(&hub, ListenerState::Listening).await;
+
+    // Act
+    let terminated: Vec<bool> = sqlx::query_scalar(
+        "SELECT pg_terminate_backend(pid) FROM pg_stat_activity WHERE query LIKE 'LISTEN %' || $1 || '%' AND pid <> pg_backend_pid()",
+    )
+    .bind(&channel)
+    .fetch_all(&ctx.test_pool)
+    .await
+    .expect("Terminating the listening connection should succeed");
+    assert_eq!(terminated, [true]);
+    tokio::time::timeout(Duration::from_secs(10), async {
+        while hub.status().reconnects == 0 {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+    })
+    .await
+    .expect("The lost connection should be recorded");
+    wait_for(&hub, ListenerState::Listening).await;
+    notify::notify(&ctx.test_pool, &channel, "after reconnect").await.expect("Notifying should succeed");
+
+    // Assert
+    assert_eq!(next(&mut receiver).await.payload, "after reconnect");
+    let status = hub.status();
+    assert_eq!(status.reconnects, 1);
+    assert!(status.last_error.is_some());
+
+    listener.abort();
+    ctx.cleanup().await;
+}
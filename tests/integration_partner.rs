@@ -0,0 +1,268 @@
+//! Integration tests for partner signing keys and signed requests
+//!
+//! Verifies that signed routes accept correctly signed requests once, reject
+//! unsigned, stale, replayed and badly signed ones, that rotated keys keep
+//! working for the grace period only, that stored keys are sealed so the
+//! stored column alone cannot sign requests, and that key rotation and
+//! account grants are restricted to admins.
+
+mod common;
+
+use std::time::Duration;
+
+use axum::body::Body;
+use axum::extract::Extension;
+use axum::http::{Method, Request, StatusCode};
+use axum::{Router, middleware, routing::post};
+use base64::{Engine as _, engine::general_purpose};
+use chrono::{TimeZone, Utc};
+use common::{ADMIN_TOKEN, TestContext};
+use hmac::{Hmac, Mac};
+use http_body_util::BodyExt;
+use rust_kickstart::clock::MockClock;
+use rust_kickstart::partner::{
+    KeyEncryptionKey, PartnerError, PartnerId, PartnerService, SignatureVerifier, SignedRoutes, sign, verify_signatures,
+};
+use rust_kickstart::testing::{UserBuilder, send, send_as};
+use serde_json::{Value, json};
+use sha2::{Digest, Sha256};
+use tower::ServiceExt;
+
+/// Router with one signed route echoing the signing partner and the body
+fn signed_app(partners: PartnerService) -> Router {
+    let verifier = SignatureVerifier::new(
+        SignedRoutes::new().route(Method::POST, "/partner/echo"),
+        partners,
+        Duration::from_mins(5),
+    );
+    Router::new()
+        .route(
+            "/partner/echo",
+            post(|Extension(PartnerId(partner)): Extension<PartnerId>, body: String| async move { format!("{partner}:{body}") }),
+        )
+        .route_layer(middleware::from_fn_with_state(verifier, verify_signatures))
+}
+
+/// Path and query of the signed route
+const ECHO_URI: &str = "/partner/echo?source=test";
+
+/// Posts `body` to the signed route, signed with `secret` at `timestamp` when given
+async fn post_signed(app: &Router, secret: Option<&str>, timestamp: i64, body: &str) -> (StatusCode, String) {
+    let signature = secret.map(|secret| sign(secret, timestamp, &Method::POST, ECHO_URI, body.as_bytes()));
+    post_with_signature(app, signature, timestamp, body).await
+}
+
+/// Posts `body` to the signed route with the `X-Signature` `signature` when given
+async fn post_with_signature(app: &Router, signature: Option<String>, timestamp: i64, body: &str) -> (StatusCode, String) {
+    let mut request = Request::builder().method("POST").uri(ECHO_URI);
+    if let Some(signature) = signature {
+        request = request
+            .header("x-partner-id", "acme")
+            .header("x-timestamp", timestamp.to_string())
+            .header("x-signature", signature);
+    }
+    let response = app
+        .clone()
+        .oneshot(request.body(Body::from(body.to_owned())).expect("Failed to build request"))
+        .await
+        .expect("Failed to send request");
+    let status = response.status();
+    let body = response.into_body().collect().await.expect("Failed to read body").to_bytes();
+    (status, String::from_utf8(body.to_vec()).expect("Body should be UTF-8"))
+}
+
+/// `X-Signature` of a `body` posted to the signed route, using `key` as the HMAC key
+fn sign_with_key(key: &[u8], timestamp: i64, body: &str) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(format!("{timestamp}\nPOST\n{ECHO_URI}\n{body}").as_bytes());
+    general_purpose::URL_SAFE_NO_PAD.encode(mac.finalize().into_bytes())
+}
+
+/// Stored `(key_digest, sealed_key)` columns of the keys of `partner`
+async fn stored_keys(pool: &sqlx::PgPool, partner: &str) -> Vec<(Option<Vec<u8>>, Option<Vec<u8>>)> {
+    sqlx::query_as("SELECT key_digest, sealed_key FROM partner_keys WHERE partner = $1 ORDER BY id")
+        .bind(partner)
+        .fetch_all(pool)
+        .await
+        .expect("Failed to read partner keys")
+}
+
+/// Error code of a JSON error body
+fn code(body: &str) -> Value {
+    serde_json::from_str::<Value>(body).expect("Body should be JSON")["code"].clone()
+}
+
+#[tokio::test]
+async fn test_signed_requests_are_verified() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    let start = Utc.with_ymd_and_hms(2025, 3, 1, 9, 0, 0).unwrap();
+    let clock = MockClock::new(start);
+    let partners = PartnerService::new(ctx.test_pool.clone()).with_clock(clock.shared());
+    let key = partners.rotate_key("acme").await.expect("Rotation should succeed");
+    let app = signed_app(partners);
+    let now = start.timestamp();
+
+    // Act
+    let accepted = post_signed(&app, Some(&key.secret), now, r#"{"id":1}"#).await;
+    let replayed = post_signed(&app, Some(&key.secret), now, r#"{"id":1}"#).await;
+    let unsigned = post_signed(&app, None, now, r#"{"id":2}"#).await;
+    let stale = post_signed(&app, Some(&key.secret), now - 600, r#"{"id":3}"#).await;
+    let forged = post_signed(&app, Some("not-the-secret"), now, r#"{"id":4}"#).await;
+
+    // Assert
+    assert_eq!(accepted, (StatusCode::OK, r#"acme:{"id":1}"#.to_owned()), "The handler should see the partner and the body");
+    assert_eq!(key.previous_keys_expire_at, None);
+    assert_eq!((replayed.0, code(&replayed.1)), (StatusCode::UNAUTHORIZED, "SIGNATURE_REPLAYED".into()));
+    assert_eq!((unsigned.0, code(&unsigned.1)), (StatusCode::UNAUTHORIZED, "SIGNATURE_REQUIRED".into()));
+    assert_eq!((stale.0, code(&stale.1)), (StatusCode::UNAUTHORIZED, "SIGNATURE_EXPIRED".into()));
+    assert_eq!((forged.0, code(&forged.1)), (StatusCode::UNAUTHORIZED, "SIGNATURE_MISMATCH".into()));
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_rotated_keys_work_for_the_grace_period() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    let start = Utc.with_ymd_and_hms(2025, 3, 1, 9, 0, 0).unwrap();
+    let clock = MockClock::new(start);
+    let partners = PartnerService::new(ctx.test_pool.clone())
+        .with_clock(clock.shared())
+        .with_rotation_grace(Duration::from_hours(1));
+    let old = partners.rotate_key("acme").await.expect("Rotation should succeed");
+    let new = partners.rotate_key("acme").await.expect("Rotation should succeed");
+    let app = signed_app(partners.clone());
+
+    // Act
+    let old_in_grace = post_signed(&app, Some(&old.secret), start.timestamp(), "old").await;
+    clock.advance(chrono::Duration::hours(2));
+    let later = start.timestamp() + 7200;
+    let old_after_grace = post_signed(&app, Some(&old.secret), later, "old").await;
+    let new_after_grace = post_signed(&app, Some(&new.secret), later, "new").await;
+    let invalid = partners.rotate_key("Not A Partner").await;
+
+    // Assert
+    assert_ne!(old.secret, new.secret);
+    assert_eq!(new.previous_keys_expire_at, Some(start + chrono::Duration::hours(1)));
+    assert_eq!(old_in_grace.0, StatusCode::OK, "The previous key should work during the grace period");
+    assert_eq!(old_after_grace.0, StatusCode::UNAUTHORIZED, "The previous key should expire after the grace period");
+    assert_eq!(new_after_grace.0, StatusCode::OK);
+    assert!(matches!(invalid, Err(PartnerError::ValidationError(_))));
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_stored_keys_cannot_sign_requests() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    let start = Utc.with_ymd_and_hms(2025, 3, 1, 9, 0, 0).unwrap();
+    let partners = PartnerService::new(ctx.test_pool.clone())
+        .with_clock(MockClock::new(start).shared())
+        .with_key_encryption_key(KeyEncryptionKey::new("test-kek"));
+    let key = partners.rotate_key("acme").await.expect("Rotation should succeed");
+    let app = signed_app(partners);
+    let now = start.timestamp();
+    let stored = stored_keys(&ctx.test_pool, "acme").await;
+    let sealed = stored[0].1.clone().expect("The key should be stored sealed");
+
+    // Act
+    let with_sealed = post_with_signature(&app, Some(sign_with_key(&sealed, now, "sealed")), now, "sealed").await;
+    let with_secret = post_signed(&app, Some(&key.secret), now, "secret").await;
+
+    // Assert
+    assert_eq!(stored.len(), 1);
+    assert_eq!(stored[0].0, None, "The signing key should not be stored in the clear");
+    let digest = Sha256::digest(key.secret.as_bytes()).to_vec();
+    assert_eq!(sign_with_key(&digest, now, "x"), sign(&key.secret, now, &Method::POST, ECHO_URI, b"x"), "The digest alone would sign");
+    assert_ne!(sealed, digest);
+    assert_eq!((with_sealed.0, code(&with_sealed.1)), (StatusCode::UNAUTHORIZED, "SIGNATURE_MISMATCH".into()));
+    assert_eq!(with_secret.0, StatusCode::OK);
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_keys_stored_in_the_clear_get_sealed() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    let start = Utc.with_ymd_and_hms(2025, 3, 1, 9, 0, 0).unwrap();
+    let secret = "legacy-secret";
+    sqlx::query("INSERT INTO partner_keys (partner, key_digest, created_at) VALUES ('acme', $1, $2)")
+        .bind(Sha256::digest(secret.as_bytes()).to_vec())
+        .bind(start)
+        .execute(&ctx.test_pool)
+        .await
+        .expect("Failed to insert legacy key");
+    let partners = PartnerService::new(ctx.test_pool.clone())
+        .with_clock(MockClock::new(start).shared())
+        .with_key_encryption_key(KeyEncryptionKey::new("test-kek"));
+    let app = signed_app(partners.clone());
+    let now = start.timestamp();
+
+    // Act
+    let before = post_signed(&app, Some(secret), now, "before").await;
+    let sealed = partners.seal_stored_keys().await.expect("Sealing should succeed");
+    let sealed_again = partners.seal_stored_keys().await.expect("Sealing should succeed");
+    let after = post_signed(&app, Some(secret), now, "after").await;
+    let stored = stored_keys(&ctx.test_pool, "acme").await;
+
+    // Assert
+    assert_eq!(before.0, StatusCode::OK, "Keys stored in the clear should keep working until sealed");
+    assert_eq!((sealed, sealed_again), (1, 0));
+    assert!(matches!(stored.as_slice(), [(None, Some(_))]), "The key should now be stored sealed");
+    assert_eq!(after.0, StatusCode::OK, "The sealed key should still verify");
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_key_rotation_requires_admin() {
+    // Arrange
+    let ctx = TestContext::new().await;
+
+    // Act
+    let (status, body) = send(&ctx.app, "POST", "/admin/partners/acme/keys", None).await;
+
+    // Assert
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert_eq!(body["code"], "UNAUTHENTICATED");
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_account_grants() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    let user_id = UserBuilder::new().insert(&ctx.test_pool).await.id;
+    let (_, account) = send_as(
+        &ctx.admin_app(),
+        ADMIN_TOKEN,
+        "POST",
+        "/accounts",
+        Some(json!({ "user_id": user_id, "initial_balance_cents": 0 })),
+    )
+    .await;
+    let uri = format!("/admin/partners/acme/accounts/{}", account["id"]);
+
+    // Act
+    let (anonymous, _) = send(&ctx.app, "PUT", &uri, None).await;
+    let (granted_status, granted) = send_as(&ctx.admin_app(), ADMIN_TOKEN, "PUT", &uri, None).await;
+    let (_, regranted) = send_as(&ctx.admin_app(), ADMIN_TOKEN, "PUT", &uri, None).await;
+    let (missing_status, missing) = send_as(&ctx.admin_app(), ADMIN_TOKEN, "PUT", "/admin/partners/acme/accounts/999999", None).await;
+    let (revoked, _) = send_as(&ctx.admin_app(), ADMIN_TOKEN, "DELETE", &uri, None).await;
+    let (revoked_again, again) = send_as(&ctx.admin_app(), ADMIN_TOKEN, "DELETE", &uri, None).await;
+
+    // Assert
+    assert_eq!(anonymous, StatusCode::UNAUTHORIZED);
+    assert_eq!(granted_status, StatusCode::OK);
+    assert_eq!((&granted["partner"], &granted["account_id"]), (&"acme".into(), &account["id"]));
+    assert_eq!(regranted["created_at"], granted["created_at"], "Granting again should keep the first grant");
+    assert_eq!((missing_status, &missing["code"]), (StatusCode::NOT_FOUND, &"ACCOUNT_NOT_FOUND".into()));
+    assert_eq!(revoked, StatusCode::OK);
+    assert_eq!((revoked_again, &again["code"]), (StatusCode::NOT_FOUND, &"ACCOUNT_NOT_GRANTED".into()));
+
+    ctx.cleanup().await;
+}
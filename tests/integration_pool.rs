@@ -0,0 +1,67 @@
+//! Integration tests for connection acquisition
+//!
+//! Verifies that waiting longer than the threshold for a pool connection is
+//! logged with the pool's statistics, and that fast acquisitions are not.
+
+mod common;
+
+use std::time::Duration;
+
+use common::TestContext;
+use rust_kickstart::db::{self, PoolStats};
+use rust_kickstart::testing::SpanCapture;
+
+#[tokio::test]
+async fn test_slow_acquisition_is_logged_with_pool_stats() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    let max_connections = ctx.test_pool.options().get_max_connections();
+    let mut held = Vec::new();
+    for _ in 0..max_connections {
+        held.push(ctx.test_pool.acquire().await.expect("Acquiring should succeed"));
+    }
+    let capture = SpanCapture::new();
+
+    // Act
+    let guard = capture.install();
+    let release = async {
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        held.pop();
+    };
+    let (connection, ()) = tokio::join!(db::acquire(&ctx.test_pool, Some(Duration::from_millis(20))), release);
+    drop(guard);
+
+    // Assert
+    assert!(connection.is_ok(), "The released connection should be acquired");
+    capture.assert_event("Slow database connection acquisition");
+    let event = capture.find_event("Slow database connection acquisition").expect("event");
+    let field = |name: &str| event.fields.get(name).cloned().unwrap_or_default();
+    let max = max_connections.to_string();
+    assert_eq!([field("size"), field("idle"), field("in_use"), field("max_connections")], [max.clone(), "0".to_owned(), max.clone(), max]);
+    assert!(field("waited_ms").parse::<u64>().expect("waited_ms") >= 20);
+
+    drop((connection, held));
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_fast_acquisition_is_not_logged() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    drop(ctx.test_pool.acquire().await.expect("Acquiring should succeed"));
+    let capture = SpanCapture::new();
+
+    // Act
+    let guard = capture.install();
+    let connection = db::acquire(&ctx.test_pool, Some(Duration::from_secs(5))).await;
+    drop(guard);
+
+    // Assert
+    assert!(connection.is_ok());
+    assert!(capture.find_event("Slow database connection acquisition").is_none());
+    let stats = PoolStats::of(&ctx.test_pool);
+    assert_eq!((stats.in_use(), stats.max_connections), (1, ctx.test_pool.options().get_max_connections()));
+
+    drop(connection);
+    ctx.cleanup().await;
+}
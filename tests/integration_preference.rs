@@ -0,0 +1,92 @@
+//! Integration tests for user preferences
+//!
+//! Verifies defaults, merge semantics of updates, resets with `null`,
+//! rejection of unknown keys and invalid values, and configured defaults.
+
+mod common;
+
+use axum::http::StatusCode;
+use common::TestContext;
+use rust_kickstart::testing::{UserBuilder, send};
+use rust_kickstart::{PreferenceService, UserService};
+use serde_json::json;
+
+#[tokio::test]
+async fn test_preferences_merge_and_reset() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    let user = UserBuilder::new().insert(&ctx.test_pool).await;
+    let path = format!("/users/{}/preferences", user.id);
+
+    // Act & Assert - Defaults
+    let (status, preferences) = send(&ctx.app, "GET", &path, None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(preferences["preferences"], json!({ "theme": "system", "language": "en", "email_notifications": true }));
+    assert_eq!(preferences["customized"], json!([]));
+    assert!(preferences["updated_at"].is_null());
+
+    // Act & Assert - Merge
+    send(&ctx.app, "PUT", &path, Some(json!({ "theme": "dark", "page_size": 50 }))).await;
+    let (status, preferences) = send(&ctx.app, "PUT", &path, Some(json!({ "language": "de" }))).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(
+        preferences["preferences"],
+        json!({ "theme": "dark", "language": "de", "email_notifications": true, "page_size": 50 }),
+        "Updates should merge into earlier ones"
+    );
+    assert_eq!(preferences["customized"], json!(["language", "page_size", "theme"]));
+    assert!(preferences["updated_at"].is_string());
+
+    // Act & Assert - Reset
+    let (_, preferences) = send(&ctx.app, "PUT", &path, Some(json!({ "theme": null, "page_size": null }))).await;
+    assert_eq!(preferences["preferences"]["theme"], "system", "null should restore the default");
+    assert!(preferences["preferences"].get("page_size").is_none());
+    assert_eq!(preferences["customized"], json!(["language"]));
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_preferences_validation() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    let user = UserBuilder::new().insert(&ctx.test_pool).await;
+    let path = format!("/users/{}/preferences", user.id);
+
+    // Act
+    let (unknown_status, unknown) = send(&ctx.app, "PUT", &path, Some(json!({ "favorite_color": "blue" }))).await;
+    let (invalid_status, _) = send(&ctx.app, "PUT", &path, Some(json!({ "theme": "pink", "page_size": 1000 }))).await;
+    let (missing_status, _) = send(&ctx.app, "GET", "/users/999999/preferences", None).await;
+    let (_, unchanged) = send(&ctx.app, "GET", &path, None).await;
+
+    // Assert
+    assert_eq!(unknown_status, StatusCode::BAD_REQUEST);
+    assert_eq!(unknown["errors"][0]["field"], "favorite_color");
+    assert_eq!(invalid_status, StatusCode::BAD_REQUEST);
+    assert_eq!(missing_status, StatusCode::NOT_FOUND);
+    assert_eq!(unchanged["customized"], json!([]), "Rejected updates should change nothing");
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_configured_defaults() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    let user = UserBuilder::new().insert(&ctx.test_pool).await;
+    let defaults = json!({ "theme": "dark", "page_size": 25, "timezone": "", "favorite_color": "blue" });
+    let preferences = PreferenceService::new(ctx.test_pool.clone(), UserService::new(ctx.test_pool.clone()))
+        .with_defaults(defaults.as_object().expect("An object"));
+
+    // Act
+    let resolved = preferences.get_preferences(user.id).await.expect("Preferences should resolve");
+
+    // Assert
+    assert_eq!(
+        serde_json::Value::Object(resolved.preferences),
+        json!({ "theme": "dark", "language": "en", "email_notifications": true, "page_size": 25 }),
+        "Valid defaults should override the built-in ones and invalid ones be ignored"
+    );
+
+    ctx.cleanup().await;
+}
@@ -0,0 +1,245 @@
+//! Integration tests for GDPR data exports
+//!
+//! Verifies that an export is queued, built by the export job with everything
+//! stored about the user, and downloadable only through an untampered,
+//! unexpired signed link that only admins are handed, and that erasure
+//! anonymizes a user without touching balances or the ledger, with a dry run
+//! that changes nothing.
+
+mod common;
+
+use axum::http::StatusCode;
+use chrono::{Duration, Utc};
+use common::{ADMIN_TOKEN, TestContext};
+use rust_kickstart::clock::MockClock;
+use rust_kickstart::privacy::{DownloadQuery, ExportStatus, PrivacyError};
+use rust_kickstart::testing::{UserBuilder, send, send_as};
+use rust_kickstart::{AccountService, ExportSigner, LinkBuilder, PrivacyService, UserService};
+use serde_json::{Value, json};
+
+fn privacy_service(ctx: &TestContext) -> PrivacyService {
+    PrivacyService::new(ctx.test_pool.clone(), UserService::new(ctx.test_pool.clone()), ExportSigner::new("test-key"))
+}
+
+#[tokio::test]
+async fn test_export_is_built_and_downloadable() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    let user = UserBuilder::new().insert(&ctx.test_pool).await;
+    let (status, account) = send_as(
+        &ctx.admin_app(),
+        ADMIN_TOKEN,
+        "POST",
+        "/accounts",
+        Some(json!({ "user_id": user.id, "initial_balance_cents": 5_000 })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    send(&ctx.app, "PUT", &format!("/users/{}/tags/vip", user.id), None).await;
+    send(&ctx.app, "PUT", &format!("/users/{}/identities/crm", user.id), Some(json!({ "external_id": "0015g00000abc" }))).await;
+    send(&ctx.app, "PUT", &format!("/users/{}/preferences", user.id), Some(json!({ "theme": "dark" }))).await;
+
+    let admin = ctx.admin_app();
+
+    // Act & Assert - Request
+    let (status, export) = send_as(&admin, ADMIN_TOKEN, "POST", &format!("/users/{}/export", user.id), None).await;
+    assert_eq!(status, StatusCode::ACCEPTED, "Requesting an export should be accepted");
+    assert_eq!(export["status"], "pending");
+    assert!(export["download_url"].is_null());
+    let export_path = format!("/users/{}/exports/{}", user.id, export["id"]);
+
+    // Act & Assert - Build
+    let built = privacy_service(&ctx).build_pending_exports(10).await.expect("Building exports should succeed");
+    assert_eq!(built, 1);
+    let (status, export) = send_as(&admin, ADMIN_TOKEN, "GET", &export_path, None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(export["status"], "ready");
+    let download_url = export["download_url"].as_str().expect("A ready export should have a download link");
+
+    // Act & Assert - Download
+    let (status, bundle) = send(&ctx.app, "GET", download_url, None).await;
+    assert_eq!(status, StatusCode::OK, "The signed link should download the bundle");
+    assert_eq!(bundle["user_id"], user.id);
+    assert_eq!(bundle["data"]["profile"]["name"], user.name.as_str());
+    assert_eq!(bundle["data"]["tags"], json!(["vip"]));
+    assert_eq!(bundle["data"]["external_identities"][0]["external_id"], "0015g00000abc");
+    assert_eq!(bundle["data"]["preferences"], json!({ "theme": "dark" }));
+    assert_eq!(bundle["data"]["accounts"][0]["id"], account["id"]);
+    assert_eq!(bundle["data"]["ledger_entries"][0]["amount_cents"], 5_000);
+    assert_eq!(bundle["data"]["audit_events"][0]["action"], "privacy.export_requested");
+
+    // Act & Assert - Tampering
+    let tampered = download_url.replace("signature=", "signature=x");
+    let (status, _) = send(&ctx.app, "GET", &tampered, None).await;
+    assert_eq!(status, StatusCode::FORBIDDEN, "A tampered link should be rejected");
+    let (status, _) = send_as(&admin, ADMIN_TOKEN, "GET", &format!("/users/{}/exports/999999", user.id), None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_download_link_expires() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    let user_id = UserBuilder::new().insert(&ctx.test_pool).await.id;
+    let clock = MockClock::new(Utc::now());
+    let privacy = privacy_service(&ctx).with_clock(clock.shared()).with_link_ttl(Duration::hours(1));
+    let export = privacy.request_export(user_id).await.expect("Requesting an export should succeed");
+    privacy.build_pending_exports(10).await.expect("Building exports should succeed");
+    let ready = privacy.get_export(user_id, export.id).await.expect("The export should exist");
+    assert_eq!(ready.status, ExportStatus::Ready);
+    let expires = ready.expires_at.expect("A ready export should expire").timestamp();
+    let signature = ExportSigner::new("test-key").sign(export.id, expires);
+    let query = DownloadQuery { expires, signature };
+
+    // Act
+    let before = privacy.download_export(export.id, &query).await;
+    clock.advance(Duration::hours(2));
+    let after = privacy.download_export(export.id, &query).await;
+    let after_expiry = privacy.get_export(user_id, export.id).await.expect("The export should exist");
+
+    // Assert
+    assert!(before.is_ok(), "The link should work before it expires");
+    assert!(matches!(after, Err(PrivacyError::LinkExpired)), "The link should stop working once expired");
+    assert!(after_expiry.with_download_url(&LinkBuilder::default()).download_url.is_none());
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_export_for_unknown_user() {
+    // Arrange
+    let ctx = TestContext::new().await;
+
+    // Act
+    let (status, _) = send_as(&ctx.admin_app(), ADMIN_TOKEN, "POST", "/users/999999/export", None).await;
+
+    // Assert
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_exports_require_admin() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    let user_id = UserBuilder::new().insert(&ctx.test_pool).await.id;
+    let export = privacy_service(&ctx).request_export(user_id).await.expect("Requesting an export should succeed");
+
+    // Act
+    let (request, _) = send(&ctx.app, "POST", &format!("/users/{user_id}/export"), None).await;
+    let (get, _) = send(&ctx.app, "GET", &format!("/users/{user_id}/exports/{}", export.id), None).await;
+
+    // Assert
+    assert_eq!(request, StatusCode::UNAUTHORIZED, "Requesting an export should require credentials");
+    assert_eq!(get, StatusCode::UNAUTHORIZED, "Reading an export and its download link should require credentials");
+
+    ctx.cleanup().await;
+}
+
+/// Rows per table in an erasure report
+fn erased_rows(report: &Value) -> Vec<(String, u64)> {
+    report["changes"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|change| (change["table"].as_str().unwrap_or_default().to_owned(), change["rows"].as_u64().unwrap_or_default()))
+        .filter(|(_, rows)| *rows > 0)
+        .collect()
+}
+
+#[tokio::test]
+async fn test_erase_user_keeps_ledger_and_supports_dry_run() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    let user = UserBuilder::new().insert(&ctx.test_pool).await;
+    let base = format!("/users/{}", user.id);
+    let address = json!({ "line1": "1 Main Street", "city": "Springfield", "postal_code": "12345", "country_code": "US" });
+    send(&ctx.app, "POST", &format!("{base}/addresses"), Some(address)).await;
+    send(&ctx.app, "PUT", &format!("{base}/tags/vip"), None).await;
+    let beneficiary = json!({ "name": "Erika Mustermann", "account_number": "DE89370400440532013000" });
+    send_as(&ctx.admin_app(), ADMIN_TOKEN, "POST", &format!("{base}/beneficiaries"), Some(beneficiary)).await;
+    send(&ctx.app, "PUT", &format!("{base}/identities/crm"), Some(json!({ "external_id": "0015g00000abc" }))).await;
+    send(&ctx.app, "PUT", &format!("{base}/preferences"), Some(json!({ "language": "de" }))).await;
+    let (_, account) = send_as(&ctx.admin_app(), ADMIN_TOKEN, "POST", "/accounts", Some(json!({ "user_id": user.id, "initial_balance_cents": 5_000 }))).await;
+    let account_path = format!("/accounts/{}", account["id"]);
+    let admin = ctx.admin_app();
+    send_as(&admin, ADMIN_TOKEN, "POST", &format!("{account_path}/freeze"), Some(json!({ "reason": "Called about her divorce" }))).await;
+
+    // Act & Assert - Dry run
+    let (status, report) = send_as(&admin, ADMIN_TOKEN, "POST", &format!("{base}/erase?dry_run=true"), None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(report["dry_run"], true);
+    let expected = [
+        ("users", 1),
+        ("addresses", 1),
+        ("user_tags", 1),
+        ("beneficiaries", 1),
+        ("external_identities", 1),
+        ("user_preferences", 1),
+        ("accounts", 1),
+        ("audit_events", 1),
+        ("users_history", 1),
+    ]
+        .map(|(table, rows)| (table.to_owned(), rows));
+    assert_eq!(erased_rows(&report), expected);
+    let (_, unchanged) = send(&ctx.app, "GET", &base, None).await;
+    assert_eq!(unchanged["name"], user.name.as_str(), "A dry run should change nothing");
+
+    // Act & Assert - Erase
+    let (status, report) = send_as(&admin, ADMIN_TOKEN, "POST", &format!("{base}/erase"), None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(erased_rows(&report), expected);
+
+    let (_, erased) = send(&ctx.app, "GET", &base, None).await;
+    assert_eq!(erased["name"], "[erased]");
+    assert_eq!(erased["status"], "archived");
+    let (_, addresses) = send(&ctx.app, "GET", &format!("{base}/addresses"), None).await;
+    assert_eq!(addresses, json!([]));
+    let (_, account) = send_as(&admin, ADMIN_TOKEN, "GET", &account_path, None).await;
+    assert_eq!(account["balance_cents"], 5_000, "Balances should be kept");
+    assert_eq!(account["frozen_reason"], "[erased]");
+    let accounts = AccountService::new(ctx.test_pool.clone(), UserService::new(ctx.test_pool.clone()));
+    let verification = accounts.verify_ledger().await.expect("Ledger verification should succeed");
+    assert!(verification.consistent, "The ledger should stay consistent");
+    let (_, activity) = send_as(&admin, ADMIN_TOKEN, "GET", &format!("{base}/activity"), None).await;
+    assert_eq!(activity["entries"][0]["action"], "privacy.user_erased");
+    assert_eq!(activity["entries"][1]["details"], json!({}), "Audit details should be blanked");
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_erase_unknown_user() {
+    // Arrange
+    let ctx = TestContext::new().await;
+
+    // Act
+    let (status, _) = send_as(&ctx.admin_app(), ADMIN_TOKEN, "POST", "/users/999999/erase?dry_run=true", None).await;
+
+    // Assert
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_erasure_requires_admin() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    let user = UserBuilder::new().insert(&ctx.test_pool).await;
+
+    // Act
+    let (dry_run, _) = send(&ctx.app, "POST", &format!("/users/{}/erase?dry_run=true", user.id), None).await;
+    let (erase, _) = send(&ctx.app, "POST", &format!("/users/{}/erase", user.id), None).await;
+    let (_, unchanged) = send(&ctx.app, "GET", &format!("/users/{}", user.id), None).await;
+
+    // Assert
+    assert_eq!(dry_run, StatusCode::UNAUTHORIZED, "Erasure reports should require credentials");
+    assert_eq!(erase, StatusCode::UNAUTHORIZED, "Erasure should require credentials");
+    assert_eq!(unchanged["name"], user.name.as_str(), "A rejected erasure should change nothing");
+
+    ctx.cleanup().await;
+}
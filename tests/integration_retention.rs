@@ -0,0 +1,76 @@
+//! Integration tests for data retention
+//!
+//! Verifies that purges delete only rows past their policy, in batches, and
+//! leave pending data exports alone.
+
+mod common;
+
+use chrono::Duration;
+use common::TestContext;
+use rust_kickstart::retention::{PurgeOutcome, RetentionTarget};
+use rust_kickstart::testing::UserBuilder;
+use rust_kickstart::{RetentionPolicy, RetentionService};
+
+async fn count(ctx: &TestContext, table: &str) -> i64 {
+    sqlx::query_scalar(&format!("SELECT COUNT(*) FROM {table}"))
+        .fetch_one(&ctx.test_pool)
+        .await
+        .expect("Failed to count rows")
+}
+
+#[tokio::test]
+async fn test_purge_deletes_rows_past_their_policy() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    let user_id = UserBuilder::new().insert(&ctx.test_pool).await.id;
+    sqlx::query(
+        "INSERT INTO audit_events (user_id, action, occurred_at)
+         SELECT $1, 'test.old', NOW() - INTERVAL '100 days' FROM generate_series(1, 5)
+         UNION ALL SELECT $1, 'test.recent', NOW() - INTERVAL '1 day'",
+    )
+    .bind(user_id)
+    .execute(&ctx.test_pool)
+    .await
+    .expect("Failed to insert audit events");
+    sqlx::query(
+        "INSERT INTO data_exports (user_id, status, completed_at, expires_at) VALUES
+         ($1, 'ready', NOW() - INTERVAL '40 days', NOW() - INTERVAL '39 days'),
+         ($1, 'ready', NOW(), NOW() + INTERVAL '1 day')",
+    )
+    .bind(user_id)
+    .execute(&ctx.test_pool)
+    .await
+    .expect("Failed to insert data exports");
+    sqlx::query("INSERT INTO data_exports (user_id, requested_at) VALUES ($1, NOW() - INTERVAL '90 days')")
+        .bind(user_id)
+        .execute(&ctx.test_pool)
+        .await
+        .expect("Failed to insert pending data export");
+    let retention = RetentionService::new(
+        ctx.test_pool.clone(),
+        vec![
+            RetentionPolicy { target: RetentionTarget::AuditEvents, max_age: Duration::days(90) },
+            RetentionPolicy { target: RetentionTarget::DataExports, max_age: Duration::days(30) },
+        ],
+    )
+    .with_batch_size(2);
+
+    // Act
+    let outcomes = retention.purge().await.expect("Purge should succeed");
+    let again = retention.purge().await.expect("Purge should succeed");
+
+    // Assert
+    assert_eq!(
+        outcomes,
+        [
+            PurgeOutcome { target: RetentionTarget::AuditEvents, purged: 5 },
+            PurgeOutcome { target: RetentionTarget::DataExports, purged: 1 },
+        ],
+        "Old rows should be purged across several batches"
+    );
+    assert!(again.iter().all(|outcome| outcome.purged == 0), "A second purge should find nothing");
+    assert_eq!(count(&ctx, "audit_events").await, 1, "Recent audit events should be kept");
+    assert_eq!(count(&ctx, "data_exports").await, 2, "Unexpired and pending exports should be kept");
+
+    ctx.cleanup().await;
+}
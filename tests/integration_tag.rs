@@ -0,0 +1,79 @@
+//! Integration tests for user tags
+//!
+//! Verifies attaching/detaching tags, listing a user's tags, and autocompletion.
+
+mod common;
+
+use axum::http::StatusCode;
+use common::TestContext;
+use rust_kickstart::testing::{UserBuilder, assert_validation_error, send};
+
+#[tokio::test]
+async fn test_attach_list_and_detach_tags() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    let user_id = UserBuilder::new().name("Ann Lee").insert(&ctx.test_pool).await.id;
+
+    // Act & Assert - Attach (twice, idempotent)
+    let (status, tag) = send(&ctx.app, "PUT", &format!("/users/{user_id}/tags/VIP"), None).await;
+    assert_eq!(status, StatusCode::OK, "Attaching a tag should succeed");
+    assert_eq!(tag["name"], "vip", "Tag names should be normalized");
+    let (status, _) = send(&ctx.app, "PUT", &format!("/users/{user_id}/tags/vip"), None).await;
+    assert_eq!(status, StatusCode::OK, "Attaching the same tag again should be a no-op");
+    send(&ctx.app, "PUT", &format!("/users/{user_id}/tags/beta"), None).await;
+
+    // Act & Assert - List
+    let (status, tags) = send(&ctx.app, "GET", &format!("/users/{user_id}/tags"), None).await;
+    assert_eq!(status, StatusCode::OK);
+    let names: Vec<&str> = tags.as_array().unwrap().iter().map(|t| t["name"].as_str().unwrap()).collect();
+    assert_eq!(names, vec!["beta", "vip"], "Tags should be listed alphabetically");
+
+    // Act & Assert - Detach
+    let (status, _) = send(&ctx.app, "DELETE", &format!("/users/{user_id}/tags/vip"), None).await;
+    assert_eq!(status, StatusCode::OK, "Detaching an attached tag should succeed");
+    let (status, _) = send(&ctx.app, "DELETE", &format!("/users/{user_id}/tags/vip"), None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND, "Detaching a missing tag should be 404");
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_attach_invalid_tag() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    let user_id = UserBuilder::new().name("Ann Lee").insert(&ctx.test_pool).await.id;
+
+    // Act
+    let (status, body) = send(&ctx.app, "PUT", &format!("/users/{user_id}/tags/not%20valid"), None).await;
+
+    // Assert
+    assert_eq!(status, StatusCode::BAD_REQUEST, "Invalid tag names should be rejected");
+    assert_validation_error(&body, "tag");
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_tag_autocomplete_orders_by_usage() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    let first = UserBuilder::new().name("Ann Lee").insert(&ctx.test_pool).await.id;
+    let second = UserBuilder::new().name("Bob Ray").insert(&ctx.test_pool).await.id;
+    send(&ctx.app, "PUT", &format!("/users/{first}/tags/vip"), None).await;
+    send(&ctx.app, "PUT", &format!("/users/{second}/tags/vip"), None).await;
+    send(&ctx.app, "PUT", &format!("/users/{first}/tags/visitor"), None).await;
+    send(&ctx.app, "PUT", &format!("/users/{first}/tags/beta"), None).await;
+
+    // Act
+    let (status, suggestions) = send(&ctx.app, "GET", "/tags/autocomplete?prefix=vi", None).await;
+
+    // Assert
+    assert_eq!(status, StatusCode::OK);
+    let suggestions = suggestions.as_array().unwrap();
+    assert_eq!(suggestions.len(), 2, "Only tags matching the prefix should be suggested");
+    assert_eq!(suggestions[0]["name"], "vip", "Most used tag should come first");
+    assert_eq!(suggestions[0]["user_count"], 2);
+    assert_eq!(suggestions[1]["name"], "visitor");
+
+    ctx.cleanup().await;
+}
@@ -0,0 +1,98 @@
+//! Integration tests for per-request transactions
+//!
+//! Verifies that writes made through `Tx` are committed on 2xx responses and
+//! rolled back otherwise, and that `POST /users` runs in the request's transaction.
+
+mod common;
+
+use axum::{Router, http::StatusCode, middleware, routing::post};
+use common::TestContext;
+use rust_kickstart::db::ColumnRename;
+use rust_kickstart::testing::{UserBuilder, send};
+use rust_kickstart::{AppState, Tx, UserService, tx};
+use sqlx::PgPool;
+
+/// Router creating two users in one transaction, then answering with `status`
+fn two_step_app(pool: &PgPool, status: StatusCode) -> Router {
+    let users = UserService::new(pool.clone());
+    let handler = move |mut tx: Tx| async move {
+        let first = UserBuilder::new().name("First Step").build();
+        users.create_user_in(tx.conn().await.expect("Failed to begin"), first).await.expect("Failed to create user");
+        let second = UserBuilder::new().name("Second Step").build();
+        users.create_user_in(tx.conn().await.expect("Failed to begin"), second).await.expect("Failed to create user");
+        status
+    };
+    Router::new()
+        .route("/two-step", post(handler))
+        .layer(middleware::from_fn_with_state(pool.clone(), tx::transactions))
+}
+
+async fn user_count(pool: &PgPool) -> i64 {
+    sqlx::query_scalar("SELECT COUNT(*) FROM users").fetch_one(pool).await.expect("Failed to count users")
+}
+
+#[tokio::test]
+async fn test_transaction_commits_on_success() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    let app = two_step_app(&ctx.test_pool, StatusCode::CREATED);
+
+    // Act
+    let (status, _) = send(&app, "POST", "/two-step", None).await;
+
+    // Assert
+    assert_eq!(status, StatusCode::CREATED);
+    assert_eq!(user_count(&ctx.test_pool).await, 2, "Both users should be committed");
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_transaction_rolls_back_on_error_status() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    let app = two_step_app(&ctx.test_pool, StatusCode::CONFLICT);
+
+    // Act
+    let (status, _) = send(&app, "POST", "/two-step", None).await;
+
+    // Assert
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(user_count(&ctx.test_pool).await, 0, "Neither user should be persisted");
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_app_routes_run_behind_the_transaction_layer() {
+    // Arrange
+    let ctx = TestContext::new().await;
+
+    // Act
+    let (status, _) = send(&ctx.app, "POST", "/users", Some(UserBuilder::new().json())).await;
+
+    // Assert
+    assert_eq!(status, StatusCode::OK, "The user should be created in the request's transaction");
+    assert_eq!(user_count(&ctx.test_pool).await, 1);
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_create_user_rolls_back_when_a_later_step_fails() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    let rename = ColumnRename::parse("users", "id", "name:missing_column").expect("Valid rename");
+    let app = AppState::builder(ctx.test_pool.clone())
+        .service(UserService::new(ctx.test_pool.clone()).with_dual_write(rename))
+        .into_router();
+
+    // Act
+    let (status, _) = send(&app, "POST", "/users", Some(UserBuilder::new().json())).await;
+
+    // Assert
+    assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR, "The failed dual-write copy should fail the request");
+    assert_eq!(user_count(&ctx.test_pool).await, 0, "The inserted user should be rolled back with it");
+
+    ctx.cleanup().await;
+}
@@ -7,7 +7,16 @@ mod common;
 
 use axum::body::Body;
 use axum::http::{Request, StatusCode};
-use common::TestContext;
+use common::{ADMIN_TOKEN, TestContext};
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use rust_kickstart::clock::MockClock;
+use rust_kickstart::db::DbErrorKind;
+use rust_kickstart::ids::SequentialIdGenerator;
+use rust_kickstart::pagination::PageLimits;
+use rust_kickstart::user::domain::{BulkUpdateUsers, Metadata, PaginationParams, UserError};
+use rust_kickstart::user::validation::{DenylistPolicy, ValidationContext, ValidationPolicy};
+use rust_kickstart::{AppProviders, UserReadPort, UserService, UserWritePort, create_app_with_providers};
+use rust_kickstart::testing::{SpanCapture, UserBuilder, send, send_as};
 use http_body_util::BodyExt;
 use serde_json::{Value, json};
 use tower::ServiceExt;
@@ -67,6 +76,31 @@ async fn test_create_user() {
     ctx.cleanup().await;
 }
 
+#[tokio::test]
+async fn test_create_user_is_traced() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    let capture = SpanCapture::new();
+    let guard = capture.install();
+
+    // Act
+    let (status, created) = send(&ctx.app, "POST", "/users", Some(UserBuilder::new().name("Ann Lee").json())).await;
+    drop(guard);
+
+    // Assert
+    assert_eq!(status, StatusCode::OK);
+    capture.assert_span("request", &["method", "uri", "client_ip"]);
+    capture.assert_span("create_user_handler", &["user_name", "user_age"]);
+    let request = capture.find_span("request", &[]).unwrap();
+    assert_eq!((request.field("method"), request.field("uri")), (Some("POST"), Some("/users")));
+    let handler = capture.find_span("create_user_handler", &[]).unwrap();
+    assert_eq!(handler.field("user_name"), Some("Ann Lee"));
+    assert_eq!(handler.parent.as_deref(), Some("request"), "Handler spans should nest under the request span");
+    assert!(created["id"].is_number());
+
+    ctx.cleanup().await;
+}
+
 #[tokio::test]
 async fn test_get_user_not_found() {
     // Arrange
@@ -86,6 +120,124 @@ async fn test_get_user_not_found() {
     ctx.cleanup().await;
 }
 
+#[tokio::test]
+async fn test_get_user_honours_if_modified_since() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    let clock = MockClock::new(Utc.with_ymd_and_hms(2025, 3, 1, 9, 0, 0).unwrap());
+    let app = create_app_with_providers(
+        ctx.test_pool.clone(),
+        AppProviders { clock: clock.shared(), ..AppProviders::default() },
+    );
+    let (_, user) = send(&app, "POST", "/users", Some(UserBuilder::new().name("Ann Lee").json())).await;
+    let uri = format!("/users/{}", user["id"]);
+    let get = |since: Option<&str>| {
+        let mut request = Request::builder().uri(uri.as_str());
+        if let Some(since) = since {
+            request = request.header("if-modified-since", since);
+        }
+        app.clone().oneshot(request.body(Body::empty()).unwrap())
+    };
+
+    // Act
+    let fresh = get(None).await.unwrap();
+    let cached = get(Some("Sat, 01 Mar 2025 09:00:00 GMT")).await.unwrap();
+    let stale = get(Some("Sat, 01 Mar 2025 08:59:59 GMT")).await.unwrap();
+    clock.advance(Duration::hours(1));
+    send(&app, "PUT", &uri, Some(json!({"age": 31}))).await;
+    let updated = get(Some("Sat, 01 Mar 2025 09:00:00 GMT")).await.unwrap();
+
+    // Assert
+    assert_eq!(fresh.status(), StatusCode::OK);
+    assert_eq!(fresh.headers()["last-modified"], "Sat, 01 Mar 2025 09:00:00 GMT");
+    assert_eq!(cached.status(), StatusCode::NOT_MODIFIED, "An up-to-date copy should not be resent");
+    assert_eq!(cached.headers()["last-modified"], "Sat, 01 Mar 2025 09:00:00 GMT");
+    assert!(cached.into_body().collect().await.unwrap().to_bytes().is_empty(), "304 should have no body");
+    assert_eq!(stale.status(), StatusCode::OK, "An older copy should get the full response");
+    assert_eq!(updated.status(), StatusCode::OK, "Updating the user should move Last-Modified forward");
+    assert_eq!(updated.headers()["last-modified"], "Sat, 01 Mar 2025 10:00:00 GMT");
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_updated_at_follows_the_injected_clock() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    let start = Utc.with_ymd_and_hms(2025, 3, 1, 9, 0, 0).unwrap();
+    let clock = MockClock::new(start);
+    let service = UserService::new(ctx.test_pool.clone()).with_clock(clock.shared());
+    let user = service.create_user(UserBuilder::new().age(30).build()).await.expect("User should be created");
+    let change = |age| rust_kickstart::UpdateUser { name: None, age, metadata: None };
+
+    // Act
+    clock.advance(Duration::hours(1));
+    let unchanged = service.update_user(user.id, change(Some(30))).await.expect("Update should succeed");
+    let updated = service.update_user(user.id, change(Some(31))).await.expect("Update should succeed");
+    clock.advance(Duration::hours(1));
+    let bulk = service
+        .update_users(BulkUpdateUsers { ids: vec![user.id], changes: change(Some(32)) })
+        .await
+        .expect("Bulk update should succeed");
+    clock.advance(Duration::hours(1));
+    let suspended = service.suspend_user(user.id).await.expect("Suspension should succeed");
+
+    // Assert
+    assert_eq!(user.updated_at, start);
+    assert_eq!(unchanged.updated_at, start, "An update that changes nothing should keep updated_at");
+    assert_eq!(updated.updated_at, start + Duration::hours(1));
+    assert_eq!(bulk.results[0].user.as_ref().expect("User should be returned").updated_at, start + Duration::hours(2));
+    assert_eq!(suspended.updated_at, start + Duration::hours(3), "Status transitions should stamp the clock's time");
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_only_successful_user_reads_are_cacheable() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    let user = UserBuilder::new().insert(&ctx.test_pool).await;
+    let request = |method: &str, uri: String| Request::builder().method(method).uri(uri).body(Body::empty()).unwrap();
+
+    // Act
+    let found = ctx.app.clone().oneshot(request("GET", format!("/users/{}", user.id))).await.unwrap();
+    let missing = ctx.app.clone().oneshot(request("GET", "/users/999".to_owned())).await.unwrap();
+    let deleted = ctx.app.clone().oneshot(request("DELETE", format!("/users/{}", user.id))).await.unwrap();
+
+    // Assert
+    assert_eq!(found.headers()["cache-control"], "public, max-age=60");
+    assert_eq!(found.headers()["vary"], "accept");
+    assert_eq!(missing.headers()["cache-control"], "no-store", "Errors should never be cached");
+    assert_eq!(deleted.headers()["cache-control"], "no-store", "Writes should never be cached");
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_options_describes_route_capabilities() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    let options = |uri: &str| Request::builder().method("OPTIONS").uri(uri).body(Body::empty()).unwrap();
+
+    // Act
+    let resource = ctx.app.clone().oneshot(options("/users/42")).await.unwrap();
+    let allow = resource.headers()["allow"].clone();
+    let resource: Value = serde_json::from_slice(&resource.into_body().collect().await.unwrap().to_bytes()).unwrap();
+    let (status, collection) = send(&ctx.app, "OPTIONS", "/users", None).await;
+
+    // Assert
+    assert_eq!(allow, "GET, PUT, DELETE, HEAD, OPTIONS");
+    assert_eq!(resource["path"], "/users/{id}");
+    assert_eq!(resource["accepts"], json!(["application/cbor", "application/json", "application/msgpack"]));
+    assert!(resource["produces"].as_array().unwrap().contains(&json!("application/xml")));
+    assert_eq!(status, StatusCode::OK);
+    let get_parameters = collection["query_parameters"]["GET"].as_array().unwrap();
+    assert!(get_parameters.iter().any(|parameter| parameter["name"] == "limit"), "Documented query parameters should be listed");
+    assert!(collection["query_parameters"].get("POST").is_none());
+
+    ctx.cleanup().await;
+}
+
 #[tokio::test]
 async fn test_full_crud_workflow() {
     // Arrange
@@ -153,10 +305,11 @@ async fn test_full_crud_workflow() {
     let delete_request = Request::builder()
         .method("DELETE")
         .uri(format!("/users/{user_id}"))
+        .header("authorization", format!("Bearer {ADMIN_TOKEN}"))
         .body(Body::empty())
         .unwrap();
 
-    let delete_response = ctx.app.clone().oneshot(delete_request).await.unwrap();
+    let delete_response = ctx.admin_app().oneshot(delete_request).await.unwrap();
     let delete_status = delete_response.status();
     assert_eq!(delete_status, StatusCode::OK, "User deletion should succeed");
 
@@ -231,4 +384,941 @@ async fn test_invalid_pagination_token() {
     assert_eq!(response.status(), StatusCode::BAD_REQUEST);
 
     ctx.cleanup().await;
-}
\ No newline at end of file
+}
+
+#[tokio::test]
+async fn test_pagination_estimated_total() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    for name in ["Alice", "Bob", "Carol"] {
+        let user = UserBuilder::new().name(name).insert(&ctx.test_pool).await;
+        if name != "Carol" {
+            send(&ctx.app, "PUT", &format!("/users/{}/tags/vip", user.id), None).await;
+        }
+    }
+    let (_, unanalyzed) = send(&ctx.app, "GET", "/users?limit=1&include_estimated_total=true", None).await;
+    sqlx::query("ANALYZE users").execute(&ctx.test_pool).await.expect("Failed to analyze users");
+
+    // Act
+    let (_, plain) = send(&ctx.app, "GET", "/users?limit=1", None).await;
+    let (_, estimated) = send(&ctx.app, "GET", "/users?limit=1&include_estimated_total=true", None).await;
+    let (_, tagged) = send(&ctx.app, "GET", "/users?limit=1&tag=vip&include_estimated_total=true", None).await;
+
+    // Assert
+    assert!(plain.get("estimated_total").is_none(), "The estimate should be opt-in");
+    assert_eq!(unanalyzed["estimated_total"], 3, "Tables without statistics should be counted");
+    assert_eq!(estimated["estimated_total"], 3);
+    assert_eq!(estimated["count"], 1);
+    assert_eq!(tagged["estimated_total"], 2);
+    let next = estimated["_links"]["next"]["href"].as_str().expect("There should be a next link");
+    assert!(next.contains("include_estimated_total=true"), "Page links should keep asking for the estimate");
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_metadata_create_update_and_filter() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    let payload = UserBuilder::new().name("Alice").metadata("department", "eng").metadata("level", "senior").json();
+    let (status, alice) = send(&ctx.app, "POST", "/users", Some(payload)).await;
+    assert_eq!(status, StatusCode::OK);
+    let bob = UserBuilder::new().name("Bob").metadata("department", "eng").insert(&ctx.test_pool).await;
+    UserBuilder::new().name("Carol").metadata("department", "sales").insert(&ctx.test_pool).await;
+    UserBuilder::new().name("Dave").insert(&ctx.test_pool).await;
+
+    // Act
+    let (_, eng) = send(&ctx.app, "GET", "/users?metadata.department=eng&limit=1&include_estimated_total=true", None).await;
+    let (_, senior) = send(&ctx.app, "GET", "/users?metadata.department=eng&metadata.level=senior", None).await;
+    let (status, updated) = send(
+        &ctx.app,
+        "PUT",
+        &format!("/users/{}", bob.id),
+        Some(json!({ "metadata": { "department": "sales" } })),
+    )
+    .await;
+    let (_, sales) = send(&ctx.app, "GET", "/users?metadata.department=sales", None).await;
+
+    // Assert
+    assert_eq!(alice["metadata"], json!({ "department": "eng", "level": "senior" }));
+    assert_eq!(eng["estimated_total"], 2, "Metadata filters should be counted exactly");
+    assert_eq!(eng["count"], 1);
+    let next = eng["_links"]["next"]["href"].as_str().expect("There should be a next link");
+    assert!(next.contains("metadata.department=eng"), "Page links should keep the metadata filter");
+    assert_eq!(senior["count"], 1);
+    assert_eq!(senior["users"][0]["name"], "Alice");
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(updated["metadata"], json!({ "department": "sales" }));
+    assert_eq!(updated["name"], "Bob", "Updating metadata should keep the other fields");
+    assert_eq!(sales["count"], 2);
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_invalid_metadata_is_rejected() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    let too_many: serde_json::Map<String, Value> = (0..51).map(|i| (format!("k{i}"), json!("v"))).collect();
+
+    // Act
+    let (bad_key, body) = send(&ctx.app, "POST", "/users", Some(json!({ "name": "Alice", "age": 30, "metadata": { "team lead": "x" } }))).await;
+    let (many_keys, _) = send(&ctx.app, "POST", "/users", Some(json!({ "name": "Alice", "age": 30, "metadata": too_many }))).await;
+    let (long_value, _) = send(&ctx.app, "POST", "/users", Some(json!({ "name": "Alice", "age": 30, "metadata": { "note": "x".repeat(501) } }))).await;
+    let (not_string, _) = send(&ctx.app, "POST", "/users", Some(json!({ "name": "Alice", "age": 30, "metadata": { "count": 3 } }))).await;
+    let (bad_filter, _) = send(&ctx.app, "GET", "/users?metadata.bad%20key=x", None).await;
+
+    // Assert
+    assert_eq!(bad_key, StatusCode::BAD_REQUEST);
+    assert_eq!(body["errors"][0]["field"], "metadata.team lead");
+    assert_eq!(many_keys, StatusCode::BAD_REQUEST);
+    assert_eq!(long_value, StatusCode::BAD_REQUEST);
+    assert!(not_string.is_client_error(), "Metadata values must be strings");
+    assert_eq!(bad_filter, StatusCode::BAD_REQUEST);
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_pagination_descending_with_prev_tokens() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    let mut ids = Vec::new();
+    for name in ["Alice", "Bob", "Carol", "Dave", "Erin"] {
+        ids.push(UserBuilder::new().name(name).insert(&ctx.test_pool).await.id);
+    }
+    let page_ids = |page: &Value| -> Vec<i64> {
+        page["users"].as_array().unwrap().iter().map(|u| u["id"].as_i64().unwrap()).collect()
+    };
+    let expected = |range: &[i32]| -> Vec<i64> { range.iter().map(|&id| i64::from(id)).collect() };
+
+    // Act
+    let (_, first) = send(&ctx.app, "GET", "/users?order=desc&limit=2", None).await;
+    let next = first["next_token"].as_str().expect("First page should have a next token");
+    let (_, second) = send(&ctx.app, "GET", &format!("/users?order=desc&limit=2&next_token={next}"), None).await;
+    let prev = second["prev_token"].as_str().expect("Second page should have a prev token");
+    let (_, back) = send(&ctx.app, "GET", &format!("/users?order=desc&limit=2&prev_token={prev}"), None).await;
+    let (both, _) = send(&ctx.app, "GET", &format!("/users?next_token={next}&prev_token={prev}"), None).await;
+
+    // Assert
+    assert_eq!(page_ids(&first), expected(&[ids[4], ids[3]]));
+    assert!(first.get("prev_token").is_none(), "The first page has nothing before it");
+    assert_eq!(page_ids(&second), expected(&[ids[2], ids[1]]));
+    assert_eq!(page_ids(&back), page_ids(&first), "Paging back should return the first page");
+    assert!(back.get("prev_token").is_none());
+    assert_eq!(back["next_token"], first["next_token"]);
+    assert_eq!(both, StatusCode::BAD_REQUEST, "Both tokens at once should be rejected");
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_stream_users_ndjson() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    for (name, age) in [("Ann Lee", 21), ("Bob Ray", 32), ("Cid Moe", 43)] {
+        let request = Request::builder()
+            .method("POST")
+            .uri("/users")
+            .header("content-type", "application/json")
+            .body(Body::from(json!({ "name": name, "age": age }).to_string()))
+            .unwrap();
+        let response = ctx.app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK, "User creation should succeed");
+    }
+
+    let request = Request::builder()
+        .uri("/users/stream")
+        .body(Body::empty())
+        .unwrap();
+
+    // Act
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    let status = response.status();
+    let content_type = response.headers()["content-type"].to_str().unwrap().to_owned();
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let lines: Vec<Value> = std::str::from_utf8(&body)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+
+    // Assert
+    assert_eq!(status, StatusCode::OK, "Should return OK status");
+    assert_eq!(content_type, "application/x-ndjson", "Should be served as NDJSON");
+    assert_eq!(lines.len(), 3, "Should stream one line per user");
+    assert_eq!(lines[0]["name"], "Ann Lee", "Users should be streamed in creation order");
+    assert_eq!(lines[2]["name"], "Cid Moe", "Users should be streamed in creation order");
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_bulk_delete_users_with_query_ids() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    let first_id = UserBuilder::new().name("Ann Lee").age(21).insert(&ctx.test_pool).await.id;
+    let second_id = UserBuilder::new().name("Bob Ray").age(32).insert(&ctx.test_pool).await.id;
+    let missing_id = second_id + 1000;
+    let request = Request::builder()
+        .method("DELETE")
+        .header("authorization", format!("Bearer {ADMIN_TOKEN}"))
+        .uri(format!("/users?ids={first_id},{second_id},{missing_id}"))
+        .body(Body::empty())
+        .unwrap();
+
+    // Act
+    let response = ctx.admin_app().oneshot(request).await.unwrap();
+    let status = response.status();
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let result: Value = serde_json::from_slice(&body).unwrap();
+
+    // Assert
+    assert_eq!(status, StatusCode::OK, "Bulk delete should succeed");
+    assert_eq!(result["succeeded"], 2, "Two users should be deleted");
+    assert_eq!(result["not_found"], 1, "One ID should be reported as not found");
+    assert_eq!(result["results"][0]["status"], "deleted");
+    assert_eq!(result["results"][2]["id"], missing_id);
+    assert_eq!(result["results"][2]["status"], "not_found");
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_bulk_delete_users_with_json_body() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    let user_id = UserBuilder::new().name("Ann Lee").age(21).insert(&ctx.test_pool).await.id;
+    let request = Request::builder()
+        .method("DELETE")
+        .header("authorization", format!("Bearer {ADMIN_TOKEN}"))
+        .uri("/users")
+        .header("content-type", "application/json")
+        .body(Body::from(json!({ "ids": [user_id] }).to_string()))
+        .unwrap();
+
+    // Act
+    let response = ctx.admin_app().oneshot(request).await.unwrap();
+    let status = response.status();
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let result: Value = serde_json::from_slice(&body).unwrap();
+
+    // Assert
+    assert_eq!(status, StatusCode::OK, "Bulk delete should succeed");
+    assert_eq!(result["succeeded"], 1, "The user should be deleted");
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_bulk_delete_users_without_ids() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    let request = Request::builder()
+        .method("DELETE")
+        .header("authorization", format!("Bearer {ADMIN_TOKEN}"))
+        .uri("/users")
+        .body(Body::empty())
+        .unwrap();
+
+    // Act
+    let response = ctx.admin_app().oneshot(request).await.unwrap();
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST, "Missing IDs should be rejected");
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_bulk_update_users() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    let first_id = UserBuilder::new().name("Ann Lee").age(21).insert(&ctx.test_pool).await.id;
+    let second_id = UserBuilder::new().name("Bob Ray").age(32).insert(&ctx.test_pool).await.id;
+    let missing_id = second_id + 1000;
+    let request = Request::builder()
+        .method("PATCH")
+        .header("authorization", format!("Bearer {ADMIN_TOKEN}"))
+        .uri("/users")
+        .header("content-type", "application/json")
+        .body(Body::from(
+            json!({ "ids": [first_id, second_id, missing_id], "changes": { "age": 40 } }).to_string(),
+        ))
+        .unwrap();
+
+    // Act
+    let response = ctx.admin_app().oneshot(request).await.unwrap();
+    let status = response.status();
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let result: Value = serde_json::from_slice(&body).unwrap();
+
+    // Assert
+    assert_eq!(status, StatusCode::OK, "Bulk update should succeed");
+    assert_eq!(result["succeeded"], 2, "Two users should be updated");
+    assert_eq!(result["not_found"], 1, "One ID should be reported as not found");
+    assert_eq!(result["results"][0]["user"]["age"], 40, "Age should be updated");
+    assert_eq!(result["results"][0]["user"]["name"], "Ann Lee", "Name should be unchanged");
+    assert_eq!(result["results"][2]["status"], "not_found");
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_bulk_operations_require_admin() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    let user = UserBuilder::new().name("Ann Lee").age(21).insert(&ctx.test_pool).await;
+    let ids = json!({ "ids": [user.id] });
+
+    // Act
+    let (delete, _) = send(&ctx.app, "DELETE", "/users", Some(ids)).await;
+    let (update, _) = send(&ctx.app, "PATCH", "/users", Some(json!({ "ids": [user.id], "changes": { "age": 40 } }))).await;
+    let (_, unchanged) = send(&ctx.app, "GET", &format!("/users/{}", user.id), None).await;
+
+    // Assert
+    assert_eq!(delete, StatusCode::UNAUTHORIZED, "Bulk deletes should require credentials");
+    assert_eq!(update, StatusCode::UNAUTHORIZED, "Bulk updates should require credentials");
+    assert_eq!(unchanged["age"], 21, "Rejected bulk operations should change nothing");
+
+    ctx.cleanup().await;
+}
+
+/// Sends a lifecycle transition request as an admin and returns the status code and JSON body
+async fn transition_user(ctx: &TestContext, user_id: i32, action: &str) -> (StatusCode, Value) {
+    send_as(&ctx.admin_app(), ADMIN_TOKEN, "POST", &format!("/users/{user_id}/{action}"), None).await
+}
+
+#[tokio::test]
+async fn test_user_lifecycle_transitions() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    let user_id = UserBuilder::new().name("Ann Lee").age(21).insert(&ctx.test_pool).await.id;
+
+    // Act & Assert - Suspend an active user
+    let (status, user) = transition_user(&ctx, user_id, "suspend").await;
+    assert_eq!(status, StatusCode::OK, "Active user should be suspendable");
+    assert_eq!(user["status"], "suspended");
+
+    // Act & Assert - Suspending twice is a conflict
+    let (status, body) = transition_user(&ctx, user_id, "suspend").await;
+    assert_eq!(status, StatusCode::CONFLICT, "Suspended user cannot be suspended again");
+    assert!(body["message"].as_str().unwrap().contains("suspended"));
+
+    // Act & Assert - Reactivate
+    let (status, user) = transition_user(&ctx, user_id, "activate").await;
+    assert_eq!(status, StatusCode::OK, "Suspended user should be reactivatable");
+    assert_eq!(user["status"], "active");
+
+    // Act & Assert - Archive is terminal
+    let (status, user) = transition_user(&ctx, user_id, "archive").await;
+    assert_eq!(status, StatusCode::OK, "Active user should be archivable");
+    assert_eq!(user["status"], "archived");
+
+    let (status, _) = transition_user(&ctx, user_id, "activate").await;
+    assert_eq!(status, StatusCode::CONFLICT, "Archived user cannot be reactivated");
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_user_lifecycle_transitions_and_deletion_require_admin() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    let user = UserBuilder::new().name("Ann Lee").age(21).insert(&ctx.test_pool).await;
+    let base = format!("/users/{}", user.id);
+
+    // Act
+    let (suspend, _) = send(&ctx.app, "POST", &format!("{base}/suspend"), None).await;
+    let (archive, _) = send(&ctx.app, "POST", &format!("{base}/archive"), None).await;
+    let (delete, _) = send(&ctx.app, "DELETE", &base, None).await;
+    let (_, unchanged) = send(&ctx.app, "GET", &base, None).await;
+
+    // Assert
+    assert_eq!(suspend, StatusCode::UNAUTHORIZED, "Suspending should require credentials");
+    assert_eq!(archive, StatusCode::UNAUTHORIZED, "Archiving should require credentials");
+    assert_eq!(delete, StatusCode::UNAUTHORIZED, "Deleting should require credentials");
+    assert_eq!(unchanged["status"], "active", "Rejected transitions should change nothing");
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_user_lifecycle_transition_not_found() {
+    // Arrange
+    let ctx = TestContext::new().await;
+
+    // Act
+    let (status, _) = transition_user(&ctx, 999, "suspend").await;
+
+    // Assert
+    assert_eq!(status, StatusCode::NOT_FOUND, "Transition of a missing user should be 404");
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_user_lifecycle_transition_publishes_event() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    let event_bus = rust_kickstart::EventBus::new();
+    let mut events = event_bus.subscribe();
+    let user_service =
+        rust_kickstart::UserService::with_event_bus(ctx.get_test_pool().clone(), event_bus);
+    let user = user_service
+        .create_user(rust_kickstart::CreateUser { name: "Ann Lee".to_owned(), age: 21, metadata: None })
+        .await
+        .unwrap();
+
+    // Act
+    user_service.suspend_user(user.id).await.unwrap();
+
+    // Assert
+    match events.recv().await.unwrap() {
+        rust_kickstart::DomainEvent::UserStatusChanged { user_id, from, to, .. } => {
+            assert_eq!(user_id, user.id);
+            assert_eq!(from.to_string(), "active");
+            assert_eq!(to.to_string(), "suspended");
+        },
+    }
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_user_lifecycle_transition_conflicts_when_status_changes_concurrently() {
+    // Arrange - Hold an uncommitted suspension so the archive reads `active` and then waits on the row
+    let ctx = TestContext::new().await;
+    let event_bus = rust_kickstart::EventBus::new();
+    let mut events = event_bus.subscribe();
+    let user_service = UserService::with_event_bus(ctx.get_test_pool().clone(), event_bus);
+    let user_id = UserBuilder::new().name("Ann Lee").age(21).insert(&ctx.test_pool).await.id;
+    let mut concurrent = ctx.test_pool.begin().await.expect("Failed to begin transaction");
+    sqlx::query("UPDATE users SET status = 'suspended' WHERE id = $1")
+        .bind(user_id)
+        .execute(&mut *concurrent)
+        .await
+        .expect("Failed to suspend user");
+
+    // Act
+    let archive = tokio::spawn(async move { user_service.archive_user(user_id).await });
+    tokio::time::timeout(std::time::Duration::from_secs(10), async {
+        loop {
+            let waiting: i64 = sqlx::query_scalar(
+                "SELECT COUNT(*) FROM pg_stat_activity WHERE wait_event_type = 'Lock' AND query LIKE 'UPDATE users SET status%'",
+            )
+            .fetch_one(&ctx.test_pool)
+            .await
+            .expect("Failed to inspect activity");
+            if waiting > 0 {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        }
+    })
+    .await
+    .expect("The archive should wait on the suspended row");
+    concurrent.commit().await.expect("Failed to commit suspension");
+    let result = archive.await.expect("The archive task should complete");
+
+    // Assert - The event would otherwise claim an `active` → `archived` transition from a suspended user
+    assert!(
+        matches!(result, Err(UserError::InvalidStatusTransition { .. })),
+        "A status changed after the read should be a conflict, got {result:?}"
+    );
+    assert!(events.try_recv().is_err(), "No event should be published for the conflict");
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_filter_users_by_tag() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    let vip_id = UserBuilder::new().name("Ann Lee").age(21).insert(&ctx.test_pool).await.id;
+    UserBuilder::new().name("Bob Ray").age(32).insert(&ctx.test_pool).await;
+    let attach_request = Request::builder()
+        .method("PUT")
+        .uri(format!("/users/{vip_id}/tags/VIP"))
+        .body(Body::empty())
+        .unwrap();
+    let attach_response = ctx.app.clone().oneshot(attach_request).await.unwrap();
+    assert_eq!(attach_response.status(), StatusCode::OK, "Tag attach should succeed");
+
+    let request = Request::builder()
+        .uri("/users?tag=vip")
+        .body(Body::empty())
+        .unwrap();
+
+    // Act
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    let status = response.status();
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let paginated_response: Value = serde_json::from_slice(&body).unwrap();
+
+    // Assert
+    assert_eq!(status, StatusCode::OK, "Should return OK status");
+    assert_eq!(paginated_response["count"], 1, "Only the tagged user should be returned");
+    assert_eq!(paginated_response["users"][0]["id"], vip_id);
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_get_user_as_xml() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    let user_id = UserBuilder::new().name("Ann Lee").age(21).insert(&ctx.test_pool).await.id;
+    let request = Request::builder()
+        .uri(format!("/users/{user_id}"))
+        .header("accept", "application/xml")
+        .body(Body::empty())
+        .unwrap();
+
+    // Act
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    let status = response.status();
+    let content_type = response.headers()["content-type"].clone();
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let xml = String::from_utf8(body.to_vec()).unwrap();
+
+    // Assert
+    assert_eq!(status, StatusCode::OK, "Should return OK status");
+    assert_eq!(content_type, "application/xml", "Should honour the Accept header");
+    assert!(xml.starts_with("<?xml"), "Body should be an XML document");
+    assert!(xml.contains(&format!("<User><id>{user_id}</id><name>Ann Lee</name><age>21</age>")));
+    assert!(xml.contains("<status>active</status>"));
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_list_users_as_xml() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    UserBuilder::new().name("Ann Lee").age(21).insert(&ctx.test_pool).await;
+    UserBuilder::new().name("Bob Ray").age(32).insert(&ctx.test_pool).await;
+    let request = Request::builder()
+        .uri("/users")
+        .header("accept", "application/json;q=0.5, application/xml")
+        .body(Body::empty())
+        .unwrap();
+
+    // Act
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    let status = response.status();
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let xml = String::from_utf8(body.to_vec()).unwrap();
+
+    // Assert
+    assert_eq!(status, StatusCode::OK, "Should return OK status");
+    assert!(xml.contains("<PaginatedUsersResponse>"), "Root element should be the response type");
+    assert_eq!(xml.matches("<users>").count(), 2, "Each user should be its own element");
+    assert!(xml.contains("<count>2</count>"));
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_create_user_with_msgpack_body_and_cbor_response() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    let body = rmp_serde::to_vec_named(&json!({ "name": "Ann Lee", "age": 21 })).unwrap();
+    let request = Request::builder()
+        .method("POST")
+        .uri("/users")
+        .header("content-type", "application/msgpack")
+        .header("accept", "application/cbor")
+        .body(Body::from(body))
+        .unwrap();
+
+    // Act
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    let status = response.status();
+    let content_type = response.headers()["content-type"].clone();
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let created_user: Value = ciborium::from_reader(&body[..]).unwrap();
+
+    // Assert
+    assert_eq!(status, StatusCode::OK, "Should return OK status");
+    assert_eq!(content_type, "application/cbor");
+    assert_eq!(created_user["name"], "Ann Lee");
+    assert_eq!(created_user["age"], 21);
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_enveloped_responses() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    let user_id = UserBuilder::new().name("Ann Lee").age(21).insert(&ctx.test_pool).await.id;
+    let request = Request::builder()
+        .uri(format!("/users/{user_id}?envelope=true"))
+        .header("x-request-id", "req-42")
+        .body(Body::empty())
+        .unwrap();
+    let invalid_request = Request::builder()
+        .method("POST")
+        .uri("/users?envelope=true")
+        .header("content-type", "application/json")
+        .body(Body::from(json!({ "name": "", "age": 21 }).to_string()))
+        .unwrap();
+
+    // Act
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let envelope: Value = serde_json::from_slice(&body).unwrap();
+    let invalid_response = ctx.app.clone().oneshot(invalid_request).await.unwrap();
+    let invalid_status = invalid_response.status();
+    let body = invalid_response.into_body().collect().await.unwrap().to_bytes();
+    let invalid_envelope: Value = serde_json::from_slice(&body).unwrap();
+
+    // Assert
+    assert_eq!(envelope["data"]["id"], user_id, "Payload should be under `data`");
+    assert_eq!(envelope["meta"]["request_id"], "req-42", "Request ID should be echoed");
+    assert!(envelope["meta"]["duration_ms"].is_u64());
+    assert_eq!(envelope["errors"], json!([]));
+    assert_eq!(invalid_status, StatusCode::BAD_REQUEST);
+    assert!(invalid_envelope["data"].is_null());
+    assert_eq!(invalid_envelope["errors"][0]["field"], "name", "Errors should be listed under `errors`");
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_hypermedia_links() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    let first_id = UserBuilder::new().name("Ann Lee").age(21).insert(&ctx.test_pool).await.id;
+    UserBuilder::new().name("Bob Ray").age(32).insert(&ctx.test_pool).await;
+    let user_request = Request::builder()
+        .uri(format!("/users/{first_id}"))
+        .body(Body::empty())
+        .unwrap();
+    let page_request = Request::builder()
+        .uri("/users?limit=1")
+        .body(Body::empty())
+        .unwrap();
+
+    // Act
+    let response = ctx.app.clone().oneshot(user_request).await.unwrap();
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let user: Value = serde_json::from_slice(&body).unwrap();
+    let response = ctx.app.clone().oneshot(page_request).await.unwrap();
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let page: Value = serde_json::from_slice(&body).unwrap();
+
+    // Assert
+    assert_eq!(user["_links"]["self"]["href"], format!("/users/{first_id}"));
+    assert_eq!(user["_links"]["addresses"]["href"], format!("/users/{first_id}/addresses"));
+    assert_eq!(user["_links"]["tags"]["href"], format!("/users/{first_id}/tags"));
+    assert_eq!(page["_links"]["self"]["href"], "/users?limit=1");
+    assert_eq!(page["_links"]["first"]["href"], "/users?limit=1");
+    let next = page["_links"]["next"]["href"].as_str().expect("First page should link to the next one");
+    assert!(next.starts_with("/users?next_token="), "Next link should carry the cursor: {next}");
+    assert!(next.ends_with("&limit=1"), "Next link should keep the page size: {next}");
+    assert_eq!(page["users"][0]["_links"]["self"]["href"], format!("/users/{first_id}"));
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_rejects_non_positive_user_ids() {
+    // Arrange
+    let ctx = TestContext::new().await;
+
+    for uri in ["/users/-5", "/users/0", "/users/0/addresses", "/users/-1/tags"] {
+        let request = Request::builder()
+            .uri(uri)
+            .body(Body::empty())
+            .unwrap();
+
+        // Act
+        let response = ctx.app.clone().oneshot(request).await.unwrap();
+        let status = response.status();
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let error_response: Value = serde_json::from_slice(&body).unwrap();
+
+        // Assert
+        assert_eq!(status, StatusCode::BAD_REQUEST, "{uri} should be rejected");
+        assert_eq!(error_response["errors"][0]["field"], "id", "{uri} should report the id field");
+    }
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_timestamps_come_from_the_injected_clock() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    let start = Utc.with_ymd_and_hms(2025, 3, 1, 9, 0, 0).unwrap();
+    let clock = MockClock::new(start);
+    let app = create_app_with_providers(
+        ctx.test_pool.clone(),
+        AppProviders { clock: clock.shared(), ..AppProviders::default() },
+    );
+
+    // Act
+    let (_, first) = send(&app, "POST", "/users", Some(UserBuilder::new().name("Ann Lee").json())).await;
+    clock.advance(Duration::hours(1));
+    let (_, second) = send(&app, "POST", "/users", Some(UserBuilder::new().name("Bob Ray").json())).await;
+    let (_, live) = send(&app, "GET", "/live", None).await;
+
+    // Assert
+    let created_at = |user: &Value| user["created_at"].as_str().unwrap().parse::<DateTime<Utc>>().unwrap();
+    assert_eq!(created_at(&first), start, "created_at should come from the clock");
+    assert_eq!(created_at(&second), start + Duration::hours(1), "created_at should follow the clock");
+    assert_eq!(live["timestamp"], (start + Duration::hours(1)).to_rfc3339());
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_identifiers_come_from_the_injected_generator() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    let ids = SequentialIdGenerator::starting_at(100);
+    let app = create_app_with_providers(
+        ctx.test_pool.clone(),
+        AppProviders { ids: ids.shared(), ..AppProviders::default() },
+    );
+
+    // Act
+    let (_, first) = send(&app, "POST", "/users", Some(UserBuilder::new().name("Ann Lee").json())).await;
+    let (_, second) = send(&app, "POST", "/users", Some(UserBuilder::new().name("Bob Ray").json())).await;
+    let (_, envelope) = send(&app, "GET", "/users/100?envelope=true", None).await;
+
+    // Assert
+    assert_eq!(first["id"], 100, "User IDs should come from the generator");
+    assert_eq!(second["id"], 101, "User IDs should come from the generator");
+    assert_eq!(envelope["meta"]["request_id"], "00000000-0000-0000-0000-000000000066");
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_upsert_user_by_external_id() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    let payload = |name: &str| json!({ "external_id": "crm-42", "name": name, "age": 30 });
+
+    // Act
+    let (created_status, created) = send(&ctx.app, "PUT", "/users", Some(payload("Ann Lee"))).await;
+    let (updated_status, updated) = send(&ctx.app, "PUT", "/users", Some(payload("Ann Lee Smith"))).await;
+    let (invalid_status, invalid) = send(&ctx.app, "PUT", "/users", Some(json!({ "external_id": " ", "name": "Ann Lee", "age": 30 }))).await;
+
+    // Assert
+    assert_eq!(created_status, StatusCode::CREATED, "The first upsert should create the user");
+    assert_eq!(updated_status, StatusCode::OK, "A repeated upsert should update the user");
+    assert_eq!(updated["id"], created["id"], "Upserts of one external ID should hit the same user");
+    assert_eq!(updated["name"], "Ann Lee Smith");
+    assert_eq!(invalid_status, StatusCode::BAD_REQUEST);
+    assert_eq!(invalid["errors"][0]["field"], "external_id");
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_concurrent_upserts_converge_on_one_user() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    let payload = json!({ "external_id": "idp|7f3a", "name": "Bob Ray", "age": 41 });
+
+    // Act
+    let responses = futures_util::future::join_all(
+        (0..8).map(|_| send(&ctx.app, "PUT", "/users", Some(payload.clone()))),
+    )
+    .await;
+
+    // Assert
+    let created = responses.iter().filter(|(status, _)| *status == StatusCode::CREATED).count();
+    assert_eq!(created, 1, "Exactly one upsert should create the user");
+    assert!(responses.iter().all(|(status, user)| status.is_success() && user["id"] == responses[0].1["id"]));
+    let (_, page) = send(&ctx.app, "GET", "/users", None).await;
+    assert_eq!(page["count"], 1, "Concurrent upserts should not create duplicates");
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_page_limits_are_configurable() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    for _ in 0..4 {
+        UserBuilder::new().insert(&ctx.test_pool).await;
+    }
+    let users = UserService::new(ctx.test_pool.clone()).with_page_limits(PageLimits { default: 2, max: 3 });
+    let params = |limit| PaginationParams { next_token: None, limit, tag: None, prev_token: None, order: None, include_estimated_total: None, metadata: Metadata::new() };
+
+    // Act
+    let default_page = users.get_users_paginated(params(None)).await.expect("Listing should succeed");
+    let capped_page = users.get_users_paginated(params(Some(50))).await.expect("Listing should succeed");
+
+    // Assert
+    assert_eq!(default_page.count, 2, "Requests without a limit should get the default page size");
+    assert_eq!(capped_page.count, 3, "Requested limits should be capped at the maximum");
+    assert!(capped_page.has_more);
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_name_screening_rejects_denied_names() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    let user = UserBuilder::new().name("Ada Lovelace").insert(&ctx.test_pool).await;
+    let users = UserService::new(ctx.test_pool.clone()).with_name_screening(DenylistPolicy::from_spec("root,system admin").shared());
+
+    // Act
+    let created = users.create_user(UserBuilder::new().name("Root Beer").build()).await;
+    let updated = users
+        .update_user(user.id, rust_kickstart::UpdateUser { name: Some("The System-Admin".to_owned()), age: None, metadata: None })
+        .await;
+    let allowed = users.create_user(UserBuilder::new().name("Rooted Tree").build()).await;
+
+    // Assert
+    assert!(
+        matches!(&created, Err(UserError::ValidationError(errors)) if errors[0].field.as_deref() == Some("name")),
+        "Denied names should fail validation on name, got {created:?}"
+    );
+    assert!(matches!(updated, Err(UserError::ValidationError(_))), "Denied names should fail validation on update");
+    assert!(allowed.is_ok(), "Only whole words should be denied");
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_validation_policy_is_configurable() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    let policy = ValidationPolicy {
+        name_max_length: 10,
+        age_min: 18,
+        age_max: 65,
+        unusual_age_above: 60,
+        required_metadata_keys: vec!["department".to_owned()],
+    };
+    let users = UserService::new(ctx.test_pool.clone()).with_validation_policy(policy);
+
+    // Act
+    let rejected = users
+        .create_user(UserBuilder::new().name("Bartholomew Jones").age(17).build())
+        .await;
+    let created = users
+        .create_user(UserBuilder::new().name("Ann Lee").age(65).metadata("department", "eng").build())
+        .await;
+    let default_users = UserService::new(ctx.test_pool.clone());
+    let default_created = default_users.create_user(UserBuilder::new().name("Bartholomew Jones").age(17).build()).await;
+
+    // Assert
+    let fields: Vec<_> = match &rejected {
+        Err(UserError::ValidationError(errors)) => errors.iter().filter_map(|e| e.field.as_deref()).collect(),
+        _ => Vec::new(),
+    };
+    assert_eq!(fields, ["name", "age", "metadata.department"], "Creating outside the policy should fail validation, got {rejected:?}");
+    assert!(created.is_ok(), "Users within the policy should be created");
+    assert!(default_created.is_ok(), "Services without a policy should keep the default limits");
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_validation_warnings_are_returned_and_escalated_in_strict_mode() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    let users = UserService::new(ctx.test_pool.clone());
+    let strict_users = UserService::new(ctx.test_pool.clone()).with_validation(ValidationContext::strict());
+
+    // Act
+    let warned = users.create_user(UserBuilder::new().name("ADA LOVELACE").age(120).build()).await;
+    let clean = users.create_user(UserBuilder::new().name("Ada Lovelace").age(36).build()).await;
+    let rejected = strict_users.create_user(UserBuilder::new().name("Ada Lovelace").age(120).build()).await;
+
+    // Assert
+    let warned = warned.expect("Warnings should not block creation");
+    let fields: Vec<_> = warned.warnings.iter().filter_map(|w| w.field.as_deref()).collect();
+    assert_eq!(fields, ["name", "age"]);
+    assert!(clean.expect("Clean users should be created").warnings.is_empty());
+    let fields: Vec<_> = match &rejected {
+        Err(UserError::ValidationError(errors)) => errors.iter().filter_map(|e| e.field.as_deref()).collect(),
+        _ => Vec::new(),
+    };
+    assert_eq!(fields, ["age"], "Strict mode should reject warnings, got {rejected:?}");
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_error_responses_carry_codes() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    let long_name = "A".repeat(101);
+
+    // Act
+    let (missing_status, missing) = send(&ctx.app, "GET", "/users/999999", None).await;
+    let (invalid_status, invalid) = send(&ctx.app, "POST", "/users", Some(json!({ "name": long_name, "age": 0 }))).await;
+    let (token_status, token) = send(&ctx.app, "GET", "/users?next_token=garbage", None).await;
+
+    // Assert
+    assert_eq!(missing_status, StatusCode::NOT_FOUND);
+    assert_eq!(missing["code"], "USER_NOT_FOUND");
+    assert_eq!(invalid_status, StatusCode::BAD_REQUEST);
+    let codes: Vec<_> = invalid["errors"].as_array().into_iter().flatten().map(|error| error["code"].clone()).collect();
+    assert_eq!(codes, [json!("NAME_TOO_LONG"), json!("AGE_TOO_LOW")]);
+    assert_eq!(token_status, StatusCode::BAD_REQUEST);
+    assert_eq!(token["code"], "INVALID_PAGINATION_TOKEN");
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_database_errors_are_classified() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    let user = UserBuilder::new().insert(&ctx.test_pool).await;
+    let insert_external = || {
+        sqlx::query("INSERT INTO users (name, age, external_id) VALUES ('Twin User', 30, 'crm-1')").execute(&ctx.test_pool)
+    };
+    insert_external().await.expect("First insert should succeed");
+
+    // Act
+    let duplicate = insert_external().await.expect_err("Duplicate external ID should fail");
+    let dangling = sqlx::query("INSERT INTO user_tags (user_id, tag_id) VALUES ($1, -1)")
+        .bind(user.id)
+        .execute(&ctx.test_pool)
+        .await
+        .expect_err("Unknown tag should fail");
+
+    // Assert
+    assert_eq!(DbErrorKind::of(&duplicate), DbErrorKind::Conflict);
+    assert_eq!(DbErrorKind::of(&dangling), DbErrorKind::UnprocessableEntity);
+    assert_eq!(DbErrorKind::of(&dangling).status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_unreachable_database_returns_503_with_retry_hint() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    let user = UserBuilder::new().insert(&ctx.test_pool).await;
+    ctx.test_pool.close().await;
+
+    // Act
+    let response = ctx
+        .app
+        .clone()
+        .oneshot(Request::builder().uri(format!("/users/{}", user.id)).body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(response.headers()["retry-after"], "1");
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let body: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(body["message"], "The database is temporarily unavailable");
+
+    ctx.cleanup().await;
+}
@@ -0,0 +1,146 @@
+//! Integration tests for the users change feed
+//!
+//! Verifies that `GET /users/changes` hands out a cursor at the end of the
+//! feed, returns creates, updates and deletes after a cursor in order, waits
+//! for a change when there is none yet, answers empty once the wait runs out,
+//! pages with `limit`, and rejects malformed cursors and waits.
+
+mod common;
+
+use std::time::{Duration, Instant};
+
+use axum::http::StatusCode;
+use common::{ADMIN_TOKEN, TestContext};
+use rust_kickstart::testing::{UserBuilder, assert_validation_error, send, send_as};
+use serde_json::{Value, json};
+
+/// Cursor at the end of the feed
+async fn head(ctx: &TestContext) -> String {
+    let (status, body) = send(&ctx.app, "GET", "/users/changes", None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["changes"], json!([]), "A poll without a cursor should return no changes");
+    body["next_cursor"].as_str().expect("The poll should return a cursor").to_owned()
+}
+
+/// Polls from `cursor` until `count` changes were returned, as `(user_id, operation)`
+///
+/// Changes only show up once every transaction running when they were
+/// written has finished, so they may arrive over several polls.
+async fn collect(ctx: &TestContext, mut cursor: String, count: usize) -> (Vec<(i64, String)>, String) {
+    let mut changes = Vec::new();
+    while changes.len() < count {
+        let (status, body) = send(&ctx.app, "GET", &format!("/users/changes?since={cursor}&wait=5s"), None).await;
+        assert_eq!(status, StatusCode::OK);
+        let page = body["changes"].as_array().expect("The poll should return changes");
+        assert!(!page.is_empty(), "Pending changes should arrive within the wait");
+        changes.extend(page.iter().map(|change| {
+            (change["user_id"].as_i64().expect("user_id"), change["operation"].as_str().expect("operation").to_owned())
+        }));
+        body["next_cursor"].as_str().expect("The poll should return a cursor").clone_into(&mut cursor);
+    }
+    (changes, cursor)
+}
+
+#[tokio::test]
+async fn test_writes_are_returned_in_order_after_the_cursor() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    let cursor = head(&ctx).await;
+
+    // Act
+    let (_, created) = send(&ctx.app, "POST", "/users", Some(json!({ "name": "Feed User", "age": 30 }))).await;
+    let id = created["id"].as_i64().expect("The user should be created");
+    send(&ctx.app, "PUT", &format!("/users/{id}"), Some(json!({ "age": 31 }))).await;
+    send_as(&ctx.admin_app(), ADMIN_TOKEN, "DELETE", &format!("/users/{id}"), None).await;
+    let (changes, cursor) = collect(&ctx, cursor, 3).await;
+    let (status, after) = send(&ctx.app, "GET", &format!("/users/changes?since={cursor}"), None).await;
+
+    // Assert
+    assert_eq!(changes, [(id, "create".to_owned()), (id, "update".to_owned()), (id, "delete".to_owned())]);
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(after["changes"], json!([]));
+    assert_eq!(after["next_cursor"], json!(cursor), "An empty poll should keep the cursor");
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_poll_waits_for_the_next_change() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    let cursor = head(&ctx).await;
+    let pool = ctx.test_pool.clone();
+    let writer = tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        UserBuilder::new().insert(&pool).await
+    });
+
+    // Act
+    let started = Instant::now();
+    let (status, body) = send(&ctx.app, "GET", &format!("/users/changes?since={cursor}&wait=10s"), None).await;
+
+    // Assert
+    let user = writer.await.expect("The writer should finish");
+    assert_eq!(status, StatusCode::OK);
+    assert!(started.elapsed() >= Duration::from_millis(300), "The poll should wait for the change");
+    assert!(started.elapsed() < Duration::from_secs(10), "The poll should answer as soon as the change arrives");
+    assert_eq!(body["changes"][0]["user_id"], json!(user.id));
+    assert_eq!(body["changes"][0]["operation"], "create");
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_poll_returns_empty_when_the_wait_runs_out() {
+    let ctx = TestContext::new().await;
+    let cursor = head(&ctx).await;
+
+    let started = Instant::now();
+    let (status, body) = send(&ctx.app, "GET", &format!("/users/changes?since={cursor}&wait=300ms"), None).await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert!(started.elapsed() >= Duration::from_millis(300), "The poll should wait before answering");
+    assert_eq!(body, json!({ "changes": [], "next_cursor": cursor, "has_more": false }));
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_changes_are_paged_by_limit() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    let cursor = head(&ctx).await;
+    // One statement, so all three changes settle together
+    sqlx::query("INSERT INTO users (name, age) VALUES ('Ann', 20), ('Bob', 21), ('Cid', 22)")
+        .execute(&ctx.test_pool)
+        .await
+        .expect("Users should be inserted");
+
+    // Act
+    let (_, first) = send(&ctx.app, "GET", &format!("/users/changes?since={cursor}&wait=5s&limit=2"), None).await;
+    let next = first["next_cursor"].as_str().expect("The poll should return a cursor");
+    let (_, second) = send(&ctx.app, "GET", &format!("/users/changes?since={next}&limit=2"), None).await;
+
+    // Assert
+    let count = |page: &Value| page["changes"].as_array().map(Vec::len);
+    assert_eq!((count(&first), first["has_more"].as_bool()), (Some(2), Some(true)));
+    assert_eq!((count(&second), second["has_more"].as_bool()), (Some(1), Some(false)));
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_malformed_cursor_and_wait_are_rejected() {
+    let ctx = TestContext::new().await;
+    let cursor = head(&ctx).await;
+
+    let (cursor_status, cursor_body) = send(&ctx.app, "GET", "/users/changes?since=not-a-cursor", None).await;
+    let (wait_status, wait_body) = send(&ctx.app, "GET", &format!("/users/changes?since={cursor}&wait=soon"), None).await;
+
+    assert_eq!(cursor_status, StatusCode::BAD_REQUEST);
+    assert_eq!(cursor_body["code"], "INVALID_PAGINATION_TOKEN");
+    assert_eq!(wait_status, StatusCode::BAD_REQUEST);
+    assert_validation_error(&wait_body, "wait");
+
+    ctx.cleanup().await;
+}
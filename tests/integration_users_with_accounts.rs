@@ -0,0 +1,128 @@
+//! Integration tests for listing users with their accounts expanded
+//!
+//! Verifies that `GET /users/with-accounts` embeds each user's accounts, is
+//! restricted to admins and pages like `GET /users`, and that the accounts of a page are loaded in one
+//! query however many users it holds.
+
+mod common;
+
+use axum::http::StatusCode;
+use common::{ADMIN_TOKEN, TestContext};
+use rust_kickstart::testing::{QueryCounter, UserBuilder, send, send_as};
+use serde_json::{Value, json};
+
+/// Opens an account with `balance_cents` for `user_id`
+async fn open_account(ctx: &TestContext, user_id: i32, balance_cents: i64) {
+    let (status, _) = send_as(
+        &ctx.admin_app(),
+        ADMIN_TOKEN,
+        "POST",
+        "/accounts",
+        Some(json!({ "user_id": user_id, "initial_balance_cents": balance_cents })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED, "Opening an account should succeed");
+}
+
+/// `(user ID, account balances)` of each user of a page
+fn balances(page: &Value) -> Vec<(i64, Vec<i64>)> {
+    page["users"]
+        .as_array()
+        .expect("The page should list users")
+        .iter()
+        .map(|user| {
+            let accounts = user["accounts"].as_array().expect("Users should list their accounts");
+            let balances = accounts.iter().map(|account| account["balance_cents"].as_i64().expect("balance")).collect();
+            (user["id"].as_i64().expect("id"), balances)
+        })
+        .collect()
+}
+
+/// Statements executed while listing users with their accounts
+async fn count_listing_queries(ctx: &TestContext) -> QueryCounter {
+    let app = ctx.admin_app();
+    let counter = QueryCounter::new();
+    let _guard = counter.install();
+    let (status, _) = send_as(&app, ADMIN_TOKEN, "GET", "/users/with-accounts", None).await;
+    assert_eq!(status, StatusCode::OK);
+    counter
+}
+
+#[tokio::test]
+async fn test_users_are_listed_with_their_accounts() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    let ann = UserBuilder::new().name("Ann Lee").insert(&ctx.test_pool).await;
+    let bob = UserBuilder::new().name("Bob Ray").insert(&ctx.test_pool).await;
+    let cat = UserBuilder::new().name("Cat Poe").insert(&ctx.test_pool).await;
+    open_account(&ctx, ann.id, 100).await;
+    open_account(&ctx, cat.id, 300).await;
+    open_account(&ctx, ann.id, 200).await;
+
+    // Act
+    let (status, page) = send_as(&ctx.admin_app(), ADMIN_TOKEN, "GET", "/users/with-accounts", None).await;
+    let (anonymous, _) = send(&ctx.app, "GET", "/users/with-accounts", None).await;
+
+    // Assert
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(anonymous, StatusCode::UNAUTHORIZED, "Listing balances should require the admin role");
+    let expected = [(ann.id, vec![100, 200]), (bob.id, vec![]), (cat.id, vec![300])];
+    assert_eq!(balances(&page), expected.map(|(id, balances)| (i64::from(id), balances)));
+    assert_eq!(page["users"][0]["name"], "Ann Lee", "User fields should be inlined");
+    assert_eq!(page["users"][0]["accounts"][0]["user_id"], json!(ann.id));
+    assert_eq!((page["count"].as_u64(), page["has_more"].as_bool()), (Some(3), Some(false)));
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_users_with_accounts_are_paginated() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    let mut user_ids = Vec::new();
+    for balance_cents in [100, 200, 300] {
+        let user = UserBuilder::new().insert(&ctx.test_pool).await;
+        open_account(&ctx, user.id, balance_cents).await;
+        user_ids.push(i64::from(user.id));
+    }
+
+    // Act
+    let (_, first) = send_as(&ctx.admin_app(), ADMIN_TOKEN, "GET", "/users/with-accounts?limit=2", None).await;
+    let next_token = first["next_token"].as_str().expect("The first page should have a next token");
+    let (_, second) = send_as(&ctx.admin_app(), ADMIN_TOKEN, "GET", &format!("/users/with-accounts?limit=2&next_token={next_token}"), None).await;
+    let (status, invalid) = send_as(&ctx.admin_app(), ADMIN_TOKEN, "GET", "/users/with-accounts?next_token=invalid", None).await;
+
+    // Assert
+    assert_eq!(balances(&first), [(user_ids[0], vec![100]), (user_ids[1], vec![200])]);
+    assert_eq!(first["has_more"], true);
+    assert_eq!(balances(&second), [(user_ids[2], vec![300])]);
+    assert_eq!(second["has_more"], false);
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(invalid["code"], "INVALID_PAGINATION_TOKEN");
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_accounts_are_loaded_in_one_query_whatever_the_page_size() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    let user = UserBuilder::new().insert(&ctx.test_pool).await;
+    open_account(&ctx, user.id, 100).await;
+    let one_user = count_listing_queries(&ctx).await;
+    for _ in 0..9 {
+        let user = UserBuilder::new().insert(&ctx.test_pool).await;
+        open_account(&ctx, user.id, 100).await;
+        open_account(&ctx, user.id, 200).await;
+    }
+
+    // Act
+    let ten_users = count_listing_queries(&ctx).await;
+
+    // Assert
+    assert_eq!(ten_users.count_matching("FROM accounts"), 1, "Accounts should be loaded in one query: {:#?}", ten_users.statements());
+    ten_users.assert_count(one_user.count());
+    ten_users.assert_count(2);
+
+    ctx.cleanup().await;
+}
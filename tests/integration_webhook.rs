@@ -0,0 +1,269 @@
+//! Integration tests for outgoing webhooks
+//!
+//! Verifies that published events are delivered to the webhooks subscribed to
+//! their type with a signature per live secret, that rotated secrets keep
+//! signing deliveries for the grace period only, that failed deliveries are
+//! retried and every attempt is recorded with the receiver's response code,
+//! that the delivery log is purged by retention, and that the webhook
+//! endpoints are restricted to admins.
+
+mod common;
+
+use std::sync::{
+    Arc, Mutex,
+    atomic::{AtomicU16, Ordering},
+};
+use std::time::Duration;
+
+use axum::{
+    Router,
+    body::Bytes,
+    extract::State,
+    http::{HeaderMap, StatusCode},
+    routing::post,
+};
+use chrono::{TimeZone, Utc};
+use common::TestContext;
+use rust_kickstart::clock::{Clock, MockClock};
+use rust_kickstart::events::DomainEvent;
+use rust_kickstart::jobs::{Job, JobQueue, QueueWorker};
+use rust_kickstart::retention::{PurgeOutcome, RetentionTarget};
+use rust_kickstart::testing::send;
+use rust_kickstart::{RetentionPolicy, RetentionService};
+use rust_kickstart::user::domain::UserStatus;
+use rust_kickstart::webhook::{CreateWebhook, UpdateWebhook, WEBHOOK_QUEUE, WebhookDeliveryHandler, WebhookError, WebhookService, sign};
+use serde_json::Value;
+use tokio::net::TcpListener;
+
+/// Requests received by a [`Receiver`], with their headers and body
+type Received = Arc<Mutex<Vec<(HeaderMap, Bytes)>>>;
+
+/// Local webhook receiver answering with a settable status
+struct Receiver {
+    url: String,
+    received: Received,
+    status: Arc<AtomicU16>,
+}
+
+impl Receiver {
+    /// Starts a receiver answering 200 on `/hook`
+    async fn start() -> Self {
+        let received = Received::default();
+        let status = Arc::new(AtomicU16::new(200));
+        let app = Router::new()
+            .route(
+                "/hook",
+                post(|State((received, status)): State<(Received, Arc<AtomicU16>)>, headers: HeaderMap, body: Bytes| async move {
+                    received.lock().expect("Receiver lock poisoned").push((headers, body));
+                    StatusCode::from_u16(status.load(Ordering::Relaxed)).expect("Status should be valid")
+                }),
+            )
+            .with_state((Arc::clone(&received), Arc::clone(&status)));
+        let listener = TcpListener::bind("127.0.0.1:0").await.expect("Failed to bind");
+        let url = format!("http://{}/hook", listener.local_addr().expect("Listener should have an address"));
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        Self { url, received, status }
+    }
+
+    /// Requests received so far
+    fn requests(&self) -> Vec<(HeaderMap, Bytes)> {
+        self.received.lock().expect("Receiver lock poisoned").clone()
+    }
+}
+
+/// Header value as a string
+fn header<'a>(headers: &'a HeaderMap, name: &str) -> &'a str {
+    headers[name].to_str().expect("Header should be ASCII")
+}
+
+fn status_event() -> DomainEvent {
+    DomainEvent::UserStatusChanged {
+        user_id: 7,
+        from: UserStatus::Active,
+        to: UserStatus::Suspended,
+        occurred_at: Utc.with_ymd_and_hms(2025, 3, 1, 9, 0, 0).unwrap(),
+    }
+}
+
+/// Worker running the deliveries queued by `webhooks`
+fn delivery_worker(queue: &JobQueue, webhooks: &WebhookService) -> QueueWorker {
+    QueueWorker::new(queue.clone())
+        .on_queue(WEBHOOK_QUEUE)
+        .with_handler(Arc::new(WebhookDeliveryHandler::new(webhooks.clone())))
+}
+
+#[tokio::test]
+async fn test_events_are_delivered_signed_to_subscribed_webhooks() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    let clock = MockClock::new(Utc.with_ymd_and_hms(2025, 3, 1, 9, 0, 0).unwrap());
+    let queue = JobQueue::new(ctx.test_pool.clone());
+    let webhooks = WebhookService::new(ctx.test_pool.clone(), queue.clone()).with_clock(clock.shared());
+    let receiver = Receiver::start().await;
+    let created = webhooks
+        .create(CreateWebhook { url: receiver.url.clone(), event_types: vec!["user_status_changed".to_owned()] })
+        .await
+        .unwrap();
+
+    // Act
+    let queued = webhooks.dispatch(&status_event()).await.unwrap();
+    delivery_worker(&queue, &webhooks).run().await.unwrap();
+
+    // Assert
+    assert_eq!(queued, 1);
+    let requests = receiver.requests();
+    assert_eq!(requests.len(), 1, "The subscribed webhook should receive the event");
+    let (headers, body) = &requests[0];
+    let timestamp: i64 = header(headers, "x-timestamp").parse().unwrap();
+    assert_eq!(timestamp, clock.now().timestamp());
+    assert_eq!(header(headers, "x-webhook-event"), "user_status_changed");
+    assert_eq!(header(headers, "x-webhook-signature"), sign(&created.secret, timestamp, body));
+    let delivered: Value = serde_json::from_slice(body).unwrap();
+    assert_eq!((delivered["type"].as_str(), delivered["user_id"].as_i64()), (Some("user_status_changed"), Some(7)));
+
+    let deliveries = webhooks.deliveries(created.webhook.id, None).await.unwrap();
+    assert_eq!((deliveries[0].attempt, deliveries[0].response_status), (1, Some(200)));
+    assert_eq!(deliveries[0].delivery_id, header(headers, "x-webhook-id"));
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_failed_deliveries_are_retried_and_every_attempt_is_recorded() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    let queue = JobQueue::new(ctx.test_pool.clone());
+    let webhooks = WebhookService::new(ctx.test_pool.clone(), queue.clone());
+    let receiver = Receiver::start().await;
+    let created = webhooks.create(CreateWebhook { url: receiver.url.clone(), event_types: Vec::new() }).await.unwrap();
+    let worker = delivery_worker(&queue, &webhooks);
+    webhooks.dispatch(&status_event()).await.unwrap();
+
+    // Act
+    receiver.status.store(503, Ordering::Relaxed);
+    worker.run().await.unwrap();
+    receiver.status.store(204, Ordering::Relaxed);
+    worker.run().await.unwrap();
+
+    // Assert
+    let deliveries = webhooks.deliveries(created.webhook.id, None).await.unwrap();
+    let attempts: Vec<_> = deliveries.iter().map(|delivery| (delivery.attempt, delivery.response_status, delivery.error.is_some())).collect();
+    assert_eq!(attempts, [(2, Some(204), false), (1, Some(503), true)], "Attempts should be listed newest first");
+    assert_eq!(deliveries[0].delivery_id, deliveries[1].delivery_id, "Retries should share the delivery ID");
+    let ids: Vec<_> = receiver.requests().iter().map(|(headers, _)| header(headers, "x-webhook-id").to_owned()).collect();
+    assert_eq!(ids, [deliveries[0].delivery_id.clone(), deliveries[0].delivery_id.clone()]);
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_rotated_secrets_sign_deliveries_until_the_grace_period_ends() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    let clock = MockClock::new(Utc.with_ymd_and_hms(2025, 3, 1, 9, 0, 0).unwrap());
+    let queue = JobQueue::new(ctx.test_pool.clone());
+    let webhooks = WebhookService::new(ctx.test_pool.clone(), queue.clone())
+        .with_clock(clock.shared())
+        .with_rotation_grace(Duration::from_hours(1));
+    let receiver = Receiver::start().await;
+    let created = webhooks.create(CreateWebhook { url: receiver.url.clone(), event_types: Vec::new() }).await.unwrap();
+    let worker = delivery_worker(&queue, &webhooks);
+
+    // Act
+    let rotated = webhooks.rotate_secret(created.webhook.id).await.unwrap();
+    webhooks.dispatch(&status_event()).await.unwrap();
+    worker.run().await.unwrap();
+    clock.advance(chrono::Duration::hours(2));
+    webhooks.dispatch(&status_event()).await.unwrap();
+    worker.run().await.unwrap();
+
+    // Assert
+    assert_eq!(rotated.previous_secrets_expire_at, Some(rotated.created_at + chrono::Duration::hours(1)));
+    let requests = receiver.requests();
+    let signatures = |index: usize| {
+        let (headers, body) = &requests[index];
+        let timestamp: i64 = header(headers, "x-timestamp").parse().unwrap();
+        let expected = |secret: &str| sign(secret, timestamp, body);
+        (header(headers, "x-webhook-signature").to_owned(), expected(&rotated.secret), expected(&created.secret))
+    };
+    let (during, new, old) = signatures(0);
+    assert_eq!(during, format!("{new},{old}"), "Both secrets should sign during the grace period, newest first");
+    let (after, new, _) = signatures(1);
+    assert_eq!(after, new, "Only the new secret should sign once the grace period ends");
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_old_delivery_attempts_are_purged_by_retention() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    let clock = MockClock::new(Utc.with_ymd_and_hms(2025, 3, 1, 9, 0, 0).unwrap());
+    let queue = JobQueue::new(ctx.test_pool.clone());
+    let webhooks = WebhookService::new(ctx.test_pool.clone(), queue.clone()).with_clock(clock.shared());
+    let receiver = Receiver::start().await;
+    let created = webhooks.create(CreateWebhook { url: receiver.url.clone(), event_types: Vec::new() }).await.unwrap();
+    let worker = delivery_worker(&queue, &webhooks);
+    webhooks.dispatch(&status_event()).await.unwrap();
+    worker.run().await.unwrap();
+    clock.advance(chrono::Duration::days(31));
+    webhooks.dispatch(&status_event()).await.unwrap();
+    worker.run().await.unwrap();
+    let retention = RetentionService::new(
+        ctx.test_pool.clone(),
+        vec![RetentionPolicy { target: RetentionTarget::WebhookDeliveries, max_age: chrono::Duration::days(30) }],
+    )
+    .with_clock(clock.shared());
+
+    // Act
+    let outcomes = retention.purge().await.unwrap();
+
+    // Assert
+    assert_eq!(outcomes, [PurgeOutcome { target: RetentionTarget::WebhookDeliveries, purged: 1 }]);
+    let deliveries = webhooks.deliveries(created.webhook.id, None).await.unwrap();
+    assert_eq!(deliveries.len(), 1, "Only the recent attempt should be kept");
+    assert_eq!(deliveries[0].attempted_at, clock.now());
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_subscriptions_are_validated_and_can_be_changed() {
+    let ctx = TestContext::new().await;
+    let webhooks = WebhookService::new(ctx.test_pool.clone(), JobQueue::new(ctx.test_pool.clone()));
+
+    let unknown = webhooks
+        .create(CreateWebhook { url: "https://example.com/hook".to_owned(), event_types: vec!["user_deleted".to_owned()] })
+        .await;
+    let relative = webhooks.create(CreateWebhook { url: "/hook".to_owned(), event_types: Vec::new() }).await;
+    let created = webhooks
+        .create(CreateWebhook { url: "https://example.com/hook".to_owned(), event_types: Vec::new() })
+        .await
+        .unwrap();
+    let updated = webhooks
+        .update(created.webhook.id, UpdateWebhook { url: None, event_types: Some(vec!["user_status_changed".to_owned(); 2]) })
+        .await
+        .unwrap();
+
+    assert!(matches!(unknown, Err(WebhookError::ValidationError(_))), "Unknown event types should be rejected");
+    assert!(matches!(relative, Err(WebhookError::ValidationError(_))), "Relative URLs should be rejected");
+    assert_eq!((updated.url.as_str(), updated.event_types.as_slice()), ("https://example.com/hook", ["user_status_changed".to_owned()].as_slice()));
+    assert!(matches!(webhooks.deliveries(created.webhook.id + 1, None).await, Err(WebhookError::NotFound(_))));
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_webhook_endpoints_require_admin() {
+    let ctx = TestContext::new().await;
+
+    let (create_status, _) = send(&ctx.app, "POST", "/webhooks", Some(serde_json::json!({ "url": "https://example.com/hook" }))).await;
+    let (rotate_status, _) = send(&ctx.app, "POST", "/webhooks/1/secrets", None).await;
+    let (deliveries_status, _) = send(&ctx.app, "GET", "/webhooks/1/deliveries", None).await;
+
+    assert_eq!(create_status, StatusCode::UNAUTHORIZED, "Creating webhooks should require credentials");
+    assert_eq!(rotate_status, StatusCode::UNAUTHORIZED, "Rotating secrets should require credentials");
+    assert_eq!(deliveries_status, StatusCode::UNAUTHORIZED, "Listing deliveries should require credentials");
+
+    ctx.cleanup().await;
+}
@@ -0,0 +1,139 @@
+//! In-process load test
+//!
+//! Drives the router directly (no network) with concurrent workers against a
+//! seeded test schema and reports requests per second for the main read
+//! paths. Fails when any scenario drops below `LOAD_TEST_MIN_RPS`.
+//!
+//! Gated behind the `load-test` feature; run with `make test/load`.
+//! Tunables: `LOAD_TEST_SECONDS` (default 5), `LOAD_TEST_CONCURRENCY`
+//! (default 32) and `LOAD_TEST_MIN_RPS` (default 200).
+
+mod common;
+
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+use axum::{
+    Router,
+    body::Body,
+    http::{Request, StatusCode},
+};
+use common::TestContext;
+use serde_json::json;
+use tower::ServiceExt;
+
+/// Users created before measuring
+const SEEDED_USERS: u64 = 200;
+
+/// Builds the request URI for the n-th request of a scenario
+type UriFor = fn(u64) -> String;
+
+/// Reads a numeric tunable from the environment
+fn env_or<T: std::str::FromStr>(name: &str, default: T) -> T {
+    std::env::var(name).ok().and_then(|value| value.parse().ok()).unwrap_or(default)
+}
+
+/// Outcome of one scenario
+struct LoadReport {
+    requests: u64,
+    failures: u64,
+    elapsed: Duration,
+}
+
+impl LoadReport {
+    #[allow(clippy::cast_precision_loss)]
+    fn requests_per_second(&self) -> f64 {
+        self.requests as f64 / self.elapsed.as_secs_f64()
+    }
+}
+
+/// Hammers `uri_for(n)` from `concurrency` workers for `duration`
+async fn run_scenario(
+    app: &Router,
+    concurrency: usize,
+    duration: Duration,
+    uri_for: UriFor,
+) -> LoadReport {
+    let requests = Arc::new(AtomicU64::new(0));
+    let failures = Arc::new(AtomicU64::new(0));
+    let started = Instant::now();
+    let deadline = started + duration;
+
+    let workers: Vec<_> = (0..concurrency)
+        .map(|_| {
+            let app = app.clone();
+            let requests = Arc::clone(&requests);
+            let failures = Arc::clone(&failures);
+            tokio::spawn(async move {
+                while Instant::now() < deadline {
+                    let n = requests.fetch_add(1, Ordering::Relaxed);
+                    let request = Request::builder()
+                        .uri(uri_for(n))
+                        .body(Body::empty())
+                        .expect("Failed to build request");
+                    let status = app.clone().oneshot(request).await.map(|response| response.status());
+                    if status.ok() != Some(StatusCode::OK) {
+                        failures.fetch_add(1, Ordering::Relaxed);
+                    }
+                }
+            })
+        })
+        .collect();
+
+    for worker in workers {
+        worker.await.expect("Load worker panicked");
+    }
+
+    LoadReport {
+        requests: requests.load(Ordering::Relaxed),
+        failures: failures.load(Ordering::Relaxed),
+        elapsed: started.elapsed(),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[allow(clippy::print_stdout)]
+async fn test_read_paths_throughput() {
+    let ctx = TestContext::new().await;
+    let seconds = env_or("LOAD_TEST_SECONDS", 5);
+    let concurrency = env_or("LOAD_TEST_CONCURRENCY", 32);
+    let min_rps = env_or("LOAD_TEST_MIN_RPS", 200.0);
+
+    for i in 0..SEEDED_USERS {
+        let request = Request::builder()
+            .method("POST")
+            .uri("/users")
+            .header("content-type", "application/json")
+            .body(Body::from(json!({ "name": "Load Tester", "age": 20 + i % 50 }).to_string()))
+            .expect("Failed to build request");
+        let response = ctx.app.clone().oneshot(request).await.expect("Failed to seed user");
+        assert_eq!(response.status(), StatusCode::OK, "Seeding should succeed");
+    }
+
+    let scenarios: [(&str, UriFor); 3] = [
+        ("GET /live", |_n| "/live".to_owned()),
+        ("GET /users/{id}", |n| format!("/users/{}", n % SEEDED_USERS + 1)),
+        ("GET /users?limit=50", |_n| "/users?limit=50".to_owned()),
+    ];
+
+    println!("{:<22} {:>10} {:>10} {:>12}", "scenario", "requests", "failures", "req/s");
+    let mut slow = Vec::new();
+    for (name, uri_for) in scenarios {
+        let report = run_scenario(&ctx.app, concurrency, Duration::from_secs(seconds), uri_for).await;
+        let rps = report.requests_per_second();
+        println!("{name:<22} {:>10} {:>10} {rps:>12.1}", report.requests, report.failures);
+
+        assert_eq!(report.failures, 0, "{name} should not fail under load");
+        if rps < min_rps {
+            slow.push(format!("{name}: {rps:.1} req/s"));
+        }
+    }
+
+    ctx.cleanup().await;
+    assert!(slow.is_empty(), "Below {min_rps} req/s: {}", slow.join(", "));
+}
@@ -0,0 +1,58 @@
+//! Golden `OpenAPI` guard
+//!
+//! Renders the served specification and compares it with the committed
+//! `openapi.json`. Any route or schema change fails this test until the file is
+//! regenerated with `make openapi` and the change is noted in `CHANGELOG.md`.
+
+use std::{fs, path::Path};
+
+use similar::{ChangeTag, TextDiff};
+
+/// Environment variable that rewrites `openapi.json` instead of comparing
+const UPDATE_VAR: &str = "UPDATE_OPENAPI";
+
+/// Renders the specification the way it is committed
+fn render_spec() -> String {
+    let spec = serde_json::to_string_pretty(&rust_kickstart::openapi_spec()).expect("Failed to serialize OpenAPI spec");
+    format!("{spec}\n")
+}
+
+/// Changed lines between `expected` and `actual`, with line numbers
+fn readable_diff(expected: &str, actual: &str) -> String {
+    let diff = TextDiff::from_lines(expected, actual);
+    let mut out = Vec::new();
+    for group in diff.grouped_ops(2) {
+        for op in group {
+            for change in diff.iter_changes(&op) {
+                let (sign, line) = match change.tag() {
+                    ChangeTag::Delete => ("-", change.old_index()),
+                    ChangeTag::Insert => ("+", change.new_index()),
+                    ChangeTag::Equal => (" ", change.new_index()),
+                };
+                let line = line.map_or_else(String::new, |index| (index + 1).to_string());
+                out.push(format!("{sign}{line:>5} | {change}"));
+            }
+        }
+        out.push("  ...\n".to_owned());
+    }
+    out.concat()
+}
+
+#[test]
+fn test_openapi_matches_golden_file() {
+    let golden_path = Path::new(env!("CARGO_MANIFEST_DIR")).join("openapi.json");
+    let actual = render_spec();
+
+    if std::env::var_os(UPDATE_VAR).is_some() {
+        fs::write(&golden_path, &actual).expect("Failed to write openapi.json");
+        return;
+    }
+
+    let expected = fs::read_to_string(&golden_path).unwrap_or_default();
+    assert!(
+        expected == actual,
+        "The OpenAPI spec differs from openapi.json (- committed, + current):\n\n{}\n\
+         If the change is intentional, run `make openapi` and add a CHANGELOG.md entry.",
+        readable_diff(&expected, &actual)
+    );
+}
@@ -0,0 +1,83 @@
+//! Snapshot tests of representative API responses
+//!
+//! Responses are compared against the committed snapshots in
+//! `tests/snapshots/`, so any contract change shows up as a reviewable diff.
+//! The app runs with a fixed clock and sequential IDs; values that still vary
+//! between runs or releases (response times, the crate version) are redacted.
+//!
+//! After an intentional change, review and accept the new snapshots with
+//! `cargo insta review` (or rerun with `INSTA_UPDATE=always`).
+
+mod common;
+
+use axum::Router;
+use chrono::{TimeZone, Utc};
+use common::TestContext;
+use insta::assert_json_snapshot;
+use rust_kickstart::clock::MockClock;
+use rust_kickstart::ids::SequentialIdGenerator;
+use rust_kickstart::testing::{UserBuilder, send};
+use rust_kickstart::{AppProviders, create_app_with_providers};
+use serde_json::json;
+
+/// App with a fixed clock and sequential IDs on the test schema
+fn deterministic_app(ctx: &TestContext) -> Router {
+    let clock = MockClock::new(Utc.with_ymd_and_hms(2025, 1, 1, 12, 0, 0).unwrap());
+    create_app_with_providers(
+        ctx.test_pool.clone(),
+        AppProviders { clock: clock.shared(), ids: SequentialIdGenerator::new().shared(), ..AppProviders::default() },
+    )
+}
+
+#[tokio::test]
+async fn test_user_responses_snapshot() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    let app = deterministic_app(&ctx);
+
+    // Act
+    let (_, created) = send(&app, "POST", "/users", Some(UserBuilder::new().name("Ann Lee").json())).await;
+    let (_, page) = send(&app, "GET", "/users?limit=1", None).await;
+    let (_, enveloped) = send(&app, "GET", "/users/1?envelope=true", None).await;
+
+    // Assert
+    assert_json_snapshot!("user_created", created);
+    assert_json_snapshot!("users_page", page);
+    assert_json_snapshot!("user_enveloped", enveloped, { ".meta.duration_ms" => "[duration]" });
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_validation_error_snapshot() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    let app = deterministic_app(&ctx);
+
+    // Act
+    let (_, errors) = send(&app, "POST", "/users", Some(json!({ "name": "R2D2", "age": 0 }))).await;
+
+    // Assert
+    assert_json_snapshot!("validation_errors", errors);
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_health_snapshot() {
+    // Arrange
+    let ctx = TestContext::new().await;
+    let app = deterministic_app(&ctx);
+
+    // Act
+    let (_, health) = send(&app, "GET", "/health", None).await;
+
+    // Assert
+    assert_json_snapshot!("health", health, {
+        ".version" => "[version]",
+        ".total_response_time_ms" => "[duration]",
+        ".components[].response_time_ms" => "[duration]",
+    });
+
+    ctx.cleanup().await;
+}
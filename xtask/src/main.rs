@@ -0,0 +1,206 @@
+//! # Project tasks
+//!
+//! Run with `cargo xtask <task>`.
+//!
+//! - `prepare` regenerates the `.sqlx/` offline query data, so builds without
+//!   a database (CI, Docker, fresh clones) can type-check the `query!`
+//!   macros. It applies the migrations to a throwaway schema of the database
+//!   at `DATABASE_URL`, the same way the integration tests do, rebuilds the
+//!   crate against that schema with `SQLX_OFFLINE_DIR` pointing at `.sqlx/`,
+//!   and drops the schema again.
+//! - `migrate` applies pending migrations to the database at `DATABASE_URL`.
+//!
+//! Migrations live in one directory per domain under `./migrations`; both
+//! tasks apply all of them merged in version order, as `db::Migrations` does
+//! in the crate (which cannot be used here: the crate may not compile before
+//! `prepare` has run). No `sqlx-cli` is needed.
+
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process::{Command, ExitCode},
+};
+
+use futures_util::future::BoxFuture;
+use sqlx::{
+    PgPool,
+    error::BoxDynError,
+    migrate::{Migration, MigrationSource, Migrator},
+    postgres::PgPoolOptions,
+};
+use uuid::Uuid;
+
+/// Error of a task, reported on stderr
+type TaskError = Box<dyn std::error::Error>;
+
+/// Package whose queries are prepared
+const PACKAGE: &str = "rust-kickstart";
+
+#[tokio::main]
+#[allow(clippy::print_stderr)]
+async fn main() -> ExitCode {
+    let result = match env::args().nth(1).as_deref() {
+        Some("prepare") => prepare().await,
+        Some("migrate") => migrate_database().await,
+        _ => Err("Usage: cargo xtask <prepare|migrate>".into()),
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("❌ {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Applies pending migrations to the database at `DATABASE_URL`
+#[allow(clippy::print_stderr)]
+async fn migrate_database() -> Result<(), TaskError> {
+    let root = workspace_root();
+    let pool = PgPoolOptions::new().max_connections(1).connect(&database_url(&root)?).await?;
+    migrator(&root.join("migrations")).await?.run(&pool).await?;
+    eprintln!("✅ Migrations applied");
+    Ok(())
+}
+
+/// Regenerates `.sqlx/` against a freshly migrated throwaway schema
+#[allow(clippy::print_stderr)]
+async fn prepare() -> Result<(), TaskError> {
+    let root = workspace_root();
+    let database_url = database_url(&root)?;
+
+    let schema = format!("sqlx_prepare_{}", Uuid::now_v7().simple());
+    let pool = PgPoolOptions::new().max_connections(1).connect(&database_url).await?;
+    sqlx::query(&format!("CREATE SCHEMA {schema}")).execute(&pool).await?;
+    eprintln!("📦 Migrating throwaway schema {schema}");
+
+    let result = match migrate(&database_url, &schema, &root.join("migrations")).await {
+        Ok(()) => regenerate(&root, &schema_url(&database_url, &schema)),
+        Err(e) => Err(e),
+    };
+
+    sqlx::query(&format!("DROP SCHEMA {schema} CASCADE")).execute(&pool).await?;
+    if result.is_ok() {
+        eprintln!("✅ Query data written to .sqlx/; commit it with the query changes");
+    }
+    result
+}
+
+/// `DATABASE_URL`, from the environment or `.env`
+fn database_url(root: &Path) -> Result<String, TaskError> {
+    dotenvy::from_path(root.join(".env")).ok();
+    Ok(env::var("DATABASE_URL").map_err(|e| format!("DATABASE_URL must be set (see .env.example): {e}"))?)
+}
+
+/// Applies the migrations under `dir` to `schema`
+async fn migrate(database_url: &str, schema: &str, dir: &Path) -> Result<(), TaskError> {
+    let pool = schema_pool(database_url, schema).await?;
+    migrator(dir).await?.run(&pool).await?;
+    pool.close().await;
+    Ok(())
+}
+
+/// Migrations of every domain directory under `dir`, merged in version order
+#[derive(Debug)]
+struct Merged(Vec<Migration>);
+
+impl MigrationSource<'static> for Merged {
+    fn resolve(self) -> BoxFuture<'static, Result<Vec<Migration>, BoxDynError>> {
+        Box::pin(async move { Ok(self.0) })
+    }
+}
+
+/// Migrator applying every domain directory under `dir`
+async fn migrator(dir: &Path) -> Result<Migrator, TaskError> {
+    let mut migrations = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            migrations.extend(Migrator::new(path).await?.iter().cloned());
+        }
+    }
+    migrations.sort_by_key(|migration| (migration.version, migration.migration_type.is_down_migration()));
+    if let Some(pair) = migrations.windows(2).find(|pair| {
+        (pair[0].version, pair[0].migration_type) == (pair[1].version, pair[1].migration_type)
+    }) {
+        return Err(format!("Migration version {} is used twice", pair[0].version).into());
+    }
+    Ok(Migrator::new(Merged(migrations)).await?)
+}
+
+/// Pool whose connections resolve unqualified names in `schema` first
+async fn schema_pool(database_url: &str, schema: &str) -> Result<PgPool, sqlx::Error> {
+    let search_path = format!("SET search_path TO {schema}, public");
+    PgPoolOptions::new()
+        .max_connections(1)
+        .after_connect(move |conn, _meta| {
+            let search_path = search_path.clone();
+            Box::pin(async move {
+                sqlx::query(&search_path).execute(conn).await?;
+                Ok(())
+            })
+        })
+        .connect(database_url)
+        .await
+}
+
+/// Replaces the query data in `.sqlx/` by rebuilding every target against `database_url`
+fn regenerate(root: &Path, database_url: &str) -> Result<(), TaskError> {
+    let offline_dir = root.join(".sqlx");
+    for entry in fs::read_dir(&offline_dir)? {
+        let path = entry?.path();
+        if path.extension().is_some_and(|extension| extension == "json") {
+            fs::remove_file(path)?;
+        }
+    }
+
+    // The query macros only run when their crate is compiled again
+    cargo(root, &["clean", "--package", PACKAGE], database_url, &offline_dir)?;
+    cargo(root, &["check", "--package", PACKAGE, "--all-targets", "--all-features"], database_url, &offline_dir)
+}
+
+/// Runs `cargo args` in `root` with the query macros checking against `database_url`
+fn cargo(root: &Path, args: &[&str], database_url: &str, offline_dir: &Path) -> Result<(), TaskError> {
+    let status = Command::new(env::var("CARGO").unwrap_or_else(|_| "cargo".to_owned()))
+        .args(args)
+        .current_dir(root)
+        .env("DATABASE_URL", database_url)
+        .env("SQLX_OFFLINE", "false")
+        .env("SQLX_OFFLINE_DIR", offline_dir)
+        .status()?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!("cargo {} failed ({status})", args.join(" ")).into())
+    }
+}
+
+/// `database_url` with `schema` first on the search path of every connection
+fn schema_url(database_url: &str, schema: &str) -> String {
+    let separator = if database_url.contains('?') { '&' } else { '?' };
+    format!("{database_url}{separator}options=-c%20search_path%3D{schema}%2Cpublic")
+}
+
+/// Directory of the workspace manifest
+fn workspace_root() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .map_or_else(|| PathBuf::from("."), Path::to_path_buf)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_schema_url_keeps_existing_parameters() {
+        assert_eq!(
+            schema_url("postgres://app@db/app", "s1"),
+            "postgres://app@db/app?options=-c%20search_path%3Ds1%2Cpublic"
+        );
+        assert_eq!(
+            schema_url("postgres://app@db/app?sslmode=disable", "s1"),
+            "postgres://app@db/app?sslmode=disable&options=-c%20search_path%3Ds1%2Cpublic"
+        );
+    }
+}